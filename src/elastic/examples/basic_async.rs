@@ -7,32 +7,28 @@
 
 extern crate elastic;
 extern crate env_logger;
-extern crate futures;
-extern crate tokio_threadpool;
 #[macro_use]
 extern crate serde_json;
 extern crate tokio;
 
 use elastic::prelude::*;
-use futures::Future;
 use serde_json::Value;
-use std::{
-    error::Error,
-    sync::Arc,
-};
-use tokio_threadpool::ThreadPool;
+use std::error::Error;
+use tokio::runtime::Runtime;
 
 fn run() -> Result<(), Box<Error>> {
+    let runtime = Runtime::new()?;
+
     // A reqwest HTTP client and default parameters.
-    // We also specify a cpu pool for serialising and deserialising data on.
-    // The cpu pool is optional.
+    // We also specify the runtime's blocking pool for serialising and deserialising data on.
+    // The pool is optional.
     let client = AsyncClientBuilder::new()
         .static_node("http://localhost:9200")
-        .serde_pool(Arc::new(ThreadPool::new()))
+        .serde_pool(runtime.handle().clone())
         .build()?;
 
     // Send the request and process the response.
-    let res_future = client
+    let search_future = client
         .search::<Value>()
         .index("_all")
         .body(json!({
@@ -44,16 +40,12 @@ fn run() -> Result<(), Box<Error>> {
         }))
         .send();
 
-    let search_future = res_future.and_then(|res| {
-        // Iterate through the hits in the response.
-        for hit in res.hits() {
-            println!("{:?}", hit);
-        }
-
-        Ok(())
-    });
+    let res = runtime.block_on(search_future)?;
 
-    tokio::executor::current_thread::block_on_all(search_future)?;
+    // Iterate through the hits in the response.
+    for hit in res.hits() {
+        println!("{:?}", hit);
+    }
 
     Ok(())
 }
@@ -9,13 +9,11 @@
 
 extern crate elastic;
 extern crate env_logger;
-extern crate futures;
 extern crate tokio;
 #[macro_use]
 extern crate serde_json;
 
 use elastic::prelude::*;
-use futures::Future;
 use std::error::Error;
 
 fn run() -> Result<(), Box<Error>> {
@@ -39,18 +37,14 @@ fn run() -> Result<(), Box<Error>> {
         .extend(ops)
         .send();
 
-    let res_future = res_future.and_then(|bulk| {
-        for op in bulk {
-            match op {
-                Ok(op) => println!("ok: {:?}", op),
-                Err(op) => println!("err: {:?}", op),
-            }
-        }
-
-        Ok(())
-    });
+    let bulk = tokio::runtime::Runtime::new()?.block_on(res_future)?;
 
-    tokio::executor::current_thread::block_on_all(res_future)?;
+    for op in bulk {
+        match op {
+            Ok(op) => println!("ok: {:?}", op),
+            Err(op) => println!("err: {:?}", op),
+        }
+    }
 
     Ok(())
 }
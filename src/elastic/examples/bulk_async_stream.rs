@@ -10,6 +10,7 @@
 extern crate elastic;
 extern crate env_logger;
 extern crate futures;
+extern crate futures_util;
 extern crate tokio;
 #[macro_use]
 extern crate serde_json;
@@ -21,6 +22,7 @@ use futures::{
     Sink,
     Stream,
 };
+use futures_util::compat::Future01CompatExt;
 use std::{
     error::Error,
     time::Duration,
@@ -63,7 +65,7 @@ fn run() -> Result<(), Box<Error>> {
         Ok(())
     });
 
-    tokio::executor::current_thread::block_on_all(req_future.join(res_future))?;
+    tokio::runtime::Runtime::new()?.block_on(req_future.join(res_future).compat())?;
 
     Ok(())
 }
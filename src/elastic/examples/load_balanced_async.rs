@@ -6,11 +6,9 @@
 
 extern crate elastic;
 extern crate env_logger;
-extern crate futures;
 extern crate tokio;
 
 use elastic::prelude::*;
-use futures::Future;
 use std::error::Error;
 
 fn run() -> Result<(), Box<Error>> {
@@ -19,18 +17,13 @@ fn run() -> Result<(), Box<Error>> {
         .sniff_nodes("http://localhost:9200")
         .build()?;
 
-    // Send the request and process the response.
-    let ping_future = client
-        .request(PingRequest::new())
-        .send()
-        .and_then(|res| res.into_response::<PingResponse>())
-        .and_then(|ping| {
-            println!("{:?}", ping);
+    let runtime = tokio::runtime::Runtime::new()?;
 
-            Ok(())
-        });
+    // Send the request and process the response.
+    let res = runtime.block_on(client.request(PingRequest::new()).send())?;
+    let ping = runtime.block_on(res.into_response::<PingResponse>())?;
 
-    tokio::executor::current_thread::block_on_all(ping_future)?;
+    println!("{:?}", ping);
 
     Ok(())
 }
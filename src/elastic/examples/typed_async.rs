@@ -9,11 +9,14 @@
 //! - Put the mapping for a document type
 //! - Index a document
 //! - Search the index and iterate over hits
+//!
+//! The requests are combined with `futures` 0.1 combinators by converting them with `compat`.
 
 #[macro_use]
 extern crate elastic_derive;
 extern crate env_logger;
 extern crate futures;
+extern crate futures_util;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
@@ -34,6 +37,7 @@ use futures::{
     Future,
     IntoFuture,
 };
+use futures_util::compat::Future01CompatExt;
 use std::error::Error as StdError;
 
 #[derive(Debug, Serialize, Deserialize, ElasticType)]
@@ -68,7 +72,7 @@ fn run() -> Result<(), Box<StdError>> {
         Ok(())
     });
 
-    tokio::executor::current_thread::block_on_all(res_future)?;
+    tokio::runtime::Runtime::new()?.block_on(res_future.compat())?;
 
     Ok(())
 }
@@ -78,6 +82,7 @@ fn ensure_indexed(client: AsyncClient, doc: MyType) -> Box<Future<Item = (), Err
         .document::<MyType>()
         .get(doc.id.clone())
         .send()
+        .compat()
         .map(|res| res.into_document());
 
     let put_doc = get_res.then(move |res| {
@@ -111,9 +116,18 @@ fn ensure_indexed(client: AsyncClient, doc: MyType) -> Box<Future<Item = (), Err
 }
 
 fn put_index(client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
-    let create_index = client.index(MyType::static_index()).create().send();
+    let create_index = client
+        .index(MyType::static_index())
+        .create()
+        .send()
+        .compat();
 
-    let put_mapping = client.document::<MyType>().put_mapping().send().map(|_| ());
+    let put_mapping = client
+        .document::<MyType>()
+        .put_mapping()
+        .send()
+        .compat()
+        .map(|_| ());
 
     Box::new(create_index.and_then(|_| put_mapping))
 }
@@ -124,6 +138,7 @@ fn put_doc(client: AsyncClient, doc: MyType) -> Box<Future<Item = (), Error = Er
         .index(doc)
         .params_fluent(|p| p.url_param("refresh", true))
         .send()
+        .compat()
         .map(|_| ());
 
     Box::new(index_doc)
@@ -143,7 +158,8 @@ fn search(
                   }
               }
         }))
-        .send();
+        .send()
+        .compat();
 
     Box::new(search)
 }
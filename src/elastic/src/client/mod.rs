@@ -45,6 +45,23 @@ let client = AsyncClientBuilder::new().build()?;
 Requests on the asynchronous client won't block the current thread.
 Instead a `Future` will be returned immediately that will resolve to a response at a later point.
 
+The futures returned by the asynchronous client implement the standard library's `Future` trait, so they can be awaited directly in an `async` function:
+
+```edition2018,no_run
+# use elastic::prelude::*;
+# fn main() {}
+# async fn run() -> Result<(), elastic::Error> {
+# let client = AsyncClientBuilder::new().build()?;
+let ping = client.ping().send().await?;
+
+println!("cluster: {}", ping.name());
+# Ok(())
+# }
+```
+
+The client's connections and timers run on a `tokio` 1 runtime, like the one started by `#[tokio::main]`.
+Code that still uses `futures` 0.1 combinators can call `compat` on a returned future to convert it into a `futures` 0.1 future.
+
 ## Sending requests
 
 Requests can be sent with an instance of a client using a builder API:
@@ -151,6 +168,8 @@ Client method                                                 | Elasticsearch AP
 [`document.update`][Client.document.update]                   | [Update Document][docs-update]     | [`UpdateRequest`][UpdateRequest]                        | [`UpdateResponse`][UpdateResponse]
 [`document.delete`][Client.document.delete]                   | [Delete Document][docs-delete]     | [`DeleteRequest`][DeleteRequest]                        | [`DeleteResponse`][DeleteResponse]
 [`document.put_mapping`][Client.document.put_mapping]         | [Put Mapping][docs-mapping]        | [`IndicesPutMappingRequest`][IndicesPutMappingRequest]  | [`CommandResponse`][CommandResponse]
+[`document.verify_mapping`][Client.document.verify_mapping]   | [Get Mapping][docs-get-mapping]    | [`IndicesGetMappingRequest`][IndicesGetMappingRequest]  | [`VerifyMappingResponse`][VerifyMappingResponse]
+[`document.ensure_index`][Client.document.ensure_index]       | [Create Index][docs-create-index]  | [`IndicesCreateRequest`][IndicesCreateRequest]          | [`EnsureIndexResponse`][EnsureIndexResponse]
 [`index.create`][Client.index.create]                         | [Create Index][docs-create-index]  | [`IndicesCreateRequest`][IndicesCreateRequest]          | [`CommandResponse`][CommandResponse]
 [`index.open`][Client.index.open]                             | [Open Index][docs-open-index]      | [`IndicesOpenRequest`][IndicesOpenRequest]              | [`CommandResponse`][CommandResponse]
 [`index.close`][Client.index.close]                           | [Close Index][docs-close-index]    | [`IndicesCloseRequest`][IndicesCloseRequest]            | [`CommandResponse`][CommandResponse]
@@ -404,19 +423,14 @@ For more details see the [`responses`][responses-mod] module.
 
 Call [`AsyncResponseBuilder.into_response`][AsyncResponseBuilder.into_response] on a sent request to get a [strongly typed response][response-types]:
 
-```no_run
-# extern crate futures;
-# extern crate tokio;
+```edition2018,no_run
 # extern crate serde;
-# extern crate serde_json;
 # #[macro_use] extern crate serde_derive;
 # #[macro_use] extern crate elastic_derive;
-# extern crate elastic;
-# use futures::Future;
 # use serde_json::Value;
 # use elastic::prelude::*;
-# fn main() { run().unwrap() }
-# fn run() -> Result<(), Box<::std::error::Error>> {
+# fn main() {}
+# async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
 # #[derive(Serialize, Deserialize, ElasticType)]
 # struct MyType {
 #     pub id: String,
@@ -425,52 +439,40 @@ Call [`AsyncResponseBuilder.into_response`][AsyncResponseBuilder.into_response]
 # }
 # let client = AsyncClientBuilder::new().build()?;
 # let req = PingRequest::new();
-let future = client.request(req)
-                   .send()
-                   .and_then(|response| response.into_response::<SearchResponse<Value>>());
-
-future.and_then(|response| {
-    // Iterate through the response hits
-    for hit in response.hits() {
-        println!("{:?}", hit);
-    }
-
-    Ok(())
-});
+let response = client.request(req)
+                     .send()
+                     .await?
+                     .into_response::<SearchResponse<Value>>()
+                     .await?;
+
+// Iterate through the response hits
+for hit in response.hits() {
+    println!("{:?}", hit);
+}
 # Ok(())
 # }
 ```
 
 Alternatively, call [`AsyncResponseBuilder.into_raw`][AsyncResponseBuilder.into_raw] on a sent request to get a raw [`AsyncHttpResponse`][AsyncHttpResponse]:
 
-```no_run
-# extern crate futures;
-# extern crate tokio;
-# extern crate serde;
-# #[macro_use] extern crate serde_derive;
-# #[macro_use] extern crate elastic_derive;
-# extern crate elastic;
+```edition2018,no_run
 # use std::str;
-# use std::io::Read;
-# use futures::{Future, Stream};
+# use futures::Stream;
+# use futures_util::compat::Future01CompatExt;
 # use elastic::prelude::*;
-# fn main() { run().unwrap() }
-# fn run() -> Result<(), Box<::std::error::Error>> {
+# fn main() {}
+# async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
 # let client = AsyncClientBuilder::new().build()?;
 # let req = PingRequest::new();
-let future = client.request(req)
-                   .send()
-                   .and_then(|response| Ok(response.into_raw()))
-                   .and_then(|raw| raw.concat2())
-                   .map_err(|e| Box::new(e) as Box<::std::error::Error>);
-
-future.and_then(|body| {
-    let body = str::from_utf8(body.as_ref())?;
+let raw = client.request(req)
+                .send()
+                .await?
+                .into_raw();
 
-    println!("{}", body);
+let body = raw.concat2().compat().await?;
+let body = str::from_utf8(body.as_ref())?;
 
-    Ok(())
-});
+println!("{}", body);
 # Ok(())
 # }
 ```
@@ -485,6 +487,7 @@ For more details see the [`responses`][responses-mod] module.
 [docs-delete]: http://www.elastic.co/guide/en/elasticsearch/reference/current/docs-delete.html
 [docs-index]: https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-index_.html
 [docs-mapping]: https://www.elastic.co/guide/en/elasticsearch/reference/current/mapping.html
+[docs-get-mapping]: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-get-mapping.html
 [docs-create-index]: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-create-index.html
 [docs-close-index]: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-open-close.html
 [docs-open-index]: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-open-close.html
@@ -508,6 +511,8 @@ For more details see the [`responses`][responses-mod] module.
 [Client.document.delete]: struct.DocumentClient.html#delete-document-request
 [Client.document.index]: struct.DocumentClient.html#index-document-request
 [Client.document.put_mapping]: struct.DocumentClient.html#method.put_mapping
+[Client.document.verify_mapping]: struct.DocumentClient.html#verify-mapping-request
+[Client.document.ensure_index]: struct.DocumentClient.html#ensure-index-request
 [Client.index.create]: struct.IndexClient.html#create-index-request
 [Client.index.open]: struct.IndexClient.html#open-index-request
 [Client.index.close]: struct.IndexClient.html#close-index-request
@@ -525,6 +530,7 @@ For more details see the [`responses`][responses-mod] module.
 [DeleteRequest]: requests/endpoints/struct.DeleteRequest.html
 [IndexRequest]: requests/endpoints/struct.IndexRequest.html
 [IndicesPutMappingRequest]: requests/endpoints/struct.IndicesPutMappingRequest.html
+[IndicesGetMappingRequest]: requests/endpoints/struct.IndicesGetMappingRequest.html
 [IndicesCreateRequest]: requests/endpoints/struct.IndicesCreateRequest.html
 [IndicesOpenRequest]: requests/endpoints/struct.IndicesOpenRequest.html
 [IndicesCloseRequest]: requests/endpoints/struct.IndicesCloseRequest.html
@@ -542,6 +548,8 @@ For more details see the [`responses`][responses-mod] module.
 [SearchResponse]: responses/struct.SearchResponse.html
 [BulkResponse]: responses/struct.BulkResponse.html
 [GetResponse]: responses/struct.GetResponse.html
+[VerifyMappingResponse]: requests/document_verify_mapping/struct.VerifyMappingResponse.html
+[EnsureIndexResponse]: requests/document_ensure_index/struct.EnsureIndexResponse.html
 [UpdateResponse]: responses/struct.UpdateResponse.html
 [DeleteResponse]: responses/struct.DeleteResponse.html
 [IndexResponse]: responses/struct.IndexResponse.html
@@ -607,20 +615,16 @@ let response = client.request(PingRequest::new())
 Create an asynchronous `Client` and send a ping request:
 
 ```no_run
-# extern crate futures;
 # extern crate tokio;
 # extern crate elastic;
-# use futures::Future;
 # use elastic::prelude::*;
 # fn main() { run().unwrap() }
 # fn run() -> Result<(), Box<::std::error::Error>> {
 let client = AsyncClientBuilder::new().build()?;
+let runtime = tokio::runtime::Runtime::new()?;
 
-let response_future = client.request(PingRequest::new())
-                            .send()
-                            .and_then(|res| res.into_response::<PingResponse>());
-
-tokio::runtime::current_thread::block_on_all(response_future)?;
+let response = runtime.block_on(client.request(PingRequest::new()).send())?;
+let ping = runtime.block_on(response.into_response::<PingResponse>())?;
 # Ok(())
 # }
 ```
@@ -638,6 +642,20 @@ impl<TSender> Client<TSender>
 where
     TSender: Sender,
 {
+    /**
+    Create a client that sends requests to a set of nodes using a custom sender.
+
+    Clients that use the built-in senders are created using a [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder] instead.
+    See the [`Sender`][Sender] trait for details on implementing a custom sender.
+
+    [SyncClientBuilder]: struct.SyncClientBuilder.html
+    [AsyncClientBuilder]: struct.AsyncClientBuilder.html
+    [Sender]: sender/trait.Sender.html
+    */
+    pub fn from_sender(sender: TSender, addresses: NodeAddresses<TSender>) -> Self {
+        Client { sender, addresses }
+    }
+
     /**
     Get a client for working with specific document type.
 
@@ -673,6 +691,46 @@ pub struct DocumentClient<TSender, TDocument> {
     _m: PhantomData<TDocument>,
 }
 
+impl<TSender, TDocument> DocumentClient<TSender, TDocument> {
+    /**
+    Specify default request parameters for requests about this document type.
+
+    The parameters are applied over the client's default parameters, and parameters set on a request are applied over them.
+    Each call to `params_fluent` is chained.
+
+    # Examples
+
+    Send requests for a document type with a routing value:
+
+    ```no_run
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # #[derive(Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    let tenant = client
+        .document::<MyType>()
+        .params_fluent(|p| p.url_param("routing", "tenant123"));
+
+    let response = tenant.get(1).send()?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn params_fluent(
+        mut self,
+        builder: impl Fn(RequestParams) -> RequestParams + Send + Sync + 'static,
+    ) -> Self {
+        self.inner.addresses = self.inner.addresses.with_defaults(builder);
+
+        self
+    }
+}
+
 /**
 A [`Client`] for a specific index.
 
@@ -684,6 +742,42 @@ pub struct IndexClient<TSender> {
     index: Index<'static>,
 }
 
+impl<TSender> IndexClient<TSender> {
+    /**
+    Specify default request parameters for requests to this index.
+
+    The parameters are applied over the client's default parameters, and parameters set on a request are applied over them.
+    Each call to `params_fluent` is chained.
+
+    # Examples
+
+    Send requests for an index with a routing value:
+
+    ```no_run
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    let tenant = client
+        .index("tenant_idx")
+        .params_fluent(|p| p.url_param("routing", "tenant123"));
+
+    let response = tenant.exists().send()?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn params_fluent(
+        mut self,
+        builder: impl Fn(RequestParams) -> RequestParams + Send + Sync + 'static,
+    ) -> Self {
+        self.inner.addresses = self.inner.addresses.with_defaults(builder);
+
+        self
+    }
+}
+
 pub mod prelude {
     /*! A glob import for convenience. */
 
@@ -691,6 +785,7 @@ pub mod prelude {
         requests::prelude::*,
         responses::prelude::*,
         sender::{
+            auth::Credentials,
             PreRequestParams,
             RequestParams,
         },
@@ -714,4 +809,53 @@ mod tests {
         assert_send::<AsyncClient>();
         assert_sync::<AsyncClient>();
     }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_index_params_are_layered() {
+        use client::sender::mock::{
+            MockResponse,
+            MockSender,
+        };
+        use http::{
+            Method,
+            StatusCode,
+        };
+
+        let mock = MockSender::new();
+        mock.respond(
+            Method::HEAD,
+            "/tenant_idx",
+            MockResponse::new(StatusCode::OK),
+        );
+
+        let client = SyncClientBuilder::new()
+            .mock(mock.clone())
+            .params_fluent(|p| p.url_param("pretty", true).url_param("routing", "default"))
+            .build()
+            .unwrap();
+
+        let tenant = client
+            .index("tenant_idx")
+            .params_fluent(|p| p.url_param("routing", "tenant123"));
+
+        tenant.clone().exists().send().unwrap();
+        tenant
+            .exists()
+            .params_fluent(|p| p.url_param("routing", "override"))
+            .send()
+            .unwrap();
+        client.index("tenant_idx").exists().send().unwrap();
+
+        let queries: Vec<_> = mock
+            .requests()
+            .iter()
+            .map(|req| req.url().query().unwrap_or_default().to_owned())
+            .collect();
+
+        assert!(queries[0].contains("routing=tenant123"));
+        assert!(queries[0].contains("pretty=true"));
+        assert!(queries[1].contains("routing=override"));
+        assert!(queries[2].contains("routing=default"));
+    }
 }
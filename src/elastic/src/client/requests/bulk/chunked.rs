@@ -0,0 +1,985 @@
+use std::{
+    collections::BTreeMap,
+    marker::PhantomData,
+    thread,
+    time::Instant,
+};
+
+use fluent_builder::FluentBuilder;
+use futures::{
+    future::{
+        self,
+        Either,
+        Loop,
+    },
+    stream,
+    Future,
+    Stream,
+};
+use serde::{
+    de::{
+        DeserializeOwned,
+        IgnoredAny,
+    },
+    ser::Serialize,
+};
+use serde_json;
+use runtime::Delay;
+
+use super::{
+    BulkBody,
+    BulkOperation,
+    BulkRequestBuilder,
+    BulkRequestInner,
+    BulkRetry,
+    Pending,
+    WrappedBody,
+    DEFAULT_BODY_SIZE,
+};
+use client::{
+    requests::{
+        params::{
+            Index,
+            Type,
+        },
+        RequestBuilder,
+    },
+    responses::{
+        parse::IsOk,
+        BulkErrorsResponse,
+        BulkResponse,
+    },
+    sender::{
+        AsyncSender,
+        Sender,
+        SyncSender,
+    },
+    Client,
+    RequestParams,
+};
+use error::{
+    self,
+    Error,
+};
+
+const DEFAULT_CONCURRENCY: usize = 4;
+
+/**
+A bulk request body that's split into multiple requests when it's sent.
+
+Operations are buffered up-front and then divided into chunks that don't exceed a maximum size in bytes or number of operations.
+Chunks are split from the buffered body as they're sent, so only the chunks that are in-flight are copied.
+*/
+pub struct Chunked {
+    body: Vec<u8>,
+    ops: Vec<usize>,
+    body_size: usize,
+    body_size_ops: Option<usize>,
+    concurrency: usize,
+    retry: Option<BulkRetry>,
+}
+
+/**
+A single request body split from a chunked body.
+
+The offsets of each operation are kept so failed operations can be sent again.
+*/
+struct Chunk {
+    body: Vec<u8>,
+    ops: Vec<usize>,
+}
+
+impl Chunk {
+    /** Copy the operations at the given positions into a new request body. */
+    fn select(&self, positions: &[usize]) -> Vec<u8> {
+        let mut body = Vec::new();
+
+        for &position in positions {
+            let start = match position {
+                0 => Some(0),
+                position => self.ops.get(position - 1).cloned(),
+            };
+
+            if let (Some(start), Some(&end)) = (start, self.ops.get(position)) {
+                body.extend_from_slice(&self.body[start..end]);
+            }
+        }
+
+        body
+    }
+}
+
+impl Chunked {
+    fn new() -> Self {
+        Chunked {
+            body: Vec::new(),
+            ops: Vec::new(),
+            body_size: DEFAULT_BODY_SIZE,
+            body_size_ops: None,
+            concurrency: DEFAULT_CONCURRENCY,
+            retry: None,
+        }
+    }
+
+    /**
+    Buffer the operations in an existing bulk request body so they're sent in a single chunk.
+
+    The operations are found by reading the action on each line, because `delete` operations don't have a source line.
+    */
+    fn from_body(body: Vec<u8>) -> Result<Self, Error> {
+        let mut ops = Vec::new();
+
+        let mut line_start = 0;
+        let mut needs_source = false;
+
+        for line_end in body
+            .iter()
+            .enumerate()
+            .filter(|&(_, &b)| b == b'\n')
+            .map(|(i, _)| i + 1)
+        {
+            if needs_source {
+                needs_source = false;
+            } else {
+                let action: BTreeMap<String, IgnoredAny> =
+                    serde_json::from_slice(&body[line_start..line_end]).map_err(error::request)?;
+
+                needs_source = !action.contains_key("delete");
+            }
+
+            if !needs_source {
+                ops.push(line_end);
+            }
+
+            line_start = line_end;
+        }
+
+        if needs_source || line_start < body.len() {
+            return Err(error::request(error::message(
+                "the bulk request body ends with an incomplete operation",
+            )));
+        }
+
+        Ok(Chunked {
+            body,
+            ops,
+            body_size: usize::max_value(),
+            ..Chunked::new()
+        })
+    }
+
+    /**
+    Split the buffered operations into request bodies.
+
+    Operations are never split across chunks, so a single operation that's larger than the maximum body size is sent on its own.
+    There's always at least one chunk, even if no operations were pushed.
+    */
+    fn into_chunks(self) -> Chunks {
+        Chunks {
+            body: self.body,
+            ops: self.ops,
+            body_size: self.body_size,
+            body_size_ops: self.body_size_ops,
+            next_op: 0,
+            started: false,
+        }
+    }
+}
+
+/**
+An iterator that splits a chunked body into request bodies as they're needed.
+*/
+struct Chunks {
+    body: Vec<u8>,
+    ops: Vec<usize>,
+    body_size: usize,
+    body_size_ops: Option<usize>,
+    next_op: usize,
+    started: bool,
+}
+
+impl Iterator for Chunks {
+    type Item = Chunk;
+
+    fn next(&mut self) -> Option<Chunk> {
+        if self.started && self.next_op >= self.ops.len() {
+            return None;
+        }
+
+        self.started = true;
+
+        let first_op = self.next_op;
+        let chunk_start = match first_op {
+            0 => 0,
+            first_op => self.ops[first_op - 1],
+        };
+
+        let mut chunk_end = chunk_start;
+        for &op_end in &self.ops[first_op..] {
+            let chunk_ops = self.next_op - first_op;
+
+            let over_size = op_end - chunk_start > self.body_size;
+            let over_ops = self
+                .body_size_ops
+                .map(|body_size_ops| chunk_ops >= body_size_ops)
+                .unwrap_or(false);
+
+            if chunk_ops > 0 && (over_size || over_ops) {
+                break;
+            }
+
+            chunk_end = op_end;
+            self.next_op += 1;
+        }
+
+        Some(Chunk {
+            body: self.body[chunk_start..chunk_end].to_vec(),
+            ops: self.ops[first_op..self.next_op]
+                .iter()
+                .map(|op_end| op_end - chunk_start)
+                .collect(),
+        })
+    }
+}
+
+impl BulkBody for Chunked {
+    fn push<TDocument>(&mut self, op: BulkOperation<TDocument>) -> Result<(), Error>
+    where
+        TDocument: Serialize,
+    {
+        let op_start = self.body.len();
+
+        // Don't leave a partially written operation in the body
+        if let Err(e) = op.write(&mut self.body) {
+            self.body.truncate(op_start);
+            return Err(error::request(e));
+        }
+
+        self.ops.push(self.body.len());
+
+        Ok(())
+    }
+
+    fn estimated_size(&self) -> usize {
+        self.body.len()
+    }
+}
+
+/**
+A bulk response that can be combined with the response to another bulk request.
+*/
+#[doc(hidden)]
+pub trait MergeResponse {
+    fn append(&mut self, other: Self);
+
+    fn retryable(&self, retry: &BulkRetry) -> Vec<usize>;
+
+    fn replace(&mut self, positions: &[usize], other: Self);
+}
+
+impl<TIndex, TType, TId> MergeResponse for BulkResponse<TIndex, TType, TId> {
+    fn append(&mut self, other: Self) {
+        BulkResponse::append(self, other)
+    }
+
+    fn retryable(&self, retry: &BulkRetry) -> Vec<usize> {
+        self.iter()
+            .enumerate()
+            .filter_map(|(position, item)| match item {
+                Err(item) if retry.is_retryable(item.status()) => Some(position),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn replace(&mut self, positions: &[usize], other: Self) {
+        BulkResponse::replace(self, positions, other)
+    }
+}
+
+impl<TIndex, TType, TId> MergeResponse for BulkErrorsResponse<TIndex, TType, TId> {
+    fn append(&mut self, other: Self) {
+        BulkErrorsResponse::append(self, other)
+    }
+
+    // Errors-only responses don't know the position of each item, so they can't be retried
+    fn retryable(&self, _: &BulkRetry) -> Vec<usize> {
+        Vec::new()
+    }
+
+    fn replace(&mut self, _: &[usize], other: Self) {
+        BulkErrorsResponse::append(self, other)
+    }
+}
+
+fn append_response<TResponse>(response: Option<TResponse>, chunk: TResponse) -> Option<TResponse>
+where
+    TResponse: MergeResponse,
+{
+    match response {
+        Some(mut response) => {
+            response.append(chunk);
+            Some(response)
+        }
+        None => Some(chunk),
+    }
+}
+
+/**
+The parts of a bulk request that are shared by each chunk.
+*/
+struct ChunkRequestTemplate<TSender, TResponse> {
+    client: Client<TSender>,
+    params: RequestParams,
+    index: Option<Index<'static>>,
+    ty: Option<Type<'static>>,
+    _marker: PhantomData<TResponse>,
+}
+
+impl<TSender, TResponse> Clone for ChunkRequestTemplate<TSender, TResponse>
+where
+    TSender: Sender,
+{
+    fn clone(&self) -> Self {
+        ChunkRequestTemplate::new(
+            self.client.clone(),
+            self.params.clone(),
+            self.index.clone(),
+            self.ty.clone(),
+        )
+    }
+}
+
+impl<TSender, TResponse> ChunkRequestTemplate<TSender, TResponse>
+where
+    TSender: Sender,
+{
+    fn new(
+        client: Client<TSender>,
+        params: RequestParams,
+        index: Option<Index<'static>>,
+        ty: Option<Type<'static>>,
+    ) -> Self {
+        ChunkRequestTemplate {
+            client,
+            params,
+            index,
+            ty,
+            _marker: PhantomData,
+        }
+    }
+
+    fn to_request(&self, body: Vec<u8>) -> BulkRequestBuilder<TSender, Vec<u8>, TResponse> {
+        RequestBuilder::new(
+            self.client.clone(),
+            FluentBuilder::new().value(self.params.clone()),
+            BulkRequestInner::<Vec<u8>, TResponse> {
+                index: self.index.clone(),
+                ty: self.ty.clone(),
+                body: WrappedBody::new(body),
+                _marker: PhantomData,
+            },
+        )
+    }
+}
+
+impl<TResponse> ChunkRequestTemplate<SyncSender, TResponse>
+where
+    TResponse: DeserializeOwned + IsOk + MergeResponse + Send + 'static,
+{
+    /** Send a chunk, retrying any failed operations until they succeed or the retry policy gives up. */
+    fn send(&self, chunk: Chunk, retry: Option<&BulkRetry>) -> Result<TResponse, Error> {
+        let retry = match retry {
+            Some(retry) => retry,
+            None => return self.to_request(chunk.body).send(),
+        };
+
+        let mut response = self.to_request(chunk.body.clone()).send()?;
+        let mut attempts = 1;
+
+        loop {
+            let positions = response.retryable(retry);
+
+            if positions.is_empty() || !retry.can_retry(attempts) {
+                return Ok(response);
+            }
+
+            debug!(
+                "Elasticsearch Bulk Chunked: retrying {} failed operations after {} attempts",
+                positions.len(),
+                attempts
+            );
+
+            thread::sleep(retry.backoff(attempts));
+
+            let retried = self.to_request(chunk.select(&positions)).send()?;
+            response.replace(&positions, retried);
+
+            attempts += 1;
+        }
+    }
+}
+
+impl<TResponse> ChunkRequestTemplate<AsyncSender, TResponse>
+where
+    TResponse: DeserializeOwned + IsOk + MergeResponse + Send + 'static,
+{
+    /** Send a chunk, retrying any failed operations until they succeed or the retry policy gives up. */
+    fn send(&self, chunk: Chunk, retry: Option<BulkRetry>) -> Pending<TResponse> {
+        let retry = match retry {
+            Some(retry) => retry,
+            None => return self.to_request(chunk.body).send(),
+        };
+
+        let first_attempt = self.to_request(chunk.body.clone()).send().compat();
+        let req_template = self.clone();
+
+        let res_future = first_attempt.and_then(move |response| {
+            future::loop_fn((response, 1), move |(mut response, attempts)| {
+                let positions = response.retryable(&retry);
+
+                if positions.is_empty() || !retry.can_retry(attempts) {
+                    return Either::A(future::ok(Loop::Break(response)));
+                }
+
+                debug!(
+                    "Elasticsearch Bulk Chunked: retrying {} failed operations after {} attempts",
+                    positions.len(),
+                    attempts
+                );
+
+                let req_template = req_template.clone();
+                let body = chunk.select(&positions);
+
+                let retried = Delay::new(Instant::now() + retry.backoff(attempts))
+                    .map_err(error::request)
+                    .and_then(move |_| req_template.to_request(body).send().compat())
+                    .map(move |retried| {
+                        response.replace(&positions, retried);
+
+                        Loop::Continue((response, attempts + 1))
+                    });
+
+                Either::B(retried)
+            })
+        });
+
+        Pending::new(res_future)
+    }
+}
+
+/**
+# Bulk chunked request
+*/
+impl<TSender> Client<TSender>
+where
+    TSender: Sender,
+{
+    /**
+    Create a [`BulkRequestBuilder`][BulkRequestBuilder] with this `Client` that splits its operations into multiple requests.
+
+    Operations are buffered like a regular [`bulk`][Client.bulk] request, but when the request is sent they're split into chunks that don't exceed a maximum size in bytes or number of operations.
+    The responses for each chunk are combined into a single response, in the same order the operations were pushed.
+    If any chunk fails to send then the whole request returns an error, but chunks that were already sent aren't rolled back.
+
+    For more details, see:
+
+    - [builder methods][builder-methods]
+    - [chunked builder methods][chunked-builder-methods]
+    - [send synchronously][send-sync]
+    - [send asynchronously][send-async]
+
+    # Examples
+
+    Send the operations in requests that contain at most 100 operations and 1MB of data:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use]
+    # extern crate serde_derive;
+    # #[macro_use]
+    # extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Serialize, Deserialize, ElasticType)]
+    # struct MyType {
+    #     pub id: String,
+    #     pub title: String,
+    # }
+    # let client = SyncClientBuilder::new().build()?;
+    let ops = (0..1000)
+        .into_iter()
+        .map(|i| bulk::<MyType>().index(MyType {
+                id: i.to_string(),
+                title: "some string value".to_owned()
+            })
+            .id(i));
+
+    let response = client.bulk_chunked()
+                         .index("myindex")
+                         .ty(MyType::static_ty())
+                         .body_size_bytes(1024 * 1024)
+                         .body_size_ops(100)
+                         .extend(ops)
+                         .send()?;
+
+    for op in response {
+        match op {
+            Ok(op) => println!("ok: {:?}", op),
+            Err(op) => println!("err: {:?}", op),
+        }
+    }
+    # Ok(())
+    # }
+    ```
+
+    [Client.bulk]: #bulk-request
+    [BulkRequestBuilder]: requests/bulk/type.BulkRequestBuilder.html
+    [builder-methods]: requests/bulk/type.BulkRequestBuilder.html#builder-methods
+    [chunked-builder-methods]: requests/bulk/type.BulkRequestBuilder.html#chunked-builder-methods
+    [send-sync]: requests/bulk/type.BulkRequestBuilder.html#send-chunks-synchronously
+    [send-async]: requests/bulk/type.BulkRequestBuilder.html#send-chunks-asynchronously
+    */
+    pub fn bulk_chunked(&self) -> BulkRequestBuilder<TSender, Chunked, BulkResponse> {
+        RequestBuilder::initial(
+            self.clone(),
+            BulkRequestInner {
+                index: None,
+                ty: None,
+                body: WrappedBody::new(Chunked::new()),
+                _marker: PhantomData,
+            },
+        )
+    }
+}
+
+/**
+# Chunked builder methods
+
+Configure how a chunked `BulkRequestBuilder` is split before sending it.
+*/
+impl<TSender, TResponse> BulkRequestBuilder<TSender, Chunked, TResponse>
+where
+    TSender: Sender,
+{
+    /**
+    Specify a maximum request size in bytes.
+
+    Operations will be appended to a chunk until the `body_size` is reached.
+    An operation that's larger than the `body_size` by itself is sent in its own request.
+    */
+    pub fn body_size_bytes(mut self, body_size: usize) -> Self {
+        self.inner.body.with_inner_mut(|c| {
+            c.body_size = body_size;
+            Ok(())
+        });
+
+        self
+    }
+
+    /**
+    Specify a maximum number of operations per request.
+
+    Operations will be appended to a chunk until either the `body_size` or the number of operations is reached.
+    By default, the number of operations per request isn't limited.
+    */
+    pub fn body_size_ops(mut self, body_size_ops: usize) -> Self {
+        self.inner.body.with_inner_mut(|c| {
+            c.body_size_ops = Some(body_size_ops);
+            Ok(())
+        });
+
+        self
+    }
+}
+
+impl<TSender, TIndex, TType, TId> BulkRequestBuilder<TSender, Chunked, BulkResponse<TIndex, TType, TId>>
+where
+    TSender: Sender,
+{
+    /**
+    Retry operations that fail because Elasticsearch is overloaded or unavailable.
+
+    After a chunk is sent, any operations that failed with a retryable status are sent again in a new request after waiting for a backoff period.
+    This is repeated until there are no more retryable failures or the maximum number of attempts is reached.
+    The responses to retried operations replace their failures in the final response, so items stay in the same order the operations were pushed.
+    Operations that failed for other reasons, or are still failing once the maximum number of attempts is reached, are returned as errors.
+
+    Failed operations can only be retried for a full `BulkResponse`, so any retry policy is ignored once [`errors_only`](#method.errors_only) is called.
+
+    # Examples
+
+    Send the operations, retrying failures up to `5` times:
+
+    ```no_run
+    # #[macro_use] extern crate serde_json;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    let ops = (0..1000)
+        .into_iter()
+        .map(|i| bulk_raw().index(json!({ "id": i })).id(i));
+
+    let response = client.bulk_chunked()
+                         .index("myindex")
+                         .retry(BulkRetry::new().max_attempts(5))
+                         .extend(ops)
+                         .send()?;
+
+    assert!(response.is_ok());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn retry(mut self, retry: BulkRetry) -> Self {
+        self.inner.body.with_inner_mut(|c| {
+            c.retry = Some(retry);
+            Ok(())
+        });
+
+        self
+    }
+}
+
+impl<TSender, TIndex, TType, TId> BulkRequestBuilder<TSender, Vec<u8>, BulkResponse<TIndex, TType, TId>>
+where
+    TSender: Sender,
+{
+    /**
+    Retry operations that fail because Elasticsearch is overloaded or unavailable.
+
+    The operations are still sent in a single request, and any that fail with a retryable status are sent again like they are for a [`bulk_chunked`][Client.bulk_chunked] request.
+    The responses to retried operations replace their failures in the final response, so items stay in the same order the operations were pushed.
+    The returned builder is a chunked builder whose chunks aren't limited in size, so any [chunked builder methods][chunked-builder-methods] can also be used on it.
+
+    # Examples
+
+    Send the operations, retrying failures up to `5` times:
+
+    ```no_run
+    # #[macro_use] extern crate serde_json;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    let ops = (0..1000)
+        .into_iter()
+        .map(|i| bulk_raw().index(json!({ "id": i })).id(i));
+
+    let response = client.bulk()
+                         .index("myindex")
+                         .extend(ops)
+                         .retry(BulkRetry::new().max_attempts(5))
+                         .send()?;
+
+    assert!(response.is_ok());
+    # Ok(())
+    # }
+    ```
+
+    [Client.bulk_chunked]: ../../struct.Client.html#bulk-chunked-request
+    [chunked-builder-methods]: #chunked-builder-methods
+    */
+    pub fn retry(
+        self,
+        retry: BulkRetry,
+    ) -> BulkRequestBuilder<TSender, Chunked, BulkResponse<TIndex, TType, TId>> {
+        let BulkRequestInner {
+            index, ty, body, ..
+        } = self.inner;
+        let WrappedBody { inner, errs } = body;
+
+        let mut body = WrappedBody {
+            inner: Chunked::new(),
+            errs,
+        };
+        body.with_inner_mut(|c| {
+            *c = Chunked::from_body(inner)?;
+            c.retry = Some(retry);
+            Ok(())
+        });
+
+        RequestBuilder::new(
+            self.client,
+            self.params_builder,
+            BulkRequestInner {
+                index,
+                ty,
+                body,
+                _marker: PhantomData,
+            },
+        )
+    }
+}
+
+impl<TResponse> BulkRequestBuilder<AsyncSender, Chunked, TResponse> {
+    /**
+    Specify the maximum number of chunks that can be in-flight at once.
+
+    Synchronous requests always send one chunk at a time.
+    The default is `4`, and a concurrency of `0` is treated as `1`.
+    */
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.inner.body.with_inner_mut(|c| {
+            c.concurrency = usize::max(concurrency, 1);
+            Ok(())
+        });
+
+        self
+    }
+}
+
+/**
+# Send chunks synchronously
+*/
+impl<TResponse> BulkRequestBuilder<SyncSender, Chunked, TResponse>
+where
+    TResponse: DeserializeOwned + IsOk + MergeResponse + Send + 'static,
+{
+    /**
+    Send a chunked `BulkRequestBuilder` synchronously using a [`SyncClient`][SyncClient].
+
+    This will block the current thread until a response for each chunk has arrived and been deserialised.
+    Chunks are sent one at a time to the same node.
+
+    [SyncClient]: ../../type.SyncClient.html
+    */
+    pub fn send(self) -> Result<TResponse, Error> {
+        let (client, params_builder, inner) = (self.client, self.params_builder, self.inner);
+
+        let chunked = inner.body.try_into_inner()?;
+
+        let params = client.addresses.resolve(params_builder)?;
+
+        let req_template = ChunkRequestTemplate::new(client, params, inner.index, inner.ty);
+        let retry = chunked.retry.clone();
+
+        let mut response = None;
+        for chunk in chunked.into_chunks() {
+            let chunk_response = req_template.send(chunk, retry.as_ref())?;
+
+            response = append_response(response, chunk_response);
+        }
+
+        Ok(response.expect("there's always at least one chunk"))
+    }
+}
+
+/**
+# Send chunks asynchronously
+*/
+impl<TResponse> BulkRequestBuilder<AsyncSender, Chunked, TResponse>
+where
+    TResponse: DeserializeOwned + IsOk + MergeResponse + Send + 'static,
+{
+    /**
+    Send a chunked `BulkRequestBuilder` asynchronously using an [`AsyncClient`][AsyncClient].
+
+    This will return a future that will resolve once a response for each chunk has arrived and been deserialised.
+    Chunks are sent to the same node, with up to [`concurrency`](#method.concurrency) requests in-flight at once.
+
+    [AsyncClient]: ../../type.AsyncClient.html
+    */
+    pub fn send(self) -> Pending<TResponse> {
+        let (client, params_builder, inner) = (self.client, self.params_builder, self.inner);
+        let BulkRequestInner {
+            index, ty, body, ..
+        } = inner;
+
+        let params_future = client.addresses.resolve(params_builder).compat();
+
+        let chunks_future = client.sender.maybe_async(move || {
+            let chunked = body.try_into_inner()?;
+            let concurrency = chunked.concurrency;
+            let retry = chunked.retry.clone();
+
+            Ok((chunked.into_chunks(), concurrency, retry))
+        });
+
+        let res_future =
+            params_future
+                .join(chunks_future)
+                .and_then(move |(params, (chunks, concurrency, retry))| {
+                    let req_template = ChunkRequestTemplate::new(client, params, index, ty);
+
+                    stream::iter_ok(chunks)
+                        .map(move |chunk| req_template.send(chunk, retry.clone()).compat())
+                        .buffered(concurrency)
+                        .fold(None, |response, chunk_response| {
+                            Ok::<_, Error>(append_response(response, chunk_response))
+                        })
+                        .map(|response| response.expect("there's always at least one chunk"))
+                });
+
+        Pending::new(res_future)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BulkBody,
+        Chunked,
+    };
+    use prelude::*;
+    use tests::*;
+
+    fn chunked(ops: usize) -> Chunked {
+        let mut chunked = Chunked::new();
+
+        for i in 0..ops {
+            chunked
+                .push(bulk_raw().index(json!({ "id": i })).id(i))
+                .unwrap();
+        }
+
+        chunked
+    }
+
+    #[test]
+    fn is_send() {
+        assert_send::<super::Chunked>();
+    }
+
+    #[test]
+    fn empty_body_is_one_chunk() {
+        let chunks: Vec<_> = chunked(0).into_chunks().collect();
+
+        assert_eq!(1, chunks.len());
+        assert!(chunks[0].body.is_empty());
+    }
+
+    #[test]
+    fn split_by_ops() {
+        let mut body = chunked(10);
+        body.body_size_ops = Some(3);
+
+        let expected_body = body.body.clone();
+        let chunks: Vec<_> = body.into_chunks().collect();
+
+        assert_eq!(4, chunks.len());
+        let bodies: Vec<_> = chunks.into_iter().map(|chunk| chunk.body).collect();
+        assert_eq!(expected_body, bodies.concat());
+    }
+
+    #[test]
+    fn split_by_bytes() {
+        let single_op = chunked(1).body.len();
+
+        let mut body = chunked(10);
+        body.body_size = single_op * 2;
+
+        let chunks: Vec<_> = body.into_chunks().collect();
+
+        assert_eq!(5, chunks.len());
+        assert!(chunks.iter().all(|chunk| chunk.body.len() <= single_op * 2));
+    }
+
+    #[test]
+    fn oversized_op_is_sent_alone() {
+        let mut body = chunked(3);
+        body.body_size = 1;
+
+        let chunks: Vec<_> = body.into_chunks().collect();
+
+        assert_eq!(3, chunks.len());
+        assert!(chunks.iter().all(|chunk| !chunk.body.is_empty()));
+    }
+
+    #[test]
+    fn chunks_are_split_lazily() {
+        let mut body = chunked(10);
+        body.body_size_ops = Some(3);
+
+        let mut chunks = body.into_chunks();
+
+        assert_eq!(3, chunks.next().unwrap().ops.len());
+        assert_eq!(3, chunks.next_op);
+        assert_eq!(3, chunks.next().unwrap().ops.len());
+        assert_eq!(6, chunks.next_op);
+    }
+
+    #[test]
+    fn zero_concurrency_is_one() {
+        let client = AsyncClientBuilder::new().build().unwrap();
+
+        let chunked = client
+            .bulk_chunked()
+            .concurrency(0)
+            .inner
+            .body
+            .try_into_inner()
+            .unwrap();
+
+        assert_eq!(1, chunked.concurrency);
+    }
+
+    #[test]
+    fn from_body_finds_ops() {
+        let mut pushed = Chunked::new();
+        let mut body = Vec::new();
+
+        for i in 0..3 {
+            pushed
+                .push(bulk_raw().index(json!({ "id": i })).id(i))
+                .unwrap();
+            pushed.push(bulk_raw().delete().id(i)).unwrap();
+
+            BulkBody::push(&mut body, bulk_raw().index(json!({ "id": i })).id(i)).unwrap();
+            BulkBody::push(&mut body, bulk_raw().delete().id(i)).unwrap();
+        }
+
+        let chunked = Chunked::from_body(body).unwrap();
+
+        assert_eq!(pushed.ops, chunked.ops);
+        assert_eq!(1, chunked.into_chunks().count());
+    }
+
+    #[test]
+    fn from_body_incomplete_is_err() {
+        let mut body = Vec::new();
+        BulkBody::push(&mut body, bulk_raw().index(json!({ "id": 1 })).id(1)).unwrap();
+        body.truncate(body.len() - 1);
+
+        assert!(Chunked::from_body(body).is_err());
+    }
+
+    #[test]
+    fn retry_plain_body() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let chunked = client
+            .bulk()
+            .push(bulk_raw().index(json!({ "id": 1 })).id(1))
+            .push(bulk_raw().delete().id(2))
+            .retry(BulkRetry::new())
+            .inner
+            .body
+            .try_into_inner()
+            .unwrap();
+
+        assert_eq!(2, chunked.ops.len());
+        assert_eq!(Some(BulkRetry::new()), chunked.retry);
+    }
+
+    #[test]
+    fn select_ops_from_chunk() {
+        let single_op = chunked(1).body;
+
+        let mut body = chunked(6);
+        body.body_size_ops = Some(3);
+
+        let expected = body.body[single_op.len() * 3..].to_vec();
+        let chunks: Vec<_> = body.into_chunks().collect();
+
+        assert_eq!(vec![3, 3], chunks.iter().map(|c| c.ops.len()).collect::<Vec<_>>());
+        assert_eq!(expected, chunks[1].select(&[0, 1, 2]));
+        assert_eq!(single_op, chunks[0].select(&[0]));
+        assert!(chunks[0].select(&[3]).is_empty());
+    }
+}
@@ -7,14 +7,13 @@ Builders for [bulk requests][docs-bulk].
 use std::{
     error::Error as StdError,
     fmt,
+    io,
     marker::PhantomData,
     time::Duration,
 };
 
-use futures::{
-    Future,
-    Poll,
-};
+use bytes::BytesMut;
+use futures::Future;
 use serde::{
     de::DeserializeOwned,
     ser::Serialize,
@@ -51,7 +50,14 @@ use http::{
     AsyncBody,
     SyncBody,
 };
-use types::document::DEFAULT_DOC_TYPE;
+use std_future::{
+    box_future,
+    BoxFuture,
+};
+use types::document::{
+    DocumentType,
+    DEFAULT_DOC_TYPE,
+};
 
 /**
 A [bulk request][docs-bulk] builder that can be configured before sending.
@@ -61,20 +67,31 @@ The `send` method will either send the request [synchronously][send-sync] or [as
 
 Call [`Client.bulk_stream`][Client.bulk_stream] to get a `BulkRequestBuilder` that can be used to stream bulk operations asynchronously.
 
+Call [`Client.bulk_chunked`][Client.bulk_chunked] to get a `BulkRequestBuilder` that splits its operations into multiple requests when it's sent.
+
 [docs-bulk]: https://www.elastic.co/guide/en/elasticsearch/reference/current/bulk.html
 [send-sync]: #send-synchronously
 [send-async]: #send-asynchronously
 [Client.bulk]: ../../struct.Client.html#bulk-request
 [Client.bulk_stream]: ../../struct.Client.html#bulk-stream-request
+[Client.bulk_chunked]: ../../struct.Client.html#bulk-chunked-request
 */
 pub type BulkRequestBuilder<TSender, TBody, TResponse> =
     RequestBuilder<TSender, BulkRequestInner<TBody, TResponse>>;
 
+mod chunked;
 mod operation;
+mod processor;
+mod retry;
+mod sink;
 mod stream;
 
 pub use self::{
+    chunked::*,
     operation::*,
+    processor::*,
+    retry::*,
+    sink::*,
     stream::*,
 };
 
@@ -165,6 +182,176 @@ where
             },
         )
     }
+
+    /**
+    Create a [`BulkRequestBuilder`][BulkRequestBuilder] that serialises operations into the given body.
+
+    This is the same as [`bulk`][Client.bulk], except operations are appended to an existing buffer instead of a new one.
+    A `BytesMut` buffer can be used to avoid allocating a new body for each request.
+    The body is sent without copying it, and once the request has completed its allocation can be reclaimed by calling `reserve` on the buffer it was taken from.
+
+    The body can also be an already serialised bulk payload that's streamed to Elasticsearch, like a [`SyncBody`][SyncBody] created from a reader or an [`AsyncBody`][AsyncBody] created from a stream.
+    Operations can't be pushed onto a streamed body, but the payload never needs to be held in memory all at once.
+
+    # Examples
+
+    Send batches of documents using the same buffer:
+
+    ```no_run
+    # extern crate bytes;
+    # extern crate serde;
+    # #[macro_use]
+    # extern crate serde_derive;
+    # #[macro_use]
+    # extern crate elastic_derive;
+    # extern crate elastic;
+    # use bytes::BytesMut;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Serialize, Deserialize, ElasticType)]
+    # struct MyType {
+    #     #[elastic(id)]
+    #     pub id: String,
+    # }
+    # let client = SyncClientBuilder::new().build()?;
+    # let batches: Vec<Vec<MyType>> = vec![];
+    let mut buf = BytesMut::with_capacity(1024 * 1024);
+
+    for batch in batches {
+        // The allocation is reused once the last request's body has been dropped
+        buf.reserve(1024 * 1024);
+
+        let response = client
+            .bulk_with_body(buf.take())
+            .extend(batch.into_iter().map(|doc| bulk::<MyType>().index(doc)))
+            .send()?;
+
+        assert!(response.is_ok());
+    }
+    # Ok(())
+    # }
+    ```
+
+    Stream a bulk payload from a file:
+
+    ```no_run
+    # extern crate elastic;
+    # use std::fs::File;
+    # use elastic::prelude::*;
+    # use elastic::http::SyncBody;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    let payload = File::open("payload.ndjson")?;
+
+    let response = client
+        .bulk_with_body(SyncBody::from_reader(payload))
+        .index("myindex")
+        .send()?;
+
+    assert!(response.is_ok());
+    # Ok(())
+    # }
+    ```
+
+    [BulkRequestBuilder]: requests/bulk/type.BulkRequestBuilder.html
+    [Client.bulk]: #method.bulk
+    [SyncBody]: ../http/struct.SyncBody.html
+    [AsyncBody]: ../http/struct.AsyncBody.html
+    */
+    pub fn bulk_with_body<TBody>(
+        &self,
+        body: TBody,
+    ) -> BulkRequestBuilder<TSender, TBody, BulkResponse> {
+        RequestBuilder::initial(
+            self.clone(),
+            BulkRequestInner {
+                index: None,
+                ty: None,
+                body: WrappedBody::new(body),
+                _marker: PhantomData,
+            },
+        )
+    }
+
+    /**
+    Create a [`BulkRequestBuilder`][BulkRequestBuilder] that indexes every document in a collection.
+
+    Each document is wrapped in an `index` operation that uses the index, type and id of the document.
+    This is the same as calling [`bulk`][Client.bulk] and extending it with [`bulk::<TDocument>().index(doc)`][bulk] operations.
+    The returned builder can be configured further before sending.
+
+    # Examples
+
+    Index a collection of documents:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use]
+    # extern crate serde_derive;
+    # #[macro_use]
+    # extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    #[derive(Serialize, Deserialize, ElasticType)]
+    struct MyType {
+        #[elastic(id)]
+        pub id: String,
+        pub title: String,
+    }
+
+    # let client = SyncClientBuilder::new().build()?;
+    let docs = (0..1000).map(|i| MyType {
+        id: i.to_string(),
+        title: "some string value".to_owned(),
+    });
+
+    let response = client.bulk_index_all(docs).send()?;
+
+    assert!(response.is_ok());
+    # Ok(())
+    # }
+    ```
+
+    [BulkRequestBuilder]: requests/bulk/type.BulkRequestBuilder.html
+    [Client.bulk]: #method.bulk
+    [bulk]: requests/bulk/fn.bulk.html
+    */
+    pub fn bulk_index_all<TIter, TDocument>(
+        &self,
+        docs: TIter,
+    ) -> BulkRequestBuilder<TSender, Vec<u8>, BulkResponse>
+    where
+        TIter: IntoIterator<Item = TDocument>,
+        TDocument: DocumentType + Serialize,
+    {
+        self.bulk()
+            .extend(docs.into_iter().map(|doc| bulk::<TDocument>().index(doc)))
+    }
+
+    /**
+    Create a [`BulkRequestBuilder`][BulkRequestBuilder] that creates every document in a collection.
+
+    Each document is wrapped in a `create` operation that uses the index, type and id of the document.
+    Unlike [`bulk_index_all`][Client.bulk_index_all], documents that already exist aren't replaced and are returned as errors in the response.
+
+    [BulkRequestBuilder]: requests/bulk/type.BulkRequestBuilder.html
+    [Client.bulk_index_all]: #method.bulk_index_all
+    */
+    pub fn bulk_create_all<TIter, TDocument>(
+        &self,
+        docs: TIter,
+    ) -> BulkRequestBuilder<TSender, Vec<u8>, BulkResponse>
+    where
+        TIter: IntoIterator<Item = TDocument>,
+        TDocument: DocumentType + Serialize,
+    {
+        self.bulk()
+            .extend(docs.into_iter().map(|doc| bulk::<TDocument>().create(doc)))
+    }
 }
 
 /**
@@ -437,6 +624,17 @@ where
         }
         self
     }
+
+    /**
+    Get the estimated size of the request body in bytes.
+
+    Operations are serialised as they're pushed, so this is the size of the body that would be sent for the operations pushed so far.
+    Operations that failed to serialise aren't counted.
+    This can be used to decide when to send a request before pushing more operations onto it.
+    */
+    pub fn estimated_body_size(&self) -> usize {
+        self.inner.body.inner.estimated_size()
+    }
 }
 
 impl<TSender, TBody, TDocument, TResponse> Extend<BulkOperation<TDocument>>
@@ -493,6 +691,22 @@ impl<TDocument, TResponse> BulkRequestBuilder<AsyncSender, Streamed<TDocument>,
         self
     }
 
+    /**
+    Specify a maximum number of operations per request.
+
+    This parameter can be used to control the maximum number of operations in a single bulk request emitted.
+    Operations will be appended to the request until either the `body_size` or the number of operations is reached.
+    By default, the number of operations per request isn't limited.
+    */
+    pub fn body_size_ops(mut self, body_size_ops: usize) -> Self {
+        self.inner.body.with_inner_mut(|s| {
+            s.body_size_ops = Some(body_size_ops);
+            Ok(())
+        });
+
+        self
+    }
+
     /**
     Create a channel for streaming bulk operations.
 
@@ -506,6 +720,136 @@ impl<TDocument, TResponse> BulkRequestBuilder<AsyncSender, Streamed<TDocument>,
     > TODO
     */
     pub fn build(self) -> (BulkSender<TDocument, TResponse>, BulkReceiver<TResponse>) {
+        let (req_template, timeout, body) = self.into_sender_parts();
+
+        BulkSender::new(req_template, timeout, body)
+    }
+
+    /**
+    Create a sink for streaming bulk operations.
+
+    This will return a [`BulkSink`] that operations can be pushed or forwarded into.
+    Once an internal buffer is full, the maximum number of operations is reached or a timeout expires then the bulk request will be sent.
+    The responses to bulk requests aren't emitted by the sink, so if you need to inspect individual operation results use [`build`] instead.
+
+    # Examples
+
+    Forward a stream of operations into a bulk sink:
+
+    ```no_run
+    # extern crate serde;
+    # extern crate futures;
+    # extern crate tokio;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use std::time::Duration;
+    # use futures::{Future, Stream};
+    # use elastic::prelude::*;
+    # use elastic::Error;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Serialize, Deserialize, ElasticType)]
+    # struct MyType {
+    #     pub id: String,
+    #     pub title: String,
+    # }
+    # let client = AsyncClientBuilder::new().build()?;
+    let bulk_sink = client.bulk_stream()
+        .index("bulk_idx")
+        .ty(MyType::static_ty())
+        .timeout(Duration::from_secs(5))
+        .body_size_ops(100)
+        .build_sink();
+
+    let ops = (0..1000)
+        .into_iter()
+        .map(|i| bulk::<MyType>().index(MyType {
+                id: i.to_string(),
+                title: "some string value".into()
+            })
+            .id(i));
+
+    let req_future = futures::stream::iter_ok::<_, Error>(ops).forward(bulk_sink);
+    # Ok(())
+    # }
+    ```
+
+    [`BulkSink`]: struct.BulkSink.html
+    [`build`]: #method.build
+    */
+    pub fn build_sink(self) -> BulkSink<TDocument, TResponse> {
+        let (req_template, timeout, body) = self.into_sender_parts();
+
+        BulkSink::new(req_template, timeout, body)
+    }
+
+    /**
+    Create a background processor for bulk operations.
+
+    This will return a [`BulkProcessorBuilder`] that can configure the maximum number of in-flight requests and callbacks to run before and after each request.
+    The processor batches operations like a [`BulkSink`], but sends them from a separate task so services can queue operations without waiting for them to be sent.
+
+    # Examples
+
+    Spawn a processor that allows up to 4 requests in-flight at once and logs failed operations:
+
+    ```no_run
+    # extern crate serde;
+    # extern crate futures;
+    # extern crate futures_util;
+    # extern crate tokio;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use std::time::Duration;
+    # use futures::{Future, Sink};
+    # use futures_util::compat::Future01CompatExt;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Serialize, Deserialize, ElasticType)]
+    # struct MyType {
+    #     pub id: String,
+    #     pub title: String,
+    # }
+    # let client = AsyncClientBuilder::new().build()?;
+    let (processor, task) = client.bulk_stream()
+        .index("bulk_idx")
+        .ty(MyType::static_ty())
+        .timeout(Duration::from_secs(5))
+        .body_size_ops(100)
+        .build_processor()
+        .max_in_flight(4)
+        .after_flush(|flush, res: Result<&BulkResponse, _>| match res {
+            Ok(res) => for op in res.iter().filter_map(Result::err) {
+                println!("request {}: err: {:?}", flush.id(), op);
+            },
+            Err(e) => println!("request {} failed: {}", flush.id(), e),
+        })
+        .build();
+
+    // Queue a document without waiting for it to be sent
+    let queued = processor.clone().send(bulk::<MyType>().index(MyType {
+        id: "1".into(),
+        title: "some string value".into()
+    }));
+
+    let _ = tokio::runtime::Runtime::new()?.block_on(task.compat());
+    # Ok(())
+    # }
+    ```
+
+    [`BulkProcessorBuilder`]: struct.BulkProcessorBuilder.html
+    [`BulkSink`]: struct.BulkSink.html
+    */
+    pub fn build_processor(self) -> BulkProcessorBuilder<TDocument, TResponse> {
+        let (req_template, timeout, body) = self.into_sender_parts();
+
+        BulkProcessorBuilder::new(req_template, timeout, body)
+    }
+
+    fn into_sender_parts(self) -> (SenderRequestTemplate<TResponse>, Timeout, SenderBody) {
         let body = self
             .inner
             .body
@@ -513,22 +857,20 @@ impl<TDocument, TResponse> BulkRequestBuilder<AsyncSender, Streamed<TDocument>,
             .expect("building a stream should be infallible");
 
         let body_size = body.body_size;
+        let body_size_ops = body.body_size_ops;
         let duration = body.timeout;
 
         let params = self.params_builder.into_value(RequestParams::default);
-        let body = SenderBody::new(body_size);
+        let body = SenderBody::new(body_size, body_size_ops);
         let timeout = Timeout::new(duration);
         let req_template =
             SenderRequestTemplate::new(self.client, params, self.inner.index, self.inner.ty);
 
-        BulkSender::new(req_template, timeout, body)
+        (req_template, timeout, body)
     }
 }
 
-impl<TBody, TResponse> BulkRequestInner<TBody, TResponse>
-where
-    TBody: BulkBody,
-{
+impl<TBody, TResponse> BulkRequestInner<TBody, TResponse> {
     fn into_request(self) -> Result<BulkRequest<'static, TBody>, Error> {
         let body = self.body.try_into_inner()?;
 
@@ -551,7 +893,7 @@ where
 */
 impl<TBody, TResponse> BulkRequestBuilder<SyncSender, TBody, TResponse>
 where
-    TBody: Into<SyncBody> + BulkBody + Send + 'static,
+    TBody: Into<SyncBody> + Send + 'static,
     TResponse: DeserializeOwned + IsOk + Send + 'static,
 {
     /**
@@ -619,7 +961,7 @@ where
 */
 impl<TBody, TResponse> BulkRequestBuilder<AsyncSender, TBody, TResponse>
 where
-    TBody: Into<AsyncBody> + BulkBody + Send + 'static,
+    TBody: Into<AsyncBody> + Send + 'static,
     TResponse: DeserializeOwned + IsOk + Send + 'static,
 {
     /**
@@ -631,17 +973,14 @@ where
 
     Send a bulk request to index some documents:
 
-    ```no_run
+    ```edition2018,no_run
     # extern crate serde;
-    # extern crate futures;
-    # extern crate tokio;
     # #[macro_use] extern crate serde_derive;
     # #[macro_use] extern crate elastic_derive;
     # extern crate elastic;
-    # use futures::Future;
     # use elastic::prelude::*;
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # #[derive(Serialize, Deserialize, ElasticType)]
     # struct MyType {
     #     pub id: String,
@@ -656,22 +995,19 @@ where
             })
             .id(i));
 
-    let future = client.bulk()
-                       .index("myindex")
-                       .ty(MyType::static_ty())
-                       .extend(ops)
-                       .send();
+    let response = client.bulk()
+                         .index("myindex")
+                         .ty(MyType::static_ty())
+                         .extend(ops)
+                         .send()
+                         .await?;
 
-    future.and_then(|response| {
-        for op in response {
-            match op {
-                Ok(op) => println!("ok: {:?}", op),
-                Err(op) => println!("err: {:?}", op),
-            }
+    for op in response {
+        match op {
+            Ok(op) => println!("ok: {:?}", op),
+            Err(op) => println!("err: {:?}", op),
         }
-
-        Ok(())
-    });
+    }
     # Ok(())
     # }
     ```
@@ -686,7 +1022,8 @@ where
         let res_future = req_future.and_then(move |req| {
             RequestBuilder::new(client, params_builder, RawRequestInner::new(req))
                 .send()
-                .and_then(|res| res.into_response())
+                .compat()
+                .and_then(|res| res.into_response().compat())
         });
 
         Pending::new(res_future)
@@ -701,6 +1038,7 @@ A streaming bulk request body.
 */
 pub struct Streamed<TDocument> {
     body_size: usize,
+    body_size_ops: Option<usize>,
     timeout: Duration,
     _marker: PhantomData<TDocument>,
 }
@@ -709,6 +1047,7 @@ impl<TDocument> Streamed<TDocument> {
     fn new() -> Self {
         Streamed {
             body_size: DEFAULT_BODY_SIZE,
+            body_size_ops: None,
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
             _marker: PhantomData,
         }
@@ -796,6 +1135,9 @@ pub trait BulkBody {
     fn push<TDocument>(&mut self, op: BulkOperation<TDocument>) -> Result<(), Error>
     where
         TDocument: Serialize;
+
+    /** Get the estimated size of the body in bytes. */
+    fn estimated_size(&self) -> usize;
 }
 
 impl BulkBody for Vec<u8> {
@@ -803,15 +1145,60 @@ impl BulkBody for Vec<u8> {
     where
         TDocument: Serialize,
     {
-        op.write(self).map_err(error::request)?;
+        let op_start = self.len();
+
+        // Don't leave a partially written operation in the body
+        if let Err(e) = op.write(&mut *self) {
+            self.truncate(op_start);
+            return Err(error::request(e));
+        }
 
         Ok(())
     }
+
+    fn estimated_size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl BulkBody for BytesMut {
+    fn push<TDocument>(&mut self, op: BulkOperation<TDocument>) -> Result<(), Error>
+    where
+        TDocument: Serialize,
+    {
+        let op_start = self.len();
+
+        // Don't leave a partially written operation in the body
+        if let Err(e) = op.write(BytesMutWriter(&mut *self)) {
+            self.truncate(op_start);
+            return Err(error::request(e));
+        }
+
+        Ok(())
+    }
+
+    fn estimated_size(&self) -> usize {
+        self.len()
+    }
+}
+
+/** A writer that grows a `BytesMut` as operations are written to it. */
+pub(super) struct BytesMutWriter<'a>(pub(super) &'a mut BytesMut);
+
+impl<'a> io::Write for BytesMutWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 /** A future returned by calling `send`. */
 pub struct Pending<TResponse> {
-    inner: Box<Future<Item = TResponse, Error = Error> + Send>,
+    inner: BoxFuture<TResponse>,
 }
 
 impl<TResponse> Pending<TResponse> {
@@ -820,19 +1207,12 @@ impl<TResponse> Pending<TResponse> {
         F: Future<Item = TResponse, Error = Error> + Send + 'static,
     {
         Pending {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl<TResponse> Future for Pending<TResponse> {
-    type Item = TResponse;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(Pending<TResponse> => TResponse, TResponse);
 
 #[doc(hidden)]
 pub trait ChangeIndex<TIndex> {
@@ -923,6 +1303,190 @@ mod tests {
         assert_eq!("/test-idx/new-ty/_bulk", req.url.as_ref());
     }
 
+    #[test]
+    fn estimated_body_size() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let op = bulk_raw().index(json!({ "id": 1 })).id(1);
+
+        let mut expected = Vec::new();
+        op.write(&mut expected).unwrap();
+
+        let req = client.bulk().push(op);
+
+        assert_eq!(expected.len(), req.estimated_body_size());
+    }
+
+    #[derive(Serialize, ElasticType)]
+    struct TestDoc {
+        #[elastic(id)]
+        id: String,
+    }
+
+    #[test]
+    fn index_all() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let docs = vec![
+            TestDoc { id: "1".to_owned() },
+            TestDoc { id: "2".to_owned() },
+        ];
+
+        let req = client.bulk_index_all(docs).inner.into_request().unwrap();
+
+        let expected = concat!(
+            r#"{"index":{"_index":"testdoc","_type":"_doc","_id":"1"}}"#,
+            "\n",
+            r#"{"id":"1"}"#,
+            "\n",
+            r#"{"index":{"_index":"testdoc","_type":"_doc","_id":"2"}}"#,
+            "\n",
+            r#"{"id":"2"}"#,
+            "\n",
+        );
+
+        assert_eq!("/_bulk", req.url.as_ref());
+        assert_eq!(expected, String::from_utf8(req.body).unwrap());
+    }
+
+    #[test]
+    fn create_all() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .bulk_create_all(vec![TestDoc { id: "1".to_owned() }])
+            .inner
+            .into_request()
+            .unwrap();
+
+        let expected = concat!(
+            r#"{"create":{"_index":"testdoc","_type":"_doc","_id":"1"}}"#,
+            "\n",
+            r#"{"id":"1"}"#,
+            "\n",
+        );
+
+        assert_eq!(expected, String::from_utf8(req.body).unwrap());
+    }
+
+    #[derive(Serialize, ElasticType)]
+    struct RoutedDoc {
+        #[elastic(id)]
+        id: String,
+        #[elastic(routing)]
+        tenant: String,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(index = "logs-{timestamp:%Y.%m.%d}")]
+    struct DailyDoc {
+        timestamp: Date<DefaultDateMapping<EpochMillis>>,
+    }
+
+    #[test]
+    fn index_all_templated_index() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let docs = vec![
+            DailyDoc {
+                timestamp: Date::build(2015, 5, 14, 0, 0, 0, 0),
+            },
+            DailyDoc {
+                timestamp: Date::build(2015, 5, 15, 0, 0, 0, 0),
+            },
+        ];
+
+        let req = client.bulk_index_all(docs).inner.into_request().unwrap();
+
+        let expected = concat!(
+            r#"{"index":{"_index":"logs-2015.05.14","_type":"_doc"}}"#,
+            "\n",
+            r#"{"timestamp":"1431561600000"}"#,
+            "\n",
+            r#"{"index":{"_index":"logs-2015.05.15","_type":"_doc"}}"#,
+            "\n",
+            r#"{"timestamp":"1431648000000"}"#,
+            "\n",
+        );
+
+        assert_eq!(expected, String::from_utf8(req.body).unwrap());
+    }
+
+    #[test]
+    fn routing_from_doc() {
+        let mut body = Vec::new();
+
+        bulk()
+            .index(RoutedDoc {
+                id: "1".to_owned(),
+                tenant: "tenant123".to_owned(),
+            })
+            .write(&mut body)
+            .unwrap();
+
+        bulk::<RoutedDoc>()
+            .delete("2")
+            .routing("tenant123")
+            .write(&mut body)
+            .unwrap();
+
+        let expected = concat!(
+            r#"{"index":{"_index":"routeddoc","_type":"_doc","_id":"1","routing":"tenant123"}}"#,
+            "\n",
+            r#"{"id":"1","tenant":"tenant123"}"#,
+            "\n",
+            r#"{"delete":{"_index":"routeddoc","_type":"_doc","_id":"2","routing":"tenant123"}}"#,
+            "\n",
+        );
+
+        assert_eq!(expected, String::from_utf8(body).unwrap());
+    }
+
+    #[test]
+    fn update_script_upsert() {
+        let mut body = Vec::new();
+
+        bulk::<TestDoc>()
+            .update_script_fluent("1", "ctx._source.count += params.count", |script| {
+                script.param("count", 1)
+            })
+            .upsert(json!({ "count": 1 }))
+            .retry_on_conflict(3)
+            .write(&mut body)
+            .unwrap();
+
+        let expected = concat!(
+            r#"{"update":{"_index":"testdoc","_type":"_doc","_id":"1","retry_on_conflict":3}}"#,
+            "\n",
+            r#"{"script":{"inline":"ctx._source.count += params.count","params":{"count":1}},"upsert":{"count":1}}"#,
+            "\n",
+        );
+
+        assert_eq!(expected, String::from_utf8(body).unwrap());
+    }
+
+    #[test]
+    fn update_script_scripted_upsert() {
+        let mut body = Vec::new();
+
+        bulk_raw()
+            .update_script("ctx._source.count = (ctx._source.count ?: 0) + 1")
+            .index("counters")
+            .id(1)
+            .scripted_upsert()
+            .write(&mut body)
+            .unwrap();
+
+        let expected = concat!(
+            r#"{"update":{"_index":"counters","_id":"1"}}"#,
+            "\n",
+            r#"{"script":{"inline":"ctx._source.count = (ctx._source.count ?: 0) + 1"},"scripted_upsert":true,"upsert":{}}"#,
+            "\n",
+        );
+
+        assert_eq!(expected, String::from_utf8(body).unwrap());
+    }
+
     #[test]
     fn specify_ty_without_index() {
         let client = SyncClientBuilder::new().build().unwrap();
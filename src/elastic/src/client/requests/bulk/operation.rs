@@ -12,7 +12,10 @@ use serde::ser::{
     SerializeMap,
     Serializer,
 };
-use serde_json;
+use serde_json::{
+    self,
+    Value,
+};
 
 use client::requests::{
     common::{
@@ -20,6 +23,7 @@ use client::requests::{
         Doc,
         Script,
         ScriptBuilder,
+        ScriptUpsert,
     },
     params::{
         Id,
@@ -60,6 +64,10 @@ struct BulkHeader {
         skip_serializing_if = "Option::is_none"
     )]
     id: Option<Id<'static>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    routing: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_on_conflict: Option<u32>,
 }
 
 fn serialize_param<S, T>(field: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
@@ -91,6 +99,53 @@ impl<TParams> BulkOperation<Script<TParams>> {
             inner,
         }
     }
+
+    /**
+    Insert the given document if the document to update doesn't exist.
+
+    The script isn't run when the document is inserted unless `scripted_upsert` is also set.
+    */
+    pub fn upsert<TDocument>(
+        self,
+        doc: TDocument,
+    ) -> BulkOperation<ScriptUpsert<TParams, TDocument>> {
+        let inner = self.inner.map(|script| ScriptUpsert::value(script, doc));
+
+        BulkOperation {
+            action: self.action,
+            header: self.header,
+            inner,
+        }
+    }
+
+    /**
+    Run the script whether or not the document to update exists.
+
+    If the document doesn't exist then the script is run against an empty document.
+    */
+    pub fn scripted_upsert(self) -> BulkOperation<ScriptUpsert<TParams, Value>> {
+        let inner = self
+            .inner
+            .map(|script| ScriptUpsert::empty(script).scripted_upsert(true));
+
+        BulkOperation {
+            action: self.action,
+            header: self.header,
+            inner,
+        }
+    }
+}
+
+impl<TParams, TDocument> BulkOperation<ScriptUpsert<TParams, TDocument>> {
+    /**
+    Run the script whether or not the document to update exists.
+
+    If the document doesn't exist then the script is run against the upsert document.
+    */
+    pub fn scripted_upsert(mut self) -> Self {
+        self.inner = self.inner.map(|inner| inner.scripted_upsert(true));
+        self
+    }
 }
 
 impl<TValue> BulkOperation<TValue> {
@@ -120,6 +175,22 @@ impl<TValue> BulkOperation<TValue> {
         self.header.id = Some(id.into());
         self
     }
+
+    /**
+    Set the routing value for this bulk operation.
+    */
+    pub fn routing(mut self, routing: impl Into<String>) -> Self {
+        self.header.routing = Some(routing.into());
+        self
+    }
+
+    /**
+    Set the number of times to retry an update operation if there's a version conflict.
+    */
+    pub fn retry_on_conflict(mut self, retries: u32) -> Self {
+        self.header.retry_on_conflict = Some(retries);
+        self
+    }
 }
 
 impl<TDocument> BulkOperation<TDocument>
@@ -168,11 +239,11 @@ where
                 inner: &self.header,
             },
         )?;
-        write!(&mut writer, "\n")?;
+        writer.write_all(b"\n")?;
 
         if let Some(ref inner) = self.inner {
             serde_json::to_writer(&mut writer, inner)?;
-            write!(&mut writer, "\n")?;
+            writer.write_all(b"\n")?;
         }
 
         Ok(())
@@ -200,6 +271,8 @@ where
                 index: Some(Index::from(doc.index().into_owned())),
                 ty: Some(Type::from(doc.ty().into_owned())),
                 id: doc.partial_id().map(|id| Id::from(id.into_owned())),
+                routing: doc.partial_routing().map(|routing| routing.into_owned()),
+                retry_on_conflict: None,
             },
             inner: Some(doc),
         }
@@ -212,6 +285,8 @@ where
                 index: Some(Index::from(doc.index().into_owned())),
                 ty: Some(Type::from(doc.ty().into_owned())),
                 id: doc.partial_id().map(|id| Id::from(id.into_owned())),
+                routing: doc.partial_routing().map(|routing| routing.into_owned()),
+                retry_on_conflict: None,
             },
             inner: Some(Doc::value(doc)),
         }
@@ -232,6 +307,8 @@ where
                 index: TDocument::partial_static_index().map(Into::into),
                 ty: TDocument::partial_static_ty().map(Into::into),
                 id: Some(id.into()),
+                routing: None,
+                retry_on_conflict: None,
             },
             inner: Some(Script::new(script)),
         }
@@ -254,6 +331,8 @@ where
                 index: TDocument::partial_static_index().map(Into::into),
                 ty: TDocument::partial_static_ty().map(Into::into),
                 id: Some(id.into()),
+                routing: None,
+                retry_on_conflict: None,
             },
             inner: Some(Script::new(script)),
         }
@@ -267,6 +346,8 @@ where
                 index: Some(Index::from(doc.index().into_owned())),
                 ty: Some(Type::from(doc.ty().into_owned())),
                 id: doc.partial_id().map(|id| Id::from(id.into_owned())),
+                routing: doc.partial_routing().map(|routing| routing.into_owned()),
+                retry_on_conflict: None,
             },
             inner: Some(doc),
         }
@@ -282,6 +363,8 @@ where
                 index: TDocument::partial_static_index().map(Into::into),
                 ty: TDocument::partial_static_ty().map(Into::into),
                 id: Some(id.into()),
+                routing: None,
+                retry_on_conflict: None,
             },
             inner: None,
         }
@@ -311,6 +394,8 @@ impl BulkRawOperation {
                 index: None,
                 ty: None,
                 id: None,
+                routing: None,
+                retry_on_conflict: None,
             },
             inner: Some(doc),
         }
@@ -323,6 +408,8 @@ impl BulkRawOperation {
                 index: None,
                 ty: None,
                 id: None,
+                routing: None,
+                retry_on_conflict: None,
             },
             inner: Some(Doc::value(doc)),
         }
@@ -338,12 +425,14 @@ impl BulkRawOperation {
                 index: None,
                 ty: None,
                 id: None,
+                routing: None,
+                retry_on_conflict: None,
             },
             inner: Some(Script::new(script)),
         }
     }
 
-    pub fn update_script_fluent<TId, TScript, TBuilder, TParams>(
+    pub fn update_script_fluent<TScript, TBuilder, TParams>(
         self,
         script: TScript,
         builder: TBuilder,
@@ -358,6 +447,8 @@ impl BulkRawOperation {
                 index: None,
                 ty: None,
                 id: None,
+                routing: None,
+                retry_on_conflict: None,
             },
             inner: Some(Script::new(script)),
         }
@@ -371,6 +462,8 @@ impl BulkRawOperation {
                 index: None,
                 ty: None,
                 id: None,
+                routing: None,
+                retry_on_conflict: None,
             },
             inner: Some(doc),
         }
@@ -383,6 +476,8 @@ impl BulkRawOperation {
                 index: None,
                 ty: None,
                 id: None,
+                routing: None,
+                retry_on_conflict: None,
             },
             inner: None,
         }
@@ -0,0 +1,497 @@
+use std::{
+    marker::PhantomData,
+    sync::Arc,
+};
+
+use futures::{
+    stream::FuturesUnordered,
+    sync::mpsc,
+    Async,
+    Future,
+    Poll,
+    Sink,
+    StartSend,
+    Stream,
+};
+use serde::{
+    de::DeserializeOwned,
+    ser::Serialize,
+};
+use futures_util::compat::Future01CompatExt;
+use tokio;
+
+use super::{
+    stream::{
+        SenderBody,
+        SenderRequestTemplate,
+        Timeout,
+    },
+    BulkOperation,
+};
+use client::{
+    responses::parse::IsOk,
+    sender::shutdown::BackgroundTask,
+};
+use error::{
+    self,
+    Error,
+};
+
+const DEFAULT_MAX_IN_FLIGHT: usize = 1;
+const DEFAULT_CAPACITY: usize = 1024;
+
+/**
+A builder for a [`BulkProcessor`][BulkProcessor].
+
+Call [`build_processor`][build_processor] on a streaming bulk request builder to get a `BulkProcessorBuilder`.
+
+[BulkProcessor]: struct.BulkProcessor.html
+[build_processor]: type.BulkRequestBuilder.html#method.build_processor
+*/
+pub struct BulkProcessorBuilder<TDocument, TResponse> {
+    req_template: SenderRequestTemplate<TResponse>,
+    timeout: Timeout,
+    body: SenderBody,
+    max_in_flight: usize,
+    capacity: usize,
+    callbacks: Callbacks<TResponse>,
+    _marker: PhantomData<TDocument>,
+}
+
+type BeforeFlush = Box<Fn(&BulkFlush) + Send + Sync>;
+type AfterFlush<TResponse> = Box<Fn(&BulkFlush, Result<&TResponse, &Error>) + Send + Sync>;
+
+struct Callbacks<TResponse> {
+    before_flush: Option<BeforeFlush>,
+    after_flush: Option<AfterFlush<TResponse>>,
+}
+
+/**
+Details of a single bulk request sent by a [`BulkProcessor`][BulkProcessor].
+
+[BulkProcessor]: struct.BulkProcessor.html
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkFlush {
+    id: u64,
+    ops: usize,
+    bytes: usize,
+}
+
+impl BulkFlush {
+    /** A sequential id for the request, starting from `0`. */
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /** The number of operations in the request. */
+    pub fn ops(&self) -> usize {
+        self.ops
+    }
+
+    /** The size of the request body in bytes. */
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+impl<TDocument, TResponse> BulkProcessorBuilder<TDocument, TResponse> {
+    pub(super) fn new(
+        req_template: SenderRequestTemplate<TResponse>,
+        timeout: Timeout,
+        body: SenderBody,
+    ) -> Self {
+        BulkProcessorBuilder {
+            req_template,
+            timeout,
+            body,
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+            capacity: DEFAULT_CAPACITY,
+            callbacks: Callbacks {
+                before_flush: None,
+                after_flush: None,
+            },
+            _marker: PhantomData,
+        }
+    }
+
+    /**
+    Specify the maximum number of bulk requests that can be in-flight at once.
+
+    Once this limit is reached, operations are buffered until a request completes.
+    The default is `1`.
+    */
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = usize::max(max_in_flight, 1);
+        self
+    }
+
+    /**
+    Specify the number of operations that can be queued before the processor applies backpressure.
+
+    Operations are queued while the current request body is full and the maximum number of requests are in-flight.
+    The default is `1024`.
+    */
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /**
+    Specify a function to call before each bulk request is sent.
+    */
+    pub fn before_flush(mut self, before_flush: impl Fn(&BulkFlush) + Send + Sync + 'static) -> Self {
+        self.callbacks.before_flush = Some(Box::new(before_flush));
+        self
+    }
+
+    /**
+    Specify a function to call after each bulk request completes.
+
+    The function receives either the response or the error for the request.
+    If no function is given then errors are logged and discarded.
+    */
+    pub fn after_flush(
+        mut self,
+        after_flush: impl Fn(&BulkFlush, Result<&TResponse, &Error>) + Send + Sync + 'static,
+    ) -> Self {
+        self.callbacks.after_flush = Some(Box::new(after_flush));
+        self
+    }
+
+    /**
+    Build a processor and the background task that sends its requests.
+
+    The task needs to be spawned onto an executor for the processor to make progress.
+    The task completes once every clone of the processor has been dropped, or the client has been [closed][close], and any buffered operations have been sent.
+
+    [close]: ../../struct.Client.html#method.close
+    */
+    pub fn build(
+        mut self,
+    ) -> (
+        BulkProcessor<TDocument>,
+        BulkProcessorTask<TDocument, TResponse>,
+    ) {
+        let (tx, rx) = mpsc::channel(self.capacity);
+        let shutdown = self.req_template.background_task();
+
+        let processor = BulkProcessor { tx };
+
+        let task = BulkProcessorTask {
+            rx: Some(rx),
+            shutdown,
+            req_template: self.req_template,
+            timeout: self.timeout,
+            body: self.body,
+            in_flight: FuturesUnordered::new(),
+            max_in_flight: self.max_in_flight,
+            next_id: 0,
+            callbacks: Arc::new(self.callbacks),
+        };
+
+        (processor, task)
+    }
+}
+
+impl<TDocument, TResponse> BulkProcessorBuilder<TDocument, TResponse>
+where
+    TDocument: Serialize + Send + 'static,
+    TResponse: DeserializeOwned + IsOk + Send + 'static,
+{
+    /**
+    Build a processor and spawn its background task onto the current `tokio` 1 runtime.
+
+    # Panics
+
+    This method will panic if it's called outside of a `tokio` runtime.
+    */
+    pub fn spawn(self) -> BulkProcessor<TDocument> {
+        let (processor, task) = self.build();
+
+        tokio::spawn(task.compat());
+
+        processor
+    }
+}
+
+/**
+A handle for queuing bulk operations on a background processor.
+
+The processor is a `Sink` for bulk operations that can be cloned and shared between tasks.
+It will stop accepting operations while its queue is full, so producers are slowed down when Elasticsearch can't keep up.
+Operations are batched into bulk requests by a [`BulkProcessorTask`][BulkProcessorTask].
+The responses to individual operations can be inspected using an [`after_flush`][after_flush] callback.
+
+[BulkProcessorTask]: struct.BulkProcessorTask.html
+[after_flush]: struct.BulkProcessorBuilder.html#method.after_flush
+*/
+pub struct BulkProcessor<TDocument> {
+    tx: mpsc::Sender<BulkOperation<TDocument>>,
+}
+
+impl<TDocument> Clone for BulkProcessor<TDocument> {
+    fn clone(&self) -> Self {
+        BulkProcessor {
+            tx: self.tx.clone(),
+        }
+    }
+}
+
+impl<TDocument> Sink for BulkProcessor<TDocument> {
+    type SinkItem = BulkOperation<TDocument>;
+    type SinkError = Error;
+
+    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        self.tx
+            .start_send(item)
+            .map_err(|_| error::request(error::message("the bulk processor has shut down")))
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        self.tx
+            .poll_complete()
+            .map_err(|_| error::request(error::message("the bulk processor has shut down")))
+    }
+
+    fn close(&mut self) -> Poll<(), Self::SinkError> {
+        self.tx
+            .close()
+            .map_err(|_| error::request(error::message("the bulk processor has shut down")))
+    }
+}
+
+/**
+The background task for a [`BulkProcessor`][BulkProcessor].
+
+The task pulls operations from the processor queue into a buffer and sends it once a timer has expired, the buffer fills up or the maximum number of operations is reached.
+
+[BulkProcessor]: struct.BulkProcessor.html
+*/
+pub struct BulkProcessorTask<TDocument, TResponse> {
+    rx: Option<mpsc::Receiver<BulkOperation<TDocument>>>,
+    shutdown: BackgroundTask,
+    req_template: SenderRequestTemplate<TResponse>,
+    timeout: Timeout,
+    body: SenderBody,
+    in_flight: FuturesUnordered<Box<Future<Item = (), Error = ()> + Send>>,
+    max_in_flight: usize,
+    next_id: u64,
+    callbacks: Arc<Callbacks<TResponse>>,
+}
+
+impl<TDocument, TResponse> BulkProcessorTask<TDocument, TResponse>
+where
+    TDocument: Serialize + Send + 'static,
+    TResponse: DeserializeOwned + IsOk + Send + 'static,
+{
+    /** Pull operations from the queue until the body is full or there are no more operations. */
+    fn poll_ops(&mut self) {
+        while self.body.has_capacity() || self.body.is_empty() {
+            let poll = match self.rx {
+                Some(ref mut rx) => rx.poll(),
+                None => return,
+            };
+
+            match poll {
+                Ok(Async::Ready(Some(op))) => {
+                    if let Err(e) = self.body.push(op) {
+                        error!(
+                            "Elasticsearch Bulk Processor: failed to write an operation. Caused by: {}",
+                            e
+                        );
+                    }
+                }
+                // Every processor has been dropped
+                Ok(Async::Ready(None)) | Err(()) => self.rx = None,
+                Ok(Async::NotReady) => return,
+            }
+        }
+    }
+
+    /** Send the buffered operations. */
+    fn flush(&mut self) {
+        let ops = self.body.ops();
+        let body = self.body.take();
+
+        let flush = BulkFlush {
+            id: self.next_id,
+            ops,
+            bytes: body.len(),
+        };
+        self.next_id += 1;
+
+        debug!(
+            "Elasticsearch Bulk Processor: sending bulk request {}",
+            flush.id
+        );
+
+        if let Some(ref before_flush) = self.callbacks.before_flush {
+            before_flush(&flush);
+        }
+
+        let callbacks = self.callbacks.clone();
+        let pending = self
+            .req_template
+            .to_request(body)
+            .send()
+            .compat()
+            .then(move |res| {
+                debug!(
+                    "Elasticsearch Bulk Processor: received bulk response {}",
+                    flush.id
+                );
+
+                match callbacks.after_flush {
+                    Some(ref after_flush) => after_flush(&flush, res.as_ref()),
+                    None => {
+                        if let Err(ref e) = res {
+                            error!(
+                                "Elasticsearch Bulk Processor: bulk request {} failed. Caused by: {}",
+                                flush.id, e
+                            );
+                        }
+                    }
+                }
+
+                Ok(())
+            });
+
+        self.in_flight.push(Box::new(pending));
+    }
+}
+
+impl<TDocument, TResponse> Future for BulkProcessorTask<TDocument, TResponse>
+where
+    TDocument: Serialize + Send + 'static,
+    TResponse: DeserializeOwned + IsOk + Send + 'static,
+{
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        // Stop accepting operations once the client is closed, but send the ones already queued
+        if self.rx.is_some() && self.shutdown.poll_closed() {
+            if let Some(ref mut rx) = self.rx {
+                rx.close();
+            }
+        }
+
+        loop {
+            // Clear out any completed requests
+            while let Ok(Async::Ready(Some(()))) = self.in_flight.poll() {}
+
+            self.poll_ops();
+
+            let expired = match self.timeout.poll() {
+                Ok(Async::Ready(())) => true,
+                Ok(Async::NotReady) => false,
+                // Keep sending full bodies if the timer fails
+                Err(e) => {
+                    error!(
+                        "Elasticsearch Bulk Processor: timer failed. Caused by: {}",
+                        e
+                    );
+                    false
+                }
+            };
+
+            let closed = self.rx.is_none();
+            let should_flush = !self.body.is_empty() && (expired || closed || self.body.is_full());
+
+            if should_flush && self.in_flight.len() < self.max_in_flight {
+                self.flush();
+                self.timeout.restart();
+
+                continue;
+            }
+
+            // Restart the timer if there was nothing to send
+            if expired && self.body.is_empty() {
+                self.timeout.restart();
+
+                continue;
+            }
+
+            if closed && self.body.is_empty() && self.in_flight.is_empty() {
+                return Ok(Async::Ready(()));
+            }
+
+            return Ok(Async::NotReady);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prelude::*;
+    use tests::*;
+
+    #[test]
+    fn is_send() {
+        assert_send::<super::BulkProcessor<()>>();
+        assert_send::<super::BulkProcessorTask<(), BulkResponse>>();
+        assert_send::<super::BulkProcessorBuilder<(), BulkResponse>>();
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_close_sends_queued_operations() {
+        use futures::{
+            Future,
+            Sink,
+        };
+        use futures_util::compat::Future01CompatExt;
+        use serde_json::Value;
+
+        use runtime::tests::current_thread;
+
+        use client::sender::mock::{
+            MockResponse,
+            MockSender,
+        };
+        use http::{
+            Method,
+            StatusCode,
+        };
+
+        let mock = MockSender::new();
+        mock.respond(
+            Method::POST,
+            "/myindex/_bulk",
+            MockResponse::new(StatusCode::OK).json(&json!({
+                "took": 1,
+                "errors": false,
+                "items": []
+            })),
+        );
+
+        let client = AsyncClientBuilder::new()
+            .mock(mock.clone())
+            .build()
+            .unwrap();
+
+        let (processor, task) = client
+            .bulk_stream::<Value>()
+            .index("myindex")
+            .build_processor()
+            .build();
+
+        let processor = processor
+            .send(bulk_raw().index(json!({ "title": "a document" })))
+            .wait()
+            .unwrap();
+
+        let close = client.close();
+
+        let runtime = current_thread();
+        runtime.block_on(task.compat()).unwrap();
+        runtime.block_on(close).unwrap();
+
+        assert_eq!(1, mock.requests().len());
+        assert!(processor
+            .send(bulk_raw().index(json!({ "title": "another document" })))
+            .wait()
+            .is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    cmp,
+    time::Duration,
+};
+
+const DEFAULT_MAX_ATTEMPTS: usize = 3;
+const DEFAULT_INITIAL_BACKOFF_MILLIS: u64 = 100;
+const DEFAULT_MAX_BACKOFF_SECS: u64 = 30;
+const RETRYABLE_STATUSES: [u16; 4] = [429, 502, 503, 504];
+
+/**
+A policy for retrying failed items in a bulk request.
+
+Items that fail because Elasticsearch is temporarily overloaded or unavailable are collected and sent again in a new bulk request after waiting for a backoff period.
+The backoff period doubles after each attempt, up to a maximum.
+Items that fail for any other reason, like a version conflict or a mapping error, aren't retried.
+
+The statuses considered retryable are:
+
+- `429 Too Many Requests`
+- `502 Bad Gateway`
+- `503 Service Unavailable`
+- `504 Gateway Timeout`
+
+By default, items are sent at most `3` times with an initial backoff of `100ms`.
+
+# Examples
+
+Retry items up to `5` times, starting with a backoff of `1s`:
+
+```
+# use std::time::Duration;
+# use elastic::prelude::*;
+let retry = BulkRetry::new()
+    .max_attempts(5)
+    .initial_backoff(Duration::from_secs(1));
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkRetry {
+    max_attempts: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for BulkRetry {
+    fn default() -> Self {
+        BulkRetry {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: Duration::from_millis(DEFAULT_INITIAL_BACKOFF_MILLIS),
+            max_backoff: Duration::from_secs(DEFAULT_MAX_BACKOFF_SECS),
+        }
+    }
+}
+
+impl BulkRetry {
+    /** Create a new retry policy with the default settings. */
+    pub fn new() -> Self {
+        BulkRetry::default()
+    }
+
+    /**
+    Specify the maximum number of times an item will be sent, including the first attempt.
+
+    A value of `1` means failed items are never retried.
+    */
+    pub fn max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = usize::max(max_attempts, 1);
+        self
+    }
+
+    /** Specify the time to wait before the first retry. */
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /** Specify the longest time to wait between retries. */
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /** Whether or not another attempt can be made after the given number of attempts. */
+    pub(super) fn can_retry(&self, attempts: usize) -> bool {
+        attempts < self.max_attempts
+    }
+
+    /** The time to wait after the given number of attempts. */
+    pub(super) fn backoff(&self, attempts: usize) -> Duration {
+        let exp = cmp::min(attempts.saturating_sub(1), 31) as u32;
+
+        self.initial_backoff
+            .checked_mul(1 << exp)
+            .map(|backoff| cmp::min(backoff, self.max_backoff))
+            .unwrap_or(self.max_backoff)
+    }
+
+    /** Whether or not an item that failed with the given status should be retried. */
+    pub(super) fn is_retryable(&self, status: u16) -> bool {
+        RETRYABLE_STATUSES.contains(&status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::BulkRetry;
+
+    #[test]
+    fn backoff_doubles() {
+        let retry = BulkRetry::new().initial_backoff(Duration::from_millis(10));
+
+        assert_eq!(Duration::from_millis(10), retry.backoff(1));
+        assert_eq!(Duration::from_millis(20), retry.backoff(2));
+        assert_eq!(Duration::from_millis(40), retry.backoff(3));
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let retry = BulkRetry::new()
+            .initial_backoff(Duration::from_secs(1))
+            .max_backoff(Duration::from_secs(5));
+
+        assert_eq!(Duration::from_secs(5), retry.backoff(4));
+        assert_eq!(Duration::from_secs(5), retry.backoff(100));
+    }
+
+    #[test]
+    fn max_attempts() {
+        let retry = BulkRetry::new().max_attempts(2);
+
+        assert!(retry.can_retry(1));
+        assert!(!retry.can_retry(2));
+    }
+
+    #[test]
+    fn retryable_statuses() {
+        let retry = BulkRetry::new();
+
+        assert!(retry.is_retryable(429));
+        assert!(retry.is_retryable(503));
+        assert!(!retry.is_retryable(409));
+        assert!(!retry.is_retryable(400));
+    }
+}
@@ -0,0 +1,184 @@
+use std::marker::PhantomData;
+
+use futures::{
+    Async,
+    AsyncSink,
+    Future,
+    Poll,
+    Sink,
+};
+use futures_util::compat::Compat;
+use serde::{
+    de::DeserializeOwned,
+    ser::Serialize,
+};
+
+use super::{
+    stream::{
+        SenderBody,
+        SenderRequestTemplate,
+        Timeout,
+    },
+    BulkOperation,
+    Pending,
+};
+use client::responses::parse::IsOk;
+use error::{
+    self,
+    Error,
+};
+
+/**
+A sink for bulk operations.
+
+The sink accepts individual operations and keeps them in a buffer until a timer has expired, the buffer fills up or the maximum number of operations is reached.
+Unlike a [`BulkSender`][BulkSender], a `BulkSink` doesn't emit bulk responses.
+Errors sending a bulk request are returned from the sink, but the results of individual operations are discarded.
+That makes it possible to `forward` a stream of operations straight into Elasticsearch.
+
+Closing the sink will flush any buffered operations immediately, without waiting for the timer to expire.
+
+[BulkSender]: struct.BulkSender.html
+*/
+pub struct BulkSink<TDocument, TResponse> {
+    req_template: SenderRequestTemplate<TResponse>,
+    in_flight: BulkSinkInFlight<TResponse>,
+    timeout: Timeout,
+    body: SenderBody,
+    _marker: PhantomData<TDocument>,
+}
+
+/**
+The current state of the `BulkSink`.
+
+Operations can be pushed onto the body while a single request is in-flight.
+*/
+enum BulkSinkInFlight<TResponse> {
+    ReadyToSend,
+    Pending(Compat<Pending<TResponse>>),
+}
+
+impl<TDocument, TResponse> BulkSink<TDocument, TResponse> {
+    pub(super) fn new(
+        req_template: SenderRequestTemplate<TResponse>,
+        timeout: Timeout,
+        body: SenderBody,
+    ) -> Self {
+        BulkSink {
+            req_template,
+            timeout,
+            body,
+            in_flight: BulkSinkInFlight::ReadyToSend,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<TDocument, TResponse> BulkSink<TDocument, TResponse>
+where
+    TResponse: DeserializeOwned + IsOk + Send + 'static,
+{
+    /**
+    Send any buffered operations.
+
+    If `flush` is `false` then a request is only sent once the body is full or the timer has expired.
+    */
+    fn poll_send(&mut self, flush: bool) -> Poll<(), Error> {
+        loop {
+            let in_flight = match self.in_flight {
+                // The `Sink` is ready to send another request
+                BulkSinkInFlight::ReadyToSend => {
+                    match self.timeout.poll() {
+                        // If the timeout hasn't expired and the body isn't full then we're not ready
+                        Ok(Async::NotReady)
+                            if !flush && !self.body.is_full() && !self.body.is_empty() =>
+                        {
+                            return Ok(Async::NotReady);
+                        }
+                        // Continue
+                        Ok(Async::NotReady) => (),
+                        // Restart the expired timer
+                        Ok(Async::Ready(())) => self.timeout.restart(),
+                        Err(e) => return Err(e),
+                    }
+
+                    if self.body.is_empty() {
+                        return Ok(Async::Ready(()));
+                    }
+
+                    debug!("Elasticsearch Bulk Sink: sending a bulk request");
+
+                    let body = self.body.take();
+
+                    let req = self.req_template.to_request(body);
+
+                    BulkSinkInFlight::Pending(req.send().compat())
+                }
+                // A request is pending
+                BulkSinkInFlight::Pending(ref mut pending) => {
+                    let _ = try_ready!(pending.poll());
+
+                    debug!("Elasticsearch Bulk Sink: received a bulk response");
+
+                    BulkSinkInFlight::ReadyToSend
+                }
+            };
+
+            self.in_flight = in_flight;
+        }
+    }
+}
+
+impl<TDocument, TResponse> Sink for BulkSink<TDocument, TResponse>
+where
+    TDocument: Serialize + Send + 'static,
+    TResponse: DeserializeOwned + IsOk + Send + 'static,
+{
+    type SinkItem = BulkOperation<TDocument>;
+    type SinkError = Error;
+
+    fn start_send(
+        &mut self,
+        item: Self::SinkItem,
+    ) -> Result<AsyncSink<Self::SinkItem>, Self::SinkError> {
+        let expired = match self.timeout.poll() {
+            // Only respect the timeout if the body is not empty
+            Ok(Async::Ready(())) => !self.body.is_empty(),
+            Ok(Async::NotReady) => false,
+            Err(e) => return Err(e),
+        };
+
+        if expired || !self.body.has_capacity() {
+            if let Async::NotReady = self.poll_send(false)? {
+                return Ok(AsyncSink::NotReady(item));
+            }
+        }
+
+        // An empty body will always accept an operation, even if it doesn't fit
+        if self.body.has_capacity() || self.body.is_empty() {
+            self.body.push(item).map_err(error::request)?;
+            Ok(AsyncSink::Ready)
+        } else {
+            Ok(AsyncSink::NotReady(item))
+        }
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        self.poll_send(false)
+    }
+
+    fn close(&mut self) -> Poll<(), Self::SinkError> {
+        self.poll_send(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prelude::*;
+    use tests::*;
+
+    #[test]
+    fn is_send() {
+        assert_send::<super::BulkSink<(), BulkResponse>>();
+    }
+}
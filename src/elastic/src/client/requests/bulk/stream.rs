@@ -3,17 +3,13 @@ use std::{
     fmt,
     io,
     marker::PhantomData,
-    mem,
     time::{
         Duration,
         Instant,
     },
 };
 
-use bytes::{
-    BufMut,
-    BytesMut,
-};
+use bytes::BytesMut;
 use channel::{
     self,
     TryRecvError,
@@ -28,16 +24,18 @@ use futures::{
     Sink,
     Stream,
 };
+use futures_util::compat::Compat;
 use serde::{
     de::DeserializeOwned,
     ser::Serialize,
 };
-use tokio::timer::Delay;
+use runtime::Delay;
 
 use super::{
     BulkOperation,
     BulkRequestBuilder,
     BulkRequestInner,
+    BytesMutWriter,
     Pending,
     WrappedBody,
 };
@@ -50,7 +48,10 @@ use client::{
         RequestBuilder,
     },
     responses::parse::IsOk,
-    sender::AsyncSender,
+    sender::{
+        shutdown::BackgroundTask,
+        AsyncSender,
+    },
     Client,
     RequestParams,
 };
@@ -123,11 +124,26 @@ impl<TResponse> SenderRequestTemplate<TResponse> {
         }
     }
 
-    fn to_request(&self, body: Vec<u8>) -> BulkRequestBuilder<AsyncSender, Vec<u8>, TResponse> {
+    /**
+    Register a background task with the client.
+
+    Requests sent from this template will still be sent after the client is closed.
+    */
+    pub(super) fn background_task(&mut self) -> BackgroundTask {
+        let (shutdown, task) = self.client.sender.shutdown.background_task();
+        self.client.sender.shutdown = shutdown;
+
+        task
+    }
+
+    pub(super) fn to_request(
+        &self,
+        body: BytesMut,
+    ) -> BulkRequestBuilder<AsyncSender, BytesMut, TResponse> {
         RequestBuilder::new(
             self.client.clone(),
             FluentBuilder::new().value(self.params.clone()),
-            BulkRequestInner::<Vec<u8>, TResponse> {
+            BulkRequestInner::<BytesMut, TResponse> {
                 index: self.index.clone(),
                 ty: self.ty.clone(),
                 body: WrappedBody::new(body),
@@ -149,7 +165,7 @@ impl Timeout {
         Timeout { duration, delay }
     }
 
-    fn restart(&mut self) {
+    pub(super) fn restart(&mut self) {
         self.delay.reset(Instant::now() + self.duration);
     }
 }
@@ -170,7 +186,7 @@ The `BulkSender` and `BulkBody` combination means operations can be pushed while
 */
 enum BulkSenderInFlight<TResponse> {
     ReadyToSend,
-    Pending(Pending<TResponse>),
+    Pending(Compat<Pending<TResponse>>),
     Transmitting(Option<TResponse>),
     Transmitted,
 }
@@ -187,72 +203,86 @@ pub struct BulkReceiver<TResponse> {
     rx: BulkReceiverInner<TResponse>,
 }
 
+/**
+A buffer for bulk operations that are streamed into a request body.
+
+Operations are serialised directly into the body.
+An operation that overflows a non-empty body is moved into an overflow buffer so it can start the next body.
+
+Bodies are taken from a single `BytesMut` buffer and sent without copying them.
+Once a request has completed and its body is dropped, the buffer reclaims the allocation for the next body.
+*/
 pub(super) struct SenderBody {
-    scratch: Vec<u8>,
+    overflow: BytesMut,
     body: BytesMut,
     size: usize,
+    ops: usize,
+    max_ops: Option<usize>,
 }
 
 impl SenderBody {
-    pub(super) fn new(size: usize) -> Self {
+    pub(super) fn new(size: usize, max_ops: Option<usize>) -> Self {
         SenderBody {
-            scratch: Vec::new(),
+            overflow: BytesMut::new(),
             size,
             body: BytesMut::with_capacity(size),
+            ops: 0,
+            max_ops,
         }
     }
 
-    fn take(&mut self) -> BytesMut {
-        // Make sure any oversize remaining scratch can be copied to the new buffer
-        let size = usize::max(self.scratch.len(), self.size);
-        let mut new_body = BytesMut::with_capacity(size);
+    pub(super) fn take(&mut self) -> BytesMut {
+        let body = self.body.take();
 
-        // Copy out any scratch into the new buffer
-        // This would probably be a single operation that didn't fit
-        if self.scratch.len() > 0 {
-            new_body.put_slice(&self.scratch);
-            self.scratch.clear();
-        }
+        // Start the new body with any operation that didn't fit in the last one
+        self.ops = if self.overflow.is_empty() { 0 } else { 1 };
+        self.body.unsplit(self.overflow.take());
 
-        mem::replace(&mut self.body, new_body)
+        body
     }
 
-    fn has_capacity(&self) -> bool {
-        self.scratch.len() == 0 && self.body.remaining_mut() > 0
+    pub(super) fn ops(&self) -> usize {
+        self.ops
     }
 
-    fn is_empty(&self) -> bool {
-        self.body.len() == 0
+    fn has_op_capacity(&self) -> bool {
+        self.max_ops.map(|max_ops| self.ops < max_ops).unwrap_or(true)
     }
 
-    fn is_full(&self) -> bool {
-        self.scratch.len() > 0 || self.body.remaining_mut() == 0
+    pub(super) fn has_capacity(&self) -> bool {
+        self.overflow.is_empty() && self.body.len() < self.size && self.has_op_capacity()
     }
 
-    fn push<TDocument>(&mut self, op: BulkOperation<TDocument>) -> Result<(), io::Error>
+    pub(super) fn is_empty(&self) -> bool {
+        self.body.is_empty()
+    }
+
+    pub(super) fn is_full(&self) -> bool {
+        !self.overflow.is_empty() || self.body.len() >= self.size || !self.has_op_capacity()
+    }
+
+    pub(super) fn push<TDocument>(&mut self, op: BulkOperation<TDocument>) -> Result<(), io::Error>
     where
         TDocument: Serialize,
     {
-        op.write(&mut self.scratch)?;
-
-        // Copy the scratch buffer into the request buffer if it fits
-        if self.scratch.len() <= self.body.remaining_mut() {
-            self.body.put_slice(&self.scratch);
-            self.scratch.clear();
+        let op_start = self.body.len();
 
-            Ok(())
+        // Don't leave a partially written operation in the body
+        if let Err(e) = op.write(BytesMutWriter(&mut self.body)) {
+            self.body.truncate(op_start);
+            return Err(e);
         }
-        // If the body is empty and the buffer doesn't fit, replace the current body buffer
-        else if self.body.len() == 0 {
-            let scratch = mem::replace(&mut self.scratch, Vec::new());
-            self.body = BytesMut::from(scratch);
 
-            Ok(())
+        // If the operation fits, or it's the only operation in the body, then keep it
+        if self.body.len() <= self.size || op_start == 0 {
+            self.ops += 1;
         }
-        // If the buffer doesn't fit, then retain it for the next request
+        // If the operation doesn't fit, then retain it for the next request
         else {
-            Ok(())
+            self.overflow = self.body.split_off(op_start);
         }
+
+        Ok(())
     }
 }
 
@@ -316,8 +346,8 @@ where
 
                 let body = self.body.take();
 
-                let req = self.req_template.to_request(body.to_vec());
-                let pending = req.send();
+                let req = self.req_template.to_request(body);
+                let pending = req.send().compat();
 
                 BulkSenderInFlight::Pending(pending)
             }
@@ -437,3 +467,76 @@ impl StdError for Disconnected {
         "disconnected"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SenderBody;
+    use prelude::*;
+
+    fn op_len() -> usize {
+        let mut body = Vec::new();
+        bulk_raw().index(json!({ "id": 1 })).id(1).write(&mut body).unwrap();
+
+        body.len()
+    }
+
+    #[test]
+    fn push_fills_body() {
+        let mut body = SenderBody::new(op_len() * 2, None);
+
+        body.push(bulk_raw().index(json!({ "id": 1 })).id(1)).unwrap();
+        assert!(body.has_capacity());
+
+        body.push(bulk_raw().index(json!({ "id": 1 })).id(1)).unwrap();
+        assert!(body.is_full());
+
+        assert_eq!(2, body.ops());
+        assert_eq!(op_len() * 2, body.take().len());
+        assert!(body.is_empty());
+    }
+
+    #[test]
+    fn push_overflow_starts_next_body() {
+        let mut body = SenderBody::new(op_len() + 1, None);
+
+        body.push(bulk_raw().index(json!({ "id": 1 })).id(1)).unwrap();
+        body.push(bulk_raw().index(json!({ "id": 1 })).id(1)).unwrap();
+
+        assert!(body.is_full());
+        assert_eq!(1, body.ops());
+        assert_eq!(op_len(), body.take().len());
+
+        assert_eq!(1, body.ops());
+        assert_eq!(op_len(), body.take().len());
+
+        assert_eq!(0, body.ops());
+        assert!(body.is_empty());
+    }
+
+    #[test]
+    fn take_reuses_sent_bodies() {
+        let mut body = SenderBody::new(op_len() * 2, None);
+
+        body.push(bulk_raw().index(json!({ "id": 1 })).id(1)).unwrap();
+        body.push(bulk_raw().index(json!({ "id": 1 })).id(1)).unwrap();
+        let sent = body.take().freeze();
+        let allocation = sent.as_ptr();
+
+        // Once the sent body is dropped its allocation is reused for the next one
+        drop(sent);
+        body.push(bulk_raw().index(json!({ "id": 1 })).id(1)).unwrap();
+
+        assert_eq!(allocation, body.take().as_ptr());
+    }
+
+    #[test]
+    fn push_oversized_op() {
+        let mut body = SenderBody::new(1, None);
+
+        body.push(bulk_raw().index(json!({ "id": 1 })).id(1)).unwrap();
+
+        assert!(body.is_full());
+        assert_eq!(1, body.ops());
+        assert_eq!(op_len(), body.take().len());
+    }
+}
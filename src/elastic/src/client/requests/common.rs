@@ -3,13 +3,23 @@ Types that are common between requests.
 */
 
 use serde::ser::{
+    Error as SerError,
     Serialize,
     Serializer,
 };
 use serde_json::{
+    self,
     Map,
     Value,
 };
+use std::{
+    any,
+    collections::BTreeSet,
+    sync::Mutex,
+};
+
+use client::sender::RequestParams;
+use types::document::DocumentType;
 
 /** Update an indexed document using a new document. */
 #[derive(Serialize)]
@@ -20,19 +30,31 @@ pub struct Doc<TDocument> {
 impl<TDocument> Doc<TDocument> {
     pub(crate) fn empty() -> Self {
         Doc {
-            doc: DocInner { inner: None },
+            doc: DocInner {
+                inner: None,
+                none_fields: NoneFields::Null,
+            },
         }
     }
 
     pub(crate) fn value(doc: TDocument) -> Self {
         Doc {
-            doc: DocInner { inner: Some(doc) },
+            doc: DocInner {
+                inner: Some(doc),
+                none_fields: NoneFields::Null,
+            },
         }
     }
+
+    pub(crate) fn none_fields(mut self, none_fields: NoneFields) -> Self {
+        self.doc.none_fields = none_fields;
+        self
+    }
 }
 
 struct DocInner<TDocument> {
     inner: Option<TDocument>,
+    none_fields: NoneFields,
 }
 
 impl<TDocument> Serialize for DocInner<TDocument>
@@ -44,19 +66,80 @@ where
         S: Serializer,
     {
         match self.inner {
-            Some(ref doc) => doc.serialize(serializer),
+            Some(ref doc) => SerializeDocument::new(doc, self.none_fields).serialize(serializer),
             None => Value::Object(Map::new()).serialize(serializer),
         }
     }
 }
 
+/**
+How `None` fields on a document are serialized.
+
+By default, `None` fields are serialized as `null`.
+When a document is indexed, a `null` field is treated as missing, so it's replaced by any `null_value` in the mapping and won't match `exists` queries.
+When a document is partially updated, a `null` field replaces the existing value, whereas an omitted field keeps its existing value.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoneFields {
+    /** Serialize `None` fields as `null`. */
+    Null,
+    /** Omit `None` fields from the document. */
+    Omit,
+}
+
+/** Serialize a document, applying the given `NoneFields` option. */
+pub(crate) struct SerializeDocument<'a, TDocument: 'a> {
+    doc: &'a TDocument,
+    none_fields: NoneFields,
+}
+
+impl<'a, TDocument> SerializeDocument<'a, TDocument> {
+    pub(crate) fn new(doc: &'a TDocument, none_fields: NoneFields) -> Self {
+        SerializeDocument { doc, none_fields }
+    }
+}
+
+impl<'a, TDocument> Serialize for SerializeDocument<'a, TDocument>
+where
+    TDocument: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.none_fields {
+            NoneFields::Null => self.doc.serialize(serializer),
+            NoneFields::Omit => {
+                let doc = serde_json::to_value(self.doc).map_err(S::Error::custom)?;
+
+                omit_null_fields(doc).serialize(serializer)
+            }
+        }
+    }
+}
+
+/** Remove fields with `null` values from any objects in the given value. */
+fn omit_null_fields(value: Value) -> Value {
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, omit_null_fields(value)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(omit_null_fields).collect()),
+        value => value,
+    }
+}
+
 /** A default set of script parameters. */
 pub type DefaultParams = Map<String, Value>;
 
 /** Update an indexed document using a script. */
 #[derive(Serialize)]
 pub struct Script<TParams> {
-    script: ScriptInner<TParams>,
+    script: ScriptBuilder<TParams>,
 }
 
 impl Script<DefaultParams> {
@@ -69,21 +152,83 @@ impl Script<DefaultParams> {
     }
 }
 
+/**
+Update an indexed document using a script, or insert a new document if it doesn't exist.
+
+If `scripted_upsert` is `true` then the script is also run when the document doesn't exist, starting from the `upsert` document.
+*/
+#[derive(Serialize)]
+pub struct ScriptUpsert<TParams, TDocument> {
+    #[serde(flatten)]
+    script: Script<TParams>,
+    #[serde(skip_serializing_if = "is_false")]
+    scripted_upsert: bool,
+    upsert: DocInner<TDocument>,
+}
+
+impl<TParams, TDocument> ScriptUpsert<TParams, TDocument> {
+    pub(crate) fn empty(script: Script<TParams>) -> Self {
+        ScriptUpsert {
+            script,
+            scripted_upsert: false,
+            upsert: DocInner {
+                inner: None,
+                none_fields: NoneFields::Null,
+            },
+        }
+    }
+
+    pub(crate) fn value(script: Script<TParams>, doc: TDocument) -> Self {
+        ScriptUpsert {
+            script,
+            scripted_upsert: false,
+            upsert: DocInner {
+                inner: Some(doc),
+                none_fields: NoneFields::Null,
+            },
+        }
+    }
+
+    pub(crate) fn scripted_upsert(mut self, scripted_upsert: bool) -> Self {
+        self.scripted_upsert = scripted_upsert;
+        self
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/**
+A builder for a script that can be configured before sending.
+
+Scripts are used by update requests, bulk update operations and script sorts.
+A `ScriptBuilder` also serializes as a script object, so it can be used in request bodies built with `json!`.
+*/
 #[derive(Serialize)]
-struct ScriptInner<TParams> {
-    #[serde(rename = "inline")]
-    source: String,
+pub struct ScriptBuilder<TParams> {
+    #[serde(flatten)]
+    source: ScriptSource,
     #[serde(skip_serializing_if = "Option::is_none")]
     lang: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     params: Option<TParams>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_params_error"
+    )]
+    params_error: Option<String>,
 }
 
-/** A builder for an update script that can be configured before sending. */
-pub struct ScriptBuilder<TParams> {
-    source: String,
-    lang: Option<String>,
-    params: Option<TParams>,
+/** The source of a script. */
+#[derive(Serialize)]
+enum ScriptSource {
+    /** A script with its source inline. */
+    #[serde(rename = "inline")]
+    Inline(String),
+    /** A script that's been stored in the cluster state with an id. */
+    #[serde(rename = "id")]
+    Stored(String),
 }
 
 impl ScriptBuilder<DefaultParams> {
@@ -92,21 +237,51 @@ impl ScriptBuilder<DefaultParams> {
     where
         TScript: ToString,
     {
+        ScriptBuilder::with_source(ScriptSource::Inline(source.to_string()))
+    }
+
+    /**
+    Create a new script builder for a stored script with the given id.
+
+    Stored scripts already have a language, so `lang` shouldn't be set for them.
+    */
+    pub fn stored<TId>(id: TId) -> Self
+    where
+        TId: ToString,
+    {
+        ScriptBuilder::with_source(ScriptSource::Stored(id.to_string()))
+    }
+
+    fn with_source(source: ScriptSource) -> Self {
         ScriptBuilder {
-            source: source.to_string(),
+            source,
             params: None,
             lang: None,
+            params_error: None,
         }
     }
 
-    /** Set a script parameter. */
+    /**
+    Set a script parameter.
+
+    The value can be any type that implements `Serialize`.
+    If the value can't be serialized then serializing the script will fail.
+    */
     pub fn param<TKey, TValue>(mut self, key: TKey, value: TValue) -> Self
     where
         TKey: ToString,
-        TValue: Into<Value>,
+        TValue: Serialize,
     {
+        let value = match serde_json::to_value(value) {
+            Ok(value) => value,
+            Err(err) => {
+                self.params_error = Some(err.to_string());
+                return self;
+            }
+        };
+
         let mut params = self.params.unwrap_or_else(DefaultParams::new);
-        params.insert(key.to_string(), value.into());
+        params.insert(key.to_string(), value);
 
         self.params = Some(params);
         self
@@ -115,16 +290,10 @@ impl ScriptBuilder<DefaultParams> {
 
 impl<TParams> ScriptBuilder<TParams> {
     pub(crate) fn from_script(script: Script<TParams>) -> Self {
-        let script = script.script;
-
-        ScriptBuilder {
-            source: script.source,
-            lang: script.lang,
-            params: script.params,
-        }
+        script.script
     }
 
-    /** Set the language for the update script. */
+    /** Set the language for the script. */
     pub fn lang<TLang>(mut self, lang: Option<TLang>) -> Self
     where
         TLang: ToString,
@@ -133,26 +302,31 @@ impl<TParams> ScriptBuilder<TParams> {
         self
     }
 
-    /** Specify a new set of parameters for the update script. */
+    /** Specify a new set of parameters for the script. */
     pub fn params<TNewParams>(self, params: TNewParams) -> ScriptBuilder<TNewParams> {
         ScriptBuilder {
             source: self.source,
             lang: self.lang,
             params: Some(params),
+            params_error: None,
         }
     }
 
     pub(crate) fn build(self) -> Script<TParams> {
-        Script {
-            script: ScriptInner {
-                source: self.source,
-                params: self.params,
-                lang: self.lang,
-            },
-        }
+        Script { script: self }
     }
 }
 
+fn serialize_params_error<S>(err: &Option<String>, _: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    Err(S::Error::custom(format!(
+        "failed to serialize a script parameter: {}",
+        err.as_ref().map(String::as_str).unwrap_or_default()
+    )))
+}
+
 impl From<String> for ScriptBuilder<DefaultParams> {
     fn from(source: String) -> Self {
         ScriptBuilder::new(source)
@@ -164,3 +338,176 @@ impl<'a> From<&'a str> for ScriptBuilder<DefaultParams> {
         ScriptBuilder::new(source)
     }
 }
+
+/** Document types that have already been warned about having a disabled `_source`. */
+static SOURCE_DISABLED_WARNED: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+/** Whether a warning should be logged for a document type with a disabled `_source`, which happens once per type. */
+fn warn_source_disabled(ty: &'static str) -> bool {
+    SOURCE_DISABLED_WARNED
+        .lock()
+        .map(|mut warned| warned.insert(ty))
+        .unwrap_or(false)
+}
+
+/** Mapped datatypes that can't be read from doc values. */
+const NO_DOC_VALUES_DATATYPES: &[&str] = &["text", "nested", "geo_shape", "completion"];
+
+/**
+Request stored fields and doc values for documents with a disabled `_source`.
+
+Documents without a `_source` can't be returned directly, so the response parser builds them from fields instead.
+If `docvalue_fields` is `true` then any fields in the mapping that have doc values are also requested.
+*/
+pub(crate) fn source_disabled_params<TDocument>(
+    docvalue_fields: bool,
+) -> impl Fn(RequestParams) -> RequestParams + Send + 'static
+where
+    TDocument: DocumentType,
+{
+    if warn_source_disabled(any::type_name::<TDocument>()) {
+        warn!(
+            "Elasticsearch Request: the `_source` is disabled for documents in `{}`, so they will be read from stored fields and doc values instead",
+            TDocument::partial_static_index().unwrap_or("<dynamic index>")
+        );
+    }
+
+    let fields = if docvalue_fields {
+        let mapping = serde_json::to_value(TDocument::index_mapping()).unwrap_or(Value::Null);
+
+        let mut fields = Vec::new();
+        collect_docvalue_fields(None, &mapping, &mut fields);
+
+        fields
+    } else {
+        Vec::new()
+    };
+
+    move |params| {
+        let params = params.url_param("stored_fields", "*");
+
+        if fields.is_empty() {
+            params
+        } else {
+            params.url_param("docvalue_fields", fields.join(","))
+        }
+    }
+}
+
+/** Collect the names of mapped properties that can be read from doc values. */
+fn collect_docvalue_fields(path: Option<&str>, mapping: &Value, fields: &mut Vec<String>) {
+    let properties = match mapping.get("properties").and_then(Value::as_object) {
+        Some(properties) => properties,
+        None => return,
+    };
+
+    for (name, property) in properties {
+        let name = match path {
+            Some(path) => format!("{}.{}", path, name),
+            None => name.to_owned(),
+        };
+
+        let ty = property.get("type").and_then(Value::as_str);
+        let has_doc_values = property.get("doc_values").and_then(Value::as_bool) != Some(false);
+
+        match ty {
+            Some(ty) if NO_DOC_VALUES_DATATYPES.contains(&ty) => (),
+            Some("object") | None => collect_docvalue_fields(Some(&name), property, fields),
+            Some(_) if has_doc_values => fields.push(name),
+            Some(_) => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prelude::*;
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(source(enabled = false))]
+    struct NoSource {
+        id: i32,
+        title: String,
+        tag: Keyword<DefaultKeywordMapping>,
+    }
+
+    #[test]
+    fn source_disabled_params_requests_fields() {
+        let params = source_disabled_params::<NoSource>(true)(RequestParams::default());
+
+        let (_, query) = params.get_url_qry();
+        let query = query.unwrap();
+
+        assert!(query.contains("stored_fields=*"));
+        assert!(query.contains("docvalue_fields=id%2Ctag"));
+    }
+
+    #[test]
+    fn source_disabled_params_without_docvalue_fields() {
+        let params = source_disabled_params::<NoSource>(false)(RequestParams::default());
+
+        let (_, query) = params.get_url_qry();
+
+        assert_eq!(Some("?stored_fields=*".to_owned()), query);
+    }
+
+    #[test]
+    fn source_disabled_warning_is_logged_once_per_type() {
+        struct WarnOnce;
+
+        assert!(warn_source_disabled(any::type_name::<WarnOnce>()));
+        assert!(!warn_source_disabled(any::type_name::<WarnOnce>()));
+    }
+
+    #[test]
+    fn serialize_stored_script() {
+        let script = ScriptBuilder::stored("calculate-score").param("factor", 2);
+
+        let expected = json!({
+            "id": "calculate-score",
+            "params": {
+                "factor": 2
+            }
+        });
+
+        assert_eq!(expected, serde_json::to_value(script).unwrap());
+    }
+
+    #[test]
+    fn serialize_script_params() {
+        #[derive(Serialize)]
+        struct Range {
+            from: i32,
+            to: i32,
+        }
+
+        let script = ScriptBuilder::new("doc['a'].value > params.range.from")
+            .lang(Some("painless"))
+            .param("range", Range { from: 1, to: 5 })
+            .param("tags", vec!["a", "b"]);
+
+        let expected = json!({
+            "inline": "doc['a'].value > params.range.from",
+            "lang": "painless",
+            "params": {
+                "range": { "from": 1, "to": 5 },
+                "tags": ["a", "b"]
+            }
+        });
+
+        assert_eq!(expected, serde_json::to_value(script).unwrap());
+    }
+
+    #[test]
+    fn serialize_script_invalid_param() {
+        use std::collections::HashMap;
+
+        let mut invalid = HashMap::new();
+        invalid.insert(vec![1], "a map with non-string keys");
+
+        let script = ScriptBuilder::new("params.invalid").param("invalid", invalid);
+
+        assert!(serde_json::to_value(script.build()).is_err());
+    }
+}
@@ -0,0 +1,841 @@
+/*!
+Skip writing documents that haven't changed since they were last indexed.
+
+A content hash is computed from a document before it's written.
+If a document with the same hash has already been indexed then the write is skipped.
+This can cut the indexing load of jobs that periodically sync every document from another data source into Elasticsearch, where most documents don't change between runs.
+
+The hash is computed from the serialized document with its keys sorted, so it doesn't depend on the iteration order of any maps in the document.
+It isn't a cryptographic hash.
+
+There are two ways to keep track of content hashes, described by [`ContentHash`][ContentHash]:
+
+- store the hash in a field on the document, which is checked before writing a document with the same id
+- use the hash as the id of the document, so a document with the same content always has the same id
+
+Call [`content_hash`][IndexRequestBuilder.content_hash] on an index request to skip a single write, or [`bulk_index_changed`][DocumentClient.bulk_index_changed] to index a collection of documents in bulk.
+The bulk variant fetches the hashes of existing documents with a single [multi get request][docs-mget] before sending the changed documents.
+
+[ContentHash]: struct.ContentHash.html
+[IndexRequestBuilder.content_hash]: ../document_index/type.IndexRequestBuilder.html#method.content_hash
+[DocumentClient.bulk_index_changed]: ../../struct.DocumentClient.html#method.bulk_index_changed
+[docs-mget]: https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-multi-get.html
+*/
+
+use std::{
+    borrow::Cow,
+    fmt::Write,
+};
+
+use futures::{
+    future::Either,
+    Future,
+    IntoFuture,
+};
+use serde::Serialize;
+use serde_json::{
+    self,
+    Map,
+    Value,
+};
+
+use client::{
+    requests::{
+        bulk::{
+            bulk_raw,
+            BulkRequestBuilder,
+        },
+        endpoints::{
+            GetRequest,
+            IndexRequest,
+            MgetRequest,
+        },
+        params::{
+            Id,
+            Index,
+            Type,
+        },
+        RequestBuilder,
+    },
+    responses::{
+        BulkResponse,
+        IndexResponse,
+    },
+    sender::{
+        AsyncSender,
+        RequestParams,
+        Sender,
+        SyncSender,
+    },
+    Client,
+    DocumentClient,
+};
+use error::{
+    self,
+    Error,
+    Result,
+};
+use http::StatusCode;
+use std_future::{
+    box_future,
+    BoxFuture,
+};
+use types::document::DocumentType;
+
+const DEFAULT_HASH_FIELD: &str = "content_hash";
+
+const FNV_OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+const FNV_PRIME: u128 = 0x0000000001000000000000000000013b;
+
+/**
+How to keep track of the content hash of a document.
+
+The default stores the hash in a field called `content_hash`.
+
+# Examples
+
+Store the hash in a field called `sync_hash`:
+
+```
+# use elastic::prelude::*;
+let content_hash = ContentHash::stored_in("sync_hash");
+```
+
+Use the hash as the document id:
+
+```
+# use elastic::prelude::*;
+let content_hash = ContentHash::as_id();
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentHash {
+    mode: HashMode,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum HashMode {
+    Field(String),
+    Id,
+}
+
+impl Default for ContentHash {
+    fn default() -> Self {
+        ContentHash::stored_in(DEFAULT_HASH_FIELD)
+    }
+}
+
+impl ContentHash {
+    /**
+    Store the hash in a field on the document.
+
+    The field is added to the document before it's written.
+    Documents need an id so the hash of an existing document can be compared.
+    The field should be mapped as a `keyword` that isn't indexed, or the index should allow dynamic fields.
+    */
+    pub fn stored_in(field: impl Into<String>) -> Self {
+        ContentHash {
+            mode: HashMode::Field(field.into()),
+        }
+    }
+
+    /**
+    Use the hash as the id of the document.
+
+    Any id on the document is ignored.
+    A document is unchanged if a document with the same id already exists.
+    */
+    pub fn as_id() -> Self {
+        ContentHash { mode: HashMode::Id }
+    }
+
+    /**
+    Compute the hash for a document and prepare it to be written.
+
+    The returned document includes the hash field if the hash is stored in a field.
+    */
+    pub(crate) fn hash<TDocument>(
+        &self,
+        index: Index<'static>,
+        ty: Type<'static>,
+        id: Option<Id<'static>>,
+        doc: &TDocument,
+    ) -> Result<HashedDocument>
+    where
+        TDocument: Serialize,
+    {
+        let mut doc = serde_json::to_value(doc).map_err(error::request)?;
+        let hash = content_hash(&serde_json::to_vec(&doc).map_err(error::request)?);
+
+        let id = match self.mode {
+            HashMode::Field(ref field) => {
+                let id = id.ok_or_else(|| {
+                    error::request(error::message(
+                        "documents need an id to store their content hash in a field",
+                    ))
+                })?;
+
+                match doc {
+                    Value::Object(ref mut doc) => {
+                        doc.insert(field.clone(), Value::String(hash.clone()));
+                    }
+                    _ => {
+                        return Err(error::request(error::message(
+                            "documents need to serialize to a JSON object to store their content hash in a field",
+                        )))
+                    }
+                }
+
+                id
+            }
+            HashMode::Id => Id::from(hash.clone()),
+        };
+
+        Ok(HashedDocument {
+            index,
+            ty,
+            id,
+            hash,
+            mode: self.mode.clone(),
+            doc,
+        })
+    }
+}
+
+/** Compute a 128bit FNV-1a hash of some bytes as a hex string. */
+fn content_hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(FNV_OFFSET_BASIS, |hash, &b| {
+        (hash ^ u128::from(b)).wrapping_mul(FNV_PRIME)
+    });
+
+    let mut hex = String::with_capacity(32);
+    write!(hex, "{:032x}", hash).expect("writing to a `String` is infallible");
+
+    hex
+}
+
+/** A document along with its content hash. */
+pub(crate) struct HashedDocument {
+    index: Index<'static>,
+    ty: Type<'static>,
+    id: Id<'static>,
+    hash: String,
+    mode: HashMode,
+    doc: Value,
+}
+
+impl HashedDocument {
+    /** An index request for the document. */
+    pub(crate) fn into_index_request(self) -> Result<IndexRequest<'static, Vec<u8>>> {
+        let body = serde_json::to_vec(&self.doc).map_err(error::request)?;
+
+        Ok(IndexRequest::for_index_ty_id(self.index, self.ty, self.id, body))
+    }
+
+    /** The `_source` to fetch for an existing document. */
+    fn source_filter(&self) -> Value {
+        match self.mode {
+            HashMode::Field(ref field) => Value::Array(vec![Value::String(field.clone())]),
+            HashMode::Id => Value::Bool(false),
+        }
+    }
+
+    /** A request to get the hash of an existing document. */
+    pub(crate) fn get_existing(&self) -> GetRequest<'static> {
+        GetRequest::for_index_ty_id(self.index.clone(), self.ty.clone(), self.id.clone())
+    }
+
+    /** The `_source` url parameter for getting the hash of an existing document. */
+    pub(crate) fn source_param(&self) -> String {
+        match self.mode {
+            HashMode::Field(ref field) => field.clone(),
+            HashMode::Id => "false".to_owned(),
+        }
+    }
+
+    /**
+    Whether or not an existing document has the same content.
+
+    The existing document is the response to a get request, or a single document in the response to a multi get request.
+    */
+    pub(crate) fn is_unchanged(&self, existing: &Value) -> bool {
+        if existing.get("found").and_then(Value::as_bool) != Some(true) {
+            return false;
+        }
+
+        match self.mode {
+            HashMode::Field(ref field) => {
+                existing
+                    .get("_source")
+                    .and_then(|source| source.get(field))
+                    .and_then(Value::as_str)
+                    == Some(&*self.hash)
+            }
+            HashMode::Id => true,
+        }
+    }
+
+    /** A response for a write that was skipped because the document is unchanged. */
+    pub(crate) fn unchanged_response(&self) -> Result<IndexResponse> {
+        let res = object(vec![
+            ("_index", Value::String(self.index.to_string())),
+            ("_type", Value::String(self.ty.to_string())),
+            ("_id", Value::String(self.id.to_string())),
+            ("_version", Value::Null),
+            ("result", Value::String("noop".to_owned())),
+            (
+                "_shards",
+                object(vec![
+                    ("total", 0.into()),
+                    ("successful", 0.into()),
+                    ("failed", 0.into()),
+                ]),
+            ),
+        ]);
+
+        serde_json::from_value(res).map_err(error::request)
+    }
+}
+
+fn object(fields: Vec<(&str, Value)>) -> Value {
+    Value::Object(
+        fields
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value))
+            .collect::<Map<_, _>>(),
+    )
+}
+
+/**
+A builder for indexing the documents in a collection that have changed.
+
+Call [`Client.document.bulk_index_changed`][Client.document.bulk_index_changed] to get a `BulkIndexChangedRequestBuilder`.
+
+[Client.document.bulk_index_changed]: ../../struct.DocumentClient.html#method.bulk_index_changed
+*/
+pub type BulkIndexChangedRequestBuilder<TSender, TDocument> =
+    RequestBuilder<TSender, BulkIndexChangedRequestInner<TDocument>>;
+
+#[doc(hidden)]
+pub struct BulkIndexChangedRequestInner<TDocument> {
+    docs: Vec<TDocument>,
+    content_hash: ContentHash,
+}
+
+/** Response for indexing the documents in a collection that have changed. */
+#[derive(Debug)]
+pub struct BulkIndexChangedResponse {
+    bulk: Option<BulkResponse>,
+    unchanged: Vec<String>,
+}
+
+impl BulkIndexChangedResponse {
+    /**
+    The response to the bulk request that indexed changed documents.
+
+    This is `None` if every document was unchanged and no bulk request was sent.
+    */
+    pub fn bulk(&self) -> Option<&BulkResponse> {
+        self.bulk.as_ref()
+    }
+
+    /** The ids of documents that were unchanged and weren't indexed. */
+    pub fn unchanged(&self) -> &[String] {
+        &self.unchanged
+    }
+}
+
+/**
+# Bulk index changed documents
+*/
+impl<TSender, TDocument> DocumentClient<TSender, TDocument>
+where
+    TSender: Sender,
+    TDocument: DocumentType + Serialize,
+{
+    /**
+    Create a [`BulkIndexChangedRequestBuilder`][BulkIndexChangedRequestBuilder] that indexes the documents in a collection that have changed.
+
+    The content hash of each document is computed and compared with the hashes of existing documents, which are fetched using a single multi get request.
+    Only documents that are new or have changed are sent in a bulk request.
+
+    # Examples
+
+    Index the documents in a collection that have changed since the last run:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    #[derive(Serialize, Deserialize, ElasticType)]
+    struct MyType {
+        #[elastic(id)]
+        pub id: String,
+        pub title: String,
+    }
+
+    let docs = vec![
+        MyType { id: "1".to_owned(), title: "A title".to_owned() },
+        MyType { id: "2".to_owned(), title: "Another title".to_owned() },
+    ];
+
+    let response = client.document::<MyType>()
+                         .bulk_index_changed(docs)
+                         .content_hash(ContentHash::stored_in("sync_hash"))
+                         .send()?;
+
+    println!("skipped {} unchanged documents", response.unchanged().len());
+    # Ok(())
+    # }
+    ```
+
+    [BulkIndexChangedRequestBuilder]: requests/content_hash/type.BulkIndexChangedRequestBuilder.html
+    */
+    pub fn bulk_index_changed(
+        self,
+        docs: impl IntoIterator<Item = TDocument>,
+    ) -> BulkIndexChangedRequestBuilder<TSender, TDocument> {
+        RequestBuilder::initial(
+            self.inner,
+            BulkIndexChangedRequestInner {
+                docs: docs.into_iter().collect(),
+                content_hash: ContentHash::default(),
+            },
+        )
+    }
+}
+
+/**
+# Builder methods
+
+Configure a `BulkIndexChangedRequestBuilder` before sending it.
+*/
+impl<TSender, TDocument> BulkIndexChangedRequestBuilder<TSender, TDocument>
+where
+    TSender: Sender,
+{
+    /** Set how the content hash of documents is tracked. */
+    pub fn content_hash(mut self, content_hash: ContentHash) -> Self {
+        self.inner.content_hash = content_hash;
+        self
+    }
+}
+
+impl<TDocument> BulkIndexChangedRequestInner<TDocument>
+where
+    TDocument: DocumentType + Serialize,
+{
+    fn into_hashed(self) -> Result<Vec<HashedDocument>> {
+        let content_hash = self.content_hash;
+
+        self.docs
+            .iter()
+            .map(|doc| {
+                let index = Index::from(doc.index().into_owned());
+                let ty = Type::from(doc.ty().into_owned());
+                let id = doc.partial_id().map(Cow::into_owned).map(Id::from);
+
+                content_hash.hash(index, ty, id, doc)
+            })
+            .collect()
+    }
+}
+
+/** A multi get request for the content hashes of existing documents. */
+fn mget_existing(docs: &[HashedDocument]) -> Result<MgetRequest<'static, Vec<u8>>> {
+    let docs = docs
+        .iter()
+        .map(|doc| {
+            object(vec![
+                ("_index", Value::String(doc.index.to_string())),
+                ("_type", Value::String(doc.ty.to_string())),
+                ("_id", Value::String(doc.id.to_string())),
+                ("_source", doc.source_filter()),
+            ])
+        })
+        .collect();
+
+    let body = serde_json::to_vec(&object(vec![("docs", Value::Array(docs))])).map_err(error::request)?;
+
+    Ok(MgetRequest::new(body))
+}
+
+/** Split documents into the ones that have changed and the ids of the ones that haven't. */
+fn partition_changed(
+    docs: Vec<HashedDocument>,
+    existing: &Value,
+) -> (Vec<HashedDocument>, Vec<String>) {
+    let existing = existing
+        .get("docs")
+        .and_then(Value::as_array)
+        .map(|existing| &existing[..])
+        .unwrap_or(&[]);
+
+    let mut changed = Vec::new();
+    let mut unchanged = Vec::new();
+
+    for (i, doc) in docs.into_iter().enumerate() {
+        match existing.get(i) {
+            Some(existing) if doc.is_unchanged(existing) => unchanged.push(doc.id.to_string()),
+            _ => changed.push(doc),
+        }
+    }
+
+    (changed, unchanged)
+}
+
+/** Send a get request for an existing document, treating a missing document as not found. */
+pub(crate) fn sync_get_existing(
+    client: &Client<SyncSender>,
+    params: &RequestParams,
+    doc: &HashedDocument,
+) -> Result<Value> {
+    let params = params.clone().url_param("_source", doc.source_param());
+
+    let res = client.request(doc.get_existing()).params(params).send()?;
+
+    match res.status() {
+        StatusCode::NOT_FOUND => Ok(Value::Null),
+        _ => res.into_response(),
+    }
+}
+
+/** Send a get request for an existing document, treating a missing document as not found. */
+pub(crate) fn async_get_existing(
+    client: &Client<AsyncSender>,
+    params: &RequestParams,
+    doc: &HashedDocument,
+) -> impl Future<Item = Value, Error = Error> + Send {
+    let params = params.clone().url_param("_source", doc.source_param());
+
+    client
+        .request(doc.get_existing())
+        .params(params)
+        .send()
+        .compat()
+        .and_then(|res| match res.status() {
+            StatusCode::NOT_FOUND => Either::A(Ok(Value::Null).into_future()),
+            _ => Either::B(res.into_response().compat()),
+        })
+}
+
+fn bulk_changed<TSender>(
+    client: &Client<TSender>,
+    params: RequestParams,
+    changed: Vec<HashedDocument>,
+) -> Option<BulkRequestBuilder<TSender, Vec<u8>, BulkResponse>>
+where
+    TSender: Sender,
+{
+    if changed.is_empty() {
+        return None;
+    }
+
+    let ops = changed.into_iter().map(|doc| {
+        bulk_raw()
+            .index(doc.doc)
+            .index(doc.index)
+            .ty(doc.ty)
+            .id(doc.id)
+    });
+
+    Some(client.bulk().extend(ops).params(params))
+}
+
+/**
+# Send synchronously
+*/
+impl<TDocument> BulkIndexChangedRequestBuilder<SyncSender, TDocument>
+where
+    TDocument: DocumentType + Serialize,
+{
+    /**
+    Send a `BulkIndexChangedRequestBuilder` synchronously using a [`SyncClient`][SyncClient].
+
+    This will block the current thread until the existing hashes have been fetched and any changed documents have been indexed.
+
+    [SyncClient]: ../../type.SyncClient.html
+    */
+    pub fn send(self) -> Result<BulkIndexChangedResponse> {
+        let docs = self.inner.into_hashed()?;
+
+        if docs.is_empty() {
+            return Ok(BulkIndexChangedResponse {
+                bulk: None,
+                unchanged: Vec::new(),
+            });
+        }
+
+        let params = self.client.addresses.resolve(self.params_builder)?;
+
+        let existing = self
+            .client
+            .request(mget_existing(&docs)?)
+            .params(params.clone())
+            .send()?
+            .into_response::<Value>()?;
+
+        let (changed, unchanged) = partition_changed(docs, &existing);
+
+        let bulk = match bulk_changed(&self.client, params, changed) {
+            Some(bulk) => Some(bulk.send()?),
+            None => None,
+        };
+
+        Ok(BulkIndexChangedResponse { bulk, unchanged })
+    }
+}
+
+/**
+# Send asynchronously
+*/
+impl<TDocument> BulkIndexChangedRequestBuilder<AsyncSender, TDocument>
+where
+    TDocument: DocumentType + Serialize + Send + 'static,
+{
+    /**
+    Send a `BulkIndexChangedRequestBuilder` asynchronously using an [`AsyncClient`][AsyncClient].
+
+    This will return a future that will resolve once the existing hashes have been fetched and any changed documents have been indexed.
+
+    [AsyncClient]: ../../type.AsyncClient.html
+    */
+    pub fn send(self) -> Pending {
+        let (client, params_builder, inner) = (self.client, self.params_builder, self.inner);
+
+        let docs = client.sender.maybe_async(move || inner.into_hashed());
+
+        let res_future = docs.and_then(move |docs| {
+            if docs.is_empty() {
+                return Either::A(
+                    Ok(BulkIndexChangedResponse {
+                        bulk: None,
+                        unchanged: Vec::new(),
+                    })
+                    .into_future(),
+                );
+            }
+
+            let res = mget_existing(&docs).into_future().and_then(move |mget| {
+                client
+                    .addresses
+                    .resolve(params_builder)
+                    .compat()
+                    .and_then(move |params| {
+                        client
+                            .request(mget)
+                            .params(params.clone())
+                            .send()
+                            .compat()
+                            .and_then(|res| res.into_response::<Value>().compat())
+                            .and_then(move |existing| {
+                                let (changed, unchanged) = partition_changed(docs, &existing);
+
+                                match bulk_changed(&client, params, changed) {
+                                    Some(bulk) => {
+                                        Either::A(bulk.send().compat().map(move |bulk| {
+                                            BulkIndexChangedResponse {
+                                                bulk: Some(bulk),
+                                                unchanged,
+                                            }
+                                        }))
+                                    }
+                                    None => Either::B(
+                                        Ok(BulkIndexChangedResponse {
+                                            bulk: None,
+                                            unchanged,
+                                        })
+                                        .into_future(),
+                                    ),
+                                }
+                            })
+                    })
+            });
+
+            Either::B(res)
+        });
+
+        Pending::new(res_future)
+    }
+}
+
+/** A future returned by calling `send`. */
+pub struct Pending {
+    inner: BoxFuture<BulkIndexChangedResponse>,
+}
+
+impl Pending {
+    fn new<F>(fut: F) -> Self
+    where
+        F: Future<Item = BulkIndexChangedResponse, Error = Error> + Send + 'static,
+    {
+        Pending {
+            inner: box_future(fut),
+        }
+    }
+}
+
+pending_future!(Pending => BulkIndexChangedResponse);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+    use tests::*;
+
+    #[test]
+    fn is_send() {
+        assert_send::<super::Pending>();
+    }
+
+    #[derive(Serialize, ElasticType)]
+    struct TestDoc {
+        #[elastic(id)]
+        id: String,
+        title: String,
+    }
+
+    fn doc(id: &str, title: &str) -> TestDoc {
+        TestDoc {
+            id: id.to_owned(),
+            title: title.to_owned(),
+        }
+    }
+
+    fn hash(content_hash: &ContentHash, doc: &TestDoc) -> Result<HashedDocument> {
+        content_hash.hash(
+            "testdoc".into(),
+            "_doc".into(),
+            Some(doc.id.clone().into()),
+            doc,
+        )
+    }
+
+    #[test]
+    fn hash_is_stable() {
+        let a = content_hash(br#"{"id":"1","title":"A title"}"#);
+        let b = content_hash(br#"{"id":"1","title":"A title"}"#);
+        let c = content_hash(br#"{"id":"1","title":"Another title"}"#);
+
+        assert_eq!(32, a.len());
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn hash_stored_in_field() {
+        let hashed = hash(&ContentHash::stored_in("sync_hash"), &doc("1", "A title")).unwrap();
+
+        let hash = hashed.hash.clone();
+        let req = hashed.into_index_request().unwrap();
+        let body: Value = serde_json::from_slice(&req.body).unwrap();
+
+        assert_eq!("/testdoc/_doc/1", req.url.as_ref());
+        assert_eq!(Some(&*hash), body["sync_hash"].as_str());
+        assert_eq!("A title", body["title"]);
+    }
+
+    #[test]
+    fn hash_as_id() {
+        let hashed = hash(&ContentHash::as_id(), &doc("1", "A title")).unwrap();
+
+        let hash = hashed.hash.clone();
+        let req = hashed.into_index_request().unwrap();
+        let body: Value = serde_json::from_slice(&req.body).unwrap();
+
+        assert_eq!(format!("/testdoc/_doc/{}", hash), req.url.as_ref());
+        assert!(body.get("content_hash").is_none());
+    }
+
+    #[test]
+    fn hash_stored_in_field_needs_id() {
+        let content_hash = ContentHash::default();
+
+        let hashed = content_hash.hash("testdoc".into(), "_doc".into(), None, &doc("1", "A title"));
+
+        assert!(hashed.is_err());
+    }
+
+    #[test]
+    fn unchanged_with_same_hash() {
+        let hashed = hash(&ContentHash::default(), &doc("1", "A title")).unwrap();
+
+        let existing = json!({
+            "found": true,
+            "_source": { "content_hash": hashed.hash.clone() }
+        });
+        let changed = json!({
+            "found": true,
+            "_source": { "content_hash": "other" }
+        });
+        let missing = json!({ "found": false });
+
+        assert!(hashed.is_unchanged(&existing));
+        assert!(!hashed.is_unchanged(&changed));
+        assert!(!hashed.is_unchanged(&missing));
+        assert!(!hashed.is_unchanged(&Value::Null));
+    }
+
+    #[test]
+    fn unchanged_response() {
+        let hashed = hash(&ContentHash::default(), &doc("1", "A title")).unwrap();
+
+        let res = hashed.unchanged_response().unwrap();
+
+        assert_eq!("1", res.id());
+        assert!(!res.created());
+        assert!(res.unchanged());
+    }
+
+    #[test]
+    fn mget_existing_hashes() {
+        let docs = vec![hash(&ContentHash::default(), &doc("1", "A title")).unwrap()];
+
+        let req = mget_existing(&docs).unwrap();
+        let body: Value = serde_json::from_slice(&req.body).unwrap();
+
+        assert_eq!("/_mget", req.url.as_ref());
+        assert_eq!(
+            json!({
+                "docs": [{
+                    "_index": "testdoc",
+                    "_type": "_doc",
+                    "_id": "1",
+                    "_source": ["content_hash"]
+                }]
+            }),
+            body
+        );
+    }
+
+    #[test]
+    fn partition_changed_docs() {
+        let content_hash = ContentHash::default();
+        let docs = vec![
+            hash(&content_hash, &doc("1", "A title")).unwrap(),
+            hash(&content_hash, &doc("2", "A title")).unwrap(),
+            hash(&content_hash, &doc("3", "A title")).unwrap(),
+        ];
+
+        let existing = json!({
+            "docs": [
+                { "_id": "1", "found": true, "_source": { "content_hash": docs[0].hash.clone() } },
+                { "_id": "2", "found": true, "_source": { "content_hash": "other" } },
+                { "_id": "3", "found": false }
+            ]
+        });
+
+        let (changed, unchanged) = partition_changed(docs, &existing);
+
+        let changed: Vec<_> = changed.iter().map(|doc| doc.id.to_string()).collect();
+
+        assert_eq!(vec!["2", "3"], changed);
+        assert_eq!(vec!["1"], unchanged);
+    }
+}
@@ -4,10 +4,7 @@ Builders for [delete document requests][docs-delete].
 [docs-delete]: http://www.elastic.co/guide/en/elasticsearch/reference/current/docs-delete.html
 */
 
-use futures::{
-    Future,
-    Poll,
-};
+use futures::Future;
 use std::marker::PhantomData;
 
 use client::{
@@ -33,6 +30,10 @@ use error::{
     Error,
     Result,
 };
+use std_future::{
+    box_future,
+    BoxFuture,
+};
 use types::document::{
     DocumentType,
     StaticIndex,
@@ -285,32 +286,26 @@ impl<TDocument> DeleteRequestBuilder<AsyncSender, TDocument> {
 
     Delete a [`DocumentType`][documents-mod] called `MyType` with an id of `1`:
 
-    ```no_run
-    # extern crate futures;
-    # extern crate tokio;
+    ```edition2018,no_run
     # extern crate serde;
     # extern crate serde_json;
     # #[macro_use] extern crate serde_derive;
     # #[macro_use] extern crate elastic_derive;
     # extern crate elastic;
     # use serde_json::Value;
-    # use futures::Future;
     # use elastic::prelude::*;
     # #[derive(ElasticType)]
     # struct MyType { }
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # let client = AsyncClientBuilder::new().build()?;
-    let future = client.document::<MyType>()
-                       .delete(1)
-                       .ty("mytype")
-                       .send();
-
-    future.and_then(|response| {
-        assert!(response.deleted());
+    let response = client.document::<MyType>()
+                         .delete(1)
+                         .ty("mytype")
+                         .send()
+                         .await?;
 
-        Ok(())
-    });
+    assert!(response.deleted());
     # Ok(())
     # }
     ```
@@ -324,7 +319,8 @@ impl<TDocument> DeleteRequestBuilder<AsyncSender, TDocument> {
         let res_future =
             RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
                 .send()
-                .and_then(|res| res.into_response());
+                .compat()
+                .and_then(|res| res.into_response().compat());
 
         Pending::new(res_future)
     }
@@ -332,7 +328,7 @@ impl<TDocument> DeleteRequestBuilder<AsyncSender, TDocument> {
 
 /** A future returned by calling `send`. */
 pub struct Pending {
-    inner: Box<Future<Item = DeleteResponse, Error = Error> + Send>,
+    inner: BoxFuture<DeleteResponse>,
 }
 
 impl Pending {
@@ -341,19 +337,12 @@ impl Pending {
         F: Future<Item = DeleteResponse, Error = Error> + Send + 'static,
     {
         Pending {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl Future for Pending {
-    type Item = DeleteResponse;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(Pending => DeleteResponse);
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,670 @@
+/*!
+Builders for ensuring the [index][docs-create-index] for a document type exists with its [mapping][docs-mapping].
+
+[docs-create-index]: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-create-index.html
+[docs-mapping]: https://www.elastic.co/guide/en/elasticsearch/reference/current/mapping.html
+*/
+
+use futures::{
+    future::Either,
+    Future,
+    IntoFuture,
+};
+use serde_json::{
+    self,
+    Map,
+    Value,
+};
+use std::marker::PhantomData;
+
+use client::{
+    requests::{
+        endpoints::{
+            IndicesCreateRequest,
+            IndicesExistsRequest,
+            IndicesPutMappingRequest,
+        },
+        index_create::Analysis,
+        params::{
+            Index,
+            Type,
+        },
+        RequestBuilder,
+    },
+    responses::{
+        CommandResponse,
+        IndicesExistsResponse,
+    },
+    sender::{
+        AsyncSender,
+        Sender,
+        SyncSender,
+    },
+    DocumentClient,
+};
+use error::{
+    self,
+    ApiError,
+    Error,
+    Result,
+};
+use std_future::{
+    box_future,
+    BoxFuture,
+};
+use types::document::{
+    DocumentType,
+    StaticIndex,
+    StaticType,
+    DEFAULT_DOC_TYPE,
+};
+
+/**
+An ensure index request builder that can be configured before sending.
+
+Call [`Client.document.ensure_index`][Client.document.ensure_index] to get an `EnsureIndexRequestBuilder`.
+The `send` method will either send the requests [synchronously][send-sync] or [asynchronously][send-async], depending on the `Client` it was created from.
+
+[send-sync]: #send-synchronously
+[send-async]: #send-asynchronously
+[Client.document.ensure_index]: ../../struct.DocumentClient.html#ensure-index-request
+*/
+pub type EnsureIndexRequestBuilder<TSender, TDocument> =
+    RequestBuilder<TSender, EnsureIndexRequestInner<TDocument>>;
+
+#[doc(hidden)]
+pub struct EnsureIndexRequestInner<TDocument> {
+    index: Index<'static>,
+    ty: Type<'static>,
+    settings: Map<String, Value>,
+    put_mapping: bool,
+    _marker: PhantomData<TDocument>,
+}
+
+/**
+# Ensure index request
+*/
+impl<TSender, TDocument> DocumentClient<TSender, TDocument>
+where
+    TSender: Sender,
+{
+    /**
+    Create an [`EnsureIndexRequestBuilder`][EnsureIndexRequestBuilder] with this `Client` that can be configured before sending.
+
+    The request checks whether the index for the document type exists, and creates it with the mapping of the document type if it doesn't.
+    If the index already exists then it's left alone, unless the request is configured to [put the mapping][put_mapping] into it.
+    This is the usual sequence of requests for bootstrapping an application's indices when it starts.
+
+    For more details, see:
+
+    - [builder methods][builder-methods]
+    - [send synchronously][send-sync]
+    - [send asynchronously][send-async]
+
+    # Examples
+
+    Ensure the index for a [`DocumentType`][documents-mod] called `MyType` exists:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # #[macro_use] extern crate serde_json;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.document::<MyType>()
+                         .ensure_index()
+                         .settings(json!({
+                             "index": {
+                                 "number_of_shards": 3
+                             }
+                         }))
+                         .put_mapping(true)
+                         .send()?;
+
+    if response.created() {
+        println!("created the index for MyType");
+    }
+    # Ok(())
+    # }
+    ```
+
+    [EnsureIndexRequestBuilder]: requests/document_ensure_index/type.EnsureIndexRequestBuilder.html
+    [builder-methods]: requests/document_ensure_index/type.EnsureIndexRequestBuilder.html#builder-methods
+    [send-sync]: requests/document_ensure_index/type.EnsureIndexRequestBuilder.html#send-synchronously
+    [send-async]: requests/document_ensure_index/type.EnsureIndexRequestBuilder.html#send-asynchronously
+    [put_mapping]: requests/document_ensure_index/type.EnsureIndexRequestBuilder.html#method.put_mapping
+    [documents-mod]: ../types/document/index.html
+    */
+    pub fn ensure_index(self) -> EnsureIndexRequestBuilder<TSender, TDocument>
+    where
+        TDocument: DocumentType + StaticIndex + StaticType,
+    {
+        let index = TDocument::static_index().into();
+        let ty = TDocument::static_ty().into();
+
+        RequestBuilder::initial(
+            self.inner,
+            EnsureIndexRequestInner {
+                index,
+                ty,
+                settings: Map::new(),
+                put_mapping: false,
+                _marker: PhantomData,
+            },
+        )
+    }
+}
+
+impl<TDocument> EnsureIndexRequestInner<TDocument>
+where
+    TDocument: DocumentType,
+{
+    fn to_exists_request(&self) -> IndicesExistsRequest<'static> {
+        IndicesExistsRequest::for_index(self.index.clone())
+    }
+
+    fn to_create_request(&self) -> Result<IndicesCreateRequest<'static, Vec<u8>>> {
+        let mapping = serde_json::to_value(TDocument::index_mapping()).map_err(error::request)?;
+
+        let mut body = Map::new();
+        if !self.settings.is_empty() {
+            body.insert("settings".to_owned(), Value::Object(self.settings.clone()));
+        }
+
+        let mappings = if &self.ty[..] == DEFAULT_DOC_TYPE {
+            mapping
+        } else {
+            let mut mappings = Map::new();
+            mappings.insert(self.ty[..].to_owned(), mapping);
+
+            Value::Object(mappings)
+        };
+        body.insert("mappings".to_owned(), mappings);
+
+        let body = serde_json::to_vec(&body).map_err(error::request)?;
+
+        Ok(IndicesCreateRequest::for_index(self.index.clone(), body))
+    }
+
+    fn to_put_mapping_request(&self) -> Result<IndicesPutMappingRequest<'static, Vec<u8>>> {
+        let body = serde_json::to_vec(&TDocument::index_mapping()).map_err(error::request)?;
+
+        if &self.ty[..] == DEFAULT_DOC_TYPE {
+            Ok(IndicesPutMappingRequest::for_index(
+                self.index.clone(),
+                body,
+            ))
+        } else {
+            Ok(IndicesPutMappingRequest::for_index_ty(
+                self.index.clone(),
+                self.ty.clone(),
+                body,
+            ))
+        }
+    }
+}
+
+/**
+# Builder methods
+
+Configure an `EnsureIndexRequestBuilder` before sending it.
+*/
+impl<TSender, TDocument> EnsureIndexRequestBuilder<TSender, TDocument>
+where
+    TSender: Sender,
+{
+    /** Set the index to ensure exists. */
+    pub fn index(mut self, index: impl Into<Index<'static>>) -> Self {
+        self.inner.index = index.into();
+        self
+    }
+
+    /** Set the type for the mapping, for indices that still use mapping types. */
+    pub fn ty(mut self, ty: impl Into<Type<'static>>) -> Self {
+        self.inner.ty = ty.into();
+        self
+    }
+
+    /**
+    Set the settings to create the index with.
+
+    The settings are merged with any that are already set, like [analysis settings][analysis].
+    They're only used if the index is created, so the settings of an existing index aren't changed.
+
+    [analysis]: #method.analysis
+    */
+    pub fn settings(mut self, settings: Value) -> Self {
+        if let Value::Object(settings) = settings {
+            self.inner.settings.extend(settings);
+        }
+
+        self
+    }
+
+    /**
+    Set the analysis settings to create the index with.
+
+    Any analysis settings already set are replaced.
+    */
+    pub fn analysis(mut self, analysis: Analysis) -> Self {
+        self.inner
+            .settings
+            .insert("analysis".to_owned(), analysis.to_value());
+        self
+    }
+
+    /**
+    Whether to put the mapping of the document type into the index if it already exists.
+
+    The default is `false`, so an existing index is left alone.
+    Putting the mapping will add any new fields in the document type to the index, but will fail if a field is mapped differently.
+    */
+    pub fn put_mapping(mut self, put_mapping: bool) -> Self {
+        self.inner.put_mapping = put_mapping;
+        self
+    }
+}
+
+/**
+# Send synchronously
+*/
+impl<TDocument> EnsureIndexRequestBuilder<SyncSender, TDocument>
+where
+    TDocument: DocumentType,
+{
+    /**
+    Send an `EnsureIndexRequestBuilder` synchronously using a [`SyncClient`][SyncClient].
+
+    This will block the current thread until the index exists, or an error is returned.
+
+    # Examples
+
+    Ensure the index for a document type called `MyType` exists, with the latest mapping:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.document::<MyType>()
+                         .ensure_index()
+                         .put_mapping(true)
+                         .send()?;
+
+    assert!(response.created() || response.mapping_updated());
+    # Ok(())
+    # }
+    ```
+
+    [SyncClient]: ../../type.SyncClient.html
+    */
+    pub fn send(self) -> Result<EnsureIndexResponse> {
+        let params = self.client.addresses.resolve(self.params_builder)?;
+        let (client, inner) = (self.client, self.inner);
+
+        let exists = client
+            .request(inner.to_exists_request())
+            .params(params.clone())
+            .send()?
+            .into_response::<IndicesExistsResponse>()?
+            .exists();
+
+        let created = if exists {
+            false
+        } else {
+            let created = client
+                .request(inner.to_create_request()?)
+                .params(params.clone())
+                .send()?
+                .into_response::<CommandResponse>();
+
+            index_created(created)?
+        };
+
+        let mapping_updated = !created && inner.put_mapping;
+        if mapping_updated {
+            client
+                .request(inner.to_put_mapping_request()?)
+                .params(params)
+                .send()?
+                .into_response::<CommandResponse>()?;
+        }
+
+        Ok(EnsureIndexResponse {
+            created,
+            mapping_updated,
+        })
+    }
+}
+
+/**
+# Send asynchronously
+*/
+impl<TDocument> EnsureIndexRequestBuilder<AsyncSender, TDocument>
+where
+    TDocument: DocumentType + Send + 'static,
+{
+    /**
+    Send an `EnsureIndexRequestBuilder` asynchronously using an [`AsyncClient`][AsyncClient].
+
+    This will return a future that will resolve once the index exists.
+
+    # Examples
+
+    Ensure the index for a document type called `MyType` exists:
+
+    ```edition2018,no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
+    # #[derive(Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = AsyncClientBuilder::new().build()?;
+    let response = client.document::<MyType>()
+                         .ensure_index()
+                         .send()
+                         .await?;
+
+    if response.created() {
+        println!("created the index for MyType");
+    }
+    # Ok(())
+    # }
+    ```
+
+    [AsyncClient]: ../../type.AsyncClient.html
+    */
+    pub fn send(self) -> Pending {
+        let (client, inner) = (self.client, self.inner);
+
+        let res_future = client
+            .addresses
+            .resolve(self.params_builder)
+            .compat()
+            .and_then(move |params| {
+                let create_client = client.clone();
+                let create_params = params.clone();
+
+                client
+                    .request(inner.to_exists_request())
+                    .params(params.clone())
+                    .send()
+                    .compat()
+                    .and_then(|res| res.into_response::<IndicesExistsResponse>().compat())
+                    .and_then(move |res| {
+                        let created = if res.exists() {
+                            Either::A(Ok(false).into_future())
+                        } else {
+                            Either::B(
+                                inner
+                                    .to_create_request()
+                                    .into_future()
+                                    .and_then(move |create| {
+                                        create_client
+                                            .request(create)
+                                            .params(create_params)
+                                            .send()
+                                            .compat()
+                                    })
+                                    .and_then(|res| res.into_response::<CommandResponse>().compat())
+                                    .then(index_created),
+                            )
+                        };
+
+                        created.map(move |created| (inner, created))
+                    })
+                    .and_then(move |(inner, created)| {
+                        let mapping_updated = !created && inner.put_mapping;
+
+                        let put_mapping = if mapping_updated {
+                            Either::B(
+                                inner
+                                    .to_put_mapping_request()
+                                    .into_future()
+                                    .and_then(move |put| {
+                                        client.request(put).params(params).send().compat()
+                                    })
+                                    .and_then(|res| res.into_response::<CommandResponse>().compat())
+                                    .map(|_| ()),
+                            )
+                        } else {
+                            Either::A(Ok(()).into_future())
+                        };
+
+                        put_mapping.map(move |_| EnsureIndexResponse {
+                            created,
+                            mapping_updated,
+                        })
+                    })
+            });
+
+        Pending::new(res_future)
+    }
+}
+
+/**
+Whether a create index request created the index.
+
+The index may have been created by someone else between checking it exists and creating it, so an `IndexAlreadyExists` error is treated like the index already existed.
+*/
+fn index_created(res: Result<CommandResponse>) -> Result<bool> {
+    match res {
+        Ok(_) => Ok(true),
+        Err(Error::Api(ApiError::IndexAlreadyExists { .. })) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/** Response for ensuring the index for a document type exists. */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnsureIndexResponse {
+    created: bool,
+    mapping_updated: bool,
+}
+
+impl EnsureIndexResponse {
+    /** Whether the index was created with the mapping of the document type. */
+    pub fn created(&self) -> bool {
+        self.created
+    }
+
+    /** Whether the index already existed and the mapping of the document type was put into it. */
+    pub fn mapping_updated(&self) -> bool {
+        self.mapping_updated
+    }
+}
+
+/** A future returned by calling `send`. */
+pub struct Pending {
+    inner: BoxFuture<EnsureIndexResponse>,
+}
+
+impl Pending {
+    fn new<F>(fut: F) -> Self
+    where
+        F: Future<Item = EnsureIndexResponse, Error = Error> + Send + 'static,
+    {
+        Pending {
+            inner: box_future(fut),
+        }
+    }
+}
+
+pending_future!(Pending => EnsureIndexResponse);
+
+#[cfg(test)]
+mod tests {
+    use prelude::*;
+    use serde_json::{
+        self,
+        Value,
+    };
+    use tests::*;
+
+    use super::index_created;
+    use error::{
+        ApiError,
+        Error,
+    };
+
+    #[test]
+    fn is_send() {
+        assert_send::<super::Pending>();
+    }
+
+    #[derive(ElasticType)]
+    struct TestDoc {}
+
+    #[test]
+    fn default_request() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let inner = client.document::<TestDoc>().ensure_index().inner;
+
+        let exists = inner.to_exists_request();
+        let create = inner.to_create_request().unwrap();
+        let put = inner.to_put_mapping_request().unwrap();
+
+        let expected_body = json!({
+            "mappings": {
+                "properties": {
+
+                }
+            }
+        });
+
+        let actual_body: Value = serde_json::from_slice(&create.body).unwrap();
+
+        assert_eq!("/testdoc", exists.url.as_ref());
+        assert_eq!("/testdoc", create.url.as_ref());
+        assert_eq!("/testdoc/_mapping", put.url.as_ref());
+        assert_eq!(expected_body, actual_body);
+    }
+
+    #[test]
+    fn specify_index() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let inner = client
+            .document::<TestDoc>()
+            .ensure_index()
+            .index("new-idx")
+            .inner;
+
+        let exists = inner.to_exists_request();
+        let create = inner.to_create_request().unwrap();
+        let put = inner.to_put_mapping_request().unwrap();
+
+        assert_eq!("/new-idx", exists.url.as_ref());
+        assert_eq!("/new-idx", create.url.as_ref());
+        assert_eq!("/new-idx/_mapping", put.url.as_ref());
+    }
+
+    #[test]
+    fn specify_ty() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let inner = client
+            .document::<TestDoc>()
+            .ensure_index()
+            .ty("new-ty")
+            .inner;
+
+        let create = inner.to_create_request().unwrap();
+        let put = inner.to_put_mapping_request().unwrap();
+
+        let expected_body = json!({
+            "mappings": {
+                "new-ty": {
+                    "properties": {
+
+                    }
+                }
+            }
+        });
+
+        let actual_body: Value = serde_json::from_slice(&create.body).unwrap();
+
+        assert_eq!(expected_body, actual_body);
+        assert_eq!("/testdoc/_mappings/new-ty", put.url.as_ref());
+    }
+
+    #[test]
+    fn specify_settings() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let create = client
+            .document::<TestDoc>()
+            .ensure_index()
+            .settings(json!({
+                "index": {
+                    "number_of_shards": 3
+                }
+            }))
+            .analysis(
+                Analysis::new().normalizer("folded", Normalizer::custom().filter("lowercase")),
+            )
+            .inner
+            .to_create_request()
+            .unwrap();
+
+        let expected_body = json!({
+            "settings": {
+                "index": {
+                    "number_of_shards": 3
+                },
+                "analysis": {
+                    "normalizer": {
+                        "folded": {
+                            "type": "custom",
+                            "filter": ["lowercase"]
+                        }
+                    }
+                }
+            },
+            "mappings": {
+                "properties": {
+
+                }
+            }
+        });
+
+        let actual_body: Value = serde_json::from_slice(&create.body).unwrap();
+
+        assert_eq!(expected_body, actual_body);
+    }
+
+    #[test]
+    fn index_already_exists_is_not_created() {
+        let err = Error::Api(ApiError::IndexAlreadyExists {
+            index: "testdoc".to_owned(),
+        });
+
+        assert!(!index_created(Err(err)).unwrap());
+    }
+
+    #[test]
+    fn other_errors_are_returned() {
+        let err = Error::Api(ApiError::IndexNotFound {
+            index: "testdoc".to_owned(),
+        });
+
+        assert!(index_created(Err(err)).is_err());
+    }
+}
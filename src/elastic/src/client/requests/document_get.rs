@@ -4,15 +4,13 @@ Builders for [get document requests][docs-get].
 [docs-get]: http://www.elastic.co/guide/en/elasticsearch/reference/current/docs-get.html
 */
 
-use futures::{
-    Future,
-    Poll,
-};
+use futures::Future;
 use serde::de::DeserializeOwned;
 use std::marker::PhantomData;
 
 use client::{
     requests::{
+        common::source_disabled_params,
         endpoints::GetRequest,
         params::{
             Id,
@@ -34,6 +32,10 @@ use error::{
     Error,
     Result,
 };
+use std_future::{
+    box_future,
+    BoxFuture,
+};
 use types::document::{
     DocumentType,
     StaticIndex,
@@ -103,6 +105,8 @@ where
     # }
     ```
 
+    If the `_source` is disabled in the mapping for `TDocument` then the document is read from its stored fields instead.
+
     For more details on document types, see the [`types`][types-mod] module.
 
     [GetRequestBuilder]: requests/document_get/type.GetRequestBuilder.html
@@ -119,7 +123,7 @@ where
         let index = TDocument::static_index().into();
         let ty = TDocument::static_ty().into();
 
-        RequestBuilder::initial(
+        let builder = RequestBuilder::initial(
             self.inner,
             GetRequestInner {
                 index: index,
@@ -127,7 +131,13 @@ where
                 id: id.into(),
                 _marker: PhantomData,
             },
-        )
+        );
+
+        if TDocument::source_enabled() {
+            builder
+        } else {
+            builder.params_fluent(source_disabled_params::<TDocument>(false))
+        }
     }
 
     /**
@@ -284,33 +294,27 @@ where
 
     Get a [`DocumentType`][documents-mod] called `MyType` with an id of `1`:
 
-    ```no_run
-    # extern crate futures;
-    # extern crate tokio;
+    ```edition2018,no_run
     # extern crate serde;
     # extern crate serde_json;
     # #[macro_use] extern crate serde_derive;
     # #[macro_use] extern crate elastic_derive;
     # extern crate elastic;
     # use serde_json::Value;
-    # use futures::Future;
     # use elastic::prelude::*;
     # #[derive(Debug, ElasticType, Deserialize)]
     # struct MyType { }
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # let client = AsyncClientBuilder::new().build()?;
-    let future = client.document::<MyType>()
-                       .get(1)
-                       .send();
-
-    future.and_then(|response| {
-        if let Some(doc) = response.into_document() {
-            println!("{:?}", doc);
-        }
+    let response = client.document::<MyType>()
+                         .get(1)
+                         .send()
+                         .await?;
 
-        Ok(())
-    });
+    if let Some(doc) = response.into_document() {
+        println!("{:?}", doc);
+    }
     # Ok(())
     # }
     ```
@@ -324,7 +328,8 @@ where
         let res_future =
             RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
                 .send()
-                .and_then(|res| res.into_response());
+                .compat()
+                .and_then(|res| res.into_response().compat());
 
         Pending::new(res_future)
     }
@@ -332,7 +337,7 @@ where
 
 /** A future returned by calling `send`. */
 pub struct Pending<TDocument> {
-    inner: Box<Future<Item = GetResponse<TDocument>, Error = Error> + Send>,
+    inner: BoxFuture<GetResponse<TDocument>>,
 }
 
 impl<TDocument> Pending<TDocument> {
@@ -341,22 +346,12 @@ impl<TDocument> Pending<TDocument> {
         F: Future<Item = GetResponse<TDocument>, Error = Error> + Send + 'static,
     {
         Pending {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl<TDocument> Future for Pending<TDocument>
-where
-    TDocument: DeserializeOwned + Send + 'static,
-{
-    type Item = GetResponse<TDocument>;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(Pending<TDocument> => GetResponse<TDocument>, TDocument);
 
 #[cfg(test)]
 mod tests {
@@ -380,6 +375,21 @@ mod tests {
         assert_eq!("/testdoc/_doc/1", req.url.as_ref());
     }
 
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid_id() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let id = ::uuid::Uuid::parse_str("936da01f9abd4d9d80c702af85c822a8").unwrap();
+
+        let req = client.document::<TestDoc>().get(id).inner.into_request();
+
+        assert_eq!(
+            "/testdoc/_doc/936da01f-9abd-4d9d-80c7-02af85c822a8",
+            req.url.as_ref()
+        );
+    }
+
     #[test]
     fn specify_index() {
         let client = SyncClientBuilder::new().build().unwrap();
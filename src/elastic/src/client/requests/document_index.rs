@@ -7,14 +7,25 @@ Builders for [index requests][docs-index].
 use std::borrow::Cow;
 
 use futures::{
+    future::Either,
     Future,
-    Poll,
+    IntoFuture,
 };
 use serde::Serialize;
 use serde_json;
 
 use client::{
     requests::{
+        common::{
+            NoneFields,
+            SerializeDocument,
+        },
+        content_hash::{
+            async_get_existing,
+            sync_get_existing,
+            ContentHash,
+            HashedDocument,
+        },
         endpoints::IndexRequest,
         params::{
             Id,
@@ -37,6 +48,10 @@ use error::{
     Error,
     Result,
 };
+use std_future::{
+    box_future,
+    BoxFuture,
+};
 use types::document::{
     DocumentType,
     DEFAULT_DOC_TYPE,
@@ -62,6 +77,8 @@ pub struct IndexRequestInner<TDocument> {
     ty: Type<'static>,
     id: Option<Id<'static>>,
     doc: TDocument,
+    content_hash: Option<ContentHash>,
+    none_fields: NoneFields,
 }
 
 /**
@@ -132,16 +149,26 @@ where
         let index = doc.index().into_owned().into();
         let ty = doc.ty().into_owned().into();
         let id = doc.partial_id().map(Cow::into_owned).map(Into::into);
+        let routing = doc.partial_routing().map(Cow::into_owned);
 
-        RequestBuilder::initial(
+        let builder = RequestBuilder::initial(
             self.inner,
             IndexRequestInner {
                 index: index,
                 ty: ty,
                 id: id,
                 doc: doc,
+                content_hash: None,
+                none_fields: NoneFields::Null,
             },
-        )
+        );
+
+        match routing {
+            Some(routing) => {
+                builder.params_fluent(move |params| params.url_param("routing", &routing))
+            }
+            None => builder,
+        }
     }
 
     /**
@@ -207,6 +234,8 @@ where
                 ty: DEFAULT_DOC_TYPE.into(),
                 id: None,
                 doc: doc,
+                content_hash: None,
+                none_fields: NoneFields::Null,
             },
         )
     }
@@ -217,7 +246,8 @@ where
     TDocument: Serialize,
 {
     fn into_request(self) -> Result<IndexRequest<'static, Vec<u8>>> {
-        let body = serde_json::to_vec(&self.doc).map_err(error::request)?;
+        let body = serde_json::to_vec(&SerializeDocument::new(&self.doc, self.none_fields))
+            .map_err(error::request)?;
 
         let request = match self.id {
             Some(id) => IndexRequest::for_index_ty_id(self.index, self.ty, id, body),
@@ -226,6 +256,12 @@ where
 
         Ok(request)
     }
+
+    fn into_hashed(self, content_hash: &ContentHash) -> Result<HashedDocument> {
+        let doc = SerializeDocument::new(&self.doc, self.none_fields);
+
+        content_hash.hash(self.index, self.ty, self.id, &doc)
+    }
 }
 
 /**
@@ -254,6 +290,70 @@ where
         self.inner.id = Some(id.into());
         self
     }
+
+    /**
+    Skip the write if the document hasn't changed since it was last indexed.
+
+    A content hash is computed for the document and compared with the hash of the existing document before it's written.
+    If the hashes match then no write is sent and the response is [`unchanged`][IndexResponse.unchanged].
+    See the [`content_hash`][content-hash-mod] module for more details.
+
+    # Examples
+
+    Store the content hash in a field called `sync_hash`:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Serialize, Deserialize, ElasticType)]
+    # struct MyType {
+    #     #[elastic(id)]
+    #     pub id: String,
+    #     pub title: String,
+    # }
+    # let client = SyncClientBuilder::new().build()?;
+    let doc = MyType {
+        id: "1".to_owned(),
+        title: String::from("A title"),
+    };
+
+    let response = client.document()
+                         .index(doc)
+                         .content_hash(ContentHash::stored_in("sync_hash"))
+                         .send()?;
+
+    if response.unchanged() {
+        println!("document {} is unchanged", response.id());
+    }
+    # Ok(())
+    # }
+    ```
+
+    [IndexResponse.unchanged]: ../../responses/struct.IndexResponse.html#method.unchanged
+    [content-hash-mod]: ../content_hash/index.html
+    */
+    pub fn content_hash(mut self, content_hash: ContentHash) -> Self {
+        self.inner.content_hash = Some(content_hash);
+        self
+    }
+
+    /**
+    Set how `None` fields on the document are serialized.
+
+    By default, `None` fields are serialized as `null`.
+    See [`NoneFields`][NoneFields] for more details.
+
+    [NoneFields]: ../common/enum.NoneFields.html
+    */
+    pub fn none_fields(mut self, none_fields: NoneFields) -> Self {
+        self.inner.none_fields = none_fields;
+        self
+    }
 }
 
 /**
@@ -304,7 +404,24 @@ where
 
     [SyncClient]: ../../type.SyncClient.html
     */
-    pub fn send(self) -> Result<IndexResponse> {
+    pub fn send(mut self) -> Result<IndexResponse> {
+        if let Some(content_hash) = self.inner.content_hash.take() {
+            let doc = self.inner.into_hashed(&content_hash)?;
+            let params = self.client.addresses.resolve(self.params_builder)?;
+
+            let existing = sync_get_existing(&self.client, &params, &doc)?;
+            if doc.is_unchanged(&existing) {
+                return doc.unchanged_response();
+            }
+
+            return self
+                .client
+                .request(doc.into_index_request()?)
+                .params(params)
+                .send()?
+                .into_response();
+        }
+
         let req = self.inner.into_request()?;
 
         RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
@@ -329,17 +446,14 @@ where
 
     Index a document with an id of `1`:
 
-    ```no_run
+    ```edition2018,no_run
     # extern crate serde;
-    # extern crate futures;
-    # extern crate tokio;
     # #[macro_use] extern crate serde_derive;
     # #[macro_use] extern crate elastic_derive;
     # extern crate elastic;
-    # use futures::Future;
     # use elastic::prelude::*;
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # #[derive(Serialize, Deserialize, ElasticType)]
     # struct MyType {
     #     pub id: String,
@@ -353,15 +467,12 @@ where
         timestamp: Date::now()
     };
 
-    let future = client.document()
-                       .index(doc)
-                       .send();
-
-    future.and_then(|response| {
-        assert!(response.created());
+    let response = client.document()
+                         .index(doc)
+                         .send()
+                         .await?;
 
-        Ok(())
-    });
+    assert!(response.created());
     # Ok(())
     # }
     ```
@@ -369,14 +480,50 @@ where
     [AsyncClient]: ../../type.AsyncClient.html
     */
     pub fn send(self) -> Pending {
-        let (client, params_builder, inner) = (self.client, self.params_builder, self.inner);
+        let (client, params_builder, mut inner) = (self.client, self.params_builder, self.inner);
+
+        if let Some(content_hash) = inner.content_hash.take() {
+            let doc_future = client
+                .sender
+                .maybe_async(move || inner.into_hashed(&content_hash));
+
+            let res_future = doc_future.and_then(move |doc| {
+                client
+                    .addresses
+                    .resolve(params_builder)
+                    .compat()
+                    .and_then(move |params| {
+                        async_get_existing(&client, &params, &doc).and_then(move |existing| {
+                            if doc.is_unchanged(&existing) {
+                                return Either::A(doc.unchanged_response().into_future());
+                            }
+
+                            let res_future = doc.into_index_request().into_future().and_then(
+                                move |req| {
+                                    client
+                                        .request(req)
+                                        .params(params)
+                                        .send()
+                                        .compat()
+                                        .and_then(|res| res.into_response().compat())
+                                },
+                            );
+
+                            Either::B(res_future)
+                        })
+                    })
+            });
+
+            return Pending::new(res_future);
+        }
 
         let req_future = client.sender.maybe_async(move || inner.into_request());
 
         let res_future = req_future.and_then(move |req| {
             RequestBuilder::new(client, params_builder, RawRequestInner::new(req))
                 .send()
-                .and_then(|res| res.into_response())
+                .compat()
+                .and_then(|res| res.into_response().compat())
         });
 
         Pending::new(res_future)
@@ -385,7 +532,7 @@ where
 
 /** A future returned by calling `send`. */
 pub struct Pending {
-    inner: Box<Future<Item = IndexResponse, Error = Error> + Send>,
+    inner: BoxFuture<IndexResponse>,
 }
 
 impl Pending {
@@ -394,19 +541,12 @@ impl Pending {
         F: Future<Item = IndexResponse, Error = Error> + Send + 'static,
     {
         Pending {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl Future for Pending {
-    type Item = IndexResponse;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(Pending => IndexResponse);
 
 #[cfg(test)]
 mod tests {
@@ -436,6 +576,54 @@ mod tests {
         assert_eq!("{}".as_bytes().to_vec(), req.body);
     }
 
+    #[derive(Serialize, ElasticType)]
+    struct OptionalDoc {
+        title: Option<String>,
+        inner: Option<OptionalInner>,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    struct OptionalInner {
+        value: Option<i32>,
+    }
+
+    #[test]
+    fn none_fields_null() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let doc = OptionalDoc {
+            title: None,
+            inner: Some(OptionalInner { value: None }),
+        };
+
+        let req = client.document().index(doc).inner.into_request().unwrap();
+
+        assert_eq!(
+            r#"{"title":null,"inner":{"value":null}}"#.as_bytes().to_vec(),
+            req.body
+        );
+    }
+
+    #[test]
+    fn none_fields_omit() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let doc = OptionalDoc {
+            title: None,
+            inner: Some(OptionalInner { value: None }),
+        };
+
+        let req = client
+            .document()
+            .index(doc)
+            .none_fields(NoneFields::Omit)
+            .inner
+            .into_request()
+            .unwrap();
+
+        assert_eq!(r#"{"inner":{}}"#.as_bytes().to_vec(), req.body);
+    }
+
     #[test]
     fn specify_index() {
         let client = SyncClientBuilder::new().build().unwrap();
@@ -480,4 +668,86 @@ mod tests {
 
         assert_eq!("/testdoc/_doc/1", req.url.as_ref());
     }
+
+    #[derive(Serialize, ElasticType)]
+    struct IdDoc {
+        #[elastic(id)]
+        id: Option<i64>,
+    }
+
+    #[test]
+    fn id_from_doc() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let with_id = client
+            .document()
+            .index(IdDoc { id: Some(1) })
+            .inner
+            .into_request()
+            .unwrap();
+
+        let without_id = client
+            .document()
+            .index(IdDoc { id: None })
+            .inner
+            .into_request()
+            .unwrap();
+
+        assert_eq!("/iddoc/_doc/1", with_id.url.as_ref());
+        assert_eq!("/iddoc/_doc", without_id.url.as_ref());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_routing_from_doc() {
+        use client::sender::mock::{
+            MockResponse,
+            MockSender,
+        };
+        use http::{
+            Method,
+            StatusCode,
+        };
+
+        #[derive(Serialize, ElasticType)]
+        struct RoutedDoc {
+            #[elastic(id)]
+            id: String,
+            #[elastic(routing)]
+            tenant: String,
+        }
+
+        let mock = MockSender::new();
+        mock.respond(
+            Method::POST,
+            "/routeddoc/_doc/1",
+            MockResponse::new(StatusCode::CREATED).json(&json!({
+                "_index": "routeddoc",
+                "_type": "_doc",
+                "_id": "1",
+                "_version": 1,
+                "result": "created",
+                "_shards": {
+                    "total": 1,
+                    "successful": 1,
+                    "failed": 0
+                }
+            })),
+        );
+
+        let client = SyncClientBuilder::new().mock(mock.clone()).build().unwrap();
+
+        client
+            .document()
+            .index(RoutedDoc {
+                id: "1".to_owned(),
+                tenant: "tenant123".to_owned(),
+            })
+            .send()
+            .unwrap();
+
+        let requests = mock.requests();
+
+        assert_eq!(Some("routing=tenant123"), requests[0].url().query());
+    }
 }
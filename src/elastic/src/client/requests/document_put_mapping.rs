@@ -4,11 +4,12 @@ Builders for [put mapping requests][docs-mapping].
 [docs-mapping]: https://www.elastic.co/guide/en/elasticsearch/reference/current/mapping.html
 */
 
-use futures::{
-    Future,
-    Poll,
+use futures::Future;
+use serde_json::{
+    self,
+    Map,
+    Value,
 };
-use serde_json;
 use std::marker::PhantomData;
 
 use client::{
@@ -19,6 +20,7 @@ use client::{
             Type,
         },
         raw::RawRequestInner,
+        search::RuntimeField,
         RequestBuilder,
     },
     responses::CommandResponse,
@@ -34,6 +36,10 @@ use error::{
     Error,
     Result,
 };
+use std_future::{
+    box_future,
+    BoxFuture,
+};
 use types::document::{
     DEFAULT_DOC_TYPE,
     DocumentType,
@@ -59,6 +65,7 @@ pub type PutMappingRequestBuilder<TSender, TDocument> =
 pub struct PutMappingRequestInner<TDocument> {
     index: Index<'static>,
     ty: Type<'static>,
+    runtime: Map<String, Value>,
     _marker: PhantomData<TDocument>,
 }
 
@@ -123,6 +130,7 @@ where
             PutMappingRequestInner {
                 index: index,
                 ty: ty,
+                runtime: Map::new(),
                 _marker: PhantomData,
             },
         )
@@ -134,7 +142,24 @@ where
     TDocument: DocumentType,
 {
     fn into_request(self) -> Result<IndicesPutMappingRequest<'static, Vec<u8>>> {
-        let body = serde_json::to_vec(&TDocument::index_mapping()).map_err(error::request)?;
+        let body = if self.runtime.is_empty() {
+            serde_json::to_vec(&TDocument::index_mapping()).map_err(error::request)?
+        } else {
+            let mut mapping =
+                serde_json::to_value(TDocument::index_mapping()).map_err(error::request)?;
+
+            if let Value::Object(ref mut mapping) = mapping {
+                let runtime = mapping
+                    .entry("runtime")
+                    .or_insert_with(|| Value::Object(Map::new()));
+
+                if let Value::Object(ref mut runtime) = *runtime {
+                    runtime.extend(self.runtime);
+                }
+            }
+
+            serde_json::to_vec(&mapping).map_err(error::request)?
+        };
 
         if &self.ty[..] == DEFAULT_DOC_TYPE {
             Ok(IndicesPutMappingRequest::for_index(
@@ -168,6 +193,44 @@ where
         self.inner.ty = ty.into();
         self
     }
+
+    /**
+    Declare a field in the `runtime` section of the mapping.
+
+    Runtime fields declared on the document type are kept, unless they have the same name.
+
+    # Examples
+
+    Put the mapping for a document type called `MyType` with a runtime field:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.document::<MyType>()
+                         .put_mapping()
+                         .runtime_field(
+                             "day_of_week",
+                             RuntimeField::new(RuntimeFieldType::Keyword, "emit(doc['timestamp'].value.dayOfWeekEnum.toString())"),
+                         )
+                         .send()?;
+
+    assert!(response.acknowledged());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn runtime_field(mut self, name: impl Into<String>, field: RuntimeField) -> Self {
+        self.inner.runtime.insert(name.into(), field.to_value());
+        self
+    }
 }
 
 /**
@@ -233,29 +296,23 @@ where
 
     Put the mapping for a document type called `MyType`:
 
-    ```no_run
-    # extern crate futures;
-    # extern crate tokio;
+    ```edition2018,no_run
     # extern crate serde;
     # #[macro_use] extern crate serde_derive;
     # #[macro_use] extern crate elastic_derive;
     # extern crate elastic;
-    # use futures::Future;
     # use elastic::prelude::*;
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # #[derive(Serialize, Deserialize, ElasticType)]
     # struct MyType { }
     # let client = AsyncClientBuilder::new().build()?;
-    let future = client.document::<MyType>()
-                       .put_mapping()
-                       .send();
-
-    future.and_then(|response| {
-        assert!(response.acknowledged());
+    let response = client.document::<MyType>()
+                         .put_mapping()
+                         .send()
+                         .await?;
 
-        Ok(())
-    });
+    assert!(response.acknowledged());
     # Ok(())
     # }
     ```
@@ -270,7 +327,8 @@ where
         let res_future = req_future.and_then(move |req| {
             RequestBuilder::new(client, params_builder, RawRequestInner::new(req))
                 .send()
-                .and_then(|res| res.into_response())
+                .compat()
+                .and_then(|res| res.into_response().compat())
         });
 
         Pending::new(res_future)
@@ -279,7 +337,7 @@ where
 
 /** A future returned by calling `send`. */
 pub struct Pending {
-    inner: Box<Future<Item = CommandResponse, Error = Error> + Send>,
+    inner: BoxFuture<CommandResponse>,
 }
 
 impl Pending {
@@ -288,19 +346,12 @@ impl Pending {
         F: Future<Item = CommandResponse, Error = Error> + Send + 'static,
     {
         Pending {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl Future for Pending {
-    type Item = CommandResponse;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(Pending => CommandResponse);
 
 #[cfg(test)]
 mod tests {
@@ -371,4 +422,48 @@ mod tests {
 
         assert_eq!("/testdoc/_mappings/new-ty", req.url.as_ref());
     }
+
+    #[test]
+    fn specify_runtime_field() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .document::<TestDoc>()
+            .put_mapping()
+            .runtime_field(
+                "day_of_week",
+                RuntimeField::new(
+                    RuntimeFieldType::Keyword,
+                    "emit(doc['timestamp'].value.dayOfWeekEnum.toString())",
+                ),
+            )
+            .runtime_field(
+                "title",
+                RuntimeField::from_source(RuntimeFieldType::Keyword),
+            )
+            .inner
+            .into_request()
+            .unwrap();
+
+        let expected_body = json!({
+            "runtime": {
+                "day_of_week": {
+                    "type": "keyword",
+                    "script": {
+                        "inline": "emit(doc['timestamp'].value.dayOfWeekEnum.toString())"
+                    }
+                },
+                "title": {
+                    "type": "keyword"
+                }
+            },
+            "properties": {
+
+            }
+        });
+
+        let actual_body: Value = serde_json::from_slice(&req.body).unwrap();
+
+        assert_eq!(expected_body, actual_body);
+    }
 }
@@ -4,10 +4,7 @@ Builders for [update document requests][docs-update].
 [docs-update]: http://www.elastic.co/guide/en/elasticsearch/reference/current/docs-update.html
 */
 
-use futures::{
-    Future,
-    Poll,
-};
+use futures::Future;
 use serde::ser::Serialize;
 use serde_json;
 use std::marker::PhantomData;
@@ -35,8 +32,13 @@ use error::{
     self,
     Error,
 };
+use std_future::{
+    box_future,
+    BoxFuture,
+};
 use types::document::{
     DocumentType,
+    PartialDocument,
     StaticIndex,
     StaticType,
     DEFAULT_DOC_TYPE,
@@ -45,6 +47,7 @@ use types::document::{
 pub use client::requests::common::{
     DefaultParams,
     Doc,
+    NoneFields,
     Script,
     ScriptBuilder,
 };
@@ -599,6 +602,76 @@ where
     }
 }
 
+impl<TSender, TDocument> UpdateRequestBuilder<TSender, Doc<TDocument>>
+where
+    TSender: Sender,
+{
+    /**
+    Set how `None` fields on the partial document are serialized.
+
+    By default, `None` fields are serialized as `null`, which replaces any existing values for those fields.
+    Use `NoneFields::Omit` to leave existing values unchanged instead.
+    See [`NoneFields`][NoneFields] for more details.
+
+    [NoneFields]: ../common/enum.NoneFields.html
+    */
+    pub fn none_fields(mut self, none_fields: NoneFields) -> Self {
+        self.inner.body = self.inner.body.none_fields(none_fields);
+        self
+    }
+
+    /**
+    Update the source using a partial document.
+
+    Unlike [`doc`][UpdateRequestBuilder.doc], the partial document must be for the same document type as the request.
+    Only the fields that are set on the partial document are updated.
+
+    # Examples
+
+    Derive `ElasticType` with `#[elastic(partial)]` to generate a `MyTypePartial` struct, and use it to update the `title` of a document called `MyType` with an id of `1`:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use]
+    # extern crate serde_derive;
+    # #[macro_use]
+    # extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    #[derive(Serialize, Deserialize, ElasticType)]
+    #[elastic(partial)]
+    struct MyType {
+        pub id: String,
+        pub title: String,
+        pub timestamp: Date<DefaultDateMapping>
+    }
+
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.document::<MyType>()
+                         .update(1)
+                         .partial_doc(MyTypePartial::new().title("New Title".to_owned()))
+                         .send()?;
+
+    assert!(response.updated());
+    # Ok(())
+    # }
+    ```
+
+    [UpdateRequestBuilder.doc]: #method.doc
+    */
+    pub fn partial_doc<TPartial>(
+        self,
+        doc: TPartial,
+    ) -> UpdateRequestBuilder<TSender, Doc<TPartial>>
+    where
+        TPartial: PartialDocument<Document = TDocument>,
+    {
+        self.doc(doc)
+    }
+}
+
 /**
 # Send synchronously
 */
@@ -671,19 +744,16 @@ where
 
     Update a [`DocumentType`][documents-mod] called `MyType` with an id of `1` using a new document value:
 
-    ```no_run
-    # extern crate futures;
-    # extern crate tokio;
+    ```edition2018,no_run
     # extern crate serde;
     # extern crate serde_json;
     # #[macro_use] extern crate serde_derive;
     # #[macro_use] extern crate elastic_derive;
     # extern crate elastic;
     # use serde_json::Value;
-    # use futures::Future;
     # use elastic::prelude::*;
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # #[derive(Serialize, Deserialize, ElasticType)]
     # struct MyType {
     #     pub id: String,
@@ -692,16 +762,13 @@ where
     # }
     # let client = AsyncClientBuilder::new().build()?;
     # let new_doc = MyType { id: "1".to_owned(), title: String::new(), timestamp: Date::now() };
-    let future = client.document::<MyType>()
-                       .update(1)
-                       .doc(new_doc)
-                       .send();
-
-    future.and_then(|response| {
-        assert!(response.updated());
+    let response = client.document::<MyType>()
+                         .update(1)
+                         .doc(new_doc)
+                         .send()
+                         .await?;
 
-        Ok(())
-    });
+    assert!(response.updated());
     # Ok(())
     # }
     ```
@@ -717,7 +784,8 @@ where
         let res_future = req_future.and_then(move |req| {
             RequestBuilder::new(client, params_builder, RawRequestInner::new(req))
                 .send()
-                .and_then(|res| res.into_response())
+                .compat()
+                .and_then(|res| res.into_response().compat())
         });
 
         Pending::new(res_future)
@@ -726,7 +794,7 @@ where
 
 /** A future returned by calling `send`. */
 pub struct Pending {
-    inner: Box<Future<Item = UpdateResponse, Error = Error> + Send>,
+    inner: BoxFuture<UpdateResponse>,
 }
 
 impl Pending {
@@ -735,19 +803,12 @@ impl Pending {
         F: Future<Item = UpdateResponse, Error = Error> + Send + 'static,
     {
         Pending {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl Future for Pending {
-    type Item = UpdateResponse;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(Pending => UpdateResponse);
 
 #[cfg(test)]
 mod tests {
@@ -843,6 +904,63 @@ mod tests {
         assert_eq!(expected_body.to_string(), actual_body.to_string());
     }
 
+    #[test]
+    fn specify_doc_omit_none_fields() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .document::<TestDoc>()
+            .update("1")
+            .doc(json!({
+                "a": "string",
+                "b": null
+            }))
+            .none_fields(NoneFields::Omit)
+            .inner
+            .into_request()
+            .unwrap();
+
+        let expected_body = json!({
+            "doc": {
+                "a": "string"
+            }
+        });
+
+        let actual_body: Value = serde_json::from_slice(&req.body).unwrap();
+
+        assert_eq!(expected_body.to_string(), actual_body.to_string());
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(partial)]
+    struct TestPartialDoc {
+        a: String,
+        b: Option<i32>,
+    }
+
+    #[test]
+    fn specify_partial_doc() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .document::<TestPartialDoc>()
+            .update("1")
+            .partial_doc(TestPartialDocPartial::new().a("string".to_owned()))
+            .inner
+            .into_request()
+            .unwrap();
+
+        let expected_body = json!({
+            "doc": {
+                "a": "string"
+            }
+        });
+
+        let actual_body: Value = serde_json::from_slice(&req.body).unwrap();
+
+        assert_eq!(expected_body.to_string(), actual_body.to_string());
+    }
+
     #[test]
     fn specify_inline_script() {
         let client = SyncClientBuilder::new().build().unwrap();
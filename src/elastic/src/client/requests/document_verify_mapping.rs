@@ -0,0 +1,750 @@
+/*!
+Builders for verifying the [mapping][docs-mapping] of a document type against a live index.
+
+[docs-mapping]: https://www.elastic.co/guide/en/elasticsearch/reference/current/mapping.html
+*/
+
+use futures::Future;
+use serde_json::{
+    self,
+    Map,
+    Value,
+};
+use std::{
+    collections::BTreeMap,
+    marker::PhantomData,
+};
+
+use client::{
+    requests::{
+        endpoints::IndicesGetMappingRequest,
+        params::{
+            Index,
+            Type,
+        },
+        raw::RawRequestInner,
+        RequestBuilder,
+    },
+    sender::{
+        AsyncSender,
+        Sender,
+        SyncSender,
+    },
+    DocumentClient,
+};
+use error::{
+    self,
+    Error,
+    Result,
+};
+use std_future::{
+    box_future,
+    BoxFuture,
+};
+use types::document::{
+    DocumentType,
+    FieldMapping,
+    StaticIndex,
+    StaticType,
+};
+
+/**
+A verify mapping request builder that can be configured before sending.
+
+Call [`Client.document.verify_mapping`][Client.document.verify_mapping] to get a `VerifyMappingRequestBuilder`.
+The `send` method will either send the request [synchronously][send-sync] or [asynchronously][send-async], depending on the `Client` it was created from.
+
+[send-sync]: #send-synchronously
+[send-async]: #send-asynchronously
+[Client.document.verify_mapping]: ../../struct.DocumentClient.html#verify-mapping-request
+*/
+pub type VerifyMappingRequestBuilder<TSender, TDocument> =
+    RequestBuilder<TSender, VerifyMappingRequestInner<TDocument>>;
+
+#[doc(hidden)]
+pub struct VerifyMappingRequestInner<TDocument> {
+    index: Index<'static>,
+    ty: Type<'static>,
+    _marker: PhantomData<TDocument>,
+}
+
+/**
+# Verify mapping request
+*/
+impl<TSender, TDocument> DocumentClient<TSender, TDocument>
+where
+    TSender: Sender,
+{
+    /**
+    Create a [`VerifyMappingRequestBuilder`][VerifyMappingRequestBuilder] with this `Client` that can be configured before sending.
+
+    The request fetches the mapping of the index and compares it field by field with the mapping of the document type.
+    Any fields that are missing from the index, or are mapped with a different datatype or parameters, are returned as differences.
+    Fields in the index that aren't in the document type are ignored, because they don't stop documents from being indexed.
+    The `_meta` of each index is also returned, so migration tools can check values like a schema version before writing.
+
+    For more details, see:
+
+    - [builder methods][builder-methods]
+    - [send synchronously][send-sync]
+    - [send asynchronously][send-async]
+
+    # Examples
+
+    Check that the index for a [`DocumentType`][documents-mod] called `MyType` matches its mapping:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.document::<MyType>()
+                         .verify_mapping()
+                         .send()?;
+
+    for difference in response.differences() {
+        println!("{}: {:?}", difference.path(), difference.kind());
+    }
+
+    if let Some(version) = response.meta("myindex").and_then(|meta| meta.get("schema_version")) {
+        println!("schema version: {}", version);
+    }
+
+    assert!(response.is_compatible());
+    # Ok(())
+    # }
+    ```
+
+    [VerifyMappingRequestBuilder]: requests/document_verify_mapping/type.VerifyMappingRequestBuilder.html
+    [builder-methods]: requests/document_verify_mapping/type.VerifyMappingRequestBuilder.html#builder-methods
+    [send-sync]: requests/document_verify_mapping/type.VerifyMappingRequestBuilder.html#send-synchronously
+    [send-async]: requests/document_verify_mapping/type.VerifyMappingRequestBuilder.html#send-asynchronously
+    [documents-mod]: ../types/document/index.html
+    */
+    pub fn verify_mapping(self) -> VerifyMappingRequestBuilder<TSender, TDocument>
+    where
+        TDocument: DocumentType + StaticIndex + StaticType,
+    {
+        let index = TDocument::static_index().into();
+        let ty = TDocument::static_ty().into();
+
+        RequestBuilder::initial(
+            self.inner,
+            VerifyMappingRequestInner {
+                index,
+                ty,
+                _marker: PhantomData,
+            },
+        )
+    }
+}
+
+impl<TDocument> VerifyMappingRequestInner<TDocument>
+where
+    TDocument: DocumentType,
+{
+    fn to_request(&self) -> IndicesGetMappingRequest<'static> {
+        IndicesGetMappingRequest::for_index(self.index.clone())
+    }
+
+    fn into_response(self, live: Value) -> Result<VerifyMappingResponse> {
+        let expected = TDocument::mapping_tree();
+
+        let indices = match live {
+            Value::Object(indices) => indices,
+            _ => return Ok(VerifyMappingResponse::default()),
+        };
+
+        let mut differences = Vec::new();
+        let mut meta = BTreeMap::new();
+        for (index, mut live) in indices {
+            let mappings = live
+                .get_mut("mappings")
+                .map(|mappings| mappings.take())
+                .unwrap_or(Value::Null);
+
+            let mappings = self.unwrap_ty(mappings);
+            let actual: FieldMapping = serde_json::from_value(mappings).map_err(error::request)?;
+
+            diff_properties(&index, None, &expected, &actual, &mut differences);
+
+            if let Some(actual) = actual.meta() {
+                meta.insert(index, actual.clone());
+            }
+        }
+
+        Ok(VerifyMappingResponse { differences, meta })
+    }
+
+    /** Get the mapping for the document type from indices that still use mapping types. */
+    fn unwrap_ty(&self, mappings: Value) -> Value {
+        match mappings {
+            Value::Object(mut mappings) => {
+                let is_typed = !mappings.contains_key("properties")
+                    && mappings.get(&self.ty[..]).is_some_and(Value::is_object);
+
+                if is_typed {
+                    mappings.remove(&self.ty[..]).unwrap_or(Value::Null)
+                } else {
+                    Value::Object(mappings)
+                }
+            }
+            _ => Value::Object(Default::default()),
+        }
+    }
+}
+
+/**
+# Builder methods
+
+Configure a `VerifyMappingRequestBuilder` before sending it.
+*/
+impl<TSender, TDocument> VerifyMappingRequestBuilder<TSender, TDocument>
+where
+    TSender: Sender,
+{
+    /**
+    Set the index to verify the mapping against.
+
+    The index can be a pattern or alias, in which case every matching index is verified.
+    */
+    pub fn index(mut self, index: impl Into<Index<'static>>) -> Self {
+        self.inner.index = index.into();
+        self
+    }
+
+    /** Set the type to verify the mapping against, for indices that still use mapping types. */
+    pub fn ty(mut self, ty: impl Into<Type<'static>>) -> Self {
+        self.inner.ty = ty.into();
+        self
+    }
+}
+
+/**
+# Send synchronously
+*/
+impl<TDocument> VerifyMappingRequestBuilder<SyncSender, TDocument>
+where
+    TDocument: DocumentType,
+{
+    /**
+    Send a `VerifyMappingRequestBuilder` synchronously using a [`SyncClient`][SyncClient].
+
+    This will block the current thread until a response arrives and is compared to the document mapping.
+
+    # Examples
+
+    Fail fast if the index for a document type called `MyType` has an incompatible mapping:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.document::<MyType>()
+                         .verify_mapping()
+                         .send()?;
+
+    if !response.is_compatible() {
+        panic!("incompatible mapping: {:?}", response.differences());
+    }
+    # Ok(())
+    # }
+    ```
+
+    [SyncClient]: ../../type.SyncClient.html
+    */
+    pub fn send(self) -> Result<VerifyMappingResponse> {
+        let req = self.inner.to_request();
+
+        let live = RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
+            .send()?
+            .into_response::<Value>()?;
+
+        self.inner.into_response(live)
+    }
+}
+
+/**
+# Send asynchronously
+*/
+impl<TDocument> VerifyMappingRequestBuilder<AsyncSender, TDocument>
+where
+    TDocument: DocumentType + Send + 'static,
+{
+    /**
+    Send a `VerifyMappingRequestBuilder` asynchronously using an [`AsyncClient`][AsyncClient].
+
+    This will return a future that will resolve to the differences between the document mapping and the index mapping.
+
+    # Examples
+
+    Verify the mapping for a document type called `MyType`:
+
+    ```edition2018,no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
+    # #[derive(Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = AsyncClientBuilder::new().build()?;
+    let response = client.document::<MyType>()
+                         .verify_mapping()
+                         .send()
+                         .await?;
+
+    assert!(response.is_compatible());
+    # Ok(())
+    # }
+    ```
+
+    [AsyncClient]: ../../type.AsyncClient.html
+    */
+    pub fn send(self) -> Pending {
+        let (client, params_builder, inner) = (self.client, self.params_builder, self.inner);
+        let req = inner.to_request();
+
+        let res_future = RequestBuilder::new(client, params_builder, RawRequestInner::new(req))
+            .send()
+            .compat()
+            .and_then(|res| res.into_response::<Value>().compat())
+            .and_then(move |live| inner.into_response(live));
+
+        Pending::new(res_future)
+    }
+}
+
+/** Response for verifying the mapping of a document type against a live index. */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VerifyMappingResponse {
+    differences: Vec<MappingDifference>,
+    meta: BTreeMap<String, Map<String, Value>>,
+}
+
+impl VerifyMappingResponse {
+    /** Whether the index mapping has every field in the document mapping, with the same datatypes and parameters. */
+    pub fn is_compatible(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /** The differences between the document mapping and the index mapping. */
+    pub fn differences(&self) -> &[MappingDifference] {
+        &self.differences
+    }
+
+    /**
+    The custom metadata stored in the `_meta` field of the mapping for an index.
+
+    The metadata isn't compared with the document mapping, so indices can carry values like a schema version that tools check before migrating.
+    */
+    pub fn meta(&self, index: &str) -> Option<&Map<String, Value>> {
+        self.meta.get(index)
+    }
+}
+
+/** A field in the document mapping that's different in the index mapping. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct MappingDifference {
+    index: String,
+    path: String,
+    kind: MappingDifferenceKind,
+}
+
+impl MappingDifference {
+    /** The name of the index with the different mapping. */
+    pub fn index(&self) -> &str {
+        &self.index
+    }
+
+    /** The full path to the field, like `title.keyword`. */
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /** How the field is different. */
+    pub fn kind(&self) -> &MappingDifferenceKind {
+        &self.kind
+    }
+}
+
+/** How a field in the document mapping is different in the index mapping. */
+#[derive(Debug, Clone, PartialEq)]
+pub enum MappingDifferenceKind {
+    /** The field isn't mapped in the index. */
+    Missing {
+        /** The datatype of the field in the document mapping. */
+        expected: String,
+    },
+    /** The field is mapped with a different datatype in the index. */
+    TypeMismatch {
+        /** The datatype of the field in the document mapping. */
+        expected: String,
+        /** The datatype of the field in the index mapping. */
+        actual: String,
+    },
+    /** A mapping parameter for the field is missing or has a different value in the index. */
+    ParamMismatch {
+        /** The name of the parameter, like `format` or `analyzer`. */
+        param: String,
+        /** The value of the parameter in the document mapping. */
+        expected: Value,
+        /** The value of the parameter in the index mapping, if it's set. */
+        actual: Option<Value>,
+    },
+}
+
+fn diff_properties(
+    index: &str,
+    parent: Option<&str>,
+    expected: &FieldMapping,
+    actual: &FieldMapping,
+    differences: &mut Vec<MappingDifference>,
+) {
+    let inner_fields = expected
+        .properties()
+        .iter()
+        .map(|field| (field, actual.properties()))
+        .chain(
+            expected
+                .fields()
+                .iter()
+                .map(|field| (field, actual.fields())),
+        );
+
+    for (expected, actual) in inner_fields {
+        let path = match parent {
+            Some(parent) => format!("{}.{}", parent, expected.name()),
+            None => expected.name().to_owned(),
+        };
+
+        let mut difference = |kind| {
+            differences.push(MappingDifference {
+                index: index.to_owned(),
+                path: path.clone(),
+                kind,
+            })
+        };
+
+        let actual = match actual.iter().find(|field| field.name() == expected.name()) {
+            Some(actual) => actual,
+            None => {
+                difference(MappingDifferenceKind::Missing {
+                    expected: expected.data_type().to_owned(),
+                });
+                continue;
+            }
+        };
+
+        if expected.data_type() != actual.data_type() {
+            difference(MappingDifferenceKind::TypeMismatch {
+                expected: expected.data_type().to_owned(),
+                actual: actual.data_type().to_owned(),
+            });
+            continue;
+        }
+
+        for (param, expected) in expected.params() {
+            let actual = actual.param(param);
+
+            if !actual.is_some_and(|actual| param_eq(expected, actual)) {
+                difference(MappingDifferenceKind::ParamMismatch {
+                    param: param.to_owned(),
+                    expected: expected.clone(),
+                    actual: actual.cloned(),
+                });
+            }
+        }
+
+        diff_properties(index, Some(&path), expected, actual, differences);
+    }
+}
+
+/**
+Compare mapping parameters.
+
+Elasticsearch may return scalar parameters as strings, like `"dynamic": "false"`, so scalars are compared by their string values.
+*/
+fn param_eq(expected: &Value, actual: &Value) -> bool {
+    fn scalar(value: &Value) -> Option<String> {
+        match *value {
+            Value::Bool(value) => Some(value.to_string()),
+            Value::Number(ref value) => Some(value.to_string()),
+            Value::String(ref value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    match (scalar(expected), scalar(actual)) {
+        (Some(expected), Some(actual)) => expected == actual,
+        _ => expected == actual,
+    }
+}
+
+/** A future returned by calling `send`. */
+pub struct Pending {
+    inner: BoxFuture<VerifyMappingResponse>,
+}
+
+impl Pending {
+    fn new<F>(fut: F) -> Self
+    where
+        F: Future<Item = VerifyMappingResponse, Error = Error> + Send + 'static,
+    {
+        Pending {
+            inner: box_future(fut),
+        }
+    }
+}
+
+pending_future!(Pending => VerifyMappingResponse);
+
+#[cfg(test)]
+mod tests {
+    use prelude::*;
+    use serde_json::Value;
+    use tests::*;
+
+    use super::VerifyMappingResponse;
+
+    #[test]
+    fn is_send() {
+        assert_send::<super::Pending>();
+    }
+
+    #[derive(Serialize, ElasticType)]
+    struct TestDoc {
+        title: String,
+        count: i32,
+        inner: TestInner,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(object)]
+    struct TestInner {
+        published: Date<DefaultDateMapping>,
+    }
+
+    fn verify(live: Value) -> VerifyMappingResponse {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        client
+            .document::<TestDoc>()
+            .verify_mapping()
+            .inner
+            .into_response(live)
+            .unwrap()
+    }
+
+    #[test]
+    fn default_request() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .document::<TestDoc>()
+            .verify_mapping()
+            .inner
+            .to_request();
+
+        assert_eq!("/testdoc/_mapping", req.url.as_ref());
+    }
+
+    #[test]
+    fn specify_index() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .document::<TestDoc>()
+            .verify_mapping()
+            .index("new-idx")
+            .inner
+            .to_request();
+
+        assert_eq!("/new-idx/_mapping", req.url.as_ref());
+    }
+
+    #[test]
+    fn compatible_mapping() {
+        let response = verify(json!({
+            "testdoc": {
+                "mappings": {
+                    "properties": {
+                        "title": {
+                            "type": "text",
+                            "fields": {
+                                "keyword": {
+                                    "type": "keyword",
+                                    "ignore_above": 256
+                                }
+                            }
+                        },
+                        "count": {
+                            "type": "integer"
+                        },
+                        "inner": {
+                            "properties": {
+                                "published": {
+                                    "type": "date",
+                                    "format": "basic_date_time"
+                                }
+                            }
+                        },
+                        "extra": {
+                            "type": "keyword"
+                        }
+                    }
+                }
+            }
+        }));
+
+        assert!(response.is_compatible());
+    }
+
+    #[test]
+    fn compatible_typed_mapping() {
+        let response = verify(json!({
+            "testdoc": {
+                "mappings": {
+                    "_doc": {
+                        "properties": {
+                            "title": {
+                                "type": "text",
+                                "fields": {
+                                    "keyword": {
+                                        "type": "keyword",
+                                        "ignore_above": "256"
+                                    }
+                                }
+                            },
+                            "count": {
+                                "type": "integer"
+                            },
+                            "inner": {
+                                "type": "object",
+                                "properties": {
+                                    "published": {
+                                        "type": "date",
+                                        "format": "basic_date_time"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }));
+
+        assert!(response.is_compatible());
+    }
+
+    #[test]
+    fn read_index_meta() {
+        let response = verify(json!({
+            "testdoc-1": {
+                "mappings": {
+                    "_meta": {
+                        "schema_version": 2
+                    },
+                    "properties": {}
+                }
+            },
+            "testdoc-2": {
+                "mappings": {
+                    "properties": {}
+                }
+            }
+        }));
+
+        assert_eq!(
+            Some(&json!(2)),
+            response
+                .meta("testdoc-1")
+                .and_then(|meta| meta.get("schema_version"))
+        );
+        assert!(response.meta("testdoc-2").is_none());
+    }
+
+    #[test]
+    fn incompatible_mapping() {
+        let response = verify(json!({
+            "testdoc-1": {
+                "mappings": {
+                    "properties": {
+                        "title": {
+                            "type": "text",
+                            "fields": {
+                                "keyword": {
+                                    "type": "keyword",
+                                    "ignore_above": 1024
+                                }
+                            }
+                        },
+                        "count": {
+                            "type": "long"
+                        },
+                        "inner": {
+                            "properties": {}
+                        }
+                    }
+                }
+            }
+        }));
+
+        let differences: Vec<_> = response
+            .differences()
+            .iter()
+            .map(|difference| {
+                (
+                    difference.index(),
+                    difference.path(),
+                    difference.kind().clone(),
+                )
+            })
+            .collect();
+
+        let expected = vec![
+            (
+                "testdoc-1",
+                "title.keyword",
+                MappingDifferenceKind::ParamMismatch {
+                    param: "ignore_above".to_owned(),
+                    expected: json!(256),
+                    actual: Some(json!(1024)),
+                },
+            ),
+            (
+                "testdoc-1",
+                "count",
+                MappingDifferenceKind::TypeMismatch {
+                    expected: "integer".to_owned(),
+                    actual: "long".to_owned(),
+                },
+            ),
+            (
+                "testdoc-1",
+                "inner.published",
+                MappingDifferenceKind::Missing {
+                    expected: "date".to_owned(),
+                },
+            ),
+        ];
+
+        assert!(!response.is_compatible());
+        assert_eq!(expected, differences);
+    }
+}
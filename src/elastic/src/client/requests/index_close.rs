@@ -4,10 +4,7 @@ Builders for [close index requests][docs-close-index].
 [docs-close-index]: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-open-close.html
 */
 
-use futures::{
-    Future,
-    Poll,
-};
+use futures::Future;
 
 use client::{
     requests::{
@@ -27,6 +24,10 @@ use client::{
     IndexClient,
 };
 use error::*;
+use std_future::{
+    box_future,
+    BoxFuture,
+};
 
 /**
 A [close index request][docs-close-index] builder that can be configured before sending.
@@ -144,22 +145,15 @@ impl IndexCloseRequestBuilder<AsyncSender> {
 
     Close an index called `myindex`:
 
-    ```no_run
-    # extern crate futures;
-    # extern crate tokio;
+    ```edition2018,no_run
     # extern crate elastic;
-    # use futures::Future;
     # use elastic::prelude::*;
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # let client = AsyncClientBuilder::new().build()?;
-    let future = client.index("myindex").close().send();
+    let response = client.index("myindex").close().send().await?;
 
-    future.and_then(|response| {
-        assert!(response.acknowledged());
-
-        Ok(())
-    });
+    assert!(response.acknowledged());
     # Ok(())
     # }
     ```
@@ -172,7 +166,8 @@ impl IndexCloseRequestBuilder<AsyncSender> {
         let res_future =
             RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
                 .send()
-                .and_then(|res| res.into_response());
+                .compat()
+                .and_then(|res| res.into_response().compat());
 
         Pending::new(res_future)
     }
@@ -180,7 +175,7 @@ impl IndexCloseRequestBuilder<AsyncSender> {
 
 /** A future returned by calling `send`. */
 pub struct Pending {
-    inner: Box<Future<Item = CommandResponse, Error = Error> + Send>,
+    inner: BoxFuture<CommandResponse>,
 }
 
 impl Pending {
@@ -189,19 +184,12 @@ impl Pending {
         F: Future<Item = CommandResponse, Error = Error> + Send + 'static,
     {
         Pending {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl Future for Pending {
-    type Item = CommandResponse;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(Pending => CommandResponse);
 
 #[cfg(test)]
 mod tests {
@@ -4,9 +4,11 @@ Builders for [create index requests][docs-create-index].
 [docs-create-index]: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-create-index.html
 */
 
-use futures::{
-    Future,
-    Poll,
+use futures::Future;
+use serde_json::{
+    self,
+    Map,
+    Value,
 };
 
 use client::{
@@ -27,6 +29,15 @@ use client::{
     IndexClient,
 };
 use error::*;
+use std_future::{
+    box_future,
+    BoxFuture,
+};
+use types::document::DocumentType;
+
+mod analysis;
+
+pub use self::analysis::*;
 
 /**
 A [create index request][docs-create-index] builder that can be configured before sending.
@@ -175,6 +186,144 @@ where
     }
 }
 
+/**
+# Index body
+
+Build the body of a create index request from typed settings and mappings.
+These methods will replace any body set with [`body`](#method.body) with a JSON object.
+*/
+impl<TSender> IndexCreateRequestBuilder<TSender, DefaultBody>
+where
+    TSender: Sender,
+{
+    /**
+    Set the analysis settings for the index.
+
+    Any analysis settings already in the body are replaced.
+
+    # Examples
+
+    Create an index with a custom analyzer and normalizer that are used by fields in the mapping for a [`DocumentType`][documents-mod] called `MyType`:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    #[derive(Serialize, Deserialize, ElasticType)]
+    struct MyType {
+        #[elastic(fields(folded(analyzer = "folded")))]
+        title: String,
+        #[elastic(normalizer = "folded")]
+        code: Keyword<DefaultKeywordMapping>,
+    }
+
+    # let client = SyncClientBuilder::new().build()?;
+    let analysis = Analysis::new()
+        .analyzer("folded", Analyzer::custom("standard").filter("lowercase").filter("asciifolding"))
+        .normalizer("folded", Normalizer::custom().filter("lowercase").filter("asciifolding"));
+
+    let response = client.index("myindex")
+                         .create()
+                         .analysis(analysis)
+                         .mapping::<MyType>()
+                         .send()?;
+
+    assert!(response.acknowledged());
+    # Ok(())
+    # }
+    ```
+
+    [documents-mod]: ../../../types/document/index.html
+    */
+    pub fn analysis(self, analysis: Analysis) -> IndexCreateRequestBuilder<TSender, Value> {
+        self.into_object_body().analysis(analysis)
+    }
+
+    /**
+    Set the mapping for the index to the mapping of the given document type.
+
+    Any mapping already in the body is replaced.
+    */
+    pub fn mapping<TDocument>(self) -> IndexCreateRequestBuilder<TSender, Value>
+    where
+        TDocument: DocumentType,
+    {
+        self.into_object_body().mapping::<TDocument>()
+    }
+
+    fn into_object_body(self) -> IndexCreateRequestBuilder<TSender, Value> {
+        RequestBuilder::new(
+            self.client,
+            self.params_builder,
+            IndexCreateRequestInner {
+                index: self.inner.index,
+                body: Value::Object(Map::new()),
+            },
+        )
+    }
+}
+
+impl<TSender> IndexCreateRequestBuilder<TSender, Value>
+where
+    TSender: Sender,
+{
+    /**
+    Set the analysis settings for the index.
+
+    Any analysis settings already in the body are replaced.
+    */
+    pub fn analysis(mut self, analysis: Analysis) -> Self {
+        let settings = body_object(&mut self.inner.body)
+            .entry("settings")
+            .or_insert_with(|| Value::Object(Map::new()));
+
+        if !settings.is_object() {
+            *settings = Value::Object(Map::new());
+        }
+
+        if let Value::Object(ref mut settings) = *settings {
+            settings.insert("analysis".to_owned(), analysis.to_value());
+        }
+
+        self
+    }
+
+    /**
+    Set the mapping for the index to the mapping of the given document type.
+
+    Any mapping already in the body is replaced.
+    */
+    pub fn mapping<TDocument>(mut self) -> Self
+    where
+        TDocument: DocumentType,
+    {
+        let mapping = serde_json::to_value(TDocument::index_mapping()).unwrap_or(Value::Null);
+
+        body_object(&mut self.inner.body).insert("mappings".to_owned(), mapping);
+        self
+    }
+}
+
+/**
+Get the create index body as a JSON object.
+
+Any body that isn't already an object is replaced by an empty one.
+*/
+fn body_object(body: &mut Value) -> &mut Map<String, Value> {
+    if !body.is_object() {
+        *body = Value::Object(Map::new());
+    }
+
+    match *body {
+        Value::Object(ref mut body) => body,
+        _ => unreachable!(),
+    }
+}
+
 /**
 # Send synchronously
 */
@@ -231,22 +380,15 @@ where
 
     Create an index called `myindex`:
 
-    ```no_run
-    # extern crate futures;
-    # extern crate tokio;
+    ```edition2018,no_run
     # extern crate elastic;
-    # use futures::Future;
     # use elastic::prelude::*;
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # let client = AsyncClientBuilder::new().build()?;
-    let future = client.index("myindex").create().send();
-
-    future.and_then(|response| {
-        assert!(response.acknowledged());
+    let response = client.index("myindex").create().send().await?;
 
-        Ok(())
-    });
+    assert!(response.acknowledged());
     # Ok(())
     # }
     ```
@@ -259,7 +401,8 @@ where
         let res_future =
             RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
                 .send()
-                .and_then(|res| res.into_response());
+                .compat()
+                .and_then(|res| res.into_response().compat());
 
         Pending::new(res_future)
     }
@@ -267,7 +410,7 @@ where
 
 /** A future returned by calling `send`. */
 pub struct Pending {
-    inner: Box<Future<Item = CommandResponse, Error = Error> + Send>,
+    inner: BoxFuture<CommandResponse>,
 }
 
 impl Pending {
@@ -276,19 +419,12 @@ impl Pending {
         F: Future<Item = CommandResponse, Error = Error> + Send + 'static,
     {
         Pending {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl Future for Pending {
-    type Item = CommandResponse;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(Pending => CommandResponse);
 
 #[cfg(test)]
 mod tests {
@@ -322,4 +458,55 @@ mod tests {
 
         assert_eq!("{}", req.body);
     }
+
+    #[derive(Serialize, ElasticType)]
+    struct TestDoc {
+        #[elastic(fields(folded(analyzer = "folded")))]
+        title: String,
+    }
+
+    #[test]
+    fn specify_analysis_and_mapping() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .index("testindex")
+            .create()
+            .analysis(Analysis::new().analyzer(
+                "folded",
+                Analyzer::custom("standard").filter("asciifolding"),
+            ))
+            .mapping::<TestDoc>()
+            .inner
+            .into_request();
+
+        let expected = json!({
+            "settings": {
+                "analysis": {
+                    "analyzer": {
+                        "folded": {
+                            "type": "custom",
+                            "tokenizer": "standard",
+                            "filter": ["asciifolding"]
+                        }
+                    }
+                }
+            },
+            "mappings": {
+                "properties": {
+                    "title": {
+                        "type": "text",
+                        "fields": {
+                            "folded": {
+                                "type": "text",
+                                "analyzer": "folded"
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(expected, req.body);
+    }
 }
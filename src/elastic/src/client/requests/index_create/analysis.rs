@@ -0,0 +1,324 @@
+/*!
+Builders for [index analysis settings][docs-analysis].
+
+[docs-analysis]: https://www.elastic.co/guide/en/elasticsearch/reference/current/analysis.html
+*/
+
+use serde_json::{
+    Map,
+    Value,
+};
+
+/**
+The analysis settings for an index.
+
+Analysis settings define the custom analyzers, normalizers, tokenizers, token filters and character filters that can be used by fields in the index mapping.
+They're attached to a create index request with [`analysis`][analysis].
+
+# Examples
+
+Define an analyzer that strips html and folds text to lowercase ascii, and a normalizer for `keyword` fields:
+
+```
+# extern crate elastic;
+# use elastic::prelude::*;
+# fn main() {
+let analysis = Analysis::new()
+    .char_filter("strip_html", CharFilter::new("html_strip"))
+    .filter("english_stop", TokenFilter::new("stop").param("stopwords", "_english_"))
+    .analyzer(
+        "english_html",
+        Analyzer::custom("standard")
+            .char_filter("strip_html")
+            .filter("lowercase")
+            .filter("asciifolding")
+            .filter("english_stop"),
+    )
+    .normalizer("lowercase", Normalizer::custom().filter("lowercase"));
+# }
+```
+
+[analysis]: type.IndexCreateRequestBuilder.html#method.analysis
+*/
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Analysis {
+    analyzers: Map<String, Value>,
+    normalizers: Map<String, Value>,
+    tokenizers: Map<String, Value>,
+    filters: Map<String, Value>,
+    char_filters: Map<String, Value>,
+}
+
+impl Analysis {
+    /** Create empty analysis settings. */
+    pub fn new() -> Self {
+        Analysis::default()
+    }
+
+    /** Add a named analyzer, replacing any existing analyzer with the same name. */
+    pub fn analyzer(mut self, name: impl Into<String>, analyzer: Analyzer) -> Self {
+        self.analyzers.insert(name.into(), analyzer.to_value());
+        self
+    }
+
+    /** Add a named normalizer, replacing any existing normalizer with the same name. */
+    pub fn normalizer(mut self, name: impl Into<String>, normalizer: Normalizer) -> Self {
+        self.normalizers.insert(name.into(), normalizer.to_value());
+        self
+    }
+
+    /** Add a named tokenizer, replacing any existing tokenizer with the same name. */
+    pub fn tokenizer(mut self, name: impl Into<String>, tokenizer: Tokenizer) -> Self {
+        self.tokenizers.insert(name.into(), tokenizer.to_value());
+        self
+    }
+
+    /** Add a named token filter, replacing any existing token filter with the same name. */
+    pub fn filter(mut self, name: impl Into<String>, filter: TokenFilter) -> Self {
+        self.filters.insert(name.into(), filter.to_value());
+        self
+    }
+
+    /** Add a named character filter, replacing any existing character filter with the same name. */
+    pub fn char_filter(mut self, name: impl Into<String>, char_filter: CharFilter) -> Self {
+        self.char_filters
+            .insert(name.into(), char_filter.to_value());
+        self
+    }
+
+    pub(crate) fn to_value(&self) -> Value {
+        let mut analysis = Map::new();
+
+        for &(key, components) in &[
+            ("analyzer", &self.analyzers),
+            ("normalizer", &self.normalizers),
+            ("tokenizer", &self.tokenizers),
+            ("filter", &self.filters),
+            ("char_filter", &self.char_filters),
+        ] {
+            if !components.is_empty() {
+                analysis.insert(key.to_owned(), Value::Object(components.clone()));
+            }
+        }
+
+        Value::Object(analysis)
+    }
+}
+
+macro_rules! analysis_component {
+    ($component:ident, $kind:expr) => {
+        impl $component {
+            #[doc = "Create a "]
+            #[doc = $kind]
+            #[doc = " of the given type, like `standard`."]
+            pub fn new(ty: impl Into<String>) -> Self {
+                let mut params = Map::new();
+                params.insert("type".to_owned(), Value::String(ty.into()));
+
+                $component { params }
+            }
+
+            #[doc = "Set a parameter for the "]
+            #[doc = $kind]
+            #[doc = ", replacing any existing value."]
+            pub fn param(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+                self.params.insert(key.into(), value.into());
+                self
+            }
+
+            fn to_value(&self) -> Value {
+                Value::Object(self.params.clone())
+            }
+        }
+    };
+}
+
+/**
+An analyzer converts text into tokens.
+
+A custom analyzer is built from a tokenizer, with optional token filters and character filters.
+Built-in analyzers, like `standard` or `pattern`, can also be configured with parameters.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analyzer {
+    params: Map<String, Value>,
+}
+
+analysis_component!(Analyzer, "analyzer");
+
+impl Analyzer {
+    /** Create a `custom` analyzer that uses the given tokenizer. */
+    pub fn custom(tokenizer: impl Into<String>) -> Self {
+        Analyzer::new("custom").param("tokenizer", tokenizer.into())
+    }
+
+    /** Add a token filter to the end of the analyzer's token filters. */
+    pub fn filter(mut self, filter: impl Into<String>) -> Self {
+        push_param(&mut self.params, "filter", filter.into());
+        self
+    }
+
+    /** Add a character filter to the end of the analyzer's character filters. */
+    pub fn char_filter(mut self, char_filter: impl Into<String>) -> Self {
+        push_param(&mut self.params, "char_filter", char_filter.into());
+        self
+    }
+
+    /** Set the gap between the positions of the values in an array of text. */
+    pub fn position_increment_gap(self, gap: u32) -> Self {
+        self.param("position_increment_gap", gap)
+    }
+}
+
+/**
+A normalizer converts a `keyword` value into a single token.
+
+Normalizers are like analyzers without a tokenizer, so they can only use token filters and character filters that work on a character by character basis.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct Normalizer {
+    params: Map<String, Value>,
+}
+
+analysis_component!(Normalizer, "normalizer");
+
+impl Normalizer {
+    /** Create a `custom` normalizer. */
+    pub fn custom() -> Self {
+        Normalizer::new("custom")
+    }
+
+    /** Add a token filter to the end of the normalizer's token filters. */
+    pub fn filter(mut self, filter: impl Into<String>) -> Self {
+        push_param(&mut self.params, "filter", filter.into());
+        self
+    }
+
+    /** Add a character filter to the end of the normalizer's character filters. */
+    pub fn char_filter(mut self, char_filter: impl Into<String>) -> Self {
+        push_param(&mut self.params, "char_filter", char_filter.into());
+        self
+    }
+}
+
+/** A tokenizer breaks a stream of characters into individual tokens. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tokenizer {
+    params: Map<String, Value>,
+}
+
+analysis_component!(Tokenizer, "tokenizer");
+
+/** A token filter adds, removes or changes the tokens produced by a tokenizer. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenFilter {
+    params: Map<String, Value>,
+}
+
+analysis_component!(TokenFilter, "token filter");
+
+/** A character filter adds, removes or changes characters before they're tokenized. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharFilter {
+    params: Map<String, Value>,
+}
+
+analysis_component!(CharFilter, "character filter");
+
+/** Add a value to the end of an array parameter. */
+fn push_param(params: &mut Map<String, Value>, key: &str, value: String) {
+    let values = params
+        .entry(key.to_owned())
+        .or_insert_with(|| Value::Array(Vec::new()));
+
+    if let Value::Array(ref mut values) = *values {
+        values.push(Value::String(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prelude::*;
+
+    #[test]
+    fn analysis_empty() {
+        assert_eq!(json!({}), Analysis::new().to_value());
+    }
+
+    #[test]
+    fn analysis_components() {
+        let analysis = Analysis::new()
+            .char_filter("strip_html", CharFilter::new("html_strip"))
+            .tokenizer(
+                "trigram",
+                Tokenizer::new("ngram")
+                    .param("min_gram", 3)
+                    .param("max_gram", 3),
+            )
+            .filter(
+                "english_stop",
+                TokenFilter::new("stop").param("stopwords", "_english_"),
+            )
+            .analyzer(
+                "english_html",
+                Analyzer::custom("trigram")
+                    .char_filter("strip_html")
+                    .filter("lowercase")
+                    .filter("english_stop")
+                    .position_increment_gap(100),
+            )
+            .analyzer(
+                "pattern_comma",
+                Analyzer::new("pattern").param("pattern", ","),
+            )
+            .normalizer(
+                "lowercase",
+                Normalizer::custom()
+                    .char_filter("strip_html")
+                    .filter("lowercase"),
+            );
+
+        let expected = json!({
+            "analyzer": {
+                "english_html": {
+                    "type": "custom",
+                    "tokenizer": "trigram",
+                    "char_filter": ["strip_html"],
+                    "filter": ["lowercase", "english_stop"],
+                    "position_increment_gap": 100
+                },
+                "pattern_comma": {
+                    "type": "pattern",
+                    "pattern": ","
+                }
+            },
+            "normalizer": {
+                "lowercase": {
+                    "type": "custom",
+                    "char_filter": ["strip_html"],
+                    "filter": ["lowercase"]
+                }
+            },
+            "tokenizer": {
+                "trigram": {
+                    "type": "ngram",
+                    "min_gram": 3,
+                    "max_gram": 3
+                }
+            },
+            "filter": {
+                "english_stop": {
+                    "type": "stop",
+                    "stopwords": "_english_"
+                }
+            },
+            "char_filter": {
+                "strip_html": {
+                    "type": "html_strip"
+                }
+            }
+        });
+
+        assert_eq!(expected, analysis.to_value());
+    }
+}
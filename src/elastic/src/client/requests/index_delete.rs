@@ -4,10 +4,7 @@ Builders for [delete index requests][docs-delete-index].
 [docs-delete-index]: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-delete-index.html
 */
 
-use futures::{
-    Future,
-    Poll,
-};
+use futures::Future;
 
 use client::{
     requests::{
@@ -25,6 +22,10 @@ use client::{
     IndexClient,
 };
 use error::*;
+use std_future::{
+    box_future,
+    BoxFuture,
+};
 
 /**
 A [delete index request][docs-delete-index] builder that can be configured before sending.
@@ -142,22 +143,15 @@ impl IndexDeleteRequestBuilder<AsyncSender> {
 
     Delete an index called `myindex`:
 
-    ```no_run
-    # extern crate futures;
-    # extern crate tokio;
+    ```edition2018,no_run
     # extern crate elastic;
-    # use futures::Future;
     # use elastic::prelude::*;
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # let client = AsyncClientBuilder::new().build()?;
-    let future = client.index("myindex").delete().send();
+    let response = client.index("myindex").delete().send().await?;
 
-    future.and_then(|response| {
-        assert!(response.acknowledged());
-
-        Ok(())
-    });
+    assert!(response.acknowledged());
     # Ok(())
     # }
     ```
@@ -170,7 +164,8 @@ impl IndexDeleteRequestBuilder<AsyncSender> {
         let res_future =
             RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
                 .send()
-                .and_then(|res| res.into_response());
+                .compat()
+                .and_then(|res| res.into_response().compat());
 
         Pending::new(res_future)
     }
@@ -178,7 +173,7 @@ impl IndexDeleteRequestBuilder<AsyncSender> {
 
 /** A future returned by calling `send`. */
 pub struct Pending {
-    inner: Box<Future<Item = CommandResponse, Error = Error> + Send>,
+    inner: BoxFuture<CommandResponse>,
 }
 
 impl Pending {
@@ -187,19 +182,12 @@ impl Pending {
         F: Future<Item = CommandResponse, Error = Error> + Send + 'static,
     {
         Pending {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl Future for Pending {
-    type Item = CommandResponse;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(Pending => CommandResponse);
 
 #[cfg(test)]
 mod tests {
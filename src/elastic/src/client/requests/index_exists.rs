@@ -4,10 +4,7 @@ Builders for [index exists requests][docs-index-exists].
 [docs-index-exists]: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-exists.html
 */
 
-use futures::{
-    Future,
-    Poll,
-};
+use futures::Future;
 
 use client::{
     requests::{
@@ -25,6 +22,10 @@ use client::{
     IndexClient,
 };
 use error::*;
+use std_future::{
+    box_future,
+    BoxFuture,
+};
 
 /**
 An [index exists request][docs-index-exists] builder that can be configured before sending.
@@ -142,22 +143,15 @@ impl IndexExistsRequestBuilder<AsyncSender> {
 
     Check whether an index called `myindex` exists:
 
-    ```no_run
-    # extern crate futures;
-    # extern crate tokio;
+    ```edition2018,no_run
     # extern crate elastic;
-    # use futures::Future;
     # use elastic::prelude::*;
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # let client = AsyncClientBuilder::new().build()?;
-    let future = client.index("myindex").exists().send();
+    let response = client.index("myindex").exists().send().await?;
 
-    future.and_then(|response| {
-        assert!(response.exists());
-
-        Ok(())
-    });
+    assert!(response.exists());
     # Ok(())
     # }
     ```
@@ -170,7 +164,8 @@ impl IndexExistsRequestBuilder<AsyncSender> {
         let res_future =
             RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
                 .send()
-                .and_then(|res| res.into_response());
+                .compat()
+                .and_then(|res| res.into_response().compat());
 
         Pending::new(res_future)
     }
@@ -178,7 +173,7 @@ impl IndexExistsRequestBuilder<AsyncSender> {
 
 /** A future returned by calling `send`. */
 pub struct Pending {
-    inner: Box<Future<Item = IndicesExistsResponse, Error = Error> + Send>,
+    inner: BoxFuture<IndicesExistsResponse>,
 }
 
 impl Pending {
@@ -187,19 +182,12 @@ impl Pending {
         F: Future<Item = IndicesExistsResponse, Error = Error> + Send + 'static,
     {
         Pending {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl Future for Pending {
-    type Item = IndicesExistsResponse;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(Pending => IndicesExistsResponse);
 
 #[cfg(test)]
 mod tests {
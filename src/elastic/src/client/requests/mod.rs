@@ -4,18 +4,29 @@ Request types for the Elasticsearch REST API.
 This module contains implementation details that are useful if you want to customise the request process, but aren't generally important for sending requests.
 */
 
-use fluent_builder::SharedFluentBuilder;
-use std::sync::Arc;
-use tokio_threadpool::ThreadPool;
+use fluent_builder::{
+    SharedFluentBuilder,
+    TryIntoValue,
+};
+use tokio::runtime::Handle;
 
 use client::{
     sender::{
+        logging::{
+            opaque_id_header,
+            X_OPAQUE_ID,
+        },
         AsyncSender,
+        NodeAddress,
         RequestParams,
         Sender,
     },
     Client,
 };
+use http::header::{
+    HeaderName,
+    HeaderValue,
+};
 
 pub use elastic_requests::{
     empty_body,
@@ -43,17 +54,23 @@ pub mod sql;
 pub use self::sql::SqlRequestBuilder;
 
 // Document requests
+pub mod content_hash;
 pub mod document_delete;
+pub mod document_ensure_index;
 pub mod document_get;
 pub mod document_index;
 pub mod document_put_mapping;
 pub mod document_update;
+pub mod document_verify_mapping;
 pub use self::{
+    content_hash::BulkIndexChangedRequestBuilder,
     document_delete::DeleteRequestBuilder,
+    document_ensure_index::EnsureIndexRequestBuilder,
     document_get::GetRequestBuilder,
     document_index::IndexRequestBuilder,
     document_put_mapping::PutMappingRequestBuilder,
     document_update::UpdateRequestBuilder,
+    document_verify_mapping::VerifyMappingRequestBuilder,
 };
 
 // Index requests
@@ -73,9 +90,20 @@ pub use self::{
 // Misc requests
 pub mod bulk;
 pub mod ping;
+pub mod reindex;
+pub mod snapshot_restore;
+pub mod task_rethrottle;
+pub mod template_bundle;
 pub use self::{
     bulk::BulkRequestBuilder,
     ping::PingRequestBuilder,
+    reindex::ReindexRequestBuilder,
+    snapshot_restore::SnapshotRestoreRequestBuilder,
+    task_rethrottle::RethrottleRequestBuilder,
+    template_bundle::{
+        TemplateExportRequestBuilder,
+        TemplateImportRequestBuilder,
+    },
 };
 
 pub mod common;
@@ -201,6 +229,148 @@ where
 
         self
     }
+
+    /**
+    Set a header for this request.
+
+    The header is applied over any default headers.
+    Headers are applied over the parameters set by `params` or `params_fluent`, so this method should be called after them.
+
+    # Examples
+
+    Send a custom header with a request:
+
+    ```no_run
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # use elastic::http::header::{HeaderName, HeaderValue};
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client
+        .request(PingRequest::new())
+        .header(
+            HeaderName::from_static("x-tenant"),
+            HeaderValue::from_static("tenant123"))
+        .send()?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn header(self, name: HeaderName, value: HeaderValue) -> Self {
+        self.chain_params(move |params| params.header(name, value))
+    }
+
+    /**
+    Set the `X-Opaque-Id` for this request.
+
+    The id takes precedence over an id set on the client, and is used to correlate log records for the request.
+    Control characters aren't valid in a header, so they're removed from the id.
+    Like `header`, this method should be called after `params` or `params_fluent`.
+    See the [`logging`][logging-mod] module for more details.
+
+    # Examples
+
+    Attribute a request to a specific job:
+
+    ```no_run
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client
+        .request(PingRequest::new())
+        .opaque_id("my-service/nightly-report")
+        .send()?;
+    # Ok(())
+    # }
+    ```
+
+    [logging-mod]: ../sender/logging/index.html
+    */
+    pub fn opaque_id(self, id: impl AsRef<str>) -> Self {
+        self.header(
+            HeaderName::from_static(X_OPAQUE_ID),
+            opaque_id_header(id.as_ref()),
+        )
+    }
+
+    /** Apply a method to the request parameters after any others. */
+    fn chain_params(
+        mut self,
+        method: impl FnOnce(RequestParams) -> RequestParams + Send + 'static,
+    ) -> Self {
+        let chained: SharedFluentBuilder<RequestParams> = SharedFluentBuilder::new();
+
+        self.params_builder = match self.params_builder.try_into_value() {
+            TryIntoValue::Value(params) => chained.value(method(params)),
+            TryIntoValue::Builder(builder) => chained
+                .fluent(move |params| method(builder.into_value(move || params)))
+                .shared(),
+        };
+
+        self
+    }
+
+    /**
+    Send this request to a specific node.
+
+    The node is used instead of the one the client would otherwise select, without sniffing or load balancing.
+    Any other parameters, like default headers, are kept.
+    This is useful for follow-up requests to a node that holds some state for a previous request, like a scroll or point-in-time search context.
+    Sending these requests back to the same node avoids an extra hop within the cluster.
+
+    The node takes precedence over a base url set by `params` or `params_fluent`, so this method should be called last.
+
+    Scrolls and point-in-time searches sent with the client's `search` builder don't need this method when the client was built with `node_affinity`.
+    Their follow-up requests are sent to the node that served the initial search automatically.
+
+    # Examples
+
+    Fetch the next page of a scroll from the node that served the first page:
+
+    ```no_run
+    # extern crate elastic;
+    # #[macro_use] extern crate serde_json;
+    # use serde_json::Value;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    let res = client.request(SearchRequest::for_index("myindex", json!({ "size": 100 })))
+                    .params_fluent(|p| p.url_param("scroll", "1m"))
+                    .send()?;
+
+    let node = res.node().clone();
+    let page = res.into_response::<SearchResponse<Value>>()?;
+
+    if let Some(scroll_id) = page.scroll_id() {
+        let next_page = client.request(ScrollRequest::new(json!({ "scroll": "1m", "scroll_id": scroll_id })))
+                              .node(node)
+                              .send()?
+                              .into_response::<SearchResponse<Value>>()?;
+    }
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn node(mut self, address: impl Into<NodeAddress>) -> Self {
+        let address = address.into();
+
+        let params = match self.params_builder.try_into_value() {
+            TryIntoValue::Value(params) => params,
+            TryIntoValue::Builder(builder) => {
+                let params = self.client.addresses.pin(address.clone());
+
+                builder.into_value(move || params)
+            }
+        };
+
+        self.params_builder = SharedFluentBuilder::new().value(params.base_url(address));
+
+        self
+    }
 }
 
 /**
@@ -210,26 +380,24 @@ The following methods can be called on any asynchronous request builder.
 */
 impl<TRequest> RequestBuilder<AsyncSender, TRequest> {
     /**
-    Override the thread pool used for deserialisation for this request.
+    Override the runtime whose blocking thread pool is used for deserialisation for this request.
 
     # Examples
 
-    Use the given thread pool to deserialise the response:
+    Use the given runtime's blocking pool to deserialise the response:
 
     ```no_run
     # extern crate tokio;
-    # extern crate tokio_threadpool;
     # extern crate elastic;
-    # use std::sync::Arc;
-    # use tokio_threadpool::ThreadPool;
+    # use tokio::runtime::Runtime;
     # use elastic::prelude::*;
     # fn main() { run().unwrap() }
     # fn run() -> Result<(), Box<::std::error::Error>> {
     # let client = AsyncClientBuilder::new().build()?;
     # fn get_req() -> PingRequest<'static> { PingRequest::new() }
-    let pool = ThreadPool::new();
+    let runtime = Runtime::new()?;
     let builder = client.request(get_req())
-                        .serde_pool(Arc::new(pool));
+                        .serde_pool(runtime.handle().clone());
     # Ok(())
     # }
     ```
@@ -238,9 +406,7 @@ impl<TRequest> RequestBuilder<AsyncSender, TRequest> {
 
     ```no_run
     # extern crate tokio;
-    # extern crate tokio_threadpool;
     # extern crate elastic;
-    # use tokio_threadpool::ThreadPool;
     # use elastic::prelude::*;
     # fn main() { run().unwrap() }
     # fn run() -> Result<(), Box<::std::error::Error>> {
@@ -252,7 +418,7 @@ impl<TRequest> RequestBuilder<AsyncSender, TRequest> {
     # }
     ```
     */
-    pub fn serde_pool(mut self, pool: impl Into<Option<Arc<ThreadPool>>>) -> Self {
+    pub fn serde_pool(mut self, pool: impl Into<Option<Handle>>) -> Self {
         self.client.sender.serde_pool = pool.into();
 
         self
@@ -271,12 +437,77 @@ pub mod prelude {
         bulk,
         bulk_raw,
         BulkOperation,
+        BulkRetry,
+    };
+
+    pub use super::common::{
+        NoneFields,
+        ScriptBuilder,
+    };
+
+    pub use super::content_hash::ContentHash;
+
+    pub use super::document_verify_mapping::{
+        MappingDifference,
+        MappingDifferenceKind,
+    };
+
+    pub use super::index_create::{
+        Analysis,
+        Analyzer,
+        CharFilter,
+        Normalizer,
+        TokenFilter,
+        Tokenizer,
+    };
+
+    pub use super::reindex::Slices;
+
+    pub use super::search::{
+        escape_query_string,
+        BoostMode,
+        Collapse,
+        DocValueField,
+        FieldValueModifier,
+        FunctionScore,
+        GeoDistanceType,
+        InnerHitsOptions,
+        Knn,
+        MultiValueMode,
+        Operator,
+        RescoreMode,
+        RescoreQuery,
+        RuntimeField,
+        RuntimeFieldType,
+        ScoreFunction,
+        ScoreMode,
+        ScriptSortType,
+        SimpleQueryString,
+        SimpleQueryStringFlag,
+        Sort,
+        SortMissing,
+        SortMode,
+        SortOrder,
+        TrackTotalHits,
+    };
+
+    pub use super::task_rethrottle::{
+        RequestsPerSecond,
+        ThrottledOperation,
+    };
+
+    pub use super::template_bundle::{
+        ConflictPolicy,
+        TemplateBundle,
+        TemplateKind,
     };
 
     pub use super::{
         empty_body,
+        BulkIndexChangedRequestBuilder,
         DefaultBody,
         DeleteRequestBuilder,
+        EnsureIndexRequestBuilder,
         GetRequestBuilder,
         IndexCloseRequestBuilder,
         IndexCreateRequestBuilder,
@@ -286,8 +517,14 @@ pub mod prelude {
         PingRequestBuilder,
         PutMappingRequestBuilder,
         RawRequestBuilder,
+        ReindexRequestBuilder,
+        RethrottleRequestBuilder,
         SearchRequestBuilder,
+        SnapshotRestoreRequestBuilder,
         SqlRequestBuilder,
+        TemplateExportRequestBuilder,
+        TemplateImportRequestBuilder,
         UpdateRequestBuilder,
+        VerifyMappingRequestBuilder,
     };
 }
@@ -2,10 +2,7 @@
 Builders for ping requests.
 */
 
-use futures::{
-    Future,
-    Poll,
-};
+use futures::Future;
 
 use client::{
     requests::{
@@ -25,6 +22,10 @@ use error::{
     Error,
     Result,
 };
+use std_future::{
+    box_future,
+    BoxFuture,
+};
 
 /**
 A ping request builder that can be configured before sending.
@@ -150,27 +151,20 @@ impl PingRequestBuilder<AsyncSender> {
 
     Ping an Elasticsearch node:
 
-    ```no_run
-    # extern crate tokio;
-    # extern crate futures;
+    ```edition2018,no_run
     # extern crate serde;
     # #[macro_use] extern crate serde_derive;
     # #[macro_use] extern crate elastic_derive;
     # extern crate elastic;
-    # use futures::Future;
     # use elastic::prelude::*;
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # #[derive(Debug, Serialize, Deserialize, ElasticType)]
     # struct MyType { }
     # let client = AsyncClientBuilder::new().build()?;
-    let future = client.ping().send();
+    let response = client.ping().send().await?;
 
-    future.and_then(|response| {
-        println!("node: {}", response.name());
-
-        Ok(())
-    });
+    println!("node: {}", response.name());
     # Ok(())
     # }
     ```
@@ -183,7 +177,8 @@ impl PingRequestBuilder<AsyncSender> {
         let res_future =
             RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
                 .send()
-                .and_then(|res| res.into_response());
+                .compat()
+                .and_then(|res| res.into_response().compat());
 
         Pending::new(res_future)
     }
@@ -191,7 +186,7 @@ impl PingRequestBuilder<AsyncSender> {
 
 /** A future returned by calling `send`. */
 pub struct Pending {
-    inner: Box<Future<Item = PingResponse, Error = Error> + Send>,
+    inner: BoxFuture<PingResponse>,
 }
 
 impl Pending {
@@ -200,19 +195,12 @@ impl Pending {
         F: Future<Item = PingResponse, Error = Error> + Send + 'static,
     {
         Pending {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl Future for Pending {
-    type Item = PingResponse;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(Pending => PingResponse);
 
 #[cfg(test)]
 mod tests {
@@ -3,7 +3,10 @@ Builders for raw requests.
 */
 
 use fluent_builder::TryIntoValue;
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    sync::Arc,
+};
 
 use client::{
     requests::{
@@ -83,6 +86,30 @@ where
     # }
     ```
 
+    Stream a bulk payload without holding it in memory:
+
+    ```edition2018,no_run
+    # use bytes::Bytes;
+    # use futures::stream;
+    # use elastic::prelude::*;
+    # use elastic::http::AsyncBody;
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
+    # let client = AsyncClientBuilder::new().build()?;
+    # let chunks: Vec<Result<Bytes, ::std::io::Error>> = vec![];
+    // Chunks are sent as they're produced by the stream
+    let body = AsyncBody::from_stream(stream::iter_result(chunks));
+
+    let response = client
+        .request(BulkRequest::for_index("myindex", body))
+        .send()
+        .await?
+        .into_response::<BulkResponse>()
+        .await?;
+    # Ok(())
+    # }
+    ```
+
     [Endpoint]: requests/struct.Endpoint.html
     [RawRequestBuilder]: requests/raw/type.RawRequestBuilder.html
     [endpoints-mod]: requests/endpoints/index.html
@@ -104,7 +131,7 @@ where
     TSender: Sender,
     TEndpoint: Into<Endpoint<'static, TBody>>,
     TBody: Into<<TSender>::Body> + Send + 'static,
-    NodeAddresses<TSender>: NextParams,
+    NodeAddresses<TSender>: NextParams + Send + Sync + 'static,
     <NodeAddresses<TSender> as NextParams>::Params: Into<TSender::Params> + Send + 'static,
 {
     /**
@@ -142,29 +169,22 @@ where
 
     Send a raw request asynchronously and parse it to a concrete response type:
 
-    ```no_run
-    # extern crate tokio;
-    # extern crate futures;
-    # extern crate elastic;
-    # extern crate serde_json;
+    ```edition2018,no_run
     # use serde_json::Value;
     # use elastic::prelude::*;
-    # use futures::Future;
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # let client = AsyncClientBuilder::new().build()?;
-    let future = client.request(SimpleSearchRequest::for_index_ty("myindex", "mytype"))
-                       .send()
-                       .and_then(|res| res.into_response::<SearchResponse<Value>>());
-
-    future.and_then(|response| {
-        // Iterate through the hits (of type `MyType`)
-        for hit in response.hits() {
-            println!("{:?}", hit);
-        }
+    let response = client.request(SimpleSearchRequest::for_index_ty("myindex", "mytype"))
+                         .send()
+                         .await?
+                         .into_response::<SearchResponse<Value>>()
+                         .await?;
 
-        Ok(())
-    });
+    // Iterate through the hits (of type `MyType`)
+    for hit in response.hits() {
+        println!("{:?}", hit);
+    }
     # Ok(())
     # }
     ```
@@ -181,10 +201,17 @@ where
         // Only try fetch a next address if an explicit `RequestParams` hasn't been given
         let params = match self.params_builder.try_into_value() {
             TryIntoValue::Value(value) => SendableRequestParams::Value(value),
-            TryIntoValue::Builder(builder) => SendableRequestParams::Builder {
-                params: client.addresses.next(),
-                builder,
-            },
+            TryIntoValue::Builder(builder) => {
+                let next_addresses = client.addresses.clone();
+                let pin_addresses = client.addresses.clone();
+
+                SendableRequestParams::Builder {
+                    params: client.addresses.next(),
+                    builder,
+                    next: Arc::new(move || next_addresses.next()),
+                    pin: Arc::new(move |node| pin_addresses.pin(node)),
+                }
+            }
         };
 
         let req = SendableRequest::new(endpoint, params);
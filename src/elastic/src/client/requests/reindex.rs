@@ -0,0 +1,758 @@
+/*!
+Builders for [reindex requests][docs-reindex].
+
+[docs-reindex]: https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-reindex.html
+*/
+
+use std::{
+    fmt,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use futures::{
+    Async,
+    Future,
+    IntoFuture,
+    Poll,
+    Stream,
+};
+use serde_json;
+use runtime::Delay;
+
+use client::{
+    requests::{
+        empty_body,
+        endpoints::{
+            ReindexRequest,
+            TasksGetRequest,
+            TasksListRequest,
+        },
+        raw::RawRequestInner,
+        DefaultBody,
+        RequestBuilder,
+    },
+    responses::{
+        ReindexResponse,
+        ReindexStatus,
+        TaskResponse,
+        TaskStartedResponse,
+        TasksResponse,
+    },
+    sender::{
+        AsyncSender,
+        RequestParams,
+        Sender,
+        SyncSender,
+    },
+    Client,
+};
+use error::{
+    self,
+    Error,
+    Result,
+};
+use std_future::{
+    box_future,
+    BoxFuture,
+};
+
+/**
+A [reindex request][docs-reindex] builder that can be configured before sending.
+
+Call [`Client.reindex`][Client.reindex] to get a `ReindexRequestBuilder`.
+The `send` method will either send the request [synchronously][send-sync] or [asynchronously][send-async], depending on the `Client` it was created from.
+
+[docs-reindex]: https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-reindex.html
+[send-sync]: #send-synchronously
+[send-async]: #send-asynchronously
+[Client.reindex]: ../../struct.Client.html#reindex-request
+*/
+pub type ReindexRequestBuilder<TSender, TBody> = RequestBuilder<TSender, ReindexRequestInner<TBody>>;
+
+#[doc(hidden)]
+pub struct ReindexRequestInner<TBody> {
+    body: TBody,
+    slices: Option<Slices>,
+}
+
+/**
+The number of slices to split a reindex into.
+
+Each slice is reindexed in parallel by its own sub-task.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Slices {
+    /** Let Elasticsearch pick a number of slices based on the shards in the source index. */
+    Auto,
+    /** Use a specific number of slices. */
+    Count(u32),
+}
+
+impl fmt::Display for Slices {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Slices::Auto => f.write_str("auto"),
+            Slices::Count(count) => write!(f, "{}", count),
+        }
+    }
+}
+
+/**
+# Reindex request
+*/
+impl<TSender> Client<TSender>
+where
+    TSender: Sender,
+{
+    /**
+    Create a [`ReindexRequestBuilder`][ReindexRequestBuilder] with this `Client` that can be configured before sending.
+
+    For more details, see:
+
+    - [builder methods][builder-methods]
+    - [send synchronously][send-sync]
+    - [send asynchronously][send-async]
+
+    # Examples
+
+    Copy all documents from one index to another:
+
+    ```no_run
+    # extern crate elastic;
+    # #[macro_use] extern crate serde_json;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.reindex()
+                         .body(json!({
+                             "source": { "index": "old_index" },
+                             "dest": { "index": "new_index" }
+                         }))
+                         .slices(Slices::Auto)
+                         .send()?;
+
+    println!("created {} documents", response.status().created());
+    # Ok(())
+    # }
+    ```
+
+    [ReindexRequestBuilder]: requests/reindex/type.ReindexRequestBuilder.html
+    [builder-methods]: requests/reindex/type.ReindexRequestBuilder.html#builder-methods
+    [send-sync]: requests/reindex/type.ReindexRequestBuilder.html#send-synchronously
+    [send-async]: requests/reindex/type.ReindexRequestBuilder.html#send-asynchronously
+    */
+    pub fn reindex(&self) -> ReindexRequestBuilder<TSender, DefaultBody> {
+        RequestBuilder::initial(self.clone(), ReindexRequestInner::new(empty_body()))
+    }
+}
+
+impl<TBody> ReindexRequestInner<TBody> {
+    fn new(body: TBody) -> Self {
+        ReindexRequestInner { body, slices: None }
+    }
+
+    fn into_request(self) -> (ReindexRequest<'static, TBody>, Option<Slices>) {
+        (ReindexRequest::new(self.body), self.slices)
+    }
+}
+
+/**
+# Builder methods
+
+Configures a `ReindexRequestBuilder` before sending it.
+*/
+impl<TSender, TBody> ReindexRequestBuilder<TSender, TBody>
+where
+    TSender: Sender,
+{
+    /**
+    Set the body for the reindex request.
+
+    The body specifies the `source` to read documents from and the `dest` to write them to.
+    */
+    pub fn body<TNewBody>(self, body: TNewBody) -> ReindexRequestBuilder<TSender, TNewBody>
+    where
+        TNewBody: Into<TSender::Body>,
+    {
+        RequestBuilder::new(
+            self.client,
+            self.params_builder,
+            ReindexRequestInner {
+                body,
+                slices: self.inner.slices,
+            },
+        )
+    }
+
+    /**
+    Split the reindex into slices that run in parallel.
+
+    By default a reindex isn't sliced.
+    */
+    pub fn slices(mut self, slices: Slices) -> Self {
+        self.inner.slices = Some(slices);
+        self
+    }
+}
+
+/**
+# Send synchronously
+*/
+impl<TBody> ReindexRequestBuilder<SyncSender, TBody>
+where
+    TBody: Into<<SyncSender as Sender>::Body> + Send + 'static,
+{
+    /**
+    Send a `ReindexRequestBuilder` synchronously using a [`SyncClient`][SyncClient].
+
+    This will block the current thread until the reindex has completed and the response is deserialised.
+
+    [SyncClient]: ../../type.SyncClient.html
+    */
+    pub fn send(self) -> Result<ReindexResponse> {
+        let (req, slices) = self.inner.into_request();
+
+        RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
+            .params_fluent(move |params| slices_params(params, slices))
+            .send()?
+            .into_response()
+    }
+}
+
+/**
+# Send asynchronously
+*/
+impl<TBody> ReindexRequestBuilder<AsyncSender, TBody>
+where
+    TBody: Into<<AsyncSender as Sender>::Body> + Send + 'static,
+{
+    /**
+    Send a `ReindexRequestBuilder` asynchronously using an [`AsyncClient`][AsyncClient].
+
+    This will return a future that will resolve to the deserialised reindex response once the reindex has completed.
+
+    [AsyncClient]: ../../type.AsyncClient.html
+    */
+    pub fn send(self) -> Pending {
+        let (req, slices) = self.inner.into_request();
+
+        let res_future =
+            RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
+                .params_fluent(move |params| slices_params(params, slices))
+                .send()
+                .compat()
+                .and_then(|res| res.into_response().compat());
+
+        Pending::new(res_future)
+    }
+
+    /**
+    Start the reindex as a background task and stream its progress.
+
+    The reindex is sent with `wait_for_completion=false` and the [tasks api][docs-tasks] is polled every `interval` for its status.
+    When the reindex is sliced, the status of each sub-task is combined into a single progress report.
+    The stream ends after the progress of the completed reindex has been emitted.
+
+    Dropping the stream doesn't cancel the reindex.
+
+    # Examples
+
+    Report the progress of a large reindex:
+
+    ```no_run
+    # extern crate tokio;
+    # extern crate futures;
+    # #[macro_use] extern crate serde_json;
+    # extern crate elastic;
+    # use std::time::Duration;
+    # use futures::Stream;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = AsyncClientBuilder::new().build()?;
+    let progress = client.reindex()
+                         .body(json!({
+                             "source": { "index": "old_index" },
+                             "dest": { "index": "new_index" }
+                         }))
+                         .slices(Slices::Auto)
+                         .progress(Duration::from_secs(5));
+
+    let report = progress.for_each(|progress| {
+        println!(
+            "{}/{} documents processed, {} failed",
+            progress.processed(),
+            progress.total(),
+            progress.failed()
+        );
+
+        Ok(())
+    });
+    # Ok(())
+    # }
+    ```
+
+    [docs-tasks]: https://www.elastic.co/guide/en/elasticsearch/reference/current/tasks.html
+    */
+    pub fn progress(self, interval: Duration) -> ReindexProgressStream {
+        let (req, slices) = self.inner.into_request();
+        let client = self.client;
+
+        let started = {
+            let client = client.clone();
+
+            client
+                .addresses
+                .resolve(self.params_builder)
+                .compat()
+                .and_then(move |params| {
+                    let reindex_params = slices_params(params.clone(), slices)
+                        .url_param("wait_for_completion", false);
+
+                    client
+                        .request(req)
+                        .params(reindex_params)
+                        .send()
+                        .compat()
+                        .and_then(|res| res.into_response::<TaskStartedResponse>().compat())
+                        .map(move |res| (params, res.task_id().to_owned()))
+                })
+        };
+
+        ReindexProgressStream {
+            client,
+            interval,
+            params: None,
+            task_id: None,
+            state: ProgressState::Starting(Box::new(started)),
+        }
+    }
+}
+
+fn slices_params(params: RequestParams, slices: Option<Slices>) -> RequestParams {
+    match slices {
+        Some(slices) => params.url_param("slices", slices),
+        None => params,
+    }
+}
+
+/** A future returned by calling `send`. */
+pub struct Pending {
+    inner: BoxFuture<ReindexResponse>,
+}
+
+impl Pending {
+    fn new<F>(fut: F) -> Self
+    where
+        F: Future<Item = ReindexResponse, Error = Error> + Send + 'static,
+    {
+        Pending {
+            inner: box_future(fut),
+        }
+    }
+}
+
+pending_future!(Pending => ReindexResponse);
+
+/**
+The combined progress of a reindex task and its slices.
+
+Call [`progress`][progress] on an asynchronous reindex request builder to get a stream of `ReindexProgress`.
+
+[progress]: type.ReindexRequestBuilder.html#method.progress
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReindexProgress {
+    task_id: String,
+    slices: usize,
+    status: ReindexStatus,
+    failed: usize,
+    completed: bool,
+}
+
+impl ReindexProgress {
+    /** The id of the reindex task. */
+    pub fn task_id(&self) -> &str {
+        &self.task_id
+    }
+
+    /**
+    The number of slices currently running.
+
+    This is `0` for a reindex that isn't sliced, or once the reindex has completed.
+    */
+    pub fn slices(&self) -> usize {
+        self.slices
+    }
+
+    /** The number of documents the reindex will process. */
+    pub fn total(&self) -> u64 {
+        self.status.total()
+    }
+
+    /** The number of documents created in the destination index so far. */
+    pub fn created(&self) -> u64 {
+        self.status.created()
+    }
+
+    /** The number of documents updated in the destination index so far. */
+    pub fn updated(&self) -> u64 {
+        self.status.updated()
+    }
+
+    /** The number of documents that hit a version conflict so far. */
+    pub fn version_conflicts(&self) -> u64 {
+        self.status.version_conflicts()
+    }
+
+    /**
+    The number of documents processed so far.
+
+    This includes documents that were created, updated, deleted, ignored or hit a version conflict.
+    */
+    pub fn processed(&self) -> u64 {
+        self.status.created()
+            + self.status.updated()
+            + self.status.deleted()
+            + self.status.noops()
+            + self.status.version_conflicts()
+    }
+
+    /**
+    The number of failures that aborted the reindex.
+
+    Elasticsearch only reports failures once the reindex has completed, so this is `0` while it's running.
+    */
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+
+    /** Whether or not the reindex has completed. */
+    pub fn completed(&self) -> bool {
+        self.completed
+    }
+
+    /** The combined status of the reindex task. */
+    pub fn status(&self) -> &ReindexStatus {
+        &self.status
+    }
+}
+
+/**
+A stream of progress reports for a reindex task.
+
+The task is started when the stream is first polled.
+*/
+pub struct ReindexProgressStream {
+    client: Client<AsyncSender>,
+    interval: Duration,
+    params: Option<RequestParams>,
+    task_id: Option<String>,
+    state: ProgressState,
+}
+
+enum ProgressState {
+    Starting(Box<Future<Item = (RequestParams, String), Error = Error> + Send>),
+    Polling(Box<Future<Item = ReindexProgress, Error = Error> + Send>),
+    Waiting(Delay),
+    Done,
+}
+
+impl ReindexProgressStream {
+    /** Fetch the status of the reindex task along with any slices. */
+    fn poll_task(&self) -> Box<Future<Item = ReindexProgress, Error = Error> + Send> {
+        let client = self.client.clone();
+        let params = self.params.clone().unwrap_or_default();
+        let task_id = self.task_id.clone().unwrap_or_default();
+
+        let task = client
+            .request(TasksGetRequest::for_task_id(task_id.clone()))
+            .params(params.clone())
+            .send()
+            .compat()
+            .and_then(|res| res.into_response::<TaskResponse>().compat());
+
+        Box::new(task.and_then(move |task| -> Box<Future<Item = _, Error = _> + Send> {
+            if task.completed() {
+                return Box::new(completed_progress(task_id, &task).into_future());
+            }
+
+            let slices = client
+                .request(TasksListRequest::new())
+                .params(
+                    params
+                        .url_param("parent_task_id", task_id.clone())
+                        .url_param("detailed", true),
+                )
+                .send()
+                .compat()
+                .and_then(|res| res.into_response::<TasksResponse>().compat())
+                .and_then(move |slices| running_progress(task_id, &task, &slices));
+
+            Box::new(slices)
+        }))
+    }
+}
+
+impl Stream for ReindexProgressStream {
+    type Item = ReindexProgress;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            let next = match self.state {
+                ProgressState::Starting(ref mut started) => {
+                    let (params, task_id) = try_ready!(started.poll());
+
+                    debug!("Elasticsearch Reindex: started task {}", task_id);
+
+                    self.params = Some(params);
+                    self.task_id = Some(task_id);
+
+                    self.poll_task()
+                }
+                ProgressState::Polling(ref mut progress) => {
+                    let progress = try_ready!(progress.poll());
+
+                    self.state = if progress.completed() {
+                        ProgressState::Done
+                    } else {
+                        ProgressState::Waiting(Delay::new(Instant::now() + self.interval))
+                    };
+
+                    return Ok(Async::Ready(Some(progress)));
+                }
+                ProgressState::Waiting(ref mut delay) => {
+                    try_ready!(delay.poll().map_err(error::request));
+
+                    self.poll_task()
+                }
+                ProgressState::Done => return Ok(Async::Ready(None)),
+            };
+
+            self.state = ProgressState::Polling(next);
+        }
+    }
+}
+
+fn completed_progress(task_id: String, task: &TaskResponse) -> Result<ReindexProgress> {
+    if let Some(err) = task.error() {
+        return Err(error::request(error::message(format!(
+            "the reindex task {} failed: {}",
+            task_id, err
+        ))));
+    }
+
+    let response = task.response().cloned().ok_or_else(|| {
+        error::request(error::message(format!(
+            "the completed reindex task {} has no response",
+            task_id
+        )))
+    })?;
+
+    let response: ReindexResponse = serde_json::from_value(response).map_err(error::request)?;
+
+    Ok(ReindexProgress {
+        task_id,
+        slices: 0,
+        status: response.status().clone(),
+        failed: response.failures().len(),
+        completed: true,
+    })
+}
+
+fn running_progress(
+    task_id: String,
+    task: &TaskResponse,
+    slices: &TasksResponse,
+) -> Result<ReindexProgress> {
+    let slice_statuses = slices
+        .tasks()
+        .filter_map(|slice| slice.status())
+        .map(|status| serde_json::from_value::<ReindexStatus>(status.clone()))
+        .collect::<::std::result::Result<Vec<_>, _>>()
+        .map_err(error::request)?;
+
+    let (slices, status) = if slice_statuses.is_empty() {
+        let status = match task.task().status() {
+            Some(status) => {
+                serde_json::from_value::<ReindexStatus>(status.clone()).map_err(error::request)?
+            }
+            None => ReindexStatus::default(),
+        };
+
+        (0, status)
+    } else {
+        (slice_statuses.len(), slice_statuses.into_iter().sum())
+    };
+
+    Ok(ReindexProgress {
+        task_id,
+        slices,
+        status,
+        failed: 0,
+        completed: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{
+        self,
+        Value,
+    };
+
+    use super::*;
+    use prelude::*;
+    use tests::*;
+
+    #[test]
+    fn is_send() {
+        assert_send::<super::Pending>();
+        assert_send::<super::ReindexProgressStream>();
+    }
+
+    #[test]
+    fn default_request() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let (req, slices) = client.reindex().inner.into_request();
+
+        assert_eq!("/_reindex", req.url.as_ref());
+        assert_eq!(None, slices);
+    }
+
+    #[test]
+    fn specify_slices() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let (_, slices) = client
+            .reindex()
+            .slices(Slices::Count(4))
+            .body("{}")
+            .inner
+            .into_request();
+
+        assert_eq!(Some(Slices::Count(4)), slices);
+    }
+
+    #[test]
+    fn slices_url_param() {
+        let params = slices_params(RequestParams::default(), Some(Slices::Auto));
+
+        assert_eq!(Some("?slices=auto".to_owned()), params.get_url_qry().1);
+    }
+
+    fn task(value: Value) -> TaskResponse {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn combine_slice_progress() {
+        let parent = task(json!({
+            "completed": false,
+            "task": {
+                "node": "n",
+                "id": 1,
+                "action": "indices:data/write/reindex",
+                "status": { "total": 10 }
+            }
+        }));
+
+        let slices: TasksResponse = serde_json::from_value(json!({
+            "nodes": {
+                "n": {
+                    "tasks": {
+                        "n:2": {
+                            "node": "n",
+                            "id": 2,
+                            "action": "indices:data/write/reindex",
+                            "parent_task_id": "n:1",
+                            "status": { "slice_id": 0, "total": 5, "created": 2 }
+                        },
+                        "n:3": {
+                            "node": "n",
+                            "id": 3,
+                            "action": "indices:data/write/reindex",
+                            "parent_task_id": "n:1",
+                            "status": { "slice_id": 1, "total": 5, "created": 3, "version_conflicts": 1 }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let progress = running_progress("n:1".to_owned(), &parent, &slices).unwrap();
+
+        assert_eq!(2, progress.slices());
+        assert_eq!(10, progress.total());
+        assert_eq!(5, progress.created());
+        assert_eq!(6, progress.processed());
+        assert!(!progress.completed());
+    }
+
+    #[test]
+    fn unsliced_progress() {
+        let parent = task(json!({
+            "completed": false,
+            "task": {
+                "node": "n",
+                "id": 1,
+                "action": "indices:data/write/reindex",
+                "status": { "total": 10, "updated": 4 }
+            }
+        }));
+
+        let slices: TasksResponse = serde_json::from_value(json!({ "nodes": {} })).unwrap();
+
+        let progress = running_progress("n:1".to_owned(), &parent, &slices).unwrap();
+
+        assert_eq!(0, progress.slices());
+        assert_eq!(4, progress.processed());
+    }
+
+    #[test]
+    fn completed_progress_counts_failures() {
+        let parent = task(json!({
+            "completed": true,
+            "task": {
+                "node": "n",
+                "id": 1,
+                "action": "indices:data/write/reindex"
+            },
+            "response": {
+                "took": 10,
+                "timed_out": false,
+                "total": 3,
+                "created": 2,
+                "failures": [{ "id": "3", "status": 400 }]
+            }
+        }));
+
+        let progress = completed_progress("n:1".to_owned(), &parent).unwrap();
+
+        assert!(progress.completed());
+        assert_eq!(3, progress.total());
+        assert_eq!(1, progress.failed());
+    }
+
+    #[test]
+    fn completed_progress_with_error() {
+        let parent = task(json!({
+            "completed": true,
+            "task": {
+                "node": "n",
+                "id": 1,
+                "action": "indices:data/write/reindex"
+            },
+            "error": { "type": "index_not_found_exception" }
+        }));
+
+        assert!(completed_progress("n:1".to_owned(), &parent).is_err());
+    }
+}
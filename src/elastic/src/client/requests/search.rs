@@ -4,15 +4,22 @@ Builders for [search requests][docs-search].
 [docs-search]: https://www.elastic.co/guide/en/elasticsearch/reference/current/search-search.html
 */
 
-use futures::{
-    Future,
-    Poll,
-};
+use futures::Future;
 use serde::de::DeserializeOwned;
+use serde_json::{
+    self,
+    Map,
+    Value,
+};
 use std::marker::PhantomData;
 
 use client::{
     requests::{
+        common::{
+            source_disabled_params,
+            DefaultParams,
+            ScriptBuilder,
+        },
         empty_body,
         endpoints::SearchRequest,
         params::{
@@ -36,6 +43,10 @@ use error::{
     Error,
     Result,
 };
+use std_future::{
+    box_future,
+    BoxFuture,
+};
 use types::document::DocumentType;
 
 /**
@@ -52,6 +63,30 @@ The `send` method will either send the request [synchronously][send-sync] or [as
 pub type SearchRequestBuilder<TSender, TDocument, TBody> =
     RequestBuilder<TSender, SearchRequestInner<TDocument, TBody>>;
 
+mod collapse;
+mod docvalue_fields;
+mod function_score;
+mod knn;
+mod query_string;
+mod rescore;
+mod runtime_mappings;
+mod scroll;
+mod sort;
+mod total_hits;
+
+pub use self::{
+    collapse::*,
+    docvalue_fields::*,
+    function_score::*,
+    knn::*,
+    query_string::*,
+    rescore::*,
+    runtime_mappings::*,
+    scroll::*,
+    sort::*,
+    total_hits::*,
+};
+
 #[doc(hidden)]
 pub struct SearchRequestInner<TDocument, TBody> {
     index: Option<Index<'static>>,
@@ -204,6 +239,8 @@ where
     # }
     ```
 
+    If the `_source` is disabled in the mapping for `TDocument` then documents are read from their stored fields and doc values instead.
+
     For more details on document types and mapping, see the [`types`][types-mod] module.
 
     [SearchRequestBuilder]: requests/search/type.SearchRequestBuilder.html
@@ -221,7 +258,7 @@ where
         let index = TDocument::partial_static_index().map(|idx| Index::from(idx));
         let ty = TDocument::partial_static_ty().map(|ty| Type::from(ty));
 
-        RequestBuilder::initial(
+        let builder = RequestBuilder::initial(
             self.inner,
             SearchRequestInner {
                 index: index,
@@ -229,7 +266,13 @@ where
                 body: empty_body(),
                 _marker: PhantomData,
             },
-        )
+        );
+
+        if TDocument::source_enabled() {
+            builder
+        } else {
+            builder.params_fluent(source_disabled_params::<TDocument>(true))
+        }
     }
 }
 
@@ -307,25 +350,39 @@ where
 }
 
 /**
-# Send synchronously
+# Search body
+
+These methods set options in the search body:
+
+- `query` sets the `query`
+- `sort` adds a clause to the `sort` array
+- `script_field` adds a field to the `script_fields` object
+- `collapse` sets the `collapse` options
+- `knn` adds a search to the `knn` array
+- `post_filter` sets the `post_filter`
+- `rescore` adds a query to the `rescore` array
+- `runtime_mapping` adds a field to the `runtime_mappings` object
+- `fields` adds fields to the `fields` array
+- `docvalue_fields` and `stored_fields` add fields to the arrays with the same name
+- `track_total_hits`, `min_score` and `terminate_after` set the options with the same name
+
+The body needs to be a JSON object, so call these methods after setting a body.
 */
-impl<TDocument, TBody> SearchRequestBuilder<SyncSender, TDocument, TBody>
+impl<TSender, TDocument> SearchRequestBuilder<TSender, TDocument, DefaultBody>
 where
-    TDocument: DeserializeOwned,
-    TBody: Into<<SyncSender as Sender>::Body> + Send + 'static,
+    TSender: Sender,
 {
     /**
-    Send a `SearchRequestBuilder` synchronously using a [`SyncClient`][SyncClient].
-
-    This will block the current thread until a response arrives and is deserialised.
+    Set the query for the search request.
 
     # Examples
 
-    Run a simple [Query String][docs-querystring] query for a [`DocumentType`][documents-mod] called `MyType`:
+    Search for a [`DocumentType`][documents-mod] called `MyType`, boosting hits by their number of likes:
 
     ```no_run
     # extern crate serde;
     # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate serde_json;
     # #[macro_use] extern crate elastic_derive;
     # extern crate elastic;
     # use elastic::prelude::*;
@@ -336,172 +393,1215 @@ where
     # let client = SyncClientBuilder::new().build()?;
     let response = client.search::<MyType>()
                          .index("myindex")
+                         .query(
+                             FunctionScore::new(json!({ "match": { "title": "rust" } }))
+                                 .function(ScoreFunction::field_value_factor("likes").modifier(FieldValueModifier::Log1p))
+                         )
                          .send()?;
+    # Ok(())
+    # }
+    ```
 
-    // Iterate through the hits (of type `MyType`)
-    for hit in response.hits() {
-        println!("{:?}", hit);
+    [documents-mod]: ../../../types/document/index.html
+    */
+    pub fn query(self, query: impl Into<Value>) -> SearchRequestBuilder<TSender, TDocument, Value> {
+        self.into_object_body().query(query)
     }
+
+    /**
+    Add a sort clause to the search request.
+
+    # Examples
+
+    Sort hits for a [`DocumentType`][documents-mod] called `MyType` by a timestamp, then by their distance from a point:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Debug, Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.search::<MyType>()
+                         .index("myindex")
+                         .sort(Sort::field("timestamp").desc().missing(SortMissing::Last))
+                         .sort(Sort::geo_distance("location", -33.86, 151.21).unit(DistanceUnit::Kilometers))
+                         .send()?;
     # Ok(())
     # }
     ```
 
-    [SyncClient]: ../../type.SyncClient.html
     [documents-mod]: ../../../types/document/index.html
-    [docs-querystring]: https://www.elastic.co/guide/en/elasticsearch/reference/current/query-dsl-query-string-query.html
     */
-    pub fn send(self) -> Result<SearchResponse<TDocument>> {
-        let req = self.inner.into_request();
-
-        RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
-            .send()?
-            .into_response()
+    pub fn sort(self, sort: impl Into<Sort>) -> SearchRequestBuilder<TSender, TDocument, Value> {
+        self.into_object_body().sort(sort)
     }
-}
 
-/**
-# Send asynchronously
-*/
-impl<TDocument, TBody> SearchRequestBuilder<AsyncSender, TDocument, TBody>
-where
-    TDocument: DeserializeOwned + Send + 'static,
-    TBody: Into<<AsyncSender as Sender>::Body> + Send + 'static,
-{
     /**
-    Send a `SearchRequestBuilder` asynchronously using an [`AsyncClient`][AsyncClient].
-
-    This will return a future that will resolve to the deserialised search response.
+    Add a field to each hit that's computed by a script.
 
     # Examples
 
-    Run a simple [Query String][docs-querystring] query for a [`DocumentType`][documents-mod] called `MyType`:
+    Return a discounted price for each hit for a [`DocumentType`][documents-mod] called `MyType`:
 
     ```no_run
-    # extern crate tokio;
-    # extern crate futures;
     # extern crate serde;
     # #[macro_use] extern crate serde_derive;
     # #[macro_use] extern crate elastic_derive;
     # extern crate elastic;
-    # use futures::Future;
     # use elastic::prelude::*;
     # fn main() { run().unwrap() }
     # fn run() -> Result<(), Box<::std::error::Error>> {
     # #[derive(Debug, Serialize, Deserialize, ElasticType)]
     # struct MyType { }
-    # let client = AsyncClientBuilder::new().build()?;
-    let future = client.search::<MyType>()
-                       .index("myindex")
-                       .send();
-
-    future.and_then(|response| {
-        // Iterate through the hits (of type `MyType`)
-        for hit in response.hits() {
-            println!("{:?}", hit);
-        }
-
-        Ok(())
-    });
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.search::<MyType>()
+                         .index("myindex")
+                         .script_field(
+                             "discounted_price",
+                             ScriptBuilder::new("doc['price'].value * params.discount").param("discount", 0.9),
+                         )
+                         .send()?;
     # Ok(())
     # }
     ```
 
-    [AsyncClient]: ../../type.AsyncClient.html
     [documents-mod]: ../../../types/document/index.html
-    [docs-querystring]: https://www.elastic.co/guide/en/elasticsearch/reference/current/query-dsl-query-string-query.html
     */
-    pub fn send(self) -> Pending<TDocument> {
-        let req = self.inner.into_request();
+    pub fn script_field(
+        self,
+        name: impl Into<String>,
+        script: impl Into<ScriptBuilder<DefaultParams>>,
+    ) -> SearchRequestBuilder<TSender, TDocument, Value> {
+        self.into_object_body().script_field(name, script)
+    }
 
-        let res_future =
-            RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
-                .send()
-                .and_then(|res| res.into_response());
+    /**
+    Collapse hits on the value of a field, so only the top hit for each value is returned.
 
-        Pending::new(res_future)
-    }
-}
+    # Examples
 
-/** A future returned by calling `send`. */
-pub struct Pending<TDocument> {
-    inner: Box<Future<Item = SearchResponse<TDocument>, Error = Error> + Send>,
-}
+    Return the top hit for each user for a [`DocumentType`][documents-mod] called `MyType`, along with their 3 most recent hits:
 
-impl<TDocument> Pending<TDocument> {
-    fn new<F>(fut: F) -> Self
-    where
-        F: Future<Item = SearchResponse<TDocument>, Error = Error> + Send + 'static,
-    {
-        Pending {
-            inner: Box::new(fut),
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Debug, Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.search::<MyType>()
+                         .index("myindex")
+                         .collapse(
+                             Collapse::field("user.id")
+                                 .inner_hits(InnerHitsOptions::new("recent").size(3).sort(Sort::field("timestamp").desc()))
+                         )
+                         .send()?;
+
+    for hit in response.hits() {
+        if let Some(recent) = hit.inner_hits("recent") {
+            println!("{} recent hits for {}", recent.total(), hit.id());
         }
     }
-}
-
-impl<TDocument> Future for Pending<TDocument>
-where
-    TDocument: DeserializeOwned + Send + 'static,
-{
-    type Item = SearchResponse<TDocument>;
-    type Error = Error;
+    # Ok(())
+    # }
+    ```
 
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
+    [documents-mod]: ../../../types/document/index.html
+    */
+    pub fn collapse(
+        self,
+        collapse: impl Into<Collapse>,
+    ) -> SearchRequestBuilder<TSender, TDocument, Value> {
+        self.into_object_body().collapse(collapse)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use serde_json::Value;
+    /**
+    Add a k-nearest neighbour search over a `dense_vector` field.
 
-    use prelude::*;
-    use tests::*;
+    The nearest neighbours are returned as hits, scored by their similarity to the query vector.
+    If a `query` is also set then the hits from both are combined.
 
-    #[test]
-    fn is_send() {
-        assert_send::<super::Pending<TestDoc>>();
-    }
+    # Examples
 
-    #[derive(Serialize, ElasticType)]
-    struct TestDoc {}
+    Find the 10 documents most similar to an embedding for a [`DocumentType`][documents-mod] called `MyType`:
 
-    #[test]
-    fn default_request() {
-        let client = SyncClientBuilder::new().build().unwrap();
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Debug, Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = SyncClientBuilder::new().build()?;
+    # let embedding = vec![0.12, -0.4, 0.83];
+    let response = client.search::<MyType>()
+                         .index("myindex")
+                         .knn(Knn::new("embedding", embedding, 10, 100))
+                         .send()?;
 
-        let req = client.search::<Value>().inner.into_request();
+    for hit in response.hits() {
+        println!("{} has similarity {:?}", hit.id(), hit.score());
+    }
+    # Ok(())
+    # }
+    ```
 
-        assert_eq!("/_all/_search", req.url.as_ref());
+    [documents-mod]: ../../../types/document/index.html
+    */
+    pub fn knn(self, knn: Knn) -> SearchRequestBuilder<TSender, TDocument, Value> {
+        self.into_object_body().knn(knn)
     }
 
-    #[test]
-    fn specify_index() {
-        let client = SyncClientBuilder::new().build().unwrap();
+    /**
+    Filter the hits after aggregations have been calculated.
 
-        let req = client
-            .search::<Value>()
-            .index("new-idx")
-            .inner
-            .into_request();
+    The post filter only affects the hits, so aggregations are calculated over all documents that match the query.
+    This is useful for faceted search, where selecting a facet shouldn't change the counts of the other facets.
 
-        assert_eq!("/new-idx/_search", req.url.as_ref());
+    # Examples
+
+    Only return red shirts for a [`DocumentType`][documents-mod] called `MyType`, but count shirts of every colour:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate serde_json;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Debug, Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.search::<MyType>()
+                         .index("shirts")
+                         .body(json!({
+                             "query": { "term": { "brand": "gucci" } },
+                             "aggs": { "colors": { "terms": { "field": "color" } } }
+                         }))
+                         .post_filter(json!({ "term": { "color": "red" } }))
+                         .send()?;
+    # Ok(())
+    # }
+    ```
+
+    [documents-mod]: ../../../types/document/index.html
+    */
+    pub fn post_filter(
+        self,
+        filter: impl Into<Value>,
+    ) -> SearchRequestBuilder<TSender, TDocument, Value> {
+        self.into_object_body().post_filter(filter)
     }
 
-    #[test]
-    fn specify_ty() {
-        let client = SyncClientBuilder::new().build().unwrap();
+    /**
+    Rescore the top `window_size` hits from each shard with another query.
 
-        let req = client.search::<Value>().ty("new-ty").inner.into_request();
+    Rescoring is useful for running an expensive query, like a phrase query, over only the best hits from a cheaper one.
 
-        assert_eq!("/_all/new-ty/_search", req.url.as_ref());
+    # Examples
+
+    Rescore the top 50 hits for a [`DocumentType`][documents-mod] called `MyType` with a phrase query:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate serde_json;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Debug, Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.search::<MyType>()
+                         .index("myindex")
+                         .query(json!({ "match": { "title": "fried eggs" } }))
+                         .rescore(
+                             50,
+                             RescoreQuery::new(json!({ "match_phrase": { "title": "fried eggs" } }))
+                                 .rescore_query_weight(2.0)
+                         )
+                         .send()?;
+    # Ok(())
+    # }
+    ```
+
+    [documents-mod]: ../../../types/document/index.html
+    */
+    pub fn rescore(
+        self,
+        window_size: u32,
+        query: impl Into<RescoreQuery>,
+    ) -> SearchRequestBuilder<TSender, TDocument, Value> {
+        self.into_object_body().rescore(window_size, query)
     }
 
-    #[test]
-    fn specify_body() {
-        let client = SyncClientBuilder::new().build().unwrap();
+    /**
+    Define a field that's computed when the search runs.
 
-        let req = client.search::<Value>().body("{}").inner.into_request();
+    The runtime field can be used in the query, aggregations and sorts of the request, and retrieved for each hit using [`fields`][fields].
 
-        assert_eq!("{}", req.body);
+    # Examples
+
+    Search and retrieve a runtime field for a [`DocumentType`][documents-mod] called `MyType`:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate serde_json;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Debug, Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.search::<MyType>()
+                         .index("myindex")
+                         .runtime_mapping(
+                             "total_price",
+                             RuntimeField::new(RuntimeFieldType::Double, "emit(doc['price'].value * doc['quantity'].value)"),
+                         )
+                         .query(json!({ "range": { "total_price": { "gte": 100 } } }))
+                         .fields(vec!["total_price"])
+                         .send()?;
+
+    for hit in response.hits() {
+        let total_price: Option<Vec<f64>> = hit.field_values("total_price");
+
+        println!("{} costs {:?}", hit.id(), total_price);
+    }
+    # Ok(())
+    # }
+    ```
+
+    [fields]: #method.fields
+    [documents-mod]: ../../../types/document/index.html
+    */
+    pub fn runtime_mapping(
+        self,
+        name: impl Into<String>,
+        field: RuntimeField,
+    ) -> SearchRequestBuilder<TSender, TDocument, Value> {
+        self.into_object_body().runtime_mapping(name, field)
+    }
+
+    /**
+    Retrieve the values of fields for each hit.
+
+    Fields can be mapped fields, runtime fields or patterns like `user.*`.
+    Values are returned in the `fields` of each hit, and can be read with `Hit.field_values`.
+    */
+    pub fn fields<I>(self, fields: I) -> SearchRequestBuilder<TSender, TDocument, Value>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.into_object_body().fields(fields)
+    }
+
+    /**
+    Retrieve the doc values of fields for each hit.
+
+    Doc values can be retrieved without loading the `_source`, and are returned in the `fields` of each hit.
+
+    # Examples
+
+    Retrieve the doc values of fields without loading the `_source` for a [`DocumentType`][documents-mod] called `MyType`:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # #[macro_use] extern crate serde_json;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Debug, Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.search::<MyType>()
+                         .index("myindex")
+                         .body(json!({ "_source": false }))
+                         .docvalue_fields(vec![
+                             DocValueField::from("user.id"),
+                             DocValueField::new("timestamp").format("epoch_millis"),
+                         ])
+                         .send()?;
+
+    for hit in response.hits() {
+        let user: Option<String> = hit.field_value("user.id");
+        let timestamp: Option<i64> = hit.field_value("timestamp");
+
+        println!("{:?} at {:?}", user, timestamp);
+    }
+    # Ok(())
+    # }
+    ```
+
+    [documents-mod]: ../../../types/document/index.html
+    */
+    pub fn docvalue_fields<I>(self, fields: I) -> SearchRequestBuilder<TSender, TDocument, Value>
+    where
+        I: IntoIterator,
+        I::Item: Into<DocValueField>,
+    {
+        self.into_object_body().docvalue_fields(fields)
+    }
+
+    /**
+    Retrieve the stored fields for each hit.
+
+    Only fields that are mapped with `store` enabled can be retrieved.
+    Values are returned in the `fields` of each hit.
+    Use `_none_` to disable returning the `_source` and all other stored metadata.
+    */
+    pub fn stored_fields<I>(self, fields: I) -> SearchRequestBuilder<TSender, TDocument, Value>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.into_object_body().stored_fields(fields)
+    }
+
+    /**
+    Set how accurately to count the total number of hits.
+
+    When the total isn't counted exactly, the total hits in the response may be a lower bound.
+
+    # Examples
+
+    Count every hit for a [`DocumentType`][documents-mod] called `MyType`:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Debug, Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.search::<MyType>()
+                         .index("myindex")
+                         .track_total_hits(true)
+                         .send()?;
+
+    if let Some(total) = response.total_hits() {
+        println!("exact total: {}, hits: {}", total.is_exact(), total.value());
+    }
+    # Ok(())
+    # }
+    ```
+
+    [documents-mod]: ../../../types/document/index.html
+    */
+    pub fn track_total_hits(
+        self,
+        track: impl Into<TrackTotalHits>,
+    ) -> SearchRequestBuilder<TSender, TDocument, Value> {
+        self.into_object_body().track_total_hits(track)
+    }
+
+    /** Exclude hits with a score below the given value. */
+    pub fn min_score(self, min_score: f64) -> SearchRequestBuilder<TSender, TDocument, Value> {
+        self.into_object_body().min_score(min_score)
+    }
+
+    /**
+    Stop collecting hits on each shard after the given number of documents have been found.
+
+    If the search terminates early then the response's `terminated_early` flag will be set.
+    */
+    pub fn terminate_after(
+        self,
+        terminate_after: u64,
+    ) -> SearchRequestBuilder<TSender, TDocument, Value> {
+        self.into_object_body().terminate_after(terminate_after)
+    }
+
+    fn into_object_body(self) -> SearchRequestBuilder<TSender, TDocument, Value> {
+        RequestBuilder::new(
+            self.client,
+            self.params_builder,
+            SearchRequestInner {
+                body: Value::Object(Map::new()),
+                index: self.inner.index,
+                ty: self.inner.ty,
+                _marker: PhantomData,
+            },
+        )
+    }
+}
+
+impl<TSender, TDocument> SearchRequestBuilder<TSender, TDocument, Value>
+where
+    TSender: Sender,
+{
+    /**
+    Set the query for the search request.
+
+    Any query already in the body is replaced.
+    */
+    pub fn query(mut self, query: impl Into<Value>) -> Self {
+        body_object(&mut self.inner.body).insert("query".to_owned(), query.into());
+        self
+    }
+
+    /**
+    Add a sort clause to the search request.
+
+    Any sort clauses already in the body are kept, and the new clause is sorted after them.
+    */
+    pub fn sort(mut self, sort: impl Into<Sort>) -> Self {
+        push_body_array(&mut self.inner.body, "sort", sort.into().to_value());
+        self
+    }
+
+    /**
+    Add a field to each hit that's computed by a script.
+
+    Any script fields already in the body are kept, unless they have the same name.
+    */
+    pub fn script_field(
+        mut self,
+        name: impl Into<String>,
+        script: impl Into<ScriptBuilder<DefaultParams>>,
+    ) -> Self {
+        let script = serde_json::to_value(script.into()).unwrap_or(Value::Null);
+
+        let mut script_field = Map::new();
+        script_field.insert("script".to_owned(), script);
+
+        body_object_entry(&mut self.inner.body, "script_fields")
+            .insert(name.into(), Value::Object(script_field));
+
+        self
+    }
+
+    /**
+    Collapse hits on the value of a field, so only the top hit for each value is returned.
+
+    Any collapse options already in the body are replaced.
+    */
+    pub fn collapse(mut self, collapse: impl Into<Collapse>) -> Self {
+        body_object(&mut self.inner.body).insert("collapse".to_owned(), collapse.into().to_value());
+        self
+    }
+
+    /**
+    Add a k-nearest neighbour search over a `dense_vector` field.
+
+    Any kNN searches already in the body are kept.
+    */
+    pub fn knn(mut self, knn: Knn) -> Self {
+        push_body_array(&mut self.inner.body, "knn", knn.to_value());
+        self
+    }
+
+    /**
+    Filter the hits after aggregations have been calculated.
+
+    Any post filter already in the body is replaced.
+    */
+    pub fn post_filter(mut self, filter: impl Into<Value>) -> Self {
+        body_object(&mut self.inner.body).insert("post_filter".to_owned(), filter.into());
+        self
+    }
+
+    /**
+    Rescore the top `window_size` hits from each shard with another query.
+
+    Any rescore queries already in the body are kept, and the new query is applied after them.
+    */
+    pub fn rescore(mut self, window_size: u32, query: impl Into<RescoreQuery>) -> Self {
+        let rescore = query.into().to_value(window_size);
+
+        push_body_array(&mut self.inner.body, "rescore", rescore);
+        self
+    }
+
+    /**
+    Define a field that's computed when the search runs.
+
+    Any runtime fields already in the body are kept, unless they have the same name.
+    */
+    pub fn runtime_mapping(mut self, name: impl Into<String>, field: RuntimeField) -> Self {
+        body_object_entry(&mut self.inner.body, "runtime_mappings")
+            .insert(name.into(), field.to_value());
+
+        self
+    }
+
+    /**
+    Retrieve the values of fields for each hit.
+
+    Any fields already in the body are kept.
+    */
+    pub fn fields<I>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        for field in fields {
+            push_body_array(&mut self.inner.body, "fields", Value::String(field.into()));
+        }
+
+        self
+    }
+
+    /**
+    Retrieve the doc values of fields for each hit.
+
+    Any doc value fields already in the body are kept.
+    */
+    pub fn docvalue_fields<I>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<DocValueField>,
+    {
+        for field in fields {
+            push_body_array(
+                &mut self.inner.body,
+                "docvalue_fields",
+                field.into().to_value(),
+            );
+        }
+
+        self
+    }
+
+    /**
+    Retrieve the stored fields for each hit.
+
+    Any stored fields already in the body are kept.
+    */
+    pub fn stored_fields<I>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        for field in fields {
+            push_body_array(
+                &mut self.inner.body,
+                "stored_fields",
+                Value::String(field.into()),
+            );
+        }
+
+        self
+    }
+
+    /** Set how accurately to count the total number of hits. */
+    pub fn track_total_hits(mut self, track: impl Into<TrackTotalHits>) -> Self {
+        body_object(&mut self.inner.body)
+            .insert("track_total_hits".to_owned(), track.into().to_value());
+        self
+    }
+
+    /** Exclude hits with a score below the given value. */
+    pub fn min_score(mut self, min_score: f64) -> Self {
+        body_object(&mut self.inner.body).insert("min_score".to_owned(), min_score.into());
+        self
+    }
+
+    /** Stop collecting hits on each shard after the given number of documents have been found. */
+    pub fn terminate_after(mut self, terminate_after: u64) -> Self {
+        body_object(&mut self.inner.body)
+            .insert("terminate_after".to_owned(), terminate_after.into());
+        self
+    }
+}
+
+/** Build a JSON object from the given fields, skipping any that are `None`. */
+fn object(fields: Vec<(&str, Option<Value>)>) -> Value {
+    let mut object = Map::new();
+
+    for (key, value) in fields {
+        if let Some(value) = value {
+            object.insert(key.to_owned(), value);
+        }
+    }
+
+    Value::Object(object)
+}
+
+/**
+Get the search body as a JSON object.
+
+Any body that isn't already an object is replaced by an empty one.
+*/
+fn body_object(body: &mut Value) -> &mut Map<String, Value> {
+    if !body.is_object() {
+        *body = Value::Object(Map::new());
+    }
+
+    match *body {
+        Value::Object(ref mut body) => body,
+        _ => unreachable!(),
+    }
+}
+
+/**
+Get an object in the search body.
+
+Any value for the key that isn't already an object is replaced by an empty one.
+*/
+fn body_object_entry<'a>(body: &'a mut Value, key: &str) -> &'a mut Map<String, Value> {
+    body_object(body_object(body).entry(key).or_insert(Value::Null))
+}
+
+/**
+Add a value to an array in the search body.
+
+If the body already contains a single value for the key then it's converted into an array.
+*/
+fn push_body_array(body: &mut Value, key: &str, value: Value) {
+    let values = body_object(body)
+        .entry(key)
+        .or_insert_with(|| Value::Array(vec![]));
+
+    match *values {
+        Value::Array(ref mut values) => values.push(value),
+        ref mut existing => {
+            let existing = existing.take();
+            *values = Value::Array(vec![existing, value]);
+        }
+    }
+}
+
+/**
+# Send synchronously
+*/
+impl<TDocument, TBody> SearchRequestBuilder<SyncSender, TDocument, TBody>
+where
+    TDocument: DeserializeOwned,
+    TBody: Into<<SyncSender as Sender>::Body> + Send + 'static,
+{
+    /**
+    Send a `SearchRequestBuilder` synchronously using a [`SyncClient`][SyncClient].
+
+    This will block the current thread until a response arrives and is deserialised.
+
+    # Examples
+
+    Run a simple [Query String][docs-querystring] query for a [`DocumentType`][documents-mod] called `MyType`:
+
+    ```no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # #[derive(Debug, Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.search::<MyType>()
+                         .index("myindex")
+                         .send()?;
+
+    // Iterate through the hits (of type `MyType`)
+    for hit in response.hits() {
+        println!("{:?}", hit);
+    }
+    # Ok(())
+    # }
+    ```
+
+    [SyncClient]: ../../type.SyncClient.html
+    [documents-mod]: ../../../types/document/index.html
+    [docs-querystring]: https://www.elastic.co/guide/en/elasticsearch/reference/current/query-dsl-query-string-query.html
+    */
+    pub fn send(self) -> Result<SearchResponse<TDocument>> {
+        let req = self.inner.into_request();
+        let affinity = self.client.sender.inner.affinity.clone();
+
+        let res = RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
+            .send()?;
+
+        let node = res.node().clone();
+        let res = res.into_response()?;
+
+        // Remember the node that served any search contexts the search opened
+        if let Some(affinity) = affinity {
+            affinity.pin_search(&res, &node);
+        }
+
+        Ok(res)
+    }
+}
+
+/**
+# Send asynchronously
+*/
+impl<TDocument, TBody> SearchRequestBuilder<AsyncSender, TDocument, TBody>
+where
+    TDocument: DeserializeOwned + Send + 'static,
+    TBody: Into<<AsyncSender as Sender>::Body> + Send + 'static,
+{
+    /**
+    Send a `SearchRequestBuilder` asynchronously using an [`AsyncClient`][AsyncClient].
+
+    This will return a future that will resolve to the deserialised search response.
+
+    # Examples
+
+    Run a simple [Query String][docs-querystring] query for a [`DocumentType`][documents-mod] called `MyType`:
+
+    ```edition2018,no_run
+    # extern crate serde;
+    # #[macro_use] extern crate serde_derive;
+    # #[macro_use] extern crate elastic_derive;
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
+    # #[derive(Debug, Serialize, Deserialize, ElasticType)]
+    # struct MyType { }
+    # let client = AsyncClientBuilder::new().build()?;
+    let response = client.search::<MyType>()
+                         .index("myindex")
+                         .send()
+                         .await?;
+
+    // Iterate through the hits (of type `MyType`)
+    for hit in response.hits() {
+        println!("{:?}", hit);
+    }
+    # Ok(())
+    # }
+    ```
+
+    [AsyncClient]: ../../type.AsyncClient.html
+    [documents-mod]: ../../../types/document/index.html
+    [docs-querystring]: https://www.elastic.co/guide/en/elasticsearch/reference/current/query-dsl-query-string-query.html
+    */
+    pub fn send(self) -> Pending<TDocument> {
+        let req = self.inner.into_request();
+
+        let affinity = self.client.sender.affinity.clone();
+
+        let res_future =
+            RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
+                .send()
+                .compat()
+                .and_then(move |res| {
+                    let node = res.node().clone();
+
+                    res.into_response().compat().map(move |res| {
+                        // Remember the node that served any search contexts the search opened
+                        if let Some(affinity) = affinity {
+                            affinity.pin_search(&res, &node);
+                        }
+
+                        res
+                    })
+                });
+
+        Pending::new(res_future)
+    }
+}
+
+/** A future returned by calling `send`. */
+pub struct Pending<TDocument> {
+    inner: BoxFuture<SearchResponse<TDocument>>,
+}
+
+impl<TDocument> Pending<TDocument> {
+    fn new<F>(fut: F) -> Self
+    where
+        F: Future<Item = SearchResponse<TDocument>, Error = Error> + Send + 'static,
+    {
+        Pending {
+            inner: box_future(fut),
+        }
+    }
+}
+
+pending_future!(Pending<TDocument> => SearchResponse<TDocument>, TDocument);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use prelude::*;
+    use tests::*;
+
+    #[test]
+    fn is_send() {
+        assert_send::<super::Pending<TestDoc>>();
+    }
+
+    #[derive(Serialize, ElasticType)]
+    struct TestDoc {}
+
+    #[test]
+    fn default_request() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client.search::<Value>().inner.into_request();
+
+        assert_eq!("/_all/_search", req.url.as_ref());
+    }
+
+    #[test]
+    fn specify_index() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .search::<Value>()
+            .index("new-idx")
+            .inner
+            .into_request();
+
+        assert_eq!("/new-idx/_search", req.url.as_ref());
+    }
+
+    #[test]
+    fn specify_ty() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client.search::<Value>().ty("new-ty").inner.into_request();
+
+        assert_eq!("/_all/new-ty/_search", req.url.as_ref());
+    }
+
+    #[test]
+    fn specify_body() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client.search::<Value>().body("{}").inner.into_request();
+
+        assert_eq!("{}", req.body);
+    }
+
+    #[test]
+    fn specify_sort() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .search::<Value>()
+            .sort(Sort::field("timestamp").desc())
+            .sort("_score")
+            .inner
+            .into_request();
+
+        let expected = json!({
+            "sort": [
+                { "timestamp": { "order": "desc" } },
+                { "_score": {} }
+            ]
+        });
+
+        assert_eq!(expected, req.body);
+    }
+
+    #[test]
+    fn specify_sort_after_body() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .search::<Value>()
+            .body(json!({
+                "query": { "match_all": {} },
+                "sort": "_doc"
+            }))
+            .sort(Sort::field("timestamp"))
+            .inner
+            .into_request();
+
+        let expected = json!({
+            "query": { "match_all": {} },
+            "sort": [
+                "_doc",
+                { "timestamp": {} }
+            ]
+        });
+
+        assert_eq!(expected, req.body);
+    }
+
+    #[test]
+    fn specify_query() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .search::<Value>()
+            .body(json!({
+                "query": { "match_all": {} },
+                "size": 5
+            }))
+            .query(FunctionScore::match_all().function(ScoreFunction::weight(2.0)))
+            .inner
+            .into_request();
+
+        let expected = json!({
+            "query": {
+                "function_score": {
+                    "functions": [
+                        { "weight": 2.0 }
+                    ]
+                }
+            },
+            "size": 5
+        });
+
+        assert_eq!(expected, req.body);
+    }
+
+    #[test]
+    fn specify_script_fields() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .search::<Value>()
+            .body(json!({
+                "query": { "match_all": {} }
+            }))
+            .script_field("a", "doc['a'].value * 2")
+            .script_field("b", ScriptBuilder::stored("calculate-b").param("factor", 2))
+            .inner
+            .into_request();
+
+        let expected = json!({
+            "query": { "match_all": {} },
+            "script_fields": {
+                "a": {
+                    "script": { "inline": "doc['a'].value * 2" }
+                },
+                "b": {
+                    "script": { "id": "calculate-b", "params": { "factor": 2 } }
+                }
+            }
+        });
+
+        assert_eq!(expected, req.body);
+    }
+
+    #[test]
+    fn specify_knn() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .search::<Value>()
+            .body(json!({
+                "knn": { "field": "title_vector", "query_vector": [1.0], "k": 1, "num_candidates": 10 }
+            }))
+            .knn(Knn::new("image_vector", vec![0.5], 5, 50))
+            .inner
+            .into_request();
+
+        let expected = json!({
+            "knn": [
+                { "field": "title_vector", "query_vector": [1.0], "k": 1, "num_candidates": 10 },
+                { "field": "image_vector", "query_vector": [0.5], "k": 5, "num_candidates": 50 }
+            ]
+        });
+
+        assert_eq!(expected, req.body);
+    }
+
+    #[test]
+    fn specify_post_filter() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .search::<Value>()
+            .post_filter(json!({ "term": { "color": "blue" } }))
+            .post_filter(json!({ "term": { "color": "red" } }))
+            .inner
+            .into_request();
+
+        let expected = json!({
+            "post_filter": { "term": { "color": "red" } }
+        });
+
+        assert_eq!(expected, req.body);
+    }
+
+    #[test]
+    fn specify_rescore() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .search::<Value>()
+            .rescore(50, json!({ "match_phrase": { "title": "eggs" } }))
+            .rescore(
+                10,
+                RescoreQuery::new(FunctionScore::match_all().function(ScoreFunction::weight(2.0)))
+                    .score_mode(RescoreMode::Multiply),
+            )
+            .inner
+            .into_request();
+
+        let expected = json!({
+            "rescore": [
+                {
+                    "window_size": 50,
+                    "query": {
+                        "rescore_query": { "match_phrase": { "title": "eggs" } }
+                    }
+                },
+                {
+                    "window_size": 10,
+                    "query": {
+                        "rescore_query": {
+                            "function_score": {
+                                "functions": [
+                                    { "weight": 2.0 }
+                                ]
+                            }
+                        },
+                        "score_mode": "multiply"
+                    }
+                }
+            ]
+        });
+
+        assert_eq!(expected, req.body);
+    }
+
+    #[test]
+    fn specify_runtime_mappings() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .search::<Value>()
+            .body(json!({
+                "runtime_mappings": {
+                    "day_of_week": { "type": "keyword" }
+                },
+                "fields": "day_of_week"
+            }))
+            .runtime_mapping(
+                "total_price",
+                RuntimeField::new(RuntimeFieldType::Double, "emit(doc['price'].value * 2)"),
+            )
+            .fields(vec!["total_price", "user.*"])
+            .inner
+            .into_request();
+
+        let expected = json!({
+            "runtime_mappings": {
+                "day_of_week": { "type": "keyword" },
+                "total_price": {
+                    "type": "double",
+                    "script": { "inline": "emit(doc['price'].value * 2)" }
+                }
+            },
+            "fields": ["day_of_week", "total_price", "user.*"]
+        });
+
+        assert_eq!(expected, req.body);
+    }
+
+    #[test]
+    fn specify_docvalue_and_stored_fields() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .search::<Value>()
+            .body(json!({
+                "_source": false,
+                "docvalue_fields": "user.id"
+            }))
+            .docvalue_fields(vec![DocValueField::new("timestamp").format("epoch_millis")])
+            .stored_fields(vec!["title", "body"])
+            .inner
+            .into_request();
+
+        let expected = json!({
+            "_source": false,
+            "docvalue_fields": [
+                "user.id",
+                { "field": "timestamp", "format": "epoch_millis" }
+            ],
+            "stored_fields": ["title", "body"]
+        });
+
+        assert_eq!(expected, req.body);
+    }
+
+    #[test]
+    fn specify_hit_limits() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .search::<Value>()
+            .track_total_hits(TrackTotalHits::UpTo(100))
+            .min_score(0.5)
+            .terminate_after(1000)
+            .inner
+            .into_request();
+
+        let expected = json!({
+            "track_total_hits": 100,
+            "min_score": 0.5,
+            "terminate_after": 1000
+        });
+
+        assert_eq!(expected, req.body);
+    }
+
+    #[test]
+    fn specify_track_total_hits() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let exact = client
+            .search::<Value>()
+            .track_total_hits(true)
+            .inner
+            .into_request();
+
+        let disabled = client
+            .search::<Value>()
+            .track_total_hits(false)
+            .inner
+            .into_request();
+
+        assert_eq!(json!({ "track_total_hits": true }), exact.body);
+        assert_eq!(json!({ "track_total_hits": false }), disabled.body);
+    }
+
+    #[test]
+    fn specify_collapse() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let req = client
+            .search::<Value>()
+            .collapse("user.id")
+            .collapse(Collapse::field("user.name").inner_hits("recent"))
+            .inner
+            .into_request();
+
+        let expected = json!({
+            "collapse": {
+                "field": "user.name",
+                "inner_hits": { "name": "recent" }
+            }
+        });
+
+        assert_eq!(expected, req.body);
     }
 }
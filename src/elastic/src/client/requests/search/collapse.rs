@@ -0,0 +1,264 @@
+/*!
+Builders for [collapsing][docs-collapse] search hits.
+
+[docs-collapse]: https://www.elastic.co/guide/en/elasticsearch/reference/current/search-request-collapse.html
+*/
+
+use serde_json::Value;
+
+use super::{
+    object,
+    Sort,
+};
+
+/**
+Collapse search hits on the value of a field.
+
+Only the top hit for each distinct value of the field is returned.
+Other hits for the same value can be returned with each top hit using [`inner_hits`][Collapse.inner_hits].
+Collapsing is attached to a search request with [`collapse`][collapse].
+
+# Examples
+
+Return the most recent post for each user, along with their 3 most liked posts:
+
+```
+# extern crate elastic;
+# use elastic::prelude::*;
+# fn main() {
+let collapse = Collapse::field("user.id").inner_hits(
+    InnerHitsOptions::new("most_liked")
+        .size(3)
+        .sort(Sort::field("likes").desc()),
+);
+# }
+```
+
+[Collapse.inner_hits]: #method.inner_hits
+[collapse]: type.SearchRequestBuilder.html#method.collapse
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct Collapse {
+    field: String,
+    inner_hits: Vec<InnerHitsOptions>,
+    max_concurrent_group_searches: Option<u32>,
+}
+
+impl Collapse {
+    /**
+    Collapse hits on the value of a field.
+
+    The field must be a single valued `keyword` or numeric field with doc values.
+    */
+    pub fn field(field: impl Into<String>) -> Self {
+        Collapse {
+            field: field.into(),
+            inner_hits: vec![],
+            max_concurrent_group_searches: None,
+        }
+    }
+
+    /**
+    Return other hits for each collapsed value.
+
+    This method can be called multiple times to return differently named sets of inner hits.
+    */
+    pub fn inner_hits(mut self, inner_hits: impl Into<InnerHitsOptions>) -> Self {
+        self.inner_hits.push(inner_hits.into());
+        self
+    }
+
+    /** Set the number of concurrent requests allowed to fetch inner hits per group. */
+    pub fn max_concurrent_group_searches(mut self, max: u32) -> Self {
+        self.max_concurrent_group_searches = Some(max);
+        self
+    }
+
+    pub(crate) fn to_value(&self) -> Value {
+        let inner_hits = match self.inner_hits.len() {
+            0 => None,
+            1 => Some(self.inner_hits[0].to_value()),
+            _ => Some(Value::Array(
+                self.inner_hits
+                    .iter()
+                    .map(InnerHitsOptions::to_value)
+                    .collect(),
+            )),
+        };
+
+        object(vec![
+            ("field", Some(self.field.as_str().into())),
+            ("inner_hits", inner_hits),
+            (
+                "max_concurrent_group_searches",
+                self.max_concurrent_group_searches.map(Value::from),
+            ),
+        ])
+    }
+}
+
+impl<'a> From<&'a str> for Collapse {
+    fn from(field: &'a str) -> Self {
+        Collapse::field(field)
+    }
+}
+
+impl From<String> for Collapse {
+    fn from(field: String) -> Self {
+        Collapse::field(field)
+    }
+}
+
+impl From<Collapse> for Value {
+    fn from(collapse: Collapse) -> Self {
+        collapse.to_value()
+    }
+}
+
+/**
+Options for the inner hits returned with each collapsed hit.
+
+The inner hits are returned with each hit in the response under the given name.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct InnerHitsOptions {
+    name: String,
+    from: Option<u32>,
+    size: Option<u32>,
+    sort: Vec<Sort>,
+    collapse: Option<Box<Collapse>>,
+}
+
+impl InnerHitsOptions {
+    /** Return inner hits with the given name. */
+    pub fn new(name: impl Into<String>) -> Self {
+        InnerHitsOptions {
+            name: name.into(),
+            from: None,
+            size: None,
+            sort: vec![],
+            collapse: None,
+        }
+    }
+
+    /** Set the offset of the first inner hit to return. */
+    pub fn from(mut self, from: u32) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    /** Set the maximum number of inner hits to return. */
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /** Add a sort clause for the inner hits. */
+    pub fn sort(mut self, sort: impl Into<Sort>) -> Self {
+        self.sort.push(sort.into());
+        self
+    }
+
+    /** Collapse the inner hits on the value of another field. */
+    pub fn collapse(mut self, collapse: impl Into<Collapse>) -> Self {
+        self.collapse = Some(Box::new(collapse.into()));
+        self
+    }
+
+    pub(crate) fn to_value(&self) -> Value {
+        let sort = if self.sort.is_empty() {
+            None
+        } else {
+            Some(Value::Array(self.sort.iter().map(Sort::to_value).collect()))
+        };
+
+        object(vec![
+            ("name", Some(self.name.as_str().into())),
+            ("from", self.from.map(Value::from)),
+            ("size", self.size.map(Value::from)),
+            ("sort", sort),
+            (
+                "collapse",
+                self.collapse.as_ref().map(|collapse| collapse.to_value()),
+            ),
+        ])
+    }
+}
+
+impl<'a> From<&'a str> for InnerHitsOptions {
+    fn from(name: &'a str) -> Self {
+        InnerHitsOptions::new(name)
+    }
+}
+
+impl From<String> for InnerHitsOptions {
+    fn from(name: String) -> Self {
+        InnerHitsOptions::new(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use prelude::*;
+
+    #[test]
+    fn collapse_field() {
+        let collapse: Value = Collapse::field("user.id").into();
+
+        assert_eq!(json!({ "field": "user.id" }), collapse);
+    }
+
+    #[test]
+    fn collapse_single_inner_hits() {
+        let collapse: Value = Collapse::field("user.id")
+            .inner_hits(
+                InnerHitsOptions::new("most_liked")
+                    .size(3)
+                    .sort(Sort::field("likes").desc()),
+            )
+            .max_concurrent_group_searches(4)
+            .into();
+
+        let expected = json!({
+            "field": "user.id",
+            "inner_hits": {
+                "name": "most_liked",
+                "size": 3,
+                "sort": [
+                    { "likes": { "order": "desc" } }
+                ]
+            },
+            "max_concurrent_group_searches": 4
+        });
+
+        assert_eq!(expected, collapse);
+    }
+
+    #[test]
+    fn collapse_multiple_inner_hits() {
+        let collapse: Value = Collapse::field("user.id")
+            .inner_hits("all")
+            .inner_hits(
+                InnerHitsOptions::new("by_thread")
+                    .from(1)
+                    .collapse("thread.id"),
+            )
+            .into();
+
+        let expected = json!({
+            "field": "user.id",
+            "inner_hits": [
+                { "name": "all" },
+                {
+                    "name": "by_thread",
+                    "from": 1,
+                    "collapse": { "field": "thread.id" }
+                }
+            ]
+        });
+
+        assert_eq!(expected, collapse);
+    }
+}
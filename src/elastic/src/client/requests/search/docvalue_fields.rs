@@ -0,0 +1,97 @@
+/*!
+Builders for retrieving [doc value fields][docs-docvalue-fields] in a search request.
+
+[docs-docvalue-fields]: https://www.elastic.co/guide/en/elasticsearch/reference/current/search-fields.html#docvalue-fields
+*/
+
+use serde_json::Value;
+
+use super::object;
+
+/**
+A field to retrieve from doc values for each hit.
+
+Doc value fields are attached to a search request with [`docvalue_fields`][docvalue_fields].
+A field name can be converted into a `DocValueField` that uses the default format.
+
+# Examples
+
+Retrieve a date field formatted as epoch milliseconds:
+
+```
+# extern crate elastic;
+# use elastic::prelude::*;
+# fn main() {
+let field = DocValueField::new("timestamp").format("epoch_millis");
+# }
+```
+
+[docvalue_fields]: type.SearchRequestBuilder.html#method.docvalue_fields
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocValueField {
+    field: String,
+    format: Option<String>,
+}
+
+impl DocValueField {
+    /** Retrieve the given field, which can also be a pattern like `user.*`. */
+    pub fn new(field: impl Into<String>) -> Self {
+        DocValueField {
+            field: field.into(),
+            format: None,
+        }
+    }
+
+    /** Set the format to return values in, like a date format or a decimal pattern. */
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+
+    pub(crate) fn to_value(&self) -> Value {
+        match self.format {
+            Some(ref format) => object(vec![
+                ("field", Some(self.field.as_str().into())),
+                ("format", Some(format.as_str().into())),
+            ]),
+            None => self.field.as_str().into(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for DocValueField {
+    fn from(field: &'a str) -> Self {
+        DocValueField::new(field)
+    }
+}
+
+impl From<String> for DocValueField {
+    fn from(field: String) -> Self {
+        DocValueField::new(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prelude::*;
+
+    #[test]
+    fn docvalue_field() {
+        let field = DocValueField::from("user.id");
+
+        assert_eq!(json!("user.id"), field.to_value());
+    }
+
+    #[test]
+    fn docvalue_field_format() {
+        let field = DocValueField::new("timestamp").format("epoch_millis");
+
+        let expected = json!({
+            "field": "timestamp",
+            "format": "epoch_millis"
+        });
+
+        assert_eq!(expected, field.to_value());
+    }
+}
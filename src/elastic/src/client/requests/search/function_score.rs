@@ -0,0 +1,741 @@
+/*!
+Builders for [function score][docs-function-score] queries.
+
+[docs-function-score]: https://www.elastic.co/guide/en/elasticsearch/reference/current/query-dsl-function-score-query.html
+*/
+
+use serde::ser::{
+    Serialize,
+    Serializer,
+};
+use serde_json::{
+    self,
+    Value,
+};
+
+use super::object;
+use client::requests::common::{
+    DefaultParams,
+    ScriptBuilder,
+};
+
+/**
+A `function_score` query that adjusts the score of hits matching a query.
+
+Functions are added with [`function`][FunctionScore.function].
+A `FunctionScore` can be used as the [`query`][query] of a search request, or serialized as part of a larger query.
+
+# Examples
+
+Boost recent, popular posts:
+
+```
+# extern crate elastic;
+# #[macro_use] extern crate serde_json;
+# use elastic::prelude::*;
+# fn main() {
+let query = FunctionScore::new(json!({ "match": { "title": "elasticsearch" } }))
+    .function(ScoreFunction::gauss("published", "now", "30d").offset("7d").decay(0.5))
+    .function(
+        ScoreFunction::field_value_factor("likes")
+            .modifier(FieldValueModifier::Log1p)
+            .missing(1.0),
+    )
+    .function(ScoreFunction::weight(2.0).filter(json!({ "term": { "featured": true } })))
+    .score_mode(ScoreMode::Sum)
+    .boost_mode(BoostMode::Multiply)
+    .max_boost(10.0);
+# }
+```
+
+[FunctionScore.function]: #method.function
+[query]: type.SearchRequestBuilder.html#method.query
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionScore {
+    query: Option<Value>,
+    functions: Vec<ScoreFunction>,
+    score_mode: Option<ScoreMode>,
+    boost_mode: Option<BoostMode>,
+    max_boost: Option<f64>,
+    min_score: Option<f64>,
+    boost: Option<f64>,
+}
+
+impl FunctionScore {
+    /** Create a `function_score` query that scores hits matching the given query. */
+    pub fn new(query: impl Into<Value>) -> Self {
+        FunctionScore {
+            query: Some(query.into()),
+            ..FunctionScore::match_all()
+        }
+    }
+
+    /** Create a `function_score` query that scores all documents. */
+    pub fn match_all() -> Self {
+        FunctionScore {
+            query: None,
+            functions: vec![],
+            score_mode: None,
+            boost_mode: None,
+            max_boost: None,
+            min_score: None,
+            boost: None,
+        }
+    }
+
+    /** Add a function to compute a score with. */
+    pub fn function(mut self, function: impl Into<ScoreFunction>) -> Self {
+        self.functions.push(function.into());
+        self
+    }
+
+    /** Set how the scores of each function are combined. */
+    pub fn score_mode(mut self, score_mode: ScoreMode) -> Self {
+        self.score_mode = Some(score_mode);
+        self
+    }
+
+    /** Set how the combined function score is combined with the score of the query. */
+    pub fn boost_mode(mut self, boost_mode: BoostMode) -> Self {
+        self.boost_mode = Some(boost_mode);
+        self
+    }
+
+    /** Set the maximum score the functions can compute. */
+    pub fn max_boost(mut self, max_boost: f64) -> Self {
+        self.max_boost = Some(max_boost);
+        self
+    }
+
+    /** Exclude hits with a score below the given value. */
+    pub fn min_score(mut self, min_score: f64) -> Self {
+        self.min_score = Some(min_score);
+        self
+    }
+
+    /** Set a boost for the whole query. */
+    pub fn boost(mut self, boost: f64) -> Self {
+        self.boost = Some(boost);
+        self
+    }
+
+    pub(crate) fn to_value(&self) -> Value {
+        let functions = if self.functions.is_empty() {
+            None
+        } else {
+            Some(Value::Array(
+                self.functions.iter().map(ScoreFunction::to_value).collect(),
+            ))
+        };
+
+        let options = object(vec![
+            ("query", self.query.clone()),
+            ("functions", functions),
+            ("score_mode", self.score_mode.map(score_mode)),
+            ("boost_mode", self.boost_mode.map(boost_mode)),
+            ("max_boost", self.max_boost.map(Value::from)),
+            ("min_score", self.min_score.map(Value::from)),
+            ("boost", self.boost.map(Value::from)),
+        ]);
+
+        object(vec![("function_score", Some(options))])
+    }
+}
+
+impl Serialize for FunctionScore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_value().serialize(serializer)
+    }
+}
+
+impl From<FunctionScore> for Value {
+    fn from(function_score: FunctionScore) -> Self {
+        function_score.to_value()
+    }
+}
+
+/**
+A function that computes a score for hits in a `function_score` query.
+
+Use `ScoreFunction::weight`, `ScoreFunction::field_value_factor`, `ScoreFunction::gauss`, `ScoreFunction::exp`, `ScoreFunction::linear` or `ScoreFunction::script_score` to build a function.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreFunction(ScoreFunctionInner);
+
+#[derive(Debug, Clone, PartialEq)]
+enum ScoreFunctionInner {
+    Weight(WeightFunction),
+    FieldValueFactor(FieldValueFactor),
+    Decay(DecayFunction),
+    ScriptScore(ScriptScoreFunction),
+}
+
+impl ScoreFunction {
+    /** Multiply the score by a constant weight. */
+    pub fn weight(weight: f64) -> WeightFunction {
+        WeightFunction {
+            weight,
+            filter: None,
+        }
+    }
+
+    /** Compute a score from the value of a numeric field. */
+    pub fn field_value_factor(field: impl Into<String>) -> FieldValueFactor {
+        FieldValueFactor {
+            field: field.into(),
+            factor: None,
+            modifier: None,
+            missing: None,
+            filter: None,
+            weight: None,
+        }
+    }
+
+    /**
+    Compute a score that decays with the distance of a field value from an origin, following a normal curve.
+
+    The field can be numeric, a date or a geo point.
+    The `scale` is the distance from the origin where the score is equal to the `decay`, which defaults to `0.5`.
+    */
+    pub fn gauss(
+        field: impl Into<String>,
+        origin: impl Into<Value>,
+        scale: impl Into<Value>,
+    ) -> DecayFunction {
+        DecayFunction::new(DecayKind::Gauss, field.into(), origin.into(), scale.into())
+    }
+
+    /** Compute a score that decays exponentially with the distance of a field value from an origin. */
+    pub fn exp(
+        field: impl Into<String>,
+        origin: impl Into<Value>,
+        scale: impl Into<Value>,
+    ) -> DecayFunction {
+        DecayFunction::new(DecayKind::Exp, field.into(), origin.into(), scale.into())
+    }
+
+    /** Compute a score that decays linearly with the distance of a field value from an origin. */
+    pub fn linear(
+        field: impl Into<String>,
+        origin: impl Into<Value>,
+        scale: impl Into<Value>,
+    ) -> DecayFunction {
+        DecayFunction::new(DecayKind::Linear, field.into(), origin.into(), scale.into())
+    }
+
+    /** Compute a score with a script. */
+    pub fn script_score(script: impl Into<ScriptBuilder<DefaultParams>>) -> ScriptScoreFunction {
+        let script = serde_json::to_value(script.into()).unwrap_or(Value::Null);
+
+        ScriptScoreFunction {
+            script,
+            filter: None,
+            weight: None,
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        match self.0 {
+            ScoreFunctionInner::Weight(ref function) => object(vec![
+                ("filter", function.filter.clone()),
+                ("weight", Some(function.weight.into())),
+            ]),
+            ScoreFunctionInner::FieldValueFactor(ref function) => {
+                let options = object(vec![
+                    ("field", Some(function.field.as_str().into())),
+                    ("factor", function.factor.map(Value::from)),
+                    ("modifier", function.modifier.map(modifier)),
+                    ("missing", function.missing.map(Value::from)),
+                ]);
+
+                object(vec![
+                    ("filter", function.filter.clone()),
+                    ("weight", function.weight.map(Value::from)),
+                    ("field_value_factor", Some(options)),
+                ])
+            }
+            ScoreFunctionInner::Decay(ref function) => {
+                let kind = match function.kind {
+                    DecayKind::Gauss => "gauss",
+                    DecayKind::Exp => "exp",
+                    DecayKind::Linear => "linear",
+                };
+
+                let field = object(vec![
+                    ("origin", Some(function.origin.clone())),
+                    ("scale", Some(function.scale.clone())),
+                    ("offset", function.offset.clone()),
+                    ("decay", function.decay.map(Value::from)),
+                ]);
+
+                let options = object(vec![
+                    (function.field.as_str(), Some(field)),
+                    (
+                        "multi_value_mode",
+                        function.multi_value_mode.map(multi_value_mode),
+                    ),
+                ]);
+
+                object(vec![
+                    ("filter", function.filter.clone()),
+                    ("weight", function.weight.map(Value::from)),
+                    (kind, Some(options)),
+                ])
+            }
+            ScoreFunctionInner::ScriptScore(ref function) => object(vec![
+                ("filter", function.filter.clone()),
+                ("weight", function.weight.map(Value::from)),
+                (
+                    "script_score",
+                    Some(object(vec![("script", Some(function.script.clone()))])),
+                ),
+            ]),
+        }
+    }
+}
+
+/** How the scores of each function in a `function_score` query are combined. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreMode {
+    /** Multiply the scores. */
+    Multiply,
+    /** Add the scores. */
+    Sum,
+    /** Average the scores. */
+    Avg,
+    /** Use the score of the first function with a matching filter. */
+    First,
+    /** Use the highest score. */
+    Max,
+    /** Use the lowest score. */
+    Min,
+}
+
+/** How the combined function score is combined with the score of the query. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoostMode {
+    /** Multiply the query score and function score. */
+    Multiply,
+    /** Ignore the query score and only use the function score. */
+    Replace,
+    /** Add the query score and function score. */
+    Sum,
+    /** Average the query score and function score. */
+    Avg,
+    /** Use the higher of the query score and function score. */
+    Max,
+    /** Use the lower of the query score and function score. */
+    Min,
+}
+
+/** A modifier to apply to the value of a field in a `field_value_factor` function. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldValueModifier {
+    /** Don't modify the value. */
+    None,
+    /** Take the common logarithm of the value. */
+    Log,
+    /** Add 1 to the value and take the common logarithm. */
+    Log1p,
+    /** Add 2 to the value and take the common logarithm. */
+    Log2p,
+    /** Take the natural logarithm of the value. */
+    Ln,
+    /** Add 1 to the value and take the natural logarithm. */
+    Ln1p,
+    /** Add 2 to the value and take the natural logarithm. */
+    Ln2p,
+    /** Square the value. */
+    Square,
+    /** Take the square root of the value. */
+    Sqrt,
+    /** Take the reciprocal of the value. */
+    Reciprocal,
+}
+
+/** How to pick a value to compute a decay function with for fields with multiple values. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultiValueMode {
+    /** Use the value closest to the origin. */
+    Min,
+    /** Use the value furthest from the origin. */
+    Max,
+    /** Use the average distance from the origin. */
+    Avg,
+    /** Use the sum of the distances from the origin. */
+    Sum,
+}
+
+/** A function that multiplies the score by a constant weight. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightFunction {
+    weight: f64,
+    filter: Option<Value>,
+}
+
+impl WeightFunction {
+    /** Only apply the function to hits matching the given filter. */
+    pub fn filter(mut self, filter: impl Into<Value>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+}
+
+/** A function that computes a score from the value of a numeric field. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldValueFactor {
+    field: String,
+    factor: Option<f64>,
+    modifier: Option<FieldValueModifier>,
+    missing: Option<f64>,
+    filter: Option<Value>,
+    weight: Option<f64>,
+}
+
+impl FieldValueFactor {
+    /** Set a factor to multiply the field value by. */
+    pub fn factor(mut self, factor: f64) -> Self {
+        self.factor = Some(factor);
+        self
+    }
+
+    /** Set a modifier to apply to the field value. */
+    pub fn modifier(mut self, modifier: FieldValueModifier) -> Self {
+        self.modifier = Some(modifier);
+        self
+    }
+
+    /** Set the value to use for hits that are missing a value for the field. */
+    pub fn missing(mut self, missing: f64) -> Self {
+        self.missing = Some(missing);
+        self
+    }
+
+    /** Only apply the function to hits matching the given filter. */
+    pub fn filter(mut self, filter: impl Into<Value>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    /** Multiply the computed score by a constant weight. */
+    pub fn weight(mut self, weight: f64) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+}
+
+/** A function that computes a score that decays with the distance of a field value from an origin. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecayFunction {
+    kind: DecayKind,
+    field: String,
+    origin: Value,
+    scale: Value,
+    offset: Option<Value>,
+    decay: Option<f64>,
+    multi_value_mode: Option<MultiValueMode>,
+    filter: Option<Value>,
+    weight: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DecayKind {
+    Gauss,
+    Exp,
+    Linear,
+}
+
+impl DecayFunction {
+    fn new(kind: DecayKind, field: String, origin: Value, scale: Value) -> Self {
+        DecayFunction {
+            kind,
+            field,
+            origin,
+            scale,
+            offset: None,
+            decay: None,
+            multi_value_mode: None,
+            filter: None,
+            weight: None,
+        }
+    }
+
+    /** Only start decaying the score for values further than the offset from the origin. */
+    pub fn offset(mut self, offset: impl Into<Value>) -> Self {
+        self.offset = Some(offset.into());
+        self
+    }
+
+    /** Set the score for values at the `scale` distance from the origin. */
+    pub fn decay(mut self, decay: f64) -> Self {
+        self.decay = Some(decay);
+        self
+    }
+
+    /** Set how to pick a value to compute the score with if the field has multiple values. */
+    pub fn multi_value_mode(mut self, multi_value_mode: MultiValueMode) -> Self {
+        self.multi_value_mode = Some(multi_value_mode);
+        self
+    }
+
+    /** Only apply the function to hits matching the given filter. */
+    pub fn filter(mut self, filter: impl Into<Value>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    /** Multiply the computed score by a constant weight. */
+    pub fn weight(mut self, weight: f64) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+}
+
+/** A function that computes a score with a script. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptScoreFunction {
+    script: Value,
+    filter: Option<Value>,
+    weight: Option<f64>,
+}
+
+impl ScriptScoreFunction {
+    /** Only apply the function to hits matching the given filter. */
+    pub fn filter(mut self, filter: impl Into<Value>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    /** Multiply the computed score by a constant weight. */
+    pub fn weight(mut self, weight: f64) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+}
+
+impl From<WeightFunction> for ScoreFunction {
+    fn from(function: WeightFunction) -> Self {
+        ScoreFunction(ScoreFunctionInner::Weight(function))
+    }
+}
+
+impl From<FieldValueFactor> for ScoreFunction {
+    fn from(function: FieldValueFactor) -> Self {
+        ScoreFunction(ScoreFunctionInner::FieldValueFactor(function))
+    }
+}
+
+impl From<DecayFunction> for ScoreFunction {
+    fn from(function: DecayFunction) -> Self {
+        ScoreFunction(ScoreFunctionInner::Decay(function))
+    }
+}
+
+impl From<ScriptScoreFunction> for ScoreFunction {
+    fn from(function: ScriptScoreFunction) -> Self {
+        ScoreFunction(ScoreFunctionInner::ScriptScore(function))
+    }
+}
+
+fn score_mode(score_mode: ScoreMode) -> Value {
+    match score_mode {
+        ScoreMode::Multiply => "multiply".into(),
+        ScoreMode::Sum => "sum".into(),
+        ScoreMode::Avg => "avg".into(),
+        ScoreMode::First => "first".into(),
+        ScoreMode::Max => "max".into(),
+        ScoreMode::Min => "min".into(),
+    }
+}
+
+fn boost_mode(boost_mode: BoostMode) -> Value {
+    match boost_mode {
+        BoostMode::Multiply => "multiply".into(),
+        BoostMode::Replace => "replace".into(),
+        BoostMode::Sum => "sum".into(),
+        BoostMode::Avg => "avg".into(),
+        BoostMode::Max => "max".into(),
+        BoostMode::Min => "min".into(),
+    }
+}
+
+fn modifier(modifier: FieldValueModifier) -> Value {
+    match modifier {
+        FieldValueModifier::None => "none".into(),
+        FieldValueModifier::Log => "log".into(),
+        FieldValueModifier::Log1p => "log1p".into(),
+        FieldValueModifier::Log2p => "log2p".into(),
+        FieldValueModifier::Ln => "ln".into(),
+        FieldValueModifier::Ln1p => "ln1p".into(),
+        FieldValueModifier::Ln2p => "ln2p".into(),
+        FieldValueModifier::Square => "square".into(),
+        FieldValueModifier::Sqrt => "sqrt".into(),
+        FieldValueModifier::Reciprocal => "reciprocal".into(),
+    }
+}
+
+fn multi_value_mode(multi_value_mode: MultiValueMode) -> Value {
+    match multi_value_mode {
+        MultiValueMode::Min => "min".into(),
+        MultiValueMode::Max => "max".into(),
+        MultiValueMode::Avg => "avg".into(),
+        MultiValueMode::Sum => "sum".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use client::requests::common::ScriptBuilder;
+    use prelude::*;
+
+    #[test]
+    fn match_all_function_score() {
+        let query = FunctionScore::match_all();
+
+        assert_eq!(
+            json!({ "function_score": {} }),
+            serde_json::to_value(query).unwrap()
+        );
+    }
+
+    #[test]
+    fn weight_function() {
+        let query = FunctionScore::new(json!({ "match_all": {} }))
+            .function(ScoreFunction::weight(2.0).filter(json!({ "term": { "featured": true } })))
+            .boost_mode(BoostMode::Replace);
+
+        let expected = json!({
+            "function_score": {
+                "query": { "match_all": {} },
+                "functions": [
+                    {
+                        "filter": { "term": { "featured": true } },
+                        "weight": 2.0
+                    }
+                ],
+                "boost_mode": "replace"
+            }
+        });
+
+        assert_eq!(expected, serde_json::to_value(query).unwrap());
+    }
+
+    #[test]
+    fn field_value_factor_function() {
+        let query = FunctionScore::match_all().function(
+            ScoreFunction::field_value_factor("likes")
+                .factor(1.2)
+                .modifier(FieldValueModifier::Sqrt)
+                .missing(1.0)
+                .weight(0.5),
+        );
+
+        let expected = json!({
+            "function_score": {
+                "functions": [
+                    {
+                        "weight": 0.5,
+                        "field_value_factor": {
+                            "field": "likes",
+                            "factor": 1.2,
+                            "modifier": "sqrt",
+                            "missing": 1.0
+                        }
+                    }
+                ]
+            }
+        });
+
+        assert_eq!(expected, serde_json::to_value(query).unwrap());
+    }
+
+    #[test]
+    fn decay_functions() {
+        let query = FunctionScore::match_all()
+            .function(
+                ScoreFunction::gauss("published", "now", "30d")
+                    .offset("7d")
+                    .decay(0.5),
+            )
+            .function(
+                ScoreFunction::exp("location", json!({ "lat": 1.0, "lon": 2.0 }), "2km")
+                    .multi_value_mode(MultiValueMode::Min),
+            )
+            .function(ScoreFunction::linear("price", 10, 5))
+            .score_mode(ScoreMode::Multiply);
+
+        let expected = json!({
+            "function_score": {
+                "functions": [
+                    {
+                        "gauss": {
+                            "published": {
+                                "origin": "now",
+                                "scale": "30d",
+                                "offset": "7d",
+                                "decay": 0.5
+                            }
+                        }
+                    },
+                    {
+                        "exp": {
+                            "location": {
+                                "origin": { "lat": 1.0, "lon": 2.0 },
+                                "scale": "2km"
+                            },
+                            "multi_value_mode": "min"
+                        }
+                    },
+                    {
+                        "linear": {
+                            "price": {
+                                "origin": 10,
+                                "scale": 5
+                            }
+                        }
+                    }
+                ],
+                "score_mode": "multiply"
+            }
+        });
+
+        assert_eq!(expected, serde_json::to_value(query).unwrap());
+    }
+
+    #[test]
+    fn script_score_function() {
+        let query = FunctionScore::match_all()
+            .function(ScoreFunction::script_score(
+                ScriptBuilder::new("_score * params.factor").param("factor", 2),
+            ))
+            .max_boost(10.0)
+            .min_score(1.0)
+            .boost(2.0);
+
+        let expected = json!({
+            "function_score": {
+                "functions": [
+                    {
+                        "script_score": {
+                            "script": {
+                                "inline": "_score * params.factor",
+                                "params": { "factor": 2 }
+                            }
+                        }
+                    }
+                ],
+                "max_boost": 10.0,
+                "min_score": 1.0,
+                "boost": 2.0
+            }
+        });
+
+        assert_eq!(expected, serde_json::to_value(query).unwrap());
+    }
+}
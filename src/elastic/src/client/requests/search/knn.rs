@@ -0,0 +1,149 @@
+/*!
+Builders for [k-nearest neighbour][docs-knn] searches.
+
+[docs-knn]: https://www.elastic.co/guide/en/elasticsearch/reference/current/knn-search.html
+*/
+
+use serde_json::Value;
+
+use super::object;
+
+/**
+A k-nearest neighbour search over a `dense_vector` field.
+
+The `k` closest documents to the query vector are returned as hits, scored by their similarity to the vector.
+A kNN search is attached to a search request with [`knn`][knn].
+
+# Examples
+
+Find the 10 products most similar to an embedding, out of 100 candidates per shard:
+
+```
+# extern crate elastic;
+# #[macro_use] extern crate serde_json;
+# use elastic::prelude::*;
+# fn main() {
+let knn = Knn::new("embedding", vec![0.12, -0.4, 0.83], 10, 100)
+    .filter(json!({ "term": { "in_stock": true } }));
+# }
+```
+
+[knn]: type.SearchRequestBuilder.html#method.knn
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct Knn {
+    field: String,
+    query_vector: Vec<f32>,
+    k: u32,
+    num_candidates: u32,
+    filter: Option<Value>,
+    similarity: Option<f32>,
+    boost: Option<f32>,
+}
+
+impl Knn {
+    /**
+    Search for the `k` nearest neighbours to a vector.
+
+    The `num_candidates` is the number of nearest neighbour candidates to consider on each shard.
+    It must be at least `k`, and increasing it improves accuracy at the cost of speed.
+    */
+    pub fn new(
+        field: impl Into<String>,
+        query_vector: impl Into<Vec<f32>>,
+        k: u32,
+        num_candidates: u32,
+    ) -> Self {
+        Knn {
+            field: field.into(),
+            query_vector: query_vector.into(),
+            k,
+            num_candidates,
+            filter: None,
+            similarity: None,
+            boost: None,
+        }
+    }
+
+    /**
+    Only consider documents that match a query.
+
+    The filter is applied while searching for neighbours, so `k` hits are still returned if enough documents match.
+    */
+    pub fn filter(mut self, filter: impl Into<Value>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    /** Only return hits that are at least this similar to the query vector. */
+    pub fn similarity(mut self, similarity: f32) -> Self {
+        self.similarity = Some(similarity);
+        self
+    }
+
+    /** Set a boost for the scores of hits when combined with a query. */
+    pub fn boost(mut self, boost: f32) -> Self {
+        self.boost = Some(boost);
+        self
+    }
+
+    pub(crate) fn to_value(&self) -> Value {
+        object(vec![
+            ("field", Some(self.field.as_str().into())),
+            ("query_vector", Some(self.query_vector.clone().into())),
+            ("k", Some(self.k.into())),
+            ("num_candidates", Some(self.num_candidates.into())),
+            ("filter", self.filter.clone()),
+            ("similarity", self.similarity.map(Value::from)),
+            ("boost", self.boost.map(Value::from)),
+        ])
+    }
+}
+
+impl From<Knn> for Value {
+    fn from(knn: Knn) -> Self {
+        knn.to_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use prelude::*;
+
+    #[test]
+    fn knn() {
+        let knn: Value = Knn::new("embedding", vec![0.5, -1.0], 5, 50).into();
+
+        let expected = json!({
+            "field": "embedding",
+            "query_vector": [0.5, -1.0],
+            "k": 5,
+            "num_candidates": 50
+        });
+
+        assert_eq!(expected, knn);
+    }
+
+    #[test]
+    fn knn_options() {
+        let knn: Value = Knn::new("embedding", vec![1.0], 5, 50)
+            .filter(json!({ "term": { "in_stock": true } }))
+            .similarity(0.5)
+            .boost(2.0)
+            .into();
+
+        let expected = json!({
+            "field": "embedding",
+            "query_vector": [1.0],
+            "k": 5,
+            "num_candidates": 50,
+            "filter": { "term": { "in_stock": true } },
+            "similarity": 0.5,
+            "boost": 2.0
+        });
+
+        assert_eq!(expected, knn);
+    }
+}
@@ -0,0 +1,401 @@
+/*!
+Builders for [`simple_query_string`][docs-simple-query-string] queries and escaping [`query_string`][docs-query-string] input.
+
+[docs-simple-query-string]: https://www.elastic.co/guide/en/elasticsearch/reference/current/query-dsl-simple-query-string-query.html
+[docs-query-string]: https://www.elastic.co/guide/en/elasticsearch/reference/current/query-dsl-query-string-query.html
+*/
+
+use serde::ser::{
+    Serialize,
+    Serializer,
+};
+use serde_json::Value;
+
+use super::object;
+
+/**
+Escape the reserved characters in user input for a `query_string` query.
+
+Reserved characters are escaped with a backslash so they're matched literally instead of being parsed as query syntax.
+The `<` and `>` characters can't be escaped, so they're removed.
+
+# Examples
+
+```
+# extern crate elastic;
+# use elastic::prelude::*;
+# fn main() {
+assert_eq!("\\(1 \\+ 1\\) \\= 2", escape_query_string("(1 + 1) = 2"));
+assert_eq!("a  b", escape_query_string("a <> b"));
+# }
+```
+*/
+pub fn escape_query_string(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+
+    for c in input.chars() {
+        match c {
+            '<' | '>' => (),
+            '+' | '-' | '=' | '&' | '|' | '!' | '(' | ')' | '{' | '}' | '[' | ']' | '^' | '"'
+            | '~' | '*' | '?' | ':' | '\\' | '/' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/**
+A `simple_query_string` query.
+
+The simple query string syntax never throws an error for invalid input, so it's a good fit for user-facing search boxes.
+The operators users can use are limited to the given [`flags`][SimpleQueryString.flags].
+
+# Examples
+
+Search the title and body of documents, only allowing phrases and prefixes:
+
+```
+# extern crate elastic;
+# use elastic::prelude::*;
+# fn main() {
+let query = SimpleQueryString::new("\"fried eggs\" potat*")
+    .field("title^2")
+    .field("body")
+    .default_operator(Operator::And)
+    .flags(vec![SimpleQueryStringFlag::Phrase, SimpleQueryStringFlag::Prefix]);
+# }
+```
+
+[SimpleQueryString.flags]: #method.flags
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimpleQueryString {
+    query: String,
+    fields: Vec<String>,
+    default_operator: Option<Operator>,
+    flags: Option<Vec<SimpleQueryStringFlag>>,
+    analyzer: Option<String>,
+    analyze_wildcard: Option<bool>,
+    lenient: Option<bool>,
+    minimum_should_match: Option<String>,
+    quote_field_suffix: Option<String>,
+    boost: Option<f64>,
+}
+
+impl SimpleQueryString {
+    /** Create a `simple_query_string` query for the given input. */
+    pub fn new(query: impl Into<String>) -> Self {
+        SimpleQueryString {
+            query: query.into(),
+            fields: vec![],
+            default_operator: None,
+            flags: None,
+            analyzer: None,
+            analyze_wildcard: None,
+            lenient: None,
+            minimum_should_match: None,
+            quote_field_suffix: None,
+            boost: None,
+        }
+    }
+
+    /**
+    Add a field to search.
+
+    The field can include a boost, like `title^2`, or a wildcard, like `title.*`.
+    If no fields are added then the index's default fields are searched.
+    */
+    pub fn field(mut self, field: impl Into<String>) -> Self {
+        self.fields.push(field.into());
+        self
+    }
+
+    /** Add fields to search. */
+    pub fn fields<I>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.fields.extend(fields.into_iter().map(Into::into));
+        self
+    }
+
+    /** Set the operator used to combine terms that don't have an explicit operator. */
+    pub fn default_operator(mut self, operator: Operator) -> Self {
+        self.default_operator = Some(operator);
+        self
+    }
+
+    /**
+    Set the operators that are enabled in the query syntax.
+
+    Any syntax for operators that aren't enabled is searched as plain text.
+    */
+    pub fn flags<I>(mut self, flags: I) -> Self
+    where
+        I: IntoIterator<Item = SimpleQueryStringFlag>,
+    {
+        self.flags = Some(flags.into_iter().collect());
+        self
+    }
+
+    /** Set the analyzer used to convert the query text into tokens. */
+    pub fn analyzer(mut self, analyzer: impl Into<String>) -> Self {
+        self.analyzer = Some(analyzer.into());
+        self
+    }
+
+    /** Set whether terms with a wildcard are analyzed. */
+    pub fn analyze_wildcard(mut self, analyze_wildcard: bool) -> Self {
+        self.analyze_wildcard = Some(analyze_wildcard);
+        self
+    }
+
+    /** Set whether errors from searching fields with a mismatched type, like text in a numeric field, are ignored. */
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = Some(lenient);
+        self
+    }
+
+    /** Set the minimum number of terms that must match, like `2` or `75%`. */
+    pub fn minimum_should_match(mut self, minimum_should_match: impl Into<String>) -> Self {
+        self.minimum_should_match = Some(minimum_should_match.into());
+        self
+    }
+
+    /** Set a suffix to append to fields for quoted text in the query, like `.exact`. */
+    pub fn quote_field_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.quote_field_suffix = Some(suffix.into());
+        self
+    }
+
+    /** Set a boost for the query. */
+    pub fn boost(mut self, boost: f64) -> Self {
+        self.boost = Some(boost);
+        self
+    }
+
+    pub(crate) fn to_value(&self) -> Value {
+        let fields = if self.fields.is_empty() {
+            None
+        } else {
+            Some(self.fields.clone().into())
+        };
+
+        let flags = self.flags.as_ref().map(|flags| {
+            if flags.is_empty() {
+                "NONE".into()
+            } else {
+                flags
+                    .iter()
+                    .map(|flag| flag.as_str())
+                    .collect::<Vec<_>>()
+                    .join("|")
+                    .into()
+            }
+        });
+
+        let options = object(vec![
+            ("query", Some(self.query.as_str().into())),
+            ("fields", fields),
+            (
+                "default_operator",
+                self.default_operator.map(|op| op.as_str().into()),
+            ),
+            ("flags", flags),
+            (
+                "analyzer",
+                self.analyzer.as_ref().map(|a| a.as_str().into()),
+            ),
+            ("analyze_wildcard", self.analyze_wildcard.map(Value::from)),
+            ("lenient", self.lenient.map(Value::from)),
+            (
+                "minimum_should_match",
+                self.minimum_should_match
+                    .as_ref()
+                    .map(|m| m.as_str().into()),
+            ),
+            (
+                "quote_field_suffix",
+                self.quote_field_suffix.as_ref().map(|s| s.as_str().into()),
+            ),
+            ("boost", self.boost.map(Value::from)),
+        ]);
+
+        object(vec![("simple_query_string", Some(options))])
+    }
+}
+
+impl Serialize for SimpleQueryString {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_value().serialize(serializer)
+    }
+}
+
+impl From<SimpleQueryString> for Value {
+    fn from(query: SimpleQueryString) -> Self {
+        query.to_value()
+    }
+}
+
+/** The operator used to combine terms in a query. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    /** All terms must match. */
+    And,
+    /** Any term can match. */
+    Or,
+}
+
+impl Operator {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            Operator::And => "AND",
+            Operator::Or => "OR",
+        }
+    }
+}
+
+/** An operator that can be enabled in a `simple_query_string` query. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimpleQueryStringFlag {
+    /** Enable all operators. */
+    All,
+    /** `+` for AND. */
+    And,
+    /** `\` to escape other operators. */
+    Escape,
+    /** `~N` after a word for fuzziness. */
+    Fuzzy,
+    /** `~N` after a phrase for slop. Same as `Slop`. */
+    Near,
+    /** `-` to negate a term. */
+    Not,
+    /** `|` for OR. */
+    Or,
+    /** `"` to search for phrases. */
+    Phrase,
+    /** `(` and `)` for precedence. */
+    Precedence,
+    /** `*` at the end of a term for prefixes. */
+    Prefix,
+    /** `~N` after a phrase for slop. Same as `Near`. */
+    Slop,
+    /** Whitespace to split terms. */
+    Whitespace,
+}
+
+impl SimpleQueryStringFlag {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            SimpleQueryStringFlag::All => "ALL",
+            SimpleQueryStringFlag::And => "AND",
+            SimpleQueryStringFlag::Escape => "ESCAPE",
+            SimpleQueryStringFlag::Fuzzy => "FUZZY",
+            SimpleQueryStringFlag::Near => "NEAR",
+            SimpleQueryStringFlag::Not => "NOT",
+            SimpleQueryStringFlag::Or => "OR",
+            SimpleQueryStringFlag::Phrase => "PHRASE",
+            SimpleQueryStringFlag::Precedence => "PRECEDENCE",
+            SimpleQueryStringFlag::Prefix => "PREFIX",
+            SimpleQueryStringFlag::Slop => "SLOP",
+            SimpleQueryStringFlag::Whitespace => "WHITESPACE",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use prelude::*;
+
+    #[test]
+    fn escape_reserved_characters() {
+        let escaped = escape_query_string(r#"a+b-c=d&&e||f!g(h)i{j}k[l]m^n"o~p*q?r:s\t/u"#);
+
+        assert_eq!(
+            r#"a\+b\-c\=d\&\&e\|\|f\!g\(h\)i\{j\}k\[l\]m\^n\"o\~p\*q\?r\:s\\t\/u"#,
+            escaped
+        );
+    }
+
+    #[test]
+    fn escape_removes_angle_brackets() {
+        assert_eq!("age  18", escape_query_string("age >< 18"));
+    }
+
+    #[test]
+    fn escape_plain_text() {
+        assert_eq!("fried eggs", escape_query_string("fried eggs"));
+    }
+
+    #[test]
+    fn simple_query_string() {
+        let query = SimpleQueryString::new("fried eggs");
+
+        let expected = json!({
+            "simple_query_string": {
+                "query": "fried eggs"
+            }
+        });
+
+        assert_eq!(expected, serde_json::to_value(query).unwrap());
+    }
+
+    #[test]
+    fn simple_query_string_options() {
+        let query = SimpleQueryString::new("\"fried eggs\" potat*")
+            .field("title^2")
+            .fields(vec!["body", "tags"])
+            .default_operator(Operator::And)
+            .flags(vec![
+                SimpleQueryStringFlag::Phrase,
+                SimpleQueryStringFlag::Prefix,
+            ])
+            .analyzer("english")
+            .analyze_wildcard(true)
+            .lenient(true)
+            .minimum_should_match("75%")
+            .quote_field_suffix(".exact")
+            .boost(1.5);
+
+        let expected = json!({
+            "simple_query_string": {
+                "query": "\"fried eggs\" potat*",
+                "fields": ["title^2", "body", "tags"],
+                "default_operator": "AND",
+                "flags": "PHRASE|PREFIX",
+                "analyzer": "english",
+                "analyze_wildcard": true,
+                "lenient": true,
+                "minimum_should_match": "75%",
+                "quote_field_suffix": ".exact",
+                "boost": 1.5
+            }
+        });
+
+        assert_eq!(expected, serde_json::to_value(query).unwrap());
+    }
+
+    #[test]
+    fn simple_query_string_no_flags() {
+        let query = SimpleQueryString::new("a+b").flags(vec![]);
+
+        let expected = json!({
+            "simple_query_string": {
+                "query": "a+b",
+                "flags": "NONE"
+            }
+        });
+
+        assert_eq!(expected, serde_json::to_value(query).unwrap());
+    }
+}
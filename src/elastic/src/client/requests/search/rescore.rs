@@ -0,0 +1,179 @@
+/*!
+Builders for [rescoring][docs-rescore] search hits.
+
+[docs-rescore]: https://www.elastic.co/guide/en/elasticsearch/reference/current/search-request-rescore.html
+*/
+
+use serde_json::Value;
+
+use super::{
+    object,
+    FunctionScore,
+    SimpleQueryString,
+};
+
+/**
+A query used to rescore the top hits of a search request.
+
+Rescoring is attached to a search request with [`rescore`][rescore].
+Any query can be converted into a `RescoreQuery` with the default weights.
+
+# Examples
+
+Rescore the top hits with a phrase query, weighting its score more highly than the original query:
+
+```
+# extern crate elastic;
+# #[macro_use] extern crate serde_json;
+# use elastic::prelude::*;
+# fn main() {
+let rescore = RescoreQuery::new(json!({ "match_phrase": { "title": "fried eggs" } }))
+    .query_weight(0.7)
+    .rescore_query_weight(1.2)
+    .score_mode(RescoreMode::Total);
+# }
+```
+
+[rescore]: type.SearchRequestBuilder.html#method.rescore
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct RescoreQuery {
+    query: Value,
+    query_weight: Option<f64>,
+    rescore_query_weight: Option<f64>,
+    score_mode: Option<RescoreMode>,
+}
+
+impl RescoreQuery {
+    /** Rescore hits with the given query. */
+    pub fn new(query: impl Into<Value>) -> Self {
+        RescoreQuery {
+            query: query.into(),
+            query_weight: None,
+            rescore_query_weight: None,
+            score_mode: None,
+        }
+    }
+
+    /** Set the weight of the original query's score. */
+    pub fn query_weight(mut self, weight: f64) -> Self {
+        self.query_weight = Some(weight);
+        self
+    }
+
+    /** Set the weight of the rescore query's score. */
+    pub fn rescore_query_weight(mut self, weight: f64) -> Self {
+        self.rescore_query_weight = Some(weight);
+        self
+    }
+
+    /** Set how the original and rescore query scores are combined. */
+    pub fn score_mode(mut self, score_mode: RescoreMode) -> Self {
+        self.score_mode = Some(score_mode);
+        self
+    }
+
+    pub(crate) fn to_value(&self, window_size: u32) -> Value {
+        let query = object(vec![
+            ("rescore_query", Some(self.query.clone())),
+            ("query_weight", self.query_weight.map(Value::from)),
+            (
+                "rescore_query_weight",
+                self.rescore_query_weight.map(Value::from),
+            ),
+            (
+                "score_mode",
+                self.score_mode.map(|mode| mode.as_str().into()),
+            ),
+        ]);
+
+        object(vec![
+            ("window_size", Some(window_size.into())),
+            ("query", Some(query)),
+        ])
+    }
+}
+
+impl From<Value> for RescoreQuery {
+    fn from(query: Value) -> Self {
+        RescoreQuery::new(query)
+    }
+}
+
+impl From<FunctionScore> for RescoreQuery {
+    fn from(query: FunctionScore) -> Self {
+        RescoreQuery::new(query)
+    }
+}
+
+impl From<SimpleQueryString> for RescoreQuery {
+    fn from(query: SimpleQueryString) -> Self {
+        RescoreQuery::new(query)
+    }
+}
+
+/** How the scores of the original and rescore queries are combined. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RescoreMode {
+    /** Add the scores. */
+    Total,
+    /** Multiply the scores. */
+    Multiply,
+    /** Average the scores. */
+    Avg,
+    /** Use the highest score. */
+    Max,
+    /** Use the lowest score. */
+    Min,
+}
+
+impl RescoreMode {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            RescoreMode::Total => "total",
+            RescoreMode::Multiply => "multiply",
+            RescoreMode::Avg => "avg",
+            RescoreMode::Max => "max",
+            RescoreMode::Min => "min",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prelude::*;
+
+    #[test]
+    fn rescore_query() {
+        let rescore = RescoreQuery::from(json!({ "match_phrase": { "title": "eggs" } }));
+
+        let expected = json!({
+            "window_size": 50,
+            "query": {
+                "rescore_query": { "match_phrase": { "title": "eggs" } }
+            }
+        });
+
+        assert_eq!(expected, rescore.to_value(50));
+    }
+
+    #[test]
+    fn rescore_query_options() {
+        let rescore = RescoreQuery::new(json!({ "match_phrase": { "title": "eggs" } }))
+            .query_weight(0.7)
+            .rescore_query_weight(1.2)
+            .score_mode(RescoreMode::Multiply);
+
+        let expected = json!({
+            "window_size": 10,
+            "query": {
+                "rescore_query": { "match_phrase": { "title": "eggs" } },
+                "query_weight": 0.7,
+                "rescore_query_weight": 1.2,
+                "score_mode": "multiply"
+            }
+        });
+
+        assert_eq!(expected, rescore.to_value(10));
+    }
+}
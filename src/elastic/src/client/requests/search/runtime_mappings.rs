@@ -0,0 +1,159 @@
+/*!
+Builders for [runtime fields][docs-runtime] defined in a search request.
+
+[docs-runtime]: https://www.elastic.co/guide/en/elasticsearch/reference/current/runtime-search-request.html
+*/
+
+use serde_json::{
+    self,
+    Value,
+};
+
+use super::object;
+use client::requests::common::{
+    DefaultParams,
+    ScriptBuilder,
+};
+
+/**
+A field that's computed when a search request runs, instead of being indexed.
+
+Runtime fields are attached to a search request with [`runtime_mapping`][runtime_mapping].
+Once defined, they can be used like any other field in the query, aggregations, sorts and [`fields`][fields] of the request.
+
+# Examples
+
+Compute the day of the week from a timestamp:
+
+```
+# extern crate elastic;
+# use elastic::prelude::*;
+# fn main() {
+let day_of_week = RuntimeField::new(
+    RuntimeFieldType::Keyword,
+    "emit(doc['timestamp'].value.dayOfWeekEnum.getDisplayName(TextStyle.FULL, Locale.ROOT))",
+);
+# }
+```
+
+[runtime_mapping]: type.SearchRequestBuilder.html#method.runtime_mapping
+[fields]: type.SearchRequestBuilder.html#method.fields
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeField {
+    ty: RuntimeFieldType,
+    script: Option<Value>,
+    format: Option<String>,
+}
+
+impl RuntimeField {
+    /**
+    Compute the value of a field with a painless script.
+
+    The script emits values for the field using `emit`.
+    */
+    pub fn new(ty: RuntimeFieldType, script: impl Into<ScriptBuilder<DefaultParams>>) -> Self {
+        let script = serde_json::to_value(script.into()).unwrap_or(Value::Null);
+
+        RuntimeField {
+            ty,
+            script: Some(script),
+            format: None,
+        }
+    }
+
+    /**
+    Read the value of a field from the `_source` of each document.
+
+    This can be used to search a field in `_source` that isn't mapped, or to override the type of a mapped field.
+    */
+    pub fn from_source(ty: RuntimeFieldType) -> Self {
+        RuntimeField {
+            ty,
+            script: None,
+            format: None,
+        }
+    }
+
+    /** Set the format used to parse and display values for a `date` field. */
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+
+    pub(crate) fn to_value(&self) -> Value {
+        object(vec![
+            ("type", Some(self.ty.as_str().into())),
+            ("script", self.script.clone()),
+            ("format", self.format.as_ref().map(|f| f.as_str().into())),
+        ])
+    }
+}
+
+/** The type of value a runtime field contains. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuntimeFieldType {
+    /** A `boolean` field. */
+    Boolean,
+    /** A `date` field. */
+    Date,
+    /** A `double` field. */
+    Double,
+    /** A `geo_point` field. */
+    GeoPoint,
+    /** An `ip` field. */
+    Ip,
+    /** A `keyword` field. */
+    Keyword,
+    /** A `long` field. */
+    Long,
+}
+
+impl RuntimeFieldType {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            RuntimeFieldType::Boolean => "boolean",
+            RuntimeFieldType::Date => "date",
+            RuntimeFieldType::Double => "double",
+            RuntimeFieldType::GeoPoint => "geo_point",
+            RuntimeFieldType::Ip => "ip",
+            RuntimeFieldType::Keyword => "keyword",
+            RuntimeFieldType::Long => "long",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prelude::*;
+
+    #[test]
+    fn runtime_field_script() {
+        let field = RuntimeField::new(
+            RuntimeFieldType::Long,
+            ScriptBuilder::new("emit(doc['price'].value * params.factor)").param("factor", 2),
+        );
+
+        let expected = json!({
+            "type": "long",
+            "script": {
+                "inline": "emit(doc['price'].value * params.factor)",
+                "params": { "factor": 2 }
+            }
+        });
+
+        assert_eq!(expected, field.to_value());
+    }
+
+    #[test]
+    fn runtime_field_from_source() {
+        let field = RuntimeField::from_source(RuntimeFieldType::Date).format("yyyy-MM-dd");
+
+        let expected = json!({
+            "type": "date",
+            "format": "yyyy-MM-dd"
+        });
+
+        assert_eq!(expected, field.to_value());
+    }
+}
@@ -0,0 +1,670 @@
+use std::{
+    collections::VecDeque,
+    marker::PhantomData,
+    time::Duration,
+};
+
+use fluent_builder::SharedFluentBuilder;
+use futures::{
+    Async,
+    Future,
+    IntoFuture,
+    Poll,
+    Stream,
+};
+use serde::{
+    de::DeserializeOwned,
+    ser::Serialize,
+};
+use serde_json::{
+    self,
+    Map,
+    Value,
+};
+
+use super::SearchRequestBuilder;
+use client::{
+    requests::{
+        endpoints::{
+            ClearScrollRequest,
+            ScrollRequest,
+            SearchRequest,
+        },
+        params::{
+            Index,
+            Type,
+        },
+        Endpoint,
+    },
+    responses::{
+        search::Hit,
+        SearchResponse,
+    },
+    sender::AsyncSender,
+    Client,
+    RequestParams,
+};
+use error::{
+    self,
+    Error,
+};
+use http::Method;
+
+const DEFAULT_KEEP_ALIVE_SECS: u64 = 60;
+
+/**
+A builder for a [`ScrollStream`][ScrollStream].
+
+Call [`scroll`][scroll] on an asynchronous search request builder to get a `ScrollStreamBuilder`.
+
+[ScrollStream]: struct.ScrollStream.html
+[scroll]: type.SearchRequestBuilder.html#method.scroll
+*/
+pub struct ScrollStreamBuilder<TDocument> {
+    client: Client<AsyncSender>,
+    params_builder: SharedFluentBuilder<RequestParams>,
+    index: Index<'static>,
+    ty: Option<Type<'static>>,
+    body: Result<Value, Error>,
+    keep_alive: Duration,
+    recover: bool,
+    _marker: PhantomData<TDocument>,
+}
+
+/**
+# Scroll a search
+
+Stream every hit matching a search instead of a single page.
+*/
+impl<TDocument, TBody> SearchRequestBuilder<AsyncSender, TDocument, TBody>
+where
+    TDocument: DeserializeOwned + Send + 'static,
+    TBody: Serialize,
+{
+    /**
+    Create a [`ScrollStreamBuilder`][ScrollStreamBuilder] that streams every hit matching this search.
+
+    The search body must serialize to a JSON object.
+    Use a `size` in the body to control the number of hits fetched in each page.
+
+    # Examples
+
+    Export all documents from an index, recovering from expired search contexts:
+
+    ```no_run
+    # extern crate tokio;
+    # extern crate futures;
+    # #[macro_use] extern crate serde_json;
+    # extern crate elastic;
+    # use futures::Stream;
+    # use serde_json::Value;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = AsyncClientBuilder::new().build()?;
+    let hits = client.search::<Value>()
+                     .index("myindex")
+                     .body(json!({
+                         "size": 1000,
+                         "sort": ["_doc"]
+                     }))
+                     .scroll()
+                     .recover_with_point_in_time()
+                     .build();
+
+    let export = hits.for_each(|hit| {
+        println!("{:?}", hit.document());
+
+        Ok(())
+    });
+    # Ok(())
+    # }
+    ```
+
+    [ScrollStreamBuilder]: struct.ScrollStreamBuilder.html
+    */
+    pub fn scroll(self) -> ScrollStreamBuilder<TDocument> {
+        let body = serde_json::to_value(&self.inner.body)
+            .map_err(error::request)
+            .and_then(|body| match body {
+                Value::Object(_) => Ok(body),
+                Value::Null => Ok(Value::Object(Map::new())),
+                _ => Err(error::request(error::message(
+                    "the body of a scrolled search must be a JSON object",
+                ))),
+            });
+
+        ScrollStreamBuilder {
+            client: self.client,
+            params_builder: self.params_builder,
+            index: self.inner.index.unwrap_or_else(|| "_all".into()),
+            ty: self.inner.ty,
+            body,
+            keep_alive: Duration::from_secs(DEFAULT_KEEP_ALIVE_SECS),
+            recover: false,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<TDocument> ScrollStreamBuilder<TDocument>
+where
+    TDocument: DeserializeOwned + Send + 'static,
+{
+    /**
+    Specify how long the search context is kept alive between pages.
+
+    The default is `60s`.
+    */
+    pub fn keep_alive(mut self, keep_alive: Duration) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /**
+    Restart the stream using a point-in-time if the scroll context goes missing.
+
+    Scroll contexts can expire when a consumer takes longer than the keep-alive to process a page, or be lost when a node restarts.
+    When that happens Elasticsearch returns a `search_context_missing_exception`.
+    With recovery enabled, the stream opens a [point-in-time][docs-pit] and continues with [`search_after`][docs-search-after] from the sort values of the last emitted hit instead of returning an error.
+
+    The search body needs a `sort` that uniquely orders the hits for recovery to resume in the right place.
+
+    [docs-pit]: https://www.elastic.co/guide/en/elasticsearch/reference/current/point-in-time-api.html
+    [docs-search-after]: https://www.elastic.co/guide/en/elasticsearch/reference/current/paginate-search-results.html#search-after
+    */
+    pub fn recover_with_point_in_time(mut self) -> Self {
+        self.recover = true;
+        self
+    }
+
+    /**
+    Build a stream of hits.
+
+    The first page is fetched when the stream is first polled.
+    Every page is fetched from the same node.
+    */
+    pub fn build(self) -> ScrollStream<TDocument> {
+        let ScrollStreamBuilder {
+            client,
+            params_builder,
+            index,
+            ty,
+            body,
+            keep_alive,
+            recover,
+            ..
+        } = self;
+
+        let keep_alive = format!("{}s", u64::max(keep_alive.as_secs(), 1));
+
+        let body = match body {
+            Ok(body) => body,
+            Err(e) => {
+                return ScrollStream {
+                    state: ScrollState::Failed(Some(e)),
+                    ctx: ScrollContext::new(client, index, Value::Null, keep_alive, recover),
+                }
+            }
+        };
+
+        let first_page = {
+            let client = client.clone();
+            let index = index.clone();
+            let body = body.clone();
+            let keep_alive = keep_alive.clone();
+
+            client
+                .addresses
+                .resolve(params_builder)
+                .compat()
+                .and_then(move |params| {
+                    let req = match ty {
+                        Some(ty) => SearchRequest::for_index_ty(index, ty, body),
+                        None => SearchRequest::for_index(index, body),
+                    };
+
+                    send_page(
+                        &client,
+                        params.clone().url_param("scroll", keep_alive),
+                        req,
+                    )
+                    .map(move |page| (params, page))
+                })
+        };
+
+        ScrollStream {
+            state: ScrollState::Pending(Box::new(first_page)),
+            ctx: ScrollContext::new(client, index, body, keep_alive, recover),
+        }
+    }
+}
+
+/**
+A stream of hits from a scrolled search.
+
+Pages of hits are fetched as the stream is polled.
+The search context is cleared once every hit has been emitted.
+
+If the stream is dropped before it completes then the search context is left to expire.
+*/
+pub struct ScrollStream<TDocument> {
+    state: ScrollState<TDocument>,
+    ctx: ScrollContext<TDocument>,
+}
+
+type PendingPage<TDocument> =
+    Box<Future<Item = (RequestParams, SearchResponse<TDocument>), Error = Error> + Send>;
+
+enum ScrollState<TDocument> {
+    Pending(PendingPage<TDocument>),
+    Emitting,
+    Clearing(Box<Future<Item = (), Error = ()> + Send>),
+    Failed(Option<Error>),
+    Done,
+}
+
+enum SearchContextId {
+    Scroll(String),
+    PointInTime(String),
+}
+
+struct ScrollContext<TDocument> {
+    client: Client<AsyncSender>,
+    index: Index<'static>,
+    body: Value,
+    keep_alive: String,
+    recover: bool,
+    params: Option<RequestParams>,
+    id: Option<SearchContextId>,
+    hits: VecDeque<Hit<TDocument>>,
+    emitted: bool,
+    last_sort: Option<Vec<Value>>,
+    exhausted: bool,
+}
+
+impl<TDocument> ScrollContext<TDocument>
+where
+    TDocument: DeserializeOwned + Send + 'static,
+{
+    fn new(
+        client: Client<AsyncSender>,
+        index: Index<'static>,
+        body: Value,
+        keep_alive: String,
+        recover: bool,
+    ) -> Self {
+        ScrollContext {
+            client,
+            index,
+            body,
+            keep_alive,
+            recover,
+            params: None,
+            id: None,
+            hits: VecDeque::new(),
+            emitted: false,
+            last_sort: None,
+            exhausted: false,
+        }
+    }
+
+    /** Buffer the hits from a page and keep track of the latest search context id. */
+    fn receive(&mut self, params: RequestParams, page: SearchResponse<TDocument>) {
+        self.id = match (page.scroll_id(), page.pit_id()) {
+            (Some(id), _) => Some(SearchContextId::Scroll(id.to_owned())),
+            (None, Some(id)) => Some(SearchContextId::PointInTime(id.to_owned())),
+            (None, None) => self.id.take(),
+        };
+        self.params = Some(params);
+
+        self.hits.extend(page.into_hits());
+        self.exhausted = self.hits.is_empty();
+    }
+
+    fn params(&self) -> RequestParams {
+        self.params.clone().unwrap_or_default()
+    }
+
+    /** Fetch the next page for the current search context. */
+    fn next_page(&self) -> Result<PendingPage<TDocument>, Error> {
+        match self.id {
+            Some(SearchContextId::Scroll(ref scroll_id)) => {
+                let params = self.params();
+                let req = ScrollRequest::new(object(vec![
+                    ("scroll", self.keep_alive.clone().into()),
+                    ("scroll_id", scroll_id.clone().into()),
+                ]));
+
+                Ok(Box::new(
+                    send_page(&self.client, params.clone(), req).map(move |page| (params, page)),
+                ))
+            }
+            Some(SearchContextId::PointInTime(ref pit_id)) => {
+                let last_sort = self.last_sort.clone().ok_or_else(|| {
+                    error::request(error::message(
+                        "the hits of a point-in-time search must have sort values to fetch the next page",
+                    ))
+                })?;
+
+                Ok(self.pit_page(pit_id.to_owned(), Some(last_sort)))
+            }
+            None => Err(error::request(error::message(
+                "the search didn't return a scroll id",
+            ))),
+        }
+    }
+
+    /** Open a point-in-time and continue from the last emitted hit. */
+    fn recover(&self) -> Result<PendingPage<TDocument>, Error> {
+        let search_after = match (self.emitted, self.last_sort.clone()) {
+            (false, _) => None,
+            (true, Some(last_sort)) => Some(last_sort),
+            (true, None) => {
+                return Err(error::request(error::message(
+                    "the search context is missing and the last hit has no sort values to resume from",
+                )))
+            }
+        };
+
+        debug!("Elasticsearch Scroll: search context missing, resuming with a point-in-time");
+
+        let params = self.params();
+        let open_pit = Endpoint {
+            url: format!("/{}/_pit", &*self.index).into(),
+            method: Method::POST,
+            body: None,
+            name: "open_point_in_time",
+        };
+
+        let client = self.client.clone();
+        let body = self.body.clone();
+        let keep_alive = self.keep_alive.clone();
+
+        let page = client
+            .request::<_, Vec<u8>>(open_pit)
+            .params(params.clone().url_param("keep_alive", keep_alive.clone()))
+            .send()
+            .compat()
+            .and_then(|res| res.into_response::<Value>().compat())
+            .and_then(move |res| {
+                let pit_id = res
+                    .get("id")
+                    .and_then(Value::as_str)
+                    .map(str::to_owned)
+                    .ok_or_else(|| {
+                        error::request(error::message("the point-in-time response has no id"))
+                    })?;
+
+                Ok(pit_search(body, &pit_id, &keep_alive, search_after))
+            })
+            .and_then(move |req| send_page(&client, params.clone(), req).map(move |page| (params, page)));
+
+        Ok(Box::new(page))
+    }
+
+    fn pit_page(&self, pit_id: String, search_after: Option<Vec<Value>>) -> PendingPage<TDocument> {
+        let params = self.params();
+        let req = pit_search(self.body.clone(), &pit_id, &self.keep_alive, search_after);
+
+        Box::new(send_page(&self.client, params.clone(), req).map(move |page| (params, page)))
+    }
+
+    /** Release the search context once all hits have been emitted. */
+    fn clear(&mut self) -> Box<Future<Item = (), Error = ()> + Send> {
+        let params = self.params();
+
+        let res = match self.id.take() {
+            Some(SearchContextId::Scroll(scroll_id)) => {
+                let req = ClearScrollRequest::new(body_bytes(object(vec![(
+                    "scroll_id",
+                    vec![scroll_id].into(),
+                )])));
+
+                self.client.request(req).params(params).send()
+            }
+            Some(SearchContextId::PointInTime(pit_id)) => {
+                let req = Endpoint {
+                    url: "/_pit".into(),
+                    method: Method::DELETE,
+                    body: Some(body_bytes(object(vec![("id", pit_id.into())]))),
+                    name: "close_point_in_time",
+                };
+
+                self.client.request(req).params(params).send()
+            }
+            None => return Box::new(Ok(()).into_future()),
+        };
+
+        Box::new(res.compat().then(|res| {
+            if let Err(e) = res {
+                warn!(
+                    "Elasticsearch Scroll: failed to clear search context. Caused by: {}",
+                    e
+                );
+            }
+
+            Ok(())
+        }))
+    }
+}
+
+impl<TDocument> Stream for ScrollStream<TDocument>
+where
+    TDocument: DeserializeOwned + Send + 'static,
+{
+    type Item = Hit<TDocument>;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            let state = match self.state {
+                ScrollState::Emitting => {
+                    if let Some(hit) = self.ctx.hits.pop_front() {
+                        self.ctx.emitted = true;
+                        self.ctx.last_sort = hit.sort().map(<[Value]>::to_vec);
+
+                        return Ok(Async::Ready(Some(hit)));
+                    }
+
+                    if self.ctx.exhausted {
+                        ScrollState::Clearing(self.ctx.clear())
+                    } else {
+                        match self.ctx.next_page() {
+                            Ok(page) => ScrollState::Pending(page),
+                            Err(e) => ScrollState::Failed(Some(e)),
+                        }
+                    }
+                }
+                ScrollState::Pending(ref mut page) => match page.poll() {
+                    Ok(Async::Ready((params, page))) => {
+                        self.ctx.receive(params, page);
+
+                        ScrollState::Emitting
+                    }
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(ref e) if self.ctx.can_recover(e) => match self.ctx.recover() {
+                        Ok(page) => {
+                            self.ctx.id = None;
+
+                            ScrollState::Pending(page)
+                        }
+                        Err(e) => ScrollState::Failed(Some(e)),
+                    },
+                    Err(e) => ScrollState::Failed(Some(e)),
+                },
+                ScrollState::Clearing(ref mut clear) => match clear.poll() {
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    _ => ScrollState::Done,
+                },
+                ScrollState::Failed(ref mut e) => match e.take() {
+                    Some(e) => {
+                        self.state = ScrollState::Done;
+
+                        return Err(e);
+                    }
+                    None => ScrollState::Done,
+                },
+                ScrollState::Done => return Ok(Async::Ready(None)),
+            };
+
+            self.state = state;
+        }
+    }
+}
+
+impl<TDocument> ScrollContext<TDocument> {
+    /** Whether a failed page can be recovered by switching to a point-in-time. */
+    fn can_recover(&self, err: &Error) -> bool {
+        // Only recover from missing scroll contexts so a missing point-in-time can't loop
+        if let Some(SearchContextId::Scroll(_)) = self.id {
+            self.recover && is_search_context_missing(err)
+        } else {
+            false
+        }
+    }
+}
+
+/** Whether an error was caused by a missing scroll or point-in-time context. */
+fn is_search_context_missing(err: &Error) -> bool {
+    let cause = match *err {
+        Error::Client(ref err) => err.api_error_cause(),
+        _ => None,
+    };
+
+    cause.map_or(false, |cause| {
+        cause
+            .chain()
+            .chain(cause.root_cause())
+            .any(|cause| cause.ty() == "search_context_missing_exception")
+    })
+}
+
+/** Build a search request against a point-in-time. */
+fn pit_search(
+    body: Value,
+    pit_id: &str,
+    keep_alive: &str,
+    search_after: Option<Vec<Value>>,
+) -> SearchRequest<'static, Value> {
+    let mut body = match body {
+        Value::Object(body) => body,
+        _ => Map::new(),
+    };
+
+    body.insert(
+        "pit".to_owned(),
+        object(vec![("id", pit_id.into()), ("keep_alive", keep_alive.into())]),
+    );
+
+    if let Some(search_after) = search_after {
+        body.insert("search_after".to_owned(), Value::Array(search_after));
+    }
+
+    SearchRequest::new(Value::Object(body))
+}
+
+fn object(fields: Vec<(&str, Value)>) -> Value {
+    Value::Object(
+        fields
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value))
+            .collect(),
+    )
+}
+
+fn body_bytes(body: Value) -> Vec<u8> {
+    serde_json::to_vec(&body).expect("serializing a `Value` is infallible")
+}
+
+/** Send a search request for a single page of hits. */
+fn send_page<TDocument, TRequest>(
+    client: &Client<AsyncSender>,
+    params: RequestParams,
+    req: TRequest,
+) -> impl Future<Item = SearchResponse<TDocument>, Error = Error> + Send
+where
+    TDocument: DeserializeOwned + Send + 'static,
+    TRequest: Into<Endpoint<'static, Value>>,
+{
+    let Endpoint {
+        url,
+        method,
+        body,
+        name,
+    } = req.into();
+    let req = Endpoint {
+        url,
+        method,
+        body: body.map(body_bytes),
+        name,
+    };
+
+    client
+        .request(req)
+        .params(params)
+        .send()
+        .compat()
+        .and_then(|res| res.into_response().compat())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use client::responses::parse::parse;
+    use error;
+    use http::StatusCode;
+    use tests::*;
+
+    #[test]
+    fn is_send() {
+        assert_send::<super::ScrollStream<Value>>();
+        assert_send::<super::ScrollStreamBuilder<Value>>();
+    }
+
+    #[test]
+    fn pit_search_body() {
+        let req = super::pit_search(
+            json!({ "size": 10, "sort": ["_doc"] }),
+            "abc",
+            "60s",
+            Some(vec![json!(42)]),
+        );
+
+        assert_eq!("/_search", req.url.as_ref());
+        assert_eq!(
+            json!({
+                "size": 10,
+                "sort": ["_doc"],
+                "pit": { "id": "abc", "keep_alive": "60s" },
+                "search_after": [42]
+            }),
+            req.body
+        );
+    }
+
+    #[test]
+    fn search_context_missing() {
+        let body = json!({
+            "error": {
+                "root_cause": [
+                    {
+                        "type": "search_context_missing_exception",
+                        "reason": "No search context found for id [34]"
+                    }
+                ],
+                "type": "search_phase_execution_exception",
+                "reason": "all shards failed"
+            },
+            "status": 404
+        });
+
+        let err = parse::<Value>()
+            .from_slice(StatusCode::NOT_FOUND, body.to_string())
+            .unwrap_err();
+        let err = error::response(StatusCode::NOT_FOUND, err);
+
+        assert!(super::is_search_context_missing(&err));
+    }
+}
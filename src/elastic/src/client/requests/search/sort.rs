@@ -0,0 +1,525 @@
+/*!
+Builders for [sorting][docs-sort] search hits.
+
+[docs-sort]: https://www.elastic.co/guide/en/elasticsearch/reference/current/search-request-sort.html
+*/
+
+use serde::ser::{
+    Serialize,
+    Serializer,
+};
+use serde_json::{
+    self,
+    Value,
+};
+
+use super::object;
+use client::requests::common::{
+    DefaultParams,
+    ScriptBuilder,
+};
+use types::geo::mapping::DistanceUnit;
+
+/**
+A sort clause for a search request.
+
+Sort clauses are attached to a search request with [`sort`][sort].
+Use `Sort::field`, `Sort::geo_distance` or `Sort::script` to build a sort clause.
+
+# Examples
+
+Sort by a timestamp descending, then by the minimum price of nested variants:
+
+```
+# extern crate elastic;
+# use elastic::prelude::*;
+# fn main() {
+let by_timestamp = Sort::field("timestamp").desc();
+
+let by_price = Sort::field("variants.price")
+    .asc()
+    .mode(SortMode::Min)
+    .missing(SortMissing::Last)
+    .nested_path("variants");
+# }
+```
+
+[sort]: type.SearchRequestBuilder.html#method.sort
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sort(SortInner);
+
+#[derive(Debug, Clone, PartialEq)]
+enum SortInner {
+    Field(FieldSort),
+    GeoDistance(GeoDistanceSort),
+    Script(ScriptSort),
+}
+
+impl Sort {
+    /** Sort hits by the value of a field. */
+    pub fn field(field: impl Into<String>) -> FieldSort {
+        FieldSort {
+            field: field.into(),
+            order: None,
+            mode: None,
+            missing: None,
+            unmapped_type: None,
+            nested_path: None,
+        }
+    }
+
+    /** Sort hits by their distance from a geo point. */
+    pub fn geo_distance(field: impl Into<String>, lat: f64, lon: f64) -> GeoDistanceSort {
+        GeoDistanceSort {
+            field: field.into(),
+            lat,
+            lon,
+            order: None,
+            unit: None,
+            mode: None,
+            distance_type: None,
+            nested_path: None,
+        }
+    }
+
+    /** Sort hits by the value computed by a script. */
+    pub fn script(
+        script: impl Into<ScriptBuilder<DefaultParams>>,
+        ty: ScriptSortType,
+    ) -> ScriptSort {
+        let script = serde_json::to_value(script.into()).unwrap_or(Value::Null);
+
+        ScriptSort {
+            script,
+            ty,
+            order: None,
+            mode: None,
+            nested_path: None,
+        }
+    }
+}
+
+/** The order to sort hits in. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /** Sort in ascending order. */
+    Asc,
+    /** Sort in descending order. */
+    Desc,
+}
+
+/** How to pick a value to sort by for fields with multiple values. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    /** Pick the lowest value. */
+    Min,
+    /** Pick the highest value. */
+    Max,
+    /** Use the sum of all values. */
+    Sum,
+    /** Use the average of all values. */
+    Avg,
+    /** Use the median of all values. */
+    Median,
+}
+
+/** Where to sort hits that are missing a value for the sort field. */
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortMissing {
+    /** Sort hits without a value first. */
+    First,
+    /** Sort hits without a value last. */
+    Last,
+    /** Sort hits without a value as if they had the given value. */
+    Value(Value),
+}
+
+/** The type of value computed by a script sort. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptSortType {
+    /** The script returns a number. */
+    Number,
+    /** The script returns a string. */
+    String,
+}
+
+/** How to compute the distance between geo points. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoDistanceType {
+    /** The most accurate distance calculation. */
+    Arc,
+    /** A faster, but less accurate, distance calculation. */
+    Plane,
+}
+
+/** A sort clause for the value of a field. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSort {
+    field: String,
+    order: Option<SortOrder>,
+    mode: Option<SortMode>,
+    missing: Option<SortMissing>,
+    unmapped_type: Option<String>,
+    nested_path: Option<String>,
+}
+
+impl FieldSort {
+    /** Set the order to sort hits in. */
+    pub fn order(mut self, order: SortOrder) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /** Sort hits in ascending order. */
+    pub fn asc(self) -> Self {
+        self.order(SortOrder::Asc)
+    }
+
+    /** Sort hits in descending order. */
+    pub fn desc(self) -> Self {
+        self.order(SortOrder::Desc)
+    }
+
+    /** Set how to pick a value to sort by if the field has multiple values. */
+    pub fn mode(mut self, mode: SortMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /** Set where to sort hits that are missing a value for the field. */
+    pub fn missing(mut self, missing: SortMissing) -> Self {
+        self.missing = Some(missing);
+        self
+    }
+
+    /** Set the type to use for indices that don't have a mapping for the field. */
+    pub fn unmapped_type(mut self, ty: impl Into<String>) -> Self {
+        self.unmapped_type = Some(ty.into());
+        self
+    }
+
+    /** Set the path of the nested object that contains the field. */
+    pub fn nested_path(mut self, path: impl Into<String>) -> Self {
+        self.nested_path = Some(path.into());
+        self
+    }
+}
+
+/** A sort clause for the distance from a geo point. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoDistanceSort {
+    field: String,
+    lat: f64,
+    lon: f64,
+    order: Option<SortOrder>,
+    unit: Option<DistanceUnit>,
+    mode: Option<SortMode>,
+    distance_type: Option<GeoDistanceType>,
+    nested_path: Option<String>,
+}
+
+impl GeoDistanceSort {
+    /** Set the order to sort hits in. */
+    pub fn order(mut self, order: SortOrder) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /** Sort the closest hits first. */
+    pub fn asc(self) -> Self {
+        self.order(SortOrder::Asc)
+    }
+
+    /** Sort the furthest hits first. */
+    pub fn desc(self) -> Self {
+        self.order(SortOrder::Desc)
+    }
+
+    /** Set the unit to use for the sort values returned with each hit. */
+    pub fn unit(mut self, unit: DistanceUnit) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    /** Set how to pick a distance to sort by if the field has multiple points. */
+    pub fn mode(mut self, mode: SortMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /** Set how to compute the distance. */
+    pub fn distance_type(mut self, distance_type: GeoDistanceType) -> Self {
+        self.distance_type = Some(distance_type);
+        self
+    }
+
+    /** Set the path of the nested object that contains the field. */
+    pub fn nested_path(mut self, path: impl Into<String>) -> Self {
+        self.nested_path = Some(path.into());
+        self
+    }
+}
+
+/** A sort clause for the value computed by a script. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptSort {
+    script: Value,
+    ty: ScriptSortType,
+    order: Option<SortOrder>,
+    mode: Option<SortMode>,
+    nested_path: Option<String>,
+}
+
+impl ScriptSort {
+    /** Set the order to sort hits in. */
+    pub fn order(mut self, order: SortOrder) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /** Sort hits in ascending order. */
+    pub fn asc(self) -> Self {
+        self.order(SortOrder::Asc)
+    }
+
+    /** Sort hits in descending order. */
+    pub fn desc(self) -> Self {
+        self.order(SortOrder::Desc)
+    }
+
+    /** Set how to pick a value to sort by if the script returns multiple values. */
+    pub fn mode(mut self, mode: SortMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /** Set the path of the nested object the script is run against. */
+    pub fn nested_path(mut self, path: impl Into<String>) -> Self {
+        self.nested_path = Some(path.into());
+        self
+    }
+}
+
+impl From<FieldSort> for Sort {
+    fn from(sort: FieldSort) -> Self {
+        Sort(SortInner::Field(sort))
+    }
+}
+
+impl From<GeoDistanceSort> for Sort {
+    fn from(sort: GeoDistanceSort) -> Self {
+        Sort(SortInner::GeoDistance(sort))
+    }
+}
+
+impl From<ScriptSort> for Sort {
+    fn from(sort: ScriptSort) -> Self {
+        Sort(SortInner::Script(sort))
+    }
+}
+
+impl<'a> From<&'a str> for Sort {
+    fn from(field: &'a str) -> Self {
+        Sort::field(field).into()
+    }
+}
+
+impl From<String> for Sort {
+    fn from(field: String) -> Self {
+        Sort::field(field).into()
+    }
+}
+
+impl Sort {
+    pub(crate) fn to_value(&self) -> Value {
+        fn nested(path: &Option<String>) -> Option<Value> {
+            path.as_ref()
+                .map(|path| object(vec![("path", Some(path.as_str().into()))]))
+        }
+
+        match self.0 {
+            SortInner::Field(ref sort) => {
+                let options = object(vec![
+                    ("order", sort.order.map(order)),
+                    ("mode", sort.mode.map(mode)),
+                    ("missing", sort.missing.as_ref().map(missing)),
+                    (
+                        "unmapped_type",
+                        sort.unmapped_type.as_ref().map(|ty| ty.as_str().into()),
+                    ),
+                    ("nested", nested(&sort.nested_path)),
+                ]);
+
+                object(vec![(sort.field.as_str(), Some(options))])
+            }
+            SortInner::GeoDistance(ref sort) => {
+                let point = object(vec![
+                    ("lat", Some(sort.lat.into())),
+                    ("lon", Some(sort.lon.into())),
+                ]);
+
+                let options = object(vec![
+                    (sort.field.as_str(), Some(point)),
+                    ("order", sort.order.map(order)),
+                    (
+                        "unit",
+                        sort.unit.and_then(|unit| serde_json::to_value(unit).ok()),
+                    ),
+                    ("mode", sort.mode.map(mode)),
+                    (
+                        "distance_type",
+                        sort.distance_type.map(|distance_type| match distance_type {
+                            GeoDistanceType::Arc => "arc".into(),
+                            GeoDistanceType::Plane => "plane".into(),
+                        }),
+                    ),
+                    ("nested", nested(&sort.nested_path)),
+                ]);
+
+                object(vec![("_geo_distance", Some(options))])
+            }
+            SortInner::Script(ref sort) => {
+                let ty = match sort.ty {
+                    ScriptSortType::Number => "number",
+                    ScriptSortType::String => "string",
+                };
+
+                let options = object(vec![
+                    ("type", Some(ty.into())),
+                    ("script", Some(sort.script.clone())),
+                    ("order", sort.order.map(order)),
+                    ("mode", sort.mode.map(mode)),
+                    ("nested", nested(&sort.nested_path)),
+                ]);
+
+                object(vec![("_script", Some(options))])
+            }
+        }
+    }
+}
+
+impl Serialize for Sort {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_value().serialize(serializer)
+    }
+}
+
+fn order(order: SortOrder) -> Value {
+    match order {
+        SortOrder::Asc => "asc".into(),
+        SortOrder::Desc => "desc".into(),
+    }
+}
+
+fn mode(mode: SortMode) -> Value {
+    match mode {
+        SortMode::Min => "min".into(),
+        SortMode::Max => "max".into(),
+        SortMode::Sum => "sum".into(),
+        SortMode::Avg => "avg".into(),
+        SortMode::Median => "median".into(),
+    }
+}
+
+fn missing(missing: &SortMissing) -> Value {
+    match *missing {
+        SortMissing::First => "_first".into(),
+        SortMissing::Last => "_last".into(),
+        SortMissing::Value(ref value) => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use client::requests::common::ScriptBuilder;
+    use prelude::*;
+
+    #[test]
+    fn field_sort() {
+        let sort: Sort = Sort::field("timestamp").into();
+
+        assert_eq!(
+            json!({ "timestamp": {} }),
+            serde_json::to_value(sort).unwrap()
+        );
+    }
+
+    #[test]
+    fn field_sort_options() {
+        let sort: Sort = Sort::field("variants.price")
+            .desc()
+            .mode(SortMode::Avg)
+            .missing(SortMissing::Value(json!(0)))
+            .unmapped_type("long")
+            .nested_path("variants")
+            .into();
+
+        let expected = json!({
+            "variants.price": {
+                "order": "desc",
+                "mode": "avg",
+                "missing": 0,
+                "unmapped_type": "long",
+                "nested": {
+                    "path": "variants"
+                }
+            }
+        });
+
+        assert_eq!(expected, serde_json::to_value(sort).unwrap());
+    }
+
+    #[test]
+    fn geo_distance_sort() {
+        let sort: Sort = Sort::geo_distance("location", -33.86, 151.21)
+            .asc()
+            .unit(DistanceUnit::Kilometers)
+            .mode(SortMode::Min)
+            .distance_type(GeoDistanceType::Plane)
+            .into();
+
+        let expected = json!({
+            "_geo_distance": {
+                "location": {
+                    "lat": -33.86,
+                    "lon": 151.21
+                },
+                "order": "asc",
+                "unit": "km",
+                "mode": "min",
+                "distance_type": "plane"
+            }
+        });
+
+        assert_eq!(expected, serde_json::to_value(sort).unwrap());
+    }
+
+    #[test]
+    fn script_sort() {
+        let script =
+            ScriptBuilder::new("doc['popularity'].value * params.factor").param("factor", 1.5);
+
+        let sort: Sort = Sort::script(script, ScriptSortType::Number).desc().into();
+
+        let expected = json!({
+            "_script": {
+                "type": "number",
+                "script": {
+                    "inline": "doc['popularity'].value * params.factor",
+                    "params": {
+                        "factor": 1.5
+                    }
+                },
+                "order": "desc"
+            }
+        });
+
+        assert_eq!(expected, serde_json::to_value(sort).unwrap());
+    }
+}
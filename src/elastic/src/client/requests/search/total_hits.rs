@@ -0,0 +1,45 @@
+/*!
+Options for [tracking the total number of hits][docs-track-total-hits] matching a search.
+
+[docs-track-total-hits]: https://www.elastic.co/guide/en/elasticsearch/reference/current/search-your-data.html#track-total-hits
+*/
+
+use serde_json::Value;
+
+/**
+How accurately to count the total number of hits matching a search.
+
+Counting every hit can be expensive, so by default Elasticsearch only counts hits accurately up to `10,000`.
+When the total isn't exact the response's total hits are a lower bound.
+
+A `bool` can be converted into `TrackTotalHits::Exact` or `TrackTotalHits::Disabled`.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackTotalHits {
+    /** Count every matching hit. */
+    Exact,
+    /** Don't count matching hits. */
+    Disabled,
+    /** Count matching hits accurately up to the given number. */
+    UpTo(u64),
+}
+
+impl TrackTotalHits {
+    pub(crate) fn to_value(&self) -> Value {
+        match *self {
+            TrackTotalHits::Exact => Value::Bool(true),
+            TrackTotalHits::Disabled => Value::Bool(false),
+            TrackTotalHits::UpTo(limit) => limit.into(),
+        }
+    }
+}
+
+impl From<bool> for TrackTotalHits {
+    fn from(track: bool) -> Self {
+        if track {
+            TrackTotalHits::Exact
+        } else {
+            TrackTotalHits::Disabled
+        }
+    }
+}
@@ -0,0 +1,432 @@
+/*!
+Builders for [snapshot restore requests][docs-restore].
+
+[docs-restore]: https://www.elastic.co/guide/en/elasticsearch/reference/current/modules-snapshots.html#_restore
+*/
+
+use futures::Future;
+use serde_json::{
+    self,
+    Map,
+    Value,
+};
+
+use client::{
+    requests::{
+        endpoints::SnapshotRestoreRequest,
+        params::{
+            Repository,
+            Snapshot,
+        },
+        raw::RawRequestInner,
+        RequestBuilder,
+    },
+    responses::SnapshotRestoreResponse,
+    sender::{
+        AsyncSender,
+        Sender,
+        SyncSender,
+    },
+    Client,
+};
+use error::{
+    self,
+    Error,
+    Result,
+};
+use std_future::{
+    box_future,
+    BoxFuture,
+};
+
+/**
+A [snapshot restore request][docs-restore] builder that can be configured before sending.
+
+Call [`Client.snapshot_restore`][Client.snapshot_restore] to get a `SnapshotRestoreRequestBuilder`.
+The `send` method will either send the request [synchronously][send-sync] or [asynchronously][send-async], depending on the `Client` it was created from.
+
+[docs-restore]: https://www.elastic.co/guide/en/elasticsearch/reference/current/modules-snapshots.html#_restore
+[send-sync]: #send-synchronously
+[send-async]: #send-asynchronously
+[Client.snapshot_restore]: ../../struct.Client.html#snapshot-restore-request
+*/
+pub type SnapshotRestoreRequestBuilder<TSender> =
+    RequestBuilder<TSender, SnapshotRestoreRequestInner>;
+
+#[doc(hidden)]
+pub struct SnapshotRestoreRequestInner {
+    repository: Repository<'static>,
+    snapshot: Snapshot<'static>,
+    wait_for_completion: bool,
+    body: RestoreBody,
+}
+
+#[derive(Serialize, Default)]
+struct RestoreBody {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    indices: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ignore_unavailable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_global_state: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_aliases: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    partial: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rename_pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rename_replacement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index_settings: Option<Map<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ignore_index_settings: Option<Vec<String>>,
+}
+
+/**
+# Snapshot restore request
+*/
+impl<TSender> Client<TSender>
+where
+    TSender: Sender,
+{
+    /**
+    Create a [`SnapshotRestoreRequestBuilder`][SnapshotRestoreRequestBuilder] with this `Client` that can be configured before sending.
+
+    By default, all indices in the snapshot are restored with their original names and the cluster state isn't restored.
+    An index can only be restored over an existing index if the existing index is closed.
+
+    For more details, see:
+
+    - [builder methods][builder-methods]
+    - [send synchronously][send-sync]
+    - [send asynchronously][send-async]
+
+    # Examples
+
+    Restore the `logs-*` indices from a snapshot alongside the existing ones, with replicas disabled:
+
+    ```no_run
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.snapshot_restore("my_backup", "snapshot_1")
+                         .indices(vec!["logs-*"])
+                         .rename("logs-(.+)", "restored-logs-$1")
+                         .index_setting("index.number_of_replicas", 0)
+                         .include_global_state(false)
+                         .wait_for_completion(true)
+                         .send()?;
+
+    if let Some(snapshot) = response.snapshot() {
+        for index in snapshot.indices() {
+            println!("restored {}", index);
+        }
+    }
+    # Ok(())
+    # }
+    ```
+
+    [SnapshotRestoreRequestBuilder]: requests/snapshot_restore/type.SnapshotRestoreRequestBuilder.html
+    [builder-methods]: requests/snapshot_restore/type.SnapshotRestoreRequestBuilder.html#builder-methods
+    [send-sync]: requests/snapshot_restore/type.SnapshotRestoreRequestBuilder.html#send-synchronously
+    [send-async]: requests/snapshot_restore/type.SnapshotRestoreRequestBuilder.html#send-asynchronously
+    */
+    pub fn snapshot_restore<IRepository, ISnapshot>(
+        &self,
+        repository: IRepository,
+        snapshot: ISnapshot,
+    ) -> SnapshotRestoreRequestBuilder<TSender>
+    where
+        IRepository: Into<Repository<'static>>,
+        ISnapshot: Into<Snapshot<'static>>,
+    {
+        RequestBuilder::initial(
+            self.clone(),
+            SnapshotRestoreRequestInner {
+                repository: repository.into(),
+                snapshot: snapshot.into(),
+                wait_for_completion: false,
+                body: RestoreBody::default(),
+            },
+        )
+    }
+}
+
+impl SnapshotRestoreRequestInner {
+    fn into_request(self) -> Result<(SnapshotRestoreRequest<'static, Vec<u8>>, bool)> {
+        let body = serde_json::to_vec(&self.body).map_err(error::request)?;
+
+        let req =
+            SnapshotRestoreRequest::for_repository_snapshot(self.repository, self.snapshot, body);
+
+        Ok((req, self.wait_for_completion))
+    }
+}
+
+/**
+# Builder methods
+
+Configures a `SnapshotRestoreRequestBuilder` before sending it.
+*/
+impl<TSender> SnapshotRestoreRequestBuilder<TSender>
+where
+    TSender: Sender,
+{
+    /**
+    Set the indices to restore from the snapshot.
+
+    Index names can include wildcards.
+    By default, all indices in the snapshot are restored.
+    */
+    pub fn indices<I>(mut self, indices: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: ToString,
+    {
+        self.inner.body.indices =
+            Some(indices.into_iter().map(|index| index.to_string()).collect());
+        self
+    }
+
+    /** Whether or not to ignore indices that don't exist in the snapshot. */
+    pub fn ignore_unavailable(mut self, ignore_unavailable: bool) -> Self {
+        self.inner.body.ignore_unavailable = Some(ignore_unavailable);
+        self
+    }
+
+    /**
+    Whether or not to restore the cluster state, like templates and persistent settings.
+
+    The cluster state isn't restored by default.
+    */
+    pub fn include_global_state(mut self, include_global_state: bool) -> Self {
+        self.inner.body.include_global_state = Some(include_global_state);
+        self
+    }
+
+    /** Whether or not to restore the aliases of restored indices. */
+    pub fn include_aliases(mut self, include_aliases: bool) -> Self {
+        self.inner.body.include_aliases = Some(include_aliases);
+        self
+    }
+
+    /**
+    Whether or not to allow restoring indices that don't have snapshots of all their shards.
+
+    Shards that weren't snapshotted successfully are created empty.
+    By default, the restore fails if any index doesn't have all of its shards.
+    */
+    pub fn partial(mut self, partial: bool) -> Self {
+        self.inner.body.partial = Some(partial);
+        self
+    }
+
+    /**
+    Rename indices as they're restored.
+
+    Indices with names that match the `pattern` regular expression are renamed using `replacement`.
+    The replacement can reference capture groups in the pattern, like `$1`.
+    */
+    pub fn rename<IPattern, IReplacement>(
+        mut self,
+        pattern: IPattern,
+        replacement: IReplacement,
+    ) -> Self
+    where
+        IPattern: ToString,
+        IReplacement: ToString,
+    {
+        self.inner.body.rename_pattern = Some(pattern.to_string());
+        self.inner.body.rename_replacement = Some(replacement.to_string());
+        self
+    }
+
+    /**
+    Override an index setting on restored indices.
+
+    Some settings, like `index.number_of_shards`, can't be changed on restore.
+    */
+    pub fn index_setting<TKey, TValue>(mut self, key: TKey, value: TValue) -> Self
+    where
+        TKey: ToString,
+        TValue: Into<Value>,
+    {
+        self.inner
+            .body
+            .index_settings
+            .get_or_insert_with(Map::new)
+            .insert(key.to_string(), value.into());
+        self
+    }
+
+    /** Reset index settings on restored indices to their defaults instead of using the values from the snapshot. */
+    pub fn ignore_index_settings<I>(mut self, settings: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: ToString,
+    {
+        self.inner.body.ignore_index_settings = Some(
+            settings
+                .into_iter()
+                .map(|setting| setting.to_string())
+                .collect(),
+        );
+        self
+    }
+
+    /**
+    Whether or not to wait for the restore to complete before returning a response.
+
+    By default, a response is returned as soon as the restore has been accepted.
+    */
+    pub fn wait_for_completion(mut self, wait_for_completion: bool) -> Self {
+        self.inner.wait_for_completion = wait_for_completion;
+        self
+    }
+}
+
+/**
+# Send synchronously
+*/
+impl SnapshotRestoreRequestBuilder<SyncSender> {
+    /**
+    Send a `SnapshotRestoreRequestBuilder` synchronously using a [`SyncClient`][SyncClient].
+
+    This will block the current thread until a response arrives and is deserialised.
+
+    [SyncClient]: ../../type.SyncClient.html
+    */
+    pub fn send(self) -> Result<SnapshotRestoreResponse> {
+        let (req, wait_for_completion) = self.inner.into_request()?;
+
+        RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
+            .params_fluent(move |params| {
+                params.url_param("wait_for_completion", wait_for_completion)
+            })
+            .send()?
+            .into_response()
+    }
+}
+
+/**
+# Send asynchronously
+*/
+impl SnapshotRestoreRequestBuilder<AsyncSender> {
+    /**
+    Send a `SnapshotRestoreRequestBuilder` asynchronously using an [`AsyncClient`][AsyncClient].
+
+    This will return a future that will resolve to the deserialised snapshot restore response.
+
+    [AsyncClient]: ../../type.AsyncClient.html
+    */
+    pub fn send(self) -> Pending {
+        let (client, params_builder, inner) = (self.client, self.params_builder, self.inner);
+
+        let req_future = client.sender.maybe_async(move || inner.into_request());
+
+        let res_future = req_future.and_then(move |(req, wait_for_completion)| {
+            RequestBuilder::new(client, params_builder, RawRequestInner::new(req))
+                .params_fluent(move |params| {
+                    params.url_param("wait_for_completion", wait_for_completion)
+                })
+                .send()
+                .compat()
+                .and_then(|res| res.into_response().compat())
+        });
+
+        Pending::new(res_future)
+    }
+}
+
+/** A future returned by calling `send`. */
+pub struct Pending {
+    inner: BoxFuture<SnapshotRestoreResponse>,
+}
+
+impl Pending {
+    fn new<F>(fut: F) -> Self
+    where
+        F: Future<Item = SnapshotRestoreResponse, Error = Error> + Send + 'static,
+    {
+        Pending {
+            inner: box_future(fut),
+        }
+    }
+}
+
+pending_future!(Pending => SnapshotRestoreResponse);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{
+        self,
+        Value,
+    };
+
+    use prelude::*;
+    use tests::*;
+
+    #[test]
+    fn is_send() {
+        assert_send::<super::Pending>();
+    }
+
+    #[test]
+    fn default_request() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let (req, wait_for_completion) = client
+            .snapshot_restore("my_backup", "snapshot_1")
+            .inner
+            .into_request()
+            .unwrap();
+
+        assert_eq!("/_snapshot/my_backup/snapshot_1/_restore", req.url.as_ref());
+        assert_eq!("{}", String::from_utf8(req.body).unwrap());
+        assert!(!wait_for_completion);
+    }
+
+    #[test]
+    fn specify_restore_options() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let (req, wait_for_completion) = client
+            .snapshot_restore("my_backup", "snapshot_1")
+            .indices(vec!["logs-*", "metrics"])
+            .ignore_unavailable(true)
+            .include_global_state(false)
+            .include_aliases(false)
+            .partial(true)
+            .rename("(.+)", "restored-$1")
+            .index_setting("index.number_of_replicas", 0)
+            .index_setting("index.refresh_interval", "30s")
+            .ignore_index_settings(vec!["index.routing.allocation.require.zone"])
+            .wait_for_completion(true)
+            .inner
+            .into_request()
+            .unwrap();
+
+        let expected = json!({
+            "indices": ["logs-*", "metrics"],
+            "ignore_unavailable": true,
+            "include_global_state": false,
+            "include_aliases": false,
+            "partial": true,
+            "rename_pattern": "(.+)",
+            "rename_replacement": "restored-$1",
+            "index_settings": {
+                "index.number_of_replicas": 0,
+                "index.refresh_interval": "30s"
+            },
+            "ignore_index_settings": ["index.routing.allocation.require.zone"]
+        });
+
+        let actual: Value = serde_json::from_slice(&req.body).unwrap();
+
+        assert_eq!(expected, actual);
+        assert!(wait_for_completion);
+    }
+}
@@ -4,10 +4,7 @@ Builders for [sql queries][sql].
 [sql]: https://www.elastic.co/guide/en/elasticsearch/reference/current/sql-rest.html
 */
 
-use futures::{
-    Future,
-    Poll,
-};
+use futures::Future;
 
 use client::{
     requests::{
@@ -32,6 +29,10 @@ use error::{
 };
 
 use serde_json::json;
+use std_future::{
+    box_future,
+    BoxFuture,
+};
 
 /**
 A [sql query request][sql] builder that can be configured before sending.
@@ -256,28 +257,22 @@ where
 
     Runs a simple [Query String][docs-querystring] query:
 
-    ```no_run
-    # extern crate tokio;
-    # extern crate futures;
+    ```edition2018,no_run
     # extern crate elastic;
-    # use futures::Future;
     # use elastic::prelude::*;
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # let client = AsyncClientBuilder::new().build()?;
-    let future = client.sql_query("SELECT * FROM library GROUP BY author")
-                       .send();
-
-    future.and_then(|response| {
-        // Iterate through the hits
-        for row in response.rows() {
-            for column in row {
-                println!("{:?}", column);
-            }
-        }
+    let response = client.sql_query("SELECT * FROM library GROUP BY author")
+                         .send()
+                         .await?;
 
-        Ok(())
-    });
+    // Iterate through the hits
+    for row in response.rows() {
+        for column in row {
+            println!("{:?}", column);
+        }
+    }
     # Ok(())
     # }
     ```
@@ -292,7 +287,8 @@ where
         let res_future =
             RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
                 .send()
-                .and_then(|res| res.into_response());
+                .compat()
+                .and_then(|res| res.into_response().compat());
 
         Pending::new(res_future)
     }
@@ -300,7 +296,7 @@ where
 
 /** A future returned by calling `send`. */
 pub struct Pending {
-    inner: Box<Future<Item = SqlResponse, Error = Error> + Send>,
+    inner: BoxFuture<SqlResponse>,
 }
 
 impl Pending {
@@ -309,19 +305,12 @@ impl Pending {
         F: Future<Item = SqlResponse, Error = Error> + Send + 'static,
     {
         Pending {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl Future for Pending {
-    type Item = SqlResponse;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(Pending => SqlResponse);
 
 #[cfg(test)]
 mod tests {
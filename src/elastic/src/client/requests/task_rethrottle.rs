@@ -0,0 +1,379 @@
+/*!
+Builders for [rethrottle requests][docs-rethrottle].
+
+[docs-rethrottle]: https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-reindex.html#docs-reindex-rethrottle
+*/
+
+use std::fmt;
+
+use futures::Future;
+
+use client::{
+    requests::{
+        empty_body,
+        endpoints::{
+            DeleteByQueryRethrottleRequest,
+            ReindexRethrottleRequest,
+            UpdateByQueryRethrottleRequest,
+        },
+        raw::RawRequestInner,
+        DefaultBody,
+        Endpoint,
+        RequestBuilder,
+    },
+    responses::TasksResponse,
+    sender::{
+        AsyncSender,
+        RequestParams,
+        Sender,
+        SyncSender,
+    },
+    Client,
+};
+use error::*;
+use std_future::{
+    box_future,
+    BoxFuture,
+};
+
+/**
+A [rethrottle request][docs-rethrottle] builder that can be configured before sending.
+
+Call [`Client.rethrottle`][Client.rethrottle] to get a `RethrottleRequestBuilder`.
+The `send` method will either send the request [synchronously][send-sync] or [asynchronously][send-async], depending on the `Client` it was created from.
+
+[docs-rethrottle]: https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-reindex.html#docs-reindex-rethrottle
+[send-sync]: #send-synchronously
+[send-async]: #send-asynchronously
+[Client.rethrottle]: ../../struct.Client.html#rethrottle-request
+*/
+pub type RethrottleRequestBuilder<TSender> = RequestBuilder<TSender, RethrottleRequestInner>;
+
+#[doc(hidden)]
+pub struct RethrottleRequestInner {
+    task_id: String,
+    operation: ThrottledOperation,
+    requests_per_second: RequestsPerSecond,
+}
+
+/** The kind of long-running operation a task belongs to. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThrottledOperation {
+    /** A task started by a reindex request. */
+    Reindex,
+    /** A task started by an update by query request. */
+    UpdateByQuery,
+    /** A task started by a delete by query request. */
+    DeleteByQuery,
+}
+
+/**
+The number of requests per second a task can issue.
+
+A float can be converted into a `RequestsPerSecond::Limit`.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RequestsPerSecond {
+    /** Limit the task to a number of requests per second. */
+    Limit(f32),
+    /** Don't throttle the task. */
+    Unlimited,
+}
+
+impl From<f32> for RequestsPerSecond {
+    fn from(limit: f32) -> Self {
+        RequestsPerSecond::Limit(limit)
+    }
+}
+
+impl fmt::Display for RequestsPerSecond {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RequestsPerSecond::Limit(limit) => write!(f, "{}", limit),
+            RequestsPerSecond::Unlimited => f.write_str("-1"),
+        }
+    }
+}
+
+/**
+# Rethrottle request
+*/
+impl<TSender> Client<TSender>
+where
+    TSender: Sender,
+{
+    /**
+    Create a [`RethrottleRequestBuilder`][RethrottleRequestBuilder] with this `Client` that can be configured before sending.
+
+    Rethrottling changes the `requests_per_second` of a running reindex, update by query or delete by query task.
+    Speeding a task up takes effect immediately, but slowing it down only takes effect after the current batch has completed.
+    The request targets a reindex task unless another [`operation`][builder-methods] is set.
+
+    For more details, see:
+
+    - [builder methods][builder-methods]
+    - [send synchronously][send-sync]
+    - [send asynchronously][send-async]
+
+    # Examples
+
+    Slow down a running update by query task:
+
+    ```no_run
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.rethrottle("oTUltX4IQMOUUVeiohTt8A:12345", 100.0)
+                         .operation(ThrottledOperation::UpdateByQuery)
+                         .send()?;
+
+    for task in response.tasks() {
+        println!("rethrottled task {}", task.task_id());
+    }
+    # Ok(())
+    # }
+    ```
+
+    Remove the throttle from a running reindex task:
+
+    ```no_run
+    # extern crate elastic;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    let response = client.rethrottle("oTUltX4IQMOUUVeiohTt8A:12345", RequestsPerSecond::Unlimited)
+                         .send()?;
+    # Ok(())
+    # }
+    ```
+
+    [RethrottleRequestBuilder]: requests/task_rethrottle/type.RethrottleRequestBuilder.html
+    [builder-methods]: requests/task_rethrottle/type.RethrottleRequestBuilder.html#builder-methods
+    [send-sync]: requests/task_rethrottle/type.RethrottleRequestBuilder.html#send-synchronously
+    [send-async]: requests/task_rethrottle/type.RethrottleRequestBuilder.html#send-asynchronously
+    */
+    pub fn rethrottle<ITaskId, IRequestsPerSecond>(
+        &self,
+        task_id: ITaskId,
+        requests_per_second: IRequestsPerSecond,
+    ) -> RethrottleRequestBuilder<TSender>
+    where
+        ITaskId: Into<String>,
+        IRequestsPerSecond: Into<RequestsPerSecond>,
+    {
+        RequestBuilder::initial(
+            self.clone(),
+            RethrottleRequestInner {
+                task_id: task_id.into(),
+                operation: ThrottledOperation::Reindex,
+                requests_per_second: requests_per_second.into(),
+            },
+        )
+    }
+}
+
+impl RethrottleRequestInner {
+    fn into_request(self) -> (Endpoint<'static, DefaultBody>, RequestsPerSecond) {
+        let task_id = self.task_id;
+
+        let req = match self.operation {
+            ThrottledOperation::Reindex => {
+                ReindexRethrottleRequest::for_task_id(task_id, empty_body()).into()
+            }
+            ThrottledOperation::UpdateByQuery => {
+                UpdateByQueryRethrottleRequest::for_task_id(task_id, empty_body()).into()
+            }
+            ThrottledOperation::DeleteByQuery => {
+                DeleteByQueryRethrottleRequest::for_task_id(task_id, empty_body()).into()
+            }
+        };
+
+        (req, self.requests_per_second)
+    }
+}
+
+/**
+# Builder methods
+
+Configures a `RethrottleRequestBuilder` before sending it.
+*/
+impl<TSender> RethrottleRequestBuilder<TSender>
+where
+    TSender: Sender,
+{
+    /**
+    Set the kind of operation the task belongs to.
+
+    By default the task is assumed to be a reindex.
+    */
+    pub fn operation(mut self, operation: ThrottledOperation) -> Self {
+        self.inner.operation = operation;
+        self
+    }
+
+    /** Set the number of requests per second the task can issue. */
+    pub fn requests_per_second<IRequestsPerSecond>(
+        mut self,
+        requests_per_second: IRequestsPerSecond,
+    ) -> Self
+    where
+        IRequestsPerSecond: Into<RequestsPerSecond>,
+    {
+        self.inner.requests_per_second = requests_per_second.into();
+        self
+    }
+}
+
+/**
+# Send synchronously
+*/
+impl RethrottleRequestBuilder<SyncSender> {
+    /**
+    Send a `RethrottleRequestBuilder` synchronously using a [`SyncClient`][SyncClient].
+
+    This will block the current thread until a response arrives and is deserialised.
+    The response contains the tasks that were rethrottled.
+
+    [SyncClient]: ../../type.SyncClient.html
+    */
+    pub fn send(self) -> Result<TasksResponse> {
+        let (req, requests_per_second) = self.inner.into_request();
+
+        RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
+            .params_fluent(move |params| requests_per_second_params(params, requests_per_second))
+            .send()?
+            .into_response()
+    }
+}
+
+/**
+# Send asynchronously
+*/
+impl RethrottleRequestBuilder<AsyncSender> {
+    /**
+    Send a `RethrottleRequestBuilder` asynchronously using an [`AsyncClient`][AsyncClient].
+
+    This will return a future that will resolve to the deserialised response containing the tasks that were rethrottled.
+
+    [AsyncClient]: ../../type.AsyncClient.html
+    */
+    pub fn send(self) -> Pending {
+        let (req, requests_per_second) = self.inner.into_request();
+
+        let res_future =
+            RequestBuilder::new(self.client, self.params_builder, RawRequestInner::new(req))
+                .params_fluent(move |params| {
+                    requests_per_second_params(params, requests_per_second)
+                })
+                .send()
+                .compat()
+                .and_then(|res| res.into_response().compat());
+
+        Pending::new(res_future)
+    }
+}
+
+fn requests_per_second_params(
+    params: RequestParams,
+    requests_per_second: RequestsPerSecond,
+) -> RequestParams {
+    params.url_param("requests_per_second", requests_per_second)
+}
+
+/** A future returned by calling `send`. */
+pub struct Pending {
+    inner: BoxFuture<TasksResponse>,
+}
+
+impl Pending {
+    fn new<F>(fut: F) -> Self
+    where
+        F: Future<Item = TasksResponse, Error = Error> + Send + 'static,
+    {
+        Pending {
+            inner: box_future(fut),
+        }
+    }
+}
+
+pending_future!(Pending => TasksResponse);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prelude::*;
+    use tests::*;
+
+    #[test]
+    fn is_send() {
+        assert_send::<super::Pending>();
+    }
+
+    #[test]
+    fn default_request() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let (req, requests_per_second) = client.rethrottle("node:1", 100.0).inner.into_request();
+
+        assert_eq!("/_reindex/node:1/_rethrottle", req.url.as_ref());
+        assert_eq!(RequestsPerSecond::Limit(100.0), requests_per_second);
+    }
+
+    #[test]
+    fn specify_operation() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let (update_req, _) = client
+            .rethrottle("node:1", 100.0)
+            .operation(ThrottledOperation::UpdateByQuery)
+            .inner
+            .into_request();
+
+        let (delete_req, _) = client
+            .rethrottle("node:1", 100.0)
+            .operation(ThrottledOperation::DeleteByQuery)
+            .inner
+            .into_request();
+
+        assert_eq!(
+            "/_update_by_query/node:1/_rethrottle",
+            update_req.url.as_ref()
+        );
+        assert_eq!(
+            "/_delete_by_query/node:1/_rethrottle",
+            delete_req.url.as_ref()
+        );
+    }
+
+    #[test]
+    fn specify_requests_per_second() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        let (_, requests_per_second) = client
+            .rethrottle("node:1", 100.0)
+            .requests_per_second(RequestsPerSecond::Unlimited)
+            .inner
+            .into_request();
+
+        assert_eq!(RequestsPerSecond::Unlimited, requests_per_second);
+    }
+
+    #[test]
+    fn requests_per_second_url_param() {
+        let limited = requests_per_second_params(RequestParams::default(), 2.5.into());
+        let unlimited =
+            requests_per_second_params(RequestParams::default(), RequestsPerSecond::Unlimited);
+
+        assert_eq!(
+            Some("?requests_per_second=2.5".to_owned()),
+            limited.get_url_qry().1
+        );
+        assert_eq!(
+            Some("?requests_per_second=-1".to_owned()),
+            unlimited.get_url_qry().1
+        );
+    }
+}
@@ -0,0 +1,904 @@
+/*!
+Builders for exporting and importing bundles of templates, policies and pipelines.
+
+A [`TemplateBundle`][TemplateBundle] is a serializable collection of:
+
+- [index templates][docs-index-templates]
+- [component templates][docs-component-templates]
+- [index lifecycle policies][docs-ilm]
+- [ingest pipelines][docs-ingest]
+
+Bundles can be exported from one cluster, saved to a file, and imported into another cluster.
+This is useful for promoting cluster configuration between environments.
+
+[TemplateBundle]: struct.TemplateBundle.html
+[docs-index-templates]: https://www.elastic.co/guide/en/elasticsearch/reference/current/index-templates.html
+[docs-component-templates]: https://www.elastic.co/guide/en/elasticsearch/reference/current/indices-component-template.html
+[docs-ilm]: https://www.elastic.co/guide/en/elasticsearch/reference/current/index-lifecycle-management.html
+[docs-ingest]: https://www.elastic.co/guide/en/elasticsearch/reference/current/ingest.html
+*/
+
+use std::collections::BTreeMap;
+
+use futures::{
+    future::Either,
+    stream,
+    Future,
+    IntoFuture,
+    Stream,
+};
+use serde_json::{
+    self,
+    Map,
+    Value,
+};
+
+use client::{
+    requests::{
+        Endpoint,
+        RequestBuilder,
+    },
+    sender::{
+        AsyncSender,
+        RequestParams,
+        Sender,
+        SyncSender,
+    },
+    Client,
+};
+use error::{
+    self,
+    Error,
+    Result,
+};
+use http::{
+    Method,
+    StatusCode,
+};
+use std_future::{
+    box_future,
+    BoxFuture,
+};
+
+/** The kind of an item in a [`TemplateBundle`](struct.TemplateBundle.html). */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TemplateKind {
+    /** An index lifecycle policy. */
+    IlmPolicy,
+    /** An ingest pipeline. */
+    IngestPipeline,
+    /** A component template. */
+    ComponentTemplate,
+    /** A composable index template. */
+    IndexTemplate,
+}
+
+impl TemplateKind {
+    fn path(&self) -> &'static str {
+        match *self {
+            TemplateKind::IlmPolicy => "/_ilm/policy",
+            TemplateKind::IngestPipeline => "/_ingest/pipeline",
+            TemplateKind::ComponentTemplate => "/_component_template",
+            TemplateKind::IndexTemplate => "/_index_template",
+        }
+    }
+
+    fn get(&self, names: &str) -> Endpoint<'static, Vec<u8>> {
+        let name = match *self {
+            TemplateKind::IlmPolicy => "ilm.get_lifecycle",
+            TemplateKind::IngestPipeline => "ingest.get_pipeline",
+            TemplateKind::ComponentTemplate => "cluster.get_component_template",
+            TemplateKind::IndexTemplate => "indices.get_index_template",
+        };
+
+        Endpoint {
+            url: format!("{}/{}", self.path(), names).into(),
+            method: Method::GET,
+            body: None,
+            name,
+        }
+    }
+
+    fn put(&self, name: &str, body: &Value) -> Result<Endpoint<'static, Vec<u8>>> {
+        let body = match *self {
+            TemplateKind::IlmPolicy => {
+                let mut policy = Map::new();
+                policy.insert("policy".to_owned(), body.clone());
+
+                serde_json::to_vec(&policy)
+            }
+            _ => serde_json::to_vec(body),
+        }
+        .map_err(error::request)?;
+
+        let endpoint_name = match *self {
+            TemplateKind::IlmPolicy => "ilm.put_lifecycle",
+            TemplateKind::IngestPipeline => "ingest.put_pipeline",
+            TemplateKind::ComponentTemplate => "cluster.put_component_template",
+            TemplateKind::IndexTemplate => "indices.put_index_template",
+        };
+
+        Ok(Endpoint {
+            url: format!("{}/{}", self.path(), name).into(),
+            method: Method::PUT,
+            body: Some(body),
+            name: endpoint_name,
+        })
+    }
+
+    /** Get the named items from the response to a `GET` request. */
+    fn extract(&self, res: Value) -> Vec<(String, Value)> {
+        let named = |list: &str, item: &str| -> Vec<(String, Value)> {
+            match res.get(list) {
+                Some(Value::Array(items)) => items
+                    .iter()
+                    .filter_map(|entry| {
+                        let name = entry.get("name").and_then(Value::as_str)?;
+                        let body = entry.get(item)?;
+
+                        Some((name.to_owned(), body.clone()))
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            }
+        };
+
+        match *self {
+            TemplateKind::IndexTemplate => named("index_templates", "index_template"),
+            TemplateKind::ComponentTemplate => named("component_templates", "component_template"),
+            TemplateKind::IlmPolicy => match res {
+                Value::Object(policies) => policies
+                    .into_iter()
+                    .filter_map(|(name, policy)| {
+                        policy.get("policy").cloned().map(|policy| (name, policy))
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            },
+            TemplateKind::IngestPipeline => match res {
+                Value::Object(pipelines) => pipelines.into_iter().collect(),
+                _ => Vec::new(),
+            },
+        }
+    }
+}
+
+/**
+A serializable collection of templates, policies and pipelines.
+
+Call [`Client.export_templates`][Client.export_templates] to export a bundle from a cluster, and [`Client.import_templates`][Client.import_templates] to import it into another.
+Bundles can also be built by hand.
+
+[Client.export_templates]: ../../struct.Client.html#method.export_templates
+[Client.import_templates]: ../../struct.Client.html#method.import_templates
+*/
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TemplateBundle {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    ilm_policies: BTreeMap<String, Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    ingest_pipelines: BTreeMap<String, Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    component_templates: BTreeMap<String, Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    index_templates: BTreeMap<String, Value>,
+}
+
+impl TemplateBundle {
+    /** Create an empty bundle. */
+    pub fn new() -> Self {
+        TemplateBundle::default()
+    }
+
+    /** Add an item to the bundle, replacing any existing item of the same kind with the same name. */
+    pub fn insert(&mut self, kind: TemplateKind, name: impl Into<String>, body: Value) {
+        self.items_mut(kind).insert(name.into(), body);
+    }
+
+    /** Get the items of a given kind in the bundle. */
+    pub fn items(&self, kind: TemplateKind) -> &BTreeMap<String, Value> {
+        match kind {
+            TemplateKind::IlmPolicy => &self.ilm_policies,
+            TemplateKind::IngestPipeline => &self.ingest_pipelines,
+            TemplateKind::ComponentTemplate => &self.component_templates,
+            TemplateKind::IndexTemplate => &self.index_templates,
+        }
+    }
+
+    /** The total number of items in the bundle. */
+    pub fn len(&self) -> usize {
+        self.ilm_policies.len()
+            + self.ingest_pipelines.len()
+            + self.component_templates.len()
+            + self.index_templates.len()
+    }
+
+    /** Whether or not the bundle contains any items. */
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn items_mut(&mut self, kind: TemplateKind) -> &mut BTreeMap<String, Value> {
+        match kind {
+            TemplateKind::IlmPolicy => &mut self.ilm_policies,
+            TemplateKind::IngestPipeline => &mut self.ingest_pipelines,
+            TemplateKind::ComponentTemplate => &mut self.component_templates,
+            TemplateKind::IndexTemplate => &mut self.index_templates,
+        }
+    }
+
+    /**
+    Get all items in the order they need to be imported.
+
+    Policies and pipelines come first because templates can refer to them, and component templates come before the index templates that compose them.
+    */
+    fn into_items(self) -> Vec<BundleItem> {
+        let TemplateBundle {
+            ilm_policies,
+            ingest_pipelines,
+            component_templates,
+            index_templates,
+        } = self;
+
+        vec![
+            (TemplateKind::IlmPolicy, ilm_policies),
+            (TemplateKind::IngestPipeline, ingest_pipelines),
+            (TemplateKind::ComponentTemplate, component_templates),
+            (TemplateKind::IndexTemplate, index_templates),
+        ]
+        .into_iter()
+        .flat_map(|(kind, items)| {
+            items
+                .into_iter()
+                .map(move |(name, body)| BundleItem { kind, name, body })
+        })
+        .collect()
+    }
+}
+
+struct BundleItem {
+    kind: TemplateKind,
+    name: String,
+    body: Value,
+}
+
+/** What to do when an item in an imported bundle already exists in the cluster. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictPolicy {
+    /** Return an error without importing anything. This is the default. */
+    Fail,
+    /** Leave the existing item in place. */
+    Skip,
+    /** Replace the existing item. */
+    Overwrite,
+}
+
+/** What happened to an item when a bundle was imported. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportOutcome {
+    /** The item didn't exist and was created. */
+    Created,
+    /** The item already existed and was replaced. */
+    Overwritten,
+    /** The item already existed and was left in place. */
+    Skipped,
+}
+
+/** An item that was imported from a bundle. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedItem {
+    kind: TemplateKind,
+    name: String,
+    outcome: ImportOutcome,
+}
+
+impl ImportedItem {
+    /** The kind of item. */
+    pub fn kind(&self) -> TemplateKind {
+        self.kind
+    }
+
+    /** The name of the item. */
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /** What happened to the item. */
+    pub fn outcome(&self) -> ImportOutcome {
+        self.outcome
+    }
+}
+
+/** Response for importing a [`TemplateBundle`](struct.TemplateBundle.html). */
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateImportResponse {
+    items: Vec<ImportedItem>,
+}
+
+impl TemplateImportResponse {
+    /** The imported items, in the order they were imported. */
+    pub fn items(&self) -> &[ImportedItem] {
+        &self.items
+    }
+
+    /** The number of items with the given outcome. */
+    pub fn count(&self, outcome: ImportOutcome) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.outcome == outcome)
+            .count()
+    }
+}
+
+/**
+A builder for exporting a [`TemplateBundle`][TemplateBundle] from a cluster.
+
+Call [`Client.export_templates`][Client.export_templates] to get a `TemplateExportRequestBuilder`.
+
+[TemplateBundle]: struct.TemplateBundle.html
+[Client.export_templates]: ../../struct.Client.html#method.export_templates
+*/
+pub type TemplateExportRequestBuilder<TSender> = RequestBuilder<TSender, TemplateExportRequestInner>;
+
+#[doc(hidden)]
+pub struct TemplateExportRequestInner {
+    patterns: Vec<(TemplateKind, String)>,
+}
+
+/**
+A builder for importing a [`TemplateBundle`][TemplateBundle] into a cluster.
+
+Call [`Client.import_templates`][Client.import_templates] to get a `TemplateImportRequestBuilder`.
+
+[TemplateBundle]: struct.TemplateBundle.html
+[Client.import_templates]: ../../struct.Client.html#method.import_templates
+*/
+pub type TemplateImportRequestBuilder<TSender> = RequestBuilder<TSender, TemplateImportRequestInner>;
+
+#[doc(hidden)]
+pub struct TemplateImportRequestInner {
+    bundle: TemplateBundle,
+    on_conflict: ConflictPolicy,
+}
+
+/**
+# Template bundles
+*/
+impl<TSender> Client<TSender>
+where
+    TSender: Sender,
+{
+    /**
+    Create a [`TemplateExportRequestBuilder`][TemplateExportRequestBuilder] with this `Client` that can be configured before sending.
+
+    Only the kinds of items that are given a name or wildcard pattern are exported.
+
+    # Examples
+
+    Export all index templates starting with `logs-` along with the pipelines and policies they use, and save them to a file:
+
+    ```no_run
+    # extern crate elastic;
+    # extern crate serde_json;
+    # use std::fs::File;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    let bundle = client.export_templates()
+                       .index_templates("logs-*")
+                       .component_templates("logs-*")
+                       .ilm_policies("logs")
+                       .ingest_pipelines("logs-*")
+                       .send()?;
+
+    serde_json::to_writer_pretty(File::create("templates.json")?, &bundle)?;
+    # Ok(())
+    # }
+    ```
+
+    [TemplateExportRequestBuilder]: requests/template_bundle/type.TemplateExportRequestBuilder.html
+    */
+    pub fn export_templates(&self) -> TemplateExportRequestBuilder<TSender> {
+        RequestBuilder::initial(
+            self.clone(),
+            TemplateExportRequestInner {
+                patterns: Vec::new(),
+            },
+        )
+    }
+
+    /**
+    Create a [`TemplateImportRequestBuilder`][TemplateImportRequestBuilder] with this `Client` that can be configured before sending.
+
+    Policies and pipelines are imported first, then component templates, then index templates.
+    Every item is checked for conflicts before anything is imported.
+
+    # Examples
+
+    Import a bundle from a file, replacing any items that already exist:
+
+    ```no_run
+    # extern crate elastic;
+    # extern crate serde_json;
+    # use std::fs::File;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # let client = SyncClientBuilder::new().build()?;
+    let bundle: TemplateBundle = serde_json::from_reader(File::open("templates.json")?)?;
+
+    let response = client.import_templates(bundle)
+                         .on_conflict(ConflictPolicy::Overwrite)
+                         .send()?;
+
+    for item in response.items() {
+        println!("{:?} {}: {:?}", item.kind(), item.name(), item.outcome());
+    }
+    # Ok(())
+    # }
+    ```
+
+    [TemplateImportRequestBuilder]: requests/template_bundle/type.TemplateImportRequestBuilder.html
+    */
+    pub fn import_templates(&self, bundle: TemplateBundle) -> TemplateImportRequestBuilder<TSender> {
+        RequestBuilder::initial(
+            self.clone(),
+            TemplateImportRequestInner {
+                bundle,
+                on_conflict: ConflictPolicy::Fail,
+            },
+        )
+    }
+}
+
+/**
+# Builder methods
+
+Configures a `TemplateExportRequestBuilder` before sending it.
+*/
+impl<TSender> TemplateExportRequestBuilder<TSender>
+where
+    TSender: Sender,
+{
+    /** Export index templates matching a name or wildcard pattern. */
+    pub fn index_templates(self, pattern: impl Into<String>) -> Self {
+        self.pattern(TemplateKind::IndexTemplate, pattern)
+    }
+
+    /** Export component templates matching a name or wildcard pattern. */
+    pub fn component_templates(self, pattern: impl Into<String>) -> Self {
+        self.pattern(TemplateKind::ComponentTemplate, pattern)
+    }
+
+    /** Export index lifecycle policies matching a comma-separated list of names. */
+    pub fn ilm_policies(self, names: impl Into<String>) -> Self {
+        self.pattern(TemplateKind::IlmPolicy, names)
+    }
+
+    /** Export ingest pipelines matching a name or wildcard pattern. */
+    pub fn ingest_pipelines(self, pattern: impl Into<String>) -> Self {
+        self.pattern(TemplateKind::IngestPipeline, pattern)
+    }
+
+    fn pattern(mut self, kind: TemplateKind, pattern: impl Into<String>) -> Self {
+        self.inner.patterns.push((kind, pattern.into()));
+        self
+    }
+}
+
+/**
+# Builder methods
+
+Configures a `TemplateImportRequestBuilder` before sending it.
+*/
+impl<TSender> TemplateImportRequestBuilder<TSender>
+where
+    TSender: Sender,
+{
+    /**
+    Specify what to do when an item already exists in the cluster.
+
+    By default, importing fails if any item already exists.
+    */
+    pub fn on_conflict(mut self, on_conflict: ConflictPolicy) -> Self {
+        self.inner.on_conflict = on_conflict;
+        self
+    }
+}
+
+/** Decide what to do with each item once its existence is known. */
+fn plan_import(
+    items: Vec<(BundleItem, bool)>,
+    on_conflict: ConflictPolicy,
+) -> Result<Vec<(BundleItem, ImportOutcome)>> {
+    if on_conflict == ConflictPolicy::Fail {
+        let conflicts: Vec<_> = items
+            .iter()
+            .filter(|(_, exists)| *exists)
+            .map(|(item, _)| format!("{:?} `{}`", item.kind, item.name))
+            .collect();
+
+        if !conflicts.is_empty() {
+            return Err(error::request(error::message(format!(
+                "the following items already exist: {}",
+                conflicts.join(", ")
+            ))));
+        }
+    }
+
+    Ok(items
+        .into_iter()
+        .map(|(item, exists)| {
+            let outcome = match (exists, on_conflict) {
+                (false, _) => ImportOutcome::Created,
+                (true, ConflictPolicy::Skip) => ImportOutcome::Skipped,
+                (true, _) => ImportOutcome::Overwritten,
+            };
+
+            (item, outcome)
+        })
+        .collect())
+}
+
+/**
+# Send synchronously
+*/
+impl TemplateExportRequestBuilder<SyncSender> {
+    /**
+    Export a `TemplateBundle` synchronously using a [`SyncClient`][SyncClient].
+
+    This will block the current thread until every kind of item has been fetched.
+
+    [SyncClient]: ../../type.SyncClient.html
+    */
+    pub fn send(self) -> Result<TemplateBundle> {
+        let params = self.client.addresses.resolve(self.params_builder)?;
+
+        let mut bundle = TemplateBundle::new();
+        for (kind, pattern) in self.inner.patterns {
+            if let Some(res) = sync_get(&self.client, &params, kind.get(&pattern))? {
+                for (name, body) in kind.extract(res) {
+                    bundle.insert(kind, name, body);
+                }
+            }
+        }
+
+        Ok(bundle)
+    }
+}
+
+impl TemplateImportRequestBuilder<SyncSender> {
+    /**
+    Import a `TemplateBundle` synchronously using a [`SyncClient`][SyncClient].
+
+    This will block the current thread until every item has been imported.
+
+    [SyncClient]: ../../type.SyncClient.html
+    */
+    pub fn send(self) -> Result<TemplateImportResponse> {
+        let params = self.client.addresses.resolve(self.params_builder)?;
+
+        let mut items = Vec::new();
+        for item in self.inner.bundle.into_items() {
+            let exists = sync_get(&self.client, &params, item.kind.get(&item.name))?.is_some();
+            items.push((item, exists));
+        }
+
+        let mut imported = Vec::new();
+        for (item, outcome) in plan_import(items, self.inner.on_conflict)? {
+            if outcome != ImportOutcome::Skipped {
+                self.client
+                    .request(item.kind.put(&item.name, &item.body)?)
+                    .params(params.clone())
+                    .send()?
+                    .into_response::<Value>()?;
+            }
+
+            imported.push(ImportedItem {
+                kind: item.kind,
+                name: item.name,
+                outcome,
+            });
+        }
+
+        Ok(TemplateImportResponse { items: imported })
+    }
+}
+
+/** Send a `GET` request, returning `None` if nothing was found. */
+fn sync_get(
+    client: &Client<SyncSender>,
+    params: &RequestParams,
+    endpoint: Endpoint<'static, Vec<u8>>,
+) -> Result<Option<Value>> {
+    let res = client.request(endpoint).params(params.clone()).send()?;
+
+    match res.status() {
+        StatusCode::NOT_FOUND => Ok(None),
+        _ => res.into_response().map(Some),
+    }
+}
+
+/**
+# Send asynchronously
+*/
+impl TemplateExportRequestBuilder<AsyncSender> {
+    /**
+    Export a `TemplateBundle` asynchronously using an [`AsyncClient`][AsyncClient].
+
+    This will return a future that will resolve to the bundle once every kind of item has been fetched.
+
+    [AsyncClient]: ../../type.AsyncClient.html
+    */
+    pub fn send(self) -> Pending<TemplateBundle> {
+        let client = self.client;
+        let patterns = self.inner.patterns;
+
+        let bundle = client
+            .addresses
+            .resolve(self.params_builder)
+            .compat()
+            .and_then(move |params| {
+                stream::iter_ok(patterns)
+                    .and_then(move |(kind, pattern)| {
+                        async_get(&client, &params, kind.get(&pattern))
+                            .map(move |res| (kind, res))
+                    })
+                    .fold(TemplateBundle::new(), |mut bundle, (kind, res)| {
+                        if let Some(res) = res {
+                            for (name, body) in kind.extract(res) {
+                                bundle.insert(kind, name, body);
+                            }
+                        }
+
+                        Ok::<_, Error>(bundle)
+                    })
+            });
+
+        Pending::new(bundle)
+    }
+}
+
+impl TemplateImportRequestBuilder<AsyncSender> {
+    /**
+    Import a `TemplateBundle` asynchronously using an [`AsyncClient`][AsyncClient].
+
+    This will return a future that will resolve once every item has been imported.
+
+    [AsyncClient]: ../../type.AsyncClient.html
+    */
+    pub fn send(self) -> Pending<TemplateImportResponse> {
+        let client = self.client;
+        let TemplateImportRequestInner {
+            bundle,
+            on_conflict,
+        } = self.inner;
+
+        let imported = client
+            .addresses
+            .resolve(self.params_builder)
+            .compat()
+            .and_then(move |params| {
+                let check_client = client.clone();
+                let check_params = params.clone();
+
+                stream::iter_ok(bundle.into_items())
+                    .and_then(move |item| {
+                        async_get(&check_client, &check_params, item.kind.get(&item.name))
+                            .map(move |res| (item, res.is_some()))
+                    })
+                    .collect()
+                    .and_then(move |items| plan_import(items, on_conflict))
+                    .and_then(move |plan| {
+                        stream::iter_ok(plan)
+                            .and_then(move |(item, outcome)| {
+                                let put = match outcome {
+                                    ImportOutcome::Skipped => Either::A(Ok(()).into_future()),
+                                    _ => Either::B(
+                                        item.kind
+                                            .put(&item.name, &item.body)
+                                            .into_future()
+                                            .and_then({
+                                                let client = client.clone();
+                                                let params = params.clone();
+
+                                                move |put| {
+                                                    client
+                                                        .request(put)
+                                                        .params(params)
+                                                        .send()
+                                                        .compat()
+                                                }
+                                            })
+                                            .and_then(|res| res.into_response::<Value>().compat())
+                                            .map(|_| ()),
+                                    ),
+                                };
+
+                                put.map(move |_| ImportedItem {
+                                    kind: item.kind,
+                                    name: item.name,
+                                    outcome,
+                                })
+                            })
+                            .collect()
+                    })
+                    .map(|items| TemplateImportResponse { items })
+            });
+
+        Pending::new(imported)
+    }
+}
+
+/** Send a `GET` request, returning `None` if nothing was found. */
+fn async_get(
+    client: &Client<AsyncSender>,
+    params: &RequestParams,
+    endpoint: Endpoint<'static, Vec<u8>>,
+) -> impl Future<Item = Option<Value>, Error = Error> + Send {
+    client
+        .request(endpoint)
+        .params(params.clone())
+        .send()
+        .compat()
+        .and_then(|res| match res.status() {
+            StatusCode::NOT_FOUND => Either::A(Ok(None).into_future()),
+            _ => Either::B(res.into_response().compat().map(Some)),
+        })
+}
+
+/** A future returned by calling `send`. */
+pub struct Pending<T> {
+    inner: BoxFuture<T>,
+}
+
+impl<T> Pending<T> {
+    fn new<F>(fut: F) -> Self
+    where
+        F: Future<Item = T, Error = Error> + Send + 'static,
+    {
+        Pending {
+            inner: box_future(fut),
+        }
+    }
+}
+
+pending_future!(Pending<T> => T, T);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{
+        self,
+        Value,
+    };
+
+    use super::*;
+    use tests::*;
+
+    #[test]
+    fn is_send() {
+        assert_send::<super::Pending<TemplateBundle>>();
+        assert_send::<super::Pending<TemplateImportResponse>>();
+    }
+
+    #[test]
+    fn extract_index_templates() {
+        let res = json!({
+            "index_templates": [
+                { "name": "logs", "index_template": { "index_patterns": ["logs-*"] } }
+            ]
+        });
+
+        let items = TemplateKind::IndexTemplate.extract(res);
+
+        assert_eq!(
+            vec![("logs".to_owned(), json!({ "index_patterns": ["logs-*"] }))],
+            items
+        );
+    }
+
+    #[test]
+    fn extract_ilm_policies() {
+        let res = json!({
+            "logs": {
+                "version": 1,
+                "modified_date": "2020-01-01T00:00:00.000Z",
+                "policy": { "phases": {} }
+            }
+        });
+
+        let items = TemplateKind::IlmPolicy.extract(res);
+
+        assert_eq!(vec![("logs".to_owned(), json!({ "phases": {} }))], items);
+    }
+
+    #[test]
+    fn put_ilm_policy() {
+        let put = TemplateKind::IlmPolicy
+            .put("logs", &json!({ "phases": {} }))
+            .unwrap();
+
+        let body: Value = serde_json::from_slice(&put.body.unwrap()).unwrap();
+
+        assert_eq!("/_ilm/policy/logs", put.url.as_ref());
+        assert_eq!(json!({ "policy": { "phases": {} } }), body);
+    }
+
+    #[test]
+    fn items_are_ordered_for_import() {
+        let mut bundle = TemplateBundle::new();
+        bundle.insert(TemplateKind::IndexTemplate, "logs", json!({}));
+        bundle.insert(TemplateKind::ComponentTemplate, "logs-settings", json!({}));
+        bundle.insert(TemplateKind::IngestPipeline, "logs", json!({}));
+        bundle.insert(TemplateKind::IlmPolicy, "logs", json!({}));
+
+        let kinds: Vec<_> = bundle.into_items().into_iter().map(|item| item.kind).collect();
+
+        assert_eq!(
+            vec![
+                TemplateKind::IlmPolicy,
+                TemplateKind::IngestPipeline,
+                TemplateKind::ComponentTemplate,
+                TemplateKind::IndexTemplate,
+            ],
+            kinds
+        );
+    }
+
+    #[test]
+    fn bundle_roundtrip() {
+        let mut bundle = TemplateBundle::new();
+        bundle.insert(TemplateKind::IngestPipeline, "logs", json!({ "processors": [] }));
+
+        let ser = serde_json::to_value(&bundle).unwrap();
+        assert_eq!(json!({ "ingest_pipelines": { "logs": { "processors": [] } } }), ser);
+
+        let de: TemplateBundle = serde_json::from_value(ser).unwrap();
+        assert_eq!(bundle, de);
+    }
+
+    fn checked_items() -> Vec<(BundleItem, bool)> {
+        let mut bundle = TemplateBundle::new();
+        bundle.insert(TemplateKind::IndexTemplate, "existing", json!({}));
+        bundle.insert(TemplateKind::IndexTemplate, "new", json!({}));
+
+        bundle
+            .into_items()
+            .into_iter()
+            .map(|item| {
+                let exists = item.name == "existing";
+                (item, exists)
+            })
+            .collect()
+    }
+
+    fn outcomes(plan: Vec<(BundleItem, ImportOutcome)>) -> Vec<ImportOutcome> {
+        plan.into_iter().map(|(_, outcome)| outcome).collect()
+    }
+
+    #[test]
+    fn plan_import_fail_on_conflict() {
+        assert!(plan_import(checked_items(), ConflictPolicy::Fail).is_err());
+    }
+
+    #[test]
+    fn plan_import_skip_conflicts() {
+        let plan = plan_import(checked_items(), ConflictPolicy::Skip).unwrap();
+
+        assert_eq!(
+            vec![ImportOutcome::Skipped, ImportOutcome::Created],
+            outcomes(plan)
+        );
+    }
+
+    #[test]
+    fn plan_import_overwrite_conflicts() {
+        let plan = plan_import(checked_items(), ConflictPolicy::Overwrite).unwrap();
+
+        assert_eq!(
+            vec![ImportOutcome::Overwritten, ImportOutcome::Created],
+            outcomes(plan)
+        );
+    }
+}
@@ -1,19 +1,15 @@
-use std::sync::Arc;
-
 use futures::{
-    future::lazy,
     Future,
-    Poll,
     Stream,
 };
-use reqwest::async::Response as RawResponse;
 use serde::de::DeserializeOwned;
-use tokio_threadpool::ThreadPool;
+use tokio::runtime::Handle;
 
 use super::parse::{
     parse,
     IsOk,
 };
+use client::sender::NodeAddress;
 use error::{
     self,
     Error,
@@ -23,6 +19,11 @@ use http::{
     AsyncHttpResponse,
     StatusCode,
 };
+use runtime::Blocking;
+use std_future::{
+    box_future,
+    BoxFuture,
+};
 
 /**
 A builder for a response.
@@ -31,27 +32,60 @@ This structure wraps the completed HTTP response but gives you options for conve
 You can also `Read` directly from the response body.
 */
 pub struct AsyncResponseBuilder {
-    inner: RawResponse,
-    status: StatusCode,
-    de_pool: Option<Arc<ThreadPool>>,
+    inner: AsyncHttpResponse,
+    node: NodeAddress,
+    de_pool: Option<Handle>,
 }
 
 pub(crate) fn async_response(
-    res: RawResponse,
-    de_pool: Option<Arc<ThreadPool>>,
-) -> Result<AsyncResponseBuilder, Error> {
-    let status = StatusCode::from_u16(res.status().into()).map_err(error::request)?;
-    Ok(AsyncResponseBuilder {
+    res: AsyncHttpResponse,
+    node: NodeAddress,
+    de_pool: Option<Handle>,
+) -> AsyncResponseBuilder {
+    AsyncResponseBuilder {
         inner: res,
-        status,
+        node,
         de_pool: de_pool,
-    })
+    }
 }
 
 impl AsyncResponseBuilder {
+    /**
+    Create a response from the status and body returned by a node.
+
+    The body is a stream of chunks of bytes.
+    This method is used by custom [`Sender`][Sender] implementations.
+
+    [Sender]: ../trait.Sender.html
+    */
+    pub fn new<TBody>(node: NodeAddress, status: StatusCode, body: TBody) -> Self
+    where
+        TBody: Stream<Error = Error> + Send + 'static,
+        TBody::Item: AsRef<[u8]>,
+    {
+        let body = body.map(|bytes| AsyncChunk::from(bytes.as_ref().to_vec()));
+
+        AsyncResponseBuilder {
+            inner: AsyncHttpResponse::new(status, body),
+            node,
+            de_pool: None,
+        }
+    }
+
     /** Get the HTTP status for the response. */
     pub fn status(&self) -> StatusCode {
-        self.status
+        self.inner.status()
+    }
+
+    /**
+    Get the address of the node that served the request.
+
+    Follow-up requests, like fetching the next page of a scroll, can be sent back to the same node using [`RequestBuilder.node`][RequestBuilder.node].
+
+    [RequestBuilder.node]: ../requests/struct.RequestBuilder.html#method.node
+    */
+    pub fn node(&self) -> &NodeAddress {
+        &self.node
     }
 
     /**
@@ -60,7 +94,7 @@ impl AsyncResponseBuilder {
     Convert the builder into a raw HTTP response that implements `Read`.
     */
     pub fn into_raw(self) -> AsyncHttpResponse {
-        AsyncHttpResponse::from_raw(self.status, self.inner)
+        self.inner
     }
 
     /**
@@ -75,32 +109,26 @@ impl AsyncResponseBuilder {
 
     Get a strongly typed `SearchResponse`:
 
-    ```no_run
-    # extern crate tokio;
-    # extern crate futures;
+    ```edition2018,no_run
     # extern crate serde;
     # #[macro_use] extern crate serde_derive;
     # #[macro_use] extern crate elastic_derive;
-    # extern crate elastic;
-    # use futures::Future;
     # use elastic::prelude::*;
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # #[derive(Debug, Serialize, Deserialize, ElasticType)]
     # struct MyType { }
     # let client = AsyncClientBuilder::new().build()?;
-    let future = client.request(SimpleSearchRequest::for_index_ty("myindex", "mytype"))
-                       .send()
-                       .and_then(|response| response.into_response::<SearchResponse<MyType>>());
-
-    future.and_then(|response| {
-        // Iterate through the hits (of type `MyType`)
-        for hit in response.hits() {
-            println!("{:?}", hit);
-        }
-
-        Ok(())
-    });
+    let response = client.request(SimpleSearchRequest::for_index_ty("myindex", "mytype"))
+                         .send()
+                         .await?
+                         .into_response::<SearchResponse<MyType>>()
+                         .await?;
+
+    // Iterate through the hits (of type `MyType`)
+    for hit in response.hits() {
+        println!("{:?}", hit);
+    }
     # Ok(())
     # }
     ```
@@ -108,20 +136,17 @@ impl AsyncResponseBuilder {
     You can also read a response as a `serde_json::Value`, which will be `Ok(Value)`
     if the HTTP status code is `Ok` or `Err(ApiError)` otherwise:
 
-    ```no_run
-    # extern crate tokio;
-    # extern crate futures;
-    # extern crate serde_json;
-    # extern crate elastic;
-    # use futures::Future;
+    ```edition2018,no_run
     # use serde_json::Value;
     # use elastic::prelude::*;
-    # fn main() { run().unwrap() }
-    # fn run() -> Result<(), Box<::std::error::Error>> {
+    # fn main() {}
+    # async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
     # let client = AsyncClientBuilder::new().build()?;
-    let future = client.request(SimpleSearchRequest::for_index_ty("myindex", "mytype"))
-                       .send()
-                       .and_then(|response| response.into_response::<Value>());
+    let response = client.request(SimpleSearchRequest::for_index_ty("myindex", "mytype"))
+                         .send()
+                         .await?
+                         .into_response::<Value>()
+                         .await?;
     # Ok(())
     # }
     ```
@@ -132,20 +157,24 @@ impl AsyncResponseBuilder {
     where
         T: IsOk + DeserializeOwned + Send + 'static,
     {
-        let status = self.status;
-        let body = self.inner.into_body();
+        let status = self.inner.status();
+        let body = self.inner;
 
-        let de_fn = move |body: AsyncChunk| {
+        let de_fn = move |body: Vec<u8>| {
             parse()
-                .from_slice(status, body.as_ref())
+                .from_slice(status, &body)
                 .map_err(move |e| error::response(status, e))
         };
 
-        let body_future = body.concat2().map_err(move |e| error::response(status, e));
+        let body_future = body.fold(Vec::new(), |mut body, chunk| {
+            body.extend_from_slice(&chunk);
+
+            Ok::<_, Error>(body)
+        });
 
         if let Some(de_pool) = self.de_pool {
             IntoResponse::new(
-                body_future.and_then(move |body| de_pool.spawn_handle(lazy(move || de_fn(body)))),
+                body_future.and_then(move |body| Blocking::spawn(&de_pool, move || de_fn(body))),
             )
         } else {
             IntoResponse::new(body_future.and_then(de_fn))
@@ -155,7 +184,7 @@ impl AsyncResponseBuilder {
 
 /** A future returned by calling `into_response`. */
 pub struct IntoResponse<T> {
-    inner: Box<Future<Item = T, Error = Error> + Send>,
+    inner: BoxFuture<T>,
 }
 
 impl<T> IntoResponse<T> {
@@ -164,25 +193,19 @@ impl<T> IntoResponse<T> {
         F: Future<Item = T, Error = Error> + Send + 'static,
     {
         IntoResponse {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl<T> Future for IntoResponse<T>
-where
-    T: IsOk + DeserializeOwned + Send + 'static,
-{
-    type Item = T;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(IntoResponse<T> => T, T);
 
 #[cfg(test)]
 mod tests {
+    use futures::stream;
+    use serde_json::Value;
+
+    use super::*;
     use client;
     use tests::*;
 
@@ -190,4 +213,20 @@ mod tests {
     fn is_send() {
         assert_send::<super::IntoResponse<client::responses::PingResponse>>();
     }
+
+    #[test]
+    fn custom_response_from_stream() {
+        let body = stream::iter_ok::<_, Error>(vec![&b"{\"ok\""[..], &b":true}"[..]]);
+        let res = AsyncResponseBuilder::new(
+            NodeAddress::from("http://localhost:9200"),
+            StatusCode::OK,
+            body,
+        );
+
+        assert_eq!(StatusCode::OK, res.status());
+        assert_eq!(
+            json!({ "ok": true }),
+            res.into_response::<Value>().compat().wait().unwrap()
+        );
+    }
 }
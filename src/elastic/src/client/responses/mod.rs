@@ -27,9 +27,17 @@ pub use elastic_responses::{
     IndexResponse,
     IndicesExistsResponse,
     PingResponse,
+    ReindexResponse,
+    ReindexStatus,
     SearchResponse,
+    RestoredSnapshot,
     Shards,
+    SnapshotRestoreResponse,
     SqlResponse,
+    Task,
+    TaskResponse,
+    TaskStartedResponse,
+    TasksResponse,
     UpdateResponse,
 };
 
@@ -52,10 +60,14 @@ pub mod prelude {
         IndexResponse,
         IndicesExistsResponse,
         PingResponse,
+        ReindexResponse,
         SearchResponse,
         Shards,
+        SnapshotRestoreResponse,
         SqlResponse,
         SyncResponseBuilder,
+        TaskResponse,
+        TasksResponse,
         UpdateResponse,
     };
 }
@@ -1,10 +1,11 @@
-use reqwest::Response as RawResponse;
 use serde::de::DeserializeOwned;
+use std::io::Read;
 
 use super::parse::{
     parse,
     IsOk,
 };
+use client::sender::NodeAddress;
 use error::{
     self,
     Result,
@@ -20,17 +21,37 @@ A builder for a response.
 This structure wraps the completed HTTP response but gives you options for converting it into a concrete type.
 You can also `Read` directly from the response body.
 */
-pub struct SyncResponseBuilder(StatusCode, RawResponse);
-
-pub(crate) fn sync_response(res: RawResponse) -> Result<SyncResponseBuilder> {
-    let status = StatusCode::from_u16(res.status().into()).map_err(error::request)?;
-    Ok(SyncResponseBuilder(status, res))
-}
+pub struct SyncResponseBuilder(SyncHttpResponse, NodeAddress);
 
 impl SyncResponseBuilder {
+    /**
+    Create a response from the status and body returned by a node.
+
+    This method is used by custom [`Sender`][Sender] implementations.
+
+    [Sender]: ../trait.Sender.html
+    */
+    pub fn new<TBody>(node: NodeAddress, status: StatusCode, body: TBody) -> Self
+    where
+        TBody: Read + Send + 'static,
+    {
+        SyncResponseBuilder(SyncHttpResponse::new(status, body), node)
+    }
+
     /** Get the HTTP status for the response. */
     pub fn status(&self) -> StatusCode {
-        self.0
+        self.0.status()
+    }
+
+    /**
+    Get the address of the node that served the request.
+
+    Follow-up requests, like fetching the next page of a scroll, can be sent back to the same node using [`RequestBuilder.node`][RequestBuilder.node].
+
+    [RequestBuilder.node]: ../requests/struct.RequestBuilder.html#method.node
+    */
+    pub fn node(&self) -> &NodeAddress {
+        &self.1
     }
 
     /**
@@ -39,7 +60,7 @@ impl SyncResponseBuilder {
     Convert the builder into a raw HTTP response that implements `Read`.
     */
     pub fn into_raw(self) -> SyncHttpResponse {
-        SyncHttpResponse::from_raw(self.0, self.1)
+        self.0
     }
 
     /**
@@ -101,9 +122,9 @@ impl SyncResponseBuilder {
     where
         T: IsOk + DeserializeOwned,
     {
-        let status = self.0;
+        let status = self.0.status();
         parse()
-            .from_reader(status, self.1)
+            .from_reader(status, self.0)
             .map_err(|e| error::response(status, e))
     }
 }
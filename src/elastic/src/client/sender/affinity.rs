@@ -0,0 +1,285 @@
+/*!
+Route requests for a search context to the node that created it.
+
+Scrolls and point-in-time searches keep a search context open on the node that served the initial search.
+Follow-up requests, like fetching the next page of a scroll or searching a point-in-time, are cheaper when they're sent to that same node, because it doesn't need to fetch the context from another node in the cluster.
+
+Node affinity is disabled by default.
+Enable it with the `node_affinity` method on a [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+
+When a search sent with the client's [`search`][Client.search] builder returns a scroll id or point-in-time id, the client remembers the node that served it.
+The ids are read from the deserialised [`SearchResponse`][SearchResponse], so the response body isn't parsed twice.
+Follow-up requests that reference the id in their body are then sent to that node, using the same parameters as [`RequestBuilder.node`][RequestBuilder.node].
+These are `scroll` and `clear_scroll` requests with a `scroll_id`, `search` requests with a `pit.id`, and requests to close a point-in-time.
+The id is forgotten once its search context is cleared, or when a request for it fails because the node is unavailable.
+
+Responses to raw requests aren't deserialised by the client, so their search contexts aren't remembered.
+Send their follow-up requests to the node returned by the response's `node` method using `RequestBuilder.node` instead.
+Requests with explicit parameters, like ones sent to a specific node using `RequestBuilder.node`, aren't affected.
+
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+[Client.search]: ../../struct.Client.html#method.search
+[SearchResponse]: ../../responses/struct.SearchResponse.html
+[RequestBuilder.node]: ../../requests/struct.RequestBuilder.html#method.node
+*/
+
+use serde_json::{
+    self,
+    Value,
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use client::{
+    responses::SearchResponse,
+    sender::{
+        observer::RequestOutcome,
+        NodeAddress,
+    },
+};
+use http::BufferedBody;
+
+/**
+The maximum number of search contexts to remember a node for.
+
+Contexts that are never cleared are left to expire on the node, so once the limit is reached they're all forgotten.
+*/
+const MAX_SEARCH_CONTEXTS: usize = 1024;
+
+/** The nodes that served search contexts. */
+#[derive(Clone, Default)]
+pub(crate) struct NodeAffinity {
+    nodes: Arc<Mutex<HashMap<String, NodeAddress>>>,
+}
+
+/** The search contexts referenced by a request. */
+#[derive(Clone, Default)]
+pub(crate) struct SearchContexts {
+    endpoint: &'static str,
+    ids: Vec<String>,
+}
+
+impl NodeAffinity {
+    /** Remember the node that served any search contexts opened by a search. */
+    pub(crate) fn pin_search<TDocument>(
+        &self,
+        res: &SearchResponse<TDocument>,
+        node: &NodeAddress,
+    ) {
+        for id in res.scroll_id().into_iter().chain(res.pit_id()) {
+            self.pin(id, node);
+        }
+    }
+
+    fn pin(&self, id: &str, node: &NodeAddress) {
+        let mut nodes = self.nodes.lock().expect("lock poisoned");
+
+        if nodes.len() >= MAX_SEARCH_CONTEXTS && !nodes.contains_key(id) {
+            nodes.clear();
+        }
+
+        nodes.insert(id.to_owned(), node.clone());
+    }
+
+    fn unpin(&self, ids: &[String]) {
+        let mut nodes = self.nodes.lock().expect("lock poisoned");
+
+        for id in ids {
+            nodes.remove(id);
+        }
+    }
+
+    /**
+    Get the search contexts referenced by a request.
+
+    The body is only parsed if there are search contexts that could be referenced.
+    */
+    pub(crate) fn contexts<TBody>(
+        &self,
+        endpoint: &'static str,
+        body: Option<&TBody>,
+    ) -> SearchContexts
+    where
+        TBody: BufferedBody,
+    {
+        let is_empty = self.nodes.lock().expect("lock poisoned").is_empty();
+
+        let ids = match body.and_then(BufferedBody::buffered) {
+            Some(body) if !is_empty => search_context_ids(endpoint, body),
+            _ => Vec::new(),
+        };
+
+        SearchContexts { endpoint, ids }
+    }
+
+    /** Get the node that served the search contexts referenced by a request. */
+    pub(crate) fn node(&self, contexts: &SearchContexts) -> Option<NodeAddress> {
+        let nodes = self.nodes.lock().expect("lock poisoned");
+
+        contexts
+            .ids
+            .iter()
+            .filter_map(|id| nodes.get(id))
+            .next()
+            .cloned()
+    }
+
+    /** Forget search contexts that were cleared or whose node failed. */
+    pub(crate) fn on_complete(&self, contexts: &SearchContexts, outcome: RequestOutcome) {
+        if contexts.ids.is_empty() {
+            return;
+        }
+
+        let cleared = match (contexts.endpoint, outcome) {
+            ("clear_scroll", RequestOutcome::Response(status))
+            | ("close_point_in_time", RequestOutcome::Response(status)) => status.is_success(),
+            _ => false,
+        };
+
+        if cleared || outcome.is_node_failure() {
+            self.unpin(&contexts.ids);
+        }
+    }
+}
+
+/** Find the ids of search contexts referenced in the body of a request. */
+fn search_context_ids(endpoint: &str, body: &[u8]) -> Vec<String> {
+    let pointer = match endpoint {
+        "scroll" | "clear_scroll" => "/scroll_id",
+        "search" => "/pit/id",
+        "close_point_in_time" => "/id",
+        _ => return Vec::new(),
+    };
+
+    let body: Value = match serde_json::from_slice(body) {
+        Ok(body) => body,
+        Err(_) => return Vec::new(),
+    };
+
+    match body.pointer(pointer) {
+        Some(Value::String(id)) => vec![id.to_owned()],
+        Some(Value::Array(ids)) => ids
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_owned)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::responses::parse::parse;
+    use http::{
+        StatusCode,
+        SyncBody,
+    };
+
+    fn contexts(
+        affinity: &NodeAffinity,
+        endpoint: &'static str,
+        body: &'static str,
+    ) -> SearchContexts {
+        affinity.contexts(endpoint, Some(&SyncBody::from(body)))
+    }
+
+    #[test]
+    fn follow_up_requests_use_pinned_node() {
+        let affinity = NodeAffinity::default();
+        let node = NodeAddress::from("http://a:9200");
+
+        affinity.pin("scroll-1", &node);
+        affinity.pin("pit-1", &node);
+
+        let scroll = contexts(
+            &affinity,
+            "scroll",
+            r#"{"scroll":"1m","scroll_id":"scroll-1"}"#,
+        );
+        let clear = contexts(&affinity, "clear_scroll", r#"{"scroll_id":["scroll-1"]}"#);
+        let search = contexts(&affinity, "search", r#"{"pit":{"id":"pit-1"}}"#);
+        let other = contexts(&affinity, "search", r#"{"query":{"match_all":{}}}"#);
+
+        assert_eq!(Some(&node), affinity.node(&scroll).as_ref());
+        assert_eq!(Some(&node), affinity.node(&clear).as_ref());
+        assert_eq!(Some(&node), affinity.node(&search).as_ref());
+        assert_eq!(None, affinity.node(&other));
+    }
+
+    #[test]
+    fn cleared_contexts_are_forgotten() {
+        let affinity = NodeAffinity::default();
+        affinity.pin("scroll-1", &NodeAddress::from("http://a:9200"));
+
+        let clear = contexts(&affinity, "clear_scroll", r#"{"scroll_id":"scroll-1"}"#);
+        affinity.on_complete(&clear, RequestOutcome::Response(StatusCode::OK));
+
+        assert_eq!(None, affinity.node(&clear));
+    }
+
+    #[test]
+    fn contexts_on_failed_nodes_are_forgotten() {
+        let affinity = NodeAffinity::default();
+        affinity.pin("scroll-1", &NodeAddress::from("http://a:9200"));
+
+        let scroll = contexts(&affinity, "scroll", r#"{"scroll_id":"scroll-1"}"#);
+
+        affinity.on_complete(&scroll, RequestOutcome::Response(StatusCode::OK));
+        assert!(affinity.node(&scroll).is_some());
+
+        affinity.on_complete(
+            &scroll,
+            RequestOutcome::Response(StatusCode::SERVICE_UNAVAILABLE),
+        );
+        assert_eq!(None, affinity.node(&scroll));
+    }
+
+    #[test]
+    fn searches_pin_opened_contexts() {
+        let affinity = NodeAffinity::default();
+        let node = NodeAddress::from("http://a:9200");
+
+        let res: SearchResponse<Value> = parse()
+            .from_slice(
+                StatusCode::OK,
+                br#"{
+                    "took": 1,
+                    "timed_out": false,
+                    "_shards": { "total": 1, "successful": 1, "failed": 0 },
+                    "hits": { "total": 0, "max_score": null, "hits": [] },
+                    "_scroll_id": "scroll-1",
+                    "pit_id": "pit-1"
+                }"#,
+            )
+            .unwrap();
+
+        affinity.pin_search(&res, &node);
+
+        let scroll = contexts(&affinity, "scroll", r#"{"scroll_id":"scroll-1"}"#);
+        let search = contexts(&affinity, "search", r#"{"pit":{"id":"pit-1"}}"#);
+
+        assert_eq!(Some(&node), affinity.node(&scroll).as_ref());
+        assert_eq!(Some(&node), affinity.node(&search).as_ref());
+    }
+
+    #[test]
+    fn contexts_are_forgotten_at_limit() {
+        let affinity = NodeAffinity::default();
+        let node = NodeAddress::from("http://a:9200");
+
+        for i in 0..MAX_SEARCH_CONTEXTS + 1 {
+            affinity.pin(&i.to_string(), &node);
+        }
+
+        let nodes = affinity.nodes.lock().unwrap();
+        assert_eq!(1, nodes.len());
+        assert!(nodes.contains_key(&MAX_SEARCH_CONTEXTS.to_string()));
+    }
+}
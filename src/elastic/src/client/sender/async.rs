@@ -1,37 +1,101 @@
-use fluent_builder::SharedFluentBuilder;
+use fluent_builder::{
+    SharedFluentBuilder,
+    TryIntoValue,
+};
 use futures::{
     future::{
-        lazy,
+        loop_fn,
         Either,
         FutureResult,
+        Loop,
     },
     Future,
     IntoFuture,
     Poll,
 };
-use reqwest::async::{
+use futures_util::compat::Compat;
+use reqwest::{
     Client as AsyncHttpClient,
+    ClientBuilder as AsyncHttpClientBuilder,
+    Error as AsyncHttpError,
     RequestBuilder as AsyncHttpRequestBuilder,
 };
 use std::{
     error::Error as StdError,
     sync::Arc,
+    time::{
+        Duration,
+        Instant,
+    },
 };
-use tokio_threadpool::{
-    SpawnHandle,
-    ThreadPool,
-};
+use tokio::runtime::Handle;
 
 use client::{
-    requests::Endpoint,
+    requests::{
+        DefaultBody,
+        Endpoint,
+        PingRequest,
+    },
     responses::{
         async_response,
         AsyncResponseBuilder,
+        PingResponse,
     },
     sender::{
+        affinity::{
+            NodeAffinity,
+            SearchContexts,
+        },
+        auth::{
+            Auth,
+            Credentials,
+            RefreshCredentials,
+        },
         build_reqwest_method,
+        cloud_id::CloudId,
+        cluster_info::{
+            ClusterCheck,
+            ClusterInfo,
+        },
+        compression::RequestCompression,
         build_url,
+        dead_nodes::DeadNodes,
+        logging::{
+            log_slow_request,
+            opaque_id_header,
+            CorrelationId,
+            X_OPAQUE_ID,
+        },
+        middleware::{
+            Middleware,
+            Middlewares,
+            SharedMiddleware,
+        },
+        observer::{
+            Observation,
+            Observer,
+            RequestErrorKind,
+            RequestOutcome,
+            SharedObserver,
+        },
+        pool::ConnectionPool,
+        proxy::Proxy,
+        rate_limit::{
+            RateLimit,
+            RateLimiter,
+        },
+        retry::RetryPolicy,
+        shutdown::{
+            Close,
+            Shutdown,
+        },
         sniffed_nodes::SniffedNodesBuilder,
+        static_nodes::{
+            RoundRobin,
+            SharedStrategy,
+            Strategy,
+        },
+        tls::Tls,
         NextParams,
         NodeAddress,
         NodeAddresses,
@@ -50,11 +114,31 @@ use error::{
     Error,
 };
 use http::{
+    header::{
+        HeaderName,
+        HeaderValue,
+    },
     AsyncBody,
     AsyncHttpRequest,
+    AsyncHttpResponse,
+    StatusCode,
     Url,
 };
 use private;
+use runtime::{
+    self,
+    Blocking,
+    Delay,
+};
+use std_future::{
+    box_future,
+    BoxFuture,
+};
+
+#[cfg(feature = "aws-sigv4")]
+use client::sender::aws_sigv4::AwsSigV4;
+#[cfg(feature = "testing")]
+use client::sender::mock::MockSender;
 
 /**
 An asynchronous Elasticsearch client.
@@ -67,20 +151,16 @@ For more details about the methods available to an `AsyncClient`, see the base [
 Create an asynchronous `Client` and send a ping request:
 
 ```no_run
-# extern crate futures;
 # extern crate tokio;
 # extern crate elastic;
-# use futures::Future;
 # use elastic::prelude::*;
 # fn main() { run().unwrap() }
 # fn run() -> Result<(), Box<::std::error::Error>> {
 let client = AsyncClientBuilder::new().build()?;
+let runtime = tokio::runtime::Runtime::new()?;
 
-let response_future = client.request(PingRequest::new())
-                            .send()
-                            .and_then(|res| res.into_response::<PingResponse>());
-
-tokio::runtime::current_thread::block_on_all(response_future)?;
+let response = runtime.block_on(client.request(PingRequest::new()).send())?;
+let ping = runtime.block_on(response.into_response::<PingResponse>())?;
 # Ok(())
 # }
 ```
@@ -94,7 +174,7 @@ pub type AsyncClient = Client<AsyncSender>;
 #[derive(Clone)]
 pub struct AsyncSender {
     pub(in client) http: AsyncHttpClient,
-    pub(in client) serde_pool: Option<Arc<ThreadPool>>,
+    pub(in client) serde_pool: Option<Handle>,
     pre_send: Option<
         Arc<
             Fn(
@@ -105,21 +185,435 @@ pub struct AsyncSender {
                 + Sync,
         >,
     >,
+    auth: Option<Auth>,
+    middleware: Middlewares,
+    observer: Option<SharedObserver>,
+    strategy: SharedStrategy,
+    retry: Option<RetryPolicy>,
+    compression: Option<RequestCompression>,
+    slow_requests: Option<Duration>,
+    rate_limit: Option<RateLimiter>,
+    pub(in client) affinity: Option<NodeAffinity>,
+    pub(in client) shutdown: Shutdown,
+    pub(in client) cluster: ClusterCheck,
+    #[cfg(feature = "aws-sigv4")]
+    aws_sigv4: Option<AwsSigV4>,
+    #[cfg(feature = "testing")]
+    mock: Option<MockSender>,
 }
 
 impl private::Sealed for AsyncSender {}
 
+/** Select another node to retry a request that failed to connect on. */
+type Reselect = Arc<Fn() -> Box<Future<Item = NodeAddress, Error = Error> + Send> + Send + Sync>;
+
 impl AsyncSender {
+    /** Whether requests are signed, so their bodies can't be streamed. */
+    #[cfg(feature = "aws-sigv4")]
+    pub(super) fn signs_requests(&self) -> bool {
+        self.aws_sigv4.is_some()
+    }
+
+    /**
+    Send a request to a node, retrying it if the sender has a retry policy.
+
+    Requests that fail to connect are retried on the node returned by `reselect`, if there is one.
+    The node that sent the response is returned along with it.
+    Failures are recorded on the observation.
+    */
+    fn execute(
+        &self,
+        correlation_id: CorrelationId,
+        node: NodeAddress,
+        req: AsyncHttpRequest,
+        contexts: SearchContexts,
+        reselect: Option<Reselect>,
+        observation: Observation,
+    ) -> Box<Future<Item = (NodeAddress, AsyncHttpResponse), Error = Error> + Send> {
+        let sender = self.clone();
+
+        let attempts = loop_fn((req, node, 1), move |(req, node, attempt)| {
+            // Keep a copy of the request in case it needs to be retried
+            let retry_req = sender.retry.and_then(|_| req.try_clone());
+            let method = req.method.clone();
+
+            #[cfg(feature = "testing")]
+            let mock_res = sender.mock.as_ref().map(|mock| mock.send(&req));
+            #[cfg(feature = "testing")]
+            let needs_runtime = mock_res.is_none();
+            #[cfg(not(feature = "testing"))]
+            let needs_runtime = true;
+
+            // Connections are driven by a `tokio` runtime, so fail instead of panicking without one
+            if needs_runtime {
+                if let Err(e) = runtime::current() {
+                    observation.error(Some(&node), RequestErrorKind::Request);
+                    return Either::A(Err(error::request(e)).into_future());
+                }
+            }
+
+            let raw_req = match build_reqwest(&sender.http, req).build() {
+                Ok(raw_req) => raw_req,
+                Err(e) => {
+                    observation.error(Some(&node), RequestErrorKind::Request);
+                    return Either::A(Err(error::request(e)).into_future());
+                }
+            };
+
+            sender.strategy.on_send(&node);
+
+            // Mock responses can fail with an `io::Error` instead of a `reqwest::Error`
+            #[cfg(feature = "testing")]
+            let pending = match mock_res {
+                Some(mock_res) => {
+                    Either::A(mock_res.map_err(|e| error::wrapped(Box::new(e))).into_future())
+                }
+                None => Either::B(
+                    execute_reqwest(&sender.http, raw_req).map_err(|e| error::wrapped(Box::new(e))),
+                ),
+            };
+            #[cfg(not(feature = "testing"))]
+            let pending = execute_reqwest(&sender.http, raw_req);
+            let sender = sender.clone();
+            let observation = observation.clone();
+            let correlation_id = correlation_id.clone();
+            let contexts = contexts.clone();
+            let reselect = reselect.clone();
+
+            Either::B(pending.then(move |res| {
+                let outcome = match res {
+                    Ok(ref res) => RequestOutcome::Response(res.status()),
+                    Err(_) => RequestOutcome::Error(RequestErrorKind::Transport),
+                };
+
+                sender.strategy.on_complete(&node, outcome);
+                if let Some(ref affinity) = sender.affinity {
+                    affinity.on_complete(&contexts, outcome);
+                }
+
+                let backoff = sender
+                    .retry
+                    .and_then(|retry| retry.next_attempt(&method, attempt, outcome));
+
+                match (backoff, retry_req) {
+                    (Some(backoff), Some(retry_req)) => {
+                        warn!(
+                            "Elasticsearch Request Retry: correlation_id: '{}', attempt: '{}', outcome: '{:?}', backoff: '{:?}'",
+                            correlation_id, attempt, outcome, backoff
+                        );
+
+                        let delay = Delay::new(Instant::now() + backoff).map_err(error::request);
+
+                        // Requests that failed to connect are retried on the next node
+                        let retry = match (outcome, reselect) {
+                            (RequestOutcome::Error(RequestErrorKind::Transport), Some(reselect)) => {
+                                Either::A(delay.and_then(move |_| {
+                                    reselect()
+                                        .then(move |next_node| match next_node {
+                                            Ok(next_node) => {
+                                                sender.rebase(retry_req, node, next_node)
+                                            }
+                                            Err(_) => Box::new(Ok((retry_req, node)).into_future()),
+                                        })
+                                        .map(move |(retry_req, node)| {
+                                            Loop::Continue((retry_req, node, attempt + 1))
+                                        })
+                                }))
+                            }
+                            _ => Either::B(
+                                delay.map(move |_| Loop::Continue((retry_req, node, attempt + 1))),
+                            ),
+                        };
+
+                        Either::A(retry)
+                    }
+                    _ => {
+                        let exhausted = sender
+                            .retry
+                            .map(|retry| retry.is_exhausted(attempt, outcome))
+                            .unwrap_or(false);
+
+                        let res = match res {
+                            // Surface the number of retries when every attempt got a retryable status
+                            Ok(res) if exhausted => {
+                                observation.response(&node, res.status());
+
+                                Err(error::retry(
+                                    attempt - 1,
+                                    error::message(format!(
+                                        "the node responded with a `{}` status",
+                                        res.status()
+                                    )),
+                                ))
+                            }
+                            Ok(res) => Ok(Loop::Break((node, res))),
+                            Err(e) => {
+                                observation.error(Some(&node), RequestErrorKind::Transport);
+
+                                if attempt > 1 {
+                                    Err(error::retry(attempt - 1, e))
+                                } else {
+                                    Err(error::request(e))
+                                }
+                            }
+                        };
+
+                        Either::B(res.into_future())
+                    }
+                }
+            }))
+        });
+
+        Box::new(attempts)
+    }
+
+    /**
+    Point a request that's being retried at another node.
+
+    The request stays on the same node if its url doesn't start with the address of that node.
+    Signed requests are signed again, because the signature covers the host.
+    */
+    fn rebase(
+        &self,
+        mut req: AsyncHttpRequest,
+        from: NodeAddress,
+        to: NodeAddress,
+    ) -> Box<Future<Item = (AsyncHttpRequest, NodeAddress), Error = Error> + Send> {
+        let url = match req.url.as_str().get(from.as_ref().len()..) {
+            Some(path) if req.url.as_str().starts_with(from.as_ref()) => {
+                format!("{}{}", to.as_ref(), path)
+            }
+            _ => return Box::new(Ok((req, from)).into_future()),
+        };
+
+        req.url = match Url::parse(&url) {
+            Ok(url) => url,
+            Err(e) => return Box::new(Err(error::request(e)).into_future()),
+        };
+
+        #[cfg(feature = "aws-sigv4")]
+        {
+            if let Some(ref aws_sigv4) = self.aws_sigv4 {
+                return Box::new(aws_sigv4.sign_blocking(req).map(move |req| (req, to)));
+            }
+        }
+
+        Box::new(Ok((req, to)).into_future())
+    }
+
+    /**
+    Build and send a request once its parameters have been resolved.
+
+    Failures are recorded on the observation.
+    */
+    fn send_request(
+        &self,
+        correlation_id: CorrelationId,
+        params: RequestParams,
+        endpoint: Endpoint<'static, AsyncBody>,
+        contexts: SearchContexts,
+        reselect: Option<Reselect>,
+        observation: Observation,
+        started: Instant,
+    ) -> Box<Future<Item = AsyncResponseBuilder, Error = Error> + Send> {
+        let serde_pool = self.serde_pool.clone();
+        let build_req_observation = observation.clone();
+        let compression_observation = observation.clone();
+        let compression_sender = self.clone();
+        let Endpoint {
+            url,
+            method,
+            body,
+            name,
+        } = endpoint;
+        let build_req_future = Url::parse(&build_url(&url, &params))
+            .map_err(|e| {
+                build_req_observation
+                    .error(Some(params.get_node_address()), RequestErrorKind::Request);
+                error::request(e)
+            })
+            .into_future()
+            .and_then(move |url| {
+                let node = params.get_node_address().clone();
+                let mut req = AsyncHttpRequest {
+                    url,
+                    method,
+                    headers: params.get_headers(),
+                    body,
+                };
+
+                match compression_sender.compression {
+                    // Compressing a body is cpu-bound, so it's done on the serde pool if there is one
+                    Some(compression) => Either::A(
+                        compression_sender
+                            .maybe_async(move || {
+                                compression.compress(&mut req)?;
+
+                                Ok(req)
+                            })
+                            .then(move |res| match res {
+                                Ok(req) => Ok((node, req)),
+                                Err(e) => {
+                                    compression_observation
+                                        .error(Some(&node), RequestErrorKind::Request);
+                                    Err(e)
+                                }
+                            }),
+                    ),
+                    None => Either::B(Ok((node, req)).into_future()),
+                }
+            });
+
+        let pre_send = self.pre_send.clone();
+        let pre_send_observation = observation.clone();
+        let pre_send_future = build_req_future.and_then(move |(node, mut req)| {
+            if let Some(pre_send) = pre_send {
+                Either::A(
+                    pre_send(&mut req)
+                        .map_err(error::wrapped)
+                        .map_err(error::request)
+                        .then(move |res| match res {
+                            Ok(_) => Ok((node, req)),
+                            Err(e) => {
+                                pre_send_observation.error(Some(&node), RequestErrorKind::Request);
+                                Err(e)
+                            }
+                        }),
+                )
+            } else {
+                Either::B(Ok((node, req)).into_future())
+            }
+        });
+
+        #[cfg(feature = "aws-sigv4")]
+        let pre_send_future = {
+            let aws_sigv4 = self.aws_sigv4.clone();
+            let sign_observation = observation.clone();
+
+            pre_send_future.and_then(move |(node, req)| match aws_sigv4 {
+                Some(aws_sigv4) => Either::A(aws_sigv4.sign_blocking(req).then(move |res| {
+                    match res {
+                        Ok(req) => Ok((node, req)),
+                        Err(e) => {
+                            sign_observation.error(Some(&node), RequestErrorKind::Request);
+                            Err(e)
+                        }
+                    }
+                })),
+                None => Either::B(Ok((node, req)).into_future()),
+            })
+        };
+
+        let pre_send_correlation_id = correlation_id.clone();
+        let pre_send_future = pre_send_future.log_err(move |e| {
+            error!(
+                "Elasticsearch Request: correlation_id: '{}', error: '{:?}'",
+                pre_send_correlation_id, e
+            )
+        });
+
+        let sender = self.clone();
+        let rate_limit = self.rate_limit.clone();
+        let rate_limit_correlation_id = correlation_id.clone();
+        let rate_limit_observation = observation.clone();
+        let rate_limit_future = pre_send_future.and_then(move |(node, req)| {
+            let acquire = match rate_limit {
+                Some(rate_limit) => Either::A(rate_limit.acquire_async().map(Some)),
+                None => Either::B(Ok(None).into_future()),
+            };
+
+            acquire.then(move |permit| match permit {
+                Ok(permit) => Ok((node, req, permit)),
+                Err(e) => {
+                    error!(
+                        "Elasticsearch Request Rate Limit: correlation_id: '{}', error: '{:?}'",
+                        rate_limit_correlation_id, e
+                    );
+                    rate_limit_observation.error(Some(&node), RequestErrorKind::RateLimited);
+                    Err(e)
+                }
+            })
+        });
+
+        let req_future = rate_limit_future.and_then(move |(node, req, permit)| {
+            let auth = sender.auth.clone();
+            let url = req.url.clone();
+            let response_correlation_id = correlation_id.clone();
+            let error_correlation_id = correlation_id.clone();
+
+            info!(
+                "Elasticsearch Request: correlation_id: '{}', endpoint: '{}', url: '{}'",
+                correlation_id, name, url
+            );
+            observation.request(&req);
+
+            sender
+                .execute(
+                    correlation_id.clone(),
+                    node,
+                    req,
+                    contexts,
+                    reselect,
+                    observation.clone(),
+                )
+                .then(move |res| {
+                    // The request is no longer in flight once its response has been received
+                    drop(permit);
+                    res
+                })
+                .and_then(move |(node, res)| {
+                    let refresh = if res.status() == StatusCode::UNAUTHORIZED {
+                        auth.as_ref().and_then(Auth::refresh)
+                    } else {
+                        None
+                    };
+
+                    match refresh {
+                        // The refresh future logs its own errors and never fails
+                        Some(refresh) => Either::A(refresh.then(move |_| Ok((node, res)))),
+                        None => Either::B(Ok((node, res)).into_future()),
+                    }
+                })
+                .and_then(move |(node, res)| {
+                    info!(
+                        "Elasticsearch Response: correlation_id: '{}', status: '{}', elapsed: '{:?}'",
+                        response_correlation_id,
+                        res.status(),
+                        started.elapsed()
+                    );
+                    log_slow_request(
+                        sender.slow_requests,
+                        &response_correlation_id,
+                        name,
+                        &url,
+                        started,
+                    );
+                    let res = async_response(res, node, serde_pool);
+                    observation.response(res.node(), res.status());
+
+                    Ok(res)
+                })
+                .log_err(move |e| {
+                    error!(
+                        "Elasticsearch Response: correlation_id: '{}', error: '{:?}'",
+                        error_correlation_id, e
+                    )
+                })
+        });
+
+        Box::new(req_future)
+    }
+
     pub(crate) fn maybe_async<TFn, TResult>(
         &self,
         f: TFn,
-    ) -> Either<SpawnHandle<TResult, Error>, FutureResult<TResult, Error>>
+    ) -> Either<Blocking<TResult>, FutureResult<TResult, Error>>
     where
         TFn: FnOnce() -> Result<TResult, Error> + Send + 'static,
         TResult: Send + 'static,
     {
-        if let Some(ref ser_pool) = self.serde_pool {
-            Either::A(ser_pool.spawn_handle(lazy(f)))
+        if let Some(ref serde_pool) = self.serde_pool {
+            Either::A(Blocking::spawn(serde_pool, f))
         } else {
             Either::B(f().into_future())
         }
@@ -140,136 +634,365 @@ impl Sender for AsyncSender {
         TBody: Into<Self::Body> + Send + 'static,
         TParams: Into<Self::Params> + Send + 'static,
     {
+        let started = Instant::now();
         let correlation_id = request.correlation_id;
-        let serde_pool = self.serde_pool.clone();
         let params = request.params;
         let Endpoint {
-            url, method, body, ..
+            url,
+            method,
+            body,
+            name,
         } = request.inner.into();
-
-        info!(
-            "Elasticsearch Request: correlation_id: '{}', path: '{}'",
-            correlation_id,
-            url.as_ref()
-        );
+        let body: Option<AsyncBody> = body.map(Into::into);
+        let contexts = match self.affinity {
+            Some(ref affinity) => affinity.contexts(name, body.as_ref()),
+            None => SearchContexts::default(),
+        };
+        let pinned = self
+            .affinity
+            .as_ref()
+            .and_then(|affinity| affinity.node(&contexts));
+        let observation = Observation::start(self.observer.as_ref(), name, &method, &url);
+
+        let in_flight = match self.shutdown.start_request() {
+            Ok(in_flight) => in_flight,
+            Err(e) => {
+                error!(
+                    "Elasticsearch Request: correlation_id: '{}', error: '{:?}'",
+                    correlation_id, e
+                );
+                observation.error(None, RequestErrorKind::Request);
+                return PendingResponse::new(Err(e).into_future());
+            }
+        };
 
         let params_future = match params {
-            SendableRequestParams::Value(params) => Either::A(Ok(params).into_future()),
-            SendableRequestParams::Builder { params, builder } => {
-                let params = params.into().log_err(move |e| {
-                    error!(
-                        "Elasticsearch Node Selection: correlation_id: '{}', error: '{:?}'",
-                        correlation_id, e
-                    )
-                });
+            SendableRequestParams::Value(params) => Either::A(Ok((params, None)).into_future()),
+            SendableRequestParams::Builder {
+                params,
+                builder,
+                next,
+                pin,
+            } => match pinned {
+                // Requests for a search context are sent to the node that created it without selecting one
+                Some(node) => {
+                    let params = builder.into_value(move || pin(node));
+
+                    Either::B(Either::A(Ok((params, None)).into_future()))
+                }
+                None => {
+                    let node_selection_observation = observation.clone();
+                    let params: PendingParams = params.into();
+                    let params = params.compat().log_err(move |e| {
+                        error!(
+                            "Elasticsearch Node Selection: correlation_id: '{}', error: '{:?}'",
+                            correlation_id, e
+                        );
+                        node_selection_observation.error(None, RequestErrorKind::NodeSelection);
+                    });
+
+                    let reselect: Reselect = Arc::new(move || {
+                        let params: PendingParams = next().into();
+                        let node = params
+                            .compat()
+                            .map(|params| params.get_node_address().clone());
+
+                        Box::new(node) as Box<Future<Item = NodeAddress, Error = Error> + Send>
+                    });
+
+                    Either::B(Either::B(params.and_then(move |params| {
+                        let selected = params.get_node_address().clone();
+                        let params = builder.into_value(move || params);
+
+                        // Requests with an explicit base url aren't sent to another node
+                        let reselect =
+                            Some(reselect).filter(|_| *params.get_node_address() == selected);
+
+                        Ok((params, reselect))
+                    })))
+                }
+            },
+        };
 
-                Either::B(params.and_then(|params| Ok(builder.into_value(move || params))))
-            }
+        // Ping the cluster to check its version and distribution before sending the first request
+        let params_future = if self.cluster.should_ping() {
+            let cluster = self.cluster.clone();
+            let pinger = AsyncSender {
+                cluster: self.cluster.unchecked(),
+                ..self.clone()
+            };
+
+            Either::A(params_future.and_then(move |(params, reselect)| {
+                let ping: SendableRequest<_, RequestParams, DefaultBody> = SendableRequest::new(
+                    PingRequest::new(),
+                    SendableRequestParams::Value(params.clone()),
+                );
+
+                pinger
+                    .send(ping)
+                    .compat()
+                    .and_then(|res| res.into_response::<PingResponse>().compat())
+                    .and_then(move |ping| {
+                        cluster.record(&ping)?;
+
+                        Ok((params, reselect))
+                    })
+            }))
+        } else {
+            Either::B(params_future)
         };
 
-        let build_req_future = params_future
-            .and_then(move |params| {
-                Url::parse(&build_url(&url, &params))
-                    .map_err(error::request)
-                    .map(|url| (params, url))
-            })
-            .and_then(move |(params, url)| {
-                Ok(AsyncHttpRequest {
-                    url,
-                    method,
-                    headers: params.get_headers(),
-                    body: body.map(|body| body.into()),
-                })
-            });
+        let middleware = self.middleware.clone();
+        let middleware_observation = observation.clone();
+        let middleware_method = method.clone();
+        let middleware_url = url.clone();
+        let params_future = params_future.and_then(move |(mut params, reselect)| {
+            let selected = params.get_node_address().clone();
 
-        let pre_send = self.pre_send.clone();
-        let pre_send_future = build_req_future.and_then(move |mut req| {
-            if let Some(pre_send) = pre_send {
-                Either::A(
-                    pre_send(&mut req)
-                        .map_err(error::wrapped)
-                        .map_err(error::request)
-                        .and_then(move |_| Ok(req).into_future()),
-                )
-            } else {
-                Either::B(Ok(req).into_future())
-            }
-        });
+            match middleware.on_request(&middleware_method, &middleware_url, &mut params) {
+                Ok(()) => {
+                    let reselect = reselect.filter(|_| *params.get_node_address() == selected);
 
-        let pre_send_http = self.http.clone();
-        let pre_send_future = pre_send_future
-            .and_then(move |req| {
-                build_reqwest(&pre_send_http, req)
-                    .build()
-                    .map_err(error::request)
-            })
-            .log_err(move |e| {
-                error!(
-                    "Elasticsearch Request: correlation_id: '{}', error: '{:?}'",
-                    correlation_id, e
-                )
-            });
+                    Ok((params, reselect))
+                }
+                Err(e) => {
+                    let e = error::request(error::wrapped(e));
 
-        let req_http = self.http.clone();
-        let req_future = pre_send_future.and_then(move |req| {
-            req_http
-                .execute(req)
-                .map_err(error::request)
-                .and_then(move |res| {
-                    info!(
-                        "Elasticsearch Response: correlation_id: '{}', status: '{}'",
-                        correlation_id,
-                        res.status()
-                    );
-                    async_response(res, serde_pool).into_future()
-                })
-                .log_err(move |e| {
                     error!(
-                        "Elasticsearch Response: correlation_id: '{}', error: '{:?}'",
+                        "Elasticsearch Request Middleware: correlation_id: '{}', error: '{:?}'",
                         correlation_id, e
-                    )
-                })
+                    );
+                    middleware_observation
+                        .error(Some(params.get_node_address()), RequestErrorKind::Request);
+                    Err(e)
+                }
+            }
+        });
+
+        let sender = self.clone();
+        let req_future = params_future.and_then(move |(mut params, reselect)| {
+            let correlation_id = CorrelationId::resolve(correlation_id, &mut params);
+            let endpoint = Endpoint {
+                url,
+                method,
+                body,
+                name,
+            };
+
+            sender.send_request(
+                correlation_id,
+                params,
+                endpoint,
+                contexts,
+                reselect,
+                observation,
+                started,
+            )
         });
 
-        PendingResponse::new(req_future)
+        PendingResponse::new(req_future.then(move |res| {
+            // The request is no longer in flight once its response has been received
+            drop(in_flight);
+            res
+        }))
+    }
+
+    fn next_params(addresses: &NodeAddresses<Self>) -> Self::Params {
+        addresses.next_async()
     }
 }
 
-impl NextParams for NodeAddresses<AsyncSender> {
-    type Params = PendingParams;
+/**
+# Cluster info
+*/
+impl Client<AsyncSender> {
+    /**
+    Get the version and distribution of the cluster.
+
+    The cluster info is only available when the client was built with [`check_cluster`][check_cluster] and has sent a request.
+    See the [`cluster_info`][cluster-info-mod] module for more details.
+
+    [check_cluster]: struct.AsyncClientBuilder.html#method.check_cluster
+    [cluster-info-mod]: sender/cluster_info/index.html
+    */
+    pub fn cluster_info(&self) -> Option<ClusterInfo> {
+        self.sender.cluster.info()
+    }
+}
+
+/**
+# Closing the client
+*/
+impl Client<AsyncSender> {
+    /**
+    Close the client so it can be shut down cleanly.
+
+    The client stops accepting new requests, and its background tasks, like bulk processors, send any work they've already queued and then stop.
+    The returned future completes once the requests in flight and background tasks have finished, or fails if they take longer than its timeout.
+    Closing a client also closes all of its clones.
+    See the [`shutdown`][shutdown-mod] module for more details.
+
+    # Examples
+
+    Flush a bulk processor before shutting down:
+
+    ```no_run
+    # #[macro_use] extern crate serde_json;
+    # extern crate futures;
+    # extern crate futures_util;
+    # extern crate tokio;
+    # extern crate elastic;
+    # use std::time::Duration;
+    # use futures::{Future, Sink};
+    # use futures_util::compat::Future01CompatExt;
+    # use elastic::prelude::*;
+    # fn main() { run().unwrap() }
+    # fn run() -> Result<(), Box<::std::error::Error>> {
+    let client = AsyncClientBuilder::new().build()?;
+
+    let (processor, task) = client.bulk_stream::<serde_json::Value>()
+        .index("bulk_idx")
+        .ty("bulk_ty")
+        .build_processor()
+        .build();
+
+    let queued = processor.send(bulk_raw().index(json!({ "title": "a document" })));
+
+    let closed = client
+        .close()
+        .timeout(Duration::from_secs(10))
+        .compat()
+        .map_err(|e| println!("failed to close the client: {}", e));
+
+    let shutdown = task.join3(queued.map(|_| ()).map_err(|_| ()), closed);
+    let _ = tokio::runtime::Runtime::new()?.block_on(shutdown.compat());
+    # Ok(())
+    # }
+    ```
+
+    [shutdown-mod]: sender/shutdown/index.html
+    */
+    pub fn close(&self) -> Close {
+        self.sender.shutdown.close()
+    }
+}
 
-    fn next(&self) -> Self::Params {
-        match self.inner {
+impl<TSender> NodeAddresses<TSender>
+where
+    TSender: Sender<Response = PendingResponse>,
+    DefaultBody: Into<TSender::Body>,
+    RequestParams: Into<TSender::Params>,
+{
+    /**
+    Get the parameters for the next asynchronous request.
+
+    If the nodes are sniffed then the sender is used to refresh them when they're stale.
+    */
+    pub fn next_async(&self) -> PendingParams {
+        let params = match self.inner {
             NodeAddressesInner::Static(ref nodes) => PendingParams::new(nodes.next().into_future()),
-            NodeAddressesInner::Sniffed(ref sniffer) => PendingParams::new(sniffer.next()),
+            NodeAddressesInner::Sniffed(ref sniffer) => PendingParams::new(sniffer.next_async()),
+        };
+
+        match self.defaults {
+            Some(ref defaults) => {
+                let defaults = defaults.clone();
+
+                PendingParams::new(params.compat().map(move |params| defaults(params)))
+            }
+            None => params,
+        }
+    }
+}
+
+impl<TEndpoint, TParams, TBody> SendableRequest<TEndpoint, TParams, TBody>
+where
+    TEndpoint: Into<Endpoint<'static, TBody>>,
+    TBody: Into<AsyncBody> + Send + 'static,
+    TParams: Into<PendingParams>,
+{
+    /**
+    Resolve the parameters for a request and build an asynchronous raw http request.
+
+    The address of the node the request should be sent to is returned along with the raw request.
+    This method is used by custom [`Sender`][Sender] implementations.
+
+    [Sender]: trait.Sender.html
+    */
+    pub fn into_http_request_async(
+        self,
+    ) -> Box<Future<Item = (NodeAddress, AsyncHttpRequest), Error = Error> + Send> {
+        let Endpoint {
+            url, method, body, ..
+        } = self.inner.into();
+
+        let params_future = match self.params {
+            SendableRequestParams::Value(params) => Either::A(Ok(params).into_future()),
+            SendableRequestParams::Builder {
+                params, builder, ..
+            } => {
+                let params: PendingParams = params.into();
+
+                Either::B(
+                    params
+                        .compat()
+                        .map(move |params| builder.into_value(move || params)),
+                )
+            }
+        };
+
+        Box::new(params_future.and_then(move |params| {
+            let node = params.get_node_address().clone();
+            let url = Url::parse(&build_url(&url, &params)).map_err(error::request)?;
+
+            let req = AsyncHttpRequest {
+                url,
+                method,
+                headers: params.get_headers(),
+                body: body.map(|body| body.into()),
+            };
+
+            Ok((node, req))
+        }))
+    }
+}
+
+impl NodeAddresses<AsyncSender> {
+    /**
+    Resolve a set of request parameters up-front so they can be shared by multiple requests.
+
+    A node is only selected if the builder doesn't already contain a complete set of parameters.
+    */
+    pub(crate) fn resolve(&self, builder: SharedFluentBuilder<RequestParams>) -> PendingParams {
+        match builder.try_into_value() {
+            TryIntoValue::Value(params) => PendingParams::from(params),
+            TryIntoValue::Builder(builder) => PendingParams::new(
+                self.next()
+                    .compat()
+                    .map(move |params| builder.into_value(move || params)),
+            ),
         }
     }
 }
 
 /** A future returned by calling `next` on an async set of `NodeAddresses`. */
 pub struct PendingParams {
-    inner: Box<Future<Item = RequestParams, Error = Error> + Send>,
+    inner: BoxFuture<RequestParams>,
 }
 
 impl PendingParams {
-    fn new<F>(fut: F) -> Self
+    /** Wrap a future that resolves to a set of request parameters. */
+    pub fn new<F>(fut: F) -> Self
     where
         F: Future<Item = RequestParams, Error = Error> + Send + 'static,
     {
         PendingParams {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl Future for PendingParams {
-    type Item = RequestParams;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(PendingParams => RequestParams);
 
 impl From<RequestParams> for PendingParams {
     fn from(params: RequestParams) -> Self {
@@ -289,9 +1012,12 @@ fn build_reqwest(client: &AsyncHttpClient, req: AsyncHttpRequest) -> AsyncHttpRe
 
     let method = build_reqwest_method(method);
 
-    let mut req = client.request(method, url);
+    let mut req = client.request(method, url.as_str());
     {
-        req = req.headers((&*headers).clone());
+        // `reqwest` uses a newer version of `http`, so headers are converted one at a time
+        for (name, value) in headers.iter() {
+            req = req.header(name.as_str(), value.as_bytes());
+        }
 
         if let Some(body) = body {
             req = req.body(body.into_inner());
@@ -301,37 +1027,57 @@ fn build_reqwest(client: &AsyncHttpClient, req: AsyncHttpRequest) -> AsyncHttpRe
     req
 }
 
+/**
+Send a raw request using `reqwest`.
+
+The connection is driven by the current `tokio` runtime.
+*/
+fn execute_reqwest(
+    client: &AsyncHttpClient,
+    req: reqwest::Request,
+) -> impl Future<Item = AsyncHttpResponse, Error = AsyncHttpError> {
+    Compat::new(Box::pin(client.execute(req))).map(AsyncHttpResponse::from_raw)
+}
+
 /** A future returned by calling `send` on an `AsyncSender`. */
 pub struct PendingResponse {
-    inner: Box<Future<Item = AsyncResponseBuilder, Error = Error> + Send>,
+    inner: BoxFuture<AsyncResponseBuilder>,
 }
 
 impl PendingResponse {
-    fn new<F>(fut: F) -> Self
+    /**
+    Wrap a future that resolves to a response.
+
+    This method is used by custom [`Sender`][Sender] implementations.
+
+    [Sender]: trait.Sender.html
+    */
+    pub fn new<F>(fut: F) -> Self
     where
         F: Future<Item = AsyncResponseBuilder, Error = Error> + Send + 'static,
     {
         PendingResponse {
-            inner: Box::new(fut),
+            inner: box_future(fut),
         }
     }
 }
 
-impl Future for PendingResponse {
-    type Item = AsyncResponseBuilder;
-    type Error = Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.inner.poll()
-    }
-}
+pending_future!(PendingResponse => AsyncResponseBuilder);
 
 /** A builder for an asynchronous client. */
 pub struct AsyncClientBuilder {
     http: Option<AsyncHttpClient>,
-    serde_pool: Option<Arc<ThreadPool>>,
+    tls: Option<Tls>,
+    proxy: Option<Proxy>,
+    pool: Option<ConnectionPool>,
+    decompress_responses: Option<bool>,
+    check_cluster: bool,
+    node_affinity: bool,
+    serde_pool: Option<Handle>,
     nodes: NodeAddressesBuilder,
+    error: Option<Error>,
     params: SharedFluentBuilder<PreRequestParams>,
+    headers: Vec<(HeaderName, HeaderValue)>,
     pre_send: Option<
         Arc<
             Fn(
@@ -342,6 +1088,20 @@ pub struct AsyncClientBuilder {
                 + Sync,
         >,
     >,
+    auth: Option<Credentials>,
+    auth_refresh: Option<RefreshCredentials>,
+    middleware: Vec<SharedMiddleware>,
+    observer: Option<SharedObserver>,
+    strategy: Option<SharedStrategy>,
+    dead_nodes: Option<DeadNodes>,
+    retry: Option<RetryPolicy>,
+    compression: Option<RequestCompression>,
+    slow_requests: Option<Duration>,
+    rate_limit: Option<RateLimit>,
+    #[cfg(feature = "aws-sigv4")]
+    aws_sigv4: Option<AwsSigV4>,
+    #[cfg(feature = "testing")]
+    mock: Option<MockSender>,
 }
 
 impl Default for AsyncClientBuilder {
@@ -364,10 +1124,32 @@ impl AsyncClientBuilder {
     pub fn new() -> Self {
         AsyncClientBuilder {
             http: None,
+            tls: None,
+            proxy: None,
+            pool: None,
+            decompress_responses: None,
+            check_cluster: false,
+            node_affinity: false,
             serde_pool: None,
             params: SharedFluentBuilder::new(),
             nodes: NodeAddressesBuilder::default(),
+            error: None,
+            headers: Vec::new(),
             pre_send: None,
+            auth: None,
+            auth_refresh: None,
+            middleware: Vec::new(),
+            observer: None,
+            strategy: None,
+            dead_nodes: None,
+            retry: None,
+            compression: None,
+            slow_requests: None,
+            rate_limit: None,
+            #[cfg(feature = "aws-sigv4")]
+            aws_sigv4: None,
+            #[cfg(feature = "testing")]
+            mock: None,
         }
     }
 
@@ -377,10 +1159,32 @@ impl AsyncClientBuilder {
     pub fn from_params(params: PreRequestParams) -> Self {
         AsyncClientBuilder {
             http: None,
+            tls: None,
+            proxy: None,
+            pool: None,
+            decompress_responses: None,
+            check_cluster: false,
+            node_affinity: false,
             serde_pool: None,
             params: SharedFluentBuilder::new().value(params),
             nodes: NodeAddressesBuilder::default(),
+            error: None,
+            headers: Vec::new(),
             pre_send: None,
+            auth: None,
+            auth_refresh: None,
+            middleware: Vec::new(),
+            observer: None,
+            strategy: None,
+            dead_nodes: None,
+            retry: None,
+            compression: None,
+            slow_requests: None,
+            rate_limit: None,
+            #[cfg(feature = "aws-sigv4")]
+            aws_sigv4: None,
+            #[cfg(feature = "testing")]
+            mock: None,
         }
     }
 
@@ -405,6 +1209,37 @@ impl AsyncClientBuilder {
         self
     }
 
+    /**
+    Specify an Elastic Cloud id to send requests to.
+
+    The cloud id is decoded into the address of the deployment's Elasticsearch instance.
+    If the cloud id is invalid then building the client will fail.
+    See the [`cloud_id`][cloud-id-mod] module for more details.
+
+    # Examples
+
+    Connect to an Elastic Cloud deployment using a username and password:
+
+    ```
+    # use elastic::prelude::*;
+    let builder = AsyncClientBuilder::new()
+        .cloud_id("my-deployment:dXMtZWFzdC0xLmF3cy5mb3VuZC5pbyRjZWM2ZjI2MWE3NGJmMjRjZTMzYmI4ODExYjg0Mjk0ZiRjNmMyY2E2ZDA0MjI0OWFmMGNjN2Q3YTllOTYyNTc0Mw==")
+        .auth(Credentials::basic("elastic", "changeme"));
+    ```
+
+    [cloud-id-mod]: cloud_id/index.html
+    */
+    pub fn cloud_id(mut self, cloud_id: &str) -> Self {
+        match CloudId::parse(cloud_id) {
+            Ok(cloud_id) => {
+                self.nodes = NodeAddressesBuilder::Static(vec![cloud_id.elasticsearch().clone()]);
+            }
+            Err(e) => self.error = Some(e),
+        }
+
+        self
+    }
+
     /**
     Specify a node address to sniff other nodes in the cluster from.
 
@@ -450,22 +1285,49 @@ impl AsyncClientBuilder {
     }
 
     /**
-    Specify default request parameters.
+    Specify a strategy for load balancing requests between nodes.
+
+    The strategy is used for both static and sniffed nodes.
+    By default, requests are load balanced using a round-robin strategy.
+    See the [`static_nodes`][static-nodes-mod] module for the strategies that are available.
 
     # Examples
 
-    Require all responses use pretty-printing:
+    Send requests to the node with the fewest in-flight requests:
 
     ```
     # use elastic::prelude::*;
+    # use elastic::client::sender::static_nodes::LeastInFlight;
     let builder = AsyncClientBuilder::new()
-        .params_fluent(|p| p
-            .url_param("pretty", true));
+        .static_nodes(vec!["http://a:9200", "http://b:9200"])
+        .load_balancing(LeastInFlight::default());
     ```
+
+    [static-nodes-mod]: static_nodes/index.html
     */
-    pub fn params_fluent(
-        mut self,
-        builder: impl Fn(PreRequestParams) -> PreRequestParams + Send + 'static,
+    pub fn load_balancing(mut self, strategy: impl Strategy + 'static) -> Self {
+        self.strategy = Some(Arc::new(strategy));
+
+        self
+    }
+
+    /**
+    Specify default request parameters.
+
+    # Examples
+
+    Require all responses use pretty-printing:
+
+    ```
+    # use elastic::prelude::*;
+    let builder = AsyncClientBuilder::new()
+        .params_fluent(|p| p
+            .url_param("pretty", true));
+    ```
+    */
+    pub fn params_fluent(
+        mut self,
+        builder: impl Fn(PreRequestParams) -> PreRequestParams + Send + 'static,
     ) -> Self {
         self.params = self.params.fluent(builder).shared();
 
@@ -493,30 +1355,80 @@ impl AsyncClientBuilder {
     }
 
     /**
-    Use the given `ThreadPool` for serialising and deserialising responses.
+    Specify a header to send with every request.
+
+    Default headers are applied over any headers in the default request parameters, and headers set on a request are applied over them.
+
+    # Examples
+
+    Send a custom header with every request:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::http::header::{HeaderName, HeaderValue};
+    let builder = AsyncClientBuilder::new()
+        .default_header(
+            HeaderName::from_static("x-tenant"),
+            HeaderValue::from_static("tenant123"));
+    ```
+    */
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.push((name, value));
+
+        self
+    }
+
+    /**
+    Specify an `X-Opaque-Id` to send with every request.
+
+    Elasticsearch includes the id in slow log and tasks API entries, so they can be attributed to the service that sent the request.
+    The id is also used to correlate log records for requests.
+    Control characters aren't valid in a header, so they're removed from the id.
+    See the [`logging`][logging-mod] module for more details.
+
+    # Examples
+
+    ```
+    # use elastic::prelude::*;
+    let builder = AsyncClientBuilder::new()
+        .opaque_id("my-service");
+    ```
+
+    [logging-mod]: logging/index.html
+    */
+    pub fn opaque_id(mut self, id: impl AsRef<str>) -> Self {
+        self.headers.push((
+            HeaderName::from_static(X_OPAQUE_ID),
+            opaque_id_header(id.as_ref()),
+        ));
+
+        self
+    }
+
+    /**
+    Serialise and deserialise requests and responses on the blocking thread pool of the given `tokio` runtime.
 
-    If the pool is `None` then responses will be serialised and deserialised on the same thread as the io `Core`.
+    If the runtime is `None` then requests and responses will be serialised and deserialised on the same thread that polls the request.
 
     # Examples
 
-    Use a cpu pool to serialise and deserialise responses:
+    Use a runtime's blocking pool to serialise and deserialise responses:
 
     ```
-    # extern crate tokio_threadpool;
+    # extern crate tokio;
     # extern crate elastic;
-    # use std::sync::Arc;
     # use elastic::prelude::*;
-    # use tokio_threadpool::ThreadPool;
+    # use tokio::runtime::Runtime;
     # fn main() { run().unwrap() }
     # fn run() -> Result<(), Box<::std::error::Error>> {
-    let pool = ThreadPool::new();
+    let runtime = Runtime::new()?;
 
-    let builder = AsyncClientBuilder::new().serde_pool(Arc::new(pool));
+    let builder = AsyncClientBuilder::new().serde_pool(runtime.handle().clone());
     # Ok(())
     # }
     ```
     */
-    pub fn serde_pool(mut self, serde_pool: impl Into<Option<Arc<ThreadPool>>>) -> Self {
+    pub fn serde_pool(mut self, serde_pool: impl Into<Option<Handle>>) -> Self {
         self.serde_pool = serde_pool.into();
 
         self
@@ -544,6 +1456,488 @@ impl AsyncClientBuilder {
         self
     }
 
+    /**
+    Specify credentials to authenticate every request sent by the client.
+
+    The client sets the `Authorization` header on requests, unless they already have one.
+    See the [`auth`][auth-mod] module for more details.
+
+    # Examples
+
+    Authenticate using a username and password:
+
+    ```
+    # use elastic::prelude::*;
+    let builder = AsyncClientBuilder::new()
+        .auth(Credentials::basic("elastic", "changeme"));
+    ```
+
+    [auth-mod]: auth/index.html
+    */
+    pub fn auth(mut self, credentials: Credentials) -> Self {
+        self.auth = Some(credentials);
+
+        self
+    }
+
+    /**
+    Specify a callback that gets new credentials when a request receives a `401 Unauthorized` response.
+
+    The new credentials are used for requests sent after the callback's future completes.
+    The callback is only used if credentials have been specified with [`auth`][auth].
+    See the [`auth`][auth-mod] module for more details.
+
+    # Examples
+
+    Fetch a new bearer token when the current one is rejected:
+
+    ```
+    # extern crate futures;
+    # extern crate elastic;
+    # use futures::Future;
+    # use elastic::prelude::*;
+    # fn main() {
+    # fn fetch_token() -> Result<String, Box<::std::error::Error + Send + Sync>> { Ok("token".to_owned()) }
+    let builder = AsyncClientBuilder::new()
+        .auth(Credentials::bearer("initial-token"))
+        .auth_refresh(|| {
+            Box::new(futures::future::result(fetch_token()).map(Credentials::bearer))
+        });
+    # }
+    ```
+
+    [auth]: #method.auth
+    [auth-mod]: auth/index.html
+    */
+    pub fn auth_refresh(
+        mut self,
+        refresh: impl Fn() -> Box<Future<Item = Credentials, Error = Box<StdError + Send + Sync>> + Send>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.auth_refresh = Some(Arc::new(refresh));
+
+        self
+    }
+
+    /**
+    Add a middleware that can inspect and modify every request sent by the client.
+
+    Middleware is called in the order it's added.
+    See the [`middleware`][middleware-mod] module for more details.
+
+    # Examples
+
+    Add a header to every request:
+
+    ```
+    # use std::error::Error;
+    # use elastic::prelude::*;
+    # use elastic::http::header::{HeaderName, HeaderValue};
+    # use elastic::client::sender::middleware::{Middleware, MiddlewareRequest};
+    struct Tenant;
+
+    impl Middleware for Tenant {
+        fn on_request(&self, req: &mut MiddlewareRequest) -> Result<(), Box<Error + Send + Sync>> {
+            req.header(HeaderName::from_static("x-tenant"), HeaderValue::from_static("a"));
+
+            Ok(())
+        }
+    }
+
+    let builder = AsyncClientBuilder::new()
+        .middleware(Tenant);
+    ```
+
+    [middleware-mod]: middleware/index.html
+    */
+    pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+
+        self
+    }
+
+    /**
+    Specify an observer that's notified about every request sent by the client.
+
+    The observer is called once a response has been received or the request has failed.
+    See the [`observer`][observer-mod] module for more details.
+
+    # Examples
+
+    Collect metrics in the Prometheus text format:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::observer::PrometheusObserver;
+    let metrics = PrometheusObserver::new();
+
+    let builder = AsyncClientBuilder::new()
+        .observer(metrics.clone());
+    ```
+
+    [observer-mod]: observer/index.html
+    */
+    pub fn observer(mut self, observer: impl Observer + 'static) -> Self {
+        self.observer = Some(Arc::new(observer));
+
+        self
+    }
+
+    /**
+    Specify a policy for temporarily removing failing nodes from rotation.
+
+    Dead node detection is disabled by default, so requests are load balanced over every node even if they're failing.
+    See the [`dead_nodes`][dead-nodes-mod] module for more details.
+
+    # Examples
+
+    Remove a node from rotation after 3 consecutive failures:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::dead_nodes::DeadNodes;
+    let builder = AsyncClientBuilder::new()
+        .static_nodes(vec!["http://a:9200", "http://b:9200"])
+        .dead_nodes(DeadNodes::new().failure_threshold(3));
+    ```
+
+    [dead-nodes-mod]: dead_nodes/index.html
+    */
+    pub fn dead_nodes(mut self, dead_nodes: DeadNodes) -> Self {
+        self.dead_nodes = Some(dead_nodes);
+
+        self
+    }
+
+    /**
+    Don't remove failing nodes from rotation.
+
+    This removes any policy set by [`dead_nodes`](#method.dead_nodes).
+    Requests are load balanced over every node, even if they're failing.
+    */
+    pub fn disable_dead_nodes(mut self) -> Self {
+        self.dead_nodes = None;
+
+        self
+    }
+
+    /**
+    Specify a policy for retrying requests that fail because a node is temporarily unavailable.
+
+    By default, requests aren't retried.
+    Retries are delayed using a timer, so requests must be sent on a `tokio` 1 runtime.
+    See the [`retry`][retry-mod] module for more details.
+
+    # Examples
+
+    Retry idempotent requests using the default policy:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::retry::RetryPolicy;
+    let builder = AsyncClientBuilder::new()
+        .retry(RetryPolicy::default());
+    ```
+
+    [retry-mod]: retry/index.html
+    */
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+
+        self
+    }
+
+    /**
+    Log requests that take longer than the given threshold to complete.
+
+    Slow requests are logged at the `warn` level with their correlation id, endpoint, url and latency.
+    See the [`logging`][logging-mod] module for more details.
+
+    # Examples
+
+    Log requests that take longer than a second:
+
+    ```
+    # use std::time::Duration;
+    # use elastic::prelude::*;
+    let builder = AsyncClientBuilder::new()
+        .log_slow_requests(Duration::from_secs(1));
+    ```
+
+    [logging-mod]: logging/index.html
+    */
+    pub fn log_slow_requests(mut self, threshold: Duration) -> Self {
+        self.slow_requests = Some(threshold);
+
+        self
+    }
+
+    /**
+    Specify a limit on the number of requests sent per second or in flight at once.
+
+    By default, requests aren't limited.
+    See the [`rate_limit`][rate-limit-mod] module for more details.
+
+    # Examples
+
+    Send at most 100 requests per second:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::rate_limit::RateLimit;
+    let builder = AsyncClientBuilder::new()
+        .rate_limit(RateLimit::new().requests_per_second(100));
+    ```
+
+    [rate-limit-mod]: rate_limit/index.html
+    */
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+
+        self
+    }
+
+    /**
+    Specify TLS options for connections to nodes.
+
+    TLS options can't be combined with a custom http client set using [`http_client`][http_client].
+    See the [`tls`][tls-mod] module for more details.
+
+    # Examples
+
+    Connect to a development cluster with a self-signed certificate:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::tls::Tls;
+    let builder = AsyncClientBuilder::new()
+        .static_node("https://localhost:9200")
+        .tls(Tls::new().danger_accept_invalid_certs(true));
+    ```
+
+    [http_client]: #method.http_client
+    [tls-mod]: tls/index.html
+    */
+    pub fn tls(mut self, tls: Tls) -> Self {
+        self.tls = Some(tls);
+
+        self
+    }
+
+    /**
+    Send requests through a HTTP proxy.
+
+    Proxy options can't be combined with a custom http client set using [`http_client`][http_client].
+    See the [`proxy`][proxy-mod] module for more details.
+
+    # Examples
+
+    Send requests through the proxy in the `HTTPS_PROXY` environment variable:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::proxy::Proxy;
+    let builder = AsyncClientBuilder::new()
+        .static_node("https://elasticsearch:9200")
+        .proxy(Proxy::from_env());
+    ```
+
+    [http_client]: #method.http_client
+    [proxy-mod]: proxy/index.html
+    */
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+
+        self
+    }
+
+    /**
+    Tune the pool of connections to nodes.
+
+    Connection pool options can't be combined with a custom http client set using [`http_client`][http_client].
+    See the [`pool`][pool-mod] module for more details.
+
+    # Examples
+
+    Keep at most 32 idle connections for each node, and wait at most 2 seconds to connect:
+
+    ```
+    # use std::time::Duration;
+    # use elastic::prelude::*;
+    # use elastic::client::sender::pool::ConnectionPool;
+    let builder = AsyncClientBuilder::new()
+        .connection_pool(
+            ConnectionPool::new()
+                .max_idle_per_host(32)
+                .connect_timeout(Duration::from_secs(2)),
+        );
+    ```
+
+    [http_client]: #method.http_client
+    [pool-mod]: pool/index.html
+    */
+    pub fn connection_pool(mut self, pool: ConnectionPool) -> Self {
+        self.pool = Some(pool);
+
+        self
+    }
+
+    /**
+    Gzip the bodies of requests that are larger than a minimum size.
+
+    Bodies are compressed on the serde pool if one is set using [`serde_pool`][serde_pool].
+    See the [`compression`][compression-mod] module for more details.
+
+    # Examples
+
+    Compress request bodies that are at least 1KiB:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::compression::RequestCompression;
+    let builder = AsyncClientBuilder::new()
+        .compress_requests(RequestCompression::new());
+    ```
+
+    [serde_pool]: #method.serde_pool
+    [compression-mod]: compression/index.html
+    */
+    pub fn compress_requests(mut self, compression: RequestCompression) -> Self {
+        self.compression = Some(compression);
+
+        self
+    }
+
+    /**
+    Specify whether to ask for gzipped responses and decompress them.
+
+    Responses are decompressed by default.
+    This option can't be combined with a custom http client set using [`http_client`][http_client].
+    See the [`compression`][compression-mod] module for more details.
+
+    # Examples
+
+    Ask for uncompressed responses:
+
+    ```
+    # use elastic::prelude::*;
+    let builder = AsyncClientBuilder::new()
+        .decompress_responses(false);
+    ```
+
+    [http_client]: #method.http_client
+    [compression-mod]: compression/index.html
+    */
+    pub fn decompress_responses(mut self, decompress: bool) -> Self {
+        self.decompress_responses = Some(decompress);
+
+        self
+    }
+
+    /**
+    Ping the cluster before sending the first request to check its version and distribution.
+
+    The cluster info can be read using the client's [`cluster_info`][cluster_info] method once the ping has completed.
+    See the [`cluster_info`][cluster-info-mod] module for more details.
+
+    # Examples
+
+    ```
+    # use elastic::prelude::*;
+    let builder = AsyncClientBuilder::new()
+        .check_cluster(true);
+    ```
+
+    [cluster_info]: ../struct.Client.html#method.cluster_info
+    [cluster-info-mod]: cluster_info/index.html
+    */
+    pub fn check_cluster(mut self, check: bool) -> Self {
+        self.check_cluster = check;
+
+        self
+    }
+
+    /**
+    Send follow-up requests for a scroll or point-in-time search to the node that served the search.
+
+    By default, follow-up requests are sent to whichever node the client selects.
+    See the [`affinity`][affinity-mod] module for more details.
+
+    # Examples
+
+    ```
+    # use elastic::prelude::*;
+    let builder = AsyncClientBuilder::new()
+        .node_affinity(true);
+    ```
+
+    [affinity-mod]: affinity/index.html
+    */
+    pub fn node_affinity(mut self, affinity: bool) -> Self {
+        self.node_affinity = affinity;
+
+        self
+    }
+
+    /**
+    Sign requests using AWS Signature Version 4.
+
+    This method is only available when the `aws-sigv4` feature is enabled.
+    See the [`aws_sigv4`][aws-sigv4-mod] module for more details.
+
+    # Examples
+
+    Sign requests to a domain in `us-east-1`:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::aws_sigv4::AwsSigV4;
+    let builder = AsyncClientBuilder::new()
+        .static_node("https://search-my-domain.us-east-1.es.amazonaws.com")
+        .aws_sigv4(AwsSigV4::new("us-east-1"));
+    ```
+
+    [aws-sigv4-mod]: aws_sigv4/index.html
+    */
+    #[cfg(feature = "aws-sigv4")]
+    pub fn aws_sigv4(mut self, signer: AwsSigV4) -> Self {
+        self.aws_sigv4 = Some(signer);
+
+        self
+    }
+
+    /**
+    Answer requests using canned responses from a mock instead of sending them to nodes.
+
+    This method is only available when the `testing` feature is enabled.
+    See the [`mock`][mock-mod] module for more details.
+
+    # Examples
+
+    Respond to ping requests:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::mock::{MockSender, MockResponse};
+    # use elastic::http::{Method, StatusCode};
+    let mock = MockSender::new();
+    mock.respond(Method::GET, "/", MockResponse::new(StatusCode::OK));
+
+    let builder = AsyncClientBuilder::new()
+        .mock(mock.clone());
+    ```
+
+    [mock-mod]: mock/index.html
+    */
+    #[cfg(feature = "testing")]
+    pub fn mock(mut self, mock: MockSender) -> Self {
+        self.mock = Some(mock);
+
+        self
+    }
+
     /** Use the given `reqwest::Client` for sending requests. */
     pub fn http_client(mut self, client: AsyncHttpClient) -> Self {
         self.http = Some(client);
@@ -557,18 +1951,125 @@ impl AsyncClientBuilder {
     [AsyncClient]: type.AsyncClient.html
     */
     pub fn build(self) -> Result<AsyncClient, Error> {
-        let http = self.http.unwrap_or_else(|| AsyncHttpClient::new());
-        let params = self.params.into_value(|| PreRequestParams::default());
+        self.build_with(|sender| sender)
+    }
+
+    /**
+    Construct a client from this builder with a sender that wraps the asynchronous sender.
+
+    The wrapping sender is also used to sniff node addresses.
+    Sniffing requests are still sent after the client is closed, so requests that are draining can find a node to send to.
+    */
+    pub(super) fn build_with<TSender>(
+        self,
+        wrap: impl Fn(AsyncSender) -> TSender,
+    ) -> Result<Client<TSender>, Error>
+    where
+        TSender: Clone,
+    {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+
+        let http = match self.http {
+            Some(_)
+                if self.tls.is_some()
+                    || self.proxy.is_some()
+                    || self.pool.is_some()
+                    || self.decompress_responses.is_some() =>
+            {
+                Err(error::build(error::message(
+                    "TLS, proxy, connection pool and decompression options can't be used with a custom http client",
+                )))?
+            }
+            Some(http) => http,
+            None => {
+                let mut http = AsyncHttpClientBuilder::new();
+
+                if let Some(tls) = self.tls {
+                    let tls = tls.build()?;
+
+                    for certificate in tls.root_certificates {
+                        http = http.add_root_certificate(certificate);
+                    }
+
+                    if let Some(identity) = tls.identity {
+                        http = http.identity(identity);
+                    }
+
+                    http = http.danger_accept_invalid_certs(tls.accept_invalid_certs);
+                }
+
+                if let Some(proxy) = self.proxy {
+                    for proxy in proxy.build()? {
+                        http = http.proxy(proxy);
+                    }
+                }
+
+                if let Some(pool) = self.pool {
+                    http = pool.build(http);
+                }
+
+                http = http.gzip(self.decompress_responses.unwrap_or(true));
+
+                http.build().map_err(error::build)?
+            }
+        };
+        let params = self.headers.into_iter().fold(
+            self.params.into_value(|| PreRequestParams::default()),
+            |params, (name, value)| params.header(name, value),
+        );
+        let strategy = self
+            .strategy
+            .unwrap_or_else(|| Arc::new(RoundRobin::default()));
+        let strategy = match self.dead_nodes {
+            Some(dead_nodes) => dead_nodes.wrap(strategy),
+            None => strategy,
+        };
+        let auth = match self.auth {
+            Some(credentials) => Some(Auth::new(&credentials, self.auth_refresh)?),
+            None => None,
+        };
+        let middleware = {
+            let auth = auth.clone().map(|auth| Arc::new(auth) as SharedMiddleware);
+
+            Middlewares::new(auth.into_iter().chain(self.middleware).collect())
+        };
 
         let sender = AsyncSender {
             http,
             serde_pool: self.serde_pool,
             pre_send: self.pre_send,
+            auth,
+            middleware: middleware.clone(),
+            observer: middleware.observer(self.observer),
+            strategy: strategy.clone(),
+            retry: self.retry,
+            compression: self.compression,
+            slow_requests: self.slow_requests,
+            rate_limit: self.rate_limit.map(RateLimit::build),
+            affinity: if self.node_affinity {
+                Some(NodeAffinity::default())
+            } else {
+                None
+            },
+            shutdown: Shutdown::default(),
+            cluster: ClusterCheck::new(self.check_cluster),
+            #[cfg(feature = "aws-sigv4")]
+            aws_sigv4: self.aws_sigv4,
+            #[cfg(feature = "testing")]
+            mock: self.mock,
         };
 
-        let addresses = self.nodes.build(params, sender.clone());
+        let sniffer = AsyncSender {
+            shutdown: sender.shutdown.allow_after_close(),
+            cluster: sender.cluster.unchecked(),
+            ..sender.clone()
+        };
+        let addresses = self.nodes.build(params, strategy, wrap(sniffer));
+        let sender = wrap(sender);
 
-        Ok(AsyncClient {
+        Ok(Client {
             sender: sender,
             addresses: addresses,
         })
@@ -633,4 +2134,309 @@ mod tests {
         assert_send::<super::PendingParams>();
         assert_send::<super::PendingResponse>();
     }
+
+    #[test]
+    fn dead_nodes_are_disabled_by_default() {
+        use super::AsyncClientBuilder;
+
+        assert!(AsyncClientBuilder::new().dead_nodes.is_none());
+        assert!(AsyncClientBuilder::new()
+            .disable_dead_nodes()
+            .dead_nodes
+            .is_none());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_answers_requests() {
+        use futures::Future;
+        use serde_json::Value;
+
+        use super::*;
+        use client::{
+            requests::PingRequest,
+            sender::mock::{
+                MockResponse,
+                MockSender,
+            },
+        };
+        use http::Method;
+
+        let mock = MockSender::new();
+        mock.respond(
+            Method::GET,
+            "/",
+            MockResponse::new(StatusCode::OK).json(&json!({ "ok": true })),
+        );
+
+        let client = AsyncClientBuilder::new()
+            .mock(mock.clone())
+            .build()
+            .unwrap();
+
+        let res = client
+            .request(PingRequest::new())
+            .send()
+            .compat()
+            .and_then(|res| res.into_response::<Value>().compat())
+            .wait()
+            .unwrap();
+
+        assert_eq!(json!({ "ok": true }), res);
+        assert_eq!("/", mock.requests()[0].url().path());
+        assert!(mock.requests()[0]
+            .headers()
+            .contains_key(::client::sender::logging::X_OPAQUE_ID));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_closed_client_rejects_requests() {
+        use futures::Future;
+
+        use super::*;
+        use client::{
+            requests::PingRequest,
+            sender::mock::{
+                MockResponse,
+                MockSender,
+            },
+        };
+        use http::Method;
+
+        let mock = MockSender::new();
+        mock.respond(Method::GET, "/", MockResponse::new(StatusCode::OK));
+
+        let client = AsyncClientBuilder::new()
+            .mock(mock.clone())
+            .build()
+            .unwrap();
+
+        client.clone().close().compat().wait().unwrap();
+
+        assert!(client
+            .request(PingRequest::new())
+            .send()
+            .compat()
+            .wait()
+            .is_err());
+        assert_eq!(0, mock.requests().len());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_requests_can_be_awaited() {
+        use std::{
+            future::Future as StdFuture,
+            pin::Pin,
+            sync::Arc,
+            task::{
+                Context,
+                Poll,
+                Wake,
+                Waker,
+            },
+            thread::{
+                self,
+                Thread,
+            },
+        };
+
+        use serde_json::Value;
+
+        use super::*;
+        use client::{
+            requests::PingRequest,
+            sender::mock::{
+                MockResponse,
+                MockSender,
+            },
+        };
+        use http::Method;
+
+        struct Unpark(Thread);
+
+        impl Wake for Unpark {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        fn block_on<F>(mut fut: F) -> F::Output
+        where
+            F: StdFuture + Unpin,
+        {
+            let waker = Waker::from(Arc::new(Unpark(thread::current())));
+            let mut cx = Context::from_waker(&waker);
+
+            loop {
+                match Pin::new(&mut fut).poll(&mut cx) {
+                    Poll::Ready(output) => return output,
+                    Poll::Pending => thread::park(),
+                }
+            }
+        }
+
+        let mock = MockSender::new();
+        mock.respond(
+            Method::GET,
+            "/",
+            MockResponse::new(StatusCode::OK).json(&json!({ "ok": true })),
+        );
+
+        let client = AsyncClientBuilder::new()
+            .mock(mock.clone())
+            .build()
+            .unwrap();
+
+        let res = block_on(client.request(PingRequest::new()).send()).unwrap();
+        let res = block_on(res.into_response::<Value>()).unwrap();
+
+        assert_eq!(json!({ "ok": true }), res);
+    }
+
+    /** Serve a single JSON response to the first connection on a local port. */
+    fn serve_once(body: &'static str) -> String {
+        use std::{
+            io::{
+                Read,
+                Write,
+            },
+            net::TcpListener,
+            thread,
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            // Read the request head; ping requests don't have a body
+            let mut req = Vec::new();
+            let mut buf = [0; 1024];
+            while !req.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buf).unwrap();
+                if read == 0 {
+                    break;
+                }
+                req.extend_from_slice(&buf[..read]);
+            }
+
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn requests_can_be_awaited_on_tokio() {
+        use serde_json::Value;
+
+        use super::*;
+        use client::requests::PingRequest;
+        use runtime::tests::current_thread;
+
+        let node = serve_once(r#"{"ok":true}"#);
+
+        let client = AsyncClientBuilder::new().static_node(node).build().unwrap();
+
+        // Await the standard futures directly on a `tokio` 1 runtime, with no `tokio` 0.1 reactor
+        let runtime = current_thread();
+        let res = runtime
+            .block_on(client.request(PingRequest::new()).send())
+            .unwrap();
+        let res = runtime.block_on(res.into_response::<Value>()).unwrap();
+
+        assert_eq!(json!({ "ok": true }), res);
+    }
+
+    #[test]
+    fn requests_awaited_outside_tokio_are_err() {
+        use futures::Future;
+
+        use super::*;
+        use client::requests::PingRequest;
+
+        let client = AsyncClientBuilder::new()
+            .static_node("http://127.0.0.1:1")
+            .build()
+            .unwrap();
+
+        assert!(client
+            .request(PingRequest::new())
+            .send()
+            .compat()
+            .wait()
+            .is_err());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_streamed_bodies_are_not_retried() {
+        use bytes::Bytes;
+        use futures::{
+            stream,
+            Future,
+        };
+        use std::{
+            io,
+            time::Duration,
+        };
+
+        use super::*;
+        use client::{
+            requests::BulkRequest,
+            sender::{
+                mock::{
+                    MockResponse,
+                    MockSender,
+                },
+                retry::RetryPolicy,
+            },
+        };
+        use http::Method;
+
+        let mock = MockSender::new();
+        mock.respond(
+            Method::POST,
+            "/myindex/_bulk",
+            MockResponse::new(StatusCode::SERVICE_UNAVAILABLE),
+        );
+        mock.respond(
+            Method::POST,
+            "/myindex/_bulk",
+            MockResponse::new(StatusCode::OK),
+        );
+
+        let client = AsyncClientBuilder::new()
+            .mock(mock.clone())
+            .retry(
+                RetryPolicy::new()
+                    .initial_backoff(Duration::from_millis(1))
+                    .retry_non_idempotent(true),
+            )
+            .build()
+            .unwrap();
+
+        let chunks: Vec<Result<Bytes, io::Error>> = vec![Ok(Bytes::from(&b"{}\n"[..]))];
+        let body = AsyncBody::from_stream(stream::iter_result(chunks));
+
+        let res = client
+            .request(BulkRequest::for_index("myindex", body))
+            .send()
+            .compat()
+            .wait()
+            .unwrap();
+
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, res.status());
+        assert_eq!(1, mock.requests().len());
+        assert_eq!(None, mock.requests()[0].body());
+    }
 }
@@ -0,0 +1,401 @@
+/*!
+Authenticate the requests sent by a client.
+
+[`Credentials`][Credentials] are attached to a client using the `auth` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+The client then sets the `Authorization` header on every request it sends, including requests used to sniff nodes.
+An `Authorization` header set on an individual request using its `params` takes precedence over the client's credentials.
+
+# Examples
+
+Authenticate using a username and password:
+
+```
+# use elastic::prelude::*;
+let builder = SyncClientBuilder::new()
+    .auth(Credentials::basic("elastic", "changeme"));
+```
+
+Authenticate using an API key:
+
+```
+# use elastic::prelude::*;
+let builder = SyncClientBuilder::new()
+    .auth(Credentials::api_key("VuaCfGcBCdbkQm-e5aOx", "ui2lp2axTNmsyakw9tvNnw"));
+```
+
+# Refreshing credentials
+
+Tokens, like service account tokens, may expire or be rotated while a client is running.
+A refresh callback can be attached to a client using the `auth_refresh` method on the builders.
+When a request receives a `401 Unauthorized` response the callback is invoked to get new credentials, which are used for all requests sent after the refresh completes.
+The request that received the `401` response isn't retried, so its error is still returned to the caller.
+
+Only one refresh is run at a time.
+Requests that receive a `401` response while a refresh is already in progress don't start another one.
+If the callback fails then the error is logged and the current credentials are kept.
+
+Refreshing credentials for an async client runs the callback's future before the `401` response is returned.
+A sync client waits for the callback's future on the thread that sent the request.
+
+```
+# extern crate futures;
+# extern crate elastic;
+# use futures::Future;
+# use elastic::prelude::*;
+# fn main() {
+# fn fetch_token() -> Result<String, Box<::std::error::Error + Send + Sync>> { Ok("token".to_owned()) }
+let builder = AsyncClientBuilder::new()
+    .auth(Credentials::bearer("initial-token"))
+    .auth_refresh(|| {
+        Box::new(futures::future::result(fetch_token()).map(Credentials::bearer))
+    });
+# }
+```
+
+[Credentials]: enum.Credentials.html
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+use base64;
+use futures::Future;
+use std::{
+    error::Error as StdError,
+    fmt,
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+        Arc,
+        RwLock,
+    },
+};
+
+use client::sender::middleware::{
+    Middleware,
+    MiddlewareRequest,
+};
+use error::{
+    self,
+    Error,
+};
+use http::header::{
+    HeaderValue,
+    AUTHORIZATION,
+};
+
+/**
+Credentials used to authenticate requests.
+
+Secrets aren't included in the `Debug` output for credentials.
+*/
+#[derive(Clone, PartialEq)]
+pub enum Credentials {
+    /** Authenticate using a username and password with basic authentication. */
+    Basic {
+        /** The name of the user. */
+        username: String,
+        /** The password of the user. */
+        password: String,
+    },
+    /**
+    Authenticate using an [API key][docs-api-key].
+
+    The `id` and `key` are returned by the Create API Key API.
+
+    [docs-api-key]: https://www.elastic.co/guide/en/elasticsearch/reference/current/security-api-create-api-key.html
+    */
+    ApiKey {
+        /** The id of the API key. */
+        id: String,
+        /** The secret value of the API key. */
+        key: String,
+    },
+    /**
+    Authenticate using a bearer token, like an OAuth2 access token or a [service account token][docs-service-token].
+
+    [docs-service-token]: https://www.elastic.co/guide/en/elasticsearch/reference/current/service-accounts.html
+    */
+    Bearer(String),
+}
+
+impl Credentials {
+    /** Authenticate using a username and password. */
+    pub fn basic(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Credentials::Basic {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+
+    /** Authenticate using the id and secret value of an API key. */
+    pub fn api_key(id: impl Into<String>, key: impl Into<String>) -> Self {
+        Credentials::ApiKey {
+            id: id.into(),
+            key: key.into(),
+        }
+    }
+
+    /** Authenticate using a bearer token. */
+    pub fn bearer(token: impl Into<String>) -> Self {
+        Credentials::Bearer(token.into())
+    }
+
+    /** Get the value of the `Authorization` header for these credentials. */
+    pub(crate) fn header_value(&self) -> Result<HeaderValue, Error> {
+        let value = match *self {
+            Credentials::Basic {
+                ref username,
+                ref password,
+            } => format!(
+                "Basic {}",
+                base64::encode(&format!("{}:{}", username, password))
+            ),
+            Credentials::ApiKey { ref id, ref key } => {
+                format!("ApiKey {}", base64::encode(&format!("{}:{}", id, key)))
+            }
+            Credentials::Bearer(ref token) => format!("Bearer {}", token),
+        };
+
+        let mut value = HeaderValue::from_str(&value).map_err(|_| {
+            error::build(error::message(
+                "the credentials contain characters that aren't valid in a header",
+            ))
+        })?;
+        value.set_sensitive(true);
+
+        Ok(value)
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Credentials::Basic { ref username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"<redacted>")
+                .finish(),
+            Credentials::ApiKey { ref id, .. } => f
+                .debug_struct("ApiKey")
+                .field("id", id)
+                .field("key", &"<redacted>")
+                .finish(),
+            Credentials::Bearer(_) => f.debug_tuple("Bearer").field(&"<redacted>").finish(),
+        }
+    }
+}
+
+/** A callback that gets new credentials after a request is rejected. */
+pub(crate) type RefreshCredentials = Arc<
+    Fn() -> Box<Future<Item = Credentials, Error = Box<StdError + Send + Sync>> + Send>
+        + Send
+        + Sync,
+>;
+
+/**
+A middleware that sets the `Authorization` header on requests that don't already have one.
+
+Clones of the middleware share the same credentials.
+*/
+#[derive(Clone)]
+pub(crate) struct Auth {
+    inner: Arc<AuthInner>,
+}
+
+struct AuthInner {
+    header: RwLock<HeaderValue>,
+    refresh: Option<RefreshCredentials>,
+    refreshing: AtomicBool,
+}
+
+impl Auth {
+    pub(crate) fn new(
+        credentials: &Credentials,
+        refresh: Option<RefreshCredentials>,
+    ) -> Result<Self, Error> {
+        Ok(Auth {
+            inner: Arc::new(AuthInner {
+                header: RwLock::new(credentials.header_value()?),
+                refresh,
+                refreshing: AtomicBool::new(false),
+            }),
+        })
+    }
+
+    /**
+    Refresh the credentials after a request was rejected.
+
+    This returns `None` if there's no refresh callback, or a refresh is already in progress.
+    The returned future never fails; errors from the callback are logged instead.
+    */
+    pub(crate) fn refresh(&self) -> Option<Box<Future<Item = (), Error = ()> + Send>> {
+        let refresh = self.inner.refresh.as_ref()?;
+
+        if self.inner.refreshing.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+
+        info!("Elasticsearch Auth: refreshing credentials");
+
+        let inner = self.inner.clone();
+        let refreshed = refresh().then(move |credentials| {
+            match credentials.map(|credentials| credentials.header_value()) {
+                Ok(Ok(header)) => {
+                    info!("Elasticsearch Auth: refreshed credentials");
+                    *inner.header.write().expect("lock poisoned") = header;
+                }
+                Ok(Err(e)) => error!("Elasticsearch Auth: error: '{:?}'", e),
+                Err(e) => error!("Elasticsearch Auth: error: '{:?}'", e),
+            }
+
+            inner.refreshing.store(false, Ordering::SeqCst);
+
+            Ok(())
+        });
+
+        Some(Box::new(refreshed))
+    }
+}
+
+impl Middleware for Auth {
+    fn on_request(&self, req: &mut MiddlewareRequest) -> Result<(), Box<StdError + Send + Sync>> {
+        if !req.headers().contains_key(AUTHORIZATION) {
+            let header = self.inner.header.read().expect("lock poisoned").clone();
+            req.header(AUTHORIZATION, header);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::sender::{
+        middleware::Middlewares,
+        RequestParams,
+    };
+    use futures::future;
+    use http::Method;
+    use std::sync::Arc;
+
+    fn authorize(credentials: Credentials, params: &mut RequestParams) {
+        authorize_with(&Auth::new(&credentials, None).unwrap(), params)
+    }
+
+    fn authorize_with(auth: &Auth, params: &mut RequestParams) {
+        Middlewares::new(vec![Arc::new(auth.clone())])
+            .on_request(&Method::GET, "/", params)
+            .unwrap();
+    }
+
+    fn header(auth: &Auth) -> HeaderValue {
+        let mut params = RequestParams::default();
+        authorize_with(auth, &mut params);
+
+        params.get_headers()[AUTHORIZATION].clone()
+    }
+
+    #[test]
+    fn basic_header() {
+        let mut params = RequestParams::default();
+        authorize(Credentials::basic("elastic", "changeme"), &mut params);
+
+        assert_eq!(
+            "Basic ZWxhc3RpYzpjaGFuZ2VtZQ==",
+            params.get_headers()[AUTHORIZATION]
+        );
+    }
+
+    #[test]
+    fn api_key_header() {
+        let mut params = RequestParams::default();
+        authorize(
+            Credentials::api_key("VuaCfGcBCdbkQm-e5aOx", "ui2lp2axTNmsyakw9tvNnw"),
+            &mut params,
+        );
+
+        assert_eq!(
+            "ApiKey VnVhQ2ZHY0JDZGJrUW0tZTVhT3g6dWkybHAyYXhUTm1zeWFrdzl0dk5udw==",
+            params.get_headers()[AUTHORIZATION]
+        );
+    }
+
+    #[test]
+    fn bearer_header() {
+        let mut params = RequestParams::default();
+        authorize(Credentials::bearer("abc"), &mut params);
+
+        assert_eq!("Bearer abc", params.get_headers()[AUTHORIZATION]);
+    }
+
+    #[test]
+    fn invalid_bearer_fails() {
+        assert!(Auth::new(&Credentials::bearer("a\nb"), None).is_err());
+    }
+
+    #[test]
+    fn refresh_updates_header() {
+        let refresh: RefreshCredentials =
+            Arc::new(|| Box::new(future::ok(Credentials::bearer("refreshed"))));
+        let auth = Auth::new(&Credentials::bearer("initial"), Some(refresh)).unwrap();
+
+        assert_eq!("Bearer initial", header(&auth));
+
+        auth.refresh().unwrap().wait().unwrap();
+
+        assert_eq!("Bearer refreshed", header(&auth));
+    }
+
+    #[test]
+    fn refresh_error_keeps_header() {
+        let refresh: RefreshCredentials = Arc::new(|| Box::new(future::err("failed".into())));
+        let auth = Auth::new(&Credentials::bearer("initial"), Some(refresh)).unwrap();
+
+        auth.refresh().unwrap().wait().unwrap();
+
+        assert_eq!("Bearer initial", header(&auth));
+
+        // The failed refresh doesn't prevent another one
+        assert!(auth.refresh().is_some());
+    }
+
+    #[test]
+    fn refresh_in_progress_is_not_repeated() {
+        let refresh: RefreshCredentials =
+            Arc::new(|| Box::new(future::ok(Credentials::bearer("refreshed"))));
+        let auth = Auth::new(&Credentials::bearer("initial"), Some(refresh)).unwrap();
+
+        let pending = auth.refresh().unwrap();
+        assert!(auth.refresh().is_none());
+
+        pending.wait().unwrap();
+        assert!(auth.refresh().is_some());
+    }
+
+    #[test]
+    fn no_refresh_callback() {
+        let auth = Auth::new(&Credentials::bearer("initial"), None).unwrap();
+
+        assert!(auth.refresh().is_none());
+    }
+
+    #[test]
+    fn request_header_takes_precedence() {
+        let mut params =
+            RequestParams::default().header(AUTHORIZATION, HeaderValue::from_static("Bearer abc"));
+        authorize(Credentials::basic("elastic", "changeme"), &mut params);
+
+        assert_eq!("Bearer abc", params.get_headers()[AUTHORIZATION]);
+    }
+
+    #[test]
+    fn debug_redacts_secrets() {
+        let debug = format!("{:?}", Credentials::basic("elastic", "changeme"));
+
+        assert!(debug.contains("elastic"));
+        assert!(!debug.contains("changeme"));
+    }
+}
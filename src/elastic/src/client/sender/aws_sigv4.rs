@@ -0,0 +1,1252 @@
+/*!
+Sign requests to IAM-protected Amazon Elasticsearch Service and Amazon OpenSearch Service domains.
+
+This module is only available when the `aws-sigv4` feature is enabled.
+
+An [`AwsSigV4`][AwsSigV4] signer can be attached to a client using the `aws_sigv4` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+The client then signs every request it sends using [AWS Signature Version 4][docs-sigv4], including requests used to sniff nodes.
+Requests are signed after the `pre_send` function is called, so changes made by it are included in the signature.
+Request bodies that are read from a file are buffered in memory so they can be signed.
+
+By default, credentials are fetched from the environment, the shared credentials file, a container's credentials endpoint or the instance metadata service.
+They're cached until shortly before they expire.
+Fetching credentials can block, so requests are signed on the `tokio` runtime's blocking thread pool instead of the thread that's sending them.
+See [`DefaultCredentialsProvider`][DefaultCredentialsProvider] for details.
+Credentials from other sources can be used by implementing [`AwsCredentialsProvider`][AwsCredentialsProvider].
+
+# Examples
+
+Sign requests to a domain in `us-east-1`:
+
+```
+# use elastic::prelude::*;
+# use elastic::client::sender::aws_sigv4::AwsSigV4;
+let builder = SyncClientBuilder::new()
+    .static_node("https://search-my-domain.us-east-1.es.amazonaws.com")
+    .aws_sigv4(AwsSigV4::new("us-east-1"));
+```
+
+Sign requests using static credentials:
+
+```
+# use elastic::prelude::*;
+# use elastic::client::sender::aws_sigv4::{AwsSigV4, AwsCredentials};
+let credentials = AwsCredentials::new("AKIDEXAMPLE", "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY");
+
+let builder = SyncClientBuilder::new()
+    .static_node("https://search-my-domain.us-east-1.es.amazonaws.com")
+    .aws_sigv4(AwsSigV4::new("us-east-1").credentials(credentials));
+```
+
+[docs-sigv4]: https://docs.aws.amazon.com/general/latest/gr/signature-version-4.html
+[AwsSigV4]: struct.AwsSigV4.html
+[AwsCredentialsProvider]: trait.AwsCredentialsProvider.html
+[DefaultCredentialsProvider]: struct.DefaultCredentialsProvider.html
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+use chrono::{
+    DateTime,
+    Duration as ChronoDuration,
+    Utc,
+};
+use futures::{
+    Future,
+    IntoFuture,
+};
+use reqwest::{
+    Client as AsyncHttpClient,
+    Method as HttpMethod,
+};
+use ring::{
+    digest,
+    hmac,
+};
+use serde_json;
+use std::{
+    env,
+    error::Error as StdError,
+    fmt,
+    fs,
+    io,
+    path::PathBuf,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::Duration,
+};
+
+use error::{
+    self,
+    Error,
+};
+use http::{
+    header::{
+        HeaderValue,
+        AUTHORIZATION,
+    },
+    BufferedBody,
+    HttpRequest,
+};
+use runtime::{
+    self,
+    Blocking,
+};
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const DEFAULT_SERVICE: &str = "es";
+const SERVERLESS_SERVICE: &str = "aoss";
+
+const X_AMZ_DATE: &str = "x-amz-date";
+const X_AMZ_SECURITY_TOKEN: &str = "x-amz-security-token";
+const X_AMZ_CONTENT_SHA256: &str = "x-amz-content-sha256";
+
+const CONTAINER_ENDPOINT: &str = "http://169.254.170.2";
+const INSTANCE_METADATA_ENDPOINT: &str = "http://169.254.169.254";
+const INSTANCE_METADATA_TOKEN_TTL_SECS: u64 = 21600;
+
+/** How long to wait for a credentials endpoint to respond. */
+const METADATA_TIMEOUT: Duration = Duration::from_secs(1);
+
+/** How long credentials that don't expire are cached for before they're fetched again. */
+const STATIC_CREDENTIALS_SECS: i64 = 10 * 60;
+
+/** How long before credentials expire that they're fetched again. */
+const REFRESH_WINDOW_SECS: i64 = 5 * 60;
+
+/** How long to wait after failing to fetch credentials before fetching them again. */
+const FAILED_FETCH_BACKOFF_SECS: i64 = 30;
+
+/**
+AWS credentials used to sign requests.
+
+The secret access key and session token aren't included in the `Debug` output.
+*/
+#[derive(Clone, PartialEq)]
+pub struct AwsCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+impl AwsCredentials {
+    /** Create credentials from an access key id and secret access key. */
+    pub fn new(access_key_id: impl Into<String>, secret_access_key: impl Into<String>) -> Self {
+        AwsCredentials {
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token: None,
+        }
+    }
+
+    /** Set the session token for temporary credentials. */
+    pub fn session_token(mut self, session_token: impl Into<String>) -> Self {
+        self.session_token = Some(session_token.into());
+        self
+    }
+}
+
+impl fmt::Debug for AwsCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AwsCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &"<redacted>")
+            .field(
+                "session_token",
+                &self.session_token.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
+}
+
+/**
+A source of AWS credentials.
+
+The provider is called for every request that's signed, so it should cache credentials that are expensive to fetch.
+It's called on the `tokio` runtime's blocking thread pool, so it can block while it fetches them.
+*/
+pub trait AwsCredentialsProvider: Send + Sync {
+    /** Get the credentials to sign a request with. */
+    fn credentials(&self) -> Result<AwsCredentials, Box<dyn StdError + Send + Sync>>;
+}
+
+impl AwsCredentialsProvider for AwsCredentials {
+    fn credentials(&self) -> Result<AwsCredentials, Box<dyn StdError + Send + Sync>> {
+        Ok(self.clone())
+    }
+}
+
+impl<TProvider> AwsCredentialsProvider for Arc<TProvider>
+where
+    TProvider: AwsCredentialsProvider + ?Sized,
+{
+    fn credentials(&self) -> Result<AwsCredentials, Box<dyn StdError + Send + Sync>> {
+        (**self).credentials()
+    }
+}
+
+/**
+Fetch credentials from the standard locations used by AWS tools.
+
+Credentials are fetched from the first of these sources that has them:
+
+1. The `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN` environment variables.
+1. The shared credentials file at `~/.aws/credentials`, or the path in the `AWS_SHARED_CREDENTIALS_FILE` environment variable.
+1. A container's credentials endpoint, if the `AWS_CONTAINER_CREDENTIALS_RELATIVE_URI` or `AWS_CONTAINER_CREDENTIALS_FULL_URI` environment variable is set.
+   See [`ContainerCredentialsProvider`][ContainerCredentialsProvider].
+1. The EC2 instance metadata service, unless the `AWS_EC2_METADATA_DISABLED` environment variable is `true`.
+   See [`InstanceMetadataCredentialsProvider`][InstanceMetadataCredentialsProvider].
+
+The profile in the shared credentials file is read from the `AWS_PROFILE` environment variable, or `default` if it isn't set.
+
+Credentials are cached, and shared by clones of the provider.
+Temporary credentials from a container or instance are fetched again 5 minutes before they expire.
+Credentials that don't expire are fetched again every 5 minutes, so rotated credentials are picked up without rebuilding the client.
+If fetching credentials again fails then the cached credentials are used until they expire.
+After a failed fetch, credentials aren't fetched again for 30 seconds.
+
+Credentials endpoints are requested using `tokio`, so the provider must be called from a thread that can block within a `tokio` runtime.
+
+Web identity credentials aren't fetched by this provider, but can be used by implementing [`AwsCredentialsProvider`][AwsCredentialsProvider].
+
+[AwsCredentialsProvider]: trait.AwsCredentialsProvider.html
+[ContainerCredentialsProvider]: struct.ContainerCredentialsProvider.html
+[InstanceMetadataCredentialsProvider]: struct.InstanceMetadataCredentialsProvider.html
+*/
+#[derive(Debug, Clone, Default)]
+pub struct DefaultCredentialsProvider {
+    cache: Arc<CredentialsCache>,
+}
+
+impl DefaultCredentialsProvider {
+    /** Create a provider that fetches credentials from the standard locations. */
+    pub fn new() -> Self {
+        DefaultCredentialsProvider::default()
+    }
+}
+
+impl AwsCredentialsProvider for DefaultCredentialsProvider {
+    fn credentials(&self) -> Result<AwsCredentials, Box<dyn StdError + Send + Sync>> {
+        self.cache.get(Utc::now(), || {
+            if let Some(credentials) = env_credentials() {
+                return Ok(static_credentials(credentials));
+            }
+
+            if let Some(credentials) = shared_file_credentials()? {
+                return Ok(static_credentials(credentials));
+            }
+
+            if let Some(uri) = container_uri()? {
+                return container_credentials(&uri, container_authorization()?);
+            }
+
+            if instance_metadata_disabled() {
+                return Err(
+                    "AWS credentials weren't found in the environment or shared credentials file"
+                        .into(),
+                );
+            }
+
+            instance_metadata_credentials(INSTANCE_METADATA_ENDPOINT).map_err(|e| {
+                format!(
+                    "AWS credentials weren't found in the environment, shared credentials file or instance metadata: {}",
+                    e
+                )
+                .into()
+            })
+        })
+    }
+}
+
+/**
+Fetch temporary credentials from a container's credentials endpoint.
+
+This is the endpoint used by tasks on Amazon ECS, and by pods using EKS Pod Identity.
+By default, its URI is read from the `AWS_CONTAINER_CREDENTIALS_RELATIVE_URI` or `AWS_CONTAINER_CREDENTIALS_FULL_URI` environment variable,
+and the authorization token from the `AWS_CONTAINER_AUTHORIZATION_TOKEN` or `AWS_CONTAINER_AUTHORIZATION_TOKEN_FILE` environment variable.
+Only `http` endpoints are supported.
+
+Credentials are cached until 5 minutes before they expire, and shared by clones of the provider.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct ContainerCredentialsProvider {
+    uri: Option<String>,
+    cache: Arc<CredentialsCache>,
+}
+
+impl ContainerCredentialsProvider {
+    /** Create a provider that fetches credentials from the endpoint in the environment. */
+    pub fn new() -> Self {
+        ContainerCredentialsProvider::default()
+    }
+
+    /** Fetch credentials from the given endpoint URI instead of the one in the environment. */
+    pub fn uri(mut self, uri: impl Into<String>) -> Self {
+        self.uri = Some(uri.into());
+        self
+    }
+}
+
+impl AwsCredentialsProvider for ContainerCredentialsProvider {
+    fn credentials(&self) -> Result<AwsCredentials, Box<dyn StdError + Send + Sync>> {
+        self.cache.get(Utc::now(), || {
+            let uri = match self.uri {
+                Some(ref uri) => uri.clone(),
+                None => container_uri()?
+                    .ok_or("a container credentials endpoint wasn't found in the environment")?,
+            };
+
+            container_credentials(&uri, container_authorization()?)
+        })
+    }
+}
+
+/**
+Fetch temporary credentials for an EC2 instance's IAM role from the instance metadata service.
+
+Credentials are fetched using IMDSv2, from the endpoint at `http://169.254.169.254`.
+
+Credentials are cached until 5 minutes before they expire, and shared by clones of the provider.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct InstanceMetadataCredentialsProvider {
+    endpoint: Option<String>,
+    cache: Arc<CredentialsCache>,
+}
+
+impl InstanceMetadataCredentialsProvider {
+    /** Create a provider that fetches credentials from the instance metadata service. */
+    pub fn new() -> Self {
+        InstanceMetadataCredentialsProvider::default()
+    }
+
+    /** Fetch credentials from the instance metadata service at the given endpoint, like `http://169.254.169.254`. */
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+}
+
+impl AwsCredentialsProvider for InstanceMetadataCredentialsProvider {
+    fn credentials(&self) -> Result<AwsCredentials, Box<dyn StdError + Send + Sync>> {
+        let endpoint = self
+            .endpoint
+            .as_deref()
+            .unwrap_or(INSTANCE_METADATA_ENDPOINT);
+
+        self.cache
+            .get(Utc::now(), || instance_metadata_credentials(endpoint))
+    }
+}
+
+/** Credentials and the time they should no longer be used. */
+type ExpiringCredentials = (AwsCredentials, DateTime<Utc>);
+
+/**
+A cache of credentials that expire.
+
+Credentials are fetched while the cache is locked, so requests signed at the same time wait for a single fetch.
+Requests are signed on a blocking thread, so waiting for the lock doesn't stall the runtime.
+A failed fetch is remembered for a while, so requests don't each wait for credentials that can't be fetched.
+*/
+#[derive(Debug, Default)]
+struct CredentialsCache(Mutex<CachedCredentials>);
+
+#[derive(Debug, Default)]
+struct CachedCredentials {
+    credentials: Option<ExpiringCredentials>,
+    // The time of the last failed fetch and its error
+    failed: Option<(DateTime<Utc>, String)>,
+}
+
+impl CachedCredentials {
+    /** Get credentials that haven't expired yet, even if they should be refreshed. */
+    fn unexpired(&self, now: DateTime<Utc>) -> Option<AwsCredentials> {
+        match self.credentials {
+            Some((ref credentials, expires)) if now < expires => Some(credentials.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl CredentialsCache {
+    fn get(
+        &self,
+        now: DateTime<Utc>,
+        fetch: impl FnOnce() -> Result<ExpiringCredentials, Box<dyn StdError + Send + Sync>>,
+    ) -> Result<AwsCredentials, Box<dyn StdError + Send + Sync>> {
+        let mut cached = self
+            .0
+            .lock()
+            .map_err(|_| "the credentials cache is poisoned")?;
+
+        if let Some((ref credentials, expires)) = cached.credentials {
+            if now < expires - ChronoDuration::seconds(REFRESH_WINDOW_SECS) {
+                return Ok(credentials.clone());
+            }
+        }
+
+        // Keep using credentials that haven't expired yet if they can't be refreshed
+        if let Some((failed_at, ref e)) = cached.failed {
+            if now < failed_at + ChronoDuration::seconds(FAILED_FETCH_BACKOFF_SECS) {
+                return cached.unexpired(now).ok_or_else(|| e.clone().into());
+            }
+        }
+
+        match fetch() {
+            Ok((credentials, expires)) => {
+                cached.credentials = Some((credentials.clone(), expires));
+                cached.failed = None;
+
+                Ok(credentials)
+            }
+            Err(e) => {
+                cached.failed = Some((now, e.to_string()));
+
+                cached.unexpired(now).ok_or(e)
+            }
+        }
+    }
+}
+
+/** Cache credentials that don't expire for a while so they're fetched again if they're rotated. */
+fn static_credentials(credentials: AwsCredentials) -> ExpiringCredentials {
+    (
+        credentials,
+        Utc::now() + ChronoDuration::seconds(STATIC_CREDENTIALS_SECS),
+    )
+}
+
+fn env_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn env_credentials() -> Option<AwsCredentials> {
+    let access_key_id = env_var("AWS_ACCESS_KEY_ID")?;
+    let secret_access_key = env_var("AWS_SECRET_ACCESS_KEY")?;
+
+    Some(AwsCredentials {
+        access_key_id,
+        secret_access_key,
+        session_token: env_var("AWS_SESSION_TOKEN"),
+    })
+}
+
+/**
+Read credentials from the shared credentials file.
+
+A missing file or profile isn't an error, so credentials can be fetched from another source instead.
+*/
+fn shared_file_credentials() -> Result<Option<AwsCredentials>, Box<dyn StdError + Send + Sync>> {
+    let path = match env_var("AWS_SHARED_CREDENTIALS_FILE") {
+        Some(path) => PathBuf::from(path),
+        None => match env_var("HOME").or_else(|| env_var("USERPROFILE")) {
+            Some(home) => PathBuf::from(home).join(".aws").join("credentials"),
+            None => return Ok(None),
+        },
+    };
+
+    let profile = env_var("AWS_PROFILE").unwrap_or_else(|| "default".to_owned());
+
+    match fs::read_to_string(&path) {
+        Ok(file) => Ok(profile_credentials(&file, &profile)),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("failed to read '{}': {}", path.display(), e).into()),
+    }
+}
+
+fn instance_metadata_disabled() -> bool {
+    env_var("AWS_EC2_METADATA_DISABLED")
+        .is_some_and(|disabled| disabled.eq_ignore_ascii_case("true"))
+}
+
+/** Get the URI of a container's credentials endpoint from the environment. */
+fn container_uri() -> Result<Option<String>, Box<dyn StdError + Send + Sync>> {
+    if let Some(relative) = env_var("AWS_CONTAINER_CREDENTIALS_RELATIVE_URI") {
+        return Ok(Some(format!("{}{}", CONTAINER_ENDPOINT, relative)));
+    }
+
+    Ok(env_var("AWS_CONTAINER_CREDENTIALS_FULL_URI"))
+}
+
+/** Get the authorization token for a container's credentials endpoint from the environment. */
+fn container_authorization() -> Result<Option<String>, Box<dyn StdError + Send + Sync>> {
+    if let Some(path) = env_var("AWS_CONTAINER_AUTHORIZATION_TOKEN_FILE") {
+        let token =
+            fs::read_to_string(&path).map_err(|e| format!("failed to read '{}': {}", path, e))?;
+
+        return Ok(Some(token.trim().to_owned()));
+    }
+
+    Ok(env_var("AWS_CONTAINER_AUTHORIZATION_TOKEN"))
+}
+
+/** Fetch credentials from a container's credentials endpoint. */
+fn container_credentials(
+    uri: &str,
+    authorization: Option<String>,
+) -> Result<ExpiringCredentials, Box<dyn StdError + Send + Sync>> {
+    let headers: Vec<(&str, &str)> = authorization
+        .as_ref()
+        .map(|token| ("Authorization", token.as_str()))
+        .into_iter()
+        .collect();
+
+    let body = metadata_request(HttpMethod::GET, uri, &headers)?;
+
+    metadata_credentials(&body)
+}
+
+/** Fetch credentials for an instance's IAM role using IMDSv2. */
+fn instance_metadata_credentials(
+    endpoint: &str,
+) -> Result<ExpiringCredentials, Box<dyn StdError + Send + Sync>> {
+    let endpoint = endpoint.trim_end_matches('/');
+
+    let ttl = INSTANCE_METADATA_TOKEN_TTL_SECS.to_string();
+    let token = metadata_request(
+        HttpMethod::PUT,
+        &format!("{}/latest/api/token", endpoint),
+        &[("X-aws-ec2-metadata-token-ttl-seconds", &ttl)],
+    )?;
+    let headers = [("X-aws-ec2-metadata-token", token.trim())];
+
+    let roles = metadata_request(
+        HttpMethod::GET,
+        &format!("{}/latest/meta-data/iam/security-credentials/", endpoint),
+        &headers,
+    )?;
+    let role = roles
+        .lines()
+        .map(str::trim)
+        .find(|role| !role.is_empty())
+        .ok_or("the instance doesn't have an IAM role")?;
+
+    let body = metadata_request(
+        HttpMethod::GET,
+        &format!(
+            "{}/latest/meta-data/iam/security-credentials/{}",
+            endpoint, role
+        ),
+        &headers,
+    )?;
+
+    metadata_credentials(&body)
+}
+
+/** Temporary credentials returned by a container or instance metadata endpoint. */
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MetadataCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    token: Option<String>,
+    expiration: Option<String>,
+}
+
+fn metadata_credentials(body: &str) -> Result<ExpiringCredentials, Box<dyn StdError + Send + Sync>> {
+    let credentials: MetadataCredentials = serde_json::from_str(body)?;
+
+    let expires = match credentials.expiration {
+        Some(ref expiration) => DateTime::parse_from_rfc3339(expiration)?.with_timezone(&Utc),
+        None => Utc::now() + ChronoDuration::seconds(STATIC_CREDENTIALS_SECS),
+    };
+
+    Ok((
+        AwsCredentials {
+            access_key_id: credentials.access_key_id,
+            secret_access_key: credentials.secret_access_key,
+            session_token: credentials.token,
+        },
+        expires,
+    ))
+}
+
+/**
+Send a request to a local credentials endpoint and return the body of a successful response.
+
+The request is sent on the current `tokio` runtime and the thread blocks until it completes.
+Credentials endpoints are link-local or loopback addresses, so proxies aren't used.
+*/
+fn metadata_request(
+    method: HttpMethod,
+    uri: &str,
+    headers: &[(&str, &str)],
+) -> Result<String, Box<dyn StdError + Send + Sync>> {
+    let runtime = runtime::current()?;
+
+    let http = AsyncHttpClient::builder()
+        .timeout(METADATA_TIMEOUT)
+        .no_proxy()
+        .build()?;
+
+    let mut req = http.request(method, uri);
+    for &(name, value) in headers {
+        req = req.header(name, value);
+    }
+
+    let res = runtime.block_on(req.send())?;
+
+    if !res.status().is_success() {
+        return Err(format!(
+            "credentials endpoint '{}' returned status {}",
+            uri,
+            res.status().as_u16()
+        )
+        .into());
+    }
+
+    Ok(runtime.block_on(res.text())?)
+}
+
+/** Read credentials for a profile from the contents of a shared credentials file. */
+fn profile_credentials(file: &str, profile: &str) -> Option<AwsCredentials> {
+    let mut in_profile = false;
+    let mut access_key_id = None;
+    let mut secret_access_key = None;
+    let mut session_token = None;
+
+    for line in file.lines().map(str::trim) {
+        if line.starts_with('[') && line.ends_with(']') {
+            in_profile = line[1..line.len() - 1].trim() == profile;
+            continue;
+        }
+
+        if !in_profile {
+            continue;
+        }
+
+        if let Some(i) = line.find('=') {
+            let value = line[i + 1..].trim().to_owned();
+
+            match line[..i].trim() {
+                "aws_access_key_id" => access_key_id = Some(value),
+                "aws_secret_access_key" => secret_access_key = Some(value),
+                "aws_session_token" => session_token = Some(value),
+                _ => (),
+            }
+        }
+    }
+
+    Some(AwsCredentials {
+        access_key_id: access_key_id?,
+        secret_access_key: secret_access_key?,
+        session_token,
+    })
+}
+
+/** Sign requests using AWS Signature Version 4. */
+#[derive(Clone)]
+pub struct AwsSigV4 {
+    region: String,
+    service: String,
+    credentials: Arc<dyn AwsCredentialsProvider>,
+}
+
+impl AwsSigV4 {
+    /**
+    Sign requests for a domain in the given region.
+
+    Requests are signed for the `es` service using credentials from the [`DefaultCredentialsProvider`][DefaultCredentialsProvider].
+
+    [DefaultCredentialsProvider]: struct.DefaultCredentialsProvider.html
+    */
+    pub fn new(region: impl Into<String>) -> Self {
+        AwsSigV4 {
+            region: region.into(),
+            service: DEFAULT_SERVICE.to_owned(),
+            credentials: Arc::new(DefaultCredentialsProvider::new()),
+        }
+    }
+
+    /**
+    Set the name of the service to sign requests for.
+
+    The default is `es`, which is used by Amazon Elasticsearch Service and Amazon OpenSearch Service domains.
+    Amazon OpenSearch Serverless collections use `aoss`.
+    */
+    pub fn service(mut self, service: impl Into<String>) -> Self {
+        self.service = service.into();
+        self
+    }
+
+    /** Set the source of credentials to sign requests with. */
+    pub fn credentials(mut self, credentials: impl AwsCredentialsProvider + 'static) -> Self {
+        self.credentials = Arc::new(credentials);
+        self
+    }
+
+    /**
+    Sign a request on the runtime's blocking thread pool.
+
+    Fetching credentials can block, so the request isn't signed on the thread that's driving the runtime.
+    */
+    pub(crate) fn sign_blocking<TBody>(
+        &self,
+        mut req: HttpRequest<TBody>,
+    ) -> impl Future<Item = HttpRequest<TBody>, Error = Error> + Send
+    where
+        TBody: BufferedBody + Send + 'static,
+    {
+        let signer = self.clone();
+
+        runtime::current()
+            .map_err(error::request)
+            .into_future()
+            .and_then(move |runtime| {
+                Blocking::spawn(&runtime, move || {
+                    signer.sign(&mut req)?;
+
+                    Ok(req)
+                })
+            })
+    }
+
+    /** Sign a request, adding the `Authorization` and `X-Amz-*` headers to it. */
+    pub(crate) fn sign<TBody>(&self, req: &mut HttpRequest<TBody>) -> Result<(), Error>
+    where
+        TBody: BufferedBody,
+    {
+        let credentials = self
+            .credentials
+            .credentials()
+            .map_err(error::wrapped)
+            .map_err(error::request)?;
+
+        self.sign_at(req, &credentials, Utc::now())
+    }
+
+    fn sign_at<TBody>(
+        &self,
+        req: &mut HttpRequest<TBody>,
+        credentials: &AwsCredentials,
+        now: DateTime<Utc>,
+    ) -> Result<(), Error>
+    where
+        TBody: BufferedBody,
+    {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+
+        let payload_hash = {
+            let body = match req.body {
+                Some(ref mut body) => body.buffer().map_err(error::request)?,
+                None => &[],
+            };
+
+            hex(digest::digest(&digest::SHA256, body).as_ref())
+        };
+
+        {
+            let headers = req.headers_mut();
+
+            headers.insert(X_AMZ_DATE, header_value(&amz_date)?);
+
+            match credentials.session_token {
+                Some(ref token) => {
+                    headers.insert(X_AMZ_SECURITY_TOKEN, header_value(token)?);
+                }
+                None => {
+                    headers.remove(X_AMZ_SECURITY_TOKEN);
+                }
+            }
+
+            // OpenSearch Serverless requires the payload hash to be sent
+            if self.service == SERVERLESS_SERVICE {
+                headers.insert(X_AMZ_CONTENT_SHA256, header_value(&payload_hash)?);
+            }
+
+            headers.remove(AUTHORIZATION);
+        }
+
+        let (canonical_headers, signed_headers) = canonical_headers(req)?;
+
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            req.method.as_str(),
+            canonical_uri(req.url.path()),
+            canonical_query(req),
+            canonical_headers,
+            signed_headers,
+            payload_hash
+        );
+
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, self.service);
+
+        let string_to_sign = format!(
+            "{}\n{}\n{}\n{}",
+            ALGORITHM,
+            amz_date,
+            scope,
+            hex(digest::digest(&digest::SHA256, canonical_request.as_bytes()).as_ref())
+        );
+
+        let signing_key = [
+            date.as_str(),
+            self.region.as_str(),
+            self.service.as_str(),
+            "aws4_request",
+        ]
+        .iter()
+        .fold(
+            format!("AWS4{}", credentials.secret_access_key).into_bytes(),
+            |key, part| hmac_sha256(&key, part.as_bytes()),
+        );
+
+        let signature = hex(&hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+        let authorization = format!(
+            "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+            ALGORITHM, credentials.access_key_id, scope, signed_headers, signature
+        );
+
+        let mut authorization = header_value(&authorization)?;
+        authorization.set_sensitive(true);
+
+        req.headers_mut().insert(AUTHORIZATION, authorization);
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for AwsSigV4 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AwsSigV4")
+            .field("region", &self.region)
+            .field("service", &self.service)
+            .finish()
+    }
+}
+
+/** Get the canonical headers and signed header names for a request. */
+fn canonical_headers<TBody>(req: &HttpRequest<TBody>) -> Result<(String, String), Error> {
+    let host = match (req.url.host_str(), req.url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_owned(),
+        (None, _) => Err(error::request(error::message(
+            "the url to sign doesn't have a host",
+        )))?,
+    };
+
+    let mut headers = vec![("host".to_owned(), host)];
+
+    for name in req.headers.keys() {
+        let values = req
+            .headers
+            .get_all(name)
+            .iter()
+            .map(|value| {
+                value
+                    .to_str()
+                    .map(|value| value.split_whitespace().collect::<Vec<_>>().join(" "))
+                    .map_err(error::request)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        headers.push((name.as_str().to_owned(), values.join(",")));
+    }
+
+    headers.sort();
+
+    let canonical = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value))
+        .collect();
+
+    let signed = headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    Ok((canonical, signed))
+}
+
+/**
+Get the canonical path for a request.
+
+The path in the url is already encoded, so encoding it again gives the double-encoded path expected by services other than S3.
+*/
+fn canonical_uri(path: &str) -> String {
+    if path.is_empty() {
+        return "/".to_owned();
+    }
+
+    path.split('/')
+        .map(uri_encode)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn canonical_query<TBody>(req: &HttpRequest<TBody>) -> String {
+    let mut query = req
+        .url
+        .query_pairs()
+        .map(|(key, value)| (uri_encode(&key), uri_encode(&value)))
+        .collect::<Vec<_>>();
+
+    query.sort();
+
+    query
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn uri_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+
+    for &b in value.as_bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(b as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+
+    encoded
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, key);
+
+    hmac::sign(&key, data).as_ref().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn header_value(value: &str) -> Result<HeaderValue, Error> {
+    HeaderValue::from_str(value).map_err(error::request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::{
+        AsyncBody,
+        AsyncHttpRequest,
+        Method,
+        Url,
+    };
+
+    // Requests and signatures from the AWS Signature Version 4 test suite
+    fn signer() -> AwsSigV4 {
+        AwsSigV4::new("us-east-1").service("service")
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2015-08-30T12:36:00Z".parse().unwrap()
+    }
+
+    fn credentials() -> AwsCredentials {
+        AwsCredentials::new("AKIDEXAMPLE", "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY")
+    }
+
+    fn request(method: Method, url: &str) -> AsyncHttpRequest {
+        HttpRequest {
+            url: Url::parse(url).unwrap(),
+            method,
+            headers: Default::default(),
+            body: None,
+        }
+    }
+
+    fn sign(signer: &AwsSigV4, req: &mut AsyncHttpRequest) {
+        signer.sign_at(req, &credentials(), now()).unwrap();
+    }
+
+    #[test]
+    fn sign_get_vanilla() {
+        let mut req = request(Method::GET, "https://example.amazonaws.com/");
+        sign(&signer(), &mut req);
+
+        assert_eq!("20150830T123600Z", req.headers[X_AMZ_DATE]);
+        assert_eq!(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31",
+            req.headers[AUTHORIZATION]
+        );
+    }
+
+    #[test]
+    fn sign_get_query_order() {
+        let mut req = request(
+            Method::GET,
+            "https://example.amazonaws.com/?Param2=value2&Param1=value1",
+        );
+        sign(&signer(), &mut req);
+
+        assert_eq!(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500",
+            req.headers[AUTHORIZATION]
+        );
+    }
+
+    #[test]
+    fn sign_session_token() {
+        let mut req = request(Method::GET, "https://example.amazonaws.com/");
+        signer()
+            .sign_at(&mut req, &credentials().session_token("token"), now())
+            .unwrap();
+
+        assert_eq!("token", req.headers[X_AMZ_SECURITY_TOKEN]);
+        assert!(req.headers[AUTHORIZATION]
+            .to_str()
+            .unwrap()
+            .contains("SignedHeaders=host;x-amz-date;x-amz-security-token,"));
+    }
+
+    #[test]
+    fn sign_serverless_payload_hash() {
+        let mut req = request(Method::POST, "https://example.amazonaws.com/_search");
+        req.body = Some(AsyncBody::from("{}"));
+        sign(&AwsSigV4::new("us-east-1").service("aoss"), &mut req);
+
+        assert_eq!(
+            "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a",
+            req.headers[X_AMZ_CONTENT_SHA256]
+        );
+    }
+
+    #[test]
+    fn canonical_uri_is_double_encoded() {
+        assert_eq!(
+            "/my-index/_doc/a%2520b",
+            canonical_uri("/my-index/_doc/a%20b")
+        );
+        assert_eq!("/", canonical_uri(""));
+    }
+
+    #[test]
+    fn read_profile_credentials() {
+        let file = "
+[default]
+aws_access_key_id = default-id
+aws_secret_access_key = default-secret
+
+[other]
+aws_access_key_id=other-id
+aws_secret_access_key=other-secret
+aws_session_token=other-token
+";
+
+        assert_eq!(
+            Some(AwsCredentials::new("default-id", "default-secret")),
+            profile_credentials(file, "default")
+        );
+        assert_eq!(
+            Some(AwsCredentials::new("other-id", "other-secret").session_token("other-token")),
+            profile_credentials(file, "other")
+        );
+        assert_eq!(None, profile_credentials(file, "missing"));
+    }
+
+    /** Serve responses to successive connections on a local port, returning the requests that were received. */
+    fn serve(responses: Vec<String>) -> (String, Arc<Mutex<Vec<String>>>) {
+        use std::{
+            io::{
+                Read,
+                Write,
+            },
+            net::TcpListener,
+            thread,
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let received = requests.clone();
+        thread::spawn(move || {
+            for res in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+
+                let mut req = Vec::new();
+                let mut buf = [0; 1024];
+                while !req.ends_with(b"\r\n\r\n") {
+                    let read = stream.read(&mut buf).unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    req.extend_from_slice(&buf[..read]);
+                }
+
+                received
+                    .lock()
+                    .unwrap()
+                    .push(String::from_utf8(req).unwrap());
+                stream.write_all(res.as_bytes()).unwrap();
+            }
+        });
+
+        (endpoint, requests)
+    }
+
+    fn ok(body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    }
+
+    /** Call a credentials provider from a thread that can block within a runtime, like the client does. */
+    fn blocking<T>(f: impl FnOnce() -> T) -> T {
+        use tokio::runtime::Builder;
+
+        let runtime = Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let _runtime = runtime.enter();
+
+        f()
+    }
+
+    const METADATA_CREDENTIALS: &str = r#"{
+        "Code": "Success",
+        "AccessKeyId": "ASIAEXAMPLE",
+        "SecretAccessKey": "secret",
+        "Token": "token",
+        "Expiration": "2015-08-30T18:36:00Z"
+    }"#;
+
+    #[test]
+    fn cache_reuses_credentials_until_refresh() {
+        let cache = CredentialsCache::default();
+        let expires = now() + ChronoDuration::hours(1);
+
+        let fetched = cache.get(now(), || Ok((credentials(), expires))).unwrap();
+        assert_eq!(credentials(), fetched);
+
+        let cached = cache
+            .get(now() + ChronoDuration::minutes(50), || {
+                panic!("cached credentials should be used")
+            })
+            .unwrap();
+        assert_eq!(credentials(), cached);
+
+        let refreshed = cache
+            .get(now() + ChronoDuration::minutes(56), || {
+                Ok((credentials().session_token("new"), expires))
+            })
+            .unwrap();
+        assert_eq!(credentials().session_token("new"), refreshed);
+    }
+
+    #[test]
+    fn cache_keeps_unexpired_credentials_if_refresh_fails() {
+        let cache = CredentialsCache::default();
+        let expires = now() + ChronoDuration::hours(1);
+
+        cache.get(now(), || Ok((credentials(), expires))).unwrap();
+
+        let cached = cache
+            .get(now() + ChronoDuration::minutes(56), || Err("failed".into()))
+            .unwrap();
+        assert_eq!(credentials(), cached);
+
+        assert!(cache
+            .get(now() + ChronoDuration::minutes(61), || Err("failed".into()))
+            .is_err());
+    }
+
+    #[test]
+    fn cache_backs_off_after_failed_fetch() {
+        let cache = CredentialsCache::default();
+
+        assert!(cache.get(now(), || Err("failed".into())).is_err());
+
+        let err = cache
+            .get(now() + ChronoDuration::seconds(10), || {
+                panic!("credentials shouldn't be fetched again yet")
+            })
+            .unwrap_err();
+        assert_eq!("failed", err.to_string());
+
+        let fetched = cache
+            .get(now() + ChronoDuration::seconds(31), || {
+                Ok((credentials(), now() + ChronoDuration::hours(1)))
+            })
+            .unwrap();
+        assert_eq!(credentials(), fetched);
+    }
+
+    #[test]
+    fn read_metadata_credentials() {
+        let (credentials, expires) = metadata_credentials(METADATA_CREDENTIALS).unwrap();
+
+        assert_eq!(
+            AwsCredentials::new("ASIAEXAMPLE", "secret").session_token("token"),
+            credentials
+        );
+        assert_eq!(now() + ChronoDuration::hours(6), expires);
+    }
+
+    #[test]
+    fn fetch_container_credentials() {
+        let (endpoint, requests) = serve(vec![ok(METADATA_CREDENTIALS)]);
+
+        let (credentials, _) = blocking(|| {
+            container_credentials(
+                &format!("{}/v2/credentials", endpoint),
+                Some("auth".to_owned()),
+            )
+        })
+        .unwrap();
+
+        assert_eq!("ASIAEXAMPLE", credentials.access_key_id);
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("GET /v2/credentials HTTP/1.1\r\n"));
+        assert!(requests[0].contains("authorization: auth\r\n"));
+    }
+
+    #[test]
+    fn fetch_instance_metadata_credentials() {
+        let (endpoint, requests) = serve(vec![
+            ok("imds-token"),
+            ok("my-role\n"),
+            ok(METADATA_CREDENTIALS),
+        ]);
+
+        let provider = InstanceMetadataCredentialsProvider::new().endpoint(endpoint);
+
+        assert_eq!(
+            AwsCredentials::new("ASIAEXAMPLE", "secret").session_token("token"),
+            blocking(|| provider.credentials()).unwrap()
+        );
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("PUT /latest/api/token HTTP/1.1\r\n"));
+        assert!(requests[0].contains("x-aws-ec2-metadata-token-ttl-seconds: 21600\r\n"));
+        assert!(requests[2]
+            .starts_with("GET /latest/meta-data/iam/security-credentials/my-role HTTP/1.1\r\n"));
+        assert!(requests[2].contains("x-aws-ec2-metadata-token: imds-token\r\n"));
+    }
+
+    #[test]
+    fn failed_metadata_request_is_err() {
+        let (endpoint, _) = serve(vec![
+            "HTTP/1.1 404 Not Found\r\nConnection: close\r\nContent-Length: 0\r\n\r\n".to_owned(),
+        ]);
+
+        let provider = ContainerCredentialsProvider::new().uri(endpoint);
+
+        assert!(blocking(|| provider.credentials()).is_err());
+    }
+
+    #[test]
+    fn debug_redacts_secrets() {
+        let debug = format!("{:?}", credentials().session_token("token"));
+
+        assert!(debug.contains("AKIDEXAMPLE"));
+        assert!(!debug.contains("EXAMPLEKEY"));
+        assert!(!debug.contains("token\""));
+    }
+}
@@ -0,0 +1,173 @@
+/*!
+Connect to an [Elastic Cloud][docs-cloud] deployment using its cloud id.
+
+A cloud id is a compact representation of the endpoints of a deployment, shown in the Elastic Cloud console.
+It contains a deployment name followed by the base64 encoded domain and ids of the Elasticsearch and Kibana instances, like `name:base64(domain:port$es_id$kibana_id)`.
+
+Use the `cloud_id` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder] to send requests to a deployment.
+Elastic Cloud deployments require authentication, so a cloud id is usually combined with credentials.
+
+Requests to Elastic Cloud go through a proxy that load balances between nodes, so sniffing nodes isn't needed.
+
+# Examples
+
+Connect to a deployment using a cloud id and an API key:
+
+```
+# use elastic::prelude::*;
+let builder = SyncClientBuilder::new()
+    .cloud_id("my-deployment:dXMtZWFzdC0xLmF3cy5mb3VuZC5pbyRjZWM2ZjI2MWE3NGJmMjRjZTMzYmI4ODExYjg0Mjk0ZiRjNmMyY2E2ZDA0MjI0OWFmMGNjN2Q3YTllOTYyNTc0Mw==")
+    .auth(Credentials::api_key("VuaCfGcBCdbkQm-e5aOx", "ui2lp2axTNmsyakw9tvNnw"));
+```
+
+[docs-cloud]: https://www.elastic.co/guide/en/cloud/current/ec-cloud-id.html
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+use base64;
+use std::str::{
+    self,
+    FromStr,
+};
+
+use client::sender::NodeAddress;
+use error::{
+    self,
+    Error,
+};
+
+const DEFAULT_PORT: u16 = 443;
+
+/** A decoded Elastic Cloud id. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct CloudId {
+    name: String,
+    elasticsearch: NodeAddress,
+    kibana: Option<NodeAddress>,
+}
+
+impl CloudId {
+    /**
+    Decode a cloud id.
+
+    This will fail if the id isn't valid base64, or doesn't contain a domain and Elasticsearch instance id.
+    */
+    pub fn parse(cloud_id: &str) -> Result<Self, Error> {
+        let (name, encoded) = match cloud_id.find(':') {
+            Some(i) => (&cloud_id[..i], &cloud_id[i + 1..]),
+            None => ("", cloud_id),
+        };
+
+        let decoded = base64::decode(encoded).map_err(error::build)?;
+        let decoded = str::from_utf8(&decoded).map_err(error::build)?;
+
+        let mut parts = decoded.split('$');
+
+        let host = parts.next().unwrap_or("").trim_end_matches('/');
+        let es_id = parts.next().unwrap_or("");
+        let kibana_id = parts.next().unwrap_or("");
+
+        if host.is_empty() || es_id.is_empty() {
+            Err(error::build(error::message(
+                "the cloud id doesn't contain a domain and Elasticsearch instance id",
+            )))?
+        }
+
+        let (domain, port) = match host.rfind(':') {
+            Some(i) => {
+                let port = host[i + 1..].parse::<u16>().map_err(error::build)?;
+                (&host[..i], port)
+            }
+            None => (host, DEFAULT_PORT),
+        };
+
+        let address = |id: &str| NodeAddress::from(format!("https://{}.{}:{}", id, domain, port));
+
+        Ok(CloudId {
+            name: name.to_owned(),
+            elasticsearch: address(es_id),
+            kibana: if kibana_id.is_empty() {
+                None
+            } else {
+                Some(address(kibana_id))
+            },
+        })
+    }
+
+    /** The name of the deployment. */
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /** The address of the Elasticsearch instance. */
+    pub fn elasticsearch(&self) -> &NodeAddress {
+        &self.elasticsearch
+    }
+
+    /** The address of the Kibana instance, if there is one. */
+    pub fn kibana(&self) -> Option<&NodeAddress> {
+        self.kibana.as_ref()
+    }
+}
+
+impl FromStr for CloudId {
+    type Err = Error;
+
+    fn from_str(cloud_id: &str) -> Result<Self, Error> {
+        CloudId::parse(cloud_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(decoded: &str) -> String {
+        format!("my-deployment:{}", base64::encode(decoded))
+    }
+
+    #[test]
+    fn parse_cloud_id() {
+        let cloud_id = CloudId::parse("my-deployment:dXMtZWFzdC0xLmF3cy5mb3VuZC5pbyRjZWM2ZjI2MWE3NGJmMjRjZTMzYmI4ODExYjg0Mjk0ZiRjNmMyY2E2ZDA0MjI0OWFmMGNjN2Q3YTllOTYyNTc0Mw==").unwrap();
+
+        assert_eq!("my-deployment", cloud_id.name());
+        assert_eq!(
+            "https://cec6f261a74bf24ce33bb8811b84294f.us-east-1.aws.found.io:443",
+            cloud_id.elasticsearch().as_ref()
+        );
+        assert_eq!(
+            Some("https://c6c2ca6d042249af0cc7d7a9e9625743.us-east-1.aws.found.io:443"),
+            cloud_id.kibana().map(|kibana| kibana.as_ref())
+        );
+    }
+
+    #[test]
+    fn parse_cloud_id_with_port() {
+        let cloud_id = CloudId::parse(&encode("example.com:9243$es$kibana")).unwrap();
+
+        assert_eq!(
+            "https://es.example.com:9243",
+            cloud_id.elasticsearch().as_ref()
+        );
+    }
+
+    #[test]
+    fn parse_cloud_id_without_name() {
+        let cloud_id = CloudId::parse(&base64::encode("example.com$es")).unwrap();
+
+        assert_eq!("", cloud_id.name());
+        assert_eq!(
+            "https://es.example.com:443",
+            cloud_id.elasticsearch().as_ref()
+        );
+        assert_eq!(None, cloud_id.kibana());
+    }
+
+    #[test]
+    fn parse_invalid_cloud_id() {
+        assert!(CloudId::parse("my-deployment:not base64").is_err());
+        assert!(CloudId::parse(&encode("example.com")).is_err());
+        assert!(CloudId::parse(&encode("example.com:port$es")).is_err());
+    }
+}
@@ -0,0 +1,251 @@
+/*!
+Check the version and distribution of the cluster a client sends requests to.
+
+A client can ping the cluster before it sends its first request by calling `check_cluster` on a [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+The version and distribution reported by the node are recorded and can be read from the client using `cluster_info`.
+If the ping fails, or the node isn't running a supported distribution, then the request fails and the next request pings the cluster again.
+Requests sent before the first ping has completed also ping the cluster.
+
+The cluster info can be used to adapt requests to the cluster, like leaving document types out of urls for clusters that don't expect them.
+
+# Examples
+
+Check the cluster before sending a request:
+
+```no_run
+# extern crate elastic;
+# use elastic::prelude::*;
+# fn main() { run().unwrap() }
+# fn run() -> Result<(), Box<::std::error::Error>> {
+let client = SyncClientBuilder::new()
+    .check_cluster(true)
+    .build()?;
+
+client.ping().send()?;
+
+if let Some(info) = client.cluster_info() {
+    println!("connected to {} {}", info.distribution(), info.version());
+}
+# Ok(())
+# }
+```
+
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+use std::{
+    fmt,
+    sync::{
+        Arc,
+        RwLock,
+    },
+};
+
+use client::responses::PingResponse;
+use error::{
+    self,
+    Error,
+};
+
+/** The distribution a cluster is running. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distribution {
+    /** Elasticsearch. */
+    Elasticsearch,
+    /** OpenSearch. */
+    OpenSearch,
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Distribution::Elasticsearch => f.write_str("Elasticsearch"),
+            Distribution::OpenSearch => f.write_str("OpenSearch"),
+        }
+    }
+}
+
+/** The version and distribution of a cluster. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusterInfo {
+    cluster_name: String,
+    version: String,
+    distribution: Distribution,
+}
+
+impl ClusterInfo {
+    /** Get the cluster info from a ping response, or fail if the cluster isn't supported. */
+    pub(crate) fn from_ping(ping: &PingResponse) -> Result<Self, Error> {
+        let distribution = match ping.version().distribution() {
+            None | Some("elasticsearch") => Distribution::Elasticsearch,
+            Some("opensearch") => Distribution::OpenSearch,
+            Some(distribution) => Err(error::request(error::message(format!(
+                "the cluster is running an unsupported distribution: '{}'",
+                distribution
+            ))))?,
+        };
+
+        Ok(ClusterInfo {
+            cluster_name: ping.cluster_name().to_owned(),
+            version: ping.version().number().to_owned(),
+            distribution,
+        })
+    }
+
+    /** The name of the cluster. */
+    pub fn cluster_name(&self) -> &str {
+        &self.cluster_name
+    }
+
+    /** The version number of the cluster, like `7.10.2`. */
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /** The major version of the cluster, if the version number can be parsed. */
+    pub fn major_version(&self) -> Option<u32> {
+        self.version.split('.').next()?.parse().ok()
+    }
+
+    /** The distribution the cluster is running. */
+    pub fn distribution(&self) -> Distribution {
+        self.distribution
+    }
+
+    /**
+    Whether the cluster expects urls without document types.
+
+    Document types are deprecated in Elasticsearch 7 and removed in later versions, and OpenSearch doesn't support them.
+    */
+    pub fn typeless(&self) -> bool {
+        match self.distribution {
+            Distribution::OpenSearch => true,
+            Distribution::Elasticsearch => self.major_version().is_some_and(|major| major >= 7),
+        }
+    }
+}
+
+/**
+The cluster info recorded by a client.
+
+Clones share the same recorded info.
+*/
+#[derive(Clone)]
+pub(crate) struct ClusterCheck {
+    enabled: bool,
+    info: Arc<RwLock<Option<ClusterInfo>>>,
+}
+
+impl ClusterCheck {
+    pub(crate) fn new(enabled: bool) -> Self {
+        ClusterCheck {
+            enabled,
+            info: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /** Get a handle that shares the recorded info but never pings the cluster. */
+    pub(crate) fn unchecked(&self) -> Self {
+        ClusterCheck {
+            enabled: false,
+            info: self.info.clone(),
+        }
+    }
+
+    /** Whether the cluster needs to be pinged before sending a request. */
+    pub(crate) fn should_ping(&self) -> bool {
+        self.enabled && self.info.read().expect("lock poisoned").is_none()
+    }
+
+    /** Get the recorded cluster info. */
+    pub(crate) fn info(&self) -> Option<ClusterInfo> {
+        self.info.read().expect("lock poisoned").clone()
+    }
+
+    /** Record the cluster info from a ping response. */
+    pub(crate) fn record(&self, ping: &PingResponse) -> Result<(), Error> {
+        let info = ClusterInfo::from_ping(ping)?;
+
+        debug!(
+            "Elasticsearch Cluster: connected to {} {}",
+            info.distribution, info.version
+        );
+
+        *self.info.write().expect("lock poisoned") = Some(info);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use super::*;
+
+    fn ping(version: serde_json::Value) -> PingResponse {
+        serde_json::from_value(json!({
+            "name": "node",
+            "cluster_name": "cluster",
+            "tagline": "You Know, for Search",
+            "version": version
+        }))
+        .unwrap()
+    }
+
+    fn version(number: &str) -> serde_json::Value {
+        json!({
+            "number": number,
+            "build_hash": "abc",
+            "build_date": "2020-01-01T00:00:00Z",
+            "build_snapshot": false,
+            "lucene_version": "8.0.0"
+        })
+    }
+
+    #[test]
+    fn elasticsearch_from_ping() {
+        let info = ClusterInfo::from_ping(&ping(version("6.8.0"))).unwrap();
+
+        assert_eq!(Distribution::Elasticsearch, info.distribution());
+        assert_eq!(Some(6), info.major_version());
+        assert!(!info.typeless());
+    }
+
+    #[test]
+    fn opensearch_from_ping() {
+        let mut version = version("1.3.0");
+        version["distribution"] = json!("opensearch");
+
+        let info = ClusterInfo::from_ping(&ping(version)).unwrap();
+
+        assert_eq!(Distribution::OpenSearch, info.distribution());
+        assert!(info.typeless());
+    }
+
+    #[test]
+    fn unsupported_distribution_fails() {
+        let mut version = version("1.0.0");
+        version["distribution"] = json!("something-else");
+
+        assert!(ClusterInfo::from_ping(&ping(version)).is_err());
+    }
+
+    #[test]
+    fn unchecked_shares_recorded_info() {
+        let check = ClusterCheck::new(true);
+        let unchecked = check.unchecked();
+
+        assert!(check.should_ping());
+        assert!(!unchecked.should_ping());
+
+        unchecked.record(&ping(version("7.10.2"))).unwrap();
+
+        assert!(!check.should_ping());
+        assert_eq!(
+            Some("7.10.2"),
+            check.info().as_ref().map(ClusterInfo::version)
+        );
+    }
+}
@@ -0,0 +1,238 @@
+/*!
+Compress the bodies of requests sent to, and responses received from, Elasticsearch nodes.
+
+# Requests
+
+Request bodies are sent uncompressed by default.
+A [`RequestCompression`][RequestCompression] policy can be attached to a client using the `compress_requests` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+Bodies that are at least as large as the policy's minimum size are then gzipped and sent with a `Content-Encoding: gzip` header.
+Compressing bodies uses more CPU on both the client and the node, but can dramatically reduce the bandwidth used by requests with large text bodies, like bulk requests.
+
+Only bodies that are buffered in memory are compressed.
+Bodies that are read from a file or other reader, or requests that already have a `Content-Encoding` header, are sent as-is.
+The body is compressed before the `pre_send` function is called, so it sees the bytes that will be sent to the node.
+
+# Examples
+
+Compress request bodies that are at least 4KiB:
+
+```
+# use elastic::prelude::*;
+# use elastic::client::sender::compression::RequestCompression;
+let builder = SyncClientBuilder::new()
+    .compress_requests(RequestCompression::new().min_size(4 * 1024));
+```
+
+# Responses
+
+Clients ask nodes for gzipped responses by sending an `Accept-Encoding: gzip` header, and transparently decompress responses with a `Content-Encoding: gzip` header.
+This can make large responses, like search results, much faster to transfer over slow networks.
+Nodes only compress responses when `http.compression` is enabled, which is the default.
+
+Decompressing responses can be disabled using the `decompress_responses` method on the builders.
+An `Accept-Encoding` header set on an individual request using its `params` is sent instead of the default one.
+
+```
+# use elastic::prelude::*;
+let builder = SyncClientBuilder::new()
+    .decompress_responses(false);
+```
+
+[RequestCompression]: struct.RequestCompression.html
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+use flate2::{
+    write::GzEncoder,
+    Compression,
+};
+use std::io::Write;
+
+use error::{
+    self,
+    Error,
+};
+use http::{
+    header::{
+        HeaderValue,
+        CONTENT_ENCODING,
+    },
+    BufferedBody,
+    HttpRequest,
+};
+
+const DEFAULT_MIN_SIZE: usize = 1024;
+const DEFAULT_LEVEL: u32 = 6;
+const MAX_LEVEL: u32 = 9;
+
+/** A policy for gzipping request bodies. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RequestCompression {
+    min_size: usize,
+    level: u32,
+}
+
+impl Default for RequestCompression {
+    fn default() -> Self {
+        RequestCompression {
+            min_size: DEFAULT_MIN_SIZE,
+            level: DEFAULT_LEVEL,
+        }
+    }
+}
+
+impl RequestCompression {
+    /** Compress bodies that are at least 1KiB using the default compression level. */
+    pub fn new() -> Self {
+        RequestCompression::default()
+    }
+
+    /**
+    Set the minimum size of a body in bytes before it's compressed.
+
+    Small bodies don't benefit much from compression.
+    The default minimum size is 1KiB.
+    */
+    pub fn min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /**
+    Set the gzip compression level, between `0` and `9`.
+
+    Higher levels produce smaller bodies but take longer to compress.
+    The default level is `6`, and levels above `9` are treated as `9`.
+    */
+    pub fn level(mut self, level: u32) -> Self {
+        self.level = level.min(MAX_LEVEL);
+        self
+    }
+
+    /** Compress the body of a request if it's large enough. */
+    pub(crate) fn compress<TBody>(&self, req: &mut HttpRequest<TBody>) -> Result<(), Error>
+    where
+        TBody: BufferedBody,
+    {
+        if req.headers.contains_key(CONTENT_ENCODING) {
+            return Ok(());
+        }
+
+        let compressed = match req.body.as_ref().and_then(BufferedBody::buffered) {
+            Some(body) if body.len() >= self.min_size => self.gzip(body).map_err(error::request)?,
+            _ => return Ok(()),
+        };
+
+        req.body = Some(compressed.into());
+        req.headers_mut()
+            .insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+
+        Ok(())
+    }
+
+    fn gzip(&self, body: &[u8]) -> Result<Vec<u8>, ::std::io::Error> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::new(self.level));
+        encoder.write_all(body)?;
+
+        encoder.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use flate2::read::GzDecoder;
+    use std::{
+        fs::File,
+        io::Read,
+    };
+
+    use super::*;
+    use http::{
+        header::HeaderMap,
+        AsyncBody,
+        AsyncHttpRequest,
+        Method,
+        SyncBody,
+        SyncHttpRequest,
+        Url,
+    };
+
+    fn request<TBody>(body: TBody) -> HttpRequest<TBody> {
+        HttpRequest {
+            url: Url::parse("http://localhost:9200/_bulk").unwrap(),
+            method: Method::POST,
+            headers: Default::default(),
+            body: Some(body),
+        }
+    }
+
+    fn gunzip(body: &[u8]) -> String {
+        let mut decoded = String::new();
+        GzDecoder::new(body).read_to_string(&mut decoded).unwrap();
+
+        decoded
+    }
+
+    #[test]
+    fn compress_large_body() {
+        let body = "a".repeat(2048);
+        let mut req: AsyncHttpRequest = request(AsyncBody::from(body.clone()));
+
+        RequestCompression::new().compress(&mut req).unwrap();
+
+        assert_eq!("gzip", req.headers[CONTENT_ENCODING]);
+
+        let compressed = req.body.as_ref().and_then(|body| body.buffered()).unwrap();
+        assert!(compressed.len() < body.len());
+        assert_eq!(body, gunzip(compressed));
+    }
+
+    #[test]
+    fn small_body_is_not_compressed() {
+        let mut req: AsyncHttpRequest = request(AsyncBody::from("small"));
+
+        RequestCompression::new().compress(&mut req).unwrap();
+
+        assert!(!req.headers.contains_key(CONTENT_ENCODING));
+        assert_eq!(
+            Some(&b"small"[..]),
+            req.body.as_ref().and_then(|body| body.buffered())
+        );
+    }
+
+    #[test]
+    fn encoded_body_is_not_compressed() {
+        let mut req: AsyncHttpRequest = request(AsyncBody::from("a".repeat(2048)));
+        req.headers = {
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_ENCODING, HeaderValue::from_static("identity"));
+            headers.into()
+        };
+
+        RequestCompression::new().compress(&mut req).unwrap();
+
+        assert_eq!("identity", req.headers[CONTENT_ENCODING]);
+    }
+
+    #[test]
+    fn unbuffered_body_is_not_compressed() {
+        let file = File::open("Cargo.toml").unwrap();
+        let mut req: SyncHttpRequest = request(SyncBody::from(file));
+
+        RequestCompression::new()
+            .min_size(0)
+            .compress(&mut req)
+            .unwrap();
+
+        assert!(!req.headers.contains_key(CONTENT_ENCODING));
+    }
+
+    #[test]
+    fn level_is_clamped() {
+        assert_eq!(
+            RequestCompression::new().level(9),
+            RequestCompression::new().level(20)
+        );
+    }
+}
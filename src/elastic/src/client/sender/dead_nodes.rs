@@ -0,0 +1,336 @@
+/*!
+Temporarily remove failing nodes from rotation.
+
+A node is marked dead when requests to it fail to connect, or receive a `502`, `503` or `504` response.
+Dead nodes are skipped by the client's load balancing strategy until their resurrection timeout passes.
+Once a dead node's timeout has passed it's put back into rotation, and the next request sent to it decides whether it's still dead.
+The timeout doubles each time a node fails again after being resurrected, up to a maximum.
+A successful response from a node marks it alive again.
+
+If every node is dead then requests are sent to the node that will be resurrected soonest, so requests are never rejected without being sent.
+
+Dead node detection is disabled by default.
+It can be enabled using the `dead_nodes` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+
+# Examples
+
+Mark a node dead after 3 consecutive failures, and resurrect it after 30 seconds:
+
+```
+# use std::time::Duration;
+# use elastic::prelude::*;
+# use elastic::client::sender::dead_nodes::DeadNodes;
+let builder = SyncClientBuilder::new()
+    .static_nodes(vec!["http://a:9200", "http://b:9200"])
+    .dead_nodes(DeadNodes::new()
+        .failure_threshold(3)
+        .resurrect_timeout(Duration::from_secs(30)));
+```
+
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+use std::{
+    cmp,
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use client::sender::{
+    observer::RequestOutcome,
+    static_nodes::{
+        SharedStrategy,
+        Strategy,
+        StrategyError,
+    },
+    NodeAddress,
+};
+
+/**
+A policy for detecting dead nodes.
+
+By default, a policy marks a node dead after a single failure and resurrects it after 60 seconds.
+The resurrection timeout is capped at 30 minutes.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeadNodes {
+    failure_threshold: u32,
+    resurrect_timeout: Duration,
+    max_resurrect_timeout: Duration,
+}
+
+impl DeadNodes {
+    /** Create a dead node policy with the default settings. */
+    pub fn new() -> Self {
+        DeadNodes {
+            failure_threshold: 1,
+            resurrect_timeout: Duration::from_secs(60),
+            max_resurrect_timeout: Duration::from_secs(30 * 60),
+        }
+    }
+
+    /**
+    Specify the number of consecutive failures before a node is marked dead.
+
+    A value of `0` is treated as `1`.
+    */
+    pub fn failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = cmp::max(failure_threshold, 1);
+        self
+    }
+
+    /**
+    Specify how long a node is dead for before it's put back into rotation.
+
+    The timeout doubles each time the node fails again after being resurrected.
+    */
+    pub fn resurrect_timeout(mut self, resurrect_timeout: Duration) -> Self {
+        self.resurrect_timeout = resurrect_timeout;
+        self
+    }
+
+    /** Specify the maximum time a node is dead for before it's put back into rotation. */
+    pub fn max_resurrect_timeout(mut self, max_resurrect_timeout: Duration) -> Self {
+        self.max_resurrect_timeout = max_resurrect_timeout;
+        self
+    }
+
+    /** Wrap a load balancing strategy so it skips dead nodes. */
+    pub(crate) fn wrap(self, strategy: SharedStrategy) -> SharedStrategy {
+        Arc::new(DeadNodesStrategy {
+            policy: self,
+            strategy,
+            nodes: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /** Get the timeout for a node that has failed a number of times past the threshold. */
+    fn timeout(&self, dead_count: u32) -> Duration {
+        let exponent = cmp::min(dead_count.saturating_sub(1), 31);
+
+        self.resurrect_timeout
+            .checked_mul(1 << exponent)
+            .map(|timeout| cmp::min(timeout, self.max_resurrect_timeout))
+            .unwrap_or(self.max_resurrect_timeout)
+    }
+}
+
+impl Default for DeadNodes {
+    fn default() -> Self {
+        DeadNodes::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct NodeState {
+    failures: u32,
+    dead_until: Option<Instant>,
+}
+
+/** A strategy that skips dead nodes before delegating to another strategy. */
+struct DeadNodesStrategy {
+    policy: DeadNodes,
+    strategy: SharedStrategy,
+    nodes: Arc<Mutex<HashMap<NodeAddress, NodeState>>>,
+}
+
+impl Strategy for DeadNodesStrategy {
+    fn try_next(&self, nodes: &[NodeAddress]) -> Result<NodeAddress, StrategyError> {
+        let alive = {
+            let states = self.nodes.lock().expect("lock poisoned");
+
+            if states.values().all(|state| state.dead_until.is_none()) {
+                None
+            } else {
+                let now = Instant::now();
+                let is_alive = |node: &NodeAddress| match states.get(node) {
+                    Some(&NodeState {
+                        dead_until: Some(dead_until),
+                        ..
+                    }) => dead_until <= now,
+                    _ => true,
+                };
+
+                let alive: Vec<_> = nodes
+                    .iter()
+                    .filter(|node| is_alive(node))
+                    .cloned()
+                    .collect();
+
+                if alive.is_empty() {
+                    // Every node is dead, so use the one that will be resurrected soonest
+                    let node = nodes
+                        .iter()
+                        .min_by_key(|node| states.get(*node).and_then(|state| state.dead_until))
+                        .ok_or(StrategyError::Empty)?;
+
+                    return Ok(node.clone());
+                }
+
+                Some(alive)
+            }
+        };
+
+        match alive {
+            Some(alive) => self.strategy.try_next(&alive),
+            None => self.strategy.try_next(nodes),
+        }
+    }
+
+    fn on_send(&self, node: &NodeAddress) {
+        self.strategy.on_send(node)
+    }
+
+    fn on_complete(&self, node: &NodeAddress, outcome: RequestOutcome) {
+        {
+            let mut states = self.nodes.lock().expect("lock poisoned");
+
+            if outcome.is_node_failure() {
+                let state = states.entry(node.clone()).or_insert(NodeState {
+                    failures: 0,
+                    dead_until: None,
+                });
+
+                state.failures = state.failures.saturating_add(1);
+
+                if state.failures >= self.policy.failure_threshold {
+                    let dead_count = state.failures - self.policy.failure_threshold + 1;
+                    let timeout = self.policy.timeout(dead_count);
+
+                    warn!(
+                        "Elasticsearch Node Dead: node: '{}', failures: '{}', resurrect_timeout: '{:?}'",
+                        node.as_ref(),
+                        state.failures,
+                        timeout
+                    );
+
+                    state.dead_until = Some(Instant::now() + timeout);
+                }
+            } else if let Some(NodeState {
+                dead_until: Some(_),
+                ..
+            }) = states.remove(node)
+            {
+                info!("Elasticsearch Node Alive: node: '{}'", node.as_ref());
+            }
+        }
+
+        self.strategy.on_complete(node, outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::sender::{
+        observer::RequestErrorKind,
+        static_nodes::RoundRobin,
+    };
+    use http::StatusCode;
+
+    fn transport_error() -> RequestOutcome {
+        RequestOutcome::Error(RequestErrorKind::Transport)
+    }
+
+    fn success() -> RequestOutcome {
+        RequestOutcome::Response(StatusCode::OK)
+    }
+
+    fn nodes() -> Vec<NodeAddress> {
+        vec!["http://a:9200", "http://b:9200", "http://c:9200"]
+            .into_iter()
+            .map(Into::into)
+            .collect()
+    }
+
+    fn round_robin(policy: DeadNodes) -> SharedStrategy {
+        policy.wrap(Arc::new(RoundRobin::default()))
+    }
+
+    #[test]
+    fn dead_node_is_skipped() {
+        let strategy = round_robin(DeadNodes::new());
+        let nodes = nodes();
+
+        strategy.on_complete(&nodes[1], transport_error());
+
+        for _ in 0..10 {
+            assert_ne!(nodes[1], strategy.try_next(&nodes).unwrap());
+        }
+    }
+
+    #[test]
+    fn dead_node_below_threshold_is_not_skipped() {
+        let strategy = round_robin(DeadNodes::new().failure_threshold(2));
+        let nodes = nodes();
+
+        strategy.on_complete(&nodes[1], transport_error());
+
+        let selected: Vec<_> = (0..3).map(|_| strategy.try_next(&nodes).unwrap()).collect();
+        assert!(selected.contains(&nodes[1]));
+
+        // A success resets the number of consecutive failures
+        strategy.on_complete(&nodes[1], success());
+        strategy.on_complete(&nodes[1], transport_error());
+
+        let selected: Vec<_> = (0..3).map(|_| strategy.try_next(&nodes).unwrap()).collect();
+        assert!(selected.contains(&nodes[1]));
+    }
+
+    #[test]
+    fn dead_node_is_resurrected_after_timeout() {
+        let strategy = round_robin(DeadNodes::new().resurrect_timeout(Duration::from_secs(0)));
+        let nodes = nodes();
+
+        strategy.on_complete(&nodes[1], transport_error());
+
+        let selected: Vec<_> = (0..3).map(|_| strategy.try_next(&nodes).unwrap()).collect();
+        assert!(selected.contains(&nodes[1]));
+    }
+
+    #[test]
+    fn dead_node_is_alive_after_success() {
+        let strategy = round_robin(DeadNodes::new());
+        let nodes = nodes();
+
+        strategy.on_complete(&nodes[1], transport_error());
+        strategy.on_complete(&nodes[1], success());
+
+        let selected: Vec<_> = (0..3).map(|_| strategy.try_next(&nodes).unwrap()).collect();
+        assert!(selected.contains(&nodes[1]));
+    }
+
+    #[test]
+    fn all_dead_nodes_uses_soonest_resurrected() {
+        let strategy = round_robin(DeadNodes::new());
+        let nodes = nodes();
+
+        strategy.on_complete(&nodes[1], transport_error());
+        strategy.on_complete(&nodes[0], transport_error());
+        strategy.on_complete(&nodes[0], transport_error());
+        strategy.on_complete(&nodes[2], transport_error());
+
+        assert_eq!(nodes[1], strategy.try_next(&nodes).unwrap());
+    }
+
+    #[test]
+    fn timeout_is_exponential() {
+        let policy = DeadNodes::new()
+            .resurrect_timeout(Duration::from_secs(10))
+            .max_resurrect_timeout(Duration::from_secs(60));
+
+        assert_eq!(Duration::from_secs(10), policy.timeout(1));
+        assert_eq!(Duration::from_secs(20), policy.timeout(2));
+        assert_eq!(Duration::from_secs(40), policy.timeout(3));
+        assert_eq!(Duration::from_secs(60), policy.timeout(4));
+        assert_eq!(Duration::from_secs(60), policy.timeout(40));
+    }
+}
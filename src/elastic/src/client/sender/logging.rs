@@ -0,0 +1,192 @@
+/*!
+Log requests and correlate them with Elasticsearch.
+
+Every request sent by a [`SyncClient`][SyncClient] or [`AsyncClient`][AsyncClient] is logged using the `log` crate.
+The url of the request is logged at the `info` level once it has been sent, along with the name of the endpoint, like `search` or `bulk`.
+The status and latency of the response are logged at the `info` level once it's received, and errors are logged at the `error` level.
+Records can be consumed by any `log` implementation, or forwarded to a `tracing` subscriber using the `tracing-log` crate.
+
+Each request has a correlation id that's included in every record logged for it.
+The correlation id is also sent to Elasticsearch in the `X-Opaque-Id` header, so a request can be matched with its entries in the slow log or tasks API.
+A new id is generated for each request unless an `X-Opaque-Id` header is supplied, in which case that value is used instead.
+An id can be supplied for every request sent by a client using the `opaque_id` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder],
+so entries in the slow log and tasks API can be attributed to the service that sent them.
+An id supplied for a single request using its `opaque_id` method takes precedence over the client's id.
+The request parameters aren't known until a node has been selected and any middleware has run, so errors in those steps are logged with the generated id.
+
+Requests that take longer than a threshold to complete can be logged at the `warn` level using the `log_slow_requests` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+
+# Examples
+
+Log requests that take longer than a second:
+
+```
+# use std::time::Duration;
+# use elastic::prelude::*;
+let builder = SyncClientBuilder::new()
+    .log_slow_requests(Duration::from_secs(1));
+```
+
+Attribute every request sent by a client to a service:
+
+```
+# use elastic::prelude::*;
+let builder = SyncClientBuilder::new()
+    .opaque_id("my-service");
+```
+
+Supply a correlation id for a request:
+
+```no_run
+# extern crate elastic;
+# use elastic::prelude::*;
+# fn main() { run().unwrap() }
+# fn run() -> Result<(), Box<::std::error::Error>> {
+# let client = SyncClientBuilder::new().build()?;
+let response = client
+    .request(PingRequest::new())
+    .opaque_id("my-service/nightly-report")
+    .send()?;
+# Ok(())
+# }
+```
+
+[SyncClient]: ../type.SyncClient.html
+[AsyncClient]: ../type.AsyncClient.html
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+use std::{
+    fmt,
+    sync::Arc,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+use uuid::Uuid;
+
+use client::sender::RequestParams;
+use http::{
+    header::{
+        HeaderName,
+        HeaderValue,
+    },
+    Url,
+};
+
+/** The name of the header used to send a request's correlation id to Elasticsearch. */
+pub const X_OPAQUE_ID: &'static str = "x-opaque-id";
+
+/** The id included in logs for a request and sent to Elasticsearch in the `X-Opaque-Id` header. */
+#[derive(Debug, Clone)]
+pub(crate) struct CorrelationId(Arc<str>);
+
+impl CorrelationId {
+    /**
+    Get the correlation id for a request.
+
+    If the parameters don't already contain an `X-Opaque-Id` header then the generated id is added to them.
+    */
+    pub(crate) fn resolve(generated: Uuid, params: &mut RequestParams) -> Self {
+        let supplied = params
+            .headers_ref()
+            .get(X_OPAQUE_ID)
+            .and_then(|id| id.to_str().ok())
+            .map(|id| CorrelationId(id.into()));
+
+        supplied.unwrap_or_else(|| {
+            let id = generated.to_string();
+
+            params.insert_header(
+                HeaderName::from_static(X_OPAQUE_ID),
+                HeaderValue::from_str(&id).expect("uuids are valid header values"),
+            );
+
+            CorrelationId(id.into())
+        })
+    }
+}
+
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/**
+Get an `X-Opaque-Id` header value for an id.
+
+Control characters aren't valid in a header, so they're removed from the id.
+*/
+pub(crate) fn opaque_id_header(id: &str) -> HeaderValue {
+    let id: String = id.chars().filter(|c| !c.is_control()).collect();
+
+    HeaderValue::from_str(&id).expect("ids without control characters are valid header values")
+}
+
+/** Log a request at the `warn` level if it took longer than the threshold to complete. */
+pub(crate) fn log_slow_request(
+    threshold: Option<Duration>,
+    correlation_id: &CorrelationId,
+    endpoint: &str,
+    url: &Url,
+    started: Instant,
+) {
+    let elapsed = started.elapsed();
+
+    if let Some(threshold) = threshold {
+        if elapsed > threshold {
+            warn!(
+                "Elasticsearch Slow Request: correlation_id: '{}', endpoint: '{}', url: '{}', elapsed: '{:?}'",
+                correlation_id, endpoint, url, elapsed
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::sender::PreRequestParams;
+
+    fn params() -> RequestParams {
+        RequestParams::from_parts("http://localhost:9200", PreRequestParams::default())
+    }
+
+    #[test]
+    fn generated_id_is_added_to_params() {
+        let generated = Uuid::new_v4();
+        let mut params = params();
+
+        let id = CorrelationId::resolve(generated, &mut params);
+
+        assert_eq!(generated.to_string(), id.to_string());
+        assert_eq!(
+            Some(generated.to_string().as_str()),
+            params
+                .headers_ref()
+                .get(X_OPAQUE_ID)
+                .and_then(|id| id.to_str().ok())
+        );
+    }
+
+    #[test]
+    fn supplied_id_is_used() {
+        let mut params = params().header(
+            HeaderName::from_static(X_OPAQUE_ID),
+            HeaderValue::from_static("my-service"),
+        );
+
+        let id = CorrelationId::resolve(Uuid::new_v4(), &mut params);
+
+        assert_eq!("my-service", id.to_string());
+        assert_eq!(1, params.headers_ref().get_all(X_OPAQUE_ID).iter().count());
+    }
+
+    #[test]
+    fn opaque_id_header_removes_control_characters() {
+        assert_eq!("my-service", opaque_id_header("my-\r\nservice"));
+    }
+}
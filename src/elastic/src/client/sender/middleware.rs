@@ -0,0 +1,333 @@
+/*!
+Inspect and modify the requests sent by a client.
+
+A [`Middleware`][Middleware] is called before every request a client sends, and can add headers or url parameters to it.
+It's also notified once the request has received a response or failed.
+Attach middleware to a client using the `middleware` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+
+Multiple middlewares can be attached to the same client.
+They're called in the order they were attached, and each one sees the changes made by the ones before it.
+If a middleware returns an error then the request isn't sent, and the remaining middlewares aren't called.
+
+Middleware is called once per request, before a node has been connected to, so retries of a request aren't seen by middleware.
+Requests sent while sniffing nodes also go through middleware.
+
+# Examples
+
+Add a custom header to every request:
+
+```
+# extern crate elastic;
+# use std::error::Error;
+# use elastic::prelude::*;
+# use elastic::http::header::{HeaderName, HeaderValue};
+# use elastic::client::sender::middleware::{Middleware, MiddlewareRequest};
+# fn main() {
+struct TenantHeader(HeaderValue);
+
+impl Middleware for TenantHeader {
+    fn on_request(&self, req: &mut MiddlewareRequest) -> Result<(), Box<Error + Send + Sync>> {
+        req.header(HeaderName::from_static("x-tenant"), self.0.clone());
+
+        Ok(())
+    }
+}
+
+let builder = SyncClientBuilder::new()
+    .middleware(TenantHeader(HeaderValue::from_static("tenant-a")));
+# }
+```
+
+[Middleware]: trait.Middleware.html
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+use std::{
+    error::Error as StdError,
+    sync::Arc,
+};
+
+use client::sender::{
+    observer::{
+        Observer,
+        RequestEvent,
+        SharedObserver,
+    },
+    NodeAddress,
+    RequestParams,
+};
+use http::{
+    header::{
+        HeaderMap,
+        HeaderName,
+        HeaderValue,
+    },
+    Method,
+};
+
+/**
+A type that can inspect and modify requests sent by a client.
+
+Both methods have default implementations that do nothing, so a middleware only needs to implement the ones it cares about.
+Middleware is called on the thread that sent or completed the request, so it should avoid blocking.
+*/
+pub trait Middleware: Send + Sync {
+    /**
+    Called before a request is sent.
+
+    Returning an error fails the request without sending it.
+    */
+    fn on_request(&self, _req: &mut MiddlewareRequest) -> Result<(), Box<StdError + Send + Sync>> {
+        Ok(())
+    }
+
+    /** Called when a request has completed, either with a response or an error. */
+    fn on_response(&self, _event: &RequestEvent) {}
+}
+
+impl<TMiddleware> Middleware for Arc<TMiddleware>
+where
+    TMiddleware: Middleware + ?Sized,
+{
+    fn on_request(&self, req: &mut MiddlewareRequest) -> Result<(), Box<StdError + Send + Sync>> {
+        (**self).on_request(req)
+    }
+
+    fn on_response(&self, event: &RequestEvent) {
+        (**self).on_response(event)
+    }
+}
+
+/** A request that's about to be sent. */
+pub struct MiddlewareRequest<'a> {
+    method: &'a Method,
+    path: &'a str,
+    params: &'a mut RequestParams,
+}
+
+impl<'a> MiddlewareRequest<'a> {
+    /** The HTTP method of the request. */
+    pub fn method(&self) -> &Method {
+        self.method
+    }
+
+    /** The path of the endpoint the request will be sent to, like `/_search`. */
+    pub fn path(&self) -> &str {
+        self.path
+    }
+
+    /** The address of the node the request will be sent to. */
+    pub fn node(&self) -> &NodeAddress {
+        self.params.get_node_address()
+    }
+
+    /** The headers that will be sent with the request. */
+    pub fn headers(&self) -> &HeaderMap {
+        self.params.headers_ref()
+    }
+
+    /** Set a request header, replacing any existing value. */
+    pub fn header(&mut self, key: HeaderName, value: HeaderValue) -> &mut Self {
+        self.params.insert_header(key, value);
+        self
+    }
+
+    /** Set a url param value, replacing any existing value. */
+    pub fn url_param(&mut self, key: &'static str, value: impl ToString) -> &mut Self {
+        self.params.insert_url_param(key, value);
+        self
+    }
+}
+
+pub(crate) type SharedMiddleware = Arc<Middleware>;
+
+/** A chain of middleware that's called in order. */
+#[derive(Clone, Default)]
+pub(crate) struct Middlewares {
+    inner: Arc<Vec<SharedMiddleware>>,
+}
+
+impl Middlewares {
+    pub(crate) fn new(middlewares: Vec<SharedMiddleware>) -> Self {
+        Middlewares {
+            inner: Arc::new(middlewares),
+        }
+    }
+
+    /** Call each middleware on a request, stopping at the first error. */
+    pub(crate) fn on_request(
+        &self,
+        method: &Method,
+        path: &str,
+        params: &mut RequestParams,
+    ) -> Result<(), Box<StdError + Send + Sync>> {
+        let mut req = MiddlewareRequest {
+            method,
+            path,
+            params,
+        };
+
+        for middleware in self.inner.iter() {
+            middleware.on_request(&mut req)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+    Combine the middleware with an observer.
+
+    Responses are observed by the middleware after the observer.
+    */
+    pub(crate) fn observer(&self, observer: Option<SharedObserver>) -> Option<SharedObserver> {
+        if self.inner.is_empty() {
+            observer
+        } else {
+            Some(Arc::new(MiddlewareObserver {
+                observer,
+                middlewares: self.clone(),
+            }))
+        }
+    }
+}
+
+struct MiddlewareObserver {
+    observer: Option<SharedObserver>,
+    middlewares: Middlewares,
+}
+
+impl Observer for MiddlewareObserver {
+    fn on_request(&self, event: &RequestEvent) {
+        if let Some(ref observer) = self.observer {
+            observer.on_request(event);
+        }
+
+        for middleware in self.middlewares.inner.iter() {
+            middleware.on_response(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use client::sender::observer::{
+        Observation,
+        RequestOutcome,
+    };
+    use http::StatusCode;
+
+    struct AppendHeader(&'static str);
+
+    impl Middleware for AppendHeader {
+        fn on_request(
+            &self,
+            req: &mut MiddlewareRequest,
+        ) -> Result<(), Box<StdError + Send + Sync>> {
+            let value = match req.headers().get("x-chain") {
+                Some(value) => format!("{},{}", value.to_str()?, self.0),
+                None => self.0.to_owned(),
+            };
+
+            req.header(HeaderName::from_static("x-chain"), value.parse()?);
+
+            Ok(())
+        }
+    }
+
+    struct Fail;
+
+    impl Middleware for Fail {
+        fn on_request(&self, _: &mut MiddlewareRequest) -> Result<(), Box<StdError + Send + Sync>> {
+            Err("failed".into())
+        }
+    }
+
+    #[test]
+    fn middleware_is_called_in_order() {
+        let middlewares = Middlewares::new(vec![
+            Arc::new(AppendHeader("a")),
+            Arc::new(AppendHeader("b")),
+        ]);
+
+        let mut params = RequestParams::default();
+        middlewares
+            .on_request(&Method::GET, "/_search", &mut params)
+            .unwrap();
+
+        assert_eq!("a,b", params.get_headers()["x-chain"]);
+    }
+
+    #[test]
+    fn middleware_can_set_url_params() {
+        struct Pretty;
+
+        impl Middleware for Pretty {
+            fn on_request(
+                &self,
+                req: &mut MiddlewareRequest,
+            ) -> Result<(), Box<StdError + Send + Sync>> {
+                req.url_param("pretty", true);
+
+                Ok(())
+            }
+        }
+
+        let middlewares = Middlewares::new(vec![Arc::new(Pretty)]);
+
+        let mut params = RequestParams::default();
+        middlewares
+            .on_request(&Method::GET, "/_search", &mut params)
+            .unwrap();
+
+        assert_eq!(Some("?pretty=true".to_owned()), params.get_url_qry().1);
+    }
+
+    #[test]
+    fn middleware_error_stops_chain() {
+        let middlewares = Middlewares::new(vec![
+            Arc::new(AppendHeader("a")),
+            Arc::new(Fail),
+            Arc::new(AppendHeader("b")),
+        ]);
+
+        let mut params = RequestParams::default();
+        let res = middlewares.on_request(&Method::GET, "/_search", &mut params);
+
+        assert!(res.is_err());
+        assert_eq!("a", params.get_headers()["x-chain"]);
+    }
+
+    #[test]
+    fn middleware_observes_responses() {
+        struct Statuses(Mutex<Vec<RequestOutcome>>);
+
+        impl Middleware for Statuses {
+            fn on_response(&self, event: &RequestEvent) {
+                self.0.lock().expect("lock poisoned").push(event.outcome());
+            }
+        }
+
+        let statuses = Arc::new(Statuses(Mutex::new(Vec::new())));
+        let middlewares = Middlewares::new(vec![statuses.clone()]);
+
+        let observer = middlewares.observer(None);
+        Observation::start(observer.as_ref(), "search", &Method::GET, "/_search").response(
+            &NodeAddress::from("http://localhost:9200"),
+            StatusCode::NOT_FOUND,
+        );
+
+        assert_eq!(
+            vec![RequestOutcome::Response(StatusCode::NOT_FOUND)],
+            *statuses.0.lock().expect("lock poisoned")
+        );
+    }
+
+    #[test]
+    fn no_middleware_keeps_observer() {
+        assert!(Middlewares::default().observer(None).is_none());
+    }
+}
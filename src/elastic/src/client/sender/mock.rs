@@ -0,0 +1,416 @@
+/*!
+Send requests to canned responses instead of Elasticsearch nodes.
+
+This module is only available when the `testing` feature is enabled.
+
+A [`MockSender`][MockSender] can be attached to a client using the `mock` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+Requests sent by the client are then answered by the mock instead of a node, so code that uses a `SyncClient` or `AsyncClient` can be unit tested without a live cluster.
+Everything else about the client, like middleware, retries and request building, works the same way as it does for a live cluster.
+
+Responses are registered for a http method and url path.
+If more than one response is registered for the same method and path then they're returned in the order they were registered, and the last one is returned for any requests after that.
+Requests that don't match a registered response get a `404` response with an Elasticsearch-style error body.
+A response can also fail with a transport error, like a refused connection, to test how the client handles nodes that are unavailable.
+
+The mock records every request it receives, so tests can check what the client sent.
+Request bodies that are read from a file or other reader aren't recorded.
+
+# Examples
+
+Return a canned response for a search and check the request that was sent:
+
+```
+# extern crate elastic;
+# #[macro_use] extern crate serde_json;
+# use serde_json::Value;
+# use elastic::prelude::*;
+# use elastic::client::sender::mock::{MockSender, MockResponse};
+# use elastic::http::{Method, StatusCode};
+# fn main() { run().unwrap() }
+# fn run() -> Result<(), Box<::std::error::Error>> {
+let mock = MockSender::new();
+mock.respond(
+    Method::POST,
+    "/myindex/_search",
+    MockResponse::new(StatusCode::OK).json(&json!({
+        "took": 1,
+        "timed_out": false,
+        "_shards": { "total": 1, "successful": 1, "failed": 0 },
+        "hits": { "total": 0, "max_score": null, "hits": [] }
+    })),
+);
+
+let client = SyncClientBuilder::new().mock(mock.clone()).build()?;
+
+let response = client
+    .search::<Value>()
+    .index("myindex")
+    .send()?;
+
+assert_eq!(0, response.total());
+
+let requests = mock.requests();
+assert_eq!(1, requests.len());
+assert_eq!("/myindex/_search", requests[0].url().path());
+# Ok(())
+# }
+```
+
+[MockSender]: struct.MockSender.html
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+use futures::stream;
+use serde::Serialize;
+use serde_json;
+use std::{
+    collections::VecDeque,
+    fmt,
+    io,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use http::{
+    header::HeaderMap,
+    AsyncChunk,
+    AsyncHttpResponse,
+    BufferedBody,
+    HttpRequest,
+    Method,
+    StatusCode,
+    Url,
+};
+
+/**
+A set of canned responses for requests.
+
+Clones of a `MockSender` share the same responses and recorded requests.
+*/
+#[derive(Clone, Default)]
+pub struct MockSender {
+    inner: Arc<Mutex<MockSenderInner>>,
+}
+
+#[derive(Default)]
+struct MockSenderInner {
+    responses: Vec<MockEndpoint>,
+    requests: Vec<MockRequest>,
+}
+
+struct MockEndpoint {
+    method: Method,
+    path: String,
+    responses: VecDeque<MockResponse>,
+}
+
+impl MockSender {
+    /** Create a mock without any responses. */
+    pub fn new() -> Self {
+        MockSender::default()
+    }
+
+    /**
+    Respond to requests with the given method and url path.
+
+    The path doesn't include the query string, so `/myindex/_search` matches a request to `/myindex/_search?pretty=true`.
+    */
+    pub fn respond(&self, method: Method, path: impl Into<String>, response: MockResponse) {
+        let path = path.into();
+        let mut inner = self.inner.lock().expect("lock poisoned");
+
+        let existing = inner
+            .responses
+            .iter_mut()
+            .find(|endpoint| endpoint.method == method && endpoint.path == path);
+
+        match existing {
+            Some(endpoint) => endpoint.responses.push_back(response),
+            None => inner.responses.push(MockEndpoint {
+                method,
+                path,
+                responses: vec![response].into(),
+            }),
+        }
+    }
+
+    /** Get the requests received by the mock, in the order they were sent. */
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.inner.lock().expect("lock poisoned").requests.clone()
+    }
+
+    /** Forget the requests received by the mock so far. */
+    pub fn clear_requests(&self) {
+        self.inner.lock().expect("lock poisoned").requests.clear();
+    }
+
+    /** Record a request and get the response for it. */
+    pub(crate) fn send<TBody>(&self, req: &HttpRequest<TBody>) -> Result<AsyncHttpResponse, io::Error>
+    where
+        TBody: BufferedBody,
+    {
+        let mut inner = self.inner.lock().expect("lock poisoned");
+
+        inner.requests.push(MockRequest {
+            method: req.method.clone(),
+            url: req.url.clone(),
+            headers: (*req.headers).clone(),
+            body: req
+                .body
+                .as_ref()
+                .and_then(BufferedBody::buffered)
+                .map(|body| body.to_vec()),
+        });
+
+        let endpoint = inner
+            .responses
+            .iter_mut()
+            .find(|endpoint| endpoint.method == req.method && endpoint.path == req.url.path());
+
+        let response = match endpoint {
+            Some(endpoint) if endpoint.responses.len() > 1 => {
+                endpoint.responses.pop_front().expect("missing response")
+            }
+            Some(endpoint) => endpoint.responses[0].clone(),
+            None => MockResponse::not_found(&req.method, req.url.path()),
+        };
+
+        response.into_async()
+    }
+}
+
+impl fmt::Debug for MockSender {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MockSender").finish()
+    }
+}
+
+/** A canned response returned by a `MockSender`. */
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: StatusCode,
+    body: Vec<u8>,
+    error: Option<io::ErrorKind>,
+}
+
+impl MockResponse {
+    /** Create a response with the given status and an empty body. */
+    pub fn new(status: StatusCode) -> Self {
+        MockResponse {
+            status,
+            body: Vec::new(),
+            error: None,
+        }
+    }
+
+    /**
+    Create a response that fails with a transport error instead of returning a status.
+
+    The client handles the error the same way as a request that couldn't reach its node, like one whose connection was refused or timed out.
+
+    # Examples
+
+    Fail the first attempt to send a request with a refused connection:
+
+    ```
+    # use std::io;
+    # use elastic::client::sender::mock::{MockSender, MockResponse};
+    # use elastic::http::{Method, StatusCode};
+    let mock = MockSender::new();
+    mock.respond(Method::GET, "/", MockResponse::error(io::ErrorKind::ConnectionRefused));
+    mock.respond(Method::GET, "/", MockResponse::new(StatusCode::OK));
+    ```
+    */
+    pub fn error(kind: io::ErrorKind) -> Self {
+        MockResponse {
+            status: StatusCode::default(),
+            body: Vec::new(),
+            error: Some(kind),
+        }
+    }
+
+    /** Set the raw body of the response. */
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /** Set the body of the response to a value serialized as JSON. */
+    pub fn json(mut self, body: &impl Serialize) -> Self {
+        self.body = serde_json::to_vec(body).expect("failed to serialize mock response");
+        self
+    }
+
+    fn not_found(method: &Method, path: &str) -> Self {
+        let reason = format!("no mock response for {} {}", method, path);
+
+        MockResponse::new(StatusCode::NOT_FOUND).json(&json!({
+            "error": {
+                "type": "mock_response_not_found",
+                "reason": reason
+            },
+            "status": 404
+        }))
+    }
+
+    fn into_async(self) -> Result<AsyncHttpResponse, io::Error> {
+        if let Some(kind) = self.error {
+            return Err(io::Error::new(kind, "the mock failed to send the request"));
+        }
+
+        let body = stream::once(Ok(AsyncChunk::from(self.body)));
+
+        Ok(AsyncHttpResponse::new(self.status, body))
+    }
+}
+
+/** A request received by a `MockSender`. */
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    method: Method,
+    url: Url,
+    headers: HeaderMap,
+    body: Option<Vec<u8>>,
+}
+
+impl MockRequest {
+    /** The http method of the request. */
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /** The url the request was sent to. */
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /** The headers sent with the request. */
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /**
+    The body of the request.
+
+    This is `None` if the request didn't have a body, or if the body was read from a file or other reader.
+    */
+    pub fn body(&self) -> Option<&[u8]> {
+        self.body.as_ref().map(|body| &body[..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{
+        Future,
+        Stream,
+    };
+
+    use super::*;
+    use http::{
+        AsyncBody,
+        AsyncHttpRequest,
+    };
+
+    fn request(method: Method, url: &str) -> AsyncHttpRequest {
+        HttpRequest {
+            url: Url::parse(url).unwrap(),
+            method,
+            headers: Default::default(),
+            body: Some(AsyncBody::from("{}")),
+        }
+    }
+
+    #[test]
+    fn respond_matches_method_and_path() {
+        let mock = MockSender::new();
+        mock.respond(
+            Method::GET,
+            "/myindex",
+            MockResponse::new(StatusCode::OK).body("found"),
+        );
+
+        let res = mock
+            .send(&request(
+                Method::GET,
+                "http://localhost:9200/myindex?pretty=true",
+            ))
+            .unwrap();
+        assert_eq!(StatusCode::OK, res.status());
+        assert_eq!(b"found", &res.concat2().wait().unwrap()[..]);
+
+        let res = mock
+            .send(&request(Method::DELETE, "http://localhost:9200/myindex"))
+            .unwrap();
+        assert_eq!(StatusCode::NOT_FOUND, res.status());
+    }
+
+    #[test]
+    fn responses_are_returned_in_order() {
+        let mock = MockSender::new();
+        mock.respond(
+            Method::GET,
+            "/",
+            MockResponse::new(StatusCode::SERVICE_UNAVAILABLE),
+        );
+        mock.respond(Method::GET, "/", MockResponse::new(StatusCode::OK));
+
+        let statuses: Vec<_> = (0..3)
+            .map(|_| {
+                mock.send(&request(Method::GET, "http://localhost:9200/"))
+                    .unwrap()
+                    .status()
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::OK,
+                StatusCode::OK
+            ],
+            statuses
+        );
+    }
+
+    #[test]
+    fn requests_are_recorded() {
+        let mock = MockSender::new();
+
+        mock.send(&request(
+            Method::POST,
+            "http://localhost:9200/myindex/_search",
+        ))
+        .unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(1, requests.len());
+        assert_eq!(Method::POST, *requests[0].method());
+        assert_eq!("/myindex/_search", requests[0].url().path());
+        assert_eq!(Some(&b"{}"[..]), requests[0].body());
+
+        mock.clear_requests();
+        assert!(mock.requests().is_empty());
+    }
+
+    #[test]
+    fn error_responses_fail() {
+        let mock = MockSender::new();
+        mock.respond(
+            Method::GET,
+            "/",
+            MockResponse::error(io::ErrorKind::ConnectionRefused),
+        );
+
+        let res = mock.send(&request(Method::GET, "http://localhost:9200/"));
+
+        match res {
+            Err(e) => assert_eq!(io::ErrorKind::ConnectionRefused, e.kind()),
+            Ok(_) => panic!("expected a transport error"),
+        }
+        assert_eq!(1, mock.requests().len());
+    }
+}
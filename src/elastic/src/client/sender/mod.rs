@@ -5,12 +5,23 @@ This module contains low-level implementation details for the high-level [`Clien
 Most of the types here aren't currently usable directly, but once the low-level API matures they should be extensible.
 Some notable types include:
 
-- `Sender`: a generic trait that can send a http request and return a response
+- `Sender`: a generic trait that can send a http request and return a response. It can be implemented to send requests using a different http client
 - `NextParams`: a generic trait that can fetch a set of parameters to associate with a request
 - `SyncSender`: a synchronous http client
-- `AsyncSender`: an asynchronous http client.
+- `AsyncSender`: an asynchronous http client
+- `Observer`: a generic trait that's notified about every request sent by a client.
+- `Middleware`: a generic trait that can inspect and modify every request sent by a client.
+- `RetryPolicy`: a policy for retrying requests that fail because a node is temporarily unavailable.
+- `RateLimit`: a limit on the number of requests sent per second or in flight at once.
+- `DeadNodes`: a policy for temporarily removing failing nodes from rotation.
+
+Requests are logged with a correlation id that's also sent to Elasticsearch, see the [`logging`][logging-mod] module for more details.
+
+Follow-up requests for a scroll or point-in-time search can be sent to the node that served the search, see the [`affinity`][affinity-mod] module for more details.
 
 [Client]: ../struct.Client.html
+[logging-mod]: logging/index.html
+[affinity-mod]: affinity/index.html
 */
 
 use fluent_builder::{
@@ -18,8 +29,27 @@ use fluent_builder::{
     SharedStatefulFluentBuilder,
 };
 
+pub mod affinity;
+pub mod auth;
+#[cfg(feature = "aws-sigv4")]
+pub mod aws_sigv4;
+pub mod cloud_id;
+pub mod cluster_info;
+pub mod compression;
+pub mod dead_nodes;
+pub mod logging;
+pub mod middleware;
+#[cfg(feature = "testing")]
+pub mod mock;
+pub mod observer;
+pub mod pool;
+pub mod proxy;
+pub mod rate_limit;
+pub mod retry;
+pub mod shutdown;
 pub mod sniffed_nodes;
 pub mod static_nodes;
+pub mod tls;
 
 mod async;
 mod params;
@@ -41,7 +71,12 @@ use self::{
         SniffedNodes,
         SniffedNodesBuilder,
     },
-    static_nodes::StaticNodes,
+    static_nodes::{
+        RoundRobin,
+        SharedStrategy,
+        StaticNodes,
+        Strategy,
+    },
 };
 use client::requests::Endpoint;
 use private;
@@ -77,32 +112,142 @@ pub(crate) enum SendableRequestParams<TParams> {
     Builder {
         params: TParams,
         builder: SharedFluentBuilder<RequestParams>,
+        next: SharedNextParams<TParams>,
+        pin: SharedPinParams,
     },
 }
 
+/**
+Fetch the parameters for another node.
+
+Requests that fail to connect are retried on the next node selected by the client.
+*/
+pub(crate) type SharedNextParams<TParams> = Arc<Fn() -> TParams + Send + Sync>;
+
+/**
+Fetch the parameters for a specific node.
+
+Requests for a scroll or point-in-time search context are sent to the node that created it.
+*/
+pub(crate) type SharedPinParams = Arc<Fn(NodeAddress) -> RequestParams + Send + Sync>;
+
 /**
 Represents a type that can send a request.
 
 You probably don't need to touch this trait directly.
 See the [`Client`][Client] type for making requests.
 
-Even though the `Sender` trait is quite generic, it's not really designed to be implemented externally.
-The request builders expect there to be 2 concrete implementations of `Sender`, namely `SyncSender` and `AsyncSender`.
-The real purpose is to make it possible to share builders so the sync and async APIs don't diverge.
+`elastic` provides 2 implementations of `Sender`, namely [`SyncSender`][SyncSender] and [`AsyncSender`][AsyncSender], which send requests using `reqwest`.
+The `Sender` trait can also be implemented to send requests using a different http client, like `curl` or a client that connects over a unix socket.
+
+# Implementing `Sender`
+
+A synchronous sender should use the same body, response and parameter types as `SyncSender`, and an asynchronous sender should use the same types as `AsyncSender`.
+To send a request, a sender:
+
+1. Resolves the parameters and builds a raw http request using [`SendableRequest.into_http_request`][SendableRequest.into_http_request] or [`SendableRequest.into_http_request_async`][SendableRequest.into_http_request_async].
+1. Sends the raw request using its http client.
+1. Wraps the raw response using [`SyncResponseBuilder::new`][SyncResponseBuilder.new] or [`AsyncResponseBuilder::new`][AsyncResponseBuilder.new].
+
+A client is then created from the sender and a set of nodes using [`Client::from_sender`][Client.from_sender].
+Raw requests can be sent using the [`Client.request`][Client.request] method of the client.
+The other request builders can only be sent by clients that use a `SyncSender` or `AsyncSender`.
+
+Features of the `SyncSender` and `AsyncSender`, like middleware, retries and authentication, aren't applied by custom senders.
+
+# Examples
+
+A sender that sends requests synchronously using a blocking `reqwest` client:
+
+```no_run
+# extern crate elastic;
+# extern crate reqwest;
+# use std::io::Read;
+# use elastic::prelude::*;
+# use elastic::Error;
+# use elastic::client::Client;
+# use elastic::client::requests::Endpoint;
+# use elastic::client::responses::SyncResponseBuilder;
+# use elastic::client::sender::{NodeAddresses, Params, SendableRequest, Sender};
+# use elastic::http::{SyncBody, StatusCode};
+# fn main() { run().unwrap() }
+# fn run() -> Result<(), Box<::std::error::Error>> {
+#[derive(Clone)]
+struct ReqwestSender(reqwest::blocking::Client);
+
+impl Sender for ReqwestSender {
+    type Body = SyncBody;
+    type Response = Result<SyncResponseBuilder, Error>;
+    type Params = Params;
+
+    fn send<TEndpoint, TParams, TBody>(
+        &self,
+        request: SendableRequest<TEndpoint, TParams, TBody>,
+    ) -> Self::Response
+    where
+        TEndpoint: Into<Endpoint<'static, TBody>>,
+        TBody: Into<Self::Body> + Send + 'static,
+        TParams: Into<Self::Params> + Send + 'static,
+    {
+        let (node, mut req) = request.into_http_request()?;
+
+        let mut body = Vec::new();
+        if let Some(req_body) = req.body_mut() {
+            req_body.reader().read_to_end(&mut body).map_err(Error::request)?;
+        }
+
+        let method = reqwest::Method::from_bytes(req.method().as_str().as_bytes())
+            .map_err(Error::request)?;
+
+        let mut builder = self.0.request(method, req.url().as_str());
+        for (name, value) in req.headers() {
+            builder = builder.header(name.as_str(), value.as_bytes());
+        }
+
+        let res = builder.body(body).send().map_err(Error::request)?;
+
+        let status = StatusCode::from_u16(res.status().as_u16()).map_err(Error::request)?;
+
+        Ok(SyncResponseBuilder::new(node, status, res))
+    }
+
+    fn next_params(addresses: &NodeAddresses<Self>) -> Self::Params {
+        addresses.next_sync()
+    }
+}
 
-At some point in the future though this may be made more generic so you could reasonably plug your own `Sender`s in to `elastic`.
+let sender = ReqwestSender(reqwest::blocking::Client::new());
+let nodes = NodeAddresses::static_nodes(vec!["http://localhost:9200"], PreRequestParams::default());
+
+let client = Client::from_sender(sender, nodes);
+
+let ping = client
+    .request(PingRequest::new())
+    .send()?
+    .into_response::<PingResponse>()?;
+# Ok(())
+# }
+```
 
 [Client]: struct.Client.html
+[Client.from_sender]: ../struct.Client.html#method.from_sender
+[Client.request]: ../struct.Client.html#method.request
+[SyncSender]: struct.SyncSender.html
+[AsyncSender]: struct.AsyncSender.html
+[SendableRequest.into_http_request]: struct.SendableRequest.html#method.into_http_request
+[SendableRequest.into_http_request_async]: struct.SendableRequest.html#method.into_http_request_async
+[SyncResponseBuilder.new]: ../responses/struct.SyncResponseBuilder.html#method.new
+[AsyncResponseBuilder.new]: ../responses/struct.AsyncResponseBuilder.html#method.new
 */
-pub trait Sender: private::Sealed + Clone {
-    /* The kind of request body this sender accepts. */
+pub trait Sender: Clone {
+    /** The kind of request body this sender accepts. */
     type Body;
-    /* The kind of response this sender produces. */
+    /** The kind of response this sender produces. */
     type Response;
-    /* The kind of request parameters this sender accepts. */
+    /** The kind of request parameters this sender accepts. */
     type Params;
 
-    /* Send a request. */
+    /** Send a request. */
     fn send<TEndpoint, TParams, TBody>(
         &self,
         request: SendableRequest<TEndpoint, TParams, TBody>,
@@ -111,6 +256,16 @@ pub trait Sender: private::Sealed + Clone {
         TEndpoint: Into<Endpoint<'static, TBody>>,
         TBody: Into<Self::Body> + Send + 'static,
         TParams: Into<Self::Params> + Send + 'static;
+
+    /**
+    Get the parameters for the next request sent to a set of nodes.
+
+    Synchronous senders can use [`NodeAddresses.next_sync`][NodeAddresses.next_sync], and asynchronous senders can use [`NodeAddresses.next_async`][NodeAddresses.next_async].
+
+    [NodeAddresses.next_sync]: struct.NodeAddresses.html#method.next_sync
+    [NodeAddresses.next_async]: struct.NodeAddresses.html#method.next_async
+    */
+    fn next_params(addresses: &NodeAddresses<Self>) -> Self::Params;
 }
 
 /**
@@ -135,7 +290,7 @@ pub trait NextParams: private::Sealed + Clone {
 /**
 A single node address.
 */
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct NodeAddress(Arc<str>);
 
 impl AsRef<str> for NodeAddress {
@@ -159,27 +314,121 @@ A common container for a source of node addresses.
 #[derive(Clone)]
 pub struct NodeAddresses<TSender> {
     inner: NodeAddressesInner<TSender>,
+    defaults: Option<SharedDefaultParams>,
 }
 
+type SharedDefaultParams = Arc<Fn(RequestParams) -> RequestParams + Send + Sync>;
+
 impl<TSender> NodeAddresses<TSender> {
-    fn static_nodes(nodes: StaticNodes) -> Self {
+    /**
+    Send requests to a static set of nodes.
+
+    Requests are load balanced between the nodes using a round-robin strategy.
+    */
+    pub fn static_nodes<I, S>(nodes: I, params: PreRequestParams) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<NodeAddress>,
+    {
+        NodeAddresses::from_static_nodes(StaticNodes::round_robin(nodes, params).shared())
+    }
+
+    /**
+    Send requests to a static set of nodes, load balanced using the given strategy.
+    */
+    pub fn static_nodes_with_strategy<TStrategy>(nodes: StaticNodes<TStrategy>) -> Self
+    where
+        TStrategy: Strategy + 'static,
+    {
+        NodeAddresses::from_static_nodes(nodes.shared())
+    }
+
+    /**
+    Send requests to nodes sniffed from a cluster.
+
+    The given sender is used to send requests to sniff nodes.
+    */
+    pub fn sniffed_nodes(
+        builder: impl Into<SniffedNodesBuilder>,
+        params: PreRequestParams,
+        sender: TSender,
+    ) -> Self {
+        let nodes = builder
+            .into()
+            .build(params, Arc::new(RoundRobin::default()), sender);
+
+        NodeAddresses::from_sniffed_nodes(nodes)
+    }
+
+    fn from_static_nodes(nodes: StaticNodes<SharedStrategy>) -> Self {
         NodeAddresses {
             inner: NodeAddressesInner::Static(nodes),
+            defaults: None,
         }
     }
 
-    fn sniffed_nodes(nodes: SniffedNodes<TSender>) -> Self {
+    fn from_sniffed_nodes(nodes: SniffedNodes<TSender>) -> Self {
         NodeAddresses {
             inner: NodeAddressesInner::Sniffed(nodes),
+            defaults: None,
+        }
+    }
+
+    /**
+    Get a set of request parameters for a specific node instead of selecting one.
+
+    The parameters are otherwise the same as the ones returned by `next`.
+    */
+    pub(crate) fn pin(&self, address: NodeAddress) -> RequestParams {
+        let params = match self.inner {
+            NodeAddressesInner::Static(ref nodes) => nodes.pin(address),
+            NodeAddressesInner::Sniffed(ref sniffer) => sniffer.pin(address),
+        };
+
+        self.apply_defaults(params)
+    }
+
+    /**
+    Layer default parameters over the parameters for each node.
+
+    Defaults are applied in the order they're added.
+    */
+    pub(crate) fn with_defaults(
+        mut self,
+        defaults: impl Fn(RequestParams) -> RequestParams + Send + Sync + 'static,
+    ) -> Self {
+        self.defaults = Some(match self.defaults.take() {
+            Some(previous) => Arc::new(move |params| defaults(previous(params))),
+            None => Arc::new(defaults),
+        });
+
+        self
+    }
+
+    fn apply_defaults(&self, params: RequestParams) -> RequestParams {
+        match self.defaults {
+            Some(ref defaults) => defaults(params),
+            None => params,
         }
     }
 }
 
 impl<TSender> private::Sealed for NodeAddresses<TSender> {}
 
+impl<TSender> NextParams for NodeAddresses<TSender>
+where
+    TSender: Sender,
+{
+    type Params = TSender::Params;
+
+    fn next(&self) -> Self::Params {
+        TSender::next_params(self)
+    }
+}
+
 #[derive(Clone)]
 enum NodeAddressesInner<TSender> {
-    Static(StaticNodes),
+    Static(StaticNodes<SharedStrategy>),
     Sniffed(SniffedNodes<TSender>),
 }
 
@@ -220,19 +469,24 @@ impl Default for NodeAddressesBuilder {
 }
 
 impl NodeAddressesBuilder {
-    fn build<TSender>(self, params: PreRequestParams, sender: TSender) -> NodeAddresses<TSender> {
+    fn build<TSender>(
+        self,
+        params: PreRequestParams,
+        strategy: SharedStrategy,
+        sender: TSender,
+    ) -> NodeAddresses<TSender> {
         match self {
             NodeAddressesBuilder::Static(nodes) => {
-                let nodes = StaticNodes::round_robin(nodes, params);
+                let nodes = StaticNodes::with_strategy(nodes, strategy, params);
 
-                NodeAddresses::static_nodes(nodes)
+                NodeAddresses::from_static_nodes(nodes)
             }
             NodeAddressesBuilder::Sniffed(builder) => {
                 let nodes = builder
                     .into_value(|node| SniffedNodesBuilder::new(node))
-                    .build(params, sender);
+                    .build(params, strategy, sender);
 
-                NodeAddresses::sniffed_nodes(nodes)
+                NodeAddresses::from_sniffed_nodes(nodes)
             }
         }
     }
@@ -0,0 +1,406 @@
+/*!
+Observe the requests sent by a client.
+
+An [`Observer`][Observer] is notified once for every request a client sends, after a response has been received or the request has failed.
+Observers can be used to collect metrics about a client without wrapping every call site.
+Attach an observer to a client using the `observer` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+
+A [`PrometheusObserver`][PrometheusObserver] is provided that collects request counts, error rates, latency histograms and node health in the Prometheus text format.
+
+# Examples
+
+Log the latency of each request:
+
+```
+# use elastic::prelude::*;
+# use elastic::client::sender::observer::RequestEvent;
+let builder = SyncClientBuilder::new()
+    .observer(|event: &RequestEvent| {
+        println!("{} {} took {:?}", event.method(), event.path(), event.elapsed());
+    });
+```
+
+Count the bytes sent to each endpoint, like `search` or `bulk`:
+
+```
+# use elastic::prelude::*;
+# use elastic::client::sender::observer::RequestEvent;
+let builder = SyncClientBuilder::new()
+    .observer(|event: &RequestEvent| {
+        if let Some(body_size) = event.body_size() {
+            println!("sent {} bytes to {}", body_size, event.endpoint());
+        }
+    });
+```
+
+[Observer]: trait.Observer.html
+[PrometheusObserver]: struct.PrometheusObserver.html
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+mod prometheus;
+pub use self::prometheus::*;
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use client::sender::NodeAddress;
+use http::{
+    BufferedBody,
+    HttpRequest,
+    Method,
+    StatusCode,
+};
+
+/**
+A type that's notified about requests sent by a client.
+
+`Observer` is implemented for any `Fn(&RequestEvent)` closure that can be shared between threads.
+Observers are called on the thread that sent or completed the request, so they should avoid blocking.
+*/
+pub trait Observer: Send + Sync {
+    /** Called when a request has completed, either with a response or an error. */
+    fn on_request(&self, event: &RequestEvent);
+}
+
+impl<F> Observer for F
+where
+    F: Fn(&RequestEvent) + Send + Sync,
+{
+    fn on_request(&self, event: &RequestEvent) {
+        self(event)
+    }
+}
+
+/** Details about a single completed request. */
+#[derive(Debug)]
+pub struct RequestEvent<'a> {
+    node: Option<&'a NodeAddress>,
+    endpoint: &'a str,
+    method: &'a Method,
+    path: &'a str,
+    body_size: Option<usize>,
+    elapsed: Duration,
+    outcome: RequestOutcome,
+}
+
+impl<'a> RequestEvent<'a> {
+    /**
+    The address of the node the request was sent to.
+
+    This will be `None` if the request failed before a node was selected.
+    */
+    pub fn node(&self) -> Option<&NodeAddress> {
+        self.node
+    }
+
+    /**
+    The name of the endpoint the request was sent to, like `search` or `indices.create`.
+
+    Endpoints use the names from the Elasticsearch REST API specification.
+    Unlike the path, the name doesn't include index names or document ids, so it's suitable for labelling metrics.
+    */
+    pub fn endpoint(&self) -> &str {
+        self.endpoint
+    }
+
+    /** The HTTP method of the request. */
+    pub fn method(&self) -> &Method {
+        self.method
+    }
+
+    /** The path of the endpoint the request was sent to, like `/_search`. */
+    pub fn path(&self) -> &str {
+        self.path
+    }
+
+    /**
+    The size of the request body in bytes, as it was sent to the node.
+
+    This will be `None` if the request failed before its body was built, or if the body was read from a file or other reader.
+    Requests without a body have a size of `0`.
+    */
+    pub fn body_size(&self) -> Option<usize> {
+        self.body_size
+    }
+
+    /** The time between the request being sent and the response headers being received or the request failing. */
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /** Whether the request received a response or failed. */
+    pub fn outcome(&self) -> RequestOutcome {
+        self.outcome
+    }
+}
+
+/** The outcome of a request. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RequestOutcome {
+    /**
+    A response was received with the given status.
+
+    The response may still contain an API error from Elasticsearch.
+    */
+    Response(StatusCode),
+    /** The request failed without receiving a response. */
+    Error(RequestErrorKind),
+}
+
+impl RequestOutcome {
+    /**
+    Whether the outcome means the node itself is unavailable.
+
+    This is the case when the request couldn't be sent to the node, or the node responded with a `502`, `503` or `504` status.
+    Other error statuses, like a `400` for an invalid query, don't say anything about the availability of the node.
+    */
+    pub fn is_node_failure(&self) -> bool {
+        match *self {
+            RequestOutcome::Error(RequestErrorKind::Transport)
+            | RequestOutcome::Response(StatusCode::BAD_GATEWAY)
+            | RequestOutcome::Response(StatusCode::SERVICE_UNAVAILABLE)
+            | RequestOutcome::Response(StatusCode::GATEWAY_TIMEOUT) => true,
+            _ => false,
+        }
+    }
+}
+
+/** The kind of failure for a request that didn't receive a response. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RequestErrorKind {
+    /** A node to send the request to couldn't be selected, such as when sniffing fails. */
+    NodeSelection,
+    /** The request couldn't be built, such as when the url is invalid or a pre-send function fails. */
+    Request,
+    /** The request couldn't be sent to the node or the connection failed before a response was received. */
+    Transport,
+    /** The request was rejected by the client's rate limit without being sent. */
+    RateLimited,
+}
+
+impl RequestErrorKind {
+    /** A short, lowercase name for this kind of error. */
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            RequestErrorKind::NodeSelection => "node_selection",
+            RequestErrorKind::Request => "request",
+            RequestErrorKind::Transport => "transport",
+            RequestErrorKind::RateLimited => "rate_limited",
+        }
+    }
+}
+
+impl fmt::Display for RequestErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+pub(crate) type SharedObserver = Arc<Observer>;
+
+/**
+The state of a request that's being observed.
+
+If no observer is attached to the sender then the observation does nothing.
+*/
+#[derive(Clone)]
+pub(crate) struct Observation {
+    inner: Option<Arc<ObservationInner>>,
+}
+
+struct ObservationInner {
+    observer: SharedObserver,
+    endpoint: &'static str,
+    method: Method,
+    path: String,
+    // The body size plus one, so `0` means the size isn't known
+    body_size: AtomicUsize,
+    started: Instant,
+}
+
+impl Observation {
+    pub(crate) fn start(
+        observer: Option<&SharedObserver>,
+        endpoint: &'static str,
+        method: &Method,
+        path: &str,
+    ) -> Self {
+        Observation {
+            inner: observer.map(|observer| {
+                Arc::new(ObservationInner {
+                    observer: observer.clone(),
+                    endpoint,
+                    method: method.clone(),
+                    path: path.to_owned(),
+                    body_size: AtomicUsize::new(0),
+                    started: Instant::now(),
+                })
+            }),
+        }
+    }
+
+    /** Record the size of the body of a request that's about to be sent. */
+    pub(crate) fn request<TBody>(&self, req: &HttpRequest<TBody>)
+    where
+        TBody: BufferedBody,
+    {
+        if let Some(ref inner) = self.inner {
+            let body_size = match req.body {
+                Some(ref body) => body.buffered().map(|body| body.len()),
+                None => Some(0),
+            };
+
+            if let Some(body_size) = body_size {
+                inner.body_size.store(body_size + 1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub(crate) fn response(&self, node: &NodeAddress, status: StatusCode) {
+        self.complete(Some(node), RequestOutcome::Response(status))
+    }
+
+    pub(crate) fn error(&self, node: Option<&NodeAddress>, kind: RequestErrorKind) {
+        self.complete(node, RequestOutcome::Error(kind))
+    }
+
+    fn complete(&self, node: Option<&NodeAddress>, outcome: RequestOutcome) {
+        if let Some(ref inner) = self.inner {
+            let body_size = match inner.body_size.load(Ordering::Relaxed) {
+                0 => None,
+                body_size => Some(body_size - 1),
+            };
+
+            inner.observer.on_request(&RequestEvent {
+                node,
+                endpoint: inner.endpoint,
+                method: &inner.method,
+                path: &inner.path,
+                body_size,
+                elapsed: inner.started.elapsed(),
+                outcome,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use http::{
+        SyncBody,
+        SyncHttpRequest,
+        Url,
+    };
+
+    fn request(body: Option<&'static str>) -> SyncHttpRequest {
+        HttpRequest {
+            url: Url::parse("http://localhost:9200/_search").unwrap(),
+            method: Method::GET,
+            headers: Default::default(),
+            body: body.map(SyncBody::from),
+        }
+    }
+
+    fn observed_body_size(req: Option<&SyncHttpRequest>) -> Option<usize> {
+        let body_size = Arc::new(Mutex::new(None));
+
+        let observer: SharedObserver = {
+            let body_size = body_size.clone();
+            Arc::new(move |event: &RequestEvent| {
+                *body_size.lock().expect("lock poisoned") = event.body_size();
+            })
+        };
+
+        let observation = Observation::start(Some(&observer), "search", &Method::GET, "/_search");
+        if let Some(req) = req {
+            observation.request(req);
+        }
+        observation.error(None, RequestErrorKind::Request);
+
+        let body_size = *body_size.lock().expect("lock poisoned");
+        body_size
+    }
+
+    #[test]
+    fn observation_records_body_size() {
+        assert_eq!(Some(2), observed_body_size(Some(&request(Some("{}")))));
+        assert_eq!(Some(0), observed_body_size(Some(&request(None))));
+        assert_eq!(None, observed_body_size(None));
+    }
+
+    #[test]
+    fn observation_without_observer_is_noop() {
+        let observation = Observation::start(None, "search", &Method::GET, "/_search");
+
+        observation.response(&NodeAddress::from("http://localhost:9200"), StatusCode::OK);
+    }
+
+    #[test]
+    fn observation_notifies_observer() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        let observer: SharedObserver = {
+            let events = events.clone();
+            Arc::new(move |event: &RequestEvent| {
+                events.lock().expect("lock poisoned").push((
+                    event.node().map(|node| node.as_ref().to_owned()),
+                    event.endpoint().to_owned(),
+                    event.method().clone(),
+                    event.path().to_owned(),
+                    event.body_size(),
+                    event.outcome(),
+                ));
+            })
+        };
+
+        let node = NodeAddress::from("http://localhost:9200");
+
+        let search = Observation::start(Some(&observer), "search", &Method::GET, "/_search");
+        search.request(&request(Some("{}")));
+        search.response(&node, StatusCode::OK);
+
+        Observation::start(Some(&observer), "bulk", &Method::POST, "/_bulk")
+            .error(None, RequestErrorKind::NodeSelection);
+
+        let events = events.lock().expect("lock poisoned");
+
+        assert_eq!(
+            vec![
+                (
+                    Some("http://localhost:9200".to_owned()),
+                    "search".to_owned(),
+                    Method::GET,
+                    "/_search".to_owned(),
+                    Some(2),
+                    RequestOutcome::Response(StatusCode::OK),
+                ),
+                (
+                    None,
+                    "bulk".to_owned(),
+                    Method::POST,
+                    "/_bulk".to_owned(),
+                    None,
+                    RequestOutcome::Error(RequestErrorKind::NodeSelection),
+                ),
+            ],
+            *events
+        );
+    }
+}
@@ -0,0 +1,461 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{
+        self,
+        Write,
+    },
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::Duration,
+};
+
+use super::{
+    Observer,
+    RequestErrorKind,
+    RequestEvent,
+    RequestOutcome,
+};
+
+/** The default upper bounds for latency histogram buckets, in seconds. */
+pub const DEFAULT_LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/**
+An observer that collects client metrics in the [Prometheus text format][prometheus-format].
+
+The following metrics are collected:
+
+- `elastic_requests_total`: a counter of requests by `node`, `method` and `outcome`.
+  The `outcome` is either the HTTP status code of the response or the kind of error that prevented a response being received.
+- `elastic_request_errors_total`: a counter of failed requests by `kind`.
+  Requests that received a `4xx` or `5xx` response are counted as `status_4xx` or `status_5xx` errors.
+- `elastic_request_duration_seconds`: a histogram of request latencies by `node`.
+- `elastic_node_up`: a gauge by `node` that's `1` if the last request sent to the node received a response that wasn't a `5xx` error, and `0` otherwise.
+
+A `PrometheusObserver` can be cloned cheaply, and all clones share the same metrics.
+Keep a clone to render the metrics from an http endpoint that Prometheus scrapes.
+The rendered metrics should be served with a `Content-Type` of `text/plain; version=0.0.4`.
+
+# Examples
+
+Collect metrics for a client and render them:
+
+```
+# extern crate elastic;
+# use elastic::prelude::*;
+# use elastic::client::sender::observer::PrometheusObserver;
+# fn main() { run().unwrap() }
+# fn run() -> Result<(), Box<::std::error::Error>> {
+let metrics = PrometheusObserver::new();
+
+let client = SyncClientBuilder::new()
+    .observer(metrics.clone())
+    .build()?;
+
+// Send some requests with `client`
+
+let exposition = metrics.render();
+# Ok(())
+# }
+```
+
+[prometheus-format]: https://prometheus.io/docs/instrumenting/exposition_formats/#text-based-format
+*/
+#[derive(Clone)]
+pub struct PrometheusObserver {
+    inner: Arc<Mutex<Metrics>>,
+}
+
+struct Metrics {
+    buckets: Vec<f64>,
+    requests: BTreeMap<(String, String, String), u64>,
+    errors: BTreeMap<&'static str, u64>,
+    latencies: BTreeMap<String, Histogram>,
+    nodes_up: BTreeMap<String, bool>,
+}
+
+struct Histogram {
+    counts: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+impl Default for PrometheusObserver {
+    fn default() -> Self {
+        PrometheusObserver::new()
+    }
+}
+
+impl PrometheusObserver {
+    /** Create an observer that uses the [default latency buckets][DEFAULT_LATENCY_BUCKETS]. */
+    pub fn new() -> Self {
+        PrometheusObserver::with_buckets(DEFAULT_LATENCY_BUCKETS.iter().cloned())
+    }
+
+    /**
+    Create an observer that uses the given upper bounds for latency histogram buckets, in seconds.
+
+    The bounds are sorted, and a `+Inf` bucket is always included.
+    */
+    pub fn with_buckets<I>(buckets: I) -> Self
+    where
+        I: IntoIterator<Item = f64>,
+    {
+        let mut buckets: Vec<f64> = buckets.into_iter().filter(|b| b.is_finite()).collect();
+        buckets.sort_by(|a, b| a.partial_cmp(b).expect("bucket bounds are finite"));
+        buckets.dedup();
+
+        PrometheusObserver {
+            inner: Arc::new(Mutex::new(Metrics {
+                buckets,
+                requests: BTreeMap::new(),
+                errors: BTreeMap::new(),
+                latencies: BTreeMap::new(),
+                nodes_up: BTreeMap::new(),
+            })),
+        }
+    }
+
+    /** Render the collected metrics in the Prometheus text format. */
+    pub fn render(&self) -> String {
+        let mut rendered = String::new();
+
+        {
+            let metrics = self.inner.lock().expect("lock poisoned");
+            metrics
+                .render(&mut rendered)
+                .expect("writing to a string can't fail");
+        }
+
+        rendered
+    }
+}
+
+impl Observer for PrometheusObserver {
+    fn on_request(&self, event: &RequestEvent) {
+        let mut metrics = self.inner.lock().expect("lock poisoned");
+
+        metrics.record(event);
+    }
+}
+
+impl Metrics {
+    fn record(&mut self, event: &RequestEvent) {
+        let node = event.node().map(|node| node.as_ref()).unwrap_or("");
+
+        let outcome = match event.outcome() {
+            RequestOutcome::Response(status) => status.as_u16().to_string(),
+            RequestOutcome::Error(kind) => kind.as_str().to_owned(),
+        };
+
+        *self
+            .requests
+            .entry((node.to_owned(), event.method().to_string(), outcome))
+            .or_insert(0) += 1;
+
+        let error = match event.outcome() {
+            RequestOutcome::Response(status) if status.is_server_error() => Some("status_5xx"),
+            RequestOutcome::Response(status) if status.is_client_error() => Some("status_4xx"),
+            RequestOutcome::Response(_) => None,
+            RequestOutcome::Error(kind) => Some(kind.as_str()),
+        };
+
+        if let Some(error) = error {
+            *self.errors.entry(error).or_insert(0) += 1;
+        }
+
+        // Only requests that were sent to a node tell us anything about its latency or health
+        let up = match event.outcome() {
+            RequestOutcome::Response(status) => Some(!status.is_server_error()),
+            RequestOutcome::Error(RequestErrorKind::Transport) => Some(false),
+            RequestOutcome::Error(_) => None,
+        };
+
+        if let (Some(node), Some(up)) = (event.node(), up) {
+            let buckets = &self.buckets;
+
+            self.latencies
+                .entry(node.as_ref().to_owned())
+                .or_insert_with(|| Histogram::new(buckets.len()))
+                .observe(buckets, event.elapsed());
+
+            self.nodes_up.insert(node.as_ref().to_owned(), up);
+        }
+    }
+
+    fn render(&self, w: &mut impl Write) -> fmt::Result {
+        writeln!(
+            w,
+            "# HELP elastic_requests_total The number of requests sent to Elasticsearch."
+        )?;
+        writeln!(w, "# TYPE elastic_requests_total counter")?;
+        for ((node, method, outcome), count) in &self.requests {
+            writeln!(
+                w,
+                "elastic_requests_total{{node=\"{}\",method=\"{}\",outcome=\"{}\"}} {}",
+                Escaped(node),
+                Escaped(method),
+                Escaped(outcome),
+                count
+            )?;
+        }
+
+        writeln!(
+            w,
+            "# HELP elastic_request_errors_total The number of requests that failed or returned an error status."
+        )?;
+        writeln!(w, "# TYPE elastic_request_errors_total counter")?;
+        for (kind, count) in &self.errors {
+            writeln!(
+                w,
+                "elastic_request_errors_total{{kind=\"{}\"}} {}",
+                kind, count
+            )?;
+        }
+
+        writeln!(
+            w,
+            "# HELP elastic_request_duration_seconds The latency of requests sent to Elasticsearch."
+        )?;
+        writeln!(w, "# TYPE elastic_request_duration_seconds histogram")?;
+        for (node, histogram) in &self.latencies {
+            let mut cumulative = 0;
+            for (bound, count) in self.buckets.iter().zip(&histogram.counts) {
+                cumulative += count;
+                writeln!(
+                    w,
+                    "elastic_request_duration_seconds_bucket{{node=\"{}\",le=\"{}\"}} {}",
+                    Escaped(node),
+                    bound,
+                    cumulative
+                )?;
+            }
+            writeln!(
+                w,
+                "elastic_request_duration_seconds_bucket{{node=\"{}\",le=\"+Inf\"}} {}",
+                Escaped(node),
+                histogram.count
+            )?;
+            writeln!(
+                w,
+                "elastic_request_duration_seconds_sum{{node=\"{}\"}} {}",
+                Escaped(node),
+                histogram.sum
+            )?;
+            writeln!(
+                w,
+                "elastic_request_duration_seconds_count{{node=\"{}\"}} {}",
+                Escaped(node),
+                histogram.count
+            )?;
+        }
+
+        writeln!(
+            w,
+            "# HELP elastic_node_up Whether the last request sent to a node received a healthy response."
+        )?;
+        writeln!(w, "# TYPE elastic_node_up gauge")?;
+        for (node, up) in &self.nodes_up {
+            writeln!(
+                w,
+                "elastic_node_up{{node=\"{}\"}} {}",
+                Escaped(node),
+                if *up { 1 } else { 0 }
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Histogram {
+    fn new(buckets: usize) -> Self {
+        Histogram {
+            counts: vec![0; buckets],
+            count: 0,
+            sum: 0.0,
+        }
+    }
+
+    fn observe(&mut self, buckets: &[f64], elapsed: Duration) {
+        let secs = elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) / 1_000_000_000.0;
+
+        if let Some(i) = buckets.iter().position(|bound| secs <= *bound) {
+            self.counts[i] += 1;
+        }
+
+        self.count += 1;
+        self.sum += secs;
+    }
+}
+
+/** Escape a label value for the Prometheus text format. */
+struct Escaped<'a>(&'a str);
+
+impl<'a> fmt::Display for Escaped<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '\\' => f.write_str("\\\\")?,
+                '"' => f.write_str("\\\"")?,
+                '\n' => f.write_str("\\n")?,
+                c => f.write_char(c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::sender::NodeAddress;
+    use http::{
+        Method,
+        StatusCode,
+    };
+
+    fn event<'a>(
+        node: Option<&'a NodeAddress>,
+        elapsed: Duration,
+        outcome: RequestOutcome,
+    ) -> RequestEvent<'a> {
+        RequestEvent {
+            node,
+            endpoint: "search",
+            method: &Method::GET,
+            path: "/_search",
+            body_size: None,
+            elapsed,
+            outcome,
+        }
+    }
+
+    #[test]
+    fn render_empty() {
+        let metrics = PrometheusObserver::new();
+
+        let expected = "\
+# HELP elastic_requests_total The number of requests sent to Elasticsearch.
+# TYPE elastic_requests_total counter
+# HELP elastic_request_errors_total The number of requests that failed or returned an error status.
+# TYPE elastic_request_errors_total counter
+# HELP elastic_request_duration_seconds The latency of requests sent to Elasticsearch.
+# TYPE elastic_request_duration_seconds histogram
+# HELP elastic_node_up Whether the last request sent to a node received a healthy response.
+# TYPE elastic_node_up gauge
+";
+
+        assert_eq!(expected, metrics.render());
+    }
+
+    #[test]
+    fn render_events() {
+        let metrics = PrometheusObserver::with_buckets(vec![1.0, 0.1]);
+
+        let node_a = NodeAddress::from("http://a:9200");
+        let node_b = NodeAddress::from("http://b:9200");
+
+        metrics.on_request(&event(
+            Some(&node_a),
+            Duration::from_millis(50),
+            RequestOutcome::Response(StatusCode::OK),
+        ));
+        metrics.on_request(&event(
+            Some(&node_a),
+            Duration::from_millis(500),
+            RequestOutcome::Response(StatusCode::NOT_FOUND),
+        ));
+        metrics.on_request(&event(
+            Some(&node_b),
+            Duration::from_secs(2),
+            RequestOutcome::Error(RequestErrorKind::Transport),
+        ));
+        metrics.on_request(&event(
+            None,
+            Duration::from_millis(1),
+            RequestOutcome::Error(RequestErrorKind::NodeSelection),
+        ));
+
+        let expected = "\
+# HELP elastic_requests_total The number of requests sent to Elasticsearch.
+# TYPE elastic_requests_total counter
+elastic_requests_total{node=\"\",method=\"GET\",outcome=\"node_selection\"} 1
+elastic_requests_total{node=\"http://a:9200\",method=\"GET\",outcome=\"200\"} 1
+elastic_requests_total{node=\"http://a:9200\",method=\"GET\",outcome=\"404\"} 1
+elastic_requests_total{node=\"http://b:9200\",method=\"GET\",outcome=\"transport\"} 1
+# HELP elastic_request_errors_total The number of requests that failed or returned an error status.
+# TYPE elastic_request_errors_total counter
+elastic_request_errors_total{kind=\"node_selection\"} 1
+elastic_request_errors_total{kind=\"status_4xx\"} 1
+elastic_request_errors_total{kind=\"transport\"} 1
+# HELP elastic_request_duration_seconds The latency of requests sent to Elasticsearch.
+# TYPE elastic_request_duration_seconds histogram
+elastic_request_duration_seconds_bucket{node=\"http://a:9200\",le=\"0.1\"} 1
+elastic_request_duration_seconds_bucket{node=\"http://a:9200\",le=\"1\"} 2
+elastic_request_duration_seconds_bucket{node=\"http://a:9200\",le=\"+Inf\"} 2
+elastic_request_duration_seconds_sum{node=\"http://a:9200\"} 0.55
+elastic_request_duration_seconds_count{node=\"http://a:9200\"} 2
+elastic_request_duration_seconds_bucket{node=\"http://b:9200\",le=\"0.1\"} 0
+elastic_request_duration_seconds_bucket{node=\"http://b:9200\",le=\"1\"} 0
+elastic_request_duration_seconds_bucket{node=\"http://b:9200\",le=\"+Inf\"} 1
+elastic_request_duration_seconds_sum{node=\"http://b:9200\"} 2
+elastic_request_duration_seconds_count{node=\"http://b:9200\"} 1
+# HELP elastic_node_up Whether the last request sent to a node received a healthy response.
+# TYPE elastic_node_up gauge
+elastic_node_up{node=\"http://a:9200\"} 1
+elastic_node_up{node=\"http://b:9200\"} 0
+";
+
+        assert_eq!(expected, metrics.render());
+    }
+
+    #[test]
+    fn node_up_tracks_last_response() {
+        let metrics = PrometheusObserver::new();
+        let node = NodeAddress::from("http://a:9200");
+
+        metrics.on_request(&event(
+            Some(&node),
+            Duration::from_millis(1),
+            RequestOutcome::Response(StatusCode::SERVICE_UNAVAILABLE),
+        ));
+        assert!(metrics
+            .render()
+            .contains("elastic_node_up{node=\"http://a:9200\"} 0"));
+
+        metrics.on_request(&event(
+            Some(&node),
+            Duration::from_millis(1),
+            RequestOutcome::Response(StatusCode::OK),
+        ));
+        assert!(metrics
+            .render()
+            .contains("elastic_node_up{node=\"http://a:9200\"} 1"));
+    }
+
+    #[test]
+    fn clones_share_metrics() {
+        let metrics = PrometheusObserver::new();
+        let node = NodeAddress::from("http://a:9200");
+
+        metrics.clone().on_request(&event(
+            Some(&node),
+            Duration::from_millis(1),
+            RequestOutcome::Response(StatusCode::OK),
+        ));
+
+        assert!(metrics.render().contains(
+            "elastic_requests_total{node=\"http://a:9200\",method=\"GET\",outcome=\"200\"} 1"
+        ));
+    }
+
+    #[test]
+    fn escape_label_values() {
+        assert_eq!("a\\\"b\\\\c\\nd", Escaped("a\"b\\c\nd").to_string());
+    }
+}
@@ -3,19 +3,19 @@ use std::{
     sync::Arc,
 };
 
-use reqwest::{
-    self,
+use reqwest;
+use url::form_urlencoded::Serializer;
+
+use client::sender::NodeAddress;
+use http::{
     header::{
         HeaderMap,
         HeaderName,
         HeaderValue,
         CONTENT_TYPE,
     },
+    Method,
 };
-use url::form_urlencoded::Serializer;
-
-use client::sender::NodeAddress;
-use http::Method;
 
 pub const DEFAULT_NODE_ADDRESS: &'static str = "http://localhost:9200";
 
@@ -176,10 +176,26 @@ impl RequestParams {
         self.base_url.as_ref()
     }
 
+    pub(crate) fn get_node_address(&self) -> &NodeAddress {
+        &self.base_url
+    }
+
     pub(crate) fn get_headers(&self) -> Arc<HeaderMap> {
         self.inner.headers.clone()
     }
 
+    pub(crate) fn headers_ref(&self) -> &HeaderMap {
+        &self.inner.headers
+    }
+
+    pub(crate) fn insert_url_param(&mut self, key: &'static str, value: impl ToString) {
+        Arc::make_mut(&mut self.inner.url_params).insert(key, value.to_string());
+    }
+
+    pub(crate) fn insert_header(&mut self, key: HeaderName, value: HeaderValue) {
+        Arc::make_mut(&mut self.inner.headers).insert(key, value);
+    }
+
     /**
     Get the url query params as a formatted string.
 
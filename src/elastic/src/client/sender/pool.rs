@@ -0,0 +1,154 @@
+/*!
+Tune the pool of connections to Elasticsearch nodes.
+
+A [`ConnectionPool`][ConnectionPool] can be attached to a client using the `connection_pool` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+By default, connections are kept alive and reused, any number of idle connections are kept for each node for up to 90 seconds, and connecting to a node never times out.
+
+Under bursty load a client can open many connections at once, and then close most of them again once they've been idle for a while.
+Limiting the number of idle connections kept for each node, or keeping idle connections alive for longer, can reduce this churn.
+Setting a connect timeout makes requests to unreachable nodes fail fast, so a client with a retry policy can move on to another node.
+
+Connection pool options can't be combined with a custom `reqwest::Client`, which should be configured directly instead.
+
+# Examples
+
+Keep at most 32 idle connections for each node for up to 5 minutes, and wait at most 2 seconds to connect:
+
+```
+# use std::time::Duration;
+# use elastic::prelude::*;
+# use elastic::client::sender::pool::ConnectionPool;
+let builder = SyncClientBuilder::new()
+    .connection_pool(
+        ConnectionPool::new()
+            .max_idle_per_host(32)
+            .idle_timeout(Duration::from_secs(300))
+            .connect_timeout(Duration::from_secs(2)),
+    );
+```
+
+[ConnectionPool]: struct.ConnectionPool.html
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+use reqwest::ClientBuilder as AsyncHttpClientBuilder;
+use std::time::Duration;
+
+/** Options for the pool of connections to Elasticsearch nodes. */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConnectionPool {
+    keep_alive: Option<bool>,
+    max_idle_per_host: Option<usize>,
+    idle_timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+}
+
+impl ConnectionPool {
+    /** Create connection pool options with the defaults. */
+    pub fn new() -> Self {
+        ConnectionPool::default()
+    }
+
+    /**
+    Specify whether connections are kept alive and reused for later requests.
+
+    Connections are kept alive by default.
+    If they aren't then a new connection is opened for every request.
+    */
+    pub fn keep_alive(mut self, keep_alive: bool) -> Self {
+        self.keep_alive = Some(keep_alive);
+        self
+    }
+
+    /**
+    Specify the maximum number of idle connections to keep for each node.
+
+    Connections beyond this limit are closed once their request has completed.
+    There's no limit by default.
+    */
+    pub fn max_idle_per_host(mut self, max: usize) -> Self {
+        self.max_idle_per_host = Some(max);
+        self
+    }
+
+    /**
+    Specify how long an idle connection is kept alive before it's closed.
+
+    Idle connections are kept alive for 90 seconds by default.
+    The timeout should be shorter than any idle timeout of a load balancer or proxy in front of the nodes,
+    so connections aren't reused after they've been closed on the other end.
+    */
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /**
+    Specify how long to wait for a connection to a node to be established.
+
+    A request that can't connect within the timeout fails like any other connection error.
+    If the client has a [`RetryPolicy`][RetryPolicy] then the request is retried on the next node, otherwise the error is returned.
+    There's no timeout by default.
+
+    [RetryPolicy]: ../retry/struct.RetryPolicy.html
+    */
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /** Get the maximum number of idle connections to keep for each node. */
+    fn idle_per_host(&self) -> Option<usize> {
+        match self.keep_alive {
+            Some(false) => Some(0),
+            _ => self.max_idle_per_host,
+        }
+    }
+
+    /** Apply the options to a http client builder. */
+    pub(crate) fn build(&self, mut http: AsyncHttpClientBuilder) -> AsyncHttpClientBuilder {
+        if let Some(max) = self.idle_per_host() {
+            http = http.pool_max_idle_per_host(max);
+        }
+
+        if let Some(timeout) = self.idle_timeout {
+            http = http.pool_idle_timeout(timeout);
+        }
+
+        if let Some(timeout) = self.connect_timeout {
+            http = http.connect_timeout(timeout);
+        }
+
+        http
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_idle_per_host_is_unlimited() {
+        assert_eq!(None, ConnectionPool::new().idle_per_host());
+    }
+
+    #[test]
+    fn no_keep_alive_keeps_no_idle_connections() {
+        let pool = ConnectionPool::new()
+            .max_idle_per_host(32)
+            .keep_alive(false);
+
+        assert_eq!(Some(0), pool.idle_per_host());
+    }
+
+    #[test]
+    fn build_http_client() {
+        let pool = ConnectionPool::new()
+            .max_idle_per_host(32)
+            .idle_timeout(Duration::from_secs(300))
+            .connect_timeout(Duration::from_secs(2));
+
+        assert!(pool.build(AsyncHttpClientBuilder::new()).build().is_ok());
+    }
+}
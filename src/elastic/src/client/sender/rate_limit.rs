@@ -0,0 +1,409 @@
+/*!
+Limit the rate of requests sent to Elasticsearch.
+
+A [`RateLimit`][RateLimit] caps the number of requests a client sends per second, the number of requests it has in flight at once, or both.
+This lets batch jobs run flat out without overwhelming a small cluster.
+Attach a rate limit to a client using the `rate_limit` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+
+Requests that would go over the limit either wait their turn or fail straight away, depending on the [`Overflow`][Overflow] behaviour.
+A synchronous client waits by blocking the calling thread, and an asynchronous client waits without blocking its executor.
+Requests that fail because of the limit are reported to observers with a `RequestErrorKind::RateLimited` error.
+
+The limit applies once a node has been selected for a request, so requests made while sniffing nodes are limited too.
+A request holds its place until its response has been received, including any retries.
+
+Clones of a client share the same limit.
+
+# Examples
+
+Send at most 100 requests per second, with at most 4 in flight at once:
+
+```
+# use elastic::prelude::*;
+# use elastic::client::sender::rate_limit::RateLimit;
+let builder = SyncClientBuilder::new()
+    .rate_limit(RateLimit::new()
+        .requests_per_second(100)
+        .max_concurrent_requests(4));
+```
+
+Fail requests instead of waiting when more than 4 are in flight:
+
+```
+# use elastic::prelude::*;
+# use elastic::client::sender::rate_limit::{RateLimit, Overflow};
+let builder = SyncClientBuilder::new()
+    .rate_limit(RateLimit::new()
+        .max_concurrent_requests(4)
+        .overflow(Overflow::Error));
+```
+
+[RateLimit]: struct.RateLimit.html
+[Overflow]: enum.Overflow.html
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+use futures::{
+    task::{
+        self,
+        Task,
+    },
+    Async,
+    Future,
+    Poll,
+};
+use std::{
+    cmp,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+use runtime::Delay;
+
+use error::{
+    self,
+    Error,
+};
+
+/**
+A limit on the rate of requests sent by a client.
+
+By default, there's no limit on the number of requests per second or in flight, and requests over the limit wait their turn.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    requests_per_second: Option<u32>,
+    max_concurrent_requests: Option<usize>,
+    overflow: Overflow,
+}
+
+/** What to do with a request that would go over the rate limit. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Overflow {
+    /** Wait until the request can be sent without going over the limit. */
+    Queue,
+    /** Fail the request without sending it. */
+    Error,
+}
+
+impl RateLimit {
+    /** Create a rate limit that doesn't limit any requests. */
+    pub fn new() -> Self {
+        RateLimit {
+            requests_per_second: None,
+            max_concurrent_requests: None,
+            overflow: Overflow::Queue,
+        }
+    }
+
+    /**
+    Specify the maximum number of requests sent per second.
+
+    Requests are spread evenly across each second, so a limit of `10` sends a request at most every 100ms.
+    A value of `0` is treated as `1`.
+    */
+    pub fn requests_per_second(mut self, requests_per_second: u32) -> Self {
+        self.requests_per_second = Some(cmp::max(requests_per_second, 1));
+        self
+    }
+
+    /**
+    Specify the maximum number of requests in flight at once.
+
+    A value of `0` is treated as `1`.
+    */
+    pub fn max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.max_concurrent_requests = Some(cmp::max(max_concurrent_requests, 1));
+        self
+    }
+
+    /** Specify what to do with requests that would go over the limit. */
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    pub(crate) fn build(self) -> RateLimiter {
+        RateLimiter {
+            inner: Arc::new(RateLimiterInner {
+                limit: self,
+                state: Mutex::new(RateLimiterState {
+                    next_slot: None,
+                    in_flight: 0,
+                    waiting: Vec::new(),
+                }),
+            }),
+        }
+    }
+
+    fn interval(&self) -> Option<Duration> {
+        self.requests_per_second
+            .map(|requests_per_second| Duration::from_secs(1) / requests_per_second)
+    }
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        RateLimit::new()
+    }
+}
+
+/** The shared state for a rate limit. */
+#[derive(Clone)]
+pub(crate) struct RateLimiter {
+    inner: Arc<RateLimiterInner>,
+}
+
+struct RateLimiterInner {
+    limit: RateLimit,
+    state: Mutex<RateLimiterState>,
+}
+
+struct RateLimiterState {
+    // The earliest time the next request can be sent
+    next_slot: Option<Instant>,
+    in_flight: usize,
+    // Requests waiting for a request in flight to complete
+    waiting: Vec<Task>,
+}
+
+/**
+A request's place within the rate limit.
+
+The place is given up when the permit is dropped.
+*/
+pub(crate) struct RateLimitPermit {
+    inner: Option<Arc<RateLimiterInner>>,
+}
+
+impl RateLimiter {
+    /** Wait until a request can be sent without blocking the current thread. */
+    pub(crate) fn acquire_async(&self) -> AcquireRateLimit {
+        AcquireRateLimit {
+            inner: self.inner.clone(),
+            reserved: false,
+            delay: None,
+        }
+    }
+}
+
+impl RateLimiterInner {
+    /**
+    Reserve the next slot for sending a request.
+
+    If the slot is in the future then the request should wait until then before it's sent.
+    */
+    fn reserve_slot(&self) -> Result<Option<Instant>, Error> {
+        let interval = match self.limit.interval() {
+            Some(interval) => interval,
+            None => return Ok(None),
+        };
+
+        let mut state = self.state.lock().expect("lock poisoned");
+        let now = Instant::now();
+        let slot = match state.next_slot {
+            Some(next_slot) if next_slot > now => {
+                if self.limit.overflow == Overflow::Error {
+                    return Err(rate_limited("too many requests per second"));
+                }
+
+                next_slot
+            }
+            _ => now,
+        };
+
+        state.next_slot = Some(slot + interval);
+
+        Ok(Some(slot))
+    }
+
+    /** Try take a place for a request in flight. */
+    fn try_take(&self, state: &mut RateLimiterState) -> Result<bool, Error> {
+        match self.limit.max_concurrent_requests {
+            Some(max) if state.in_flight >= max => {
+                if self.limit.overflow == Overflow::Error {
+                    Err(rate_limited("too many concurrent requests"))
+                } else {
+                    Ok(false)
+                }
+            }
+            _ => {
+                state.in_flight += 1;
+                Ok(true)
+            }
+        }
+    }
+
+    fn permit(self: &Arc<Self>) -> RateLimitPermit {
+        RateLimitPermit {
+            inner: Some(self.clone()),
+        }
+    }
+
+    fn release(&self) {
+        let waiting = {
+            let mut state = self.state.lock().expect("lock poisoned");
+            state.in_flight -= 1;
+
+            state.waiting.drain(..).collect::<Vec<_>>()
+        };
+
+        // Waiting tasks race for the released place, and any that lose will wait again
+        for task in waiting {
+            task.notify();
+        }
+    }
+}
+
+impl Drop for RateLimitPermit {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            inner.release();
+        }
+    }
+}
+
+/** A future that resolves once a request can be sent. */
+pub(crate) struct AcquireRateLimit {
+    inner: Arc<RateLimiterInner>,
+    reserved: bool,
+    delay: Option<Delay>,
+}
+
+impl Future for AcquireRateLimit {
+    type Item = RateLimitPermit;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        if !self.reserved {
+            self.reserved = true;
+
+            if let Some(slot) = self.inner.reserve_slot()? {
+                if slot > Instant::now() {
+                    self.delay = Some(Delay::new(slot));
+                }
+            }
+        }
+
+        if let Some(ref mut delay) = self.delay {
+            try_ready!(delay.poll().map_err(error::request));
+        }
+        self.delay = None;
+
+        let mut state = self.inner.state.lock().expect("lock poisoned");
+        if self.inner.try_take(&mut state)? {
+            Ok(Async::Ready(self.inner.permit()))
+        } else {
+            state.waiting.push(task::current());
+            Ok(Async::NotReady)
+        }
+    }
+}
+
+fn rate_limited(reason: &str) -> Error {
+    error::request(error::message(format!(
+        "the request was rejected by the client's rate limit: {}",
+        reason
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::future;
+    use std::thread;
+
+    use super::*;
+    use runtime::tests::block_on;
+
+    /** Wait until a request can be sent, blocking the current thread. */
+    fn acquire(limiter: &RateLimiter) -> Result<RateLimitPermit, Error> {
+        block_on(limiter.acquire_async())
+    }
+
+    #[test]
+    fn no_limit_never_waits() {
+        let limiter = RateLimit::new().build();
+
+        let permits: Vec<_> = (0..10).map(|_| acquire(&limiter).unwrap()).collect();
+
+        assert_eq!(10, permits.len());
+    }
+
+    #[test]
+    fn concurrent_requests_over_limit_error() {
+        let limiter = RateLimit::new()
+            .max_concurrent_requests(2)
+            .overflow(Overflow::Error)
+            .build();
+
+        let first = acquire(&limiter).unwrap();
+        let _second = acquire(&limiter).unwrap();
+        assert!(acquire(&limiter).is_err());
+
+        drop(first);
+        assert!(acquire(&limiter).is_ok());
+    }
+
+    #[test]
+    fn requests_per_second_over_limit_error() {
+        let limiter = RateLimit::new()
+            .requests_per_second(1)
+            .overflow(Overflow::Error)
+            .build();
+
+        assert!(acquire(&limiter).is_ok());
+        assert!(acquire(&limiter).is_err());
+    }
+
+    #[test]
+    fn requests_per_second_queue() {
+        let limiter = RateLimit::new().requests_per_second(50).build();
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            acquire(&limiter).unwrap();
+        }
+
+        // The first request is sent immediately and the others wait 20ms each
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[test]
+    fn concurrent_requests_queue() {
+        let limiter = RateLimit::new().max_concurrent_requests(1).build();
+
+        let first = acquire(&limiter).unwrap();
+
+        let waiting = {
+            let limiter = limiter.clone();
+            thread::spawn(move || acquire(&limiter).map(|_| ()))
+        };
+
+        thread::sleep(Duration::from_millis(20));
+        drop(first);
+
+        assert!(waiting.join().unwrap().is_ok());
+    }
+
+    #[test]
+    fn async_concurrent_requests_queue() {
+        let limiter = RateLimit::new().max_concurrent_requests(1).build();
+
+        let first = acquire(&limiter).unwrap();
+        let mut acquire = limiter.acquire_async();
+
+        let pending = future::lazy(|| acquire.poll()).wait().unwrap();
+        assert!(!pending.is_ready());
+
+        drop(first);
+
+        let ready = future::lazy(|| acquire.poll()).wait().unwrap();
+        assert!(ready.is_ready());
+    }
+}
@@ -0,0 +1,307 @@
+/*!
+Retry requests that fail because a node is temporarily unavailable.
+
+A [`RetryPolicy`][RetryPolicy] retries requests that fail to connect, or that receive a `429`, `502`, `503` or `504` response.
+Retries are delayed using an exponential backoff with jitter, so many clients retrying at once don't overwhelm a recovering cluster.
+Attach a retry policy to a client using the `retry` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+
+Requests that fail to connect are retried on the next node selected by the client, so a node that's down isn't tried over and over.
+Requests that receive a retryable status are retried on the same node.
+Requests for a scroll or point-in-time search, or with an explicit base url, always stay on the same node.
+Requests with a body that can only be read once, like a `File`, are never retried.
+
+If every attempt fails to connect, or every attempt receives a retryable status, then the final error contains the number of retries.
+
+# Examples
+
+Retry requests up to 5 times, starting with a 50ms backoff:
+
+```
+# use std::time::Duration;
+# use elastic::prelude::*;
+# use elastic::client::sender::retry::RetryPolicy;
+let builder = SyncClientBuilder::new()
+    .retry(RetryPolicy::new()
+        .max_attempts(6)
+        .initial_backoff(Duration::from_millis(50)));
+```
+
+[RetryPolicy]: struct.RetryPolicy.html
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+use rand::{
+    self,
+    Rng,
+};
+use std::{
+    cmp,
+    time::Duration,
+};
+
+use client::sender::observer::RequestOutcome;
+use http::{
+    Method,
+    StatusCode,
+};
+
+/**
+A policy for retrying failed requests.
+
+By default, requests are attempted at most 3 times, with a backoff starting at 100ms and capped at 10s.
+Only idempotent requests are retried, which are `GET`, `HEAD`, `PUT`, `DELETE` and `OPTIONS` requests.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    retry_non_idempotent: bool,
+}
+
+impl RetryPolicy {
+    /** Create a retry policy with the default settings. */
+    pub fn new() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            retry_non_idempotent: false,
+        }
+    }
+
+    /**
+    Specify the maximum number of times a request is attempted, including the first attempt.
+
+    A value of `1` means requests are never retried.
+    */
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /**
+    Specify the backoff before the first retry.
+
+    The backoff doubles for each retry after that.
+    */
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /** Specify the maximum backoff between retries. */
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /**
+    Specify whether requests that aren't idempotent, like `POST` requests, are also retried.
+
+    Many read-only Elasticsearch endpoints, like `_search`, use `POST` requests so they can send a body.
+    Retrying a request that isn't idempotent may apply it more than once, like indexing a document with a generated id.
+    */
+    pub fn retry_non_idempotent(mut self, retry_non_idempotent: bool) -> Self {
+        self.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+
+    /**
+    Get the backoff before retrying a request, or `None` if it shouldn't be retried.
+
+    The `attempt` is the number of times the request has been attempted so far, starting at `1`.
+    */
+    pub(crate) fn next_attempt(
+        &self,
+        method: &Method,
+        attempt: u32,
+        outcome: RequestOutcome,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        if !self.retry_non_idempotent && !is_idempotent(method) {
+            return None;
+        }
+
+        if !is_retryable(outcome) {
+            return None;
+        }
+
+        Some(self.backoff(attempt))
+    }
+
+    /**
+    Whether a request gave up after retrying an outcome that could have been retried again.
+
+    The `attempt` is the number of times the request has been attempted, including the last one.
+    */
+    pub(crate) fn is_exhausted(&self, attempt: u32, outcome: RequestOutcome) -> bool {
+        attempt > 1 && attempt >= self.max_attempts && is_retryable(outcome)
+    }
+
+    /**
+    Get the backoff after a number of attempts.
+
+    The backoff is a random duration between half and all of the exponential backoff for the attempt.
+    */
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = cmp::min(attempt.saturating_sub(1), 31);
+        let backoff = self
+            .initial_backoff
+            .checked_mul(1 << exponent)
+            .map(|backoff| cmp::min(backoff, self.max_backoff))
+            .unwrap_or(self.max_backoff);
+
+        let millis = duration_millis(backoff);
+        if millis < 2 {
+            return backoff;
+        }
+
+        Duration::from_millis(rand::thread_rng().gen_range(millis / 2, millis + 1))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new()
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    *method == Method::GET
+        || *method == Method::HEAD
+        || *method == Method::PUT
+        || *method == Method::DELETE
+        || *method == Method::OPTIONS
+}
+
+fn is_retryable(outcome: RequestOutcome) -> bool {
+    match outcome {
+        RequestOutcome::Response(StatusCode::TOO_MANY_REQUESTS) => true,
+        outcome => outcome.is_node_failure(),
+    }
+}
+
+fn duration_millis(duration: Duration) -> u64 {
+    duration.as_secs() * 1_000 + u64::from(duration.subsec_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::sender::observer::RequestErrorKind;
+
+    fn transport_error() -> RequestOutcome {
+        RequestOutcome::Error(RequestErrorKind::Transport)
+    }
+
+    #[test]
+    fn retry_transport_error() {
+        let policy = RetryPolicy::new();
+
+        assert!(policy
+            .next_attempt(&Method::GET, 1, transport_error())
+            .is_some());
+    }
+
+    #[test]
+    fn retry_status() {
+        let policy = RetryPolicy::new();
+
+        for &status in &[
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::BAD_GATEWAY,
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::GATEWAY_TIMEOUT,
+        ] {
+            let outcome = RequestOutcome::Response(status);
+
+            assert!(policy.next_attempt(&Method::GET, 1, outcome).is_some());
+        }
+
+        for &status in &[
+            StatusCode::OK,
+            StatusCode::BAD_REQUEST,
+            StatusCode::NOT_FOUND,
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ] {
+            let outcome = RequestOutcome::Response(status);
+
+            assert!(policy.next_attempt(&Method::GET, 1, outcome).is_none());
+        }
+    }
+
+    #[test]
+    fn no_retry_request_error() {
+        let policy = RetryPolicy::new();
+        let outcome = RequestOutcome::Error(RequestErrorKind::Request);
+
+        assert!(policy.next_attempt(&Method::GET, 1, outcome).is_none());
+    }
+
+    #[test]
+    fn no_retry_after_max_attempts() {
+        let policy = RetryPolicy::new().max_attempts(2);
+
+        assert!(policy
+            .next_attempt(&Method::GET, 1, transport_error())
+            .is_some());
+        assert!(policy
+            .next_attempt(&Method::GET, 2, transport_error())
+            .is_none());
+    }
+
+    #[test]
+    fn no_retry_non_idempotent() {
+        let policy = RetryPolicy::new();
+
+        assert!(policy
+            .next_attempt(&Method::POST, 1, transport_error())
+            .is_none());
+
+        let policy = policy.retry_non_idempotent(true);
+
+        assert!(policy
+            .next_attempt(&Method::POST, 1, transport_error())
+            .is_some());
+    }
+
+    #[test]
+    fn exhausted_after_max_attempts() {
+        let policy = RetryPolicy::new().max_attempts(3);
+        let unavailable = RequestOutcome::Response(StatusCode::SERVICE_UNAVAILABLE);
+
+        assert!(!policy.is_exhausted(1, unavailable));
+        assert!(!policy.is_exhausted(2, unavailable));
+        assert!(policy.is_exhausted(3, unavailable));
+        assert!(!policy.is_exhausted(3, RequestOutcome::Response(StatusCode::OK)));
+    }
+
+    #[test]
+    fn backoff_is_exponential_with_jitter() {
+        let policy = RetryPolicy::new()
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(1_000));
+
+        let expected = vec![
+            (1, 100),
+            (2, 200),
+            (3, 400),
+            (4, 800),
+            (5, 1_000),
+            (40, 1_000),
+        ];
+
+        for (attempt, max) in expected {
+            for _ in 0..10 {
+                let backoff = duration_millis(policy.backoff(attempt));
+
+                assert!(backoff >= max / 2 && backoff <= max);
+            }
+        }
+    }
+}
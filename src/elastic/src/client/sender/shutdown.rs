@@ -0,0 +1,373 @@
+/*!
+Shut down an asynchronous client gracefully.
+
+Calling [`close`][close] on an `AsyncClient` stops it from accepting new requests, then waits for the requests it already has in flight to complete.
+Background tasks started by the client are told to stop too.
+A [`BulkProcessor`][BulkProcessor] stops accepting new operations and sends the ones it has already queued before its task completes,
+so bulk operations aren't lost when a service shuts down.
+
+Closing a client closes all of its clones.
+Requests sent after the client is closed fail without being sent.
+Requests to sniff the cluster's nodes are still sent, so the requests that are draining can find a node to send to.
+
+The future returned by `close` completes once every request and background task has finished, or fails if that takes longer than its timeout.
+The default timeout is 30 seconds.
+
+# Examples
+
+Wait up to 10 seconds for a client to finish its work:
+
+```edition2018,no_run
+# use std::time::Duration;
+# use elastic::prelude::*;
+# fn main() {}
+# async fn run() -> Result<(), Box<dyn ::std::error::Error>> {
+let client = AsyncClientBuilder::new().build()?;
+
+// Use the client
+
+if let Err(e) = client.close().timeout(Duration::from_secs(10)).await {
+    println!("failed to close the client: {}", e);
+}
+# Ok(())
+# }
+```
+
+[close]: ../struct.Client.html#method.close
+[BulkProcessor]: ../requests/bulk/struct.BulkProcessor.html
+*/
+
+use futures::{
+    task::{
+        self,
+        Task,
+    },
+    Async,
+    Future,
+    Poll,
+};
+use futures_util::compat::Compat01As03;
+use std::{
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+use runtime::Delay;
+
+use error::{
+    self,
+    Error,
+};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/**
+The shared state for closing a client.
+
+A handle for a background task can still send requests after the client is closed, so the task can finish its work.
+*/
+#[derive(Clone, Default)]
+pub(crate) struct Shutdown {
+    inner: Arc<Mutex<ShutdownState>>,
+    background: bool,
+}
+
+#[derive(Default)]
+struct ShutdownState {
+    closed: bool,
+    in_flight: usize,
+    background_tasks: usize,
+    // Futures waiting for the client to finish its work
+    closing: Vec<Task>,
+    // Background tasks waiting for the client to be closed
+    tasks: Vec<Task>,
+}
+
+/**
+A request that's in flight.
+
+The request is no longer in flight once the guard is dropped.
+*/
+pub(crate) struct InFlightRequest {
+    inner: Arc<Mutex<ShutdownState>>,
+}
+
+/**
+A background task started by a client.
+
+The task is finished once the guard is dropped.
+*/
+pub(crate) struct BackgroundTask {
+    inner: Arc<Mutex<ShutdownState>>,
+}
+
+impl Shutdown {
+    /** Start sending a request, unless the client is closed. */
+    pub(crate) fn start_request(&self) -> Result<InFlightRequest, Error> {
+        let mut state = self.inner.lock().expect("lock poisoned");
+
+        if state.closed && !self.background {
+            return Err(error::request(error::message("the client is closed")));
+        }
+
+        state.in_flight += 1;
+
+        Ok(InFlightRequest {
+            inner: self.inner.clone(),
+        })
+    }
+
+    /**
+    Start a background task.
+
+    The returned handle can send requests after the client is closed.
+    */
+    pub(crate) fn background_task(&self) -> (Shutdown, BackgroundTask) {
+        self.inner.lock().expect("lock poisoned").background_tasks += 1;
+
+        let shutdown = self.allow_after_close();
+        let task = BackgroundTask {
+            inner: self.inner.clone(),
+        };
+
+        (shutdown, task)
+    }
+
+    /** Get a handle that can send requests after the client is closed. */
+    pub(crate) fn allow_after_close(&self) -> Shutdown {
+        Shutdown {
+            inner: self.inner.clone(),
+            background: true,
+        }
+    }
+
+    /** Close the client and wait for its work to finish. */
+    pub(crate) fn close(&self) -> Close {
+        let tasks = {
+            let mut state = self.inner.lock().expect("lock poisoned");
+            state.closed = true;
+
+            state.tasks.drain(..).collect::<Vec<_>>()
+        };
+
+        for task in tasks {
+            task.notify();
+        }
+
+        Close {
+            inner: Compat01As03::new(Closing {
+                inner: self.inner.clone(),
+                started: Instant::now(),
+                timeout: DEFAULT_TIMEOUT,
+                delay: None,
+            }),
+        }
+    }
+}
+
+impl BackgroundTask {
+    /**
+    Check whether the client has been closed.
+
+    If the client isn't closed then the current task is notified once it is.
+    */
+    pub(crate) fn poll_closed(&self) -> bool {
+        let mut state = self.inner.lock().expect("lock poisoned");
+
+        if !state.closed {
+            register(&mut state.tasks);
+        }
+
+        state.closed
+    }
+}
+
+/** Register the current task to be notified, unless it already is. */
+fn register(tasks: &mut Vec<Task>) {
+    if !tasks.iter().any(|task| task.will_notify_current()) {
+        tasks.push(task::current());
+    }
+}
+
+/** Notify the futures waiting for a client to finish its work. */
+fn notify_closing(state: &mut ShutdownState) {
+    if state.in_flight == 0 && state.background_tasks == 0 {
+        for task in state.closing.drain(..) {
+            task.notify();
+        }
+    }
+}
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        let mut state = self.inner.lock().expect("lock poisoned");
+        state.in_flight -= 1;
+
+        notify_closing(&mut state);
+    }
+}
+
+impl Drop for BackgroundTask {
+    fn drop(&mut self) {
+        let mut state = self.inner.lock().expect("lock poisoned");
+        state.background_tasks -= 1;
+
+        notify_closing(&mut state);
+    }
+}
+
+/**
+A future returned by calling `close` on an `AsyncClient`.
+
+The future completes once the client's requests in flight and background tasks have finished.
+It fails if they haven't finished before the timeout passes.
+*/
+pub struct Close {
+    inner: Compat01As03<Closing>,
+}
+
+impl Close {
+    /**
+    Specify how long to wait for the client to finish its work.
+
+    The timeout is measured from when the client was closed.
+    The default is 30 seconds.
+    */
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        let closing = self.inner.get_mut();
+        closing.timeout = timeout;
+        closing.delay = None;
+
+        self
+    }
+}
+
+pending_future!(Close => ());
+
+/** Waits for a closed client to finish its work. */
+struct Closing {
+    inner: Arc<Mutex<ShutdownState>>,
+    started: Instant,
+    timeout: Duration,
+    delay: Option<Delay>,
+}
+
+impl Future for Closing {
+    type Item = ();
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        {
+            let mut state = self.inner.lock().expect("lock poisoned");
+
+            if state.in_flight == 0 && state.background_tasks == 0 {
+                return Ok(Async::Ready(()));
+            }
+
+            register(&mut state.closing);
+        }
+
+        let deadline = self.started + self.timeout;
+        let delay = self.delay.get_or_insert_with(|| Delay::new(deadline));
+
+        match delay.poll().map_err(error::request)? {
+            Async::Ready(()) => {
+                let state = self.inner.lock().expect("lock poisoned");
+
+                Err(error::request(error::message(format!(
+                    "timed out closing the client with {} requests in flight and {} background tasks running",
+                    state.in_flight, state.background_tasks
+                ))))
+            }
+            Async::NotReady => Ok(Async::NotReady),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::future;
+    use futures_util::compat::Future01CompatExt;
+    use tokio::runtime::Runtime;
+
+    use super::*;
+    use runtime::tests::current_thread;
+    use std_future::poll_01;
+
+    fn poll_close(runtime: &Runtime, close: &mut Close) -> Async<Result<(), Error>> {
+        runtime
+            .block_on(future::lazy(|| Ok::<_, ()>(poll_01(close))).compat())
+            .unwrap()
+    }
+
+    #[test]
+    fn close_rejects_new_requests() {
+        let shutdown = Shutdown::default();
+
+        assert!(shutdown.start_request().is_ok());
+
+        shutdown.close();
+
+        assert!(shutdown.start_request().is_err());
+    }
+
+    #[test]
+    fn background_tasks_send_requests_after_close() {
+        let shutdown = Shutdown::default();
+        let (background, _task) = shutdown.background_task();
+
+        shutdown.close();
+
+        assert!(background.start_request().is_ok());
+    }
+
+    #[test]
+    fn close_waits_for_requests_and_tasks() {
+        let shutdown = Shutdown::default();
+        let request = shutdown.start_request().unwrap();
+        let (_, task) = shutdown.background_task();
+
+        let runtime = current_thread();
+        let mut close = shutdown.close();
+
+        assert!(!poll_close(&runtime, &mut close).is_ready());
+
+        drop(request);
+        assert!(!poll_close(&runtime, &mut close).is_ready());
+
+        drop(task);
+        match poll_close(&runtime, &mut close) {
+            Async::Ready(Ok(())) => (),
+            _ => panic!("expected the client to be closed"),
+        }
+    }
+
+    #[test]
+    fn close_times_out() {
+        let shutdown = Shutdown::default();
+        let _request = shutdown.start_request().unwrap();
+
+        let close = shutdown.close().timeout(Duration::from_millis(1));
+
+        assert!(current_thread().block_on(close).is_err());
+    }
+
+    #[test]
+    fn background_tasks_are_notified_when_closed() {
+        let shutdown = Shutdown::default();
+        let (_, task) = shutdown.background_task();
+
+        assert!(!future::lazy(|| Ok::<_, ()>(task.poll_closed()))
+            .wait()
+            .unwrap());
+
+        shutdown.close();
+
+        assert!(task.poll_closed());
+    }
+}
@@ -47,33 +47,37 @@ use client::{
         DefaultBody,
         NodesInfoRequest,
     },
+    responses::SyncResponseBuilder,
     sender::{
-        static_nodes::StaticNodes,
-        AsyncSender,
+        static_nodes::{
+            SharedStrategy,
+            StaticNodes,
+        },
         NextParams,
         NodeAddress,
         PreRequestParams,
         RequestParams,
         SendableRequest,
         SendableRequestParams,
+        PendingResponse,
         Sender,
-        SyncSender,
     },
 };
 use error::{
     self,
     Error,
 };
-use private;
 
 /**
 Periodically sniff nodes in a cluster.
 
-Requests are load balanced between the sniffed nodes using a round-robin strategy.
+Requests are load balanced between the sniffed nodes using the client's load balancing strategy, which is round-robin by default.
 The base url for the node is obtained by the `http.publish_address` field on a [node info request].
 
 Nodes are refreshed on the next request after the specified timeout.
-If updating the nodes fails for some reason then the request itself will also fail.
+Any nodes that are no longer in the cluster are removed, and any new nodes are added.
+The first refresh is sent to the base address, and later refreshes are sent to the sniffed nodes, so the base address doesn't need to stay available.
+If updating the nodes fails for some reason then the request itself will also fail, and the next refresh is sent to the base address.
 
 [node info request]: https://www.elastic.co/guide/en/elasticsearch/reference/current/cluster-nodes-info.html
 */
@@ -96,10 +100,22 @@ struct SniffedNodesInner {
     last_update: Option<Instant>,
     wait: Duration,
     refreshing: bool,
-    nodes: StaticNodes,
+    refresh_from_base_url: bool,
+    nodes: StaticNodes<SharedStrategy>,
+}
+
+enum NextOrRefresh {
+    Next(Result<RequestParams, Error>),
+    Refresh(Option<NodeAddress>),
 }
 
 impl<TSender> SniffedNodes<TSender> {
+    pub(crate) fn pin(&self, address: NodeAddress) -> RequestParams {
+        let inner = self.inner.read().expect("lock poisoned");
+
+        inner.nodes.pin(address)
+    }
+
     /**
     Get the next async address or refresh.
 
@@ -115,13 +131,14 @@ impl<TSender> SniffedNodes<TSender> {
         ) -> TRefreshFuture,
         TRefreshFuture: Future<Item = NodesInfoResponse, Error = Error> + Send + 'static,
     {
-        if let Some(address) = self.next_or_start_refresh() {
-            return Box::new(address.into_future());
-        }
+        let refresh_address = match self.next_or_start_refresh() {
+            NextOrRefresh::Next(address) => return Box::new(address.into_future()),
+            NextOrRefresh::Refresh(refresh_address) => refresh_address,
+        };
 
         // Perform the refresh
         let inner = self.inner.clone();
-        let req = self.sendable_request();
+        let req = self.sendable_request(refresh_address);
         let refresh_params = self.refresh_params.clone();
 
         let refresh_nodes = refresh(req)
@@ -141,12 +158,13 @@ impl<TSender> SniffedNodes<TSender> {
             SendableRequest<NodesInfoRequest<'static>, RequestParams, DefaultBody>,
         ) -> Result<NodesInfoResponse, Error>,
     {
-        if let Some(address) = self.next_or_start_refresh() {
-            return address;
-        }
+        let refresh_address = match self.next_or_start_refresh() {
+            NextOrRefresh::Next(address) => return address,
+            NextOrRefresh::Refresh(refresh_address) => refresh_address,
+        };
 
         // Perform the refresh
-        let req = self.sendable_request();
+        let req = self.sendable_request(refresh_address);
 
         let fresh_nodes = refresh(req);
         Self::finish_refresh(&self.inner, &self.refresh_params, fresh_nodes)
@@ -194,9 +212,11 @@ impl SniffedNodesBuilder {
     pub(crate) fn build<TSender>(
         self,
         base_params: PreRequestParams,
+        strategy: SharedStrategy,
         sender: TSender,
     ) -> SniffedNodes<TSender> {
-        let nodes = StaticNodes::round_robin(vec![self.base_url.clone()], base_params.clone());
+        let nodes =
+            StaticNodes::with_strategy(vec![self.base_url.clone()], strategy, base_params.clone());
         let wait = self.wait.unwrap_or_else(|| Duration::from_secs(90));
 
         // Specify a `filter_path` when updating node stats because deserialisation occurs on tokio thread
@@ -215,6 +235,7 @@ impl SniffedNodesBuilder {
                 last_update: None,
                 wait: wait,
                 refreshing: false,
+                refresh_from_base_url: true,
                 nodes: nodes,
             })),
         }
@@ -240,10 +261,11 @@ impl<TSender> SniffedNodes<TSender> {
     /**
     Return a node address if the set of nodes is still current.
 
-    If this method returns `Some` then the set of nodes is current and an address is returned.
-    If this method returns `None` then eventually call `finish_refresh`.
+    If this method returns `Next` then the set of nodes is current and an address is returned.
+    If this method returns `Refresh` then send a refresh request to the given address and eventually call `finish_refresh`.
+    The address is `None` if the refresh should be sent to the base address.
     */
-    fn next_or_start_refresh(&self) -> Option<Result<RequestParams, Error>> {
+    fn next_or_start_refresh(&self) -> NextOrRefresh {
         // Attempt to get an address using only a read lock first
         let read_fresh = {
             let inner = self.inner.read().expect("lock poisoned");
@@ -252,14 +274,14 @@ impl<TSender> SniffedNodes<TSender> {
                 // Return the next address without refreshing
                 let address = inner.nodes.next().map_err(error::request);
 
-                Some(address)
+                Some(NextOrRefresh::Next(address))
             } else {
                 None
             }
         };
 
         // Attempt to refresh using a write lock otherwise
-        read_fresh.or_else(|| {
+        read_fresh.unwrap_or_else(|| {
             let mut inner = self.inner.write().expect("lock poisoned");
 
             if inner.refreshing {
@@ -269,21 +291,27 @@ impl<TSender> SniffedNodes<TSender> {
                 // In that case we don't want to do another one.
                 let address = inner.nodes.next().map_err(error::request);
 
-                Some(address)
+                NextOrRefresh::Next(address)
             } else {
                 inner.refreshing = true;
 
-                None
+                NextOrRefresh::Refresh(inner.refresh_address())
             }
         })
     }
 
     fn sendable_request(
         &self,
+        address: Option<NodeAddress>,
     ) -> SendableRequest<NodesInfoRequest<'static>, RequestParams, DefaultBody> {
+        let params = match address {
+            Some(address) => self.refresh_params.clone().base_url(address),
+            None => self.refresh_params.clone(),
+        };
+
         SendableRequest::new(
             NodesInfoRequest::new(),
-            SendableRequestParams::Value(self.refresh_params.clone()),
+            SendableRequestParams::Value(params),
         )
     }
 
@@ -296,6 +324,10 @@ impl<TSender> SniffedNodes<TSender> {
 
         inner.refreshing = false;
 
+        // If the refresh fails then the next one is sent to the base address.
+        // If that fails too then the one after is sent to the sniffed nodes again.
+        inner.refresh_from_base_url = !inner.refresh_from_base_url;
+
         // TODO: We need to deal with the scheme better here
         // The `NodeAddress` should one day be a properly typed url we can interrogate
         let parsed_url =
@@ -306,6 +338,7 @@ impl<TSender> SniffedNodes<TSender> {
         let next = inner.update_nodes_and_next(fresh_nodes, scheme)?;
 
         inner.last_update = Some(Instant::now());
+        inner.refresh_from_base_url = false;
 
         Ok(next)
     }
@@ -322,6 +355,17 @@ impl SniffedNodesInner {
         !self.refreshing && last_update_is_stale.unwrap_or(true)
     }
 
+    fn refresh_address(&self) -> Option<NodeAddress> {
+        if self.refresh_from_base_url {
+            None
+        } else {
+            self.nodes
+                .next()
+                .ok()
+                .map(|params| params.get_node_address().clone())
+        }
+    }
+
     fn update_nodes_and_next(
         &mut self,
         parsed: NodesInfoResponse,
@@ -334,7 +378,7 @@ impl SniffedNodesInner {
                     .and_then(|http| http.publish_address)
                     .map(|publish_address| {
                         // NOTE: Nasty hack to include the correct scheme since `publish_address` is a `host:port`
-                        format!("{}://{}", scheme, publish_address).into()
+                        format!("{}://{}", scheme, host_and_port(&publish_address)).into()
                     })
             })
             .collect();
@@ -344,24 +388,52 @@ impl SniffedNodesInner {
     }
 }
 
-impl<TSender> private::Sealed for SniffedNodes<TSender> {}
+/**
+Get the `host:port` to send requests to from a `publish_address`.
 
-impl NextParams for SniffedNodes<AsyncSender> {
-    type Params = Box<Future<Item = RequestParams, Error = Error> + Send>;
+A `publish_address` may be formatted as `hostname/ip:port` when the node is bound to a hostname.
+In that case the hostname is used instead of the ip.
+*/
+fn host_and_port(publish_address: &str) -> String {
+    match publish_address.find('/') {
+        Some(slash) => {
+            let host = &publish_address[..slash];
+            let port = publish_address[slash + 1..]
+                .rfind(':')
+                .map(|colon| &publish_address[slash + 1 + colon..])
+                .unwrap_or("");
+
+            format!("{}{}", host, port)
+        }
+        None => publish_address.to_owned(),
+    }
+}
 
-    fn next(&self) -> Self::Params {
+impl<TSender> SniffedNodes<TSender>
+where
+    TSender: Sender<Response = PendingResponse>,
+    DefaultBody: Into<TSender::Body>,
+    RequestParams: Into<TSender::Params>,
+{
+    /** Get the next async address, using the sender to refresh if needed. */
+    pub(crate) fn next_async(&self) -> Box<Future<Item = RequestParams, Error = Error> + Send> {
         self.async_next(|req| {
             self.sender
                 .send(req)
-                .and_then(|res| res.into_response::<NodesInfoResponse>())
+                .compat()
+                .and_then(|res| res.into_response::<NodesInfoResponse>().compat())
         })
     }
 }
 
-impl NextParams for SniffedNodes<SyncSender> {
-    type Params = Result<RequestParams, Error>;
-
-    fn next(&self) -> Self::Params {
+impl<TSender> SniffedNodes<TSender>
+where
+    TSender: Sender<Response = Result<SyncResponseBuilder, Error>>,
+    DefaultBody: Into<TSender::Body>,
+    RequestParams: Into<TSender::Params>,
+{
+    /** Get the next sync address, using the sender to refresh if needed. */
+    pub(crate) fn next_sync(&self) -> Result<RequestParams, Error> {
         self.sync_next(|req| {
             self.sender
                 .send(req)
@@ -373,10 +445,15 @@ impl NextParams for SniffedNodes<SyncSender> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use client::sender::static_nodes::RoundRobin;
     use futures::Future;
 
     fn sender() -> SniffedNodes<()> {
-        SniffedNodesBuilder::new(initial_address()).build(PreRequestParams::default(), ())
+        SniffedNodesBuilder::new(initial_address()).build(
+            PreRequestParams::default(),
+            Arc::new(RoundRobin::default()),
+            (),
+        )
     }
 
     fn expected_nodes() -> NodesInfoResponse {
@@ -428,6 +505,20 @@ mod tests {
         assert_eq!(should_refresh, inner.should_refresh());
     }
 
+    fn expire(nodes: &SniffedNodes<()>) {
+        let mut inner = nodes.inner.write().expect("lock poisoned");
+        inner.last_update = None;
+    }
+
+    fn refresh_address(
+        req: &SendableRequest<NodesInfoRequest<'static>, RequestParams, DefaultBody>,
+    ) -> String {
+        match req.params {
+            SendableRequestParams::Value(ref params) => params.get_base_url().to_owned(),
+            _ => panic!("expected refresh params to be a value"),
+        }
+    }
+
     #[test]
     fn should_refresh_is_true_initially() {
         let nodes = sender();
@@ -559,4 +650,85 @@ mod tests {
         assert_refreshing_equal(&nodes, false);
         assert_should_refresh_equal(&nodes, true);
     }
+
+    #[test]
+    fn sync_refresh_removes_missing_nodes() {
+        let nodes = sender();
+
+        nodes.sync_next(|_| Ok(expected_nodes())).unwrap();
+
+        expire(&nodes);
+        nodes
+            .sync_next(|_| {
+                Ok(NodesInfoResponse {
+                    nodes: vec![SniffedNode {
+                        http: Some(SniffedNodeHttp {
+                            publish_address: Some("b:9200".to_owned()),
+                        }),
+                    }],
+                })
+            })
+            .unwrap();
+
+        assert_node_addresses_equal(&nodes, vec!["http://b:9200"]);
+    }
+
+    #[test]
+    fn sync_refresh_uses_sniffed_nodes() {
+        let nodes = sender();
+
+        nodes
+            .sync_next(|req| {
+                assert_eq!(initial_address(), refresh_address(&req));
+
+                Ok(expected_nodes())
+            })
+            .unwrap();
+
+        expire(&nodes);
+        nodes
+            .sync_next(|req| {
+                assert!(expected_addresses().contains(&refresh_address(&req).as_str()));
+
+                Ok(expected_nodes())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn sync_refresh_uses_base_url_after_fail() {
+        let nodes = sender();
+
+        nodes.sync_next(|_| Ok(expected_nodes())).unwrap();
+
+        expire(&nodes);
+        let res = nodes.sync_next(|req| {
+            assert_ne!(initial_address(), refresh_address(&req));
+
+            Err(error::test())
+        });
+        assert!(res.is_err());
+
+        let res = nodes.sync_next(|req| {
+            assert_eq!(initial_address(), refresh_address(&req));
+
+            Err(error::test())
+        });
+        assert!(res.is_err());
+
+        nodes
+            .sync_next(|req| {
+                assert_ne!(initial_address(), refresh_address(&req));
+
+                Ok(expected_nodes())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn host_and_port_from_publish_address() {
+        assert_eq!("1.1.1.1:9200", host_and_port("1.1.1.1:9200"));
+        assert_eq!("es01:9200", host_and_port("es01/172.18.0.2:9200"));
+        assert_eq!("es01:9200", host_and_port("es01/[::1]:9200"));
+    }
 }
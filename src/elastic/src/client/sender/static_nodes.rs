@@ -1,6 +1,45 @@
-/*! Multiple static nodes that can be load balanced by some strategy. */
+/*!
+Multiple static nodes that can be load balanced by some strategy.
+
+The following strategies are provided:
+
+- [`RoundRobin`][RoundRobin]: cycle through nodes sequentially. This is the default.
+- [`Random`][Random]: select a random node for each request.
+- [`LeastInFlight`][LeastInFlight]: select the node with the fewest requests that haven't completed yet.
+- [`Preferred`][Preferred]: send requests to the first node, failing over to the next ones in order when it's unavailable.
+
+A strategy can be configured using the `load_balancing` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+
+[RoundRobin]: struct.RoundRobin.html
+[Random]: struct.Random.html
+[LeastInFlight]: struct.LeastInFlight.html
+[Preferred]: struct.Preferred.html
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+use rand::{
+    self,
+    Rng,
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
 
 use client::sender::{
+    observer::RequestOutcome,
     NextParams,
     NodeAddress,
     PreRequestParams,
@@ -11,13 +50,6 @@ use error::{
     Error,
 };
 use private;
-use std::sync::{
-    atomic::{
-        AtomicUsize,
-        Ordering,
-    },
-    Arc,
-};
 
 /** Select a base address for a given request using some strategy. */
 #[derive(Clone)]
@@ -56,37 +88,104 @@ impl<TStrategy> StaticNodes<TStrategy> {
         Ok(())
     }
 
+    pub(crate) fn pin(&self, address: NodeAddress) -> RequestParams {
+        RequestParams::from_parts(address, self.params.clone())
+    }
+
+    /** Share the strategy so the nodes can be used by a client. */
+    pub(crate) fn shared(self) -> StaticNodes<SharedStrategy>
+    where
+        TStrategy: Strategy + 'static,
+    {
+        StaticNodes {
+            nodes: self.nodes,
+            strategy: Arc::new(self.strategy),
+            params: self.params,
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn get(&self) -> &[NodeAddress] {
         &self.nodes
     }
 }
 
-impl StaticNodes<RoundRobin> {
-    /** Use a round-robin strategy for balancing traffic over the given set of nodes. */
-    pub fn round_robin<I, S>(nodes: I, params: PreRequestParams) -> Self
+impl<TStrategy> StaticNodes<TStrategy>
+where
+    TStrategy: Strategy,
+{
+    /** Use the given strategy for balancing traffic over the given set of nodes. */
+    pub fn with_strategy<I, S>(nodes: I, strategy: TStrategy, params: PreRequestParams) -> Self
     where
         I: IntoIterator<Item = S>,
         S: Into<NodeAddress>,
     {
         let nodes: Vec<_> = nodes.into_iter().map(Into::into).collect();
 
-        let strategy = RoundRobin::default();
-
         StaticNodes {
-            nodes: nodes,
-            strategy: strategy,
-            params: params,
+            nodes,
+            strategy,
+            params,
         }
     }
 }
 
-/** The strategy selects an address from a given collection. */
+impl StaticNodes<RoundRobin> {
+    /** Use a round-robin strategy for balancing traffic over the given set of nodes. */
+    pub fn round_robin<I, S>(nodes: I, params: PreRequestParams) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<NodeAddress>,
+    {
+        StaticNodes::with_strategy(nodes, RoundRobin::default(), params)
+    }
+}
+
+/**
+The strategy selects an address from a given collection.
+
+Strategies are also notified when requests are sent to a node and when they complete, so they can keep track of the state of each node.
+The same strategy is shared by all clones of a client.
+*/
 pub trait Strategy: Send + Sync {
     /** Try get the next address. */
     fn try_next(&self, nodes: &[NodeAddress]) -> Result<NodeAddress, StrategyError>;
+
+    /**
+    Called when a request is about to be sent to a node.
+
+    The default implementation does nothing.
+    */
+    fn on_send(&self, _node: &NodeAddress) {}
+
+    /**
+    Called when a request sent to a node has received a response or failed.
+
+    Every call to `on_send` is followed by a call to `on_complete` for the same node.
+    The default implementation does nothing.
+    */
+    fn on_complete(&self, _node: &NodeAddress, _outcome: RequestOutcome) {}
 }
 
+impl<TStrategy> Strategy for Arc<TStrategy>
+where
+    TStrategy: Strategy + ?Sized,
+{
+    fn try_next(&self, nodes: &[NodeAddress]) -> Result<NodeAddress, StrategyError> {
+        (**self).try_next(nodes)
+    }
+
+    fn on_send(&self, node: &NodeAddress) {
+        (**self).on_send(node)
+    }
+
+    fn on_complete(&self, node: &NodeAddress, outcome: RequestOutcome) {
+        (**self).on_complete(node, outcome)
+    }
+}
+
+pub(crate) type SharedStrategy = Arc<Strategy>;
+
 quick_error! {
     /**
     An error attempting to get an address using a strategy.
@@ -131,10 +230,162 @@ impl Strategy for RoundRobin {
     }
 }
 
+/** A random strategy selects a node at random for each request. */
+#[derive(Clone, Default)]
+pub struct Random {
+    _private: (),
+}
+
+impl Strategy for Random {
+    fn try_next(&self, nodes: &[NodeAddress]) -> Result<NodeAddress, StrategyError> {
+        if nodes.is_empty() {
+            Err(StrategyError::Empty)
+        } else {
+            let i = rand::thread_rng().gen_range(0, nodes.len());
+            Ok(nodes[i].clone())
+        }
+    }
+}
+
+/**
+A least-in-flight strategy selects the node with the fewest requests that haven't completed yet.
+
+Slow nodes accumulate in-flight requests, so this strategy sends more traffic to nodes that are responding quickly.
+Ties are broken by cycling through nodes sequentially.
+*/
+#[derive(Clone, Default)]
+pub struct LeastInFlight {
+    index: Arc<AtomicUsize>,
+    in_flight: Arc<Mutex<HashMap<NodeAddress, usize>>>,
+}
+
+impl Strategy for LeastInFlight {
+    fn try_next(&self, nodes: &[NodeAddress]) -> Result<NodeAddress, StrategyError> {
+        if nodes.is_empty() {
+            return Err(StrategyError::Empty);
+        }
+
+        let start = self.index.fetch_add(1, Ordering::Relaxed);
+        let in_flight = self.in_flight.lock().expect("lock poisoned");
+
+        let node = (0..nodes.len())
+            .map(|offset| &nodes[(start + offset) % nodes.len()])
+            .min_by_key(|node| in_flight.get(*node).cloned().unwrap_or(0))
+            .expect("nodes is not empty");
+
+        Ok(node.clone())
+    }
+
+    fn on_send(&self, node: &NodeAddress) {
+        let mut in_flight = self.in_flight.lock().expect("lock poisoned");
+
+        *in_flight.entry(node.clone()).or_insert(0) += 1;
+    }
+
+    fn on_complete(&self, node: &NodeAddress, _outcome: RequestOutcome) {
+        let mut in_flight = self.in_flight.lock().expect("lock poisoned");
+
+        let remaining = match in_flight.get_mut(node) {
+            Some(count) => {
+                *count = count.saturating_sub(1);
+                *count
+            }
+            None => return,
+        };
+
+        if remaining == 0 {
+            in_flight.remove(node);
+        }
+    }
+}
+
+/**
+A preferred strategy sends requests to the first node, failing over to the next ones in order when it's unavailable.
+
+A node is considered unavailable when a request to it fails to connect, or it responds with a `502`, `503` or `504` status.
+Unavailable nodes are skipped until the `retry_after` duration has passed, or a request to them succeeds.
+If every node is unavailable then the one that failed longest ago is used.
+
+# Examples
+
+Send requests to a node in the local datacenter, failing over to a remote one:
+
+```
+# use std::time::Duration;
+# use elastic::prelude::*;
+# use elastic::client::sender::static_nodes::Preferred;
+let builder = SyncClientBuilder::new()
+    .static_nodes(vec!["http://local:9200", "http://remote:9200"])
+    .load_balancing(Preferred::new().retry_after(Duration::from_secs(30)));
+```
+*/
+#[derive(Clone)]
+pub struct Preferred {
+    retry_after: Duration,
+    failed: Arc<Mutex<HashMap<NodeAddress, Instant>>>,
+}
+
+impl Preferred {
+    /** Create a preferred strategy that retries unavailable nodes after 60 seconds. */
+    pub fn new() -> Self {
+        Preferred {
+            retry_after: Duration::from_secs(60),
+            failed: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /** Specify the minimum duration to wait before sending requests to an unavailable node again. */
+    pub fn retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+}
+
+impl Default for Preferred {
+    fn default() -> Self {
+        Preferred::new()
+    }
+}
+
+impl Strategy for Preferred {
+    fn try_next(&self, nodes: &[NodeAddress]) -> Result<NodeAddress, StrategyError> {
+        let failed = self.failed.lock().expect("lock poisoned");
+
+        let available = nodes.iter().find(|node| match failed.get(*node) {
+            Some(failed_at) => failed_at.elapsed() >= self.retry_after,
+            None => true,
+        });
+
+        let node = match available {
+            Some(node) => node,
+            None => nodes
+                .iter()
+                .min_by_key(|node| failed.get(*node))
+                .ok_or(StrategyError::Empty)?,
+        };
+
+        Ok(node.clone())
+    }
+
+    fn on_complete(&self, node: &NodeAddress, outcome: RequestOutcome) {
+        let mut failed = self.failed.lock().expect("lock poisoned");
+
+        if outcome.is_node_failure() {
+            failed.insert(node.clone(), Instant::now());
+        } else {
+            failed.remove(node);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use client::sender::NextParams;
+    use client::sender::{
+        observer::RequestErrorKind,
+        NextParams,
+    };
+    use http::StatusCode;
 
     fn round_robin(addresses: Vec<&'static str>) -> StaticNodes<RoundRobin> {
         StaticNodes::round_robin(addresses, PreRequestParams::default())
@@ -144,6 +395,10 @@ mod tests {
         vec!["http://a:9200", "http://b:9200", "http://c:9200"]
     }
 
+    fn transport_error() -> RequestOutcome {
+        RequestOutcome::Error(RequestErrorKind::Transport)
+    }
+
     #[test]
     fn round_robin_next_multi() {
         let nodes = round_robin(expected_addresses());
@@ -175,4 +430,123 @@ mod tests {
 
         assert!(nodes.next().is_err());
     }
+
+    #[test]
+    fn random_next() {
+        let nodes = StaticNodes::with_strategy(
+            expected_addresses(),
+            Random::default(),
+            PreRequestParams::default(),
+        );
+
+        for _ in 0..10 {
+            let actual = nodes.next().unwrap();
+
+            assert!(expected_addresses().contains(&actual.get_base_url()));
+        }
+    }
+
+    #[test]
+    fn random_next_empty_fails() {
+        let nodes = StaticNodes::with_strategy(
+            Vec::<&'static str>::new(),
+            Random::default(),
+            PreRequestParams::default(),
+        );
+
+        assert!(nodes.next().is_err());
+    }
+
+    #[test]
+    fn least_in_flight_next() {
+        let strategy = LeastInFlight::default();
+        let nodes: Vec<NodeAddress> = expected_addresses().into_iter().map(Into::into).collect();
+
+        // Send a request to `a` and `b`, so `c` has the fewest in-flight requests
+        strategy.on_send(&nodes[0]);
+        strategy.on_send(&nodes[1]);
+
+        for _ in 0..10 {
+            assert_eq!(nodes[2], strategy.try_next(&nodes).unwrap());
+        }
+
+        // Complete the request to `a`, so `a` and `c` are tied
+        strategy.on_complete(&nodes[0], RequestOutcome::Response(StatusCode::OK));
+
+        for _ in 0..10 {
+            assert_ne!(nodes[1], strategy.try_next(&nodes).unwrap());
+        }
+    }
+
+    #[test]
+    fn least_in_flight_complete_without_send() {
+        let strategy = LeastInFlight::default();
+        let node = NodeAddress::from("http://a:9200");
+
+        strategy.on_complete(&node, transport_error());
+
+        assert!(strategy.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn preferred_next() {
+        let strategy = Preferred::new();
+        let nodes: Vec<NodeAddress> = expected_addresses().into_iter().map(Into::into).collect();
+
+        assert_eq!(nodes[0], strategy.try_next(&nodes).unwrap());
+
+        // Fail `a`, so requests fail over to `b`
+        strategy.on_complete(&nodes[0], transport_error());
+        assert_eq!(nodes[1], strategy.try_next(&nodes).unwrap());
+
+        // A bad request from `b` doesn't mean it's unavailable
+        strategy.on_complete(&nodes[1], RequestOutcome::Response(StatusCode::BAD_REQUEST));
+        assert_eq!(nodes[1], strategy.try_next(&nodes).unwrap());
+
+        // Fail `b`, so requests fail over to `c`
+        strategy.on_complete(
+            &nodes[1],
+            RequestOutcome::Response(StatusCode::SERVICE_UNAVAILABLE),
+        );
+        assert_eq!(nodes[2], strategy.try_next(&nodes).unwrap());
+
+        // `a` succeeds again, so requests go back to it
+        strategy.on_complete(&nodes[0], RequestOutcome::Response(StatusCode::OK));
+        assert_eq!(nodes[0], strategy.try_next(&nodes).unwrap());
+    }
+
+    #[test]
+    fn preferred_next_retry_after() {
+        let strategy = Preferred::new().retry_after(Duration::from_secs(0));
+        let nodes: Vec<NodeAddress> = expected_addresses().into_iter().map(Into::into).collect();
+
+        strategy.on_complete(&nodes[0], transport_error());
+
+        assert_eq!(nodes[0], strategy.try_next(&nodes).unwrap());
+    }
+
+    #[test]
+    fn preferred_next_all_failed() {
+        let strategy = Preferred::new();
+        let nodes: Vec<NodeAddress> = expected_addresses().into_iter().map(Into::into).collect();
+
+        strategy.on_complete(&nodes[1], transport_error());
+        strategy.on_complete(&nodes[2], transport_error());
+        strategy.on_complete(&nodes[0], transport_error());
+
+        assert_eq!(nodes[1], strategy.try_next(&nodes).unwrap());
+    }
+
+    #[test]
+    fn pin_keeps_params() {
+        let nodes = StaticNodes::round_robin(
+            expected_addresses(),
+            PreRequestParams::default().url_param("pretty", true),
+        );
+
+        let actual = nodes.pin("http://pinned:9200".into());
+
+        assert_eq!("http://pinned:9200", actual.get_base_url());
+        assert_eq!(Some("?pretty=true".to_owned()), actual.get_url_qry().1);
+    }
 }
@@ -1,29 +1,67 @@
-use fluent_builder::SharedFluentBuilder;
-use reqwest::{
-    Client as SyncHttpClient,
-    ClientBuilder as SyncHttpClientBuilder,
-    RequestBuilder as SyncHttpRequestBuilder,
+use fluent_builder::{
+    SharedFluentBuilder,
+    TryIntoValue,
 };
+use futures::{
+    future::{
+        self,
+        IntoFuture,
+    },
+    sync::oneshot,
+    Future,
+    Stream,
+};
+use futures_util::compat::Future01CompatExt;
+use reqwest::Client as AsyncHttpClient;
 use std::{
     error::Error as StdError,
+    io::{
+        self,
+        Read,
+    },
+    marker::PhantomData,
+    ops::Deref,
     sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    runtime::{
+        Builder as RuntimeBuilder,
+        Handle,
+        Runtime,
+        RuntimeFlavor,
+    },
+    task,
 };
 
 use client::{
-    requests::Endpoint,
-    responses::{
-        sync_response,
-        SyncResponseBuilder,
+    requests::{
+        DefaultBody,
+        Endpoint,
     },
+    responses::SyncResponseBuilder,
     sender::{
-        build_reqwest_method,
+        auth::Credentials,
+        cluster_info::ClusterInfo,
+        compression::RequestCompression,
         build_url,
+        dead_nodes::DeadNodes,
+        middleware::Middleware,
+        observer::Observer,
+        pool::ConnectionPool,
+        proxy::Proxy,
+        rate_limit::RateLimit,
+        retry::RetryPolicy,
         sniffed_nodes::SniffedNodesBuilder,
+        static_nodes::Strategy,
+        tls::Tls,
+        AsyncClientBuilder,
+        AsyncSender,
         NextParams,
         NodeAddress,
         NodeAddresses,
-        NodeAddressesBuilder,
         NodeAddressesInner,
+        PendingParams,
         PreRequestParams,
         RequestParams,
         SendableRequest,
@@ -37,18 +75,42 @@ use error::{
     Error,
 };
 use http::{
+    header::{
+        HeaderName,
+        HeaderValue,
+    },
+    AsyncBody,
+    AsyncChunk,
+    AsyncHttpRequest,
+    StreamReader,
     SyncBody,
     SyncHttpRequest,
     Url,
 };
+#[cfg(feature = "aws-sigv4")]
+use http::BufferedBody;
 use private;
 
+#[cfg(feature = "aws-sigv4")]
+use client::sender::aws_sigv4::AwsSigV4;
+#[cfg(feature = "testing")]
+use client::sender::mock::MockSender;
+
 /**
 A synchronous Elasticsearch client.
 
 Use a [`SyncClientBuilder`][SyncClientBuilder] to configure and build a `SyncClient`.
 For more details about the methods available to a `SyncClient`, see the base [`Client`][Client] type.
 
+A `SyncClient` sends requests in the same way as an [`AsyncClient`][AsyncClient], on a runtime that's shared by clones of the client.
+Sending a request blocks the current thread until the response has been received.
+Reading the body of a response also blocks while it's streamed from the runtime.
+
+A request can be sent from a thread that's running within a multi-threaded `tokio` runtime, including its blocking thread pool.
+On the runtime's worker threads the thread is handed off using [`block_in_place`][block_in_place] while the request is sent, so other tasks can keep making progress.
+A current-thread runtime can't hand off its thread, so sending a request from within one fails with an error, even on its blocking thread pool.
+Use an `AsyncClient` there instead.
+
 # Examples
 
 Create a synchronous `Client` and send a ping request:
@@ -68,17 +130,107 @@ let response = client.request(PingRequest::new())
 ```
 
 [Client]: ../struct.Client.html
+[AsyncClient]: type.AsyncClient.html
 [SyncClientBuilder]: struct.SyncClientBuilder.html
+[block_in_place]: https://docs.rs/tokio/1/tokio/task/fn.block_in_place.html
 */
 pub type SyncClient = Client<SyncSender>;
 
 /** A synchronous request sender. */
 #[derive(Clone)]
 pub struct SyncSender {
-    pub(in client) http: SyncHttpClient,
-    pre_send: Option<
-        Arc<Fn(&mut SyncHttpRequest) -> Result<(), Box<StdError + Send + Sync>> + Send + Sync>,
-    >,
+    pub(in client) inner: AsyncSender,
+    runtime: Arc<SyncRuntime>,
+}
+
+/**
+The runtime that a synchronous sender's requests are run on.
+
+The runtime is shut down in the background when it's dropped, so a client can also be dropped from within another runtime.
+*/
+struct SyncRuntime(Option<Runtime>);
+
+impl Deref for SyncRuntime {
+    type Target = Runtime;
+
+    fn deref(&self) -> &Runtime {
+        self.0.as_ref().expect("the runtime is running")
+    }
+}
+
+impl Drop for SyncRuntime {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.0.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+type SyncPreSend =
+    Arc<Fn(&mut SyncHttpRequest) -> Result<(), Box<StdError + Send + Sync>> + Send + Sync>;
+
+impl SyncSender {
+    /**
+    Block the current thread until a future that's run on the sender's runtime completes.
+
+    Worker threads of a multi-threaded `tokio` runtime are handed off while they're blocked.
+    A current-thread runtime can't hand off its thread, so this fails within one instead.
+    */
+    fn block_on<F>(&self, fut: F) -> Result<F::Item, Error>
+    where
+        F: Future<Error = Error> + Send + 'static,
+        F::Item: Send + 'static,
+    {
+        match Handle::try_current() {
+            Ok(ref handle) if handle.runtime_flavor() == RuntimeFlavor::CurrentThread => {
+                return Err(error::request(io::Error::new(
+                    io::ErrorKind::Other,
+                    "a synchronous client can't send requests from within a current-thread `tokio` runtime, use an asynchronous client instead",
+                )));
+            }
+            _ => (),
+        }
+
+        let (tx, rx) = oneshot::channel();
+
+        self.runtime
+            .spawn(fut.then(move |res| tx.send(res)).compat());
+
+        block_in_place(|| rx.wait()).unwrap_or_else(|_| {
+            Err(error::request(io::Error::new(
+                io::ErrorKind::Other,
+                "the runtime was shut down before the request completed",
+            )))
+        })
+    }
+}
+
+/** Run a blocking function, handing off the current thread first if it's a worker of a multi-threaded `tokio` runtime. */
+fn block_in_place<R>(f: impl FnOnce() -> R) -> R {
+    match Handle::try_current() {
+        Ok(ref handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            task::block_in_place(f)
+        }
+        _ => f(),
+    }
+}
+
+/**
+The body of a response received by a synchronous sender.
+
+The body is streamed from the sender's runtime, which is kept alive until the body is dropped.
+*/
+struct SyncResponseBody {
+    body: StreamReader<Box<Stream<Item = AsyncChunk, Error = io::Error> + Send>>,
+    _runtime: Arc<SyncRuntime>,
+}
+
+impl Read for SyncResponseBody {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let body = &mut self.body;
+
+        block_in_place(move || body.read(buf))
+    }
 }
 
 impl private::Sealed for SyncSender {}
@@ -97,82 +249,168 @@ impl Sender for SyncSender {
         TBody: Into<Self::Body> + Send + 'static,
         TParams: Into<Self::Params> + Send + 'static,
     {
-        let correlation_id = request.correlation_id;
-        let params = request.params;
-        let endpoint = request.inner.into();
-
-        info!(
-            "Elasticsearch Request: correlation_id: '{}', path: '{}'",
+        let SendableRequest {
             correlation_id,
-            endpoint.url.as_ref()
-        );
+            inner,
+            params,
+            ..
+        } = request;
+        let Endpoint {
+            url,
+            method,
+            body,
+            name,
+        } = inner.into();
+        let body: Option<SyncBody> = body.map(Into::into);
+
+        // Signing needs the whole body, so a body that's read from a reader is buffered first
+        #[cfg(feature = "aws-sigv4")]
+        let body = match body {
+            Some(mut body) if self.inner.signs_requests() => {
+                body.buffer().map_err(error::request)?;
+                Some(body)
+            }
+            body => body,
+        };
 
+        let endpoint = Endpoint {
+            url,
+            method,
+            body: body.map(SyncBody::into_async),
+            name,
+        };
         let params = match params {
-            SendableRequestParams::Value(params) => params,
-            SendableRequestParams::Builder { params, builder } => {
-                let params = params.into().inner.log_err(|e| {
-                    error!(
-                        "Elasticsearch Node Selection: correlation_id: '{}', error: '{:?}'",
-                        correlation_id, e
-                    )
-                })?;
+            SendableRequestParams::Value(params) => SendableRequestParams::Value(params),
+            SendableRequestParams::Builder {
+                params,
+                builder,
+                next,
+                pin,
+            } => SendableRequestParams::Builder {
+                params: PendingParams::new(params.into().inner.into_future()),
+                builder,
+                next: Arc::new(move || PendingParams::new(next().into().inner.into_future())),
+                pin,
+            },
+        };
 
-                builder.into_value(move || params)
-            }
+        let pending = self.inner.send(SendableRequest {
+            correlation_id,
+            inner: endpoint,
+            params,
+            _marker: PhantomData,
+        });
+
+        let res = self.block_on(pending.compat())?;
+
+        let node = res.node().clone();
+        let status = res.status();
+        let body = res
+            .into_raw()
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()));
+        // The connection is driven by the runtime, so the body can be read from the current thread
+        let body = SyncResponseBody {
+            body: StreamReader::new(Box::new(body)),
+            _runtime: self.runtime.clone(),
         };
 
-        let mut req = build_req(endpoint, params).log_err(|e| {
-            error!(
-                "Elasticsearch Request: correlation_id: '{}', error: '{:?}'",
-                correlation_id, e
-            )
-        })?;
-
-        if let Some(ref pre_send) = self.pre_send {
-            pre_send(&mut req)
-                .map_err(error::wrapped)
-                .map_err(error::request)
-                .log_err(|e| {
-                    error!(
-                        "Elasticsearch Request Pre-send: correlation_id: '{}', error: '{:?}'",
-                        correlation_id, e
-                    )
-                })?;
-        }
+        Ok(SyncResponseBuilder::new(node, status, body))
+    }
 
-        let req = build_reqwest(&self.http, req)
-            .build()
-            .map_err(error::request)?;
-
-        let res = match self.http.execute(req).map_err(error::request) {
-            Ok(res) => {
-                info!(
-                    "Elasticsearch Response: correlation_id: '{}', status: '{}'",
-                    correlation_id,
-                    res.status()
-                );
-                res
-            }
-            Err(e) => {
-                error!(
-                    "Elasticsearch Response: correlation_id: '{}', error: '{:?}'",
-                    correlation_id, e
-                );
-                Err(e)?
+    fn next_params(addresses: &NodeAddresses<Self>) -> Self::Params {
+        addresses.next_sync()
+    }
+}
+
+/**
+# Cluster info
+*/
+impl Client<SyncSender> {
+    /**
+    Get the version and distribution of the cluster.
+
+    The cluster info is only available when the client was built with [`check_cluster`][check_cluster] and has sent a request.
+    See the [`cluster_info`][cluster-info-mod] module for more details.
+
+    [check_cluster]: struct.SyncClientBuilder.html#method.check_cluster
+    [cluster-info-mod]: sender/cluster_info/index.html
+    */
+    pub fn cluster_info(&self) -> Option<ClusterInfo> {
+        self.sender.inner.cluster.info()
+    }
+}
+
+impl<TSender> NodeAddresses<TSender>
+where
+    TSender: Sender<Response = Result<SyncResponseBuilder, Error>>,
+    DefaultBody: Into<TSender::Body>,
+    RequestParams: Into<TSender::Params>,
+{
+    /**
+    Get the parameters for the next synchronous request.
+
+    If the nodes are sniffed then the sender is used to refresh them when they're stale.
+    */
+    pub fn next_sync(&self) -> Params {
+        let params = match self.inner {
+            NodeAddressesInner::Static(ref nodes) => nodes.next(),
+            NodeAddressesInner::Sniffed(ref sniffer) => sniffer.next_sync(),
+        };
+
+        Params::new(params.map(|params| self.apply_defaults(params)))
+    }
+}
+
+impl<TEndpoint, TParams, TBody> SendableRequest<TEndpoint, TParams, TBody>
+where
+    TEndpoint: Into<Endpoint<'static, TBody>>,
+    TBody: Into<SyncBody>,
+    TParams: Into<Params>,
+{
+    /**
+    Resolve the parameters for a request and build a synchronous raw http request.
+
+    The address of the node the request should be sent to is returned along with the raw request.
+    This method is used by custom [`Sender`][Sender] implementations.
+
+    [Sender]: trait.Sender.html
+    */
+    pub fn into_http_request(self) -> Result<(NodeAddress, SyncHttpRequest), Error> {
+        let params = match self.params {
+            SendableRequestParams::Value(params) => params,
+            SendableRequestParams::Builder {
+                params, builder, ..
+            } => {
+                let params = params.into().inner?;
+
+                builder.into_value(move || params)
             }
         };
 
-        sync_response(res)
+        let node = params.get_node_address().clone();
+        let req = build_req(self.inner.into(), params, None)?;
+
+        Ok((node, req))
     }
 }
 
-impl NextParams for NodeAddresses<SyncSender> {
-    type Params = Params;
+impl NodeAddresses<SyncSender> {
+    /**
+    Resolve a set of request parameters up-front so they can be shared by multiple requests.
 
-    fn next(&self) -> Self::Params {
-        match self.inner {
-            NodeAddressesInner::Static(ref nodes) => Params::new(nodes.next()),
-            NodeAddressesInner::Sniffed(ref sniffer) => Params::new(sniffer.next()),
+    A node is only selected if the builder doesn't already contain a complete set of parameters.
+    */
+    pub(crate) fn resolve(
+        &self,
+        builder: SharedFluentBuilder<RequestParams>,
+    ) -> Result<RequestParams, Error> {
+        match builder.try_into_value() {
+            TryIntoValue::Value(params) => Ok(params),
+            TryIntoValue::Builder(builder) => {
+                let params = self.next().inner?;
+
+                Ok(builder.into_value(move || params))
+            }
         }
     }
 }
@@ -183,7 +421,8 @@ pub struct Params {
 }
 
 impl Params {
-    fn new(res: Result<RequestParams, Error>) -> Self {
+    /** Wrap a set of request parameters, or an error selecting a node. */
+    pub fn new(res: Result<RequestParams, Error>) -> Self {
         Params { inner: res }
     }
 }
@@ -198,56 +437,58 @@ impl From<RequestParams> for Params {
 fn build_req(
     endpoint: Endpoint<impl Into<SyncBody>>,
     params: RequestParams,
+    compression: Option<RequestCompression>,
 ) -> Result<SyncHttpRequest, Error> {
-    let endpoint = SyncHttpRequest {
+    let mut endpoint = SyncHttpRequest {
         url: Url::parse(&build_url(&endpoint.url, &params)).map_err(error::request)?,
         method: endpoint.method,
         headers: params.get_headers(),
         body: endpoint.body.map(|body| body.into()),
     };
 
+    if let Some(compression) = compression {
+        compression.compress(&mut endpoint)?;
+    }
+
     Ok(endpoint)
 }
 
-/** Build a synchronous `reqwest::RequestBuilder` from an Elasticsearch request. */
-fn build_reqwest(client: &SyncHttpClient, req: SyncHttpRequest) -> SyncHttpRequestBuilder {
-    let SyncHttpRequest {
-        url,
-        method,
-        headers,
-        body,
-        ..
-    } = req;
+/**
+Call a function that tweaks a synchronous request on an asynchronous request.
 
-    let method = build_reqwest_method(method);
+The request's body is converted without copying it if it's buffered.
+*/
+fn pre_send_sync(
+    pre_send: &SyncPreSend,
+    req: &mut AsyncHttpRequest,
+) -> Result<(), Box<StdError + Send + Sync>> {
+    let mut sync_req = SyncHttpRequest {
+        url: req.url.clone(),
+        method: req.method.clone(),
+        headers: req.headers.clone(),
+        body: req.body.take().map(AsyncBody::into_sync),
+    };
 
-    let mut req = client.request(method, url);
-    {
-        req = req.headers((&*headers).clone());
+    let res = pre_send(&mut sync_req);
 
-        if let Some(body) = body {
-            req = req.body(body.into_inner());
-        }
-    }
+    req.url = sync_req.url;
+    req.method = sync_req.method;
+    req.headers = sync_req.headers;
+    req.body = sync_req.body.map(SyncBody::into_async);
 
-    req
+    res
 }
 
 /** A builder for a syncronous client. */
 pub struct SyncClientBuilder {
-    http: Option<SyncHttpClient>,
-    nodes: NodeAddressesBuilder,
-    params: SharedFluentBuilder<PreRequestParams>,
-    pre_send: Option<
-        Arc<
-            Fn(&mut SyncHttpRequest) -> Result<(), Box<StdError + Send + Sync>>
-                + Send
-                + Sync
-                + 'static,
-        >,
-    >,
+    inner: AsyncClientBuilder,
+    pre_send: Option<SyncPreSend>,
+    worker_threads: usize,
 }
 
+/** The default number of worker threads on a synchronous client's runtime. */
+const DEFAULT_WORKER_THREADS: usize = 2;
+
 impl Default for SyncClientBuilder {
     fn default() -> Self {
         SyncClientBuilder::new()
@@ -266,10 +507,9 @@ impl SyncClientBuilder {
     */
     pub fn new() -> Self {
         SyncClientBuilder {
-            http: None,
-            nodes: NodeAddressesBuilder::default(),
-            params: SharedFluentBuilder::new(),
+            inner: AsyncClientBuilder::new(),
             pre_send: None,
+            worker_threads: DEFAULT_WORKER_THREADS,
         }
     }
 
@@ -278,10 +518,9 @@ impl SyncClientBuilder {
     */
     pub fn from_params(params: PreRequestParams) -> Self {
         SyncClientBuilder {
-            http: None,
-            nodes: NodeAddressesBuilder::default(),
-            params: SharedFluentBuilder::new().value(params),
+            inner: AsyncClientBuilder::from_params(params),
             pre_send: None,
+            worker_threads: DEFAULT_WORKER_THREADS,
         }
     }
 
@@ -300,8 +539,33 @@ impl SyncClientBuilder {
         I: IntoIterator<Item = S>,
         S: Into<NodeAddress>,
     {
-        let nodes = nodes.into_iter().map(|address| address.into()).collect();
-        self.nodes = NodeAddressesBuilder::Static(nodes);
+        self.inner = self.inner.static_nodes(nodes);
+
+        self
+    }
+
+    /**
+    Specify an Elastic Cloud id to send requests to.
+
+    The cloud id is decoded into the address of the deployment's Elasticsearch instance.
+    If the cloud id is invalid then building the client will fail.
+    See the [`cloud_id`][cloud-id-mod] module for more details.
+
+    # Examples
+
+    Connect to an Elastic Cloud deployment using a username and password:
+
+    ```
+    # use elastic::prelude::*;
+    let builder = SyncClientBuilder::new()
+        .cloud_id("my-deployment:dXMtZWFzdC0xLmF3cy5mb3VuZC5pbyRjZWM2ZjI2MWE3NGJmMjRjZTMzYmI4ODExYjg0Mjk0ZiRjNmMyY2E2ZDA0MjI0OWFmMGNjN2Q3YTllOTYyNTc0Mw==")
+        .auth(Credentials::basic("elastic", "changeme"));
+    ```
+
+    [cloud-id-mod]: cloud_id/index.html
+    */
+    pub fn cloud_id(mut self, cloud_id: &str) -> Self {
+        self.inner = self.inner.cloud_id(cloud_id);
 
         self
     }
@@ -320,7 +584,7 @@ impl SyncClientBuilder {
     ```
     */
     pub fn sniff_nodes(mut self, builder: impl Into<SniffedNodesBuilder>) -> Self {
-        self.nodes = self.nodes.sniff_nodes(builder.into());
+        self.inner = self.inner.sniff_nodes(builder);
 
         self
     }
@@ -345,7 +609,34 @@ impl SyncClientBuilder {
         address: impl Into<NodeAddress>,
         builder: impl Fn(SniffedNodesBuilder) -> SniffedNodesBuilder + Send + 'static,
     ) -> Self {
-        self.nodes = self.nodes.sniff_nodes_fluent(address.into(), builder);
+        self.inner = self.inner.sniff_nodes_fluent(address, builder);
+
+        self
+    }
+
+    /**
+    Specify a strategy for load balancing requests between nodes.
+
+    The strategy is used for both static and sniffed nodes.
+    By default, requests are load balanced using a round-robin strategy.
+    See the [`static_nodes`][static-nodes-mod] module for the strategies that are available.
+
+    # Examples
+
+    Send requests to the node with the fewest in-flight requests:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::static_nodes::LeastInFlight;
+    let builder = SyncClientBuilder::new()
+        .static_nodes(vec!["http://a:9200", "http://b:9200"])
+        .load_balancing(LeastInFlight::default());
+    ```
+
+    [static-nodes-mod]: static_nodes/index.html
+    */
+    pub fn load_balancing(mut self, strategy: impl Strategy + 'static) -> Self {
+        self.inner = self.inner.load_balancing(strategy);
 
         self
     }
@@ -368,7 +659,7 @@ impl SyncClientBuilder {
         mut self,
         builder: impl Fn(PreRequestParams) -> PreRequestParams + Send + 'static,
     ) -> Self {
-        self.params = self.params.fluent(builder).shared();
+        self.inner = self.inner.params_fluent(builder);
 
         self
     }
@@ -388,79 +679,1514 @@ impl SyncClientBuilder {
     ```
     */
     pub fn params(mut self, params: impl Into<PreRequestParams>) -> Self {
-        self.params = self.params.value(params.into());
+        self.inner = self.inner.params(params);
 
         self
     }
 
-    /** Use the given `reqwest::Client` for sending requests. */
-    pub fn http_client(mut self, client: SyncHttpClient) -> Self {
-        self.http = Some(client);
+    /**
+    Specify a header to send with every request.
+
+    Default headers are applied over any headers in the default request parameters, and headers set on a request are applied over them.
+
+    # Examples
+
+    Send a custom header with every request:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::http::header::{HeaderName, HeaderValue};
+    let builder = SyncClientBuilder::new()
+        .default_header(
+            HeaderName::from_static("x-tenant"),
+            HeaderValue::from_static("tenant123"));
+    ```
+    */
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.inner = self.inner.default_header(name, value);
 
         self
     }
 
     /**
-    Specify a function to tweak a raw request before sending.
+    Specify an `X-Opaque-Id` to send with every request.
 
-    This function will be applied to all outgoing requests and gives you the chance to perform operations the require the complete raw request,
-    such as request singing.
-    Prefer the `params` method on the client or individual requests where possible.
+    Elasticsearch includes the id in slow log and tasks API entries, so they can be attributed to the service that sent the request.
+    The id is also used to correlate log records for requests.
+    Control characters aren't valid in a header, so they're removed from the id.
+    See the [`logging`][logging-mod] module for more details.
+
+    # Examples
+
+    ```
+    # use elastic::prelude::*;
+    let builder = SyncClientBuilder::new()
+        .opaque_id("my-service");
+    ```
+
+    [logging-mod]: logging/index.html
     */
-    pub fn pre_send_raw(
-        mut self,
-        pre_send: impl Fn(&mut SyncHttpRequest) -> Result<(), Box<StdError + Send + Sync>>
-            + Send
-            + Sync
-            + 'static,
-    ) -> Self {
-        self.pre_send = Some(Arc::new(pre_send));
+    pub fn opaque_id(mut self, id: impl AsRef<str>) -> Self {
+        self.inner = self.inner.opaque_id(id);
 
         self
     }
 
     /**
-    Construct a [`SyncClient`][SyncClient] from this builder.
+    Specify TLS options for connections to nodes.
 
-    [SyncClient]: type.SyncClient.html
+    TLS options can't be combined with a custom http client set using [`http_client`][http_client].
+    See the [`tls`][tls-mod] module for more details.
+
+    # Examples
+
+    Connect to a development cluster with a self-signed certificate:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::tls::Tls;
+    let builder = SyncClientBuilder::new()
+        .static_node("https://localhost:9200")
+        .tls(Tls::new().danger_accept_invalid_certs(true));
+    ```
+
+    [http_client]: #method.http_client
+    [tls-mod]: tls/index.html
     */
-    pub fn build(self) -> Result<SyncClient, Error> {
-        let http = self
-            .http
-            .map(Ok)
-            .unwrap_or_else(|| SyncHttpClientBuilder::new().build())
-            .map_err(error::build)?;
+    pub fn tls(mut self, tls: Tls) -> Self {
+        self.inner = self.inner.tls(tls);
 
-        let params = self.params.into_value(|| PreRequestParams::default());
-        let sender = SyncSender {
-            http,
-            pre_send: self.pre_send,
-        };
+        self
+    }
+
+    /**
+    Send requests through a HTTP proxy.
 
-        let addresses = self.nodes.build(params, sender.clone());
+    Proxy options can't be combined with a custom http client set using [`http_client`][http_client].
+    See the [`proxy`][proxy-mod] module for more details.
 
-        Ok(SyncClient {
-            sender: sender,
-            addresses: addresses,
-        })
+    # Examples
+
+    Send requests through the proxy in the `HTTPS_PROXY` environment variable:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::proxy::Proxy;
+    let builder = SyncClientBuilder::new()
+        .static_node("https://elasticsearch:9200")
+        .proxy(Proxy::from_env());
+    ```
+
+    [http_client]: #method.http_client
+    [proxy-mod]: proxy/index.html
+    */
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.inner = self.inner.proxy(proxy);
+
+        self
     }
-}
 
-trait LogErr<E> {
-    fn log_err<F>(self, log: F) -> Self
-    where
-        F: FnOnce(&E);
-}
+    /**
+    Tune the pool of connections to nodes.
 
-impl<T, E> LogErr<E> for Result<T, E> {
-    fn log_err<F>(self, log: F) -> Self
-    where
-        F: FnOnce(&E),
-    {
-        if let Err(ref e) = self {
-            log(e);
-        }
+    Connection pool options can't be combined with a custom http client set using [`http_client`][http_client].
+    See the [`pool`][pool-mod] module for more details.
+
+    # Examples
+
+    Keep at most 32 idle connections for each node, and wait at most 2 seconds to connect:
+
+    ```
+    # use std::time::Duration;
+    # use elastic::prelude::*;
+    # use elastic::client::sender::pool::ConnectionPool;
+    let builder = SyncClientBuilder::new()
+        .connection_pool(
+            ConnectionPool::new()
+                .max_idle_per_host(32)
+                .connect_timeout(Duration::from_secs(2)),
+        );
+    ```
+
+    [http_client]: #method.http_client
+    [pool-mod]: pool/index.html
+    */
+    pub fn connection_pool(mut self, pool: ConnectionPool) -> Self {
+        self.inner = self.inner.connection_pool(pool);
+
+        self
+    }
+
+    /**
+    Gzip the bodies of requests that are larger than a minimum size.
+
+    See the [`compression`][compression-mod] module for more details.
+
+    # Examples
+
+    Compress request bodies that are at least 1KiB:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::compression::RequestCompression;
+    let builder = SyncClientBuilder::new()
+        .compress_requests(RequestCompression::new());
+    ```
+
+    [compression-mod]: compression/index.html
+    */
+    pub fn compress_requests(mut self, compression: RequestCompression) -> Self {
+        self.inner = self.inner.compress_requests(compression);
+
+        self
+    }
+
+    /**
+    Specify whether to ask for gzipped responses and decompress them.
+
+    Responses are decompressed by default.
+    This option can't be combined with a custom http client set using [`http_client`][http_client].
+    See the [`compression`][compression-mod] module for more details.
+
+    # Examples
+
+    Ask for uncompressed responses:
+
+    ```
+    # use elastic::prelude::*;
+    let builder = SyncClientBuilder::new()
+        .decompress_responses(false);
+    ```
+
+    [http_client]: #method.http_client
+    [compression-mod]: compression/index.html
+    */
+    pub fn decompress_responses(mut self, decompress: bool) -> Self {
+        self.inner = self.inner.decompress_responses(decompress);
 
         self
     }
+
+    /**
+    Ping the cluster before sending the first request to check its version and distribution.
+
+    The cluster info can be read using the client's [`cluster_info`][cluster_info] method once the ping has completed.
+    See the [`cluster_info`][cluster-info-mod] module for more details.
+
+    # Examples
+
+    ```
+    # use elastic::prelude::*;
+    let builder = SyncClientBuilder::new()
+        .check_cluster(true);
+    ```
+
+    [cluster_info]: ../struct.Client.html#method.cluster_info
+    [cluster-info-mod]: cluster_info/index.html
+    */
+    pub fn check_cluster(mut self, check: bool) -> Self {
+        self.inner = self.inner.check_cluster(check);
+
+        self
+    }
+
+    /**
+    Send follow-up requests for a scroll or point-in-time search to the node that served the search.
+
+    By default, follow-up requests are sent to whichever node the client selects.
+    See the [`affinity`][affinity-mod] module for more details.
+
+    # Examples
+
+    ```
+    # use elastic::prelude::*;
+    let builder = SyncClientBuilder::new()
+        .node_affinity(true);
+    ```
+
+    [affinity-mod]: affinity/index.html
+    */
+    pub fn node_affinity(mut self, affinity: bool) -> Self {
+        self.inner = self.inner.node_affinity(affinity);
+
+        self
+    }
+
+    /**
+    Sign requests using AWS Signature Version 4.
+
+    This method is only available when the `aws-sigv4` feature is enabled.
+    See the [`aws_sigv4`][aws-sigv4-mod] module for more details.
+
+    # Examples
+
+    Sign requests to a domain in `us-east-1`:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::aws_sigv4::AwsSigV4;
+    let builder = SyncClientBuilder::new()
+        .static_node("https://search-my-domain.us-east-1.es.amazonaws.com")
+        .aws_sigv4(AwsSigV4::new("us-east-1"));
+    ```
+
+    [aws-sigv4-mod]: aws_sigv4/index.html
+    */
+    #[cfg(feature = "aws-sigv4")]
+    pub fn aws_sigv4(mut self, signer: AwsSigV4) -> Self {
+        self.inner = self.inner.aws_sigv4(signer);
+
+        self
+    }
+
+    /**
+    Answer requests using canned responses from a mock instead of sending them to nodes.
+
+    This method is only available when the `testing` feature is enabled.
+    See the [`mock`][mock-mod] module for more details.
+
+    # Examples
+
+    Respond to ping requests:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::mock::{MockSender, MockResponse};
+    # use elastic::http::{Method, StatusCode};
+    let mock = MockSender::new();
+    mock.respond(Method::GET, "/", MockResponse::new(StatusCode::OK));
+
+    let builder = SyncClientBuilder::new()
+        .mock(mock.clone());
+    ```
+
+    [mock-mod]: mock/index.html
+    */
+    #[cfg(feature = "testing")]
+    pub fn mock(mut self, mock: MockSender) -> Self {
+        self.inner = self.inner.mock(mock);
+
+        self
+    }
+
+    /**
+    Use the given asynchronous `reqwest::Client` for sending requests.
+
+    Requests are sent by the same asynchronous http client as an [`AsyncClient`][AsyncClient] uses,
+    so a http client can be shared with an `AsyncClient` to send requests from both clients using one connection pool.
+
+    # Breaking changes
+
+    This method used to take a synchronous `reqwest::Client`.
+    It now takes an asynchronous `reqwest` 0.11 `reqwest::Client` instead, because a `reqwest::blocking::Client` can't send requests on the client's runtime.
+    A blocking client that was configured for a `SyncClient` should be configured with `reqwest::ClientBuilder` instead.
+
+    [AsyncClient]: type.AsyncClient.html
+    */
+    pub fn http_client(mut self, client: AsyncHttpClient) -> Self {
+        self.inner = self.inner.http_client(client);
+
+        self
+    }
+
+    /**
+    Specify a function to tweak a raw request before sending.
+
+    This function will be applied to all outgoing requests and gives you the chance to perform operations the require the complete raw request,
+    such as request singing.
+    Prefer the `params` method on the client or individual requests where possible.
+
+    The function is called on the client's runtime, so it shouldn't block for long.
+    */
+    pub fn pre_send_raw(
+        mut self,
+        pre_send: impl Fn(&mut SyncHttpRequest) -> Result<(), Box<StdError + Send + Sync>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.pre_send = Some(Arc::new(pre_send));
+
+        self
+    }
+
+    /**
+    Specify credentials to authenticate every request sent by the client.
+
+    The client sets the `Authorization` header on requests, unless they already have one.
+    See the [`auth`][auth-mod] module for more details.
+
+    # Examples
+
+    Authenticate using a username and password:
+
+    ```
+    # use elastic::prelude::*;
+    let builder = SyncClientBuilder::new()
+        .auth(Credentials::basic("elastic", "changeme"));
+    ```
+
+    [auth-mod]: auth/index.html
+    */
+    pub fn auth(mut self, credentials: Credentials) -> Self {
+        self.inner = self.inner.auth(credentials);
+
+        self
+    }
+
+    /**
+    Specify a callback that gets new credentials when a request receives a `401 Unauthorized` response.
+
+    The new credentials are used for requests sent after the callback's future completes.
+    The callback is only used if credentials have been specified with [`auth`][auth].
+    See the [`auth`][auth-mod] module for more details.
+
+    # Examples
+
+    Fetch a new bearer token when the current one is rejected:
+
+    ```
+    # extern crate futures;
+    # extern crate elastic;
+    # use futures::Future;
+    # use elastic::prelude::*;
+    # fn main() {
+    # fn fetch_token() -> Result<String, Box<::std::error::Error + Send + Sync>> { Ok("token".to_owned()) }
+    let builder = SyncClientBuilder::new()
+        .auth(Credentials::bearer("initial-token"))
+        .auth_refresh(|| {
+            Box::new(futures::future::result(fetch_token()).map(Credentials::bearer))
+        });
+    # }
+    ```
+
+    [auth]: #method.auth
+    [auth-mod]: auth/index.html
+    */
+    pub fn auth_refresh(
+        mut self,
+        refresh: impl Fn() -> Box<Future<Item = Credentials, Error = Box<StdError + Send + Sync>> + Send>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.inner = self.inner.auth_refresh(refresh);
+
+        self
+    }
+
+    /**
+    Add a middleware that can inspect and modify every request sent by the client.
+
+    Middleware is called in the order it's added.
+    See the [`middleware`][middleware-mod] module for more details.
+
+    # Examples
+
+    Add a header to every request:
+
+    ```
+    # use std::error::Error;
+    # use elastic::prelude::*;
+    # use elastic::http::header::{HeaderName, HeaderValue};
+    # use elastic::client::sender::middleware::{Middleware, MiddlewareRequest};
+    struct Tenant;
+
+    impl Middleware for Tenant {
+        fn on_request(&self, req: &mut MiddlewareRequest) -> Result<(), Box<Error + Send + Sync>> {
+            req.header(HeaderName::from_static("x-tenant"), HeaderValue::from_static("a"));
+
+            Ok(())
+        }
+    }
+
+    let builder = SyncClientBuilder::new()
+        .middleware(Tenant);
+    ```
+
+    [middleware-mod]: middleware/index.html
+    */
+    pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.inner = self.inner.middleware(middleware);
+
+        self
+    }
+
+    /**
+    Specify an observer that's notified about every request sent by the client.
+
+    The observer is called once a response has been received or the request has failed.
+    See the [`observer`][observer-mod] module for more details.
+
+    # Examples
+
+    Collect metrics in the Prometheus text format:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::observer::PrometheusObserver;
+    let metrics = PrometheusObserver::new();
+
+    let builder = SyncClientBuilder::new()
+        .observer(metrics.clone());
+    ```
+
+    [observer-mod]: observer/index.html
+    */
+    pub fn observer(mut self, observer: impl Observer + 'static) -> Self {
+        self.inner = self.inner.observer(observer);
+
+        self
+    }
+
+    /**
+    Specify a policy for temporarily removing failing nodes from rotation.
+
+    Dead node detection is disabled by default, so requests are load balanced over every node even if they're failing.
+    See the [`dead_nodes`][dead-nodes-mod] module for more details.
+
+    # Examples
+
+    Remove a node from rotation after 3 consecutive failures:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::dead_nodes::DeadNodes;
+    let builder = SyncClientBuilder::new()
+        .static_nodes(vec!["http://a:9200", "http://b:9200"])
+        .dead_nodes(DeadNodes::new().failure_threshold(3));
+    ```
+
+    [dead-nodes-mod]: dead_nodes/index.html
+    */
+    pub fn dead_nodes(mut self, dead_nodes: DeadNodes) -> Self {
+        self.inner = self.inner.dead_nodes(dead_nodes);
+
+        self
+    }
+
+    /**
+    Don't remove failing nodes from rotation.
+
+    This removes any policy set by [`dead_nodes`](#method.dead_nodes).
+    Requests are load balanced over every node, even if they're failing.
+    */
+    pub fn disable_dead_nodes(mut self) -> Self {
+        self.inner = self.inner.disable_dead_nodes();
+
+        self
+    }
+
+    /**
+    Specify a policy for retrying requests that fail because a node is temporarily unavailable.
+
+    By default, requests aren't retried.
+    See the [`retry`][retry-mod] module for more details.
+
+    # Examples
+
+    Retry idempotent requests using the default policy:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::retry::RetryPolicy;
+    let builder = SyncClientBuilder::new()
+        .retry(RetryPolicy::default());
+    ```
+
+    [retry-mod]: retry/index.html
+    */
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.inner = self.inner.retry(retry);
+
+        self
+    }
+
+    /**
+    Log requests that take longer than the given threshold to complete.
+
+    Slow requests are logged at the `warn` level with their correlation id, endpoint, url and latency.
+    See the [`logging`][logging-mod] module for more details.
+
+    # Examples
+
+    Log requests that take longer than a second:
+
+    ```
+    # use std::time::Duration;
+    # use elastic::prelude::*;
+    let builder = SyncClientBuilder::new()
+        .log_slow_requests(Duration::from_secs(1));
+    ```
+
+    [logging-mod]: logging/index.html
+    */
+    pub fn log_slow_requests(mut self, threshold: Duration) -> Self {
+        self.inner = self.inner.log_slow_requests(threshold);
+
+        self
+    }
+
+    /**
+    Specify a limit on the number of requests sent per second or in flight at once.
+
+    By default, requests aren't limited.
+    See the [`rate_limit`][rate-limit-mod] module for more details.
+
+    # Examples
+
+    Send at most 100 requests per second:
+
+    ```
+    # use elastic::prelude::*;
+    # use elastic::client::sender::rate_limit::RateLimit;
+    let builder = SyncClientBuilder::new()
+        .rate_limit(RateLimit::new().requests_per_second(100));
+    ```
+
+    [rate-limit-mod]: rate_limit/index.html
+    */
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.inner = self.inner.rate_limit(rate_limit);
+
+        self
+    }
+
+    /**
+    Specify the number of worker threads on the runtime that requests are sent from.
+
+    Each call to `build` starts a new runtime, which is shared by clones of the client it returns.
+    Sending a request blocks the calling thread rather than a worker, so a small number of workers can serve many threads.
+    By default, the runtime has `2` worker threads.
+
+    # Examples
+
+    Send requests from a runtime with a single worker thread:
+
+    ```
+    # use elastic::prelude::*;
+    let builder = SyncClientBuilder::new().worker_threads(1);
+    ```
+    */
+    pub fn worker_threads(mut self, threads: usize) -> Self {
+        self.worker_threads = threads;
+
+        self
+    }
+
+    /**
+    Construct a [`SyncClient`][SyncClient] from this builder.
+
+    [SyncClient]: type.SyncClient.html
+    */
+    pub fn build(self) -> Result<SyncClient, Error> {
+        let mut inner = self.inner;
+
+        if let Some(pre_send) = self.pre_send {
+            inner = inner
+                .pre_send_raw(move |req| Box::new(future::result(pre_send_sync(&pre_send, req))));
+        }
+
+        if self.worker_threads == 0 {
+            return Err(error::build(error::message(
+                "a synchronous client's runtime needs at least one worker thread",
+            )));
+        }
+
+        let runtime = RuntimeBuilder::new_multi_thread()
+            .worker_threads(self.worker_threads)
+            .thread_name("elastic-sync")
+            .enable_all()
+            .build()
+            .map_err(error::build)?;
+        let runtime = Arc::new(SyncRuntime(Some(runtime)));
+
+        inner.build_with(|sender| SyncSender {
+            inner: sender,
+            runtime: runtime.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::Mutex,
+        thread,
+    };
+
+    use serde_json::Value;
+
+    use super::*;
+    use client::{
+        requests::PingRequest,
+        sender::{
+            middleware::MiddlewareRequest,
+            observer::{
+                RequestErrorKind,
+                RequestEvent,
+                RequestOutcome,
+            },
+        },
+    };
+    #[cfg(feature = "testing")]
+    use client::sender::static_nodes::{
+        RoundRobin,
+        StrategyError,
+    };
+    use http::{
+        Method,
+        StatusCode,
+    };
+
+    #[derive(Default)]
+    struct Outcomes(Mutex<Vec<RequestOutcome>>);
+
+    // A round-robin strategy that records the nodes requests are sent to
+    #[cfg(feature = "testing")]
+    #[derive(Default)]
+    struct SentTo(RoundRobin, Arc<Mutex<Vec<NodeAddress>>>);
+
+    #[cfg(feature = "testing")]
+    impl Strategy for SentTo {
+        fn try_next(&self, nodes: &[NodeAddress]) -> Result<NodeAddress, StrategyError> {
+            self.0.try_next(nodes)
+        }
+
+        fn on_send(&self, node: &NodeAddress) {
+            self.1.lock().expect("lock poisoned").push(node.clone());
+        }
+    }
+
+    impl Middleware for Outcomes {
+        fn on_response(&self, event: &RequestEvent) {
+            self.0.lock().expect("lock poisoned").push(event.outcome());
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn retry_connection_errors() {
+        use client::sender::mock::{
+            MockResponse,
+            MockSender,
+        };
+
+        let mock = MockSender::new();
+        mock.respond(
+            Method::GET,
+            "/",
+            MockResponse::error(io::ErrorKind::ConnectionRefused),
+        );
+
+        let client = SyncClientBuilder::new()
+            .mock(mock.clone())
+            .retry(
+                RetryPolicy::new()
+                    .max_attempts(3)
+                    .initial_backoff(Duration::from_millis(1)),
+            )
+            .build()
+            .unwrap();
+
+        match client.request(PingRequest::new()).send() {
+            Err(Error::Client(err)) => assert_eq!(2, err.retries()),
+            _ => panic!("expected a client error"),
+        }
+
+        assert_eq!(3, mock.requests().len());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn retry_connection_errors_on_next_node() {
+        use client::sender::mock::{
+            MockResponse,
+            MockSender,
+        };
+
+        let mock = MockSender::new();
+        mock.respond(
+            Method::GET,
+            "/",
+            MockResponse::error(io::ErrorKind::ConnectionRefused),
+        );
+
+        let strategy = SentTo::default();
+        let sent = strategy.1.clone();
+
+        let client = SyncClientBuilder::new()
+            .static_nodes(vec!["http://a:9200", "http://b:9200"])
+            .load_balancing(strategy)
+            .mock(mock.clone())
+            .retry(
+                RetryPolicy::new()
+                    .max_attempts(3)
+                    .initial_backoff(Duration::from_millis(1)),
+            )
+            .build()
+            .unwrap();
+
+        assert!(client.request(PingRequest::new()).send().is_err());
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(3, sent.len());
+        assert_ne!(sent[0], sent[1]);
+        assert_ne!(sent[1], sent[2]);
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn retry_connect_timeout_on_next_node() {
+        use client::sender::mock::{
+            MockResponse,
+            MockSender,
+        };
+
+        let mock = MockSender::new();
+        mock.respond(
+            Method::GET,
+            "/",
+            MockResponse::error(io::ErrorKind::TimedOut),
+        );
+        mock.respond(Method::GET, "/", MockResponse::new(StatusCode::OK));
+
+        let strategy = SentTo::default();
+        let sent = strategy.1.clone();
+
+        let client = SyncClientBuilder::new()
+            .static_nodes(vec!["http://a:9200", "http://b:9200"])
+            .load_balancing(strategy)
+            .mock(mock.clone())
+            .retry(
+                RetryPolicy::new()
+                    .max_attempts(2)
+                    .initial_backoff(Duration::from_millis(1)),
+            )
+            .build()
+            .unwrap();
+
+        let res = client.request(PingRequest::new()).send().unwrap();
+
+        assert_eq!(StatusCode::OK, res.status());
+        assert_eq!(NodeAddress::from("http://b:9200"), *res.node());
+        assert_eq!(
+            vec![
+                NodeAddress::from("http://a:9200"),
+                NodeAddress::from("http://b:9200"),
+            ],
+            *sent.lock().unwrap()
+        );
+    }
+
+    #[test]
+    fn send_within_runtime_is_err() {
+        let client = SyncClientBuilder::new()
+            .static_node("http://127.0.0.1:1")
+            .build()
+            .unwrap();
+
+        let runtime = RuntimeBuilder::new_current_thread().build().unwrap();
+
+        // Blocking on the request would stop the runtime from driving anything else
+        // The client is also dropped within the runtime
+        let res = runtime
+            .block_on(
+                future::lazy(move || {
+                    Ok::<_, ()>(client.request(PingRequest::new()).send().is_err())
+                })
+                .compat(),
+            )
+            .unwrap();
+
+        assert!(res);
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn send_within_multi_thread_runtime_is_ok() {
+        use client::sender::mock::{
+            MockResponse,
+            MockSender,
+        };
+
+        let mock = MockSender::new();
+        mock.respond(Method::GET, "/", MockResponse::new(StatusCode::OK));
+
+        let client = SyncClientBuilder::new().mock(mock.clone()).build().unwrap();
+
+        let runtime = RuntimeBuilder::new_multi_thread().build().unwrap();
+
+        // Send from both a worker thread and the blocking thread pool
+        let worker = {
+            let client = client.clone();
+            runtime.block_on(
+                runtime.spawn(
+                    future::lazy(move || {
+                        Ok::<_, ()>(client.request(PingRequest::new()).send().is_ok())
+                    })
+                    .compat(),
+                ),
+            )
+        };
+        let blocking = runtime.block_on(
+            runtime.spawn_blocking(move || client.request(PingRequest::new()).send().is_ok()),
+        );
+
+        assert!(worker.unwrap().unwrap());
+        assert!(blocking.unwrap());
+        assert_eq!(2, mock.requests().len());
+    }
+
+    #[test]
+    fn block_on_cancelled_future_is_err() {
+        let client = SyncClientBuilder::new().build().unwrap();
+
+        // The task is dropped without completing when it panics
+        let res = client
+            .sender
+            .block_on(future::lazy(|| -> Result<(), Error> {
+                panic!("explicit panic")
+            }));
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn zero_worker_threads_is_err() {
+        assert!(SyncClientBuilder::new().worker_threads(0).build().is_err());
+    }
+
+    #[test]
+    fn middleware_observes_errors() {
+        let outcomes = Arc::new(Outcomes::default());
+
+        let client = SyncClientBuilder::new()
+            .static_node("http://127.0.0.1:1")
+            .middleware(outcomes.clone())
+            .build()
+            .unwrap();
+
+        assert!(client.request(PingRequest::new()).send().is_err());
+
+        assert_eq!(
+            vec![RequestOutcome::Error(RequestErrorKind::Transport)],
+            *outcomes.0.lock().expect("lock poisoned")
+        );
+    }
+
+    #[test]
+    fn middleware_error_fails_request() {
+        struct Fail;
+
+        impl Middleware for Fail {
+            fn on_request(
+                &self,
+                _: &mut MiddlewareRequest,
+            ) -> Result<(), Box<StdError + Send + Sync>> {
+                Err("failed".into())
+            }
+        }
+
+        let outcomes = Arc::new(Outcomes::default());
+
+        let client = SyncClientBuilder::new()
+            .static_node("http://127.0.0.1:1")
+            .middleware(Fail)
+            .middleware(outcomes.clone())
+            .build()
+            .unwrap();
+
+        assert!(client.request(PingRequest::new()).send().is_err());
+
+        assert_eq!(
+            vec![RequestOutcome::Error(RequestErrorKind::Request)],
+            *outcomes.0.lock().expect("lock poisoned")
+        );
+    }
+
+    #[test]
+    fn cloud_id_invalid_fails_build() {
+        let builder = SyncClientBuilder::new().cloud_id("my-deployment:not base64");
+
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn tls_with_http_client_fails_build() {
+        let builder = SyncClientBuilder::new()
+            .http_client(AsyncHttpClient::new())
+            .tls(Tls::new().danger_accept_invalid_certs(true));
+
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn tls_invalid_certificate_fails_build() {
+        let builder =
+            SyncClientBuilder::new().tls(Tls::new().root_certificate_pem("not a certificate"));
+
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn decompress_gzipped_response() {
+        use flate2::{
+            write::GzEncoder,
+            Compression,
+        };
+        use std::{
+            io::{
+                Read,
+                Write,
+            },
+            net::TcpListener,
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let node = format!("http://{}", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut req = [0; 1024];
+            let len = stream.read(&mut req).unwrap();
+
+            let body = {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(br#"{"ok":true}"#).unwrap();
+                encoder.finish().unwrap()
+            };
+
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            )
+            .unwrap();
+            stream.write_all(&body).unwrap();
+
+            String::from_utf8_lossy(&req[..len]).to_lowercase()
+        });
+
+        let client = SyncClientBuilder::new().static_node(node).build().unwrap();
+
+        let mut body = String::new();
+        client
+            .request(PingRequest::new())
+            .send()
+            .unwrap()
+            .into_raw()
+            .read_to_string(&mut body)
+            .unwrap();
+
+        assert_eq!(r#"{"ok":true}"#, body);
+        assert!(server.join().unwrap().contains("accept-encoding: gzip"));
+    }
+
+    #[test]
+    fn decompress_with_http_client_fails_build() {
+        let builder = SyncClientBuilder::new()
+            .http_client(AsyncHttpClient::new())
+            .decompress_responses(false);
+
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn connection_pool_with_http_client_fails_build() {
+        let builder = SyncClientBuilder::new()
+            .http_client(AsyncHttpClient::new())
+            .connection_pool(ConnectionPool::new().max_idle_per_host(32));
+
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn proxy_with_http_client_fails_build() {
+        let builder = SyncClientBuilder::new()
+            .http_client(AsyncHttpClient::new())
+            .proxy(Proxy::new("http://proxy:3128"));
+
+        assert!(builder.build().is_err());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_retries_unavailable_responses() {
+        use client::sender::mock::{
+            MockResponse,
+            MockSender,
+        };
+
+        let mock = MockSender::new();
+        mock.respond(
+            Method::GET,
+            "/",
+            MockResponse::new(StatusCode::SERVICE_UNAVAILABLE),
+        );
+        mock.respond(
+            Method::GET,
+            "/",
+            MockResponse::new(StatusCode::OK).json(&json!({ "ok": true })),
+        );
+
+        let client = SyncClientBuilder::new()
+            .mock(mock.clone())
+            .retry(RetryPolicy::new().initial_backoff(Duration::from_millis(1)))
+            .build()
+            .unwrap();
+
+        let res = client.request(PingRequest::new()).send().unwrap();
+
+        assert_eq!(StatusCode::OK, res.status());
+        assert_eq!(json!({ "ok": true }), res.into_response::<Value>().unwrap());
+        assert_eq!(2, mock.requests().len());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_exhausted_retries_are_err() {
+        use client::sender::mock::{
+            MockResponse,
+            MockSender,
+        };
+
+        let mock = MockSender::new();
+        mock.respond(
+            Method::GET,
+            "/",
+            MockResponse::new(StatusCode::SERVICE_UNAVAILABLE),
+        );
+
+        let client = SyncClientBuilder::new()
+            .mock(mock.clone())
+            .retry(
+                RetryPolicy::new()
+                    .max_attempts(3)
+                    .initial_backoff(Duration::from_millis(1)),
+            )
+            .build()
+            .unwrap();
+
+        match client.request(PingRequest::new()).send() {
+            Err(Error::Client(err)) => assert_eq!(2, err.retries()),
+            _ => panic!("expected a client error"),
+        }
+        assert_eq!(3, mock.requests().len());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_check_cluster_pings_before_first_request() {
+        use client::sender::{
+            cluster_info::Distribution,
+            mock::{
+                MockResponse,
+                MockSender,
+            },
+        };
+
+        let mock = MockSender::new();
+        mock.respond(
+            Method::GET,
+            "/",
+            MockResponse::new(StatusCode::OK).json(&json!({
+                "name": "node",
+                "cluster_name": "cluster",
+                "tagline": "The OpenSearch Project: https://opensearch.org/",
+                "version": {
+                    "distribution": "opensearch",
+                    "number": "2.11.0",
+                    "build_hash": "abc",
+                    "build_date": "2023-10-13T02:55:55Z",
+                    "build_snapshot": false,
+                    "lucene_version": "9.7.0"
+                }
+            })),
+        );
+
+        let client = SyncClientBuilder::new()
+            .mock(mock.clone())
+            .check_cluster(true)
+            .build()
+            .unwrap();
+
+        assert!(client.cluster_info().is_none());
+
+        client.ping().send().unwrap();
+
+        let info = client.cluster_info().unwrap();
+        assert_eq!(Distribution::OpenSearch, info.distribution());
+        assert_eq!("2.11.0", info.version());
+        assert_eq!(2, mock.requests().len());
+
+        client.ping().send().unwrap();
+
+        assert_eq!(3, mock.requests().len());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_default_headers_are_overridden_by_requests() {
+        use client::sender::{
+            logging::X_OPAQUE_ID,
+            mock::{
+                MockResponse,
+                MockSender,
+            },
+        };
+
+        let mock = MockSender::new();
+        mock.respond(Method::GET, "/", MockResponse::new(StatusCode::OK));
+
+        let client = SyncClientBuilder::new()
+            .mock(mock.clone())
+            .default_header(
+                HeaderName::from_static("x-tenant"),
+                HeaderValue::from_static("tenant123"),
+            )
+            .opaque_id("my-service")
+            .build()
+            .unwrap();
+
+        client.request(PingRequest::new()).send().unwrap();
+        client
+            .request(PingRequest::new())
+            .params_fluent(|p| p.url_param("pretty", true))
+            .header(
+                HeaderName::from_static("x-tenant"),
+                HeaderValue::from_static("other"),
+            )
+            .opaque_id("my-service/job")
+            .send()
+            .unwrap();
+
+        let requests = mock.requests();
+
+        assert_eq!("tenant123", requests[0].headers()["x-tenant"]);
+        assert_eq!("my-service", requests[0].headers()[X_OPAQUE_ID]);
+
+        assert_eq!("other", requests[1].headers()["x-tenant"]);
+        assert_eq!("my-service/job", requests[1].headers()[X_OPAQUE_ID]);
+        assert_eq!(Some("pretty=true"), requests[1].url().query());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_rate_limit_rejects_requests() {
+        use client::sender::{
+            mock::{
+                MockResponse,
+                MockSender,
+            },
+            rate_limit::{
+                Overflow,
+                RateLimit,
+            },
+        };
+
+        let mock = MockSender::new();
+        mock.respond(Method::GET, "/", MockResponse::new(StatusCode::OK));
+
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let client = {
+            let errors = errors.clone();
+            SyncClientBuilder::new()
+                .mock(mock.clone())
+                .rate_limit(
+                    RateLimit::new()
+                        .requests_per_second(1)
+                        .overflow(Overflow::Error),
+                )
+                .observer(move |event: &RequestEvent| {
+                    if let RequestOutcome::Error(kind) = event.outcome() {
+                        errors.lock().unwrap().push(kind);
+                    }
+                })
+                .build()
+                .unwrap()
+        };
+
+        assert!(client.request(PingRequest::new()).send().is_ok());
+        assert!(client.request(PingRequest::new()).send().is_err());
+
+        assert_eq!(1, mock.requests().len());
+        assert_eq!(vec![RequestErrorKind::RateLimited], *errors.lock().unwrap());
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_scroll_requests_use_same_node() {
+        use client::{
+            requests::ScrollRequest,
+            sender::mock::{
+                MockResponse,
+                MockSender,
+            },
+        };
+
+        let page = json!({
+            "took": 1,
+            "timed_out": false,
+            "_shards": { "total": 1, "successful": 1, "failed": 0 },
+            "hits": { "total": 0, "max_score": null, "hits": [] },
+            "_scroll_id": "scroll-1"
+        });
+
+        let mock = MockSender::new();
+        mock.respond(
+            Method::POST,
+            "/myindex/_search",
+            MockResponse::new(StatusCode::OK).json(&page),
+        );
+        mock.respond(
+            Method::POST,
+            "/_search/scroll",
+            MockResponse::new(StatusCode::OK).json(&page),
+        );
+
+        let client = SyncClientBuilder::new()
+            .static_nodes(vec!["http://a:9200", "http://b:9200"])
+            .node_affinity(true)
+            .mock(mock.clone())
+            .build()
+            .unwrap();
+
+        client
+            .search::<Value>()
+            .index("myindex")
+            .params_fluent(|p| p.url_param("scroll", "1m"))
+            .send()
+            .unwrap();
+
+        for _ in 0..3 {
+            client
+                .request(ScrollRequest::new(
+                    json!({ "scroll": "1m", "scroll_id": "scroll-1" }).to_string(),
+                ))
+                .send()
+                .unwrap();
+        }
+
+        let hosts: Vec<_> = mock
+            .requests()
+            .iter()
+            .map(|req| req.url().host_str().unwrap().to_owned())
+            .collect();
+
+        assert_eq!(4, hosts.len());
+        assert!(hosts.iter().all(|host| *host == hosts[0]));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_scroll_requests_without_node_affinity_are_balanced() {
+        use client::{
+            requests::ScrollRequest,
+            sender::mock::{
+                MockResponse,
+                MockSender,
+            },
+        };
+
+        let page = json!({
+            "took": 1,
+            "timed_out": false,
+            "_shards": { "total": 1, "successful": 1, "failed": 0 },
+            "hits": { "total": 0, "max_score": null, "hits": [] },
+            "_scroll_id": "scroll-1"
+        });
+
+        let mock = MockSender::new();
+        mock.respond(
+            Method::POST,
+            "/myindex/_search",
+            MockResponse::new(StatusCode::OK).json(&page),
+        );
+        mock.respond(
+            Method::POST,
+            "/_search/scroll",
+            MockResponse::new(StatusCode::OK).json(&page),
+        );
+
+        let client = SyncClientBuilder::new()
+            .static_nodes(vec!["http://a:9200", "http://b:9200"])
+            .mock(mock.clone())
+            .build()
+            .unwrap();
+
+        client
+            .search::<Value>()
+            .index("myindex")
+            .params_fluent(|p| p.url_param("scroll", "1m"))
+            .send()
+            .unwrap();
+
+        client
+            .request(ScrollRequest::new(
+                json!({ "scroll": "1m", "scroll_id": "scroll-1" }).to_string(),
+            ))
+            .send()
+            .unwrap();
+
+        let hosts: Vec<_> = mock
+            .requests()
+            .iter()
+            .map(|req| req.url().host_str().unwrap().to_owned())
+            .collect();
+
+        assert_eq!(vec!["a", "b"], hosts);
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_receives_correlation_ids() {
+        use client::sender::{
+            logging::X_OPAQUE_ID,
+            mock::{
+                MockResponse,
+                MockSender,
+            },
+        };
+        use http::header::{
+            HeaderName,
+            HeaderValue,
+        };
+
+        let mock = MockSender::new();
+        mock.respond(Method::GET, "/", MockResponse::new(StatusCode::OK));
+
+        let client = SyncClientBuilder::new().mock(mock.clone()).build().unwrap();
+
+        client.request(PingRequest::new()).send().unwrap();
+        client.request(PingRequest::new()).send().unwrap();
+        client
+            .request(PingRequest::new())
+            .params_fluent(|p| {
+                p.header(
+                    HeaderName::from_static(X_OPAQUE_ID),
+                    HeaderValue::from_static("my-service"),
+                )
+            })
+            .send()
+            .unwrap();
+
+        let ids: Vec<_> = mock
+            .requests()
+            .iter()
+            .map(|req| req.headers()[X_OPAQUE_ID].to_str().unwrap().to_owned())
+            .collect();
+
+        assert_ne!(ids[0], ids[1]);
+        assert_eq!("my-service", ids[2]);
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn mock_pre_send_tweaks_requests() {
+        use client::{
+            requests::SearchRequest,
+            sender::mock::{
+                MockResponse,
+                MockSender,
+            },
+        };
+        use http::header::{
+            HeaderName,
+            HeaderValue,
+        };
+
+        let mock = MockSender::new();
+        mock.respond(Method::POST, "/_search", MockResponse::new(StatusCode::OK));
+
+        let client = SyncClientBuilder::new()
+            .mock(mock.clone())
+            .pre_send_raw(|req| {
+                let mut body = Vec::new();
+                req.body_mut().unwrap().reader().read_to_end(&mut body)?;
+
+                req.headers_mut().insert(
+                    HeaderName::from_static("x-body-len"),
+                    HeaderValue::from_str(&body.len().to_string())?,
+                );
+
+                Ok(())
+            })
+            .build()
+            .unwrap();
+
+        client.request(SearchRequest::new("{}")).send().unwrap();
+
+        let requests = mock.requests();
+        assert_eq!("2", requests[0].headers()["x-body-len"]);
+        assert_eq!(Some(&b"{}"[..]), requests[0].body());
+    }
+
+    #[derive(Clone, Default)]
+    struct CustomSender(Arc<Mutex<Vec<String>>>);
+
+    impl Sender for CustomSender {
+        type Body = SyncBody;
+        type Response = Result<SyncResponseBuilder, Error>;
+        type Params = Params;
+
+        fn send<TEndpoint, TParams, TBody>(
+            &self,
+            request: SendableRequest<TEndpoint, TParams, TBody>,
+        ) -> Self::Response
+        where
+            TEndpoint: Into<Endpoint<'static, TBody>>,
+            TBody: Into<Self::Body> + Send + 'static,
+            TParams: Into<Self::Params> + Send + 'static,
+        {
+            let (node, req) = request.into_http_request()?;
+
+            self.0
+                .lock()
+                .expect("lock poisoned")
+                .push(format!("{} {}", req.method(), req.url()));
+
+            Ok(SyncResponseBuilder::new(
+                node,
+                StatusCode::OK,
+                &br#"{"ok":true}"#[..],
+            ))
+        }
+
+        fn next_params(addresses: &NodeAddresses<Self>) -> Self::Params {
+            addresses.next_sync()
+        }
+    }
+
+    #[test]
+    fn custom_sender_sends_raw_requests() {
+        let sender = CustomSender::default();
+        let client = Client::from_sender(
+            sender.clone(),
+            NodeAddresses::static_nodes(vec!["http://localhost:9200"], PreRequestParams::default()),
+        );
+
+        let res = client.request(PingRequest::new()).send().unwrap();
+
+        assert_eq!("http://localhost:9200", res.node().as_ref());
+        assert_eq!(json!({ "ok": true }), res.into_response::<Value>().unwrap());
+        assert_eq!(
+            vec!["GET http://localhost:9200/".to_owned()],
+            *sender.0.lock().expect("lock poisoned")
+        );
+    }
+
+    #[test]
+    fn into_http_request_uses_given_params() {
+        let params = RequestParams::new("http://localhost:9200").url_param("pretty", true);
+        let req: SendableRequest<_, Params, _> =
+            SendableRequest::new(PingRequest::new(), SendableRequestParams::Value(params));
+
+        let (node, req) = req.into_http_request().unwrap();
+
+        assert_eq!("http://localhost:9200", node.as_ref());
+        assert_eq!("http://localhost:9200/?pretty=true", req.url().as_str());
+        assert_eq!(Method::GET, *req.method());
+    }
 }
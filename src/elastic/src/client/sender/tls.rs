@@ -0,0 +1,173 @@
+/*!
+Configure TLS for connections to Elasticsearch nodes.
+
+By default, connections to `https` nodes are verified using the Mozilla root certificates.
+A [`Tls`][Tls] configuration can add custom root certificates, like the CA that signed a cluster's certificates, and a client certificate for mutual TLS.
+Attach a TLS configuration to a client using the `tls` method on the [`SyncClientBuilder`][SyncClientBuilder] or [`AsyncClientBuilder`][AsyncClientBuilder].
+
+TLS options can't be combined with a custom `reqwest::Client`, which should be configured directly instead.
+
+# Examples
+
+Trust a cluster's CA and authenticate using a client certificate:
+
+```no_run
+# extern crate elastic;
+# use std::fs;
+# use elastic::prelude::*;
+# use elastic::client::sender::tls::Tls;
+# fn main() { run().unwrap() }
+# fn run() -> Result<(), Box<::std::error::Error>> {
+let tls = Tls::new()
+    .root_certificate_pem(fs::read("ca.pem")?)
+    .identity_pem(fs::read("client.pem")?);
+
+let client = SyncClientBuilder::new()
+    .static_node("https://localhost:9200")
+    .tls(tls)
+    .build()?;
+# Ok(())
+# }
+```
+
+[Tls]: struct.Tls.html
+[SyncClientBuilder]: ../struct.SyncClientBuilder.html
+[AsyncClientBuilder]: ../struct.AsyncClientBuilder.html
+*/
+
+use reqwest::{
+    Certificate,
+    Identity,
+};
+use std::fmt;
+
+use error::{
+    self,
+    Error,
+};
+
+/** TLS options for connections to Elasticsearch nodes. */
+#[derive(Clone, Default)]
+pub struct Tls {
+    root_certificates: Vec<Vec<u8>>,
+    identity: Option<Vec<u8>>,
+    accept_invalid_certs: bool,
+}
+
+impl Tls {
+    /** Create a TLS configuration that uses the default root certificates. */
+    pub fn new() -> Self {
+        Tls::default()
+    }
+
+    /**
+    Trust a PEM encoded root certificate, in addition to the default ones.
+
+    This can be used to connect to a cluster with certificates signed by its own CA, or self-signed certificates.
+    */
+    pub fn root_certificate_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(pem.into());
+        self
+    }
+
+    /**
+    Authenticate using a client certificate.
+
+    The PEM buffer must contain the client's private key and its certificate chain.
+    */
+    pub fn identity_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.identity = Some(pem.into());
+        self
+    }
+
+    /**
+    Skip verifying the certificates of nodes.
+
+    This makes connections vulnerable to man-in-the-middle attacks, so it should only be used for development clusters.
+    */
+    pub fn danger_accept_invalid_certs(mut self, accept_invalid_certs: bool) -> Self {
+        self.accept_invalid_certs = accept_invalid_certs;
+        self
+    }
+
+    /** Parse the certificates and identity in this configuration. */
+    pub(crate) fn build(&self) -> Result<TlsOptions, Error> {
+        let root_certificates = self
+            .root_certificates
+            .iter()
+            .map(|pem| {
+                // Certificates are only parsed when the http client is built, and invalid ones are ignored
+                if !contains_certificate(pem) {
+                    Err(error::build(error::message(
+                        "the root certificate doesn't contain a PEM encoded certificate",
+                    )))?
+                }
+
+                Certificate::from_pem(pem).map_err(error::build)
+            })
+            .collect::<Result<_, _>>()?;
+
+        let identity = match self.identity {
+            Some(ref pem) => Some(Identity::from_pem(pem).map_err(error::build)?),
+            None => None,
+        };
+
+        Ok(TlsOptions {
+            root_certificates,
+            identity,
+            accept_invalid_certs: self.accept_invalid_certs,
+        })
+    }
+}
+
+impl fmt::Debug for Tls {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Tls")
+            .field("root_certificates", &self.root_certificates.len())
+            .field("identity", &self.identity.is_some())
+            .field("accept_invalid_certs", &self.accept_invalid_certs)
+            .finish()
+    }
+}
+
+fn contains_certificate(pem: &[u8]) -> bool {
+    const BEGIN_CERTIFICATE: &[u8] = b"-----BEGIN CERTIFICATE-----";
+
+    pem.windows(BEGIN_CERTIFICATE.len())
+        .any(|window| window == BEGIN_CERTIFICATE)
+}
+
+/** Parsed TLS options that can be applied to a http client builder. */
+pub(crate) struct TlsOptions {
+    pub(crate) root_certificates: Vec<Certificate>,
+    pub(crate) identity: Option<Identity>,
+    pub(crate) accept_invalid_certs: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_default() {
+        let options = Tls::new().build().unwrap();
+
+        assert!(options.root_certificates.is_empty());
+        assert!(options.identity.is_none());
+        assert!(!options.accept_invalid_certs);
+    }
+
+    #[test]
+    fn build_invalid_certificate_fails() {
+        let tls = Tls::new().root_certificate_pem("not a certificate");
+
+        assert!(tls.build().is_err());
+    }
+
+    #[test]
+    fn build_invalid_identity_fails() {
+        let tls = Tls::new().identity_pem("not an identity");
+
+        assert!(tls.build().is_err());
+    }
+}
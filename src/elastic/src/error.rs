@@ -47,7 +47,13 @@ use elastic_responses::error::ResponseError;
 use reqwest::Error as ReqwestError;
 use serde_json;
 
-pub use elastic_responses::error::ApiError;
+pub use elastic_responses::error::{
+    ApiError,
+    CauseChain,
+    ErrorCause,
+    ScriptError,
+    ScriptPosition,
+};
 
 use http::StatusCode;
 
@@ -81,6 +87,41 @@ quick_error! {
     }
 }
 
+impl Error {
+    /**
+    Create an error sending a request.
+
+    This method is used by custom [`Sender`][Sender] implementations when their http client fails to send a request.
+
+    [Sender]: ../client/sender/trait.Sender.html
+    */
+    pub fn request<E>(err: E) -> Self
+    where
+        E: Into<Box<StdError + Send + Sync>>,
+    {
+        request(wrapped(err.into()))
+    }
+
+    /**
+    Create an error receiving a response.
+
+    This method is used by custom [`Sender`][Sender] implementations when their http client fails to receive a response.
+
+    [Sender]: ../client/sender/trait.Sender.html
+    */
+    pub fn response<E>(status: StatusCode, err: E) -> Self
+    where
+        E: Into<Box<StdError + Send + Sync>>,
+    {
+        Error::Client(ClientError {
+            inner: inner::Error::with_chain(
+                wrapped(err.into()),
+                inner::ErrorKind::Response(status),
+            ),
+        })
+    }
+}
+
 pub(crate) mod string_error {
     quick_error! {
         #[derive(Debug)]
@@ -139,6 +180,40 @@ impl fmt::Display for ClientError {
     }
 }
 
+impl ClientError {
+    /**
+    The number of times the request was retried before failing.
+
+    This is only greater than `0` when a retry policy is configured on the client and every attempt to send the request failed.
+    */
+    pub fn retries(&self) -> u32 {
+        match *self.inner.kind() {
+            inner::ErrorKind::Retry(retries) => retries,
+            _ => 0,
+        }
+    }
+
+    /**
+    The structured cause of an API error that isn't one of the known [`ApiError`][ApiError] variants.
+
+    Errors returned by Elasticsearch with a `type` that isn't recognised are returned as client errors.
+    Their `caused_by` chain, `root_cause`s and `suppressed` errors can still be inspected through this method.
+
+    [ApiError]: enum.ApiError.html
+    */
+    pub fn api_error_cause(&self) -> Option<&ErrorCause> {
+        self.inner
+            .1
+            .next_error
+            .as_ref()
+            .and_then(|err| err.downcast_ref::<ResponseError>())
+            .and_then(|err| match *err {
+                ResponseError::Parse(ref err) => err.api_error_cause(),
+                _ => None,
+            })
+    }
+}
+
 pub(crate) fn build<E>(err: E) -> Error
 where
     E: StdError + Send + 'static,
@@ -161,6 +236,15 @@ where
     })
 }
 
+pub(crate) fn retry<E>(retries: u32, err: E) -> Error
+where
+    E: StdError + Send + 'static,
+{
+    Error::Client(ClientError {
+        inner: inner::Error::with_chain(err, inner::ErrorKind::Retry(retries)),
+    })
+}
+
 pub(crate) fn response<E>(status: StatusCode, err: E) -> Error
 where
     E: Into<MaybeApiError<E>> + StdError + Send + 'static,
@@ -235,6 +319,10 @@ mod inner {
                 description("error sending a request")
                 display("error sending a request")
             }
+            Retry(retries: u32) {
+                description("error sending a request")
+                display("error sending a request after {} retries", retries)
+            }
             Response(status: StatusCode) {
                 description("error receiving a response")
                 display("error receiving a response. Status code: {}", status)
@@ -252,4 +340,33 @@ mod tests {
     fn error_is_send_sync() {
         assert_send::<Error>();
     }
+
+    #[test]
+    fn retry_error_has_retries() {
+        match retry(2, message("connection refused")) {
+            Error::Client(err) => {
+                assert_eq!(2, err.retries());
+                assert_eq!("error sending a request after 2 retries", err.to_string());
+            }
+            _ => panic!("expected a client error"),
+        }
+    }
+
+    #[test]
+    fn custom_response_error_has_status() {
+        let err = Error::response(StatusCode::BAD_GATEWAY, "connection reset");
+
+        assert_eq!(
+            "error sending a request or receiving a response. Caused by: error receiving a response. Status code: 502 Bad Gateway",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn request_error_has_no_retries() {
+        match request(message("connection refused")) {
+            Error::Client(err) => assert_eq!(0, err.retries()),
+            _ => panic!("expected a client error"),
+        }
+    }
 }
@@ -1,53 +1,202 @@
-use bytes::Bytes;
+use bytes::{
+    Bytes,
+    BytesMut,
+};
 use serde_json::Value;
 use std::{
     borrow::Cow,
+    error::Error as StdError,
+    fmt,
     io::{
         self,
         Cursor,
         Read,
     },
+    mem,
+    ops::Deref,
+    pin::Pin,
+    sync::Arc,
+    task::{
+        Context,
+        Poll as StdPoll,
+    },
 };
 
+use bytes1;
 use futures::{
     Poll,
     Stream,
 };
-use reqwest::async::{
+use futures_util::compat::{
+    Compat,
+    Compat01As03,
+};
+use reqwest::{
     Body,
     Response as RawResponse,
 };
-use tokio::io::AsyncRead;
+use tokio::io::{
+    AsyncRead,
+    ReadBuf,
+};
 
 use error::{
     self,
     Error,
 };
 use http::{
+    BufferedBody,
     HttpRequest,
     StatusCode,
+    StreamReader,
+    SyncBody,
 };
 
-pub use reqwest::async::Chunk as AsyncChunk;
+/**
+A chunk of bytes in a response body.
+
+Chunks are cheap to clone, and dereference to a byte slice.
+*/
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct AsyncChunk(bytes1::Bytes);
+
+impl AsyncChunk {
+    /** The number of bytes in the chunk. */
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /** Whether the chunk contains no bytes. */
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Deref for AsyncChunk {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for AsyncChunk {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for AsyncChunk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl Extend<u8> for AsyncChunk {
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // The chunk is only copied if its buffer is shared
+        let mut bytes = match mem::take(&mut self.0).try_into_mut() {
+            Ok(bytes) => bytes,
+            Err(bytes) => bytes1::BytesMut::from(&bytes[..]),
+        };
+        bytes.extend(iter);
+
+        self.0 = bytes.freeze();
+    }
+}
+
+impl IntoIterator for AsyncChunk {
+    type Item = u8;
+    type IntoIter = AsyncChunkIter;
+
+    fn into_iter(self) -> AsyncChunkIter {
+        AsyncChunkIter(self.0.into_iter())
+    }
+}
+
+/** An iterator over the bytes in an `AsyncChunk`. */
+pub struct AsyncChunkIter(bytes1::buf::IntoIter<bytes1::Bytes>);
+
+impl Iterator for AsyncChunkIter {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl From<Bytes> for AsyncChunk {
+    fn from(bytes: Bytes) -> AsyncChunk {
+        AsyncChunk(bytes1::Bytes::from_owner(bytes))
+    }
+}
+
+impl From<Vec<u8>> for AsyncChunk {
+    fn from(bytes: Vec<u8>) -> AsyncChunk {
+        AsyncChunk(bytes.into())
+    }
+}
+
+impl From<String> for AsyncChunk {
+    fn from(string: String) -> AsyncChunk {
+        AsyncChunk(string.into())
+    }
+}
+
+impl From<&'static [u8]> for AsyncChunk {
+    fn from(bytes: &'static [u8]) -> AsyncChunk {
+        AsyncChunk(bytes.into())
+    }
+}
+
+impl From<&'static str> for AsyncChunk {
+    fn from(string: &'static str) -> AsyncChunk {
+        AsyncChunk(string.into())
+    }
+}
 
 /** A http request with an asynchronous body; */
 pub type AsyncHttpRequest = HttpRequest<AsyncBody>;
 
-/** A type that can be converted into a request body. */
+/**
+A type that can be converted into a request body.
+
+Bodies can be built from shared buffers like `Bytes` and `Arc<[u8]>` without copying them.
+They're also sent to the node without copying them.
+
+Bodies can also be streamed from a `Stream` of `Bytes` using [`from_stream`](#method.from_stream).
+*/
 pub struct AsyncBody(AsyncBodyInner);
 
 enum AsyncBodyInner {
+    UnBuffered(AsyncBodyStream),
+    Buffered(BufferedAsyncBodyInner),
+}
+
+type AsyncBodyStream = Box<Stream<Item = Bytes, Error = io::Error> + Send>;
+
+#[derive(Clone)]
+enum BufferedAsyncBodyInner {
     Shared(Bytes),
+    SharedSlice(Arc<[u8]>),
     Bytes(Cow<'static, [u8]>),
     Str(Cow<'static, str>),
 }
 
-impl AsRef<[u8]> for AsyncBodyInner {
+impl AsRef<[u8]> for BufferedAsyncBodyInner {
     fn as_ref(&self) -> &[u8] {
         match *self {
-            AsyncBodyInner::Shared(ref bytes) => bytes.as_ref(),
-            AsyncBodyInner::Bytes(ref bytes) => bytes.as_ref(),
-            AsyncBodyInner::Str(ref string) => string.as_bytes(),
+            BufferedAsyncBodyInner::Shared(ref bytes) => bytes.as_ref(),
+            BufferedAsyncBodyInner::SharedSlice(ref bytes) => bytes.as_ref(),
+            BufferedAsyncBodyInner::Bytes(ref bytes) => bytes.as_ref(),
+            BufferedAsyncBodyInner::Str(ref string) => string.as_bytes(),
         }
     }
 }
@@ -56,7 +205,7 @@ impl AsRef<[u8]> for AsyncBodyInner {
 A read adapter for an `AsyncBody`.
 */
 pub struct AsyncBodyReader<'a> {
-    inner: Cursor<&'a AsyncBodyInner>,
+    inner: Cursor<&'a [u8]>,
 }
 
 impl<'a> Read for AsyncBodyReader<'a> {
@@ -65,18 +214,108 @@ impl<'a> Read for AsyncBodyReader<'a> {
     }
 }
 
-impl<'a> AsyncRead for AsyncBodyReader<'a> {}
+impl<'a> AsyncRead for AsyncBodyReader<'a> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context,
+        buf: &mut ReadBuf,
+    ) -> StdPoll<io::Result<()>> {
+        let len = self.get_mut().inner.read(buf.initialize_unfilled())?;
+        buf.advance(len);
+
+        StdPoll::Ready(Ok(()))
+    }
+}
+
+impl AsyncHttpRequest {
+    /**
+    Attempt to clone the request.
+
+    This will return `None` if the body is streamed.
+    */
+    pub(crate) fn try_clone(&self) -> Option<AsyncHttpRequest> {
+        let body = match self.body {
+            Some(ref body) => Some(body.try_clone()?),
+            None => None,
+        };
+
+        Some(HttpRequest {
+            url: self.url.clone(),
+            method: self.method.clone(),
+            headers: self.headers.clone(),
+            body,
+        })
+    }
+}
 
 impl AsyncBody {
+    /**
+    Create a body that's streamed from a `Stream` of `Bytes`.
+
+    Each chunk is sent as it's produced, so the body never needs to be held in memory all at once.
+    This makes it possible to send large payloads, like a bulk request read from a file.
+
+    A streamed body can only be sent once, so requests with one aren't retried or compressed, and can't be signed.
+    */
+    pub fn from_stream<TStream>(stream: TStream) -> AsyncBody
+    where
+        TStream: Stream<Item = Bytes> + Send + 'static,
+        TStream::Error: Into<Box<StdError + Send + Sync>>,
+    {
+        let stream = stream.map_err(|e| io::Error::new(io::ErrorKind::Other, e));
+
+        AsyncBody(AsyncBodyInner::UnBuffered(Box::new(stream)))
+    }
+
+    /** Attempt to clone the body, returning `None` if it's streamed. */
+    fn try_clone(&self) -> Option<AsyncBody> {
+        match self.0 {
+            AsyncBodyInner::UnBuffered(_) => None,
+            AsyncBodyInner::Buffered(ref inner) => {
+                Some(AsyncBody(AsyncBodyInner::Buffered(inner.clone())))
+            }
+        }
+    }
+
     /** Convert the body into its inner value. */
     pub(crate) fn into_inner(self) -> Body {
         match self.0 {
-            AsyncBodyInner::Shared(bytes) => bytes.into(),
-            AsyncBodyInner::Bytes(bytes) => match bytes {
+            AsyncBodyInner::UnBuffered(stream) => {
+                Body::wrap_stream(Compat01As03::new(stream.map(bytes1::Bytes::from_owner)))
+            }
+            AsyncBodyInner::Buffered(BufferedAsyncBodyInner::Shared(bytes)) => {
+                bytes1::Bytes::from_owner(bytes).into()
+            }
+            AsyncBodyInner::Buffered(BufferedAsyncBodyInner::SharedSlice(bytes)) => {
+                bytes1::Bytes::from_owner(bytes).into()
+            }
+            AsyncBodyInner::Buffered(BufferedAsyncBodyInner::Bytes(bytes)) => match bytes {
                 Cow::Owned(bytes) => bytes.into(),
                 Cow::Borrowed(bytes) => bytes.into(),
             },
-            AsyncBodyInner::Str(string) => match string {
+            AsyncBodyInner::Buffered(BufferedAsyncBodyInner::Str(string)) => match string {
+                Cow::Owned(string) => string.into(),
+                Cow::Borrowed(string) => string.into(),
+            },
+        }
+    }
+
+    /**
+    Convert the body into a synchronous body.
+
+    Buffered bodies are converted without copying them.
+    A streamed body is read from a blocking reader over the stream.
+    */
+    pub(crate) fn into_sync(self) -> SyncBody {
+        match self.0 {
+            AsyncBodyInner::UnBuffered(stream) => SyncBody::from_reader(StreamReader::new(stream)),
+            AsyncBodyInner::Buffered(BufferedAsyncBodyInner::Shared(bytes)) => bytes.into(),
+            AsyncBodyInner::Buffered(BufferedAsyncBodyInner::SharedSlice(bytes)) => bytes.into(),
+            AsyncBodyInner::Buffered(BufferedAsyncBodyInner::Bytes(bytes)) => match bytes {
+                Cow::Owned(bytes) => bytes.into(),
+                Cow::Borrowed(bytes) => bytes.into(),
+            },
+            AsyncBodyInner::Buffered(BufferedAsyncBodyInner::Str(string)) => match string {
                 Cow::Owned(string) => string.into(),
                 Cow::Borrowed(string) => string.into(),
             },
@@ -85,56 +324,122 @@ impl AsyncBody {
 
     /**
     Get a reader over the asynchronous body.
+
+    A streamed body can't be read without blocking, so its reader will be empty.
     */
     pub fn reader(&mut self) -> AsyncBodyReader {
+        let body = self.buffered().unwrap_or(&[]);
+
         AsyncBodyReader {
-            inner: Cursor::new(&self.0),
+            inner: Cursor::new(body),
         }
     }
 }
 
+impl BufferedBody for AsyncBody {
+    fn buffered(&self) -> Option<&[u8]> {
+        match self.0 {
+            AsyncBodyInner::UnBuffered(_) => None,
+            AsyncBodyInner::Buffered(ref inner) => Some(inner.as_ref()),
+        }
+    }
+
+    #[cfg(feature = "aws-sigv4")]
+    fn buffer(&mut self) -> Result<&[u8], io::Error> {
+        self.buffered().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                "a streamed body can't be buffered without blocking",
+            )
+        })
+    }
+}
+
 impl From<Bytes> for AsyncBody {
     fn from(body: Bytes) -> AsyncBody {
-        AsyncBody(AsyncBodyInner::Shared(body))
+        AsyncBody(AsyncBodyInner::Buffered(BufferedAsyncBodyInner::Shared(
+            body,
+        )))
+    }
+}
+
+impl From<BytesMut> for AsyncBody {
+    fn from(body: BytesMut) -> AsyncBody {
+        AsyncBody::from(body.freeze())
+    }
+}
+
+impl From<Arc<[u8]>> for AsyncBody {
+    fn from(body: Arc<[u8]>) -> AsyncBody {
+        AsyncBody(AsyncBodyInner::Buffered(
+            BufferedAsyncBodyInner::SharedSlice(body),
+        ))
     }
 }
 
 impl From<Vec<u8>> for AsyncBody {
     fn from(body: Vec<u8>) -> AsyncBody {
-        AsyncBody(AsyncBodyInner::Bytes(body.into()))
+        AsyncBody(AsyncBodyInner::Buffered(BufferedAsyncBodyInner::Bytes(
+            body.into(),
+        )))
     }
 }
 
 impl From<String> for AsyncBody {
     fn from(body: String) -> AsyncBody {
-        AsyncBody(AsyncBodyInner::Str(body.into()))
+        AsyncBody(AsyncBodyInner::Buffered(BufferedAsyncBodyInner::Str(
+            body.into(),
+        )))
     }
 }
 
 impl From<Value> for AsyncBody {
     fn from(body: Value) -> AsyncBody {
-        AsyncBody(AsyncBodyInner::Str(body.to_string().into()))
+        AsyncBody(AsyncBodyInner::Buffered(BufferedAsyncBodyInner::Str(
+            body.to_string().into(),
+        )))
     }
 }
 
 impl From<&'static [u8]> for AsyncBody {
     fn from(body: &'static [u8]) -> AsyncBody {
-        AsyncBody(AsyncBodyInner::Bytes(body.into()))
+        AsyncBody(AsyncBodyInner::Buffered(BufferedAsyncBodyInner::Bytes(
+            body.into(),
+        )))
     }
 }
 
 impl From<&'static str> for AsyncBody {
     fn from(body: &'static str) -> AsyncBody {
-        AsyncBody(AsyncBodyInner::Str(body.into()))
+        AsyncBody(AsyncBodyInner::Buffered(BufferedAsyncBodyInner::Str(
+            body.into(),
+        )))
     }
 }
 
 /** A raw HTTP response that can be buffered using `Read`. */
-pub struct AsyncHttpResponse(StatusCode, RawResponse);
+pub struct AsyncHttpResponse(
+    StatusCode,
+    Box<Stream<Item = AsyncChunk, Error = Error> + Send>,
+);
 
 impl AsyncHttpResponse {
-    pub(crate) fn from_raw(status: StatusCode, response: RawResponse) -> Self {
-        AsyncHttpResponse(status, response)
+    pub(crate) fn from_raw(response: RawResponse) -> Self {
+        // Both versions of `http` accept the same range of status codes
+        let status = StatusCode::from_u16(response.status().as_u16()).expect("invalid status code");
+
+        let body = Compat::new(Box::pin(response.bytes_stream()))
+            .map(AsyncChunk)
+            .map_err(move |e| error::response(status, e));
+
+        AsyncHttpResponse::new(status, body)
+    }
+
+    pub(crate) fn new<TBody>(status: StatusCode, body: TBody) -> Self
+    where
+        TBody: Stream<Item = AsyncChunk, Error = Error> + Send + 'static,
+    {
+        AsyncHttpResponse(status, Box::new(body))
     }
 }
 
@@ -143,10 +448,7 @@ impl Stream for AsyncHttpResponse {
     type Error = Error;
 
     fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
-        self.1
-            .body_mut()
-            .poll()
-            .map_err(|e| error::response(self.0, e))
+        self.1.poll()
     }
 }
 
@@ -159,6 +461,11 @@ impl AsyncHttpResponse {
 
 #[cfg(test)]
 mod tests {
+    use futures::{
+        stream,
+        Future,
+    };
+
     use super::*;
     use client::requests::empty_body;
 
@@ -184,6 +491,48 @@ mod tests {
         AsyncBody::from(BODY);
     }
 
+    #[test]
+    fn shared_bytes_into_body() {
+        let bytes = Bytes::from(vec![0; 64]);
+        let body = AsyncBody::from(bytes.clone());
+
+        assert_eq!(
+            bytes.as_ptr(),
+            body.buffered().expect("body is buffered").as_ptr()
+        );
+    }
+
+    #[test]
+    fn shared_slice_into_body() {
+        let bytes: Arc<[u8]> = Arc::from(&b"abc"[..]);
+        let body = AsyncBody::from(bytes.clone());
+
+        assert_eq!(
+            bytes.as_ptr(),
+            body.buffered().expect("body is buffered").as_ptr()
+        );
+    }
+
+    #[test]
+    fn chunks_concat() {
+        let chunks = vec![
+            AsyncChunk::from("ab"),
+            AsyncChunk::from(Bytes::from(&b"cd"[..])),
+        ];
+        let body = stream::iter_ok::<_, ()>(chunks).concat2().wait().unwrap();
+
+        assert_eq!(b"abcd", &body[..]);
+    }
+
+    #[test]
+    fn stream_into_body() {
+        let chunks: Vec<Result<Bytes, io::Error>> = vec![Ok(Bytes::from(&b"abc"[..]))];
+        let body = AsyncBody::from_stream(stream::iter_result(chunks));
+
+        assert!(body.buffered().is_none());
+        assert!(body.try_clone().is_none());
+    }
+
     #[test]
     fn empty_body_into_body() {
         AsyncBody::from(empty_body());
@@ -1,7 +1,7 @@
 /*!
 Raw HTTP modules.
 
-These types are re-exported from `reqwest` and used in parts of `elastic`s public API.
+These types are re-exported from `http` and `url` and used in parts of `elastic`s public API.
 They may eventually be wrapped and made implementation details.
 */
 
@@ -13,10 +13,8 @@ pub use self::{
     sync::*,
 };
 
-pub use reqwest::{
-    header,
-    Url,
-};
+pub use http_types::header;
+pub use url::Url;
 
 pub use elastic_requests::Method;
 pub use elastic_responses::StatusCode;
@@ -40,6 +38,26 @@ pub struct HttpRequest<TBody> {
 }
 
 impl<TBody> HttpRequest<TBody> {
+    /** Get the url the request is sent to. */
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /** Get the http method of the request. */
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /** Get the headers sent with the request. */
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /** Get the body of the request, if there is one. */
+    pub fn body(&self) -> Option<&TBody> {
+        self.body.as_ref()
+    }
+
     pub fn url_mut(&mut self) -> &mut Url {
         &mut self.url
     }
@@ -57,6 +75,16 @@ impl<TBody> HttpRequest<TBody> {
     }
 }
 
+/** A request body that may already be buffered in memory. */
+pub(crate) trait BufferedBody: From<Vec<u8>> {
+    /** Get the bytes of the body, or `None` if it can only be read once. */
+    fn buffered(&self) -> Option<&[u8]>;
+
+    /** Get the bytes of the body, reading it into memory first if it can only be read once. */
+    #[cfg(feature = "aws-sigv4")]
+    fn buffer(&mut self) -> Result<&[u8], ::std::io::Error>;
+}
+
 impl<TBody> fmt::Debug for HttpRequest<TBody> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("HttpRequest")
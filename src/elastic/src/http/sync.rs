@@ -1,4 +1,7 @@
-use bytes::Bytes;
+use bytes::{
+    Bytes,
+    BytesMut,
+};
 use serde_json::Value;
 use std::{
     borrow::Cow,
@@ -8,22 +11,35 @@ use std::{
         Cursor,
         Read,
     },
+    sync::Arc,
 };
+use tokio::task::JoinHandle;
 
-use reqwest::{
-    Body,
-    Response as RawResponse,
+use futures::{
+    stream,
+    Async,
+    Poll,
+    Stream,
 };
 
 use http::{
+    AsyncBody,
+    BufferedBody,
     HttpRequest,
     StatusCode,
 };
+use runtime;
+use std_future::poll_01;
 
 /** A http request with a synchronous body. */
 pub type SyncHttpRequest = HttpRequest<SyncBody>;
 
-/** A type that can be converted into a request body. */
+/**
+A type that can be converted into a request body.
+
+Bodies can be built from shared buffers like `Bytes` and `Arc<[u8]>` without copying them.
+They're also sent to the node without copying them.
+*/
 pub struct SyncBody(SyncBodyInner);
 
 enum SyncBodyInner {
@@ -34,6 +50,7 @@ enum SyncBodyInner {
 #[derive(Clone)]
 enum BufferedSyncBodyInner<'a> {
     Shared(Bytes),
+    SharedSlice(Arc<[u8]>),
     Bytes(Cow<'a, [u8]>),
     Str(Cow<'a, str>),
 }
@@ -42,6 +59,7 @@ impl<'a> AsRef<[u8]> for BufferedSyncBodyInner<'a> {
     fn as_ref(&self) -> &[u8] {
         match *self {
             BufferedSyncBodyInner::Shared(ref bytes) => bytes.as_ref(),
+            BufferedSyncBodyInner::SharedSlice(ref bytes) => bytes.as_ref(),
             BufferedSyncBodyInner::Bytes(ref bytes) => bytes.as_ref(),
             BufferedSyncBodyInner::Str(ref string) => string.as_bytes(),
         }
@@ -54,6 +72,9 @@ impl<'a> BufferedSyncBodyInner<'a> {
             BufferedSyncBodyInner::Shared(ref bytes) => {
                 BufferedSyncBodyInner::Shared(bytes.clone())
             }
+            BufferedSyncBodyInner::SharedSlice(ref bytes) => {
+                BufferedSyncBodyInner::SharedSlice(bytes.clone())
+            }
             BufferedSyncBodyInner::Bytes(ref bytes) => {
                 BufferedSyncBodyInner::Bytes(Cow::Borrowed(bytes))
             }
@@ -65,11 +86,33 @@ impl<'a> BufferedSyncBodyInner<'a> {
 }
 
 impl SyncBody {
-    /** Convert the body into its inner value. */
-    pub(crate) fn into_inner(self) -> Body {
+    /**
+    Create a body that's streamed from a reader.
+
+    The body is read on the runtime's blocking thread pool as it's sent, in chunks, so it never needs to be held in memory all at once.
+    This makes it possible to send large payloads, like a bulk request read from a file.
+
+    A streamed body can only be read once, so requests with one aren't retried or compressed.
+    If the body needs to be signed then it will be read into memory first.
+    */
+    pub fn from_reader<TReader>(reader: TReader) -> SyncBody
+    where
+        TReader: Read + Send + 'static,
+    {
+        SyncBody(SyncBodyInner::UnBuffered(Box::new(reader)))
+    }
+
+    /**
+    Convert the body into an asynchronous body.
+
+    Buffered bodies are converted without copying them.
+    A body that's read from a reader is read on the runtime's blocking thread pool as it's sent so the reader doesn't block the runtime.
+    */
+    pub(crate) fn into_async(self) -> AsyncBody {
         match self.0 {
-            SyncBodyInner::UnBuffered(reader) => Body::new(reader),
-            SyncBodyInner::Buffered(BufferedSyncBodyInner::Shared(bytes)) => bytes.to_vec().into(),
+            SyncBodyInner::UnBuffered(reader) => AsyncBody::from_stream(ReadStream::new(reader)),
+            SyncBodyInner::Buffered(BufferedSyncBodyInner::Shared(bytes)) => bytes.into(),
+            SyncBodyInner::Buffered(BufferedSyncBodyInner::SharedSlice(bytes)) => bytes.into(),
             SyncBodyInner::Buffered(BufferedSyncBodyInner::Bytes(bytes)) => match bytes {
                 Cow::Owned(bytes) => bytes.into(),
                 Cow::Borrowed(bytes) => bytes.into(),
@@ -98,6 +141,30 @@ impl SyncBody {
     }
 }
 
+impl BufferedBody for SyncBody {
+    fn buffered(&self) -> Option<&[u8]> {
+        match self.0 {
+            SyncBodyInner::UnBuffered(_) => None,
+            SyncBodyInner::Buffered(ref inner) => Some(AsRef::<[u8]>::as_ref(inner)),
+        }
+    }
+
+    #[cfg(feature = "aws-sigv4")]
+    fn buffer(&mut self) -> Result<&[u8], io::Error> {
+        if let SyncBodyInner::UnBuffered(ref mut reader) = self.0 {
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf)?;
+
+            self.0 = SyncBodyInner::Buffered(BufferedSyncBodyInner::Bytes(Cow::Owned(buf)));
+        }
+
+        match self.0 {
+            SyncBodyInner::Buffered(ref inner) => Ok(AsRef::<[u8]>::as_ref(inner)),
+            SyncBodyInner::UnBuffered(_) => unreachable!("the body was buffered"),
+        }
+    }
+}
+
 /**
 A read adapter for a `SyncBody`.
 */
@@ -144,6 +211,20 @@ impl From<Bytes> for SyncBody {
     }
 }
 
+impl From<BytesMut> for SyncBody {
+    fn from(body: BytesMut) -> SyncBody {
+        SyncBody::from(body.freeze())
+    }
+}
+
+impl From<Arc<[u8]>> for SyncBody {
+    fn from(body: Arc<[u8]>) -> SyncBody {
+        SyncBody(SyncBodyInner::Buffered(BufferedSyncBodyInner::SharedSlice(
+            body,
+        )))
+    }
+}
+
 impl From<Vec<u8>> for SyncBody {
     fn from(body: Vec<u8>) -> SyncBody {
         SyncBody(SyncBodyInner::Buffered(BufferedSyncBodyInner::Bytes(
@@ -186,16 +267,156 @@ impl From<&'static str> for SyncBody {
 
 impl From<File> for SyncBody {
     fn from(body: File) -> SyncBody {
-        SyncBody(SyncBodyInner::UnBuffered(Box::new(body)))
+        SyncBody::from_reader(body)
+    }
+}
+
+/** The size of the chunks a reader body is streamed in. */
+const READ_CHUNK_SIZE: usize = 8 * 1024;
+
+/**
+Stream the chunks of a reader.
+
+Each chunk is read on the blocking thread pool of the current `tokio` runtime when the stream is polled.
+The pool has a limited number of threads, so many bodies can be sent at once without starting a thread for each of them.
+*/
+struct ReadStream {
+    reader: Option<Box<Read + Send>>,
+    pending: Option<JoinHandle<(Box<Read + Send>, io::Result<Option<Bytes>>)>>,
+}
+
+impl ReadStream {
+    fn new(reader: Box<Read + Send>) -> Self {
+        ReadStream {
+            reader: Some(reader),
+            pending: None,
+        }
+    }
+}
+
+/** Read the next chunk from a reader, or `None` if the reader is finished. */
+fn read_chunk(reader: &mut Read) -> io::Result<Option<Bytes>> {
+    let mut chunk = vec![0; READ_CHUNK_SIZE];
+
+    loop {
+        match reader.read(&mut chunk) {
+            Ok(0) => return Ok(None),
+            Ok(len) => {
+                chunk.truncate(len);
+                return Ok(Some(Bytes::from(chunk)));
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+impl Stream for ReadStream {
+    type Item = Bytes;
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<Option<Bytes>, io::Error> {
+        if self.pending.is_none() {
+            let mut reader = match self.reader.take() {
+                Some(reader) => reader,
+                None => return Ok(Async::Ready(None)),
+            };
+
+            let runtime = runtime::current()?;
+            self.pending = Some(runtime.spawn_blocking(move || {
+                let chunk = read_chunk(&mut reader);
+                (reader, chunk)
+            }));
+        }
+
+        let (reader, chunk) = match poll_01(self.pending.as_mut().expect("a read is pending")) {
+            Async::Ready(read) => read.map_err(|e| io::Error::new(io::ErrorKind::Other, e))?,
+            Async::NotReady => return Ok(Async::NotReady),
+        };
+        self.pending = None;
+
+        // The reader is dropped once it's finished or has failed
+        match chunk? {
+            Some(chunk) => {
+                self.reader = Some(reader);
+                Ok(Async::Ready(Some(chunk)))
+            }
+            None => Ok(Async::Ready(None)),
+        }
+    }
+}
+
+/**
+A blocking reader over a stream of chunks.
+
+Reading blocks the current thread until the next chunk is ready, so the stream shouldn't need to be polled on the current thread to make progress.
+*/
+pub(crate) struct StreamReader<TStream>
+where
+    TStream: Stream,
+{
+    chunks: stream::Wait<TStream>,
+    chunk: Option<TStream::Item>,
+    pos: usize,
+}
+
+impl<TStream> StreamReader<TStream>
+where
+    TStream: Stream,
+{
+    pub(crate) fn new(stream: TStream) -> Self {
+        StreamReader {
+            chunks: stream.wait(),
+            chunk: None,
+            pos: 0,
+        }
+    }
+}
+
+impl<TStream> Read for StreamReader<TStream>
+where
+    TStream: Stream<Error = io::Error>,
+    TStream::Item: AsRef<[u8]>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            if let Some(ref chunk) = self.chunk {
+                let chunk = &chunk.as_ref()[self.pos..];
+
+                if !chunk.is_empty() {
+                    let len = chunk.len().min(buf.len());
+                    buf[..len].copy_from_slice(&chunk[..len]);
+                    self.pos += len;
+
+                    return Ok(len);
+                }
+            }
+
+            match self.chunks.next() {
+                Some(Ok(chunk)) => {
+                    self.chunk = Some(chunk);
+                    self.pos = 0;
+                }
+                Some(Err(e)) => return Err(e),
+                None => return Ok(0),
+            }
+        }
     }
 }
 
 /** A raw HTTP response that can be buffered using `Read`. */
-pub struct SyncHttpResponse(StatusCode, RawResponse);
+pub struct SyncHttpResponse(StatusCode, Box<Read + Send>);
 
 impl SyncHttpResponse {
-    pub(crate) fn from_raw(status: StatusCode, response: RawResponse) -> Self {
-        SyncHttpResponse(status, response)
+    pub(crate) fn new<TBody>(status: StatusCode, body: TBody) -> Self
+    where
+        TBody: Read + Send + 'static,
+    {
+        SyncHttpResponse(status, Box::new(body))
     }
 
     /** Get the HTTP status for the response. */
@@ -214,6 +435,7 @@ impl Read for SyncHttpResponse {
 mod tests {
     use super::*;
     use client::requests::empty_body;
+    use runtime::tests::current_thread;
 
     #[test]
     fn owned_string_into_body() {
@@ -237,6 +459,77 @@ mod tests {
         SyncBody::from(BODY);
     }
 
+    #[test]
+    fn shared_bytes_into_body() {
+        let bytes = Bytes::from(vec![0; 64]);
+        let body = SyncBody::from(bytes.clone());
+
+        assert_eq!(
+            bytes.as_ptr(),
+            body.buffered().expect("body is buffered").as_ptr()
+        );
+    }
+
+    #[test]
+    fn shared_slice_into_body() {
+        let bytes: Arc<[u8]> = Arc::from(&b"abc"[..]);
+        let body = SyncBody::from(bytes.clone());
+
+        assert_eq!(
+            bytes.as_ptr(),
+            body.buffered().expect("body is buffered").as_ptr()
+        );
+    }
+
+    #[test]
+    fn reader_into_body() {
+        let body = SyncBody::from_reader(Cursor::new(b"abc".to_vec()));
+
+        assert!(body.buffered().is_none());
+    }
+
+    #[test]
+    fn reader_body_into_async_body() {
+        let runtime = current_thread();
+        let _runtime = runtime.enter();
+
+        let expected: Vec<u8> = (0..READ_CHUNK_SIZE * 2 + 1).map(|i| i as u8).collect();
+        let mut body = SyncBody::from_reader(Cursor::new(expected.clone()))
+            .into_async()
+            .into_sync();
+
+        let mut read = Vec::new();
+        body.reader().read_to_end(&mut read).unwrap();
+
+        assert_eq!(expected, read);
+    }
+
+    #[test]
+    fn reader_body_outside_runtime_is_err() {
+        let mut body = SyncBody::from_reader(Cursor::new(b"abc".to_vec()))
+            .into_async()
+            .into_sync();
+
+        let mut read = Vec::new();
+        assert!(body.reader().read_to_end(&mut read).is_err());
+    }
+
+    #[test]
+    fn stream_reader_reads_across_chunks() {
+        let chunks = vec![
+            Bytes::from(&b"ab"[..]),
+            Bytes::new(),
+            Bytes::from(&b"cde"[..]),
+        ];
+        let mut reader = StreamReader::new(stream::iter_ok::<_, io::Error>(chunks));
+
+        let mut buf = [0; 4];
+        assert_eq!(2, reader.read(&mut buf).unwrap());
+        assert_eq!(3, reader.read(&mut buf).unwrap());
+        assert_eq!(b"cde", &buf[..3]);
+        assert_eq!(0, reader.read(&mut buf).unwrap());
+    }
+
     #[test]
     fn empty_body_into_body() {
         SyncBody::from(empty_body());
@@ -206,6 +206,9 @@ if let Some(doc) = response.into_document() {
 # }
 ```
 
+Document ids can be strings or numbers.
+With the `uuid` feature, ids can also be `uuid::Uuid`s, and `Uuid` fields are mapped as `keyword`s.
+
 For more details on document types, see the [`types`][types-mod] module.
 
 ### Searching documents
@@ -285,26 +288,35 @@ This crate glues these libraries together with some simple assumptions about how
 //#![deny(warnings, missing_docs)]
 #![allow(unknown_lints)]
 
+extern crate base64;
 extern crate bytes;
+extern crate bytes1;
+#[cfg(feature = "aws-sigv4")]
+extern crate chrono;
 extern crate elastic_requests;
 extern crate elastic_responses;
 extern crate elastic_types;
 #[macro_use]
 extern crate error_chain;
+extern crate flate2;
 extern crate fluent_builder;
 #[macro_use]
 extern crate futures;
-extern crate tokio_threadpool;
+extern crate futures_util;
+extern crate http as http_types;
 #[macro_use]
 extern crate log;
 #[macro_use]
 extern crate quick_error;
 extern crate crossbeam_channel as channel;
+extern crate rand;
 extern crate reqwest;
+#[cfg(feature = "aws-sigv4")]
+extern crate ring;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
-#[cfg_attr(test, macro_use)]
+#[cfg_attr(any(test, feature = "testing"), macro_use)]
 extern crate serde_json;
 extern crate tokio;
 extern crate url;
@@ -321,6 +333,10 @@ mod private {
     pub trait Sealed {}
 }
 
+#[macro_use]
+mod std_future;
+mod runtime;
+
 pub mod client;
 pub mod http;
 pub mod types;
@@ -0,0 +1,205 @@
+/*!
+Run the client's connections and timers on a `tokio` runtime.
+
+The asynchronous client sends requests using `tokio` 1, so its futures must be polled on a `tokio` 1 runtime, like one started by `#[tokio::main]`.
+Polling a future that needs the runtime outside of one fails with an error instead of panicking.
+*/
+
+use futures::{
+    Async,
+    Future,
+    Poll,
+};
+use std::{
+    io,
+    pin::Pin,
+    time::Instant,
+};
+use tokio::{
+    runtime::Handle,
+    task::JoinHandle,
+    time::{
+        self,
+        Sleep,
+    },
+};
+
+use error::{
+    self,
+    Error,
+};
+use std_future::poll_01;
+
+/** Check that the current thread is running within a `tokio` runtime. */
+pub(crate) fn current() -> Result<Handle, io::Error> {
+    Handle::try_current().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::Other,
+            "the asynchronous client must be used within a `tokio` 1 runtime",
+        )
+    })
+}
+
+/**
+A `futures` 0.1 future that completes at a deadline.
+
+The timer isn't started until the future is first polled, so it can be created outside of a runtime.
+*/
+pub(crate) struct Delay {
+    deadline: Instant,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl Delay {
+    pub(crate) fn new(deadline: Instant) -> Self {
+        Delay {
+            deadline,
+            sleep: None,
+        }
+    }
+
+    /** Move the deadline of the timer, even if it has already completed. */
+    pub(crate) fn reset(&mut self, deadline: Instant) {
+        self.deadline = deadline;
+
+        if let Some(ref mut sleep) = self.sleep {
+            sleep.as_mut().reset(deadline.into());
+        }
+    }
+}
+
+impl Future for Delay {
+    type Item = ();
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<(), io::Error> {
+        if self.sleep.is_none() {
+            current()?;
+
+            self.sleep = Some(Box::pin(time::sleep_until(self.deadline.into())));
+        }
+
+        let sleep = self.sleep.as_mut().expect("the timer was started");
+
+        Ok(poll_01(sleep))
+    }
+}
+
+/**
+A `futures` 0.1 future that runs a function on the blocking thread pool of a runtime.
+
+The function starts running as soon as the future is created, even if the future is never polled.
+*/
+pub(crate) struct Blocking<T> {
+    handle: JoinHandle<Result<T, Error>>,
+}
+
+impl<T> Blocking<T>
+where
+    T: Send + 'static,
+{
+    pub(crate) fn spawn<F>(runtime: &Handle, f: F) -> Self
+    where
+        F: FnOnce() -> Result<T, Error> + Send + 'static,
+    {
+        Blocking {
+            handle: runtime.spawn_blocking(f),
+        }
+    }
+}
+
+impl<T> Future for Blocking<T> {
+    type Item = T;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<T, Error> {
+        match poll_01(&mut self.handle) {
+            Async::Ready(Ok(res)) => res.map(Async::Ready),
+            // The function panicked or the runtime was shut down before it finished
+            Async::Ready(Err(e)) => Err(error::request(e)),
+            Async::NotReady => Ok(Async::NotReady),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use futures::future;
+    use futures_util::compat::Future01CompatExt;
+    use std::time::Duration;
+    use tokio::runtime::{
+        Builder,
+        Runtime,
+    };
+
+    use super::*;
+
+    /** A single threaded runtime with timers enabled. */
+    pub(crate) fn current_thread() -> Runtime {
+        Builder::new_current_thread().enable_all().build().unwrap()
+    }
+
+    /** Run a `futures` 0.1 future to completion on a new runtime. */
+    pub(crate) fn block_on<F>(fut: F) -> Result<F::Item, F::Error>
+    where
+        F: Future,
+    {
+        current_thread().block_on(fut.compat())
+    }
+
+    #[test]
+    fn delay_completes_after_deadline() {
+        let start = Instant::now();
+
+        block_on(Delay::new(start + Duration::from_millis(10))).unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(10));
+    }
+
+    #[test]
+    fn delay_can_be_reset() {
+        let start = Instant::now();
+        let mut delay = Delay::new(start + Duration::from_secs(60));
+
+        let runtime = current_thread();
+        assert!(!runtime
+            .block_on(future::lazy(|| delay.poll()).compat())
+            .unwrap()
+            .is_ready());
+
+        delay.reset(start + Duration::from_millis(10));
+        runtime.block_on((&mut delay).compat()).unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn blocking_returns_result() {
+        let runtime = current_thread();
+
+        let ok = Blocking::spawn(runtime.handle(), || Ok(1));
+        assert_eq!(1, runtime.block_on(ok.compat()).unwrap());
+
+        let err = Blocking::spawn(runtime.handle(), || -> Result<(), Error> {
+            Err(error::request(io::Error::new(io::ErrorKind::Other, "failed")))
+        });
+        assert!(runtime.block_on(err.compat()).is_err());
+    }
+
+    #[test]
+    fn blocking_panic_is_err() {
+        let runtime = current_thread();
+
+        let panicked = Blocking::spawn(runtime.handle(), || -> Result<(), Error> {
+            panic!("explicit panic")
+        });
+        assert!(runtime.block_on(panicked.compat()).is_err());
+    }
+
+    #[test]
+    fn delay_outside_runtime_is_err() {
+        let mut delay = Delay::new(Instant::now());
+
+        assert!(delay.poll().is_err());
+    }
+}
@@ -0,0 +1,211 @@
+/*!
+Standard futures returned by an asynchronous client.
+
+The futures returned by an asynchronous client implement the standard library's `Future` trait, so they can be awaited in an `async` function or run with `tokio::runtime::Runtime::block_on`.
+They don't implement the `futures` 0.1 `Future` trait.
+Code that still uses `futures` 0.1 combinators or executors can convert a future into a `futures` 0.1 future with its `compat` method.
+
+Connections and timers are driven by `tokio` 1, so the futures must be awaited within a `tokio` 1 runtime.
+A future that's awaited outside of a runtime fails with an error instead of panicking.
+*/
+
+use futures::{
+    Async,
+    Future,
+};
+use futures_util::compat::Future01CompatExt;
+use std::{
+    future::Future as StdFuture,
+    pin::Pin,
+    sync::Arc,
+    task::{
+        Context,
+        Poll,
+        Wake,
+        Waker,
+    },
+};
+
+use error::Error;
+
+/** A boxed standard future that's returned by an asynchronous client. */
+pub(crate) type BoxFuture<T> = Pin<Box<StdFuture<Output = Result<T, Error>> + Send>>;
+
+/**
+Box a future that's built from `futures` 0.1 combinators as a standard future.
+
+The future is polled in a `futures` 0.1 task that wakes the standard task polling it.
+*/
+pub(crate) fn box_future<F>(fut: F) -> BoxFuture<F::Item>
+where
+    F: Future<Error = Error> + Send + 'static,
+{
+    Box::pin(fut.compat())
+}
+
+/** Notifies a `futures` 0.1 task when a standard task is woken. */
+struct TaskWake(::futures::task::Task);
+
+impl Wake for TaskWake {
+    fn wake(self: Arc<Self>) {
+        self.0.notify();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.notify();
+    }
+}
+
+/**
+Poll a standard future from within a `futures` 0.1 task.
+
+This function panics if it's called outside of a `futures` 0.1 task.
+*/
+pub(crate) fn poll_01<F>(fut: &mut F) -> Async<F::Output>
+where
+    F: StdFuture + Unpin,
+{
+    let waker = Waker::from(Arc::new(TaskWake(::futures::task::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    match Pin::new(fut).poll(&mut cx) {
+        Poll::Ready(output) => Async::Ready(output),
+        Poll::Pending => Async::NotReady,
+    }
+}
+
+/**
+Implement the standard `Future` trait for a future that wraps an unpinned standard future, like a `BoxFuture`, in an `inner` field.
+
+The future also gets a `compat` method that converts it into a `futures` 0.1 future.
+Any type parameters of the future are listed after its output.
+*/
+macro_rules! pending_future {
+    ($ty:ty => $item:ty) => {
+        pending_future!($ty => $item,);
+    };
+    ($ty:ty => $item:ty, $($param:ident),*) => {
+        impl<$($param),*> $ty {
+            /**
+            Convert the future into a `futures` 0.1 future.
+
+            The returned future can be used with `futures` 0.1 combinators, but it still needs to be polled within a `tokio` 1 runtime.
+            */
+            pub fn compat(self) -> ::futures_util::compat::Compat<Self> {
+                ::futures_util::compat::Compat::new(self)
+            }
+        }
+
+        /**
+        Await the future in an `async` function.
+
+        Requests are sent on the current `tokio` 1 runtime, so the future must be polled within one, like the runtime started by `#[tokio::main]`.
+        Polling it outside of a runtime fails with an error.
+        */
+        impl<$($param),*> ::std::future::Future for $ty {
+            type Output = ::std::result::Result<$item, ::error::Error>;
+
+            fn poll(
+                mut self: ::std::pin::Pin<&mut Self>,
+                cx: &mut ::std::task::Context,
+            ) -> ::std::task::Poll<Self::Output> {
+                ::std::future::Future::poll(::std::pin::Pin::new(&mut self.inner), cx)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{
+        future,
+        sync::oneshot,
+        Future,
+    };
+    use std::sync::atomic::{
+        AtomicBool,
+        Ordering,
+    };
+
+    use super::*;
+    use error;
+
+    struct Pending {
+        inner: BoxFuture<i32>,
+    }
+
+    impl Pending {
+        fn new(rx: oneshot::Receiver<i32>) -> Self {
+            Pending {
+                inner: box_future(rx.map_err(error::request)),
+            }
+        }
+    }
+
+    pending_future!(Pending => i32);
+
+    #[derive(Default)]
+    struct Woken(AtomicBool);
+
+    impl Wake for Woken {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn pending_future_wakes_when_ready() {
+        let (tx, rx) = oneshot::channel();
+        let mut pending = Pending::new(rx);
+
+        let woken = Arc::new(Woken::default());
+        let waker = Waker::from(woken.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        assert!(Pin::new(&mut pending).poll(&mut cx).is_pending());
+
+        tx.send(1).unwrap();
+        assert!(woken.0.load(Ordering::SeqCst));
+
+        match Pin::new(&mut pending).poll(&mut cx) {
+            Poll::Ready(Ok(1)) => (),
+            _ => panic!("expected the future to be ready"),
+        }
+    }
+
+    #[test]
+    fn pending_future_returns_errors() {
+        let (tx, rx) = oneshot::channel();
+        let mut pending = Pending::new(rx);
+        drop(tx);
+
+        let waker = Waker::from(Arc::new(Woken::default()));
+        let mut cx = Context::from_waker(&waker);
+
+        match Pin::new(&mut pending).poll(&mut cx) {
+            Poll::Ready(Err(_)) => (),
+            _ => panic!("expected the future to fail"),
+        }
+    }
+
+    #[test]
+    fn pending_future_compat() {
+        let (tx, rx) = oneshot::channel();
+        let pending = Pending::new(rx);
+
+        tx.send(1).unwrap();
+
+        assert_eq!(1, pending.compat().wait().unwrap());
+    }
+
+    #[test]
+    fn poll_01_polls_std_future() {
+        let mut ready = ::std::future::ready(1);
+
+        let polled = future::lazy(|| Ok::<_, ()>(poll_01(&mut ready)))
+            .wait()
+            .unwrap();
+
+        assert_eq!(Async::Ready(1), polled);
+    }
+}
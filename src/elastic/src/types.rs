@@ -44,13 +44,23 @@ The following table illustrates the types provided by `elastic`:
  `byte`              | `i8`                        | `std`     | [`Byte<M>`][number-mod]                                  | -
  `float`             | `f32`                       | `std`     | [`Float<M>`][number-mod]                                 | -
  `double`            | `f64`                       | `std`     | [`Double<M>`][number-mod]                                | -
+ `half_float`        | -                           | -         | [`HalfFloat<M>`][number-mod]                             | -
+ `scaled_float`      | -                           | -         | [`ScaledFloat<M>`][number-mod]                           | -
  `keyword`           | -                           | -         | [`Keyword<M>`][string-mod]                               | -
  `text`              | `String`                    | `std`     | [`Text<M>`][string-mod]                                  | -
  `boolean`           | `bool`                      | `std`     | [`Boolean<M>`][boolean-mod]                              | -
- `ip`                | `Ipv4Addr`                  | `std`     | [`Ip<M>`][ip-mod]                                        | -
+ `ip`                | `IpAddr`                    | `std`     | [`Ip<M>`][ip-mod]                                        | -
  `date`              | `DateTime<UTC>`             | `chrono`  | [`Date<M>`][date-mod]                                    | `DateFormat`
+ `date_nanos`        | -                           | -         | [`DateNanos<M>`][datenanos-mod]                          | `DateFormat`
  `geo_point`         | `Point`                     | `geo`     | [`GeoPoint<M>`][geopoint-mod]                            | `GeoPointFormat`
  `geo_shape`         | -                           | `geojson` | [`GeoShape<M>`][geoshape-mod]                            | -
+ `integer_range`     | -                           | -         | [`IntegerRange<M>`][range-mod]                           | -
+ `long_range`        | -                           | -         | [`LongRange<M>`][range-mod]                              | -
+ `float_range`       | -                           | -         | [`FloatRange<M>`][range-mod]                             | -
+ `double_range`      | -                           | -         | [`DoubleRange<M>`][range-mod]                            | -
+ `date_range`        | -                           | -         | [`DateRange<M>`][range-mod]                              | `DateFormat`
+ `ip_range`          | -                           | -         | [`IpRange<M>`][range-mod]                                | -
+ `dense_vector`      | -                           | -         | [`DenseVector<M>`][vector-mod]                           | -
 
 ## Mapping
 
@@ -260,8 +270,11 @@ Serialising `MyType`s mapping will produce the following json:
 [boolean-mod]: boolean/index.html
 [ip-mod]: ip/index.html
 [date-mod]: date/index.html
+[datenanos-mod]: date/nanos/index.html
 [geopoint-mod]: geo/point/index.html
 [geoshape-mod]: geo/shape/index.html
+[range-mod]: range/index.html
+[vector-mod]: vector/index.html
 */
 
 pub use elastic_types::{
@@ -272,7 +285,9 @@ pub use elastic_types::{
     ip,
     number,
     prelude,
+    range,
     string,
+    vector,
 };
 
 #[doc(hidden)]
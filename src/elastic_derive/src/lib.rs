@@ -11,6 +11,7 @@ extern crate proc_macro;
 extern crate elastic_types_derive_internals as internals;
 #[macro_use]
 extern crate quote;
+#[macro_use]
 extern crate syn;
 
 use internals::{
@@ -20,30 +21,21 @@ use internals::{
 
 #[proc_macro_derive(ElasticType, attributes(elastic))]
 pub fn derive_elastic_type(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let mut expanded = quote::Tokens::new();
-    let ast = syn::parse_macro_input(&input.to_string()).unwrap();
+    let ast = parse_macro_input!(input as syn::DeriveInput);
 
     match elastic_type::expand_derive(quote!(::elastic::types), &ast) {
-        Ok(genned) => {
-            expanded.append_all(genned);
-
-            expanded.to_string().parse().unwrap()
-        }
-        Err(e) => panic!("{}", e),
+        Ok(genned) => quote!(#(#genned)*).into(),
+        // Report an error as a compiler error on the tokens that caused it instead of a panic
+        Err(e) => syn::Error::new(e.span(), e).to_compile_error().into(),
     }
 }
 
 #[proc_macro_derive(ElasticDateFormat, attributes(elastic))]
 pub fn derive_date_format(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let mut expanded = quote::Tokens::new();
-    let ast = syn::parse_macro_input(&input.to_string()).unwrap();
+    let ast = parse_macro_input!(input as syn::DeriveInput);
 
     match date_format::expand_derive(quote!(::elastic::types), &ast) {
-        Ok(genned) => {
-            expanded.append_all(genned);
-
-            expanded.to_string().parse().unwrap()
-        }
-        Err(e) => panic!("{}", e),
+        Ok(genned) => quote!(#(#genned)*).into(),
+        Err(e) => syn::Error::new(e.span(), e).to_compile_error().into(),
     }
 }
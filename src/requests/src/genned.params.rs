@@ -13,4 +13,11 @@ impl_from_num_for_id!(u64);
 impl_from_num_for_id!(usize);
 impl_from_num_for_id!(i32);
 impl_from_num_for_id!(i64);
-impl_from_num_for_id!(isize);
\ No newline at end of file
+impl_from_num_for_id!(isize);
+
+#[cfg(feature = "uuid")]
+impl<'a> From<::uuid::Uuid> for Id<'a> {
+    fn from(value: ::uuid::Uuid) -> Id<'a> {
+        Id::from(value.to_string())
+    }
+}
\ No newline at end of file
@@ -56,6 +56,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.upgrade",
             }
         }
     }
@@ -134,6 +135,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "snapshot.status",
             }
         }
     }
@@ -246,6 +248,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "indices.get_field_mapping",
             }
         }
     }
@@ -281,6 +284,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.update_aliases",
             }
         }
     }
@@ -333,6 +337,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "scroll",
             }
         }
     }
@@ -374,6 +379,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "indices.get",
             }
         }
     }
@@ -427,6 +433,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "rank_eval",
             }
         }
     }
@@ -460,6 +467,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "tasks.list",
             }
         }
     }
@@ -534,6 +542,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "msearch",
             }
         }
     }
@@ -587,6 +596,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "field_caps",
             }
         }
     }
@@ -640,6 +650,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "tasks.cancel",
             }
         }
     }
@@ -708,6 +719,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.rollover",
             }
         }
     }
@@ -745,6 +757,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "scripts_painless_execute",
             }
         }
     }
@@ -794,6 +807,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.snapshots",
             }
         }
     }
@@ -846,6 +860,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "render_search_template",
             }
         }
     }
@@ -896,6 +911,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "indices.segments",
             }
         }
     }
@@ -929,6 +945,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.master",
             }
         }
     }
@@ -978,6 +995,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.fielddata",
             }
         }
     }
@@ -1013,6 +1031,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "sql.query",
             }
         }
     }
@@ -1089,6 +1108,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "update",
             }
         }
     }
@@ -1133,6 +1153,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::HEAD,
                 body: None,
+                name: "indices.exists_type",
             }
         }
     }
@@ -1218,6 +1239,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "indices.get_mapping",
             }
         }
     }
@@ -1271,6 +1293,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.refresh",
             }
         }
     }
@@ -1417,6 +1440,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "nodes.stats",
             }
         }
     }
@@ -1452,6 +1476,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "reindex",
             }
         }
     }
@@ -1501,6 +1526,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cluster.stats",
             }
         }
     }
@@ -1586,6 +1612,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "indices.get_alias",
             }
         }
     }
@@ -1660,6 +1687,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "search_template",
             }
         }
     }
@@ -1693,6 +1721,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.nodes",
             }
         }
     }
@@ -1767,6 +1796,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "msearch_template",
             }
         }
     }
@@ -1820,6 +1850,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.forcemerge",
             }
         }
     }
@@ -1875,6 +1906,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "snapshot.create",
             }
         }
     }
@@ -1928,6 +1960,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.flush",
             }
         }
     }
@@ -2013,6 +2046,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "indices.get_settings",
             }
         }
     }
@@ -2054,6 +2088,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::HEAD,
                 body: None,
+                name: "indices.exists_template",
             }
         }
     }
@@ -2110,6 +2145,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "snapshot.restore",
             }
         }
     }
@@ -2159,6 +2195,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "snapshot.get",
             }
         }
     }
@@ -2227,6 +2264,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "get_source",
             }
         }
     }
@@ -2276,6 +2314,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.aliases",
             }
         }
     }
@@ -2352,6 +2391,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "create",
             }
         }
     }
@@ -2401,6 +2441,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.recovery",
             }
         }
     }
@@ -2444,6 +2485,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::PUT,
                 body: Some(self.body),
+                name: "ingest.put_pipeline",
             }
         }
     }
@@ -2528,6 +2570,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "nodes.info",
             }
         }
     }
@@ -2577,6 +2620,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.allocation",
             }
         }
     }
@@ -2626,6 +2670,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.shards",
             }
         }
     }
@@ -2686,6 +2731,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::HEAD,
                 body: None,
+                name: "indices.exists_alias",
             }
         }
     }
@@ -2760,6 +2806,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "count",
             }
         }
     }
@@ -2793,6 +2840,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "ingest.processor_grok",
             }
         }
     }
@@ -2867,6 +2915,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.validate_query",
             }
         }
     }
@@ -2935,6 +2984,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::HEAD,
                 body: None,
+                name: "exists_source",
             }
         }
     }
@@ -2968,6 +3018,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.tasks",
             }
         }
     }
@@ -3034,6 +3085,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::HEAD,
                 body: None,
+                name: "exists",
             }
         }
     }
@@ -3087,6 +3139,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "ingest.simulate",
             }
         }
     }
@@ -3161,6 +3214,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "mget",
             }
         }
     }
@@ -3202,6 +3256,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::HEAD,
                 body: None,
+                name: "indices.exists",
             }
         }
     }
@@ -3243,6 +3298,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "get_script",
             }
         }
     }
@@ -3287,6 +3343,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "reindex_rethrottle",
             }
         }
     }
@@ -3331,6 +3388,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "update_by_query_rethrottle",
             }
         }
     }
@@ -3375,6 +3433,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::DELETE,
                 body: None,
+                name: "indices.delete_alias",
             }
         }
     }
@@ -3456,6 +3515,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.put_mapping",
             }
         }
     }
@@ -3541,6 +3601,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "nodes.usage",
             }
         }
     }
@@ -3594,6 +3655,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.clear_cache",
             }
         }
     }
@@ -3646,6 +3708,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::DELETE,
                 body: None,
+                name: "snapshot.delete",
             }
         }
     }
@@ -3692,6 +3755,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.shrink",
             }
         }
     }
@@ -3768,6 +3832,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "explain",
             }
         }
     }
@@ -3838,6 +3903,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "simple_search",
             }
         }
     }
@@ -3912,6 +3978,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "search",
             }
         }
     }
@@ -3945,6 +4012,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.help",
             }
         }
     }
@@ -3994,6 +4062,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.indices",
             }
         }
     }
@@ -4047,6 +4116,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.analyze",
             }
         }
     }
@@ -4159,6 +4229,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "index",
             }
         }
     }
@@ -4200,6 +4271,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::DELETE,
                 body: None,
+                name: "ingest.delete_pipeline",
             }
         }
     }
@@ -4244,6 +4316,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.close",
             }
         }
     }
@@ -4277,6 +4350,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.pending_tasks",
             }
         }
     }
@@ -4362,6 +4436,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "indices.stats",
             }
         }
     }
@@ -4412,6 +4487,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "indices.recovery",
             }
         }
     }
@@ -4445,6 +4521,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "info",
             }
         }
     }
@@ -4494,6 +4571,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cluster.health",
             }
         }
     }
@@ -4549,6 +4627,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "nodes.reload_secure_settings",
             }
         }
     }
@@ -4623,6 +4702,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "mtermvectors",
             }
         }
     }
@@ -4667,6 +4747,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.open",
             }
         }
     }
@@ -4700,6 +4781,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.plugins",
             }
         }
     }
@@ -4765,6 +4847,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "update_by_query",
             }
         }
     }
@@ -4814,6 +4897,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.count",
             }
         }
     }
@@ -4858,6 +4942,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "snapshot.verify_repository",
             }
         }
     }
@@ -4904,6 +4989,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.split",
             }
         }
     }
@@ -4939,6 +5025,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "cluster.reroute",
             }
         }
     }
@@ -4985,6 +5072,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.put_alias",
             }
         }
     }
@@ -5018,6 +5106,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cluster.pending_tasks",
             }
         }
     }
@@ -5059,6 +5148,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::DELETE,
                 body: None,
+                name: "indices.delete_template",
             }
         }
     }
@@ -5108,6 +5198,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "snapshot.get_repository",
             }
         }
     }
@@ -5152,6 +5243,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "delete_by_query_rethrottle",
             }
         }
     }
@@ -5185,6 +5277,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.health",
             }
         }
     }
@@ -5218,6 +5311,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.repositories",
             }
         }
     }
@@ -5251,6 +5345,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.nodeattrs",
             }
         }
     }
@@ -5303,6 +5398,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::DELETE,
                 body: Some(self.body),
+                name: "clear_scroll",
             }
         }
     }
@@ -5344,6 +5440,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "tasks.get",
             }
         }
     }
@@ -5395,6 +5492,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.thread_pool",
             }
         }
     }
@@ -5438,6 +5536,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "snapshot.create_repository",
             }
         }
     }
@@ -5479,6 +5578,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::DELETE,
                 body: None,
+                name: "delete_script",
             }
         }
     }
@@ -5532,6 +5632,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.flush_synced",
             }
         }
     }
@@ -5573,6 +5674,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::DELETE,
                 body: None,
+                name: "snapshot.delete_repository",
             }
         }
     }
@@ -5623,6 +5725,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "indices.shard_stores",
             }
         }
     }
@@ -5691,6 +5794,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cluster.state",
             }
         }
     }
@@ -5754,6 +5858,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "put_script",
             }
         }
     }
@@ -5789,6 +5894,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::PUT,
                 body: Some(self.body),
+                name: "cluster.put_settings",
             }
         }
     }
@@ -5854,6 +5960,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "delete_by_query",
             }
         }
     }
@@ -5887,6 +5994,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cluster.remote_info",
             }
         }
     }
@@ -5936,6 +6044,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.segments",
             }
         }
     }
@@ -5989,6 +6098,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::PUT,
                 body: Some(self.body),
+                name: "indices.put_settings",
             }
         }
     }
@@ -6022,6 +6132,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "ping",
             }
         }
     }
@@ -6055,6 +6166,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::HEAD,
                 body: None,
+                name: "ping_head",
             }
         }
     }
@@ -6098,6 +6210,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::PUT,
                 body: Some(self.body),
+                name: "indices.create",
             }
         }
     }
@@ -6147,6 +6260,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cat.templates",
             }
         }
     }
@@ -6197,6 +6311,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "nodes.hot_threads",
             }
         }
     }
@@ -6263,6 +6378,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::DELETE,
                 body: None,
+                name: "delete",
             }
         }
     }
@@ -6329,6 +6445,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "get",
             }
         }
     }
@@ -6444,6 +6561,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "termvectors",
             }
         }
     }
@@ -6493,6 +6611,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "ingest.get_pipeline",
             }
         }
     }
@@ -6530,6 +6649,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "cluster.allocation_explain",
             }
         }
     }
@@ -6571,6 +6691,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::DELETE,
                 body: None,
+                name: "indices.delete",
             }
         }
     }
@@ -6645,6 +6766,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "bulk",
             }
         }
     }
@@ -6678,6 +6800,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "cluster.get_settings",
             }
         }
     }
@@ -6721,6 +6844,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "indices.put_template",
             }
         }
     }
@@ -6771,6 +6895,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "indices.get_upgrade",
             }
         }
     }
@@ -6820,6 +6945,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::GET,
                 body: None,
+                name: "indices.get_template",
             }
         }
     }
@@ -6873,6 +6999,7 @@ pub mod endpoints {
                 url: self.url,
                 method: Method::POST,
                 body: Some(self.body),
+                name: "search_shards",
             }
         }
     }
@@ -6911,6 +7038,7 @@ pub mod http {
         pub url: UrlPath<'a>,
         pub method: Method,
         pub body: Option<B>,
+        pub name: &'static str,
     }
     #[doc = r" A default body type."]
     pub type DefaultBody = &'static [u8];
@@ -82,6 +82,9 @@
 
 #![deny(warnings)]
 
+#[cfg(feature = "uuid")]
+extern crate uuid;
+
 mod genned;
 
 /// Common url params like `Id` and `Index`.
@@ -89,7 +92,9 @@ mod genned;
 /// The parameter types are basically just a wrapper around a maybe
 /// owned string.
 /// They can all be constructed from a `String` or an `&str`, but some
-/// parameters may have other implementations in the future.
+/// parameters have other implementations.
+/// An `Id` can also be constructed from a number, or from a `uuid::Uuid`
+/// with the `uuid` feature.
 pub mod params {
     pub use genned::params::*;
 }
@@ -176,4 +181,12 @@ mod tests {
             assert_eq!("1", &*id);
         }
     }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn id_from_uuid() {
+        let uuid = ::uuid::Uuid::parse_str("936da01f9abd4d9d80c702af85c822a8").unwrap();
+
+        assert_eq!("936da01f-9abd-4d9d-80c7-02af85c822a8", &*Id::from(uuid));
+    }
 }
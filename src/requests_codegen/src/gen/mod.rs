@@ -130,7 +130,8 @@ pub mod types {
                 pub struct #request_ty<'a, #body_ty> {
                     pub url: #url_ty,
                     pub method: #method_ty,
-                    pub body: Option<#body_ty>
+                    pub body: Option<#body_ty>,
+                    pub name: &'static str
                 }
             )
         }
@@ -10,14 +10,16 @@ use quote;
 use syn;
 
 pub struct RequestIntoEndpointBuilder {
+    name: String,
     req_ty: syn::Ty,
     has_body: bool,
     http_verb: Method,
 }
 
 impl RequestIntoEndpointBuilder {
-    pub fn new(http_verb: Method, has_body: bool, request_ty: syn::Ty) -> Self {
+    pub fn new(name: &str, http_verb: Method, has_body: bool, request_ty: syn::Ty) -> Self {
         RequestIntoEndpointBuilder {
+            name: name.to_owned(),
             req_ty: request_ty,
             has_body: has_body,
             http_verb: http_verb,
@@ -25,6 +27,7 @@ impl RequestIntoEndpointBuilder {
     }
 
     pub fn build(self) -> quote::Tokens {
+        let name = self.name;
         let req_ty = self.req_ty;
         let method_ty = types::request::method_ty();
 
@@ -47,7 +50,8 @@ impl RequestIntoEndpointBuilder {
                         #endpoint_ty {
                             url: self.url,
                             method: #method,
-                            body: Some(self.body)
+                            body: Some(self.body),
+                            name: #name
                         }
                     }
                 }
@@ -61,7 +65,8 @@ impl RequestIntoEndpointBuilder {
                         #endpoint_ty {
                             url: self.url,
                             method: #method,
-                            body: None
+                            body: None,
+                            name: #name
                         }
                     }
                 }
@@ -72,12 +77,12 @@ impl RequestIntoEndpointBuilder {
 
 impl<'a> From<(&'a (String, Endpoint), &'a syn::Ty)> for RequestIntoEndpointBuilder {
     fn from(value: (&'a (String, Endpoint), &'a syn::Ty)) -> Self {
-        let (&(_, ref endpoint), ref req_ty) = value;
+        let (&(ref name, ref endpoint), ref req_ty) = value;
 
         let has_body = endpoint.has_body();
         let verb = endpoint.methods[0];
 
-        RequestIntoEndpointBuilder::new(verb, has_body, (*req_ty).to_owned())
+        RequestIntoEndpointBuilder::new(name, verb, has_body, (*req_ty).to_owned())
     }
 }
 
@@ -109,7 +114,8 @@ mod tests {
                     Endpoint {
                         url: self.url,
                         method: Method::GET,
-                        body: Some(self.body)
+                        body: Some(self.body),
+                        name: "indices.exists_alias"
                     }
                 }
             }
@@ -139,7 +145,8 @@ mod tests {
                     Endpoint {
                         url: self.url,
                         method: Method::GET,
-                        body: None
+                        body: None,
+                        name: "indices.exists_alias"
                     }
                 }
             }
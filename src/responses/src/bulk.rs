@@ -220,6 +220,38 @@ impl<TIndex, TType, TId> BulkResponse<TIndex, TType, TId> {
     pub fn iter(&self) -> ResultIter<TIndex, TType, TId> {
         ResultIter(self.items.iter())
     }
+
+    /**
+    Move the items from another `BulkResponse` onto the end of this one.
+
+    This can be used to combine the responses from multiple bulk requests into a single response.
+    The `took` value of the combined response will be the sum of both responses.
+    */
+    pub fn append(&mut self, mut other: Self) {
+        self.took += other.took;
+        self.errors |= other.errors;
+        self.items.append(&mut other.items);
+    }
+
+    /**
+    Replace the items at the given positions with the items from another `BulkResponse`.
+
+    This can be used to merge the response to a bulk request that retried some failed items back into the original response.
+    The items in `other` are expected to be in the same order as `positions`.
+    Positions that are out of bounds and any extra items in `other` are ignored.
+    The `took` value of the merged response will be the sum of both responses.
+    */
+    pub fn replace(&mut self, positions: &[usize], other: Self) {
+        self.took += other.took;
+
+        for (&position, item) in positions.iter().zip(other.items) {
+            if let Some(existing) = self.items.get_mut(position) {
+                *existing = item;
+            }
+        }
+
+        self.errors = self.items.iter().any(Result::is_err);
+    }
 }
 
 impl<TIndex, TType, TId> IntoIterator for BulkResponse<TIndex, TType, TId> {
@@ -409,6 +441,18 @@ impl<TIndex, TType, TId> BulkErrorsResponse<TIndex, TType, TId> {
     pub fn iter(&self) -> ErrorIter<TIndex, TType, TId> {
         ErrorIter(self.items.iter())
     }
+
+    /**
+    Move the errors from another `BulkErrorsResponse` onto the end of this one.
+
+    This can be used to combine the responses from multiple bulk requests into a single response.
+    The `took` value of the combined response will be the sum of both responses.
+    */
+    pub fn append(&mut self, mut other: Self) {
+        self.took += other.took;
+        self.errors |= other.errors;
+        self.items.append(&mut other.items);
+    }
 }
 
 type ItemResult<TIndex, TType, TId> =
@@ -494,6 +538,7 @@ pub struct ErrorItem<
     index: TIndex,
     ty: TType,
     id: TId,
+    status: u16,
     err: BulkError,
 }
 
@@ -518,6 +563,11 @@ impl<TIndex, TType, TId> ErrorItem<TIndex, TType, TId> {
         &self.id
     }
 
+    /** The HTTP status code for this item. */
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
     /** Raw error JSON. */
     pub fn err(&self) -> &BulkError {
         &self.err
@@ -608,6 +658,7 @@ where
                 index: self.inner.index,
                 ty: self.inner.ty,
                 id: self.inner.id,
+                status: self.inner.status,
                 err: err,
             }),
             None => None,
@@ -1,3 +1,20 @@
+use serde::de::{
+    Deserialize,
+    DeserializeSeed,
+    Deserializer,
+    Error as DeError,
+    MapAccess,
+    SeqAccess,
+    Visitor,
+};
+use serde_json::{
+    self,
+    map,
+    Map,
+    Value,
+};
+use std::vec;
+
 /** A default type for allocated fields in responses. */
 pub(crate) type DefaultAllocatedField = String;
 
@@ -26,6 +43,212 @@ impl Shards {
     }
 }
 
+/**
+Deserialise a document from the stored fields or doc values returned for it.
+
+This is used as a fallback for documents that don't have a `_source`.
+Field values are always returned as arrays, so arrays are kept for collections like `Vec<T>` and single values are unwrapped for everything else.
+Field names containing dots are expanded into inner objects.
+*/
+pub(crate) fn document_from_fields<'de, T>(
+    fields: &Map<String, Value>,
+) -> Result<T, serde_json::Error>
+where
+    T: Deserialize<'de>,
+{
+    let mut document = Map::new();
+
+    for (name, value) in fields {
+        let mut path: Vec<&str> = name.split('.').collect();
+        let last = path.pop().expect("split always returns at least one item");
+
+        let mut object = &mut document;
+        for part in path {
+            let inner = object
+                .entry(part.to_owned())
+                .or_insert_with(|| Value::Object(Map::new()));
+
+            object = match *inner {
+                Value::Object(ref mut inner) => inner,
+                _ => {
+                    return Err(DeError::custom(format_args!(
+                        "the field `{}` has both values and inner fields",
+                        part
+                    )))
+                }
+            };
+        }
+
+        object.insert(last.to_owned(), value.clone());
+    }
+
+    T::deserialize(FieldValue(Value::Object(document)))
+}
+
+/** A deserialiser for the values of a field, which may be wrapped in an array. */
+struct FieldValue(Value);
+
+impl FieldValue {
+    /** Unwrap an array with a single value. */
+    fn unwrap_single(self) -> Value {
+        match self.0 {
+            Value::Array(mut values) => {
+                if values.len() == 1 {
+                    values.pop().expect("the array has a single value")
+                } else {
+                    Value::Array(values)
+                }
+            }
+            value => value,
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for FieldValue {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.unwrap_single() {
+            Value::Array(values) => visitor.visit_seq(FieldSeq(values.into_iter())),
+            Value::Object(values) => visitor.visit_map(FieldMap {
+                iter: values.into_iter(),
+                value: None,
+            }),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            Value::Array(ref values) if values.is_empty() => visitor.visit_none(),
+            value => visitor.visit_some(FieldValue(value)),
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Value::Array(values) => visitor.visit_seq(FieldSeq(values.into_iter())),
+            value => visitor.visit_seq(FieldSeq(vec![value].into_iter())),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.unwrap_single()
+            .deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct map struct identifier ignored_any
+    }
+}
+
+struct FieldSeq(vec::IntoIter<Value>);
+
+impl<'de> SeqAccess<'de> for FieldSeq {
+    type Error = serde_json::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.0.next() {
+            Some(value) => seed.deserialize(FieldValue(value)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct FieldMap {
+    iter: map::IntoIter,
+    value: Option<Value>,
+}
+
+impl<'de> MapAccess<'de> for FieldMap {
+    type Error = serde_json::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(Value::String(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| DeError::custom("a value was requested before its key"))?;
+
+        seed.deserialize(FieldValue(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
 #[derive(Clone, Copy, Deserialize, Debug, PartialEq, Eq)]
 pub(crate) enum DocumentResult {
     #[serde(rename = "deleted")]
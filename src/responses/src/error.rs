@@ -27,14 +27,28 @@ mod inner {
         fmt,
     };
 
-    use super::ApiError;
+    use super::{
+        ApiError,
+        ErrorCause,
+    };
 
     #[derive(Debug)]
-    pub struct UnknownApiError(pub Map<String, Value>);
+    pub struct UnknownApiError {
+        pub body: Map<String, Value>,
+        pub cause: Option<ErrorCause>,
+    }
+
+    impl UnknownApiError {
+        pub fn new(body: Map<String, Value>) -> Self {
+            let cause = ErrorCause::parse(body.clone());
+
+            UnknownApiError { body, cause }
+        }
+    }
 
     impl fmt::Display for UnknownApiError {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            (&self.0 as &fmt::Debug).fmt(f)
+            (&self.body as &fmt::Debug).fmt(f)
         }
     }
 
@@ -73,6 +87,21 @@ impl ParseError {
             inner: Box::new(err),
         }
     }
+
+    /**
+    The structured cause of an API error that isn't one of the known [`ApiError`][ApiError] variants.
+
+    Errors returned by Elasticsearch that can't be matched to a known variant are returned as a `ParseError` containing the raw error body.
+    If that body has a `type` then its `caused_by` chain, `root_cause`s and `suppressed` errors are returned as an [`ErrorCause`][ErrorCause].
+
+    [ApiError]: enum.ApiError.html
+    [ErrorCause]: struct.ErrorCause.html
+    */
+    pub fn api_error_cause(&self) -> Option<&ErrorCause> {
+        self.inner
+            .downcast_ref::<UnknownApiError>()
+            .and_then(|err| err.cause.as_ref())
+    }
 }
 
 impl From<IoError> for ParseError {
@@ -174,11 +203,339 @@ quick_error! {
             description("verification exception")
             display("verification error: '{}", reason)
         }
+        /**
+        A script failed to compile or execute.
+
+        Script errors are often wrapped in other errors, like an `illegal_argument_exception` or a `search_phase_execution_exception`.
+        If a `script_exception` is found anywhere in the causes of an error then it will be returned as a `Script` error.
+        The `script` contains details about the script itself, like the offending line and column.
+        The `cause` contains the complete error returned by Elasticsearch.
+        */
+        Script { reason: String, script: Box<ScriptError>, cause: Box<ErrorCause> } {
+            description("script error")
+            display("script error: '{}'{}", reason, script)
+        }
         #[doc(hidden)]
         __NonExhaustive {}
     }
 }
 
+/**
+The structured reason for an API error.
+
+An error cause contains the type of error and a human readable reason along with any further causes.
+Errors returned by Elasticsearch can be nested in a few ways:
+
+- `caused_by` is the error that caused this one
+- `root_cause` contains the underlying errors at the bottom of each cause chain
+- `suppressed` contains other errors that were suppressed while handling this one.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorCause {
+    ty: String,
+    reason: Option<String>,
+    caused_by: Option<Box<ErrorCause>>,
+    root_cause: Vec<ErrorCause>,
+    suppressed: Vec<ErrorCause>,
+    script: Option<ScriptError>,
+    additional: Map<String, Value>,
+}
+
+impl ErrorCause {
+    /** The type of error, like `illegal_argument_exception`. */
+    pub fn ty(&self) -> &str {
+        &self.ty
+    }
+
+    /** A human readable reason for the error. */
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    /** The error that caused this one. */
+    pub fn caused_by(&self) -> Option<&ErrorCause> {
+        self.caused_by.as_deref()
+    }
+
+    /** The underlying causes of this error. */
+    pub fn root_cause(&self) -> &[ErrorCause] {
+        &self.root_cause
+    }
+
+    /** Any errors suppressed while handling this one. */
+    pub fn suppressed(&self) -> &[ErrorCause] {
+        &self.suppressed
+    }
+
+    /** Details of the script that caused this error, if this is a `script_exception`. */
+    pub fn script(&self) -> Option<&ScriptError> {
+        self.script.as_ref()
+    }
+
+    /** Get any other property on the error, like `index` or `shard`. */
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.additional.get(key)
+    }
+
+    /** Iterate through this error and the chain of errors that caused it. */
+    pub fn chain(&self) -> CauseChain<'_> {
+        CauseChain { next: Some(self) }
+    }
+
+    /**
+    Find the first script error in this error or its causes.
+
+    The `caused_by` chain is searched first, then the `root_cause`s and any `suppressed` errors.
+    */
+    pub fn find_script(&self) -> Option<&ErrorCause> {
+        self.chain()
+            .find(|cause| cause.script.is_some())
+            .or_else(|| {
+                self.root_cause
+                    .iter()
+                    .chain(self.suppressed.iter())
+                    .filter_map(|cause| cause.find_script())
+                    .next()
+            })
+    }
+
+    fn parse(mut obj: Map<String, Value>) -> Option<Self> {
+        let ty = match obj.remove("type") {
+            Some(Value::String(ty)) => ty,
+            _ => return None,
+        };
+
+        let reason = match obj.remove("reason") {
+            Some(Value::String(reason)) => Some(reason),
+            _ => None,
+        };
+
+        let caused_by = match obj.remove("caused_by") {
+            Some(Value::Object(caused_by)) => ErrorCause::parse(caused_by).map(Box::new),
+            _ => None,
+        };
+
+        let root_cause = ErrorCause::parse_all(obj.remove("root_cause"));
+        let suppressed = ErrorCause::parse_all(obj.remove("suppressed"));
+
+        let script = if ty == "script_exception" {
+            Some(ScriptError::parse(&mut obj))
+        } else {
+            None
+        };
+
+        Some(ErrorCause {
+            ty,
+            reason,
+            caused_by,
+            root_cause,
+            suppressed,
+            script,
+            additional: obj,
+        })
+    }
+
+    fn parse_all(causes: Option<Value>) -> Vec<Self> {
+        match causes {
+            Some(Value::Array(causes)) => causes
+                .into_iter()
+                .filter_map(|cause| match cause {
+                    Value::Object(cause) => ErrorCause::parse(cause),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for ErrorCause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.ty)?;
+
+        if let Some(ref reason) = self.reason {
+            write!(f, ": '{}'", reason)?;
+        }
+
+        if let Some(ref caused_by) = self.caused_by {
+            write!(f, ". Caused by: {}", caused_by)?;
+        }
+
+        Ok(())
+    }
+}
+
+/** An iterator over an error and the chain of errors that caused it. */
+pub struct CauseChain<'a> {
+    next: Option<&'a ErrorCause>,
+}
+
+impl<'a> Iterator for CauseChain<'a> {
+    type Item = &'a ErrorCause;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take();
+        self.next = current.and_then(|cause| cause.caused_by());
+
+        current
+    }
+}
+
+/**
+Details of a script that failed to compile or execute.
+
+The `script_stack` is a short excerpt of the script around the error.
+Newer versions of Elasticsearch also return the complete source of the `script` and the `position` of the error within it,
+which can be used to find the `line` and `column` the error occurred on.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptError {
+    script_stack: Vec<String>,
+    script: Option<String>,
+    lang: Option<String>,
+    position: Option<ScriptPosition>,
+}
+
+/** The position of an error within a script. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScriptPosition {
+    offset: usize,
+    start: usize,
+    end: usize,
+}
+
+impl ScriptError {
+    /** An excerpt of the script around the error. */
+    pub fn script_stack(&self) -> &[String] {
+        &self.script_stack
+    }
+
+    /** The source of the script. */
+    pub fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    /** The language of the script, like `painless`. */
+    pub fn lang(&self) -> Option<&str> {
+        self.lang.as_deref()
+    }
+
+    /** The position of the error within the script. */
+    pub fn position(&self) -> Option<ScriptPosition> {
+        self.position
+    }
+
+    /**
+    The line the error occurred on.
+
+    Lines start at `1`.
+    The line can only be determined if the `script` and `position` are both available.
+    */
+    pub fn line(&self) -> Option<usize> {
+        self.preceding().map(|preceding| preceding.matches('\n').count() + 1)
+    }
+
+    /**
+    The column the error occurred on.
+
+    Columns start at `1`.
+    The column can only be determined if the `script` and `position` are both available.
+    */
+    pub fn column(&self) -> Option<usize> {
+        self.preceding().map(|preceding| {
+            let line = match preceding.rfind('\n') {
+                Some(newline) => &preceding[newline + 1..],
+                None => preceding,
+            };
+
+            line.chars().count() + 1
+        })
+    }
+
+    fn preceding(&self) -> Option<&str> {
+        match (self.script.as_ref(), self.position.as_ref()) {
+            (Some(script), Some(position)) => script.get(..position.offset),
+            _ => None,
+        }
+    }
+
+    fn parse(obj: &mut Map<String, Value>) -> Self {
+        let script_stack = match obj.remove("script_stack") {
+            Some(Value::Array(stack)) => stack
+                .into_iter()
+                .filter_map(|line| match line {
+                    Value::String(line) => Some(line),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let script = match obj.remove("script") {
+            Some(Value::String(script)) => Some(script),
+            _ => None,
+        };
+
+        let lang = match obj.remove("lang") {
+            Some(Value::String(lang)) => Some(lang),
+            _ => None,
+        };
+
+        let position = match obj.remove("position") {
+            Some(Value::Object(position)) => ScriptPosition::parse(&position),
+            _ => None,
+        };
+
+        ScriptError {
+            script_stack,
+            script,
+            lang,
+            position,
+        }
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let (Some(line), Some(column)) = (self.line(), self.column()) {
+            write!(f, " at line {}, column {}", line, column)?;
+        }
+
+        for line in &self.script_stack {
+            write!(f, "\n{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ScriptPosition {
+    /** The offset of the error within the script. */
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /** The start of the excerpt around the error within the script. */
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /** The end of the excerpt around the error within the script. */
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    fn parse(obj: &Map<String, Value>) -> Option<Self> {
+        let field = |key: &str| obj.get(key).and_then(|v| v.as_u64()).map(|v| v as usize);
+
+        Some(ScriptPosition {
+            offset: field("offset")?,
+            start: field("start")?,
+            end: field("end")?,
+        })
+    }
+}
+
 macro_rules! error_key {
     ($obj:ident [ $key:ident ] : |$cast:ident| $cast_expr:expr) => {{
         let key = $obj
@@ -225,6 +582,29 @@ impl From<Map<String, Value>> for ParsedApiError {
             }
         };
 
+        let cause = match ErrorCause::parse(obj.clone()) {
+            Some(cause) => cause,
+            _ => return ParsedApiError::Unknown(obj),
+        };
+
+        let script = cause
+            .find_script()
+            .and_then(|script| script.script().map(|details| (script.reason(), details)))
+            .map(|(reason, details)| {
+                (
+                    reason.unwrap_or_default().to_owned(),
+                    Box::new(details.clone()),
+                )
+            });
+
+        if let Some((reason, script)) = script {
+            return ParsedApiError::Known(ApiError::Script {
+                reason,
+                script,
+                cause: Box::new(cause),
+            });
+        }
+
         match ty.as_ref() {
             "index_not_found_exception" => {
                 let index = error_key!(obj[index]: |v| v.as_str());
@@ -3,8 +3,18 @@ Response types for a [get document request](https://www.elastic.co/guide/en/elas
 */
 
 use http::StatusCode;
-use serde::de::DeserializeOwned;
+use serde::de::{
+    Deserialize,
+    DeserializeOwned,
+    Deserializer,
+    Error as DeError,
+};
+use serde_json::{
+    Map,
+    Value,
+};
 
+use common::document_from_fields;
 use error::*;
 use parsing::{
     HttpResponseHead,
@@ -14,9 +24,26 @@ use parsing::{
     Unbuffered,
 };
 
-/** Response for a [get document request](https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-get.html). */
-#[derive(Deserialize, Debug)]
+/**
+Response for a [get document request](https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-get.html).
+
+If the document doesn't have a `_source`, because it's disabled in the mapping or excluded from the request,
+then the document will be deserialised from any stored fields returned instead.
+*/
+#[derive(Debug)]
 pub struct GetResponse<T> {
+    index: String,
+    ty: String,
+    id: String,
+    version: Option<u32>,
+    found: bool,
+    source: Option<T>,
+    fields: Option<Map<String, Value>>,
+    routing: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GetResponseDe<T> {
     #[serde(rename = "_index")]
     index: String,
     #[serde(rename = "_type")]
@@ -28,16 +55,60 @@ pub struct GetResponse<T> {
     found: bool,
     #[serde(rename = "_source")]
     source: Option<T>,
+    fields: Option<Map<String, Value>>,
     #[serde(rename = "_routing")]
     routing: Option<String>,
 }
 
+impl<'de, T> Deserialize<'de> for GetResponse<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let res = GetResponseDe::<T>::deserialize(deserializer)?;
+
+        let source = match (res.source, &res.fields) {
+            (None, &Some(ref fields)) => {
+                Some(document_from_fields(fields).map_err(DeError::custom)?)
+            }
+            (source, _) => source,
+        };
+
+        Ok(GetResponse {
+            index: res.index,
+            ty: res.ty,
+            id: res.id,
+            version: res.version,
+            found: res.found,
+            source,
+            fields: res.fields,
+            routing: res.routing,
+        })
+    }
+}
+
 impl<T> GetResponse<T> {
-    /** Get a reference to the source document. */
+    /**
+    Get a reference to the source document.
+
+    If the `_source` wasn't returned then the document is deserialised from the stored fields.
+    */
     pub fn document(&self) -> Option<&T> {
         self.source.as_ref()
     }
 
+    /**
+    Get the stored fields returned for the document.
+
+    Stored fields are only returned when they're requested using the `stored_fields` parameter.
+    */
+    pub fn fields(&self) -> Option<&Map<String, Value>> {
+        self.fields.as_ref()
+    }
+
     /** Convert the response into the source document. */
     pub fn into_document(self) -> Option<T> {
         self.source
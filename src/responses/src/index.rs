@@ -38,6 +38,15 @@ impl IndexResponse {
         }
     }
 
+    /**
+    Whether or not the write was skipped because the document was unchanged.
+
+    Elasticsearch never skips index requests itself, but the client can skip writing documents whose content hash hasn't changed.
+    */
+    pub fn unchanged(&self) -> bool {
+        self.result == DocumentResult::NoOp
+    }
+
     /** The index for the document. */
     pub fn index(&self) -> &str {
         &self.index
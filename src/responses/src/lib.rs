@@ -147,8 +147,11 @@ mod delete;
 mod get;
 mod index;
 mod ping;
+mod reindex;
 pub mod search;
+mod snapshot;
 mod sql;
+mod tasks;
 mod update;
 
 mod indices_exists;
@@ -164,8 +167,11 @@ pub use self::{
     get::*,
     index::*,
     ping::*,
+    reindex::*,
     search::SearchResponse,
+    snapshot::*,
     sql::*,
+    tasks::*,
     update::*,
 };
 
@@ -185,7 +185,7 @@ impl<B: Read> ResponseBody for ReadBody<B> {
     fn parse_err(self) -> Result<ApiError, ParseError> {
         match serde_json::from_reader(self.0)? {
             ParsedApiError::Known(err) => Ok(err),
-            ParsedApiError::Unknown(err) => Err(ParseError::new(UnknownApiError(err))),
+            ParsedApiError::Unknown(err) => Err(ParseError::new(UnknownApiError::new(err))),
         }
     }
 }
@@ -210,7 +210,7 @@ impl<B: AsRef<[u8]>> ResponseBody for SliceBody<B> {
     fn parse_err(self) -> Result<ApiError, ParseError> {
         match serde_json::from_slice(self.0.as_ref())? {
             ParsedApiError::Known(err) => Ok(err),
-            ParsedApiError::Unknown(err) => Err(ParseError::new(UnknownApiError(err))),
+            ParsedApiError::Unknown(err) => Err(ParseError::new(UnknownApiError::new(err))),
         }
     }
 }
@@ -231,7 +231,7 @@ impl ResponseBody for Value {
     fn parse_err(self) -> Result<ApiError, ParseError> {
         match serde_json::from_value(self)? {
             ParsedApiError::Known(err) => Ok(err),
-            ParsedApiError::Unknown(err) => Err(ParseError::new(UnknownApiError(err))),
+            ParsedApiError::Unknown(err) => Err(ParseError::new(UnknownApiError::new(err))),
         }
     }
 }
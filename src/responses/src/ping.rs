@@ -16,6 +16,8 @@ pub struct PingResponse {
 #[doc(hidden)]
 #[derive(Deserialize, Debug)]
 pub struct ClusterVersion {
+    #[serde(default)]
+    distribution: Option<String>,
     number: String,
     build_hash: String,
     build_date: String,
@@ -41,6 +43,15 @@ impl PingResponse {
 }
 
 impl ClusterVersion {
+    /**
+    The distribution of the cluster.
+
+    OpenSearch clusters report `opensearch`, and Elasticsearch clusters don't report a distribution.
+    */
+    pub fn distribution(&self) -> Option<&str> {
+        self.distribution.as_deref()
+    }
+
     /** The builder number. */
     pub fn number(&self) -> &str {
         &self.number
@@ -0,0 +1,137 @@
+/*!
+Response types for a [reindex request](https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-reindex.html).
+*/
+
+use std::{
+    iter::Sum,
+    ops::Add,
+};
+
+use parsing::IsOkOnSuccess;
+use serde_json::Value;
+
+/** Response for a [reindex request](https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-reindex.html). */
+#[derive(Deserialize, Debug)]
+pub struct ReindexResponse {
+    took: u64,
+    timed_out: bool,
+    #[serde(flatten)]
+    status: ReindexStatus,
+    #[serde(default)]
+    failures: Vec<Value>,
+}
+
+impl ReindexResponse {
+    /** Time in milliseconds the whole reindex took. */
+    pub fn took(&self) -> u64 {
+        self.took
+    }
+
+    /** Whether or not any requests timed out during the reindex. */
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    /** The number of documents processed by the reindex. */
+    pub fn status(&self) -> &ReindexStatus {
+        &self.status
+    }
+
+    /**
+    Failures that aborted the reindex.
+
+    Failures are returned as raw json because they can come from bulk indexing or from the source search.
+    */
+    pub fn failures(&self) -> &[Value] {
+        &self.failures
+    }
+}
+
+impl IsOkOnSuccess for ReindexResponse {}
+
+/**
+The number of documents processed by a reindex.
+
+This is part of a completed [`ReindexResponse`][ReindexResponse], and is also the status of a running reindex task.
+
+[ReindexResponse]: struct.ReindexResponse.html
+*/
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct ReindexStatus {
+    #[serde(default)]
+    total: u64,
+    #[serde(default)]
+    created: u64,
+    #[serde(default)]
+    updated: u64,
+    #[serde(default)]
+    deleted: u64,
+    #[serde(default)]
+    batches: u64,
+    #[serde(default)]
+    version_conflicts: u64,
+    #[serde(default)]
+    noops: u64,
+}
+
+impl ReindexStatus {
+    /** The number of documents the reindex will process. */
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /** The number of documents that were created in the destination index. */
+    pub fn created(&self) -> u64 {
+        self.created
+    }
+
+    /** The number of documents that were updated in the destination index. */
+    pub fn updated(&self) -> u64 {
+        self.updated
+    }
+
+    /** The number of documents that were deleted from the destination index. */
+    pub fn deleted(&self) -> u64 {
+        self.deleted
+    }
+
+    /** The number of scroll responses pulled back by the reindex. */
+    pub fn batches(&self) -> u64 {
+        self.batches
+    }
+
+    /** The number of version conflicts the reindex hit. */
+    pub fn version_conflicts(&self) -> u64 {
+        self.version_conflicts
+    }
+
+    /** The number of documents that were ignored by a script. */
+    pub fn noops(&self) -> u64 {
+        self.noops
+    }
+}
+
+impl Add for ReindexStatus {
+    type Output = ReindexStatus;
+
+    fn add(self, other: ReindexStatus) -> ReindexStatus {
+        ReindexStatus {
+            total: self.total + other.total,
+            created: self.created + other.created,
+            updated: self.updated + other.updated,
+            deleted: self.deleted + other.deleted,
+            batches: self.batches + other.batches,
+            version_conflicts: self.version_conflicts + other.version_conflicts,
+            noops: self.noops + other.noops,
+        }
+    }
+}
+
+impl Sum for ReindexStatus {
+    fn sum<I>(iter: I) -> ReindexStatus
+    where
+        I: Iterator<Item = ReindexStatus>,
+    {
+        iter.fold(ReindexStatus::default(), Add::add)
+    }
+}
@@ -2,13 +2,22 @@
 Response types for a [search request](https://www.elastic.co/guide/en/elasticsearch/reference/current/search-search.html).
 */
 
-use serde::de::DeserializeOwned;
+use serde::de::{
+    Deserialize,
+    DeserializeOwned,
+    Deserializer,
+    Error as DeError,
+};
 use serde_json::{
+    self,
     Map,
     Value,
 };
 
-use common::Shards;
+use common::{
+    document_from_fields,
+    Shards,
+};
 use parsing::IsOkOnSuccess;
 
 use std::{
@@ -60,17 +69,22 @@ for hit in response.hits() {
 pub struct SearchResponse<T> {
     took: u64,
     timed_out: bool,
+    #[serde(default)]
+    terminated_early: bool,
     #[serde(rename = "_shards")]
     shards: Shards,
     hits: HitsWrapper<T>,
     aggregations: Option<AggsWrapper>,
     status: Option<u16>,
+    #[serde(rename = "_scroll_id")]
+    scroll_id: Option<String>,
+    pit_id: Option<String>,
 }
 
 /** Struct to hold the search's Hits, serializable to type `T` or `serde_json::Value`. */
 #[derive(Deserialize, Debug)]
 struct HitsWrapper<T> {
-    total: u64,
+    total: Option<TotalHits>,
     max_score: Option<f32>,
     #[serde(rename = "hits")]
     inner: Vec<Hit<T>>,
@@ -87,6 +101,11 @@ impl<T> SearchResponse<T> {
         self.timed_out
     }
 
+    /** Whether or not the search stopped collecting hits early because of the `terminate_after` parameter. */
+    pub fn terminated_early(&self) -> bool {
+        self.terminated_early
+    }
+
     /** Shards metadata for the request. */
     pub fn shards(&self) -> &Shards {
         &self.shards
@@ -97,8 +116,42 @@ impl<T> SearchResponse<T> {
         self.status.clone()
     }
 
-    /** The total number of documents that matched the search query. */
+    /**
+    The id of the scroll context for the search, if the `scroll` parameter was given.
+
+    The scroll id is used to fetch the next page of results.
+    */
+    pub fn scroll_id(&self) -> Option<&str> {
+        self.scroll_id.as_deref()
+    }
+
+    /**
+    The id of the point-in-time context for the search, if the search was made against a point-in-time.
+
+    The id may change between pages, so the latest one should always be used for the next search.
+    */
+    pub fn pit_id(&self) -> Option<&str> {
+        self.pit_id.as_deref()
+    }
+
+    /**
+    The total number of documents that matched the search query.
+
+    If the total wasn't tracked exactly then this is a lower bound, and if it wasn't tracked at all then it's `0`.
+    Use [`total_hits`][SearchResponse.total_hits] to tell the difference.
+
+    [SearchResponse.total_hits]: #method.total_hits
+    */
     pub fn total(&self) -> u64 {
+        self.hits.total()
+    }
+
+    /**
+    The total number of documents that matched the search query, along with whether it's exact or a lower bound.
+
+    This will be `None` if the total wasn't tracked because the `track_total_hits` parameter was `false`.
+    */
+    pub fn total_hits(&self) -> Option<TotalHits> {
         self.hits.total
     }
 
@@ -150,6 +203,75 @@ impl<T> SearchResponse<T> {
 
 impl<T: DeserializeOwned> IsOkOnSuccess for SearchResponse<T> {}
 
+impl<T> HitsWrapper<T> {
+    fn total(&self) -> u64 {
+        self.total.map(|total| total.value).unwrap_or(0)
+    }
+}
+
+/**
+The total number of hits that matched a search.
+
+Older versions of Elasticsearch always return an exact total.
+Newer versions only count hits accurately up to a limit set by the `track_total_hits` parameter, so the total may be a lower bound.
+*/
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(from = "TotalHitsDe")]
+pub struct TotalHits {
+    value: u64,
+    relation: TotalHitsRelation,
+}
+
+impl TotalHits {
+    /** The number of hits. */
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /** Whether the number of hits is exact or a lower bound. */
+    pub fn relation(&self) -> TotalHitsRelation {
+        self.relation
+    }
+
+    /** Whether the number of hits is exact. */
+    pub fn is_exact(&self) -> bool {
+        self.relation == TotalHitsRelation::Equal
+    }
+}
+
+/** How the number of total hits relates to the real number of matching hits. */
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TotalHitsRelation {
+    /** The total is exact. */
+    #[serde(rename = "eq")]
+    Equal,
+    /** The total is a lower bound. */
+    #[serde(rename = "gte")]
+    GreaterThanOrEqual,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TotalHitsDe {
+    Exact(u64),
+    WithRelation {
+        value: u64,
+        relation: TotalHitsRelation,
+    },
+}
+
+impl From<TotalHitsDe> for TotalHits {
+    fn from(total: TotalHitsDe) -> Self {
+        match total {
+            TotalHitsDe::Exact(value) => TotalHits {
+                value,
+                relation: TotalHitsRelation::Equal,
+            },
+            TotalHitsDe::WithRelation { value, relation } => TotalHits { value, relation },
+        }
+    }
+}
+
 /** A borrowing iterator over search query hits. */
 pub struct Hits<'a, T: 'a> {
     inner: Iter<'a, Hit<T>>,
@@ -234,9 +356,29 @@ impl<T> Iterator for IntoDocuments<T> {
     }
 }
 
-/** Full metadata and source for a single hit. */
-#[derive(Deserialize, Debug)]
+/**
+Full metadata and source for a single hit.
+
+If the hit doesn't have a `_source`, because it's disabled in the mapping or excluded from the request,
+then the document will be deserialised from any stored fields or doc values returned instead.
+*/
+#[derive(Debug)]
 pub struct Hit<T> {
+    index: String,
+    ty: String,
+    id: String,
+    version: Option<u32>,
+    score: Option<f32>,
+    source: Option<T>,
+    fields: Option<Map<String, Value>>,
+    routing: Option<String>,
+    sort: Option<Vec<Value>>,
+    inner_hits: BTreeMap<String, InnerHits<T>>,
+}
+
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+struct HitDe<T> {
     #[serde(rename = "_index")]
     index: String,
     #[serde(rename = "_type")]
@@ -249,16 +391,99 @@ pub struct Hit<T> {
     score: Option<f32>,
     #[serde(rename = "_source")]
     source: Option<T>,
+    fields: Option<Map<String, Value>>,
     #[serde(rename = "_routing")]
     routing: Option<String>,
+    sort: Option<Vec<Value>>,
+    #[serde(default)]
+    inner_hits: BTreeMap<String, InnerHits<T>>,
+}
+
+impl<'de, T> Deserialize<'de> for Hit<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let hit = HitDe::<T>::deserialize(deserializer)?;
+
+        let source = match (hit.source, &hit.fields) {
+            (None, &Some(ref fields)) => {
+                Some(document_from_fields(fields).map_err(DeError::custom)?)
+            }
+            (source, _) => source,
+        };
+
+        Ok(Hit {
+            index: hit.index,
+            ty: hit.ty,
+            id: hit.id,
+            version: hit.version,
+            score: hit.score,
+            source,
+            fields: hit.fields,
+            routing: hit.routing,
+            sort: hit.sort,
+            inner_hits: hit.inner_hits,
+        })
+    }
 }
 
 impl<T> Hit<T> {
-    /** Get a reference to the source document. */
+    /**
+    Get a reference to the source document.
+
+    If the `_source` wasn't returned then the document is deserialised from the stored fields or doc values.
+    */
     pub fn document(&self) -> Option<&T> {
         self.source.as_ref()
     }
 
+    /**
+    Get the stored fields or doc values returned for the hit.
+
+    Fields are only returned when they're requested using the `stored_fields` or `docvalue_fields` parameters.
+    */
+    pub fn fields(&self) -> Option<&Map<String, Value>> {
+        self.fields.as_ref()
+    }
+
+    /**
+    Get the values returned for a field.
+
+    Values are returned for fields requested using the `fields`, `docvalue_fields` or `stored_fields` parameters, including runtime fields.
+    This method returns `None` if the field wasn't returned or its values can't be deserialised as `V`.
+    */
+    pub fn field_values<V>(&self, name: &str) -> Option<Vec<V>>
+    where
+        V: DeserializeOwned,
+    {
+        self.fields
+            .as_ref()
+            .and_then(|fields| fields.get(name))
+            .and_then(|values| serde_json::from_value(values.clone()).ok())
+    }
+
+    /**
+    Get the first value returned for a field.
+
+    This is convenient for fields that only have a single value, like most doc values and stored fields.
+    This method returns `None` if the field wasn't returned, has no values, or its first value can't be deserialised as `V`.
+    */
+    pub fn field_value<V>(&self, name: &str) -> Option<V>
+    where
+        V: DeserializeOwned,
+    {
+        let value = match self.fields.as_ref().and_then(|fields| fields.get(name)) {
+            Some(&Value::Array(ref values)) => values.first(),
+            value => value,
+        };
+
+        value.and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
     /** Convert the hit into the source document. */
     pub fn into_document(self) -> Option<T> {
         self.source
@@ -288,6 +513,78 @@ impl<T> Hit<T> {
     pub fn score(&self) -> Option<f32> {
         self.score.clone()
     }
+
+    /**
+    The sort values of the hit, if the search was sorted.
+
+    These values can be passed to `search_after` to fetch the hits that follow this one.
+    */
+    pub fn sort(&self) -> Option<&[Value]> {
+        self.sort.as_deref()
+    }
+
+    /**
+    Get the inner hits with the given name.
+
+    Inner hits are returned when they're requested for collapsed hits or in `nested` and `has_child` queries.
+    */
+    pub fn inner_hits(&self, name: &str) -> Option<&InnerHits<T>> {
+        self.inner_hits.get(name)
+    }
+
+    /** Iterate over all inner hits, along with their names. */
+    pub fn all_inner_hits(&self) -> impl Iterator<Item = (&str, &InnerHits<T>)> {
+        self.inner_hits
+            .iter()
+            .map(|(name, inner_hits)| (name.as_str(), inner_hits))
+    }
+}
+
+/**
+A named set of inner hits returned with a hit.
+
+When hits are collapsed, the inner hits are the other hits that share the same collapsed value.
+*/
+#[derive(Deserialize, Debug)]
+pub struct InnerHits<T> {
+    hits: HitsWrapper<T>,
+}
+
+impl<T> InnerHits<T> {
+    /** The total number of inner hits. */
+    pub fn total(&self) -> u64 {
+        self.hits.total()
+    }
+
+    /** The total number of inner hits, along with whether it's exact or a lower bound. */
+    pub fn total_hits(&self) -> Option<TotalHits> {
+        self.hits.total
+    }
+
+    /** The max score of the inner hits. */
+    pub fn max_score(&self) -> Option<f32> {
+        self.hits.max_score
+    }
+
+    /** Iterate over the inner hits. */
+    pub fn hits(&self) -> Hits<T> {
+        Hits::new(&self.hits)
+    }
+
+    /** Convert into an iterator that consumes the inner hits. */
+    pub fn into_hits(self) -> IntoHits<T> {
+        IntoHits::new(self.hits)
+    }
+
+    /** Iterate over the source documents of the inner hits. */
+    pub fn documents(&self) -> Documents<T> {
+        Documents::new(&self.hits)
+    }
+
+    /** Convert into an iterator that consumes the source documents of the inner hits. */
+    pub fn into_documents(self) -> IntoDocuments<T> {
+        IntoDocuments::new(self.hits)
+    }
 }
 
 /** Type Struct to hold a generic `serde_json::Value` tree of the aggregation results. */
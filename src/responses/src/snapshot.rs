@@ -0,0 +1,63 @@
+/*!
+Response types for the [snapshot api](https://www.elastic.co/guide/en/elasticsearch/reference/current/modules-snapshots.html).
+*/
+
+use common::Shards;
+use parsing::IsOkOnSuccess;
+
+/**
+Response for a [snapshot restore request](https://www.elastic.co/guide/en/elasticsearch/reference/current/modules-snapshots.html#_restore).
+
+If the restore was sent with `wait_for_completion=true` then the response contains details of the restored snapshot.
+Otherwise the response only says whether or not the restore was accepted.
+*/
+#[derive(Deserialize, Debug)]
+pub struct SnapshotRestoreResponse {
+    #[serde(default)]
+    accepted: bool,
+    snapshot: Option<RestoredSnapshot>,
+}
+
+impl SnapshotRestoreResponse {
+    /** Whether or not the restore was accepted. */
+    pub fn accepted(&self) -> bool {
+        self.accepted || self.snapshot.is_some()
+    }
+
+    /**
+    The snapshot that was restored.
+
+    This is only returned when the restore was sent with `wait_for_completion=true`.
+    */
+    pub fn snapshot(&self) -> Option<&RestoredSnapshot> {
+        self.snapshot.as_ref()
+    }
+}
+
+impl IsOkOnSuccess for SnapshotRestoreResponse {}
+
+/** The result of a completed snapshot restore. */
+#[derive(Deserialize, Debug)]
+pub struct RestoredSnapshot {
+    snapshot: String,
+    #[serde(default)]
+    indices: Vec<String>,
+    shards: Shards,
+}
+
+impl RestoredSnapshot {
+    /** The name of the snapshot. */
+    pub fn name(&self) -> &str {
+        &self.snapshot
+    }
+
+    /** The names of the restored indices, after any renames were applied. */
+    pub fn indices(&self) -> impl Iterator<Item = &str> {
+        self.indices.iter().map(|index| index.as_str())
+    }
+
+    /** The shards that were restored. */
+    pub fn shards(&self) -> &Shards {
+        &self.shards
+    }
+}
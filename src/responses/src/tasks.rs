@@ -0,0 +1,137 @@
+/*!
+Response types for the [tasks api](https://www.elastic.co/guide/en/elasticsearch/reference/current/tasks.html).
+*/
+
+use std::collections::BTreeMap;
+
+use parsing::IsOkOnSuccess;
+use serde_json::Value;
+
+/** Response for a request that was started as a background task, like a reindex with `wait_for_completion=false`. */
+#[derive(Deserialize, Debug)]
+pub struct TaskStartedResponse {
+    task: String,
+}
+
+impl TaskStartedResponse {
+    /** The id of the task in the form `{node}:{id}`. */
+    pub fn task_id(&self) -> &str {
+        &self.task
+    }
+}
+
+impl IsOkOnSuccess for TaskStartedResponse {}
+
+/** Response for a [get task request](https://www.elastic.co/guide/en/elasticsearch/reference/current/tasks.html). */
+#[derive(Deserialize, Debug)]
+pub struct TaskResponse {
+    completed: bool,
+    task: Task,
+    response: Option<Value>,
+    error: Option<Value>,
+}
+
+impl TaskResponse {
+    /** Whether or not the task has finished. */
+    pub fn completed(&self) -> bool {
+        self.completed
+    }
+
+    /** The task. */
+    pub fn task(&self) -> &Task {
+        &self.task
+    }
+
+    /**
+    The result of a completed task.
+
+    The shape of the response depends on the action the task was running.
+    */
+    pub fn response(&self) -> Option<&Value> {
+        self.response.as_ref()
+    }
+
+    /** The error that caused a completed task to fail. */
+    pub fn error(&self) -> Option<&Value> {
+        self.error.as_ref()
+    }
+}
+
+impl IsOkOnSuccess for TaskResponse {}
+
+/** Response for a [list tasks request](https://www.elastic.co/guide/en/elasticsearch/reference/current/tasks.html). */
+#[derive(Deserialize, Debug)]
+pub struct TasksResponse {
+    #[serde(default)]
+    nodes: BTreeMap<String, NodeTasks>,
+}
+
+#[derive(Deserialize, Debug)]
+struct NodeTasks {
+    #[serde(default)]
+    tasks: BTreeMap<String, Task>,
+}
+
+impl TasksResponse {
+    /** Iterate over the tasks running on all nodes. */
+    pub fn tasks(&self) -> impl Iterator<Item = &Task> {
+        self.nodes.values().flat_map(|node| node.tasks.values())
+    }
+}
+
+impl IsOkOnSuccess for TasksResponse {}
+
+/** A task running on a node in the cluster. */
+#[derive(Deserialize, Debug)]
+pub struct Task {
+    node: String,
+    id: u64,
+    action: String,
+    status: Option<Value>,
+    parent_task_id: Option<String>,
+    #[serde(default)]
+    running_time_in_nanos: u64,
+    #[serde(default)]
+    cancellable: bool,
+}
+
+impl Task {
+    /** The id of the task in the form `{node}:{id}`. */
+    pub fn task_id(&self) -> String {
+        format!("{}:{}", self.node, self.id)
+    }
+
+    /** The node the task is running on. */
+    pub fn node(&self) -> &str {
+        &self.node
+    }
+
+    /** The action the task is running, like `indices:data/write/reindex`. */
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    /**
+    The status of the task.
+
+    Only some actions report a status and its shape depends on the action.
+    */
+    pub fn status(&self) -> Option<&Value> {
+        self.status.as_ref()
+    }
+
+    /** The id of the task that started this one, if it's a sub-task. */
+    pub fn parent_task_id(&self) -> Option<&str> {
+        self.parent_task_id.as_deref()
+    }
+
+    /** Time in nanoseconds the task has been running for. */
+    pub fn running_time_in_nanos(&self) -> u64 {
+        self.running_time_in_nanos
+    }
+
+    /** Whether or not the task can be cancelled. */
+    pub fn cancellable(&self) -> bool {
+        self.cancellable
+    }
+}
@@ -96,6 +96,97 @@ fn success_parse_with_errors_errors_only() {
     assert_eq!(1, deserialized.iter().count());
 }
 
+#[test]
+fn append_responses() {
+    let parse_file = |p| {
+        parse::<BulkResponse>()
+            .from_reader(StatusCode::OK, load_file(p))
+            .unwrap()
+    };
+
+    let mut deserialized = parse_file("tests/samples/bulk_index.json");
+    let other = parse_file("tests/samples/bulk_error.json");
+    let took = deserialized.took() + other.took();
+
+    deserialized.append(other);
+
+    assert!(deserialized.is_err());
+    assert_eq!(took, deserialized.took());
+
+    assert_eq!(1, deserialized.iter().filter(Result::is_err).count());
+    assert_eq!(6, deserialized.iter().filter(Result::is_ok).count());
+}
+
+#[test]
+fn append_responses_errors_only() {
+    let parse_file = |p| {
+        parse::<BulkErrorsResponse>()
+            .from_reader(StatusCode::OK, load_file(p))
+            .unwrap()
+    };
+
+    let mut deserialized = parse_file("tests/samples/bulk_index.json");
+    deserialized.append(parse_file("tests/samples/bulk_error.json"));
+
+    assert!(deserialized.is_err());
+    assert_eq!(1, deserialized.iter().count());
+}
+
+#[test]
+fn success_parse_error_status() {
+    let f = load_file("tests/samples/bulk_rejected.json");
+    let deserialized = parse::<BulkResponse>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    let statuses: Vec<_> = deserialized
+        .iter()
+        .filter_map(|item| item.err().map(|item| item.status()))
+        .collect();
+
+    assert_eq!(vec![429, 429], statuses);
+}
+
+#[test]
+fn replace_responses() {
+    let parse_file = |p| {
+        parse::<BulkResponse>()
+            .from_reader(StatusCode::OK, load_file(p))
+            .unwrap()
+    };
+
+    let mut deserialized = parse_file("tests/samples/bulk_rejected.json");
+    let retried = parse_file("tests/samples/bulk_retried.json");
+    let took = deserialized.took() + retried.took();
+
+    deserialized.replace(&[1, 2], retried);
+
+    assert!(deserialized.is_ok());
+    assert_eq!(took, deserialized.took());
+
+    let ids: Vec<_> = deserialized
+        .iter()
+        .filter_map(|item| item.ok().map(|item| item.id().to_owned()))
+        .collect();
+
+    assert_eq!(vec!["1", "2", "3"], ids);
+}
+
+#[test]
+fn replace_responses_with_errors() {
+    let parse_file = |p| {
+        parse::<BulkResponse>()
+            .from_reader(StatusCode::OK, load_file(p))
+            .unwrap()
+    };
+
+    let mut deserialized = parse_file("tests/samples/bulk_rejected.json");
+    deserialized.replace(&[1], parse_file("tests/samples/bulk_retried.json"));
+
+    assert!(deserialized.is_err());
+    assert_eq!(1, deserialized.iter().filter(Result::is_err).count());
+}
+
 #[test]
 fn error_parse_action_request_validation() {
     let f = load_file("tests/samples/error_action_request_validation.json");
@@ -0,0 +1,98 @@
+extern crate elastic_responses;
+extern crate serde_json;
+
+use elastic_responses::*;
+use elastic_responses::error::*;
+use ::load_file;
+
+#[test]
+fn error_parse_script_compile() {
+    let f = load_file("tests/samples/error_script_compile.json");
+    let deserialized = parse::<UpdateResponse>()
+        .from_reader(StatusCode::BAD_REQUEST, f)
+        .unwrap_err();
+
+    let (reason, script, cause) = match deserialized {
+        ResponseError::Api(ApiError::Script {
+            reason,
+            script,
+            cause,
+        }) => (reason, script, cause),
+        e => panic!("unexpected error: {:?}", e),
+    };
+
+    assert_eq!("compile error", reason);
+    assert_eq!(Some("painless"), script.lang());
+    assert_eq!(2, script.script_stack().len());
+    assert_eq!(Some(54), script.position().map(|p| p.offset()));
+    assert_eq!(Some(2), script.line());
+    assert_eq!(Some(22), script.column());
+
+    assert_eq!("illegal_argument_exception", cause.ty());
+    assert_eq!(Some("failed to execute script"), cause.reason());
+    assert_eq!(
+        vec![
+            "illegal_argument_exception",
+            "script_exception",
+            "illegal_argument_exception",
+        ],
+        cause.chain().map(|cause| cause.ty()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn error_parse_search_phase_script() {
+    let f = load_file("tests/samples/error_search_phase_script.json");
+    let deserialized = parse::<SearchResponse<serde_json::Value>>()
+        .from_reader(StatusCode::BAD_REQUEST, f)
+        .unwrap_err();
+
+    let (reason, script, cause) = match deserialized {
+        ResponseError::Api(ApiError::Script {
+            reason,
+            script,
+            cause,
+        }) => (reason, script, cause),
+        e => panic!("unexpected error: {:?}", e),
+    };
+
+    assert_eq!("runtime error", reason);
+    assert_eq!(Some("doc['missing'].value"), script.script());
+    assert_eq!(None, script.line());
+    assert_eq!(None, script.column());
+
+    assert_eq!("search_phase_execution_exception", cause.ty());
+    assert_eq!(1, cause.root_cause().len());
+    assert_eq!(Some(&json!("query")), cause.get("phase"));
+}
+
+#[test]
+fn error_parse_nested_causes() {
+    let f = load_file("tests/samples/error_nested_causes.json");
+    let deserialized = parse::<IndexResponse>()
+        .from_reader(StatusCode::BAD_REQUEST, f)
+        .unwrap_err();
+
+    // Unrecognised error types are still returned as parse errors
+    let err = match deserialized {
+        ResponseError::Parse(err) => err,
+        e => panic!("unexpected error: {:?}", e),
+    };
+
+    let cause = err.api_error_cause().expect("missing error cause");
+
+    assert_eq!("mapper_parsing_exception", cause.ty());
+    assert_eq!(
+        Some("mapper_parsing_exception"),
+        cause.root_cause().first().map(|cause| cause.ty())
+    );
+    assert_eq!(
+        Some("illegal_state_exception"),
+        cause.suppressed().first().map(|cause| cause.ty())
+    );
+    assert_eq!(
+        Some("date_time_parse_exception"),
+        cause.chain().last().map(|cause| cause.ty())
+    );
+    assert!(cause.find_script().is_none());
+}
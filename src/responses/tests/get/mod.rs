@@ -44,6 +44,83 @@ fn success_into_document() {
     }
 }
 
+#[test]
+fn success_parse_doc_from_stored_fields() {
+    let f = load_file("tests/samples/get_stored_fields.json");
+    let deserialized = parse::<GetResponse<Value>>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    assert_eq!(
+        4,
+        deserialized
+            .fields()
+            .map(|fields| fields.len())
+            .unwrap_or(0)
+    );
+
+    let expected = json!({
+        "id": 1,
+        "title": "a title",
+        "tags": ["a", "b"],
+        "author": {
+            "name": "someone"
+        }
+    });
+
+    assert_eq!(Some(&expected), deserialized.document());
+}
+
+#[test]
+fn success_parse_typed_doc_from_stored_fields() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Author {
+        name: String,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Doc {
+        id: u64,
+        title: Vec<String>,
+        tags: Vec<String>,
+        author: Author,
+    }
+
+    let f = load_file("tests/samples/get_stored_fields.json");
+    let deserialized = parse::<GetResponse<Doc>>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    let expected = Doc {
+        id: 1,
+        title: vec!["a title".to_owned()],
+        tags: vec!["a".to_owned(), "b".to_owned()],
+        author: Author {
+            name: "someone".to_owned(),
+        },
+    };
+
+    assert_eq!(Some(&expected), deserialized.document());
+}
+
+#[test]
+fn error_parse_doc_from_multi_value_stored_fields() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Doc {
+        id: u64,
+        tags: String,
+    }
+
+    let f = load_file("tests/samples/get_stored_fields.json");
+    let deserialized = parse::<GetResponse<Doc>>().from_reader(StatusCode::OK, f);
+
+    match deserialized {
+        Err(ResponseError::Parse(_)) => (),
+        r => panic!("expected parse error but got {:?}", r),
+    }
+}
+
 #[test]
 fn success_parse_not_found_doc_response() {
     let f = load_file("tests/samples/get_not_found.json");
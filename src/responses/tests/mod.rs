@@ -12,8 +12,12 @@ fn load_file(p: &str) -> File {
 
 pub mod bulk;
 pub mod command;
+pub mod error;
 pub mod get;
 pub mod index;
 pub mod indices_exists;
 pub mod ping;
+pub mod reindex;
 pub mod search;
+pub mod snapshot;
+pub mod tasks;
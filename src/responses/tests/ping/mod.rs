@@ -12,4 +12,16 @@ fn success_parse_ping_response() {
         .unwrap();
 
     assert_eq!("Scorcher", deserialized.name());
+    assert_eq!(None, deserialized.version().distribution());
+}
+
+#[test]
+fn success_parse_opensearch_ping_response() {
+    let f = load_file("tests/samples/ping_opensearch.json");
+    let deserialized = parse::<PingResponse>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    assert_eq!("2.11.0", deserialized.version().number());
+    assert_eq!(Some("opensearch"), deserialized.version().distribution());
 }
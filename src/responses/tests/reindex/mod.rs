@@ -0,0 +1,47 @@
+extern crate elastic_responses;
+extern crate serde_json;
+
+use elastic_responses::*;
+use ::load_file;
+
+#[test]
+fn success_parse_response() {
+    let f = load_file("tests/samples/reindex_success.json");
+    let deserialized = parse::<ReindexResponse>().from_reader(StatusCode::OK, f).unwrap();
+
+    assert_eq!(147, deserialized.took());
+    assert!(!deserialized.timed_out());
+
+    let status = deserialized.status();
+    assert_eq!(120, status.total());
+    assert_eq!(118, status.created());
+    assert_eq!(0, status.updated());
+    assert_eq!(2, status.version_conflicts());
+
+    assert_eq!(1, deserialized.failures().len());
+}
+
+#[test]
+fn success_parse_started_response() {
+    let f = load_file("tests/samples/reindex_started.json");
+    let deserialized = parse::<TaskStartedResponse>().from_reader(StatusCode::OK, f).unwrap();
+
+    assert_eq!("r1A2WoRbTwKZ516z6NEs5A:36619", deserialized.task_id());
+}
+
+#[test]
+fn sum_statuses() {
+    let f = load_file("tests/samples/tasks_list_children.json");
+    let tasks = parse::<TasksResponse>().from_reader(StatusCode::OK, f).unwrap();
+
+    let status: ReindexStatus = tasks
+        .tasks()
+        .filter_map(|task| task.status())
+        .map(|status| serde_json::from_value::<ReindexStatus>(status.clone()).unwrap())
+        .sum();
+
+    assert_eq!(6154, status.total());
+    assert_eq!(3250, status.created());
+    assert_eq!(4, status.batches());
+    assert_eq!(1, status.version_conflicts());
+}
@@ -4,6 +4,7 @@ extern crate serde_json;
 
 use elastic_responses::{
     error::*,
+    search::TotalHitsRelation,
     *,
 };
 use load_file;
@@ -29,6 +30,20 @@ fn success_parse_hits_simple() {
     assert_eq!(deserialized.hits().into_iter().count(), 5);
 }
 
+#[test]
+fn success_parse_scroll_id() {
+    let f = load_file("tests/samples/search_scroll.json");
+    let deserialized = parse::<SearchResponse<Value>>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    assert_eq!(
+        Some("DXF1ZXJ5QW5kRmV0Y2gBAAAAAAAAAD4WYm9laVYtZndUQlNsdDcwakFMNjU1QQ=="),
+        deserialized.scroll_id()
+    );
+    assert_eq!(None, deserialized.pit_id());
+}
+
 #[test]
 fn success_parse_hits_simple_of_t() {
     #[allow(dead_code)]
@@ -74,6 +89,163 @@ fn success_parse_hits_no_score() {
     assert_eq!(deserialized.hits().into_iter().count(), 1);
 }
 
+#[test]
+fn success_parse_hits_sort() {
+    let f = load_file("tests/samples/search_null_score.json");
+    let deserialized = parse::<SearchResponse<Value>>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    let hit = deserialized.hits().next().unwrap();
+
+    assert_eq!(Some(&[json!(1492767886439u64)][..]), hit.sort());
+}
+
+#[test]
+fn success_parse_collapsed_inner_hits() {
+    #[derive(Deserialize)]
+    struct Post {
+        title: String,
+    }
+
+    let f = load_file("tests/samples/search_collapse.json");
+    let deserialized = parse::<SearchResponse<Post>>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    let hits: Vec<_> = deserialized.hits().collect();
+    assert_eq!(2, hits.len());
+
+    let most_liked = hits[0].inner_hits("most_liked").unwrap();
+    assert_eq!(2, most_liked.total());
+
+    let titles: Vec<_> = most_liked
+        .documents()
+        .map(|post| post.title.as_str())
+        .collect();
+    assert_eq!(vec!["First post", "Third post"], titles);
+
+    assert_eq!(
+        vec!["most_liked"],
+        hits[1]
+            .all_inner_hits()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>()
+    );
+    assert!(hits[1].inner_hits("missing").is_none());
+}
+
+#[test]
+fn success_parse_hits_without_inner_hits() {
+    let f = load_file("tests/samples/search_null_score.json");
+    let deserialized = parse::<SearchResponse<Value>>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    let hit = deserialized.hits().next().unwrap();
+
+    assert_eq!(0, hit.all_inner_hits().count());
+}
+
+#[test]
+fn success_parse_knn_scores() {
+    let f = load_file("tests/samples/search_knn.json");
+    let deserialized = parse::<SearchResponse<Value>>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    let scores: Vec<_> = deserialized
+        .hits()
+        .map(|hit| (hit.id().to_owned(), hit.score()))
+        .collect();
+
+    assert_eq!(Some(0.9856), deserialized.max_score());
+    assert_eq!(
+        vec![
+            ("2".to_owned(), Some(0.9856)),
+            ("7".to_owned(), Some(0.4312)),
+        ],
+        scores
+    );
+}
+
+#[test]
+fn success_parse_runtime_field_values() {
+    let f = load_file("tests/samples/search_runtime_fields.json");
+    let deserialized = parse::<SearchResponse<Value>>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    let hit = deserialized.hits().next().unwrap();
+
+    assert_eq!(Some(vec![125.0]), hit.field_values::<f64>("total_price"));
+    assert_eq!(
+        Some(vec!["Monday".to_owned()]),
+        hit.field_values::<String>("day_of_week")
+    );
+    assert_eq!(None, hit.field_values::<f64>("day_of_week"));
+    assert_eq!(None, hit.field_values::<f64>("missing"));
+}
+
+#[test]
+fn success_parse_docvalue_field_value() {
+    let f = load_file("tests/samples/search_docvalue_fields.json");
+    let deserialized = parse::<SearchResponse<Value>>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    let hit = deserialized.hits().next().unwrap();
+
+    assert_eq!(Some(1), hit.field_value::<u64>("id"));
+    assert_eq!(
+        Some("a title".to_owned()),
+        hit.field_value::<String>("title")
+    );
+    assert_eq!(None, hit.field_value::<u64>("title"));
+    assert_eq!(None, hit.field_value::<u64>("missing"));
+}
+
+#[test]
+fn success_parse_total_hits_exact() {
+    let f = load_file("tests/samples/search_hits_only.json");
+    let deserialized = parse::<SearchResponse<Value>>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    let total = deserialized.total_hits().unwrap();
+
+    assert!(total.is_exact());
+    assert_eq!(deserialized.total(), total.value());
+    assert!(!deserialized.terminated_early());
+}
+
+#[test]
+fn success_parse_total_hits_lower_bound() {
+    let f = load_file("tests/samples/search_total_hits_lower_bound.json");
+    let deserialized = parse::<SearchResponse<Value>>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    let total = deserialized.total_hits().unwrap();
+
+    assert_eq!(10000, total.value());
+    assert_eq!(TotalHitsRelation::GreaterThanOrEqual, total.relation());
+    assert!(!total.is_exact());
+    assert_eq!(10000, deserialized.total());
+    assert!(deserialized.terminated_early());
+}
+
+#[test]
+fn success_parse_total_hits_untracked() {
+    let f = load_file("tests/samples/search_total_hits_untracked.json");
+    let deserialized = parse::<SearchResponse<Value>>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    assert_eq!(None, deserialized.total_hits());
+    assert_eq!(0, deserialized.total());
+}
+
 #[test]
 fn success_parse_hits_bank_sample() {
     let f = load_file("tests/samples/search_bank_sample.json");
@@ -224,3 +396,69 @@ fn error_parse_index_not_found() {
 
     assert!(valid);
 }
+
+#[test]
+fn success_parse_hits_from_docvalue_fields() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Doc {
+        id: u64,
+        title: String,
+    }
+
+    let f = load_file("tests/samples/search_docvalue_fields.json");
+    let deserialized = parse::<SearchResponse<Doc>>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    let expected = Doc {
+        id: 1,
+        title: "a title".to_owned(),
+    };
+
+    assert_eq!(
+        vec![&expected],
+        deserialized.documents().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn success_parse_single_value_docvalue_fields_into_vec() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Doc {
+        id: Vec<u64>,
+        title: Option<Vec<String>>,
+    }
+
+    let f = load_file("tests/samples/search_docvalue_fields.json");
+    let deserialized = parse::<SearchResponse<Doc>>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    let expected = Doc {
+        id: vec![1],
+        title: Some(vec!["a title".to_owned()]),
+    };
+
+    assert_eq!(
+        vec![&expected],
+        deserialized.documents().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn error_parse_hits_from_incompatible_docvalue_fields() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Doc {
+        id: u64,
+        title: u64,
+    }
+
+    let f = load_file("tests/samples/search_docvalue_fields.json");
+    let deserialized = parse::<SearchResponse<Doc>>().from_reader(StatusCode::OK, f);
+
+    match deserialized {
+        Err(ResponseError::Parse(_)) => (),
+        r => panic!("expected parse error but got {:?}", r),
+    }
+}
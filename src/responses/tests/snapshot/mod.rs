@@ -0,0 +1,34 @@
+extern crate elastic_responses;
+extern crate serde_json;
+
+use elastic_responses::*;
+use ::load_file;
+
+#[test]
+fn success_parse_restore_accepted() {
+    let f = load_file("tests/samples/snapshot_restore_accepted.json");
+    let deserialized = parse::<SnapshotRestoreResponse>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    assert!(deserialized.accepted());
+    assert!(deserialized.snapshot().is_none());
+}
+
+#[test]
+fn success_parse_restore_completed() {
+    let f = load_file("tests/samples/snapshot_restore_completed.json");
+    let deserialized = parse::<SnapshotRestoreResponse>()
+        .from_reader(StatusCode::OK, f)
+        .unwrap();
+
+    assert!(deserialized.accepted());
+
+    let snapshot = deserialized.snapshot().unwrap();
+    assert_eq!("snapshot_1", snapshot.name());
+    assert_eq!(
+        vec!["restored_index_1", "restored_index_2"],
+        snapshot.indices().collect::<Vec<_>>()
+    );
+    assert_eq!(10, snapshot.shards().successful());
+}
@@ -0,0 +1,47 @@
+extern crate elastic_responses;
+extern crate serde_json;
+
+use elastic_responses::*;
+use ::load_file;
+
+#[test]
+fn success_parse_running_task() {
+    let f = load_file("tests/samples/tasks_get_running.json");
+    let deserialized = parse::<TaskResponse>().from_reader(StatusCode::OK, f).unwrap();
+
+    assert!(!deserialized.completed());
+    assert!(deserialized.response().is_none());
+
+    let task = deserialized.task();
+    assert_eq!("r1A2WoRbTwKZ516z6NEs5A:36619", task.task_id());
+    assert_eq!("indices:data/write/reindex", task.action());
+    assert_eq!(None, task.parent_task_id());
+    assert_eq!(Some(6154), task.status().and_then(|s| s["total"].as_u64()));
+}
+
+#[test]
+fn success_parse_completed_task() {
+    let f = load_file("tests/samples/tasks_get_completed.json");
+    let deserialized = parse::<TaskResponse>().from_reader(StatusCode::OK, f).unwrap();
+
+    assert!(deserialized.completed());
+    assert!(deserialized.error().is_none());
+    assert_eq!(
+        Some(2),
+        deserialized.response().and_then(|r| r["created"].as_u64())
+    );
+}
+
+#[test]
+fn success_parse_task_list() {
+    let f = load_file("tests/samples/tasks_list_children.json");
+    let deserialized = parse::<TasksResponse>().from_reader(StatusCode::OK, f).unwrap();
+
+    let tasks: Vec<_> = deserialized.tasks().collect();
+
+    assert_eq!(2, tasks.len());
+    assert_eq!("r1A2WoRbTwKZ516z6NEs5A:36620", tasks[0].task_id());
+    assert!(tasks
+        .iter()
+        .all(|task| task.parent_task_id() == Some("r1A2WoRbTwKZ516z6NEs5A:36619")));
+}
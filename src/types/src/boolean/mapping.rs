@@ -69,6 +69,14 @@ pub trait BooleanMapping {
         None
     }
 
+    /**
+    The names of other fields to copy the values of this field into.
+    Copying several fields into one lets them be searched together as a single field.
+    */
+    fn copy_to() -> Option<&'static [&'static str]> {
+        None
+    }
+
     /**
     Should the field be stored on disk in a column-stride fashion,
     so that it can later be used for sorting, aggregations, or scripting?
@@ -78,13 +86,13 @@ pub trait BooleanMapping {
         None
     }
 
-    /** Should the field be searchable? Accepts `not_analyzed` (default) and `no`. */
+    /** Should the field be searchable? Accepts `true` (default) and `false`. */
     fn index() -> Option<bool> {
         None
     }
 
     /**
-    Accepts a string value which is substituted for any explicit null values.
+    Accepts a boolean value which is substituted for any explicit null values.
     Defaults to `null`, which means the field is treated as missing.
     */
     fn null_value() -> Option<bool> {
@@ -110,6 +118,7 @@ mod private {
         BooleanFieldType,
         BooleanMapping,
     };
+    use derive::NullValueMapping;
     use private::field::{
         FieldMapping,
         FieldType,
@@ -121,6 +130,7 @@ mod private {
         Serialize,
         Serializer,
     };
+    use serde_json::Value;
 
     #[derive(Default)]
     pub struct BooleanPivot;
@@ -151,11 +161,12 @@ mod private {
         where
             S: Serializer,
         {
-            let mut state = try!(serializer.serialize_struct("mapping", 6));
+            let mut state = try!(serializer.serialize_struct("mapping", 7));
 
             try!(state.serialize_field("type", TMapping::data_type()));
 
             ser_field!(state, "boost", TMapping::boost());
+            ser_field!(state, "copy_to", TMapping::copy_to());
             ser_field!(state, "doc_values", TMapping::doc_values());
             ser_field!(state, "index", TMapping::index());
             ser_field!(state, "store", TMapping::store());
@@ -164,6 +175,17 @@ mod private {
             state.end()
         }
     }
+
+    impl<TMapping> NullValueMapping<BooleanPivot> for TMapping
+    where
+        TMapping: BooleanMapping,
+    {
+        type NullValue = bool;
+
+        fn to_null_value(value: bool) -> Result<Value, String> {
+            Ok(Value::from(value))
+        }
+    }
 }
 
 #[cfg(test)]
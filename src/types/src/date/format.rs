@@ -193,6 +193,41 @@ and must contain a valid [format string](http://www.joda.org/joda-time/apidocs/o
 
 > NOTE: Only a small subset of the Joda time format is supported.
 
+The supported tokens are:
+
+- `yyyy`: year
+- `xxxx`: week-based year
+- `MM`: month of year
+- `ww`: week of week-based year
+- `dd`: day of month
+- `DDD`: day of year
+- `e`: day of week, where Monday is `1`
+- `HH`: hour of day
+- `mm`: minute of hour
+- `ss`: second of minute
+- `.SSS`, `.SSSSSS`, `.SSSSSSSSS`: fraction of second as milliseconds, microseconds or nanoseconds
+- `Z`: the literal `Z` for Utc
+- `ZZ`: offset with a colon, like `+10:00`
+- `XXX`: offset with a colon, or `Z` for Utc
+- `-`, `:`, `.`, `/` and ` ` as delimiters, and any other text quoted like `'T'`
+
+Multiple formats can be separated by `||`.
+Dates are parsed using the first format that matches, and are always formatted using the first format:
+
+```
+# #[macro_use]
+# extern crate elastic_types;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# extern crate chrono;
+# use elastic_types::prelude::*;
+# fn main() {
+#[derive(Default, ElasticDateFormat)]
+#[elastic(date_format="yyyy-MM-dd'T'HH:mm:ssZZ||yyyy-MM-dd")]
+struct MyFormat;
+# }
+```
+
 You can customise the indexed format name by adding an `#[elastic(date_format_name)]` attribute:
 
 ```
@@ -6,6 +6,7 @@ use super::{
 };
 use chrono::{
     DateTime,
+    NaiveDate,
     NaiveDateTime,
     Timelike,
     Utc,
@@ -15,6 +16,9 @@ use std::error::Error;
 /** The default `date` format (`BasicDateTime`). */
 pub type DefaultDateFormat = BasicDateTime;
 
+/** The default `date_nanos` format (`StrictDateOptionalTimeNanos`). */
+pub type DefaultDateNanosFormat = StrictDateOptionalTimeNanos;
+
 /** Format for default `chrono::DateTime`. */
 #[derive(ElasticDateFormat, PartialEq, Debug, Default, Clone, Copy)]
 #[elastic(date_format = "yyyy-MM-dd'T'HH:mm:ssZ")]
@@ -92,6 +96,41 @@ impl DateFormat for EpochMillis {
     }
 }
 
+/**
+Format for `strict_date_optional_time_nanos`.
+
+Dates are formatted with nanosecond precision, like `2015-07-03T14:55:02.123456789Z`.
+Dates can be parsed with any number of fractional seconds, any offset, or without a time component.
+This is the default format for `date_nanos` fields.
+
+# Links
+- [Elasticsearch Doc](https://www.elastic.co/guide/en/elasticsearch/reference/current/mapping-date-format.html#built-in-date-formats)
+*/
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub struct StrictDateOptionalTimeNanos;
+
+impl DateFormat for StrictDateOptionalTimeNanos {
+    fn name() -> &'static str {
+        "strict_date_optional_time_nanos"
+    }
+
+    fn parse(date: &str) -> Result<DateValue, ParseError> {
+        if date.contains('T') {
+            let date = DateTime::parse_from_rfc3339(date)?;
+
+            Ok(date.with_timezone(&Utc).into())
+        } else {
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")?;
+
+            Ok(DateTime::from_utc(date.and_hms(0, 0, 0), Utc).into())
+        }
+    }
+
+    fn format<'a>(date: &'a DateValue) -> FormattedDate<'a> {
+        date.format("%Y-%m-%dT%H:%M:%S%.9fZ").to_string().into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::{
@@ -375,6 +414,56 @@ mod tests {
         assert_eq!("0", &fmtd);
     }
 
+    #[test]
+    fn strict_date_optional_time_nanos() {
+        let date = parse::<DefaultDateMapping<StrictDateOptionalTimeNanos>>(
+            "2015-07-03T14:55:02.123456789Z",
+        )
+        .unwrap();
+
+        assert_eq!(
+            (2015i32, 7u32, 3u32, 14u32, 55u32, 2u32, 123456789u32),
+            (
+                date.year(),
+                date.month(),
+                date.day(),
+                date.hour(),
+                date.minute(),
+                date.second(),
+                date.nanosecond()
+            )
+        );
+
+        let fmtd = format(&date).to_string();
+        assert_eq!("2015-07-03T14:55:02.123456789Z", &fmtd);
+    }
+
+    #[test]
+    fn strict_date_optional_time_nanos_offset() {
+        let date =
+            parse::<DefaultDateMapping<StrictDateOptionalTimeNanos>>("2015-07-03T16:55:02.5+02:00")
+                .unwrap();
+
+        let fmtd = format(&date).to_string();
+        assert_eq!("2015-07-03T14:55:02.500000000Z", &fmtd);
+    }
+
+    #[test]
+    fn strict_date_optional_time_nanos_no_time() {
+        let date = parse::<DefaultDateMapping<StrictDateOptionalTimeNanos>>("2015-07-03").unwrap();
+
+        let fmtd = format(&date).to_string();
+        assert_eq!("2015-07-03T00:00:00.000000000Z", &fmtd);
+    }
+
+    #[test]
+    fn strict_date_optional_time_nanos_name() {
+        assert_eq!(
+            "strict_date_optional_time_nanos",
+            StrictDateOptionalTimeNanos::name()
+        );
+    }
+
     #[test]
     fn custom_format() {
         #[derive(Default)]
@@ -700,6 +700,18 @@ mod tests {
     #[elastic(date_format = "yyyyMMdd")]
     pub struct UnNamedDateFormat;
 
+    #[derive(ElasticDateFormat, Default, Clone, Copy)]
+    #[elastic(date_format = "xxxx-'W'ww-e")]
+    pub struct WeekDateFormat;
+
+    #[derive(ElasticDateFormat, Default, Clone, Copy)]
+    #[elastic(date_format = "yyyy-MM-dd'T'HH:mm:ss.SSSSSSZZ")]
+    pub struct OffsetDateFormat;
+
+    #[derive(ElasticDateFormat, Default, Clone, Copy)]
+    #[elastic(date_format = "yyyy-MM-dd||yyyy/MM/dd")]
+    pub struct MultiDateFormat;
+
     #[test]
     fn date_format_uses_name_if_supplied() {
         assert_eq!("test_date_1", NamedDateFormat::name());
@@ -730,6 +742,45 @@ mod tests {
         assert_eq!("20150513", actual);
     }
 
+    #[test]
+    fn dates_should_use_week_date_format() {
+        let dt = WeekDateFormat::parse("2015-W20-3").unwrap();
+
+        assert_eq!(DateValue::build(2015, 05, 13, 0, 0, 0, 0), dt);
+        assert_eq!("2015-W20-3", WeekDateFormat::format(&dt).to_string());
+    }
+
+    #[test]
+    fn dates_should_apply_parsed_offset() {
+        let dt = OffsetDateFormat::parse("2015-05-13T10:30:00.123456+10:00").unwrap();
+        let expected = chrono::Utc
+            .ymd(2015, 05, 13)
+            .and_hms_micro(0, 30, 0, 123456);
+
+        assert_eq!(DateValue::from(expected), dt);
+        assert_eq!(
+            "2015-05-13T00:30:00.123456+00:00",
+            OffsetDateFormat::format(&dt).to_string()
+        );
+    }
+
+    #[test]
+    fn dates_should_parse_with_any_of_multiple_formats() {
+        let expected = DateValue::build(2015, 05, 13, 0, 0, 0, 0);
+
+        assert_eq!(expected, MultiDateFormat::parse("2015-05-13").unwrap());
+        assert_eq!(expected, MultiDateFormat::parse("2015/05/13").unwrap());
+        assert!(MultiDateFormat::parse("13.05.2015").is_err());
+    }
+
+    #[test]
+    fn dates_should_format_with_first_of_multiple_formats() {
+        let dt = DateValue::build(2015, 05, 13, 0, 0, 0, 0);
+
+        assert_eq!("yyyy-MM-dd||yyyy/MM/dd", MultiDateFormat::name());
+        assert_eq!("2015-05-13", MultiDateFormat::format(&dt).to_string());
+    }
+
     #[test]
     fn can_change_date_mapping() {
         fn takes_epoch_millis(_: Date<DefaultDateMapping<EpochMillis>>) -> bool {
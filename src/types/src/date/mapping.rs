@@ -115,6 +115,14 @@ pub trait DateMapping {
         None
     }
 
+    /**
+    The names of other fields to copy the values of this field into.
+    Copying several fields into one lets them be searched together as a single field.
+    */
+    fn copy_to() -> Option<&'static [&'static str]> {
+        None
+    }
+
     /**
     Should the field be stored on disk in a column-stride fashion,
     so that it can later be used for sorting, aggregations, or scripting?
@@ -134,7 +142,7 @@ pub trait DateMapping {
         None
     }
 
-    /** Should the field be searchable? Accepts `not_analyzed` (default) and `no`. */
+    /** Should the field be searchable? Accepts `true` (default) and `false`. */
     fn index() -> Option<bool> {
         None
     }
@@ -192,6 +200,7 @@ mod private {
         DateFormat,
         FormattableDateValue,
     };
+    use derive::NullValueMapping;
     use private::field::{
         FieldMapping,
         FieldType,
@@ -203,6 +212,7 @@ mod private {
         Serialize,
         Serializer,
     };
+    use serde_json::Value;
 
     impl<TField, TMapping> FieldType<TMapping, DatePivot> for TField
     where
@@ -236,12 +246,13 @@ mod private {
         where
             S: Serializer,
         {
-            let mut state = try!(serializer.serialize_struct("mapping", 9));
+            let mut state = try!(serializer.serialize_struct("mapping", 10));
 
             try!(state.serialize_field("type", TMapping::data_type()));
             try!(state.serialize_field("format", TMapping::Format::name()));
 
             ser_field!(state, "boost", TMapping::boost());
+            ser_field!(state, "copy_to", TMapping::copy_to());
             ser_field!(state, "doc_values", TMapping::doc_values());
             ser_field!(state, "include_in_all", TMapping::include_in_all());
             ser_field!(state, "index", TMapping::index());
@@ -252,6 +263,19 @@ mod private {
             state.end()
         }
     }
+
+    impl<TMapping> NullValueMapping<DatePivot> for TMapping
+    where
+        TMapping: DateMapping,
+    {
+        type NullValue = &'static str;
+
+        fn to_null_value(value: &'static str) -> Result<Value, String> {
+            TMapping::Format::parse(value)
+                .map(|_| Value::from(value))
+                .map_err(|err| err.to_string())
+        }
+    }
 }
 
 #[cfg(test)]
@@ -274,6 +298,10 @@ mod tests {
             Some(1.01)
         }
 
+        fn copy_to() -> Option<&'static [&'static str]> {
+            Some(&["timestamps", "all_dates"])
+        }
+
         fn index() -> Option<bool> {
             Some(true)
         }
@@ -318,6 +346,7 @@ mod tests {
             "type": "date",
             "format": "epoch_millis",
             "boost": 1.01,
+            "copy_to": ["timestamps", "all_dates"],
             "doc_values": true,
             "include_in_all": false,
             "index": true,
@@ -14,6 +14,11 @@ The `Date<M>` type and `chrono`s `DateTime<Utc>` are the main `date` field types
 If the mapping and format aren't important, use `DateTime<Utc>`.
 If you need to specify mapping properties like `boost`, or use a specific format like `epoch_millis`, use `Date<M>`.
 
+## `DateNanos<M>`
+
+The `DateNanos<M>` type is mapped as a `date_nanos` field, which stores dates with nanosecond precision.
+See the [`nanos` module](nanos/index.html) for more details.
+
 ## `DateValue` and `FormattableDateValue<F>`
 
 The `DateValue` and `FormattableDateValue<F>` types are used in methods to represent dates that either don't have a format or have a specific format respectively.
@@ -115,21 +120,82 @@ impl DateFormat for Rfc3339Format {
 # }
 ```
 
+## The `time` crate
+
+With the `time` feature enabled, a [`TimeDate<M>`](struct.TimeDate.html) is a `date` that's backed by the `time` crate's `OffsetDateTime` instead of `chrono`.
+It's mapped and formatted in the same way as a `Date<M>`, so it can be used as a field in documents that derive `ElasticType`:
+
+```
+# extern crate serde;
+# #[macro_use] extern crate serde_derive;
+# #[macro_use] extern crate elastic_types_derive;
+# extern crate elastic_types;
+# #[cfg(feature = "time")]
+# extern crate time;
+# use elastic_types::prelude::*;
+# fn main() {
+# #[cfg(feature = "time")]
+# {
+use time::OffsetDateTime;
+
+#[derive(Serialize, Deserialize, ElasticType)]
+struct MyType {
+    timestamp: TimeDate<DefaultDateMapping<EpochMillis>>,
+}
+
+let doc = MyType {
+    timestamp: TimeDate::new(OffsetDateTime::now_utc()),
+};
+# }
+# }
+```
+
+Date formats are still parsed and formatted using `chrono`, so it's still a dependency of this crate.
+
+Dates can also be converted to and from `OffsetDateTime`:
+
+```
+# extern crate elastic_types;
+# #[cfg(feature = "time")]
+# extern crate time;
+# use elastic_types::prelude::*;
+# fn main() {
+# #[cfg(feature = "time")]
+# {
+use time::OffsetDateTime;
+
+let date: Date<DefaultDateMapping> = Date::new(DateValue::from(OffsetDateTime::now_utc()));
+
+let offset_date = DateValue::from(date).to_offset_date_time().unwrap();
+# }
+# }
+```
+
+A `Date` that uses the `ChronoFormat` can be created from an `OffsetDateTime` directly, like it can from a `chrono::DateTime<Utc>`.
+
 # Links
 - [Elasticsearch Doc](https://www.elastic.co/guide/en/elasticsearch/reference/current/date.html)
 */
 
 pub mod mapping;
+pub mod nanos;
 
 mod format;
 mod formats;
 mod impls;
+#[cfg(feature = "time")]
+mod offset_date_time;
+#[cfg(feature = "time")]
+mod time_date;
 pub use self::{
     format::*,
     formats::*,
     impls::*,
 };
 
+#[cfg(feature = "time")]
+pub use self::time_date::*;
+
 pub mod prelude {
     /*!
     Includes all types for the `date` type.
@@ -147,6 +213,10 @@ pub mod prelude {
         formats::*,
         impls::*,
         mapping::*,
+        nanos::prelude::*,
         DefaultDateFormat,
     };
+
+    #[cfg(feature = "time")]
+    pub use super::time_date::*;
 }
@@ -0,0 +1,380 @@
+use super::mapping::{
+    DateNanosFieldType,
+    DateNanosMapping,
+};
+use chrono::{
+    NaiveDate,
+    NaiveDateTime,
+    NaiveTime,
+    Utc,
+};
+use date::{
+    ChronoDateTime,
+    DateValue,
+    FormattableDateValue,
+    ParseError,
+};
+use private::field::StdField;
+use serde::{
+    de::{
+        Error,
+        Visitor,
+    },
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use std::{
+    borrow::Borrow,
+    fmt::{
+        Display,
+        Formatter,
+        Result as FmtResult,
+    },
+    marker::PhantomData,
+    ops::Deref,
+};
+
+/**
+An Elasticsearch `date_nanos` type.
+
+The [format](../format/index.html) is provided by the mapping.
+This struct wraps up a `chrono::DateTime<Utc>` struct, so the nanoseconds of the date are preserved.
+
+# Examples
+
+Defining a date using the default format:
+
+```
+# use elastic_types::prelude::*;
+let date: DateNanos<DefaultDateNanosMapping> = DateNanos::now();
+```
+
+Accessing the nanoseconds of a date:
+
+```
+# use elastic_types::prelude::*;
+let date: DateNanos<DefaultDateNanosMapping> = DateNanos::build(2015, 5, 14, 16, 45, 8, 886123456);
+
+assert_eq!(886123456, date.nanosecond());
+```
+
+# Links
+
+- [Elasticsearch Doc](https://www.elastic.co/guide/en/elasticsearch/reference/current/date_nanos.html)
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateNanos<TMapping>
+where
+    TMapping: DateNanosMapping,
+{
+    value: FormattableDateValue<TMapping::Format>,
+}
+
+impl<TMapping> DateNanos<TMapping>
+where
+    TMapping: DateNanosMapping,
+{
+    /**
+    Creates a new `DateNanos` from the given date value.
+
+    # Examples
+
+    Create a `DateNanos` from a `DateValue`:
+
+    ```
+    # use elastic_types::prelude::*;
+    let date: DateNanos<DefaultDateNanosMapping> = DateNanos::new(DateValue::now());
+    ```
+    */
+    pub fn new<I>(date: I) -> Self
+    where
+        I: Into<FormattableDateValue<TMapping::Format>>,
+    {
+        DateNanos { value: date.into() }
+    }
+
+    /**
+    Creates a `DateNanos` from the given Utc primitives:
+
+    ```
+    # use elastic_types::prelude::*;
+    let date: DateNanos<DefaultDateNanosMapping> = DateNanos::build(2015, 5, 14, 16, 45, 8, 886123456);
+    ```
+    */
+    pub fn build(
+        year: i32,
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+        nano: u32,
+    ) -> Self {
+        let ndate = NaiveDate::from_ymd(year, month, day);
+        let ntime = NaiveTime::from_hms_nano(hour, minute, second, nano);
+
+        let date = ChronoDateTime::from_utc(NaiveDateTime::new(ndate, ntime), Utc);
+
+        DateNanos::new(DateValue::from(date))
+    }
+
+    /**
+    Gets the current system time.
+
+    # Examples
+
+    ```
+    # use elastic_types::prelude::*;
+    let date: DateNanos<DefaultDateNanosMapping> = DateNanos::now();
+    ```
+    */
+    pub fn now() -> Self {
+        DateNanos::new(DateValue::now())
+    }
+
+    /**
+    Change the format/mapping of this date.
+
+    # Examples
+
+    ```
+    # use elastic_types::prelude::*;
+    let date: DateNanos<DefaultDateNanosMapping> = DateNanos::now();
+
+    let otherdate: DateNanos<DefaultDateNanosMapping<EpochMillis>> = DateNanos::remap(date);
+    ```
+    */
+    pub fn remap<TNewMapping>(date: DateNanos<TMapping>) -> DateNanos<TNewMapping>
+    where
+        TNewMapping: DateNanosMapping,
+    {
+        DateNanos::new(DateValue::from(date.value))
+    }
+}
+
+impl<TMapping> DateNanosFieldType<TMapping> for DateNanos<TMapping> where TMapping: DateNanosMapping {}
+
+impl<TMapping> From<DateNanos<TMapping>> for FormattableDateValue<TMapping::Format>
+where
+    TMapping: DateNanosMapping,
+{
+    fn from(date: DateNanos<TMapping>) -> Self {
+        date.value
+    }
+}
+
+impl<TMapping> From<DateNanos<TMapping>> for DateValue
+where
+    TMapping: DateNanosMapping,
+{
+    fn from(date: DateNanos<TMapping>) -> Self {
+        date.value.into()
+    }
+}
+
+impl<TMapping> From<DateValue> for DateNanos<TMapping>
+where
+    TMapping: DateNanosMapping,
+{
+    fn from(value: DateValue) -> Self {
+        DateNanos::new(value)
+    }
+}
+
+impl<TMapping> StdField<ChronoDateTime> for DateNanos<TMapping> where TMapping: DateNanosMapping {}
+
+impl<TMapping> PartialEq<ChronoDateTime> for DateNanos<TMapping>
+where
+    TMapping: DateNanosMapping,
+{
+    fn eq(&self, other: &ChronoDateTime) -> bool {
+        PartialEq::eq(&self.value, other)
+    }
+
+    fn ne(&self, other: &ChronoDateTime) -> bool {
+        PartialEq::ne(&self.value, other)
+    }
+}
+
+impl<TMapping> PartialEq<DateNanos<TMapping>> for ChronoDateTime
+where
+    TMapping: DateNanosMapping,
+{
+    fn eq(&self, other: &DateNanos<TMapping>) -> bool {
+        PartialEq::eq(self, &other.value)
+    }
+
+    fn ne(&self, other: &DateNanos<TMapping>) -> bool {
+        PartialEq::ne(self, &other.value)
+    }
+}
+
+impl<TMapping> Deref for DateNanos<TMapping>
+where
+    TMapping: DateNanosMapping,
+{
+    type Target = ChronoDateTime;
+    fn deref(&self) -> &ChronoDateTime {
+        self.value.borrow()
+    }
+}
+
+impl<TMapping> Borrow<ChronoDateTime> for DateNanos<TMapping>
+where
+    TMapping: DateNanosMapping,
+{
+    fn borrow(&self) -> &ChronoDateTime {
+        self.value.borrow()
+    }
+}
+
+impl<TMapping> Default for DateNanos<TMapping>
+where
+    TMapping: DateNanosMapping,
+{
+    fn default() -> Self {
+        DateNanos::now()
+    }
+}
+
+impl<TMapping> Display for DateNanos<TMapping>
+where
+    TMapping: DateNanosMapping,
+{
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.value.format())
+    }
+}
+
+impl<TMapping> Serialize for DateNanos<TMapping>
+where
+    TMapping: DateNanosMapping,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&self)
+    }
+}
+
+impl<'de, TMapping> Deserialize<'de> for DateNanos<TMapping>
+where
+    TMapping: DateNanosMapping,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct DateNanosVisitor<TMapping> {
+            _m: PhantomData<TMapping>,
+        }
+
+        impl<'de, TMapping> Visitor<'de> for DateNanosVisitor<TMapping>
+        where
+            TMapping: DateNanosMapping,
+        {
+            type Value = DateNanos<TMapping>;
+
+            fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                write!(
+                    formatter,
+                    "a json string or number containing a formatted date"
+                )
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<DateNanos<TMapping>, E>
+            where
+                E: Error,
+            {
+                parse(v).map_err(|err| Error::custom(format!("{}", err)))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<DateNanos<TMapping>, E>
+            where
+                E: Error,
+            {
+                parse(&v.to_string()).map_err(|err| Error::custom(format!("{}", err)))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<DateNanos<TMapping>, E>
+            where
+                E: Error,
+            {
+                parse(&v.to_string()).map_err(|err| Error::custom(format!("{}", err)))
+            }
+        }
+
+        deserializer.deserialize_any(DateNanosVisitor::<TMapping> { _m: PhantomData })
+    }
+}
+
+/** A convenience function for parsing a date. */
+fn parse<TMapping>(date: &str) -> Result<DateNanos<TMapping>, ParseError>
+where
+    TMapping: DateNanosMapping,
+{
+    let parsed = FormattableDateValue::parse(date)?;
+
+    Ok(DateNanos::new(parsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use prelude::*;
+
+    #[test]
+    fn can_change_date_nanos_mapping() {
+        fn takes_epoch_millis(_: DateNanos<DefaultDateNanosMapping<EpochMillis>>) -> bool {
+            true
+        }
+
+        let date: DateNanos<DefaultDateNanosMapping> = DateNanos::now();
+
+        assert!(takes_epoch_millis(DateNanos::remap(date)));
+    }
+
+    #[test]
+    fn can_build_date_nanos_from_prim() {
+        let date: DateNanos<DefaultDateNanosMapping> =
+            DateNanos::build(2015, 5, 13, 0, 0, 0, 123456789);
+
+        assert_eq!(
+            (2015, 5, 13, 0, 0, 0, 123456789),
+            (
+                date.year(),
+                date.month(),
+                date.day(),
+                date.hour(),
+                date.minute(),
+                date.second(),
+                date.nanosecond()
+            )
+        );
+    }
+
+    #[test]
+    fn serialise_elastic_date_nanos() {
+        let date: DateNanos<DefaultDateNanosMapping> =
+            DateNanos::build(2015, 5, 13, 0, 0, 0, 123456789);
+
+        let ser = serde_json::to_string(&date).unwrap();
+
+        assert_eq!(r#""2015-05-13T00:00:00.123456789Z""#, ser);
+    }
+
+    #[test]
+    fn deserialise_elastic_date_nanos() {
+        let date: DateNanos<DefaultDateNanosMapping> =
+            serde_json::from_str(r#""2015-05-13T00:00:00.123456789Z""#).unwrap();
+
+        assert_eq!(
+            (2015, 5, 13, 123456789),
+            (date.year(), date.month(), date.day(), date.nanosecond())
+        );
+    }
+}
@@ -0,0 +1,287 @@
+/*! Mapping for the Elasticsearch `date_nanos` type. */
+
+use super::DateNanos;
+use date::{
+    DateFormat,
+    DefaultDateNanosFormat,
+};
+use std::marker::PhantomData;
+
+/** A field that will be mapped as a `date_nanos`. */
+pub trait DateNanosFieldType<TMapping> {}
+
+/**
+The base requirements for mapping a `date_nanos` type.
+
+# Examples
+
+Define a custom `DateNanosMapping`:
+
+```
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# use elastic_types::prelude::*;
+#[derive(Default)]
+struct MyDateNanosMapping;
+impl DateNanosMapping for MyDateNanosMapping {
+    type Format = DefaultDateNanosFormat;
+
+    //Overload the mapping functions here
+    fn ignore_malformed() -> Option<bool> {
+        Some(true)
+    }
+}
+# fn main() {}
+```
+
+This will produce the following mapping:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# extern crate serde_json;
+# use elastic_types::prelude::*;
+# #[derive(Default)]
+# struct MyDateNanosMapping;
+# impl DateNanosMapping for MyDateNanosMapping {
+#     type Format = DefaultDateNanosFormat;
+#     fn ignore_malformed() -> Option<bool> {
+#         Some(true)
+#     }
+# }
+# fn main() {
+# let mapping = elastic_types::derive::standalone_field_ser(MyDateNanosMapping).unwrap();
+# let json = json_str!(
+{
+    "type": "date_nanos",
+    "format": "strict_date_optional_time_nanos",
+    "ignore_malformed": true
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+*/
+pub trait DateNanosMapping {
+    /**
+    The date format bound to this mapping.
+
+    The value of `Format::name()` is what's sent to Elasticsearch as the format to use.
+    This is also used to serialise and deserialise formatted `DateNanos`.
+    */
+    type Format: DateFormat;
+
+    /**
+    The names of other fields to copy the values of this field into.
+    Copying several fields into one lets them be searched together as a single field.
+    */
+    fn copy_to() -> Option<&'static [&'static str]> {
+        None
+    }
+
+    /**
+    Should the field be stored on disk in a column-stride fashion,
+    so that it can later be used for sorting, aggregations, or scripting?
+    Accepts `true` (default) or `false`.
+    */
+    fn doc_values() -> Option<bool> {
+        None
+    }
+
+    /** Should the field be searchable? Accepts `true` (default) and `false`. */
+    fn index() -> Option<bool> {
+        None
+    }
+
+    /**
+    Whether the field value should be stored and retrievable separately from the `_source` field.
+    Accepts `true` or `false` (default).
+    */
+    fn store() -> Option<bool> {
+        None
+    }
+
+    /**
+    If `true`, malformed dates are ignored.
+    If `false` (default), malformed dates throw an exception and reject the whole document.
+    */
+    fn ignore_malformed() -> Option<bool> {
+        None
+    }
+
+    /**
+    Accepts a date value in the configured format which is substituted for any explicit null values.
+    Defaults to `null`, which means the field is treated as missing.
+    */
+    fn null_value() -> Option<DateNanos<Self>>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+/** Default mapping for `date_nanos`. */
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub struct DefaultDateNanosMapping<TFormat = DefaultDateNanosFormat>
+where
+    TFormat: DateFormat,
+{
+    _f: PhantomData<TFormat>,
+}
+
+impl<TFormat> DateNanosMapping for DefaultDateNanosMapping<TFormat>
+where
+    TFormat: DateFormat,
+{
+    type Format = TFormat;
+}
+
+mod private {
+    use super::{
+        DateNanosFieldType,
+        DateNanosMapping,
+    };
+    use date::DateFormat;
+    use derive::NullValueMapping;
+    use private::field::{
+        FieldMapping,
+        FieldType,
+        SerializeFieldMapping,
+        StaticSerialize,
+    };
+    use serde::{
+        ser::SerializeStruct,
+        Serialize,
+        Serializer,
+    };
+    use serde_json::Value;
+
+    #[derive(Default)]
+    pub struct DateNanosPivot;
+
+    impl<TField, TMapping> FieldType<TMapping, DateNanosPivot> for TField
+    where
+        TField: DateNanosFieldType<TMapping> + Serialize,
+        TMapping: DateNanosMapping,
+    {
+    }
+
+    impl<TMapping> FieldMapping<DateNanosPivot> for TMapping
+    where
+        TMapping: DateNanosMapping,
+    {
+        type SerializeFieldMapping = SerializeFieldMapping<TMapping, DateNanosPivot>;
+
+        fn data_type() -> &'static str {
+            "date_nanos"
+        }
+    }
+
+    impl<TMapping> StaticSerialize for SerializeFieldMapping<TMapping, DateNanosPivot>
+    where
+        TMapping: FieldMapping<DateNanosPivot> + DateNanosMapping,
+    {
+        fn static_serialize<S>(serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut state = try!(serializer.serialize_struct("mapping", 8));
+
+            try!(state.serialize_field("type", TMapping::data_type()));
+            try!(state.serialize_field("format", TMapping::Format::name()));
+
+            ser_field!(state, "copy_to", TMapping::copy_to());
+            ser_field!(state, "doc_values", TMapping::doc_values());
+            ser_field!(state, "index", TMapping::index());
+            ser_field!(state, "store", TMapping::store());
+            ser_field!(state, "ignore_malformed", TMapping::ignore_malformed());
+            ser_field!(state, "null_value", TMapping::null_value());
+
+            state.end()
+        }
+    }
+
+    impl<TMapping> NullValueMapping<DateNanosPivot> for TMapping
+    where
+        TMapping: DateNanosMapping,
+    {
+        type NullValue = &'static str;
+
+        fn to_null_value(value: &'static str) -> Result<Value, String> {
+            TMapping::Format::parse(value)
+                .map(|_| Value::from(value))
+                .map_err(|err| err.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use prelude::*;
+    use private::field;
+
+    #[derive(Default, Clone)]
+    pub struct MyDateNanosMapping;
+    impl DateNanosMapping for MyDateNanosMapping {
+        type Format = DefaultDateNanosFormat;
+
+        fn doc_values() -> Option<bool> {
+            Some(true)
+        }
+
+        fn index() -> Option<bool> {
+            Some(false)
+        }
+
+        fn store() -> Option<bool> {
+            Some(true)
+        }
+
+        fn ignore_malformed() -> Option<bool> {
+            Some(true)
+        }
+
+        fn null_value() -> Option<DateNanos<Self>> {
+            Some(DateNanos::build(2015, 3, 14, 16, 45, 13, 778000001))
+        }
+    }
+
+    #[test]
+    fn serialise_mapping_default() {
+        let ser = serde_json::to_string(&field::serialize(DefaultDateNanosMapping::<
+            DefaultDateNanosFormat,
+        >::default()))
+        .unwrap();
+
+        let expected = json_str!({
+            "type": "date_nanos",
+            "format": "strict_date_optional_time_nanos"
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_custom() {
+        let ser = serde_json::to_string(&field::serialize(MyDateNanosMapping)).unwrap();
+
+        let expected = json_str!({
+            "type": "date_nanos",
+            "format": "strict_date_optional_time_nanos",
+            "doc_values": true,
+            "index": false,
+            "store": true,
+            "ignore_malformed": true,
+            "null_value": "2015-03-14T16:45:13.778000001Z"
+        });
+
+        assert_eq!(expected, ser);
+    }
+}
@@ -0,0 +1,61 @@
+/*!
+Implementation of the Elasticsearch `date_nanos` type.
+
+A `date_nanos` field stores dates with nanosecond precision instead of the millisecond precision of a `date` field.
+It's a good choice for high-resolution timestamps, like events that happen within the same millisecond.
+
+`DateNanos<M>` wraps a `chrono::DateTime<Utc>`, so the nanoseconds of a date are preserved when it's serialised and deserialised.
+Like `Date<M>`, the format is part of the mapping.
+The default format is `strict_date_optional_time_nanos`.
+
+# Examples
+
+For defining your own date nanos mapping, see [mapping details](mapping/trait.DateNanosMapping.html).
+
+Map with a default `date_nanos`:
+
+```
+# use elastic_types::prelude::*;
+struct MyType {
+    pub field: DateNanos<DefaultDateNanosMapping>
+}
+```
+
+Map with a custom `date_nanos` mapping:
+
+```
+# extern crate serde;
+# #[macro_use]
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+# fn main() {
+# #[derive(Default)]
+# struct MyDateNanosMapping;
+# impl DateNanosMapping for MyDateNanosMapping { type Format = DefaultDateNanosFormat; }
+struct MyType {
+    pub field: DateNanos<MyDateNanosMapping>
+}
+# }
+```
+
+# Links
+- [Elasticsearch Doc](https://www.elastic.co/guide/en/elasticsearch/reference/current/date_nanos.html)
+*/
+
+pub mod mapping;
+
+mod impls;
+pub use self::impls::*;
+
+pub mod prelude {
+    /*!
+    Includes all types for the `date_nanos` type.
+
+    This is a convenience module to make it easy to build mappings for multiple types without too many `use` statements.
+    */
+
+    pub use super::{
+        impls::*,
+        mapping::*,
+    };
+}
@@ -0,0 +1,138 @@
+/*!
+Conversions between dates and the `time` crate's `OffsetDateTime`.
+
+These are only available with the `time` feature.
+*/
+
+use chrono::{
+    TimeZone,
+    Utc,
+};
+use time::{
+    error::ComponentRange,
+    OffsetDateTime,
+    UtcOffset,
+};
+
+use super::{
+    ChronoFormat,
+    DateValue,
+    FormattableDateValue,
+};
+
+impl DateValue {
+    /**
+    Convert the date into a `time::OffsetDateTime` in `Utc`.
+
+    This will fail if the year is outside the range supported by the `time` crate.
+
+    # Examples
+
+    ```
+    # extern crate elastic_types;
+    # #[cfg(feature = "time")]
+    # extern crate time;
+    # use elastic_types::prelude::*;
+    # fn main() {
+    # #[cfg(feature = "time")]
+    # {
+    let date: Date<DefaultDateMapping> = Date::build(2015, 5, 14, 16, 45, 8, 886);
+
+    let offset_date = DateValue::from(date).to_offset_date_time().unwrap();
+
+    assert_eq!(2015, offset_date.year());
+    assert_eq!(886, offset_date.millisecond());
+    # }
+    # }
+    ```
+    */
+    pub fn to_offset_date_time(&self) -> Result<OffsetDateTime, ComponentRange> {
+        let nanos = i128::from(self.timestamp()) * 1_000_000_000
+            + i128::from(self.timestamp_subsec_nanos());
+
+        OffsetDateTime::from_unix_timestamp_nanos(nanos)
+    }
+}
+
+impl From<OffsetDateTime> for DateValue {
+    fn from(date: OffsetDateTime) -> Self {
+        let date = date.to_offset(UtcOffset::UTC);
+
+        let date = Utc
+            .timestamp_opt(date.unix_timestamp(), date.nanosecond())
+            .single()
+            .expect("dates from the `time` crate are always in range for `chrono`");
+
+        DateValue::from(date)
+    }
+}
+
+impl From<OffsetDateTime> for FormattableDateValue<ChronoFormat> {
+    fn from(date: OffsetDateTime) -> Self {
+        FormattableDateValue::from(DateValue::from(date))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{
+        Date as TimeDate,
+        Month,
+        OffsetDateTime,
+        PrimitiveDateTime,
+        Time,
+        UtcOffset,
+    };
+
+    use prelude::*;
+
+    fn offset_date(offset_hours: i8) -> OffsetDateTime {
+        let date = TimeDate::from_calendar_date(2015, Month::May, 14).unwrap();
+        let time = Time::from_hms_nano(16, 45, 8, 886_123_456).unwrap();
+
+        PrimitiveDateTime::new(date, time)
+            .assume_offset(UtcOffset::from_hms(offset_hours, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn date_from_offset_date_time() {
+        let date: Date<DefaultDateMapping> = Date::new(DateValue::from(offset_date(0)));
+
+        assert_eq!(
+            (2015i32, 5u32, 14u32, 16u32, 45u32, 8u32, 886_123_456u32),
+            (
+                date.year(),
+                date.month(),
+                date.day(),
+                date.hour(),
+                date.minute(),
+                date.second(),
+                date.nanosecond()
+            )
+        );
+    }
+
+    #[test]
+    fn date_from_offset_date_time_converts_to_utc() {
+        let date: Date<DefaultDateMapping<ChronoFormat>> = Date::new(offset_date(10));
+
+        assert_eq!((14u32, 6u32), (date.day(), date.hour()));
+    }
+
+    #[test]
+    fn date_nanos_round_trips_through_offset_date_time() {
+        let date: DateNanos<DefaultDateNanosMapping> =
+            DateNanos::new(DateValue::from(offset_date(0)));
+
+        let offset_date_time = DateValue::from(date).to_offset_date_time().unwrap();
+
+        assert_eq!(offset_date(0), offset_date_time);
+    }
+
+    #[test]
+    fn out_of_range_date_to_offset_date_time() {
+        let date = DateValue::build(20000, 1, 1, 0, 0, 0, 0);
+
+        assert!(date.to_offset_date_time().is_err());
+    }
+}
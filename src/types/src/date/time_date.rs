@@ -0,0 +1,312 @@
+use serde::{
+    de::{
+        Error,
+        Visitor,
+    },
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use std::{
+    borrow::Borrow,
+    fmt::{
+        Display,
+        Formatter,
+        Result as FmtResult,
+    },
+    marker::PhantomData,
+};
+use time::OffsetDateTime;
+
+use super::{
+    format::{
+        DateValue,
+        FormattableDateValue,
+    },
+    mapping::{
+        DateFieldType,
+        DateMapping,
+    },
+};
+
+/**
+An Elasticsearch `date` type backed by the `time` crate's `OffsetDateTime`.
+
+`TimeDate<M>` is mapped and formatted in the same way as a [`Date<M>`](struct.Date.html), using the format from its mapping.
+It can be used as a field in documents that derive `ElasticType` instead of a `Date<M>`, so the rest of an application can work with `OffsetDateTime` values.
+Dates are converted to `Utc` when they're serialised, and deserialised dates are in `Utc`.
+
+This type is only available with the `time` feature.
+
+# Examples
+
+```
+# extern crate elastic_types;
+# #[cfg(feature = "time")]
+# extern crate time;
+# use elastic_types::prelude::*;
+# fn main() {
+# #[cfg(feature = "time")]
+# {
+use time::OffsetDateTime;
+
+let date: TimeDate<DefaultDateMapping<EpochMillis>> =
+    TimeDate::new(OffsetDateTime::from_unix_timestamp(1431621908).unwrap());
+
+assert_eq!(2015, date.year());
+assert_eq!("1431621908000", date.to_string());
+# }
+# }
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeDate<TMapping>
+where
+    TMapping: DateMapping,
+{
+    value: OffsetDateTime,
+    _m: PhantomData<TMapping>,
+}
+
+impl<TMapping> TimeDate<TMapping>
+where
+    TMapping: DateMapping,
+{
+    /** Creates a new date from the given `OffsetDateTime`. */
+    pub fn new<I: Into<OffsetDateTime>>(date: I) -> TimeDate<TMapping> {
+        TimeDate {
+            value: date.into(),
+            _m: PhantomData,
+        }
+    }
+
+    /** Gets the current system time in `Utc`. */
+    pub fn now() -> TimeDate<TMapping> {
+        TimeDate::new(OffsetDateTime::now_utc())
+    }
+
+    /** Change the format/mapping of this date. */
+    pub fn remap<TNewMapping>(date: TimeDate<TMapping>) -> TimeDate<TNewMapping>
+    where
+        TNewMapping: DateMapping,
+    {
+        TimeDate::new(date.value)
+    }
+
+    /** Unwraps the `OffsetDateTime`. */
+    pub fn into_inner(self) -> OffsetDateTime {
+        self.value
+    }
+}
+
+impl<TMapping> DateFieldType<TMapping> for TimeDate<TMapping> where TMapping: DateMapping {}
+
+impl_mapping_type!(OffsetDateTime, TimeDate, DateMapping);
+
+impl<TMapping> From<TimeDate<TMapping>> for FormattableDateValue<TMapping::Format>
+where
+    TMapping: DateMapping,
+{
+    fn from(date: TimeDate<TMapping>) -> Self {
+        FormattableDateValue::from(DateValue::from(date))
+    }
+}
+
+impl<TMapping> From<TimeDate<TMapping>> for DateValue
+where
+    TMapping: DateMapping,
+{
+    fn from(date: TimeDate<TMapping>) -> Self {
+        DateValue::from(date.value)
+    }
+}
+
+impl<TMapping> Default for TimeDate<TMapping>
+where
+    TMapping: DateMapping,
+{
+    fn default() -> Self {
+        TimeDate::now()
+    }
+}
+
+impl<TMapping> Display for TimeDate<TMapping>
+where
+    TMapping: DateMapping,
+{
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let value: FormattableDateValue<TMapping::Format> =
+            FormattableDateValue::from(DateValue::from(self.value));
+
+        write!(f, "{}", value.format())
+    }
+}
+
+impl<TMapping> Serialize for TimeDate<TMapping>
+where
+    TMapping: DateMapping,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&self)
+    }
+}
+
+impl<'de, TMapping> Deserialize<'de> for TimeDate<TMapping>
+where
+    TMapping: DateMapping,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TimeDateVisitor<TMapping> {
+            _m: PhantomData<TMapping>,
+        }
+
+        impl<'de, TMapping> Visitor<'de> for TimeDateVisitor<TMapping>
+        where
+            TMapping: DateMapping,
+        {
+            type Value = TimeDate<TMapping>;
+
+            fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                write!(
+                    formatter,
+                    "a json string or number containing a formatted date"
+                )
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<TimeDate<TMapping>, E>
+            where
+                E: Error,
+            {
+                parse(v)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<TimeDate<TMapping>, E>
+            where
+                E: Error,
+            {
+                parse(&v.to_string())
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<TimeDate<TMapping>, E>
+            where
+                E: Error,
+            {
+                parse(&v.to_string())
+            }
+        }
+
+        deserializer.deserialize_any(TimeDateVisitor::<TMapping> { _m: PhantomData })
+    }
+}
+
+/** Parse a date using the format from its mapping. */
+fn parse<TMapping, E>(date: &str) -> Result<TimeDate<TMapping>, E>
+where
+    TMapping: DateMapping,
+    E: Error,
+{
+    let parsed = FormattableDateValue::<TMapping::Format>::parse(date)
+        .map_err(|err| E::custom(format!("{}", err)))?;
+
+    let date = DateValue::from(parsed)
+        .to_offset_date_time()
+        .map_err(|err| E::custom(format!("{}", err)))?;
+
+    Ok(TimeDate::new(date))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+    use time::{
+        Date as CalendarDate,
+        Month,
+        OffsetDateTime,
+        PrimitiveDateTime,
+        Time,
+        UtcOffset,
+    };
+
+    use prelude::*;
+
+    fn offset_date(offset_hours: i8) -> OffsetDateTime {
+        let date = CalendarDate::from_calendar_date(2015, Month::May, 14).unwrap();
+        let time = Time::from_hms_milli(16, 45, 8, 886).unwrap();
+
+        PrimitiveDateTime::new(date, time)
+            .assume_offset(UtcOffset::from_hms(offset_hours, 0, 0).unwrap())
+    }
+
+    #[derive(Serialize, Deserialize, ElasticType)]
+    struct TimeDateType {
+        timestamp: TimeDate<DefaultDateMapping<EpochMillis>>,
+        created: TimeDate<DefaultDateMapping>,
+    }
+
+    #[test]
+    fn serialise_time_date_mapping() {
+        let ser = serde_json::to_string(&TimeDateType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "timestamp": {
+                    "type": "date",
+                    "format": "epoch_millis"
+                },
+                "created": {
+                    "type": "date",
+                    "format": "basic_date_time"
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_time_date() {
+        let doc = TimeDateType {
+            timestamp: TimeDate::new(offset_date(0)),
+            created: TimeDate::new(offset_date(10)),
+        };
+
+        let ser = serde_json::to_string(&doc).unwrap();
+
+        assert_eq!(
+            r#"{"timestamp":"1431621908886","created":"20150514T064508.886Z"}"#,
+            ser
+        );
+    }
+
+    #[test]
+    fn deserialise_time_date() {
+        let doc: TimeDateType =
+            serde_json::from_str(r#"{"timestamp":1431621908886,"created":"20150514T164508.886Z"}"#)
+                .unwrap();
+
+        assert_eq!(offset_date(0), *doc.timestamp);
+        assert_eq!(offset_date(0), *doc.created);
+    }
+
+    #[test]
+    fn deserialise_time_date_invalid() {
+        let de = serde_json::from_str::<TimeDate<DefaultDateMapping>>(r#""not a date""#);
+
+        assert!(de.is_err());
+    }
+
+    #[test]
+    fn remap_time_date() {
+        let date: TimeDate<DefaultDateMapping> = TimeDate::new(offset_date(0));
+        let date: TimeDate<DefaultDateMapping<EpochMillis>> = TimeDate::remap(date);
+
+        assert_eq!("1431621908886", date.to_string());
+    }
+}
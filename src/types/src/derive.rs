@@ -11,34 +11,72 @@ use chrono::{
         Parsed,
     },
     DateTime,
+    Duration,
     Utc,
 };
-use serde::Serialize;
-use serde_json;
-
-use private::field::{
-    FieldMapping,
-    FieldType,
-    SerializeFieldMapping,
+use serde::ser::Error as SerError;
+use serde_json::{
+    self,
+    Map,
+    Value,
+};
+use std::{
+    borrow::Borrow,
+    collections::BTreeMap,
 };
 
+use private::field::SerializeFieldMapping;
+
 pub use date::{
     DateFormat,
     DateValue,
     FormattedDate,
     ParseError,
 };
+pub use private::{
+    field::{
+        FieldMapping,
+        FieldType,
+    },
+    impls::WrappedFieldType,
+};
+
 pub use document::{
+    fields::field_path,
     mapping::{
+        Dynamic,
+        FieldMeta,
         ObjectFieldType,
         ObjectMapping,
         PropertiesMapping,
+        RuntimeFieldMapping,
+        NESTED_DATATYPE,
+        OBJECT_DATATYPE,
     },
+    DocumentFields,
+    DocumentId,
     DocumentType,
+    FieldRef,
+    PartialDocument,
     StaticIndex,
     StaticType,
     DEFAULT_DOC_TYPE,
 };
+pub use string::{
+    keyword::{
+        mapping::{
+            KeywordFieldMapping,
+            LowercaseKeywordMapping,
+        },
+        Keyword,
+    },
+    mapping::{
+        ElasticCompletionFieldMapping,
+        ElasticTokenCountFieldMapping,
+        StringField,
+    },
+    text::mapping::TextFieldMapping,
+};
 
 pub use chrono::format::{
     Fixed,
@@ -46,7 +84,13 @@ pub use chrono::format::{
     Numeric,
     Pad,
 };
-pub use serde::ser::SerializeStruct;
+pub use serde::ser::{
+    Serialize,
+    SerializeMap,
+    SerializeStruct,
+    Serializer,
+};
+pub use serde_json::Error as MappingJsonError;
 
 /** Serialise a field mapping as a field using the given serialiser. */
 #[inline]
@@ -63,6 +107,219 @@ where
     state.serialize_field(field, &SerializeFieldMapping::<TMapping, TPivot>::default())
 }
 
+/**
+Mapping parameters for a field that are declared with `#[elastic()]` attributes.
+
+Parameters that are set replace any parameters with the same name declared by the field mapping.
+*/
+#[derive(Default)]
+pub struct FieldParams<'a> {
+    pub copy_to: Option<&'a [&'static str]>,
+    pub normalizer: Option<&'static str>,
+    pub analyzer: Option<&'static str>,
+    pub search_analyzer: Option<&'static str>,
+    pub boost: Option<f32>,
+    pub ignore_above: Option<u32>,
+    pub ignore_malformed: Option<bool>,
+    pub coerce: Option<bool>,
+    pub doc_values: Option<bool>,
+    pub index: Option<bool>,
+    pub store: Option<bool>,
+    pub null_value: Option<Result<Value, String>>,
+    pub data_type: Option<&'static str>,
+    pub fields: Option<&'a [(&'static str, StringField)]>,
+}
+
+impl<'a> FieldParams<'a> {
+    // Get the parameters that are set, except for multi-fields
+    fn to_params(&self) -> Result<Map<String, Value>, serde_json::Error> {
+        let mut params = Map::new();
+
+        if let Some(copy_to) = self.copy_to {
+            params.insert("copy_to".to_owned(), try!(serde_json::to_value(copy_to)));
+        }
+
+        if let Some(normalizer) = self.normalizer {
+            params.insert("normalizer".to_owned(), Value::from(normalizer));
+        }
+
+        if let Some(analyzer) = self.analyzer {
+            params.insert("analyzer".to_owned(), Value::from(analyzer));
+        }
+
+        if let Some(search_analyzer) = self.search_analyzer {
+            params.insert("search_analyzer".to_owned(), Value::from(search_analyzer));
+        }
+
+        if let Some(boost) = self.boost {
+            // Converting an `f32` into a `Value` directly widens it
+            // So `1.1` would be serialised as `1.100000023841858`
+            let boost = try!(serde_json::from_str(&try!(serde_json::to_string(&boost))));
+            params.insert("boost".to_owned(), boost);
+        }
+
+        if let Some(ignore_above) = self.ignore_above {
+            params.insert("ignore_above".to_owned(), Value::from(ignore_above));
+        }
+
+        if let Some(ignore_malformed) = self.ignore_malformed {
+            params.insert("ignore_malformed".to_owned(), Value::from(ignore_malformed));
+        }
+
+        if let Some(coerce) = self.coerce {
+            params.insert("coerce".to_owned(), Value::from(coerce));
+        }
+
+        if let Some(doc_values) = self.doc_values {
+            params.insert("doc_values".to_owned(), Value::from(doc_values));
+        }
+
+        if let Some(index) = self.index {
+            params.insert("index".to_owned(), Value::from(index));
+        }
+
+        if let Some(store) = self.store {
+            params.insert("store".to_owned(), Value::from(store));
+        }
+
+        if let Some(ref null_value) = self.null_value {
+            let null_value = try!(null_value.clone().map_err(serde_json::Error::custom));
+            params.insert("null_value".to_owned(), null_value);
+        }
+
+        Ok(params)
+    }
+}
+
+/**
+A field mapping that supports a `null_value` set with an `#[elastic(null_value)]` attribute.
+
+Field mappings that don't support a `null_value` don't implement this trait, so the attribute won't compile for them.
+*/
+pub trait NullValueMapping<TPivot> {
+    /** The type of the literal in the attribute. */
+    type NullValue;
+
+    /** Convert the literal into a `null_value`, or return why it isn't valid for the mapping. */
+    fn to_null_value(value: Self::NullValue) -> Result<Value, String>;
+}
+
+/** Get the `null_value` declared for a field in an attribute. */
+pub fn null_value<TField, TMapping, TPivot>(
+    value: <TMapping as NullValueMapping<TPivot>>::NullValue,
+) -> Result<Value, String>
+where
+    TField: FieldType<TMapping, TPivot>,
+    TMapping: FieldMapping<TPivot> + NullValueMapping<TPivot>,
+{
+    TMapping::to_null_value(value).map_err(|err| {
+        format!(
+            "invalid null_value for a {} field: {}",
+            TMapping::data_type(),
+            err
+        )
+    })
+}
+
+/**
+A field mapping that can be mapped as either an `object` or `nested` datatype.
+
+This is implemented for object mappings, and for wrapped types like `Vec` that contain them.
+*/
+pub trait ObjectDataTypeMapping {}
+
+impl<TMapping> ObjectDataTypeMapping for TMapping where TMapping: ObjectMapping {}
+
+/** Get the datatype declared for an object field in an `#[elastic(nested)]` or `#[elastic(object)]` attribute. */
+pub fn object_data_type<TField, TMapping, TPivot>(data_type: &'static str) -> &'static str
+where
+    TField: FieldType<TMapping, TPivot>,
+    TMapping: FieldMapping<TPivot> + ObjectDataTypeMapping,
+{
+    data_type
+}
+
+/** Serialise a field mapping with the given parameters as a field using the given serialiser. */
+pub fn field_ser_with_params<TField, TMapping, TPivot, S>(
+    state: &mut S,
+    field: &'static str,
+    params: FieldParams,
+) -> Result<(), S::Error>
+where
+    TField: FieldType<TMapping, TPivot>,
+    TMapping: FieldMapping<TPivot>,
+    S: SerializeStruct,
+    SerializeFieldMapping<TMapping, TPivot>: Serialize,
+{
+    let mapping = try!(
+        serde_json::to_value(SerializeFieldMapping::<TMapping, TPivot>::default())
+            .map_err(S::Error::custom)
+    );
+
+    match mapping {
+        Value::Object(mapping) => state.serialize_field(
+            field,
+            &ParamsFieldMapping {
+                mapping,
+                data_type: params.data_type,
+                params: try!(params.to_params().map_err(S::Error::custom)),
+                fields: params.fields.map(|fields| fields.iter().cloned().collect()),
+            },
+        ),
+        _ => Err(S::Error::custom(
+            "parameters can only be added to a field mapping that's an object",
+        )),
+    }
+}
+
+// A field mapping with parameters from attributes
+// The `type` is serialised first and the `fields` last, like other field mappings
+struct ParamsFieldMapping {
+    mapping: Map<String, Value>,
+    data_type: Option<&'static str>,
+    params: Map<String, Value>,
+    fields: Option<BTreeMap<&'static str, StringField>>,
+}
+
+impl Serialize for ParamsFieldMapping {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = try!(serializer.serialize_map(None));
+
+        if let Some(ty) = self.data_type {
+            try!(state.serialize_entry("type", ty));
+        } else if let Some(ty) = self.mapping.get("type") {
+            try!(state.serialize_entry("type", ty));
+        }
+
+        for (key, value) in &self.mapping {
+            let overridden = match key.as_ref() {
+                "type" => true,
+                // The `enabled` setting only applies to objects
+                "enabled" => self.data_type == Some(NESTED_DATATYPE),
+                "fields" => self.fields.is_some(),
+                key => self.params.contains_key(key),
+            };
+
+            if !overridden {
+                try!(state.serialize_entry(key, value));
+            }
+        }
+
+        for (key, value) in &self.params {
+            try!(state.serialize_entry(key, value));
+        }
+
+        if let Some(ref fields) = self.fields {
+            try!(state.serialize_entry("fields", fields));
+        }
+
+        state.end()
+    }
+}
+
 /**
 Serialize a field individually.
 
@@ -77,6 +334,28 @@ where
     serde_json::to_string(&SerializeFieldMapping::<TMapping, TPivot>::default())
 }
 
+/** Serialise the index mapping for a document as compact or pretty json. */
+pub fn mapping_json<TDocument>(pretty: bool) -> Result<String, serde_json::Error>
+where
+    TDocument: DocumentType,
+{
+    let mapping = TDocument::index_mapping();
+
+    if pretty {
+        serde_json::to_string_pretty(&mapping)
+    } else {
+        serde_json::to_string(&mapping)
+    }
+}
+
+/** Format a date field for an index name template. */
+pub fn format_index_date<TDate>(date: &TDate, fmt: &str) -> String
+where
+    TDate: Borrow<DateTime<Utc>>,
+{
+    date.borrow().format(fmt).to_string()
+}
+
 /** Parse a date string using an owned slice of items. */
 pub fn parse_from_tokens<'a>(date: &str, fmt: Vec<Item<'a>>) -> Result<DateValue, ParseError> {
     let mut parsed = Parsed::new();
@@ -88,8 +367,10 @@ pub fn parse_from_tokens<'a>(date: &str, fmt: Vec<Item<'a>>) -> Result<DateValue
                 let _ = parsed.set_minute(0);
             }
 
-            // Set the DateTime result
-            let naive_date = parsed.to_naive_datetime_with_offset(0)?;
+            // Convert the parsed local time into Utc using any parsed offset
+            let offset = parsed.offset.unwrap_or(0);
+            let naive_date = parsed.to_naive_datetime_with_offset(offset)?
+                - Duration::seconds(i64::from(offset));
 
             let date = DateTime::from_utc(naive_date, Utc);
 
@@ -99,6 +380,28 @@ pub fn parse_from_tokens<'a>(date: &str, fmt: Vec<Item<'a>>) -> Result<DateValue
     }
 }
 
+/**
+Parse a date string using the first of several owned slices of items that matches.
+
+If none of the formats match then the error from the last one is returned.
+*/
+pub fn parse_from_formats<'a>(
+    date: &str,
+    fmts: Vec<Vec<Item<'a>>>,
+) -> Result<DateValue, ParseError> {
+    let mut result = Err("no date formats to parse with".to_owned().into());
+
+    for fmt in fmts {
+        result = parse_from_tokens(date, fmt);
+
+        if result.is_ok() {
+            break;
+        }
+    }
+
+    result
+}
+
 /** Format a date string using an owned slice of items. */
 pub fn format_with_tokens<'a>(date: &'a DateValue, fmt: Vec<Item<'a>>) -> FormattedDate<'a> {
     date.format_with_items(fmt.into_iter()).into()
@@ -0,0 +1,456 @@
+/*!
+Typed references to the names of fields on a document.
+
+Field references are generated by `#[derive(ElasticType)]` and make it possible to refer to a field
+in a query or sort without repeating its name as a string.
+If a field is renamed then code referencing it through `fields()` will fail to compile.
+*/
+
+use std::{
+    collections::HashSet,
+    fmt,
+    marker::PhantomData,
+};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use string::keyword::{
+    mapping::{
+        DefaultKeywordMapping,
+        KeywordMapping,
+    },
+    Keyword,
+};
+
+/**
+A typed reference to the full path of a field on a document.
+
+The type parameter `T` is the Rust type of the field.
+It's used to offer methods that only make sense for certain kinds of fields, like `keyword` for strings or `fields` for inner objects.
+
+# Examples
+
+Get the name of a field on a derived document type:
+
+```
+# #[macro_use] extern crate serde_derive;
+# #[macro_use] extern crate elastic_types_derive;
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+# fn main() {
+#[derive(Serialize, ElasticType)]
+struct MyType {
+    pub title: String,
+    pub author: Author,
+}
+
+#[derive(Serialize, ElasticType)]
+struct Author {
+    pub name: String,
+}
+
+assert_eq!("title", MyType::fields().title.name());
+assert_eq!("title.keyword", MyType::fields().title.keyword().name());
+assert_eq!("author.name", MyType::fields().author.fields().name.name());
+# }
+```
+*/
+pub struct FieldRef<T> {
+    path: String,
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<T> FieldRef<T> {
+    /** Create a reference to a field with the given full path. */
+    pub fn new<I>(path: I) -> Self
+    where
+        I: Into<String>,
+    {
+        FieldRef {
+            path: path.into(),
+            _t: PhantomData,
+        }
+    }
+
+    /** The full path of the field, like `author.name`. */
+    pub fn name(&self) -> &str {
+        &self.path
+    }
+
+    /**
+    Get a reference to a multi-field of this field.
+
+    Use this for custom multi-fields that aren't covered by methods like `keyword`.
+    */
+    pub fn subfield<TSub>(&self, name: &str) -> FieldRef<TSub> {
+        FieldRef::new(field_path(&self.path, name))
+    }
+
+    /**
+    An `exists` query that matches documents with a value for this field.
+
+    # Examples
+
+    ```
+    # #[macro_use] extern crate serde_json;
+    # extern crate elastic_types;
+    # use elastic_types::prelude::*;
+    # fn main() {
+    let title: FieldRef<String> = FieldRef::new("title");
+
+    assert_eq!(json!({ "exists": { "field": "title" } }), title.exists());
+    # }
+    ```
+    */
+    pub fn exists(&self) -> Value {
+        json!({
+            "exists": {
+                "field": self.path
+            }
+        })
+    }
+
+    /**
+    A query that matches documents without a value for this field.
+
+    This is an `exists` query wrapped in a `bool` query's `must_not` clause.
+    */
+    pub fn is_null(&self) -> Value {
+        json!({
+            "bool": {
+                "must_not": [self.exists()]
+            }
+        })
+    }
+
+    /**
+    A `range` query that matches documents where this field is between `from` and `to`, inclusive.
+
+    # Examples
+
+    ```
+    # #[macro_use] extern crate serde_json;
+    # extern crate elastic_types;
+    # use elastic_types::prelude::*;
+    # fn main() {
+    let age: FieldRef<i32> = FieldRef::new("age");
+
+    assert_eq!(json!({ "range": { "age": { "gte": 18, "lte": 65 } } }), age.between(18, 65));
+    # }
+    ```
+    */
+    pub fn between<V>(&self, from: V, to: V) -> Value
+    where
+        V: Serialize,
+    {
+        json!({
+            "range": {
+                self.path.as_str(): {
+                    "gte": from,
+                    "lte": to
+                }
+            }
+        })
+    }
+
+    /**
+    A `terms` query that matches documents where this field has any of the given values.
+
+    # Examples
+
+    ```
+    # #[macro_use] extern crate serde_json;
+    # extern crate elastic_types;
+    # use elastic_types::prelude::*;
+    # fn main() {
+    let tags: FieldRef<Vec<String>> = FieldRef::new("tags");
+
+    assert_eq!(json!({ "terms": { "tags": ["rust", "search"] } }), tags.any_of(vec!["rust", "search"]));
+    # }
+    ```
+    */
+    pub fn any_of<I>(&self, values: I) -> Value
+    where
+        I: IntoIterator,
+        I::Item: Serialize,
+    {
+        let values: Vec<_> = values.into_iter().collect();
+
+        json!({
+            "terms": {
+                self.path.as_str(): values
+            }
+        })
+    }
+}
+
+impl<T> FieldRef<T>
+where
+    T: DocumentFields,
+{
+    /** Get references to the fields of this inner object. */
+    pub fn fields(&self) -> T::Fields {
+        T::fields_at(&self.path)
+    }
+}
+
+impl<T> FieldRef<T>
+where
+    T: DefaultStringField,
+{
+    /** Get a reference to the `keyword` multi-field that's mapped for strings by default. */
+    pub fn keyword(&self) -> FieldRef<Keyword<DefaultKeywordMapping>> {
+        self.subfield("keyword")
+    }
+}
+
+impl<TMapping> FieldRef<Keyword<TMapping>>
+where
+    TMapping: KeywordMapping,
+{
+    /**
+    A `term` query that matches documents where this field has the given value.
+
+    The value is normalized with `KeywordMapping::normalize_term` first, so fields with a `lowercase` normalizer are matched case-insensitively.
+
+    # Examples
+
+    ```
+    # #[macro_use] extern crate serde_json;
+    # extern crate elastic_types;
+    # use elastic_types::prelude::*;
+    # fn main() {
+    let email: FieldRef<Keyword<LowercaseKeywordMapping>> = FieldRef::new("email");
+
+    assert_eq!(json!({ "term": { "email": "kimchy@elastic.co" } }), email.term("Kimchy@elastic.co"));
+    # }
+    ```
+    */
+    pub fn term<V>(&self, value: V) -> Value
+    where
+        V: AsRef<str>,
+    {
+        json!({
+            "term": {
+                self.path.as_str(): TMapping::normalize_term(value.as_ref())
+            }
+        })
+    }
+
+    /**
+    A `terms` query that matches documents where this field has any of the given values.
+
+    Each value is normalized with `KeywordMapping::normalize_term` first, like `term`.
+    */
+    pub fn terms<I>(&self, values: I) -> Value
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let values: Vec<String> = values
+            .into_iter()
+            .map(|value| TMapping::normalize_term(value.as_ref()).into_owned())
+            .collect();
+
+        json!({
+            "terms": {
+                self.path.as_str(): values
+            }
+        })
+    }
+}
+
+impl<T> Clone for FieldRef<T> {
+    fn clone(&self) -> Self {
+        FieldRef::new(self.path.clone())
+    }
+}
+
+impl<T> PartialEq for FieldRef<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+
+impl<T> fmt::Debug for FieldRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("FieldRef").field(&self.path).finish()
+    }
+}
+
+impl<T> fmt::Display for FieldRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+impl<T> AsRef<str> for FieldRef<T> {
+    fn as_ref(&self) -> &str {
+        &self.path
+    }
+}
+
+impl<T> From<FieldRef<T>> for String {
+    fn from(field: FieldRef<T>) -> String {
+        field.path
+    }
+}
+
+impl<'a, T> From<&'a FieldRef<T>> for String {
+    fn from(field: &'a FieldRef<T>) -> String {
+        field.path.clone()
+    }
+}
+
+/**
+A type with fields that can be referenced by name.
+
+This trait is implemented by `#[derive(ElasticType)]`, which generates a `{TypeName}Fields` struct with a `FieldRef` for each mapped field.
+*/
+pub trait DocumentFields {
+    /** The struct of field references. */
+    type Fields;
+
+    /** Get references to the fields on this type, nested under the given path. */
+    fn fields_at(path: &str) -> Self::Fields;
+
+    /** Get references to the fields on this type. */
+    fn fields() -> Self::Fields {
+        Self::fields_at("")
+    }
+}
+
+impl<T> DocumentFields for Option<T>
+where
+    T: DocumentFields,
+{
+    type Fields = T::Fields;
+
+    fn fields_at(path: &str) -> Self::Fields {
+        T::fields_at(path)
+    }
+}
+
+impl<T> DocumentFields for Vec<T>
+where
+    T: DocumentFields,
+{
+    type Fields = T::Fields;
+
+    fn fields_at(path: &str) -> Self::Fields {
+        T::fields_at(path)
+    }
+}
+
+impl<T> DocumentFields for HashSet<T>
+where
+    T: DocumentFields,
+{
+    type Fields = T::Fields;
+
+    fn fields_at(path: &str) -> Self::Fields {
+        T::fields_at(path)
+    }
+}
+
+/**
+A field type that's mapped using the default string mapping.
+
+The default string mapping is `text` with a `keyword` multi-field.
+*/
+pub trait DefaultStringField {}
+
+impl DefaultStringField for String {}
+impl DefaultStringField for &str {}
+impl<T> DefaultStringField for Option<T> where T: DefaultStringField {}
+impl<T> DefaultStringField for Vec<T> where T: DefaultStringField {}
+impl<T> DefaultStringField for HashSet<T> where T: DefaultStringField {}
+
+/** Join a field name onto a parent path. */
+pub fn field_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use prelude::*;
+
+    #[test]
+    fn exists() {
+        let field: FieldRef<String> = FieldRef::new("title");
+
+        let expected = json!({ "exists": { "field": "title" } });
+
+        assert_eq!(expected, field.exists());
+    }
+
+    #[test]
+    fn is_null() {
+        let field: FieldRef<Option<String>> = FieldRef::new("author.name");
+
+        let expected = json!({
+            "bool": {
+                "must_not": [
+                    { "exists": { "field": "author.name" } }
+                ]
+            }
+        });
+
+        assert_eq!(expected, field.is_null());
+    }
+
+    #[test]
+    fn between() {
+        let field: FieldRef<f64> = FieldRef::new("price");
+
+        let expected = json!({ "range": { "price": { "gte": 1.5, "lte": 10.0 } } });
+
+        assert_eq!(expected, field.between(1.5, 10.0));
+    }
+
+    #[test]
+    fn any_of() {
+        let field: FieldRef<String> = FieldRef::new("title");
+
+        let expected = json!({ "terms": { "title.keyword": ["a", "b"] } });
+
+        assert_eq!(expected, field.keyword().any_of(&["a", "b"]));
+    }
+
+    #[test]
+    fn term_normalizes_lowercase_keywords() {
+        let lowercase: FieldRef<Keyword<LowercaseKeywordMapping>> = FieldRef::new("email");
+        let default: FieldRef<Keyword<DefaultKeywordMapping>> = FieldRef::new("code");
+
+        assert_eq!(
+            json!({ "term": { "email": "kimchy@elastic.co" } }),
+            lowercase.term("Kimchy@Elastic.co")
+        );
+        assert_eq!(json!({ "term": { "code": "AB-1" } }), default.term("AB-1"));
+    }
+
+    #[test]
+    fn terms_normalizes_lowercase_keywords() {
+        let field: FieldRef<Keyword<LowercaseKeywordMapping>> = FieldRef::new("tags");
+
+        let expected = json!({ "terms": { "tags": ["rust", "search"] } });
+
+        assert_eq!(expected, field.terms(vec!["Rust", "search"]));
+    }
+
+    #[test]
+    fn any_of_empty() {
+        let field: FieldRef<i32> = FieldRef::new("id");
+
+        let expected = json!({ "terms": { "id": [] } });
+
+        assert_eq!(expected, field.any_of(Vec::<Value>::new()));
+    }
+}
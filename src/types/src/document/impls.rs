@@ -1,9 +1,14 @@
+use super::introspect::FieldMapping;
 use super::mapping::{
+    FieldMeta,
     ObjectFieldType,
     ObjectMapping,
     PropertiesMapping,
 };
-use serde::ser::SerializeStruct;
+use serde::ser::{
+    Serialize,
+    SerializeStruct,
+};
 use serde_json::Value;
 use std::{
     borrow::Cow,
@@ -32,6 +37,34 @@ pub trait DocumentType: ObjectFieldType {
         IndexDocumentMapping::default()
     }
 
+    /**
+    Whether or not the original JSON body of this document is stored in the `_source` field.
+
+    This is `true` unless the document mapping disables its `_source`.
+    */
+    fn source_enabled() -> bool {
+        <<Self as ObjectFieldType>::Mapping as ObjectMapping>::source_enabled().unwrap_or(true)
+    }
+
+    /**
+    Get metadata for the fields of this document that are deprecated or have custom metadata.
+
+    This is the same metadata stored in the `_meta` field of the document mapping.
+    */
+    fn field_meta() -> &'static [FieldMeta] {
+        <<<Self as ObjectFieldType>::Mapping as ObjectMapping>::Properties as PropertiesMapping>::props_meta()
+    }
+
+    /**
+    Get the mapping for this document as a structured tree of fields.
+
+    The tree is built from the same mapping that's returned by `index_mapping`.
+    */
+    fn mapping_tree() -> FieldMapping {
+        FieldMapping::from_mapping(&Self::index_mapping())
+            .expect("document mappings are always valid field mappings")
+    }
+
     /** Get the name of the index this document belongs to. */
     fn index(&self) -> Cow<str>;
 
@@ -41,6 +74,15 @@ pub trait DocumentType: ObjectFieldType {
     /** Try get an id for this document. */
     fn partial_id(&self) -> Option<Cow<str>>;
 
+    /**
+    Try get a routing value for this document.
+
+    Documents are routed to a shard by their id unless they have a routing value.
+    */
+    fn partial_routing(&self) -> Option<Cow<str>> {
+        None
+    }
+
     /** Try get a statically known index this document belongs to. */
     fn partial_static_index() -> Option<&'static str>;
 
@@ -66,6 +108,71 @@ pub trait StaticType: DocumentType {
     }
 }
 
+/**
+A partial document that's used to update some of the fields of a document.
+
+This trait is implemented for the `{TypeName}Partial` struct generated when deriving `ElasticType` with `#[elastic(partial)]`.
+*/
+pub trait PartialDocument: Serialize {
+    /** The document type this partial document updates. */
+    type Document: DocumentType;
+}
+
+/**
+A field that can be used as the id of a document with `#[elastic(id)]`, or its routing value with `#[elastic(routing)]`.
+
+Strings and keywords are borrowed as the id, and numbers are converted into strings.
+An `Option` is only an id when it's `Some`, so documents can be indexed without an id before one is assigned.
+*/
+pub trait DocumentId {
+    /** Try get an id from this field. */
+    fn document_id(&self) -> Option<Cow<str>>;
+}
+
+impl DocumentId for String {
+    fn document_id(&self) -> Option<Cow<str>> {
+        Some(Cow::Borrowed(self))
+    }
+}
+
+impl<'a> DocumentId for &'a str {
+    fn document_id(&self) -> Option<Cow<str>> {
+        Some(Cow::Borrowed(self))
+    }
+}
+
+impl<'a> DocumentId for Cow<'a, str> {
+    fn document_id(&self) -> Option<Cow<str>> {
+        Some(Cow::Borrowed(self))
+    }
+}
+
+impl<TId> DocumentId for Option<TId>
+where
+    TId: DocumentId,
+{
+    fn document_id(&self) -> Option<Cow<str>> {
+        self.as_ref().and_then(DocumentId::document_id)
+    }
+}
+
+macro_rules! impl_to_string_document_id {
+    ($($id:ty),*) => {
+        $(
+            impl DocumentId for $id {
+                fn document_id(&self) -> Option<Cow<str>> {
+                    Some(Cow::Owned(self.to_string()))
+                }
+            }
+        )*
+    };
+}
+
+impl_to_string_document_id!(i32, i64, u32, u64, isize, usize);
+
+#[cfg(feature = "uuid")]
+impl_to_string_document_id!(::uuid::Uuid);
+
 /**
 A wrapper type for serialising user types as fields.
 */
@@ -337,6 +444,10 @@ where
         (*self).partial_id()
     }
 
+    fn partial_routing(&self) -> Option<Cow<str>> {
+        (*self).partial_routing()
+    }
+
     fn partial_static_index() -> Option<&'static str> {
         TDocument::partial_static_index()
     }
@@ -370,6 +481,10 @@ where
         self.as_ref().partial_id()
     }
 
+    fn partial_routing(&self) -> Option<Cow<str>> {
+        self.as_ref().partial_routing()
+    }
+
     fn partial_static_index() -> Option<&'static str> {
         TDocument::partial_static_index()
     }
@@ -431,6 +546,7 @@ mod tests {
     use std::{
         borrow::Cow,
         collections::HashSet,
+        net::Ipv4Addr,
     };
 
     // Make sure we can derive with no `uses`.
@@ -512,6 +628,295 @@ mod tests {
     #[derive(Serialize, ElasticType)]
     pub struct NoProps {}
 
+    #[derive(Serialize, ElasticType)]
+    #[elastic(source(enabled = false))]
+    pub struct NoSource {
+        pub field: i32,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(meta(owner = "search", version = 2))]
+    pub struct MetaType {
+        #[elastic(deprecated, meta(replaced_by = "field"))]
+        pub old_field: i32,
+        #[elastic(meta(unit = "ms"))]
+        pub took: i32,
+        pub field: i32,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(runtime(
+        double_field(ty = "long", script = "emit(doc['field'].value * 2)"),
+        field(ty = "keyword")
+    ))]
+    pub struct RuntimeType {
+        pub field: i32,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(nested)]
+    pub struct NestedItem {
+        pub field: i32,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(object)]
+    pub struct ObjectItem {
+        pub field: i32,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct Embedded {
+        pub nested: Vec<NestedItem>,
+        pub object: Vec<ObjectItem>,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct EmbeddedOverride {
+        #[elastic(object)]
+        pub nested: Vec<NestedItem>,
+        #[elastic(nested)]
+        pub object: Option<ObjectItem>,
+        #[elastic(nested)]
+        pub disabled: DisabledItem,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(enabled = false, dynamic = false)]
+    pub struct DisabledItem {
+        pub field: i32,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(dynamic = "strict")]
+    pub struct StrictType {
+        pub field: DisabledItem,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct MultiFieldType {
+        #[elastic(fields(keyword(ignore_above = 256), english(analyzer = "english")))]
+        pub title: String,
+        #[elastic(fields(length(ty = "token_count", analyzer = "standard")))]
+        pub tag: Keyword<DefaultKeywordMapping>,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct CopyToType {
+        #[elastic(copy_to = "name")]
+        pub first_name: Keyword<DefaultKeywordMapping>,
+        #[elastic(copy_to("name", "all"), fields(keyword))]
+        pub last_name: String,
+        pub name: Text<DefaultTextMapping>,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct NormalizerType {
+        #[elastic(normalizer = "lowercase")]
+        pub code: Keyword<DefaultKeywordMapping>,
+        #[elastic(normalizer = "ascii_folding", copy_to = "code")]
+        pub email: Keyword<LowercaseKeywordMapping>,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct MalformedType {
+        #[elastic(ignore_malformed)]
+        pub count: i32,
+        #[elastic(ignore_malformed = false, coerce = false)]
+        pub rating: f32,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct StorageType {
+        #[elastic(doc_values = false)]
+        pub session_id: Keyword<DefaultKeywordMapping>,
+        #[elastic(index = false, store)]
+        pub payload: i64,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct ParamsType {
+        #[elastic(analyzer = "english", search_analyzer = "standard", boost = 2.0)]
+        pub title: Text<DefaultTextMapping>,
+        #[elastic(ignore_above = 512, boost = 1.1)]
+        pub tag: Keyword<DefaultKeywordMapping>,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(mapping_json)]
+    pub struct CompactJsonType {
+        pub id: i32,
+        pub title: String,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(mapping_json = "pretty")]
+    pub struct PrettyJsonType {
+        pub id: i32,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(mapping_json)]
+    pub struct GenericJsonType<TPayload> {
+        pub payload: TPayload,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct UserId(String);
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(mapping = "DefaultKeywordMapping")]
+    pub struct Tag(String);
+
+    #[derive(Serialize, ElasticType)]
+    pub struct Count(i64);
+
+    #[derive(Serialize, ElasticType)]
+    pub struct Wrapper<T>(T);
+
+    #[derive(Serialize, ElasticType)]
+    pub struct NewtypeFieldsType {
+        pub user_id: UserId,
+        pub tag: Tag,
+        pub tags: Vec<Tag>,
+        pub count: Option<Count>,
+        pub wrapped: Wrapper<Date<DefaultDateMapping<EpochMillis>>>,
+        pub nested: Wrapper<SimpleNestedType>,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(partial)]
+    #[serde(rename_all = "camelCase")]
+    pub struct PartialType {
+        pub id: String,
+        pub first_name: String,
+        #[elastic(rename = "elastic_count")]
+        pub count: i32,
+        pub timestamp: Option<Date<DefaultDateMapping>>,
+        #[serde(skip_serializing)]
+        pub skipped: i32,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(partial)]
+    pub struct SerializeWithPartialType {
+        #[serde(serialize_with = "serialize_upper")]
+        pub title: String,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub tags: Vec<String>,
+    }
+
+    fn serialize_upper<S>(value: &String, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.serialize_str(&value.to_uppercase())
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(partial)]
+    pub struct GenericPartialType<TPayload> {
+        #[serde(serialize_with = "serialize_upper")]
+        pub title: String,
+        pub payload: TPayload,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RenamedType {
+        pub first_field: i32,
+        #[serde(rename = "serde_field")]
+        pub second_field: i32,
+        #[elastic(rename = "elastic_field")]
+        pub third_field: i32,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct NumericIdType {
+        #[elastic(id)]
+        pub id: i64,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct OptionalIdType {
+        #[elastic(id)]
+        pub id: Option<String>,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct GenericType<TPayload> {
+        #[elastic(id)]
+        pub id: String,
+        pub payload: TPayload,
+        pub history: Vec<TPayload>,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(bound = "TKey: KeywordFieldType<DefaultKeywordMapping> + ::serde::Serialize")]
+    pub struct BoundGenericType<TKey> {
+        pub key: TKey,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct BorrowedType<'a> {
+        pub title: &'a str,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(index = "logs-{service}-{timestamp:%Y.%m.%d}")]
+    pub struct TemplatedIndexType {
+        pub service: String,
+        pub timestamp: Date<DefaultDateMapping>,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct RoutedType {
+        #[elastic(routing)]
+        pub tenant: String,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(routing(expr = "self.tenant.to_lowercase()"))]
+    pub struct RoutedExprType {
+        pub tenant: String,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct SkippedType {
+        #[elastic(id, skip)]
+        pub id: String,
+        #[elastic(skip)]
+        pub elapsed: ::std::time::Duration,
+        pub title: String,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct NullValueType {
+        #[elastic(null_value = 0)]
+        pub count: i32,
+        #[elastic(null_value = 1.5)]
+        pub ratio: f32,
+        #[elastic(null_value = false)]
+        pub active: bool,
+        #[elastic(null_value = "NONE")]
+        pub code: Keyword<DefaultKeywordMapping>,
+        #[elastic(null_value = "127.0.0.1")]
+        pub host: Ipv4Addr,
+        #[elastic(null_value = "0")]
+        pub published: Date<DefaultDateMapping<EpochMillis>>,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct InvalidNullValueType {
+        #[elastic(null_value = "yesterday")]
+        pub published: Date<DefaultDateMapping<EpochMillis>>,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct EventType {
+        pub timestamp: DateNanos<DefaultDateNanosMapping>,
+    }
+
     #[derive(Default, Serialize)]
     pub struct Index {
         mappings: Mappings,
@@ -544,6 +949,17 @@ mod tests {
         assert_eq!("renamed_index", CustomType::static_index());
     }
 
+    #[test]
+    fn get_templated_type_index() {
+        let doc = TemplatedIndexType {
+            service: "api".to_owned(),
+            timestamp: Date::build(2015, 5, 14, 16, 45, 8, 886),
+        };
+
+        assert_eq!("logs-api-2015.05.14", doc.index());
+        assert!(TemplatedIndexType::partial_static_index().is_none());
+    }
+
     #[test]
     fn get_default_type() {
         assert_eq!("_doc", SimpleType::static_ty());
@@ -565,6 +981,38 @@ mod tests {
         assert_eq!("13", doc.partial_id().unwrap().as_ref());
     }
 
+    #[test]
+    fn get_numeric_id() {
+        let doc = NumericIdType { id: 13 };
+
+        assert_eq!("13", doc.partial_id().unwrap().as_ref());
+    }
+
+    #[test]
+    fn get_optional_id() {
+        let some = OptionalIdType {
+            id: Some("13".to_owned()),
+        };
+        let none = OptionalIdType { id: None };
+
+        assert_eq!("13", some.partial_id().unwrap().as_ref());
+        assert!(none.partial_id().is_none());
+    }
+
+    #[test]
+    fn get_routing() {
+        let field = RoutedType {
+            tenant: "Tenant".to_owned(),
+        };
+        let expr = RoutedExprType {
+            tenant: "Tenant".to_owned(),
+        };
+
+        assert_eq!("Tenant", field.partial_routing().unwrap().as_ref());
+        assert_eq!("tenant", expr.partial_routing().unwrap().as_ref());
+        assert!(NumericIdType { id: 1 }.partial_routing().is_none());
+    }
+
     #[test]
     fn derive_custom_type_mapping() {
         assert_eq!(
@@ -584,7 +1032,7 @@ mod tests {
                     "format": "epoch_millis"
                 },
                 "field2": {
-                    "type": "nested",
+                    "type": "object",
                     "properties": {
                         "field": {
                             "type": "integer"
@@ -627,16 +1075,16 @@ mod tests {
     }
 
     #[test]
-    fn serialise_document_for_custom_mapping() {
-        let ser = serde_json::to_string(&CustomType::index_mapping()).unwrap();
+    fn serialise_document_with_source_disabled() {
+        let ser = serde_json::to_string(&NoSource::index_mapping()).unwrap();
 
         let expected = json_str!({
+            "_source": {
+                "enabled": false
+            },
             "properties": {
                 "field": {
                     "type": "integer"
-                },
-                "renamed_field": {
-                    "type": "integer"
                 }
             }
         });
@@ -645,48 +1093,732 @@ mod tests {
     }
 
     #[test]
-    fn serialise_mapping_with_wrapped_types() {
-        let ser = serde_json::to_string(&Wrapped::index_mapping()).unwrap();
+    fn get_source_enabled() {
+        assert!(SimpleType::source_enabled());
+        assert!(!NoSource::source_enabled());
+    }
+
+    #[test]
+    fn serialise_document_with_meta() {
+        let ser = serde_json::to_string(&MetaType::index_mapping()).unwrap();
 
         let expected = json_str!({
+            "_meta": {
+                "owner": "search",
+                "version": "2",
+                "fields": {
+                    "old_field": {
+                        "deprecated": true,
+                        "replaced_by": "field"
+                    },
+                    "took": {
+                        "unit": "ms"
+                    }
+                }
+            },
             "properties": {
-                "field1": {
+                "old_field": {
                     "type": "integer"
                 },
-                "field2": {
-                    "type": "boolean"
-                },
-                "field3": {
-                    "type": "text",
-                    "fields": {
-                        "keyword":{
-                            "type": "keyword",
-                            "ignore_above": 256
-                        }
-                    }
-                },
-                "field4": {
-                    "type": "nested"
+                "took": {
+                    "type": "integer"
                 },
-                "field5": {
-                    "type": "nested",
-                    "properties": {
-                        "field": {
-                            "type": "integer"
-                        }
+                "field": {
+                    "type": "integer"
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_document_with_runtime_fields() {
+        let ser = serde_json::to_string(&RuntimeType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "runtime": {
+                "double_field": {
+                    "type": "long",
+                    "script": {
+                        "source": "emit(doc['field'].value * 2)"
                     }
                 },
-                "field6": {
-                    "type": "nested"
-                },
-                "field7": {
-                    "type": "text",
-                    "fields": {
-                        "keyword":{
-                            "type": "keyword",
-                            "ignore_above": 256
-                        }
-                    }
+                "field": {
+                    "type": "keyword"
+                }
+            },
+            "properties": {
+                "field": {
+                    "type": "integer"
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn get_field_meta() {
+        let deprecated: Vec<_> = MetaType::field_meta()
+            .iter()
+            .filter(|field| field.deprecated)
+            .map(|field| field.name)
+            .collect();
+
+        assert_eq!(vec!["old_field"], deprecated);
+        assert!(SimpleType::field_meta().is_empty());
+    }
+
+    #[test]
+    fn get_field_refs() {
+        assert_eq!("field1", SimpleType::fields().field1.name());
+        assert_eq!("field2.field", SimpleType::fields().field2.fields().field.name());
+        assert_eq!("renamed_field", CustomType::fields().field2.name());
+        assert_eq!("field5.field", Wrapped::fields().field5.fields().field.name());
+        assert_eq!("field7.keyword", Wrapped::fields().field7.keyword().name());
+        assert_eq!("field3.raw", Wrapped::fields().field3.subfield::<String>("raw").name());
+
+        let field: String = SimpleType::fields().field1.into();
+        assert_eq!("field1", field);
+    }
+
+    #[test]
+    fn serialise_document_for_custom_mapping() {
+        let ser = serde_json::to_string(&CustomType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "field": {
+                    "type": "integer"
+                },
+                "renamed_field": {
+                    "type": "integer"
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_with_renamed_fields() {
+        let ser = serde_json::to_string(&RenamedType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "firstField": {
+                    "type": "integer"
+                },
+                "serde_field": {
+                    "type": "integer"
+                },
+                "elastic_field": {
+                    "type": "integer"
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+        assert_eq!("firstField", RenamedType::fields().first_field.name());
+        assert_eq!("elastic_field", RenamedType::fields().third_field.name());
+    }
+
+    #[test]
+    fn serialise_mapping_with_skipped_fields() {
+        let ser = serde_json::to_string(&SkippedType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "title": {
+                    "type": "text",
+                    "fields": {
+                        "keyword": {
+                            "type": "keyword",
+                            "ignore_above": 256
+                        }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn get_skipped_field_id() {
+        let doc = SkippedType {
+            id: "1".to_owned(),
+            elapsed: Default::default(),
+            title: "title".to_owned(),
+        };
+
+        assert_eq!("1", doc.partial_id().unwrap().as_ref());
+    }
+
+    #[test]
+    fn serialise_mapping_for_generic_types() {
+        let generic =
+            serde_json::to_string(&GenericType::<SimpleNestedType>::index_mapping()).unwrap();
+        let bound = serde_json::to_string(
+            &BoundGenericType::<Keyword<DefaultKeywordMapping>>::index_mapping(),
+        )
+        .unwrap();
+        let borrowed = serde_json::to_string(&BorrowedType::index_mapping()).unwrap();
+
+        let expected_generic = json_str!({
+            "properties": {
+                "id": {
+                    "type": "text",
+                    "fields": {
+                        "keyword": {
+                            "type": "keyword",
+                            "ignore_above": 256
+                        }
+                    }
+                },
+                "payload": {
+                    "type": "object",
+                    "properties": {
+                        "field": {
+                            "type": "integer"
+                        }
+                    }
+                },
+                "history": {
+                    "type": "object",
+                    "properties": {
+                        "field": {
+                            "type": "integer"
+                        }
+                    }
+                }
+            }
+        });
+
+        let expected_bound = json_str!({
+            "properties": {
+                "key": {
+                    "type": "keyword"
+                }
+            }
+        });
+
+        let expected_borrowed = json_str!({
+            "properties": {
+                "title": {
+                    "type": "text",
+                    "fields": {
+                        "keyword": {
+                            "type": "keyword",
+                            "ignore_above": 256
+                        }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(expected_generic, generic);
+        assert_eq!(expected_bound, bound);
+        assert_eq!(expected_borrowed, borrowed);
+        assert_eq!(
+            "payload.field",
+            GenericType::<SimpleNestedType>::fields()
+                .payload
+                .fields()
+                .field
+                .name()
+        );
+    }
+
+    #[test]
+    fn get_generic_type_metadata() {
+        let doc = GenericType {
+            id: "1".to_owned(),
+            payload: SimpleNestedType { field: 1 },
+            history: vec![],
+        };
+
+        assert_eq!(
+            "generictype",
+            GenericType::<SimpleNestedType>::static_index()
+        );
+        assert_eq!("1", doc.partial_id().unwrap().as_ref());
+    }
+
+    #[test]
+    fn serialise_mapping_with_wrapped_types() {
+        let ser = serde_json::to_string(&Wrapped::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "field1": {
+                    "type": "integer"
+                },
+                "field2": {
+                    "type": "boolean"
+                },
+                "field3": {
+                    "type": "text",
+                    "fields": {
+                        "keyword":{
+                            "type": "keyword",
+                            "ignore_above": 256
+                        }
+                    }
+                },
+                "field4": {
+                    "type": "object"
+                },
+                "field5": {
+                    "type": "object",
+                    "properties": {
+                        "field": {
+                            "type": "integer"
+                        }
+                    }
+                },
+                "field6": {
+                    "type": "object"
+                },
+                "field7": {
+                    "type": "text",
+                    "fields": {
+                        "keyword":{
+                            "type": "keyword",
+                            "ignore_above": 256
+                        }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_with_nested_and_object_types() {
+        let ser = serde_json::to_string(&Embedded::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "nested": {
+                    "type": "nested",
+                    "properties": {
+                        "field": {
+                            "type": "integer"
+                        }
+                    }
+                },
+                "object": {
+                    "type": "object",
+                    "properties": {
+                        "field": {
+                            "type": "integer"
+                        }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_with_overridden_nested_and_object_fields() {
+        let ser = serde_json::to_string(&EmbeddedOverride::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "nested": {
+                    "type": "object",
+                    "properties": {
+                        "field": {
+                            "type": "integer"
+                        }
+                    }
+                },
+                "object": {
+                    "type": "nested",
+                    "properties": {
+                        "field": {
+                            "type": "integer"
+                        }
+                    }
+                },
+                "disabled": {
+                    "type": "nested",
+                    "dynamic": false,
+                    "properties": {
+                        "field": {
+                            "type": "integer"
+                        }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_with_dynamic_and_enabled() {
+        let ser = serde_json::to_string(&StrictType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "dynamic": "strict",
+            "properties": {
+                "field": {
+                    "type": "object",
+                    "dynamic": false,
+                    "enabled": false,
+                    "properties": {
+                        "field": {
+                            "type": "integer"
+                        }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_with_multi_fields() {
+        let ser = serde_json::to_string(&MultiFieldType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "title": {
+                    "type": "text",
+                    "fields": {
+                        "english": {
+                            "type": "text",
+                            "analyzer": "english"
+                        },
+                        "keyword": {
+                            "type": "keyword",
+                            "ignore_above": 256
+                        }
+                    }
+                },
+                "tag": {
+                    "type": "keyword",
+                    "fields": {
+                        "length": {
+                            "type": "token_count",
+                            "analyzer": "standard"
+                        }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_with_copy_to() {
+        let ser = serde_json::to_string(&CopyToType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "first_name": {
+                    "type": "keyword",
+                    "copy_to": ["name"]
+                },
+                "last_name": {
+                    "type": "text",
+                    "copy_to": ["name", "all"],
+                    "fields": {
+                        "keyword": {
+                            "type": "keyword"
+                        }
+                    }
+                },
+                "name": {
+                    "type": "text"
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_with_malformed_params() {
+        let ser = serde_json::to_string(&MalformedType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "count": {
+                    "type": "integer",
+                    "ignore_malformed": true
+                },
+                "rating": {
+                    "type": "float",
+                    "coerce": false,
+                    "ignore_malformed": false
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_with_storage_params() {
+        let ser = serde_json::to_string(&StorageType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "session_id": {
+                    "type": "keyword",
+                    "doc_values": false
+                },
+                "payload": {
+                    "type": "long",
+                    "index": false,
+                    "store": true
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_with_field_params() {
+        let ser = serde_json::to_string(&ParamsType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "title": {
+                    "type": "text",
+                    "analyzer": "english",
+                    "boost": 2.0,
+                    "search_analyzer": "standard"
+                },
+                "tag": {
+                    "type": "keyword",
+                    "boost": 1.1,
+                    "ignore_above": 512
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn get_mapping_json() {
+        let ser = serde_json::to_string(&CompactJsonType::index_mapping()).unwrap();
+
+        assert_eq!(ser, CompactJsonType::mapping_json().unwrap());
+    }
+
+    #[test]
+    fn get_pretty_mapping_json() {
+        let ser = serde_json::to_string_pretty(&PrettyJsonType::index_mapping()).unwrap();
+
+        assert_eq!(ser, PrettyJsonType::mapping_json().unwrap());
+        assert!(ser.contains('\n'));
+    }
+
+    #[test]
+    fn get_generic_mapping_json() {
+        let ser =
+            serde_json::to_string(&GenericJsonType::<SimpleNestedType>::index_mapping()).unwrap();
+
+        assert_eq!(
+            ser,
+            GenericJsonType::<SimpleNestedType>::mapping_json().unwrap()
+        );
+    }
+
+    #[test]
+    fn serialise_mapping_with_newtype_fields() {
+        let ser = serde_json::to_string(&NewtypeFieldsType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "user_id": {
+                    "type": "text",
+                    "fields": {
+                        "keyword": {
+                            "type": "keyword",
+                            "ignore_above": 256
+                        }
+                    }
+                },
+                "tag": {
+                    "type": "keyword"
+                },
+                "tags": {
+                    "type": "keyword"
+                },
+                "count": {
+                    "type": "long"
+                },
+                "wrapped": {
+                    "type": "date",
+                    "format": "epoch_millis"
+                },
+                "nested": {
+                    "type": "object",
+                    "properties": {
+                        "field": {
+                            "type": "integer"
+                        }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_partial_document() {
+        let partial = PartialTypePartial::new()
+            .first_name("Jane".to_owned())
+            .count(1);
+
+        let ser = serde_json::to_string(&partial).unwrap();
+
+        let expected = json_str!({
+            "firstName": "Jane",
+            "count": 1
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_partial_document_with_none_field() {
+        let partial = PartialTypePartial::new().timestamp(None);
+
+        let ser = serde_json::to_string(&partial).unwrap();
+
+        assert_eq!(json_str!({ "timestamp": null }), ser);
+    }
+
+    #[test]
+    fn serialise_empty_partial_document() {
+        let ser = serde_json::to_string(&PartialTypePartial::default()).unwrap();
+
+        assert_eq!("{}", ser);
+    }
+
+    #[test]
+    fn serialise_partial_document_with_serde_field_attributes() {
+        let partial = SerializeWithPartialTypePartial::new()
+            .title("a title".to_owned())
+            .tags(vec![]);
+
+        let ser = serde_json::to_string(&partial).unwrap();
+
+        assert_eq!(json_str!({ "title": "A TITLE" }), ser);
+    }
+
+    #[test]
+    fn serialise_generic_partial_document() {
+        let partial = GenericPartialTypePartial::new().payload(SimpleNestedType { field: 1 });
+
+        let ser = serde_json::to_string(&partial).unwrap();
+
+        assert_eq!(json_str!({ "payload": { "field": 1 } }), ser);
+    }
+
+    #[test]
+    fn get_partial_document_type() {
+        fn partial_for<TPartial, TDocument>(_: TPartial)
+        where
+            TPartial: PartialDocument<Document = TDocument>,
+        {
+        }
+
+        partial_for::<_, PartialType>(PartialTypePartial::new());
+        partial_for::<_, GenericPartialType<SimpleNestedType>>(GenericPartialTypePartial::new());
+    }
+
+    #[test]
+    fn serialise_mapping_with_null_values() {
+        let ser = serde_json::to_string(&NullValueType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "count": {
+                    "type": "integer",
+                    "null_value": 0
+                },
+                "ratio": {
+                    "type": "float",
+                    "null_value": 1.5
+                },
+                "active": {
+                    "type": "boolean",
+                    "null_value": false
+                },
+                "code": {
+                    "type": "keyword",
+                    "null_value": "NONE"
+                },
+                "host": {
+                    "type": "ip",
+                    "null_value": "127.0.0.1"
+                },
+                "published": {
+                    "type": "date",
+                    "format": "epoch_millis",
+                    "null_value": "0"
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_with_invalid_null_value() {
+        let err = serde_json::to_string(&InvalidNullValueType::index_mapping()).unwrap_err();
+
+        assert!(err
+            .to_string()
+            .starts_with("invalid null_value for a date field"));
+    }
+
+    #[test]
+    fn serialise_mapping_with_normalizer() {
+        let ser = serde_json::to_string(&NormalizerType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "code": {
+                    "type": "keyword",
+                    "normalizer": "lowercase"
+                },
+                "email": {
+                    "type": "keyword",
+                    "copy_to": ["code"],
+                    "normalizer": "ascii_folding"
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_with_date_nanos() {
+        let ser = serde_json::to_string(&EventType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "timestamp": {
+                    "type": "date_nanos",
+                    "format": "strict_date_optional_time_nanos"
                 }
             }
         });
@@ -707,7 +1839,7 @@ mod tests {
                             "format": "epoch_millis"
                         },
                         "field2": {
-                            "type": "nested",
+                            "type": "object",
                             "properties": {
                                 "field": {
                                     "type": "integer"
@@ -0,0 +1,391 @@
+/*!
+A structured model of a document mapping.
+
+The mapping for a document type is usually only needed as JSON to send to Elasticsearch.
+Tools that generate other schemas from a document type, like OpenAPI schemas or Kibana index patterns, can walk a `FieldMapping` tree instead of picking apart the JSON.
+*/
+
+use std::fmt;
+
+use serde::{
+    de::{
+        DeserializeSeed,
+        MapAccess,
+        Visitor,
+    },
+    Deserialize,
+    Deserializer,
+    Serialize,
+};
+use serde_json::{
+    self,
+    Map,
+    Value,
+};
+
+use super::mapping::OBJECT_DATATYPE;
+
+/**
+A field in a document mapping, along with its parameters and any inner fields.
+
+The `FieldMapping` for a document type is returned by [`DocumentType::mapping_tree`][mapping_tree].
+The root field has an empty name and an `object` datatype.
+Its parameters are the settings for the whole mapping, like `dynamic` or `_source`.
+
+Properties and multi-fields are kept in the order they're declared in the mapping.
+
+# Examples
+
+Walk the fields of a derived document type:
+
+```
+# #[macro_use] extern crate serde_derive;
+# #[macro_use] extern crate elastic_types_derive;
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+# fn main() {
+#[derive(Serialize, ElasticType)]
+struct MyType {
+    title: String,
+    published: Date<DefaultDateMapping>,
+}
+
+let mapping = MyType::mapping_tree();
+
+let fields: Vec<_> = mapping
+    .paths()
+    .into_iter()
+    .map(|(path, field)| (path, field.data_type().to_owned()))
+    .collect();
+
+assert_eq!(
+    vec![
+        ("title".to_owned(), "text".to_owned()),
+        ("title.keyword".to_owned(), "keyword".to_owned()),
+        ("published".to_owned(), "date".to_owned()),
+    ],
+    fields
+);
+# }
+```
+
+A `FieldMapping` can also be deserialised from the JSON for a mapping, like one returned by Elasticsearch.
+
+[mapping_tree]: trait.DocumentType.html#method.mapping_tree
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldMapping {
+    name: String,
+    data_type: String,
+    params: Map<String, Value>,
+    properties: Vec<FieldMapping>,
+    fields: Vec<FieldMapping>,
+}
+
+impl FieldMapping {
+    pub(crate) fn from_mapping<TMapping>(mapping: &TMapping) -> Result<Self, serde_json::Error>
+    where
+        TMapping: Serialize,
+    {
+        // Round-trip through a string so properties keep their declared order
+        let mapping = try!(serde_json::to_string(mapping));
+
+        serde_json::from_str(&mapping)
+    }
+
+    /** The name of the field, relative to its parent. */
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /** The datatype of the field, like `keyword` or `date`. */
+    pub fn data_type(&self) -> &str {
+        &self.data_type
+    }
+
+    /** Get the value of a mapping parameter, like `format` or `analyzer`. */
+    pub fn param(&self, key: &str) -> Option<&Value> {
+        self.params.get(key)
+    }
+
+    /**
+    The mapping parameters for the field.
+
+    Parameters don't include the `type`, `properties` or `fields`.
+    */
+    pub fn params(&self) -> &Map<String, Value> {
+        &self.params
+    }
+
+    /**
+    The custom metadata stored in the `_meta` field of the mapping.
+
+    Only the root field of a document mapping has metadata.
+    */
+    pub fn meta(&self) -> Option<&Map<String, Value>> {
+        self.params.get("_meta").and_then(Value::as_object)
+    }
+
+    /** The inner fields of an `object` or `nested` field. */
+    pub fn properties(&self) -> &[FieldMapping] {
+        &self.properties
+    }
+
+    /** The multi-fields that index this field in other ways. */
+    pub fn fields(&self) -> &[FieldMapping] {
+        &self.fields
+    }
+
+    /**
+    Find an inner field by its path, like `address.city` or `title.keyword`.
+
+    The path is relative to this field, and may step through properties and multi-fields.
+    */
+    pub fn get(&self, path: &str) -> Option<&FieldMapping> {
+        path.split('.').fold(Some(self), |field, name| {
+            field.and_then(|field| {
+                field
+                    .properties
+                    .iter()
+                    .chain(field.fields.iter())
+                    .find(|inner| inner.name == name)
+            })
+        })
+    }
+
+    /**
+    Get every inner field along with its full path, depth first.
+
+    Objects are listed before their properties, and fields are listed before their multi-fields.
+    */
+    pub fn paths(&self) -> Vec<(String, &FieldMapping)> {
+        let mut paths = Vec::new();
+        self.collect_paths(None, &mut paths);
+
+        paths
+    }
+
+    fn collect_paths<'a>(
+        &'a self,
+        parent: Option<&str>,
+        paths: &mut Vec<(String, &'a FieldMapping)>,
+    ) {
+        for inner in self.properties.iter().chain(self.fields.iter()) {
+            let path = match parent {
+                Some(parent) => format!("{}.{}", parent, inner.name),
+                None => inner.name.clone(),
+            };
+
+            paths.push((path.clone(), inner));
+            inner.collect_paths(Some(&path), paths);
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for FieldMapping {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(FieldMappingVisitor {
+            name: String::new(),
+        })
+    }
+}
+
+struct FieldMappingVisitor {
+    name: String,
+}
+
+impl<'de> Visitor<'de> for FieldMappingVisitor {
+    type Value = FieldMapping;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a field mapping")
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut data_type = None;
+        let mut params = Map::new();
+        let mut properties = Vec::new();
+        let mut fields = Vec::new();
+
+        while let Some(key) = try!(access.next_key::<String>()) {
+            match key.as_ref() {
+                "type" => data_type = Some(try!(access.next_value::<String>())),
+                "properties" => properties = try!(access.next_value::<NamedFieldMappings>()).0,
+                "fields" => fields = try!(access.next_value::<NamedFieldMappings>()).0,
+                _ => {
+                    let value = try!(access.next_value());
+                    params.insert(key, value);
+                }
+            }
+        }
+
+        Ok(FieldMapping {
+            name: self.name,
+            data_type: data_type.unwrap_or_else(|| OBJECT_DATATYPE.to_owned()),
+            params: params,
+            properties: properties,
+            fields: fields,
+        })
+    }
+}
+
+struct NamedFieldMappings(Vec<FieldMapping>);
+
+impl<'de> Deserialize<'de> for NamedFieldMappings {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct NamedFieldMappingsVisitor;
+
+        impl<'de> Visitor<'de> for NamedFieldMappingsVisitor {
+            type Value = NamedFieldMappings;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a map of field mappings")
+            }
+
+            fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut mappings = Vec::new();
+
+                while let Some(name) = try!(access.next_key::<String>()) {
+                    let mapping = try!(access.next_value_seed(FieldMappingSeed { name: name }));
+                    mappings.push(mapping);
+                }
+
+                Ok(NamedFieldMappings(mappings))
+            }
+        }
+
+        deserializer.deserialize_map(NamedFieldMappingsVisitor)
+    }
+}
+
+struct FieldMappingSeed {
+    name: String,
+}
+
+impl<'de> DeserializeSeed<'de> for FieldMappingSeed {
+    type Value = FieldMapping;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(FieldMappingVisitor { name: self.name })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use prelude::*;
+
+    #[derive(Serialize, ElasticType)]
+    #[elastic(dynamic = "strict", meta(schema_version = "3"))]
+    pub struct IntrospectType {
+        pub title: String,
+        pub count: i32,
+        pub inner: IntrospectInner,
+    }
+
+    #[derive(Serialize, ElasticType)]
+    pub struct IntrospectInner {
+        pub published: Date<DefaultDateMapping<EpochMillis>>,
+    }
+
+    #[test]
+    fn walk_document_mapping_tree() {
+        let mapping = IntrospectType::mapping_tree();
+
+        assert_eq!("", mapping.name());
+        assert_eq!("object", mapping.data_type());
+        assert_eq!(Some(&json!("strict")), mapping.param("dynamic"));
+
+        let paths: Vec<_> = mapping
+            .paths()
+            .into_iter()
+            .map(|(path, field)| (path, field.data_type()))
+            .collect();
+
+        let expected = vec![
+            ("title".to_owned(), "text"),
+            ("title.keyword".to_owned(), "keyword"),
+            ("count".to_owned(), "integer"),
+            ("inner".to_owned(), "object"),
+            ("inner.published".to_owned(), "date"),
+        ];
+
+        assert_eq!(expected, paths);
+    }
+
+    #[test]
+    fn get_field_by_path() {
+        let mapping = IntrospectType::mapping_tree();
+
+        let published = mapping.get("inner.published").unwrap();
+        assert_eq!("published", published.name());
+        assert_eq!(Some(&json!("epoch_millis")), published.param("format"));
+
+        let keyword = mapping.get("title.keyword").unwrap();
+        assert_eq!(Some(&json!(256)), keyword.param("ignore_above"));
+
+        assert!(mapping.get("title.missing").is_none());
+        assert!(mapping.get("missing").is_none());
+    }
+
+    #[test]
+    fn get_mapping_meta() {
+        let mapping = IntrospectType::mapping_tree();
+
+        assert_eq!(
+            Some(&json!("3")),
+            mapping.meta().and_then(|meta| meta.get("schema_version"))
+        );
+        assert!(mapping.get("title").unwrap().meta().is_none());
+    }
+
+    #[test]
+    fn deserialise_field_mapping() {
+        let mapping: FieldMapping = serde_json::from_str(&json_str!({
+            "properties": {
+                "b": {
+                    "type": "keyword",
+                    "normalizer": "lowercase"
+                },
+                "a": {
+                    "type": "object",
+                    "enabled": false
+                }
+            }
+        }))
+        .unwrap();
+
+        let names: Vec<_> = mapping
+            .properties()
+            .iter()
+            .map(|field| field.name())
+            .collect();
+
+        assert_eq!(vec!["b", "a"], names);
+        assert_eq!(
+            Some(&json!("lowercase")),
+            mapping.properties()[0].param("normalizer")
+        );
+        assert_eq!(
+            Some(&json!(false)),
+            mapping.properties()[1].param("enabled")
+        );
+        assert!(mapping.properties()[1].params().get("type").is_none());
+    }
+}
@@ -1,12 +1,15 @@
 /*! Mapping for Elasticsearch document types. */
 
 use serde::{
-    ser::SerializeStruct,
+    ser::{
+        SerializeMap,
+        SerializeStruct,
+    },
     Serialize,
     Serializer,
 };
 
-/** A field that will be mapped as a nested document. */
+/** A field that will be mapped as an embedded `object` or `nested` document. */
 pub trait ObjectFieldType {
     type Mapping: ObjectMapping;
 }
@@ -25,9 +28,13 @@ pub trait ObjectMapping {
     /** The source of properties for this document. */
     type Properties: PropertiesMapping;
 
-    /** Get the type name for this mapping, like `object` or `nested`. */
+    /**
+    Get the type name for this mapping, like `object` or `nested`.
+
+    Embedded documents are mapped as `object` by default.
+    */
     fn data_type() -> &'static str {
-        NESTED_DATATYPE
+        OBJECT_DATATYPE
     }
 
     /**
@@ -53,6 +60,38 @@ pub trait ObjectMapping {
     fn include_in_all() -> Option<bool> {
         None
     }
+
+    /**
+    Whether the original JSON body of a document should be stored in the `_source` field
+    (`true`, default) or discarded (`false`).
+
+    This only applies to the root object of a document type.
+    Documents without a `_source` can still be searched, but their values can only be retrieved
+    from stored fields or doc values.
+    */
+    fn source_enabled() -> Option<bool> {
+        None
+    }
+
+    /**
+    Custom metadata stored in the `_meta` field of the mapping.
+
+    This only applies to the root object of a document type.
+    Elasticsearch doesn't use the metadata itself, but it's returned along with the rest of the mapping.
+    */
+    fn meta() -> Option<&'static [(&'static str, &'static str)]> {
+        None
+    }
+
+    /**
+    Fields that are computed when they're queried, instead of being indexed.
+
+    This only applies to the root object of a document type.
+    Runtime fields are declared in the `runtime` section of the mapping, so values can be read from the `_source` without reindexing.
+    */
+    fn runtime() -> Option<&'static [RuntimeFieldMapping]> {
+        None
+    }
 }
 
 /**
@@ -76,6 +115,92 @@ pub trait PropertiesMapping {
     fn serialize_props<S>(state: &mut S) -> Result<(), S::Error>
     where
         S: SerializeStruct;
+
+    /**
+    Metadata for the mapped property fields on this type.
+
+    Only fields that are deprecated or have custom metadata are included.
+    */
+    fn props_meta() -> &'static [FieldMeta] {
+        &[]
+    }
+}
+
+/**
+Metadata for a mapped property field.
+
+Field metadata is stored in the `_meta` field of the root document mapping, under a `fields` object keyed by the field name.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldMeta {
+    /** The name of the field. */
+    pub name: &'static str,
+    /** Whether or not the field is deprecated. */
+    pub deprecated: bool,
+    /** Custom metadata for the field. */
+    pub meta: &'static [(&'static str, &'static str)],
+}
+
+impl Serialize for FieldMeta {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let len = self.meta.len() + if self.deprecated { 1 } else { 0 };
+
+        let mut state = try!(serializer.serialize_map(Some(len)));
+
+        if self.deprecated {
+            try!(state.serialize_entry("deprecated", &true));
+        }
+
+        for &(key, value) in self.meta {
+            try!(state.serialize_entry(key, value));
+        }
+
+        state.end()
+    }
+}
+
+/**
+The mapping for a [runtime field](https://www.elastic.co/guide/en/elasticsearch/reference/current/runtime.html).
+
+Runtime fields are declared in the `runtime` section of a document mapping.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RuntimeFieldMapping {
+    /** The name of the field. */
+    pub name: &'static str,
+    /** The type of value the field contains, like `keyword` or `long`. */
+    pub ty: &'static str,
+    /**
+    The painless script that emits values for the field.
+
+    If there's no script then the value is read from the field with the same name in the `_source`.
+    */
+    pub script: Option<&'static str>,
+}
+
+impl Serialize for RuntimeFieldMapping {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        struct Script {
+            source: &'static str,
+        }
+
+        let len = 1 + if self.script.is_some() { 1 } else { 0 };
+
+        let mut state = try!(serializer.serialize_struct("mapping", len));
+
+        try!(state.serialize_field("type", self.ty));
+
+        ser_field!(state, "script", self.script.map(|source| Script { source }));
+
+        state.end()
+    }
 }
 
 /**
@@ -107,9 +232,11 @@ impl Serialize for Dynamic {
 
 mod private {
     use super::{
+        FieldMeta,
         ObjectFieldType,
         ObjectMapping,
         PropertiesMapping,
+        RuntimeFieldMapping,
         OBJECT_DATATYPE,
     };
     use document::{
@@ -123,7 +250,10 @@ mod private {
         StaticSerialize,
     };
     use serde::{
-        ser::SerializeStruct,
+        ser::{
+            SerializeMap,
+            SerializeStruct,
+        },
         Serialize,
         Serializer,
     };
@@ -150,6 +280,88 @@ mod private {
         }
     }
 
+    #[derive(Serialize)]
+    struct SourceMapping {
+        enabled: bool,
+    }
+
+    struct MetaMapping<TMapping>
+    where
+        TMapping: ObjectMapping,
+    {
+        _m: PhantomData<TMapping>,
+    }
+
+    impl<TMapping> MetaMapping<TMapping>
+    where
+        TMapping: ObjectMapping,
+    {
+        fn is_empty() -> bool {
+            TMapping::meta().is_none() && TMapping::Properties::props_meta().is_empty()
+        }
+    }
+
+    impl<TMapping> Serialize for MetaMapping<TMapping>
+    where
+        TMapping: ObjectMapping,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            struct Fields(&'static [FieldMeta]);
+
+            impl Serialize for Fields {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    let mut state = try!(serializer.serialize_map(Some(self.0.len())));
+
+                    for field in self.0 {
+                        try!(state.serialize_entry(field.name, field));
+                    }
+
+                    state.end()
+                }
+            }
+
+            let meta = TMapping::meta().unwrap_or(&[]);
+            let fields = TMapping::Properties::props_meta();
+
+            let len = meta.len() + if fields.is_empty() { 0 } else { 1 };
+
+            let mut state = try!(serializer.serialize_map(Some(len)));
+
+            for &(key, value) in meta {
+                try!(state.serialize_entry(key, value));
+            }
+
+            if !fields.is_empty() {
+                try!(state.serialize_entry("fields", &Fields(fields)));
+            }
+
+            state.end()
+        }
+    }
+
+    struct RuntimeMapping(&'static [RuntimeFieldMapping]);
+
+    impl Serialize for RuntimeMapping {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut state = try!(serializer.serialize_map(Some(self.0.len())));
+
+            for field in self.0 {
+                try!(state.serialize_entry(field.name, field));
+            }
+
+            state.end()
+        }
+    }
+
     struct Properties<TMapping>
     where
         TMapping: ObjectMapping,
@@ -231,7 +443,30 @@ mod private {
         where
             S: Serializer,
         {
-            let mut state = try!(serializer.serialize_struct("mapping", 1));
+            let dynamic = TMapping::dynamic();
+            let source_enabled = TMapping::source_enabled();
+            let has_meta = !MetaMapping::<TMapping>::is_empty();
+            let runtime = TMapping::runtime();
+
+            let props_len = 1
+                + if dynamic.is_some() { 1 } else { 0 }
+                + if source_enabled.is_some() { 1 } else { 0 }
+                + if has_meta { 1 } else { 0 }
+                + if runtime.is_some() { 1 } else { 0 };
+
+            let mut state = try!(serializer.serialize_struct("mapping", props_len));
+
+            ser_field!(state, "dynamic", dynamic);
+
+            if has_meta {
+                try!(state.serialize_field("_meta", &MetaMapping::<TMapping> { _m: PhantomData }));
+            }
+
+            if let Some(enabled) = source_enabled {
+                try!(state.serialize_field("_source", &SourceMapping { enabled }));
+            }
+
+            ser_field!(state, "runtime", runtime.map(RuntimeMapping));
 
             try!(state.serialize_field("properties", &Properties::<TMapping> { _m: PhantomData }));
 
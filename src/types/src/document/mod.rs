@@ -60,7 +60,7 @@ This will produce the following field mapping:
 # let mapping = elastic_types::derive::standalone_field_ser(MyTypeMapping).unwrap();
 # let json = json_str!(
 {
-    "type": "nested",
+    "type": "object",
     "properties": {
         "my_date": {
             "type": "date",
@@ -145,6 +145,34 @@ impl MyType {
 # }
 ```
 
+Simple index names can be formatted from document fields using placeholders in the `#[elastic(index)]` attribute instead.
+A `{field}` placeholder is replaced with a field that implements `Display`, and a `{field:format}` placeholder is replaced with a date field in the given [`chrono` format][chrono-format], like daily indices for log events:
+
+```
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+#[elastic(index = "logs-{service}-{timestamp:%Y.%m.%d}")]
+pub struct LogEvent {
+    pub service: String,
+    pub timestamp: Date<DefaultDateMapping>,
+    pub message: String
+}
+# fn main() {
+# }
+```
+
+Empty placeholders and date formats `chrono` can't render are compile errors.
+
+Index names with placeholders or expressions are calculated for each document, including each document in a bulk request.
+These documents don't have a static index, so requests that need one, like getting a document by its id, need to be given an index.
+
+[chrono-format]: https://docs.rs/chrono/0.4/chrono/format/strftime/index.html
+
 ### Specifying a type name
 
 Documents will default to using `_doc` as the type name.
@@ -200,7 +228,10 @@ pub struct MyType {
 # }
 ```
 
-The field annotated with `#[elastic(id)]` must satisfy `impl Into<Cow<'_, str>>`.
+The field annotated with `#[elastic(id)]` must implement [`DocumentId`](trait.DocumentId.html).
+It's implemented for strings, integers and `Option`s of them, so a document with an id of `None` is indexed without one and Elasticsearch will generate an id for it.
+The client uses this id when the document is indexed, so it doesn't need to be given separately.
+
 An id can also be calculated based on an expression function using the `#[elastic(id(expr = "expression"))]` attribute:
 
 ```
@@ -257,6 +288,327 @@ pub struct MyType {
 # }
 ```
 
+### Specifying a routing field
+
+Documents are routed to a shard using their id by default.
+The `#[elastic(routing)]` attribute can be used to specify a field to route documents by instead, like the tenant in a multi-tenant index:
+
+```
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+pub struct MyType {
+    #[elastic(id)]
+    pub my_id: String,
+    #[elastic(routing)]
+    pub tenant: Keyword<DefaultKeywordMapping>,
+    pub my_num: i32
+}
+# fn main() {
+# }
+```
+
+Routing fields follow the same rules as id fields, so they can also use an expression with `#[elastic(routing(expr = "expression"))]`.
+The client sends the routing value when a document is indexed, or when it's indexed, created or updated in a bulk request.
+Requests that only have a document's id, like a get or a delete, need the routing value to be given in their parameters.
+
+### Multi-fields
+
+Multi-fields index the same value in different ways, like a `text` field for full-text search with a `keyword` sub-field for sorting and aggregations.
+The `#[elastic(fields)]` attribute can be used to declare the multi-fields for a field:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# extern crate serde_json;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+pub struct MyType {
+    #[elastic(fields(keyword(ignore_above = 256), english(analyzer = "english")))]
+    pub my_string: String,
+}
+# fn main() {
+# let mapping = serde_json::to_string(&MyType::index_mapping()).unwrap();
+# let json = json_str!(
+{
+    "properties": {
+        "my_string": {
+            "type": "text",
+            "fields": {
+                "english": {
+                    "type": "text",
+                    "analyzer": "english"
+                },
+                "keyword": {
+                    "type": "keyword",
+                    "ignore_above": 256
+                }
+            }
+        }
+    }
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+
+A multi-field named `keyword`, `text`, `token_count` or `completion` is mapped as that type.
+Other multi-fields are mapped as `text`, unless a type is given with `ty`, like `#[elastic(fields(length(ty = "token_count", analyzer = "standard")))]`.
+The parameters for each multi-field are the fields on the matching `StringField` mapping, like `KeywordFieldMapping`.
+Any multi-fields from the mapping of the field's type are replaced.
+
+### Copying Values to Other Fields
+
+The `#[elastic(copy_to)]` attribute copies the values of a field into other fields, so several fields can be searched together as a single catch-all field:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# extern crate serde_json;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+pub struct MyType {
+    #[elastic(copy_to = "full_name")]
+    pub first_name: Keyword<DefaultKeywordMapping>,
+    #[elastic(copy_to("full_name", "everything"))]
+    pub last_name: Keyword<DefaultKeywordMapping>,
+    pub full_name: Text<DefaultTextMapping>,
+}
+# fn main() {
+# let mapping = serde_json::to_string(&MyType::index_mapping()).unwrap();
+# let json = json_str!(
+{
+    "properties": {
+        "first_name": {
+            "type": "keyword",
+            "copy_to": ["full_name"]
+        },
+        "last_name": {
+            "type": "keyword",
+            "copy_to": ["full_name", "everything"]
+        },
+        "full_name": {
+            "type": "text"
+        }
+    }
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+
+The fields that values are copied into don't need to be declared on the document type, in which case they're mapped dynamically like any other new field.
+If you provide your own mapping type, implement `copy_to` on the field's mapping instead, like `KeywordMapping::copy_to`.
+
+### Keyword Normalizers
+
+The `#[elastic(normalizer)]` attribute sets the normalizer for a `keyword` field, so values can be matched exactly but case-insensitively:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# extern crate serde_json;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+pub struct MyType {
+    #[elastic(normalizer = "lowercase")]
+    pub email: Keyword<DefaultKeywordMapping>,
+}
+# fn main() {
+# let mapping = serde_json::to_string(&MyType::index_mapping()).unwrap();
+# let json = json_str!(
+{
+    "properties": {
+        "email": {
+            "type": "keyword",
+            "normalizer": "lowercase"
+        }
+    }
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+
+Normalizers can only be used on `keyword` fields.
+Fields with the `lowercase` normalizer are referenced as `Keyword<LowercaseKeywordMapping>` by `fields()`, so `term` and `terms` queries built from them are lowercased too:
+
+```
+# #[macro_use]
+# extern crate serde_json;
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+# #[derive(Serialize, ElasticType)]
+# pub struct MyType {
+#     #[elastic(normalizer = "lowercase")]
+#     pub email: Keyword<DefaultKeywordMapping>,
+# }
+# fn main() {
+let query = MyType::fields().email.term("Kimchy@Elastic.co");
+
+assert_eq!(json!({ "term": { "email": "kimchy@elastic.co" } }), query);
+# }
+```
+
+Custom normalizers need to be defined in the analysis settings of the index, like with the `Analysis` builder when creating an index with the `elastic` client.
+If you provide your own mapping type, implement `KeywordMapping::normalizer` instead.
+
+### Malformed Values
+
+The `#[elastic(ignore_malformed)]` and `#[elastic(coerce = false)]` attributes control how numeric, date and geo fields handle values that don't match their datatype:
+
+```
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+pub struct MyType {
+    #[elastic(ignore_malformed)]
+    pub count: i32,
+    #[elastic(coerce = false)]
+    pub rating: f32,
+}
+# fn main() {
+# }
+```
+
+With `ignore_malformed`, a document with a bad value is still indexed without that field.
+With `coerce` disabled, strings like `"5"` are rejected instead of being converted.
+
+### Storage Parameters
+
+The `#[elastic(doc_values = false)]`, `#[elastic(index = false)]` and `#[elastic(store)]` attributes control how a field is stored without needing a custom mapping type:
+
+```
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+pub struct MyType {
+    #[elastic(doc_values = false)]
+    pub session_id: Keyword<DefaultKeywordMapping>,
+    #[elastic(index = false, store)]
+    pub payload: i64,
+}
+# fn main() {
+# }
+```
+
+Disabling `doc_values` saves disk space and indexing time for fields that are never sorted or aggregated on, which matters for write-heavy indices.
+Text fields don't support `doc_values`.
+
+### Analysis and Scoring Parameters
+
+The `#[elastic(analyzer)]`, `#[elastic(search_analyzer)]`, `#[elastic(boost)]` and `#[elastic(ignore_above)]` attributes set common mapping parameters on a field:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# extern crate elastic_types;
+# extern crate serde_json;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+pub struct MyType {
+    #[elastic(analyzer = "english", boost = 2.0)]
+    pub title: Text<DefaultTextMapping>,
+    #[elastic(ignore_above = 512)]
+    pub tag: Keyword<DefaultKeywordMapping>,
+}
+# fn main() {
+# let mapping = serde_json::to_string(&MyType::index_mapping()).unwrap();
+# let json = json_str!(
+{
+    "properties": {
+        "title": {
+            "type": "text",
+            "analyzer": "english",
+            "boost": 2.0
+        },
+        "tag": {
+            "type": "keyword",
+            "ignore_above": 512
+        }
+    }
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+
+The values are checked by the compiler, so `boost` needs a float literal and `ignore_above` needs an integer.
+They replace any values declared by the field's mapping type, and parameters that don't apply to the field's datatype are rejected by Elasticsearch when the mapping is put.
+
+### Null Values
+
+The `#[elastic(null_value)]` attribute sets the value to index in place of an explicit `null`.
+The value is checked against the field's type, so a `date` field needs a string in its format and an `ip` field needs an address:
+
+```
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+# use std::net::Ipv4Addr;
+#[derive(Serialize, ElasticType)]
+pub struct MyType {
+    #[elastic(null_value = 0)]
+    pub retries: i32,
+    #[elastic(null_value = "NONE")]
+    pub status: Keyword<DefaultKeywordMapping>,
+    #[elastic(null_value = "0.0.0.0")]
+    pub client: Ipv4Addr,
+    #[elastic(null_value = "0")]
+    pub published: Date<DefaultDateMapping<EpochMillis>>,
+}
+# fn main() {
+# }
+```
+
+Using a `null_value` on a field whose datatype doesn't support one, like `text`, is a compile error.
+A value of the wrong kind is also a compile error, and a string that can't be parsed for a `date` or `ip` field is an error when the mapping is serialised.
+Negative numbers can't be written in attributes, so implement `null_value` on a custom mapping type for them instead.
+
 ### Override Default Mapping Properties
 
 You can override the mapping meta properties for an object by providing your own mapping type with `#[elastic(mapping="{TypeName}")]`:
@@ -348,9 +700,14 @@ This will produce the following field mapping:
 # }
 ```
 
-### Ignore or Rename Fields
+### Nested and Object Fields
 
-You can then serialise type mappings with `#[serde]` attributes:
+Documents that are embedded in other documents are mapped as `object` by default, including collections like `Vec<T>`.
+The full properties mapping for an embedded field is taken from the embedded type.
+
+Use `#[elastic(nested)]` to map an embedded document as `nested` instead.
+Each `nested` document is indexed as a separate hidden document, so the values of its fields are queried together.
+`#[elastic(object)]` can be used to be explicit about the default:
 
 ```
 # #[macro_use]
@@ -362,28 +719,625 @@ You can then serialise type mappings with `#[serde]` attributes:
 # #[macro_use]
 # extern crate elastic_types;
 # extern crate serde;
+# extern crate serde_json;
 # use elastic_types::prelude::*;
-#[derive(ElasticType, Serialize)]
-pub struct MyType {
-    #[serde(rename="my_renamed_date")]
-    pub my_date: Date<DefaultDateMapping>,
-    #[serde(skip_serializing)]
-    pub ignored: String,
-    pub my_num: i32
+#[derive(Serialize, ElasticType)]
+#[elastic(nested)]
+pub struct Comment {
+    pub author: Keyword<DefaultKeywordMapping>,
 }
-# fn main() {
-# }
-```
 
-> NOTE: Fields with a `#[serde(skip_deserializing)]` attribute will still be mapped, because they can
-still be indexed in Elasticsearch.
+#[derive(Serialize, ElasticType)]
+#[elastic(object)]
+pub struct Owner {
+    pub name: Keyword<DefaultKeywordMapping>,
+}
 
-## Limitations
+#[derive(Serialize, ElasticType)]
+pub struct MyType {
+    pub comments: Vec<Comment>,
+    pub owner: Owner,
+}
+# fn main() {
+# let mapping = serde_json::to_string(&MyType::index_mapping()).unwrap();
+# let json = json_str!(
+{
+    "properties": {
+        "comments": {
+            "type": "nested",
+            "properties": {
+                "author": {
+                    "type": "keyword"
+                }
+            }
+        },
+        "owner": {
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "keyword"
+                }
+            }
+        }
+    }
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+
+If you provide your own mapping type, implement `ObjectMapping::data_type` to choose between `NESTED_DATATYPE` and `OBJECT_DATATYPE`.
+
+The same attributes can be put on an embedded field to override the datatype of that field only:
+
+```
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+# #[derive(Serialize, ElasticType)]
+# pub struct Comment {
+#     pub author: Keyword<DefaultKeywordMapping>,
+# }
+#[derive(Serialize, ElasticType)]
+pub struct MyType {
+    #[elastic(nested)]
+    pub comments: Vec<Comment>,
+}
+# fn main() {
+# }
+```
+
+Putting `#[elastic(nested)]` or `#[elastic(object)]` on a field that isn't an embedded document is a compile error.
+
+### Generic Documents
+
+Generic structs can also derive `ElasticType`.
+Type parameters used by mapped fields are bound by `ObjectFieldType`, so they can be any other derived document:
+
+```
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+pub struct Envelope<TPayload> {
+    #[elastic(id)]
+    pub id: String,
+    pub payload: TPayload,
+}
+
+#[derive(Serialize, ElasticType)]
+pub struct Comment {
+    pub author: Keyword<DefaultKeywordMapping>,
+}
+# fn main() {
+assert_eq!("envelope", Envelope::<Comment>::static_index());
+# }
+```
+
+Use `#[elastic(bound = "...")]` to replace those bounds with your own when a type parameter is some other kind of field.
+Field types need to be `Serialize` as well as implementing their datatype trait:
+
+```
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# extern crate elastic_types;
+# extern crate serde;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+#[elastic(bound = "TKey: KeywordFieldType<DefaultKeywordMapping> + ::serde::Serialize")]
+pub struct Keyed<TKey> {
+    pub key: TKey,
+}
+# fn main() {
+# }
+```
+
+The index and type names are the same for every instantiation of a generic document.
+
+### Newtype Fields
+
+Newtype structs that wrap a field can also derive `ElasticType`.
+A newtype is mapped the same way as the type it wraps, or with the field mapping supplied by an `#[elastic(mapping)]` attribute:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# extern crate serde_json;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+#[elastic(mapping = "DefaultKeywordMapping")]
+pub struct UserId(String);
+
+#[derive(Serialize, ElasticType)]
+pub struct Count(i64);
+
+#[derive(Serialize, ElasticType)]
+pub struct MyType {
+    pub user_id: UserId,
+    pub count: Count
+}
+# fn main() {
+# let mapping = serde_json::to_string(&MyType::index_mapping()).unwrap();
+# let json = json_str!(
+{
+    "properties": {
+        "user_id": {
+            "type": "keyword"
+        },
+        "count": {
+            "type": "long"
+        }
+    }
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+
+The newtype should serialise the same way as the type it wraps, which is what `#[derive(Serialize)]` does for newtype structs.
+
+### Dynamic and Disabled Objects
+
+By default, Elasticsearch adds new fields it finds in a document to the mapping.
+Use `#[elastic(dynamic = false)]` to ignore unmapped fields, or `#[elastic(dynamic = "strict")]` to reject documents that contain them.
+The `dynamic` setting can be used on documents and embedded objects, and is inherited by any objects they contain.
+
+Use `#[elastic(enabled = false)]` to store an embedded object in the `_source` without parsing or indexing its fields.
+Only `object` fields can be disabled, so this attribute also maps the type as an `object`:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# extern crate serde_json;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+#[elastic(enabled = false)]
+pub struct Session {
+    pub data: Keyword<DefaultKeywordMapping>,
+}
+
+#[derive(Serialize, ElasticType)]
+#[elastic(dynamic = "strict")]
+pub struct MyType {
+    pub session: Session,
+}
+# fn main() {
+# let mapping = serde_json::to_string(&MyType::index_mapping()).unwrap();
+# let json = json_str!(
+{
+    "dynamic": "strict",
+    "properties": {
+        "session": {
+            "type": "object",
+            "enabled": false,
+            "properties": {
+                "data": {
+                    "type": "keyword"
+                }
+            }
+        }
+    }
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+
+If you provide your own mapping type, implement `ObjectMapping::dynamic` and `ObjectMapping::enabled` instead.
+
+### Disable the `_source` Field
+
+You can stop Elasticsearch from storing the original JSON body of a document with `#[elastic(source(enabled = false))]`:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# extern crate serde_json;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+#[elastic(source(enabled = false))]
+pub struct MyType {
+    pub my_num: i32
+}
+# fn main() {
+# let mapping = serde_json::to_string(&MyType::index_mapping()).unwrap();
+# let json = json_str!(
+{
+    "_source": {
+        "enabled": false
+    },
+    "properties": {
+        "my_num": {
+            "type": "integer"
+        }
+    }
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+
+Documents without a `_source` can't be returned by get or search requests, so values need to be read from stored fields or doc values instead.
+If you provide your own mapping type, implement `ObjectMapping::source_enabled` to disable the `_source` field.
+
+### Document and Field Metadata
+
+You can store custom metadata in the `_meta` field of a document mapping with `#[elastic(meta(key = "value"))]`.
+Fields can also be given metadata, or be marked as deprecated with `#[elastic(deprecated)]`:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# extern crate serde_json;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+#[elastic(meta(owner = "search"))]
+pub struct MyType {
+    #[elastic(deprecated, meta(replaced_by = "my_num"))]
+    pub my_old_num: i32,
+    pub my_num: i32
+}
+# fn main() {
+# let mapping = serde_json::to_string(&MyType::index_mapping()).unwrap();
+# let json = json_str!(
+{
+    "_meta": {
+        "owner": "search",
+        "fields": {
+            "my_old_num": {
+                "deprecated": true,
+                "replaced_by": "my_num"
+            }
+        }
+    },
+    "properties": {
+        "my_old_num": {
+            "type": "integer"
+        },
+        "my_num": {
+            "type": "integer"
+        }
+    }
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+
+Field metadata is also available through `DocumentType::field_meta`, so tools comparing mappings can find deprecated fields.
+Document metadata can be read back from a [mapping tree](#mapping-introspection), including one deserialised from a live index, with `FieldMapping::meta`.
+If you provide your own mapping type, implement `ObjectMapping::meta` to add document metadata.
+
+### Runtime Fields
+
+You can declare [runtime fields](https://www.elastic.co/guide/en/elasticsearch/reference/current/runtime.html) in the `runtime` section of a document mapping with `#[elastic(runtime(name(ty = "type", script = "source")))]`.
+Runtime fields are computed when they're queried, so they can be added to an index without reindexing:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# extern crate serde_json;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+#[elastic(runtime(
+    day_of_week(ty = "keyword", script = "emit(doc['timestamp'].value.dayOfWeekEnum.toString())"),
+    title(ty = "keyword")
+))]
+pub struct MyType {
+    pub timestamp: Date<DefaultDateMapping>,
+    pub title: String
+}
+# fn main() {
+# let mapping = serde_json::to_string(&MyType::index_mapping()).unwrap();
+# let json = json_str!(
+{
+    "runtime": {
+        "day_of_week": {
+            "type": "keyword",
+            "script": {
+                "source": "emit(doc['timestamp'].value.dayOfWeekEnum.toString())"
+            }
+        },
+        "title": {
+            "type": "keyword"
+        }
+    },
+    "properties": {
+        "timestamp": {
+            "type": "date",
+            "format": "basic_date_time"
+        },
+        "title": {
+            "type": "text",
+            "fields": {
+                "keyword": {
+                    "type": "keyword",
+                    "ignore_above": 256
+                }
+            }
+        }
+    }
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+
+A runtime field without a `script` reads its value from the field with the same name in the `_source`, which shadows the indexed field.
+If you provide your own mapping type, implement `ObjectMapping::runtime` to declare runtime fields.
+
+## Partial Documents
+
+Add an `#[elastic(partial)]` attribute to also generate a `{TypeName}Partial` struct for updating some of the fields of a document.
+Each serialised field on the document is an `Option` on the partial document, and has a builder method with the same name.
+Fields that aren't set are left out when the partial document is serialised:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# extern crate serde_json;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+#[elastic(partial)]
+pub struct MyType {
+    pub my_string: String,
+    pub my_num: i32
+}
+# fn main() {
+let partial = MyTypePartial::new().my_num(1);
+# let ser = serde_json::to_string(&partial).unwrap();
+# let json = json_str!(
+{
+    "my_num": 1
+}
+# );
+# assert_eq!(json, ser);
+# }
+```
+
+The partial document implements `PartialDocument`, so it can only be used to update documents of the type it was derived from.
+Its fields are serialised with the names given by `serde`, and the `serialize_with`, `with` and `skip_serializing_if` field attributes are applied to set fields.
+Fields with `#[serde(flatten)]` aren't supported on partial documents.
+
+## Mapping JSON
+
+Add an `#[elastic(mapping_json)]` attribute to also generate a `mapping_json` function that returns the index mapping serialised as compact json.
+Use `#[elastic(mapping_json = "pretty")]` to get pretty json instead, which is easier to read and diff:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# use elastic_types::prelude::*;
+#[derive(Serialize, ElasticType)]
+#[elastic(mapping_json)]
+pub struct MyType {
+    pub my_num: i32
+}
+# fn main() {
+let mapping = MyType::mapping_json().unwrap();
+# let json = json_str!(
+{
+    "properties": {
+        "my_num": {
+            "type": "integer"
+        }
+    }
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+
+Build scripts and migration tools can use `mapping_json` to write out or compare mappings.
+The mapping is generated as a function instead of a `const MAPPING_JSON: &str`.
+Field mappings are serialised by `serde` implementations that can't run in a constant, and a `const` can't depend on the generic parameters of a document type like `MyType<T>`.
+So the json is serialised when the function is called, which works for generic document types too.
+
+## Mapping Introspection
+
+The mapping for a document type can also be walked as a tree of [`FieldMapping`s](struct.FieldMapping.html) using `DocumentType::mapping_tree`.
+Each field has a name, a datatype, its mapping parameters and any inner properties or multi-fields, so tools can generate other schemas from a document type without parsing its JSON mapping.
+
+## Field References
+
+Deriving `ElasticType` also generates a `{TypeName}Fields` struct with a typed `FieldRef` for each mapped field.
+Field references can be used in place of field names in queries and sorts, so renaming a field is a compile error instead of a silently broken query:
+
+```
+# #[macro_use] extern crate serde_derive;
+# #[macro_use] extern crate elastic_types_derive;
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+# fn main() {
+#[derive(Serialize, ElasticType)]
+pub struct MyType {
+    pub title: String,
+    #[serde(rename = "author_details")]
+    pub author: Author,
+}
+
+#[derive(Serialize, ElasticType)]
+pub struct Author {
+    pub name: String,
+}
+
+let fields = MyType::fields();
+
+assert_eq!("title.keyword", fields.title.keyword().name());
+assert_eq!("author_details.name", fields.author.fields().name.name());
+# }
+```
+
+Field names follow `#[serde(rename)]`, and inner objects are referenced through `fields()`, including `Option` and `Vec` fields.
+
+Field references can also build common filters directly.
+`exists` and `is_null` check whether a field has a value, `between` builds an inclusive `range` query and `any_of` builds a `terms` query:
+
+```
+# #[macro_use] extern crate serde_derive;
+# #[macro_use] extern crate serde_json;
+# #[macro_use] extern crate elastic_types_derive;
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+# fn main() {
+# #[derive(Serialize, ElasticType)]
+# pub struct MyType {
+#     pub title: String,
+#     pub published: Option<i32>,
+# }
+let fields = MyType::fields();
+
+let query = json!({
+    "bool": {
+        "filter": [
+            fields.published.between(2010, 2020),
+            fields.title.keyword().any_of(vec!["Rust", "Elasticsearch"])
+        ]
+    }
+});
+# }
+```
+
+### Ignore or Rename Fields
+
+You can then serialise type mappings with `#[serde]` attributes:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# use elastic_types::prelude::*;
+#[derive(ElasticType, Serialize)]
+pub struct MyType {
+    #[serde(rename="my_renamed_date")]
+    pub my_date: Date<DefaultDateMapping>,
+    #[serde(skip_serializing)]
+    pub ignored: String,
+    pub my_num: i32
+}
+# fn main() {
+# }
+```
+
+> NOTE: Fields with a `#[serde(skip_deserializing)]` attribute will still be mapped, because they can
+still be indexed in Elasticsearch.
+
+Property names follow `#[serde(rename)]` and `#[serde(rename_all)]`, so the mapping always matches the serialised document.
+A field can also be given a name in the mapping that's independent of `serde` with `#[elastic(rename)]`:
+
+```
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+#[derive(ElasticType, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MyType {
+    pub my_date: Date<DefaultDateMapping>,
+    #[elastic(rename = "num")]
+    pub my_num: i32
+}
+# fn main() {
+# }
+```
+
+This is useful when the type has a handwritten `Serialize` implementation.
+Field references use the same names as the mapping.
+
+Fields with an `#[elastic(skip)]` attribute are left out of the mapping, but are still serialised as `serde` is configured to.
+Their types don't need to implement `FieldType`, so they're useful for fields that are only used on the client, or that are mapped manually:
+
+```
+# #[macro_use]
+# extern crate serde_derive;
+# #[macro_use]
+# extern crate elastic_types_derive;
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+# use std::time::Duration;
+#[derive(ElasticType, Serialize)]
+pub struct MyType {
+    #[elastic(id, skip)]
+    pub id: String,
+    #[elastic(skip)]
+    pub elapsed: Duration,
+    pub my_num: i32
+}
+# fn main() {
+# }
+```
+
+A skipped field can still be used as the `id` of a document.
+
+## Limitations
 
 Automatically deriving mapping has the following limitations:
 
-- Generics aren't supported by auto deriving.
-So you can't `#[derive(ElasticType)]` on `MyType<T>`.
 - Mapping types can't be shared. This is because they need to map the type fields, so are specific to that type.
 So you can't share `MyTypeMapping` between `MyType` and `MyOtherType`.
 
@@ -394,8 +1348,18 @@ So you can't share `MyTypeMapping` between `MyType` and `MyOtherType`.
 
 pub mod mapping;
 
+pub(crate) mod fields;
 mod impls;
-pub use self::impls::*;
+mod introspect;
+pub use self::{
+    fields::{
+        DefaultStringField,
+        DocumentFields,
+        FieldRef,
+    },
+    impls::*,
+    introspect::FieldMapping,
+};
 
 pub mod prelude {
     /*!
@@ -405,12 +1369,20 @@ pub mod prelude {
     */
 
     pub use super::{
+        fields::{
+            DefaultStringField,
+            DocumentFields,
+            FieldRef,
+        },
         impls::{
+            DocumentId,
             DocumentType,
             IndexDocumentMapping,
+            PartialDocument,
             StaticIndex,
             StaticType,
         },
+        introspect::FieldMapping,
         mapping::*,
     };
 }
@@ -6,6 +6,7 @@ use serde::{
 };
 
 /** A unit of measure for distance. */
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DistanceUnit {
     /** For `in`. */
     Inches,
@@ -23,13 +24,9 @@ pub enum DistanceUnit {
     Millimeters,
 }
 
-/** A distance value paired with a unit of measure. */
-pub struct Distance(pub f32, pub DistanceUnit);
-
-impl ToString for Distance {
-    fn to_string(&self) -> String {
-        let value = self.0.to_string();
-        let unit = match self.1 {
+impl DistanceUnit {
+    fn as_str(&self) -> &'static str {
+        match *self {
             DistanceUnit::Inches => "in",
             DistanceUnit::Yards => "yd",
             DistanceUnit::Miles => "mi",
@@ -37,7 +34,26 @@ impl ToString for Distance {
             DistanceUnit::Meters => "m",
             DistanceUnit::Centimeters => "cm",
             DistanceUnit::Millimeters => "mm",
-        };
+        }
+    }
+}
+
+impl Serialize for DistanceUnit {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/** A distance value paired with a unit of measure. */
+pub struct Distance(pub f32, pub DistanceUnit);
+
+impl ToString for Distance {
+    fn to_string(&self) -> String {
+        let value = self.0.to_string();
+        let unit = self.1.as_str();
 
         let mut s = String::with_capacity(value.len() + unit.len());
         s.push_str(&value);
@@ -2,6 +2,7 @@
 
 use super::{
     DefaultGeoPointFormat,
+    GeoPoint,
     GeoPointFormat,
 };
 use geo::mapping::Distance;
@@ -100,6 +101,15 @@ pub trait GeoPointMapping {
     */
     type Format: GeoPointFormat;
 
+    /**
+    Should the field be stored on disk in a column-stride fashion,
+    so that it can later be used for sorting, aggregations, or scripting?
+    Accepts `true` (default) or `false`.
+    */
+    fn doc_values() -> Option<bool> {
+        None
+    }
+
     /**
     Should the `geo-point` also be indexed as a geohash in the `.geohash` sub-field? Defaults to `false`,
     unless `geohash_prefix` is `true`.
@@ -126,6 +136,11 @@ pub trait GeoPointMapping {
         None
     }
 
+    /** Should the field be searchable? Accepts `true` (default) and `false`. */
+    fn index() -> Option<bool> {
+        None
+    }
+
     /**
     Should the `geo-point` also be indexed as `.lat` and `.lon` sub-fields?
     Accepts `true` and `false` (default).
@@ -133,6 +148,25 @@ pub trait GeoPointMapping {
     fn lat_lon() -> Option<bool> {
         None
     }
+
+    /**
+    Accepts a geo point value which is substituted for any explicit null values.
+    Defaults to `null`, which means the field is treated as missing.
+    */
+    fn null_value() -> Option<GeoPoint<Self>>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /**
+    Whether the field value should be stored and retrievable separately from the `_source` field.
+    Accepts `true` or `false` (default).
+    */
+    fn store() -> Option<bool> {
+        None
+    }
 }
 
 /** Default mapping for `geo_point`. */
@@ -197,15 +231,19 @@ mod private {
         where
             S: Serializer,
         {
-            let mut state = try!(serializer.serialize_struct("mapping", 6));
+            let mut state = try!(serializer.serialize_struct("mapping", 10));
 
             try!(state.serialize_field("type", TMapping::data_type()));
 
+            ser_field!(state, "doc_values", TMapping::doc_values());
             ser_field!(state, "geohash", TMapping::geohash());
             ser_field!(state, "geohash_precision", TMapping::geohash_precision());
             ser_field!(state, "geohash_prefix", TMapping::geohash_prefix());
             ser_field!(state, "ignore_malformed", TMapping::ignore_malformed());
+            ser_field!(state, "index", TMapping::index());
             ser_field!(state, "lat_lon", TMapping::lat_lon());
+            ser_field!(state, "null_value", TMapping::null_value());
+            ser_field!(state, "store", TMapping::store());
 
             state.end()
         }
@@ -224,6 +262,10 @@ mod tests {
     impl GeoPointMapping for MyGeoPointMapping {
         type Format = GeoPointArray;
 
+        fn doc_values() -> Option<bool> {
+            Some(false)
+        }
+
         fn geohash() -> Option<bool> {
             Some(false)
         }
@@ -240,9 +282,21 @@ mod tests {
             Some(true)
         }
 
+        fn index() -> Option<bool> {
+            Some(true)
+        }
+
         fn lat_lon() -> Option<bool> {
             Some(true)
         }
+
+        fn null_value() -> Option<GeoPoint<Self>> {
+            Some(GeoPoint::build(-71.34, 41.12))
+        }
+
+        fn store() -> Option<bool> {
+            Some(true)
+        }
     }
 
     #[test]
@@ -265,11 +319,15 @@ mod tests {
 
         let expected = json_str!({
             "type": "geo_point",
+            "doc_values": false,
             "geohash": false,
             "geohash_precision": "50m",
             "geohash_prefix": true,
             "ignore_malformed": true,
-            "lat_lon": true
+            "index": true,
+            "lat_lon": true,
+            "null_value": [-71.34, 41.12],
+            "store": true
         });
 
         assert_eq!(expected, ser);
@@ -147,6 +147,36 @@ pub trait GeoShapeMapping {
     fn points_only() -> Option<bool> {
         None
     }
+
+    /**
+    If `true`, malformed GeoJSON or WKT shapes are ignored. If `false` (default),
+    malformed shapes throw an exception and reject the whole document.
+    */
+    fn ignore_malformed() -> Option<bool> {
+        None
+    }
+
+    /**
+    If `true`, unclosed linear rings in polygons are closed automatically.
+    Accepts `true` or `false` (default).
+    */
+    fn coerce() -> Option<bool> {
+        None
+    }
+
+    /**
+    Should the field be stored on disk in a column-stride fashion,
+    so that it can later be used for sorting, aggregations, or scripting?
+    Accepts `true` (default) or `false`.
+    */
+    fn doc_values() -> Option<bool> {
+        None
+    }
+
+    /** Should the field be searchable? Accepts `true` (default) and `false`. */
+    fn index() -> Option<bool> {
+        None
+    }
 }
 
 /** Default mapping for `geo_shape`. */
@@ -267,7 +297,7 @@ mod private {
         where
             S: Serializer,
         {
-            let mut state = try!(serializer.serialize_struct("mapping", 8));
+            let mut state = try!(serializer.serialize_struct("mapping", 12));
 
             try!(state.serialize_field("type", TMapping::data_type()));
 
@@ -278,6 +308,10 @@ mod private {
             ser_field!(state, "distance_error_pct", TMapping::distance_error_pct());
             ser_field!(state, "orientation", TMapping::orientation());
             ser_field!(state, "points_only", TMapping::points_only());
+            ser_field!(state, "ignore_malformed", TMapping::ignore_malformed());
+            ser_field!(state, "coerce", TMapping::coerce());
+            ser_field!(state, "doc_values", TMapping::doc_values());
+            ser_field!(state, "index", TMapping::index());
 
             state.end()
         }
@@ -321,6 +355,22 @@ mod tests {
         fn points_only() -> Option<bool> {
             Some(false)
         }
+
+        fn ignore_malformed() -> Option<bool> {
+            Some(true)
+        }
+
+        fn coerce() -> Option<bool> {
+            Some(true)
+        }
+
+        fn doc_values() -> Option<bool> {
+            Some(false)
+        }
+
+        fn index() -> Option<bool> {
+            Some(true)
+        }
     }
 
     #[test]
@@ -346,7 +396,11 @@ mod tests {
             "strategy": "recursive",
             "distance_error_pct": 0.5,
             "orientation": "cw",
-            "points_only": false
+            "points_only": false,
+            "ignore_malformed": true,
+            "coerce": true,
+            "doc_values": false,
+            "index": true
         });
 
         assert_eq!(expected, ser);
@@ -17,16 +17,25 @@ use std::{
     borrow::Borrow,
     error::Error as StdError,
     marker::PhantomData,
-    net::Ipv4Addr,
+    net::{
+        IpAddr,
+        Ipv4Addr,
+        Ipv6Addr,
+    },
     str::FromStr,
 };
 
+impl IpFieldType<DefaultIpMapping> for IpAddr {}
+
 impl IpFieldType<DefaultIpMapping> for Ipv4Addr {}
 
+impl IpFieldType<DefaultIpMapping> for Ipv6Addr {}
+
 /**
 An Elasticsearch `ip` with a mapping.
 
-Where the mapping isn't custom, you can use the standard library `Ipv4Addr` instead.
+An `Ip` can contain either an IPv4 or IPv6 address.
+Where the mapping isn't custom, you can use the standard library `IpAddr`, `Ipv4Addr` or `Ipv6Addr` instead.
 
 # Examples
 
@@ -45,7 +54,7 @@ pub struct Ip<TMapping>
 where
     TMapping: IpMapping,
 {
-    value: Ipv4Addr,
+    value: IpAddr,
     _m: PhantomData<TMapping>,
 }
 
@@ -58,7 +67,7 @@ where
 
     # Examples
 
-    Create a new `Ip` from a `Ip4vAddr`:
+    Create a new `Ip` from an `Ipv4Addr`:
 
     ```
     use std::net::Ipv4Addr;
@@ -67,10 +76,20 @@ where
 
     let ip = Ip::<DefaultIpMapping>::new(Ipv4Addr::new(127, 0, 0, 1));
     ```
+
+    Create a new `Ip` from an `Ipv6Addr`:
+
+    ```
+    use std::net::Ipv6Addr;
+    use elastic_types::ip::mapping::DefaultIpMapping;
+    use elastic_types::ip::Ip;
+
+    let ip = Ip::<DefaultIpMapping>::new(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1));
+    ```
     */
     pub fn new<I>(ip: I) -> Ip<TMapping>
     where
-        I: Into<Ipv4Addr>,
+        I: Into<IpAddr>,
     {
         Ip {
             value: ip.into(),
@@ -111,7 +130,7 @@ where
 
 impl<TMapping> IpFieldType<TMapping> for Ip<TMapping> where TMapping: IpMapping {}
 
-impl_mapping_type!(Ipv4Addr, Ip, IpMapping);
+impl_mapping_type!(IpAddr, Ip, IpMapping);
 
 // Serialize elastic ip
 impl<TMapping> Serialize for Ip<TMapping>
@@ -146,16 +165,18 @@ where
             type Value = Ip<TMapping>;
 
             fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
-                write!(formatter, "a json string containing an IpV4 address")
+                write!(
+                    formatter,
+                    "a json string containing an IPv4 or IPv6 address"
+                )
             }
 
             fn visit_string<E>(self, v: String) -> Result<Ip<TMapping>, E>
             where
                 E: Error,
             {
-                let de = try!(
-                    Ipv4Addr::from_str(&v).map_err(|e| E::custom(e.description().to_string()))
-                );
+                let de =
+                    try!(IpAddr::from_str(&v).map_err(|e| E::custom(e.description().to_string())));
 
                 Ok(Ip::new(de))
             }
@@ -165,7 +186,7 @@ where
                 E: Error,
             {
                 let de =
-                    try!(Ipv4Addr::from_str(v).map_err(|e| E::custom(e.description().to_string())));
+                    try!(IpAddr::from_str(v).map_err(|e| E::custom(e.description().to_string())));
 
                 Ok(Ip::new(de))
             }
@@ -178,7 +199,11 @@ where
 #[cfg(test)]
 mod tests {
     use serde_json;
-    use std::net::Ipv4Addr;
+    use std::net::{
+        IpAddr,
+        Ipv4Addr,
+        Ipv6Addr,
+    };
 
     use prelude::*;
 
@@ -210,7 +235,33 @@ mod tests {
     fn deserialise_elastic_ip() {
         let ip: Ip<DefaultIpMapping> = serde_json::from_str(r#""127.0.0.1""#).unwrap();
 
-        assert_eq!(Ipv4Addr::new(127, 0, 0, 1), ip);
+        assert_eq!(IpAddr::from(Ipv4Addr::new(127, 0, 0, 1)), ip);
+    }
+
+    #[test]
+    fn serialise_elastic_ipv6() {
+        let ip: Ip<DefaultIpMapping> = Ip::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+
+        let ser = serde_json::to_string(&ip).unwrap();
+
+        assert_eq!(r#""2001:db8::1""#, ser);
+    }
+
+    #[test]
+    fn deserialise_elastic_ipv6() {
+        let ip: Ip<DefaultIpMapping> = serde_json::from_str(r#""2001:db8::1""#).unwrap();
+
+        assert_eq!(
+            IpAddr::from(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)),
+            ip
+        );
+    }
+
+    #[test]
+    fn deserialise_elastic_ip_invalid() {
+        let ip: Result<Ip<DefaultIpMapping>, _> = serde_json::from_str(r#""not an ip""#);
+
+        assert!(ip.is_err());
     }
 
 }
@@ -1,6 +1,6 @@
 /*! Mapping for the Elasticsearch `ip` type. */
 
-use std::net::Ipv4Addr;
+use std::net::IpAddr;
 
 /** A field that will be mapped as an `ip`. */
 pub trait IpFieldType<TMapping> {}
@@ -70,6 +70,14 @@ pub trait IpMapping {
         None
     }
 
+    /**
+    The names of other fields to copy the values of this field into.
+    Copying several fields into one lets them be searched together as a single field.
+    */
+    fn copy_to() -> Option<&'static [&'static str]> {
+        None
+    }
+
     /**
     Should the field be stored on disk in a column-stride fashion,
     so that it can later be used for sorting, aggregations, or scripting?
@@ -79,16 +87,24 @@ pub trait IpMapping {
         None
     }
 
-    /** Should the field be searchable? Accepts `not_analyzed` (default) and `no`. */
+    /**
+    If `true`, malformed ip addresses are ignored.
+    If `false` (default), malformed ip addresses throw an exception and reject the whole document.
+    */
+    fn ignore_malformed() -> Option<bool> {
+        None
+    }
+
+    /** Should the field be searchable? Accepts `true` (default) and `false`. */
     fn index() -> Option<bool> {
         None
     }
 
     /**
-    Accepts a string value which is substituted for any explicit null values.
+    Accepts an IPv4 or IPv6 value which is substituted for any explicit null values.
     Defaults to `null`, which means the field is treated as missing.
     */
-    fn null_value() -> Option<Ipv4Addr> {
+    fn null_value() -> Option<IpAddr> {
         None
     }
 
@@ -101,7 +117,7 @@ pub trait IpMapping {
     }
 }
 
-/** Default mapping for `geo_shape`. */
+/** Default mapping for `ip`. */
 #[derive(PartialEq, Debug, Default, Clone, Copy)]
 pub struct DefaultIpMapping;
 impl IpMapping for DefaultIpMapping {}
@@ -111,6 +127,7 @@ mod private {
         IpFieldType,
         IpMapping,
     };
+    use derive::NullValueMapping;
     use private::field::{
         FieldMapping,
         FieldType,
@@ -122,6 +139,8 @@ mod private {
         Serialize,
         Serializer,
     };
+    use serde_json::Value;
+    use std::net::IpAddr;
 
     #[derive(Default)]
     pub struct IpPivot;
@@ -152,12 +171,14 @@ mod private {
         where
             S: Serializer,
         {
-            let mut state = try!(serializer.serialize_struct("mapping", 6));
+            let mut state = try!(serializer.serialize_struct("mapping", 8));
 
             try!(state.serialize_field("type", TMapping::data_type()));
 
             ser_field!(state, "boost", TMapping::boost());
+            ser_field!(state, "copy_to", TMapping::copy_to());
             ser_field!(state, "doc_values", TMapping::doc_values());
+            ser_field!(state, "ignore_malformed", TMapping::ignore_malformed());
             ser_field!(state, "index", TMapping::index());
             ser_field!(state, "store", TMapping::store());
             ser_field!(state, "null_value", TMapping::null_value());
@@ -165,12 +186,29 @@ mod private {
             state.end()
         }
     }
+
+    impl<TMapping> NullValueMapping<IpPivot> for TMapping
+    where
+        TMapping: IpMapping,
+    {
+        type NullValue = &'static str;
+
+        fn to_null_value(value: &'static str) -> Result<Value, String> {
+            value
+                .parse::<IpAddr>()
+                .map(|ip| Value::from(ip.to_string()))
+                .map_err(|err| err.to_string())
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use serde_json;
-    use std::net::Ipv4Addr;
+    use std::net::{
+        IpAddr,
+        Ipv6Addr,
+    };
 
     use prelude::*;
     use private::field;
@@ -190,12 +228,16 @@ mod tests {
             Some(true)
         }
 
+        fn ignore_malformed() -> Option<bool> {
+            Some(true)
+        }
+
         fn store() -> Option<bool> {
             Some(true)
         }
 
-        fn null_value() -> Option<Ipv4Addr> {
-            Some(Ipv4Addr::new(127, 0, 0, 1))
+        fn null_value() -> Option<IpAddr> {
+            Some(IpAddr::from(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1)))
         }
     }
 
@@ -218,9 +260,10 @@ mod tests {
             "type": "ip",
             "boost": 1.01,
             "doc_values": true,
+            "ignore_malformed": true,
             "index": false,
             "store": true,
-            "null_value": "127.0.0.1"
+            "null_value": "::1"
         });
 
         assert_eq!(expected, ser);
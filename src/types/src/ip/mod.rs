@@ -1,6 +1,8 @@
 /*!
 Implementation of the Elasticsearch `ip` type.
 
+An `ip` field can contain either IPv4 or IPv6 addresses.
+
 # Examples
 
 For defining your own ip mapping, see [mapping details](mapping/trait.IpMapping.html#derive-mapping).
@@ -8,9 +10,9 @@ For defining your own ip mapping, see [mapping details](mapping/trait.IpMapping.
 Map with a default `ip`:
 
 ```
-# use std::net::Ipv4Addr;
+# use std::net::IpAddr;
 struct MyType {
-    pub field: std::net::Ipv4Addr
+    pub field: std::net::IpAddr
 }
 ```
 
@@ -181,7 +181,7 @@ Our mapping for `MyOtherType` then looks like:
 {
     "properties": {
         "my_type": {
-            "type": "nested",
+            "type": "object",
             "properties": {
                 "my_date": {
                     "type": "date",
@@ -429,7 +429,7 @@ In case you're interested, it'll look something like this (minus the whitespace)
             "null_value": "1435935302478"
         },
         "geoip": {
-            "type": "nested",
+            "type": "object",
             "properties": {
                 "ip": {
                     "type": "ip"
@@ -474,13 +474,27 @@ The following table illustrates the types provided by `elastic_types`:
  `byte`              | `i8`                        | `std`     | [`Byte<M>`](number/index.html)                                                   | -
  `float`             | `f32`                       | `std`     | [`Float<M>`](number/index.html)                                                  | -
  `double`            | `f64`                       | `std`     | [`Double<M>`](number/index.html)                                                 | -
- `keyword`           | -                           | -         | [`Keyword<M>`](string/index.html)                                                | -
+ `half_float`        | -                           | -         | [`HalfFloat<M>`](number/index.html)                                              | -
+ `scaled_float`      | -                           | -         | [`ScaledFloat<M>`](number/index.html)                                            | -
+ `keyword`           | `Uuid`                      | `uuid`    | [`Keyword<M>`](string/index.html)                                                | -
  `text`              | `String`                    | `std`     | [`Text<M>`](string/index.html)                                                   | -
  `boolean`           | `bool`                      | `std`     | [`Boolean<M>`](boolean/index.html)                                               | -
- `ip`                | `Ipv4Addr`                  | `std`     | [`Ip<M>`](ip/index.html)                                                         | -
+ `ip`                | `IpAddr`                    | `std`     | [`Ip<M>`](ip/index.html)                                                         | -
  `date`              | `DateTime<Utc>`             | `chrono`  | [`Date<M>`](date/index.html)                                                     | `DateFormat`
+ `date_nanos`        | -                           | -         | [`DateNanos<M>`](date/nanos/index.html)                                          | `DateFormat`
  `geo_point`         | `Point`                     | `geo`     | [`GeoPoint<M>`](geo/point/index.html)                                            | `GeoPointFormat`
  `geo_shape`         | -                           | `geojson` | [`GeoShape<M>`](geo/shape/index.html)                                            | -
+ `integer_range`     | -                           | -         | [`IntegerRange<M>`](range/index.html)                                            | -
+ `long_range`        | -                           | -         | [`LongRange<M>`](range/index.html)                                               | -
+ `float_range`       | -                           | -         | [`FloatRange<M>`](range/index.html)                                              | -
+ `double_range`      | -                           | -         | [`DoubleRange<M>`](range/index.html)                                             | -
+ `date_range`        | -                           | -         | [`DateRange<M>`](range/index.html)                                               | `DateFormat`
+ `ip_range`          | -                           | -         | [`IpRange<M>`](range/index.html)                                                 | -
+ `dense_vector`      | -                           | -         | [`DenseVector<M>`](vector/index.html)                                            | -
+
+The default mapping for `Uuid` is only available with the `uuid` feature.
+With the `rust_decimal` feature, `Decimal` is mapped as a `keyword` by default, and `ScaledDecimal<M>` maps it as a `scaled_float`.
+With the `time` feature, `TimeDate<M>` maps a `time::OffsetDateTime` as a `date`.
 
 ## Mapping
 
@@ -519,11 +533,18 @@ extern crate elastic_types_derive;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
+#[macro_use]
 extern crate serde_json;
 
 extern crate chrono;
 extern crate geo as georust;
 extern crate geojson;
+#[cfg(feature = "rust_decimal")]
+extern crate rust_decimal;
+#[cfg(feature = "time")]
+extern crate time;
+#[cfg(feature = "uuid")]
+extern crate uuid;
 
 #[cfg(test)]
 #[macro_use]
@@ -538,7 +559,9 @@ pub mod document;
 pub mod geo;
 pub mod ip;
 pub mod number;
+pub mod range;
 pub mod string;
+pub mod vector;
 
 #[doc(hidden)]
 pub mod derive;
@@ -557,7 +580,9 @@ pub mod prelude {
     pub use geo::prelude::*;
     pub use ip::prelude::*;
     pub use number::prelude::*;
+    pub use range::prelude::*;
     pub use string::prelude::*;
+    pub use vector::prelude::*;
 }
 
 // This is a simple workaround for paths needed by `elastic_types_derive`.
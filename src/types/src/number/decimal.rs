@@ -0,0 +1,181 @@
+use rust_decimal::Decimal;
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use std::{
+    borrow::Borrow,
+    marker::PhantomData,
+};
+
+use super::mapping::{
+    ScaledFloatFieldType,
+    ScaledFloatMapping,
+};
+use string::keyword::mapping::{
+    DefaultKeywordMapping,
+    KeywordFieldType,
+};
+
+impl KeywordFieldType<DefaultKeywordMapping> for Decimal {}
+
+/**
+A `rust_decimal::Decimal` mapped as a `scaled_float`.
+
+The `scaling_factor` is provided by the mapping, so a factor of `100` stores values to two decimal places.
+Values are serialised as strings, so they're kept exactly in the `_source` of a document, and Elasticsearch coerces them when they're indexed.
+Values can be deserialised from either strings or numbers.
+
+This type is only available with the `rust_decimal` feature.
+
+# Examples
+
+```
+# extern crate elastic_types;
+# #[cfg(feature = "rust_decimal")]
+# extern crate rust_decimal;
+# use elastic_types::prelude::*;
+# fn main() {
+# #[cfg(feature = "rust_decimal")]
+# {
+use rust_decimal::Decimal;
+
+#[derive(Default)]
+struct PriceMapping;
+impl ScaledFloatMapping for PriceMapping {
+    fn scaling_factor() -> f64 {
+        100.0
+    }
+}
+
+let price: ScaledDecimal<PriceMapping> = ScaledDecimal::new(Decimal::new(1999, 2));
+
+assert_eq!("19.99", price.to_string());
+# }
+# }
+```
+*/
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ScaledDecimal<TMapping>
+where
+    TMapping: ScaledFloatMapping,
+{
+    value: Decimal,
+    _m: PhantomData<TMapping>,
+}
+
+impl<TMapping> ScaledDecimal<TMapping>
+where
+    TMapping: ScaledFloatMapping,
+{
+    /** Creates a new decimal with the given mapping. */
+    pub fn new<I: Into<Decimal>>(num: I) -> ScaledDecimal<TMapping> {
+        ScaledDecimal {
+            value: num.into(),
+            _m: PhantomData,
+        }
+    }
+
+    /** Change the mapping of this decimal. */
+    pub fn remap<TNewMapping>(number: ScaledDecimal<TMapping>) -> ScaledDecimal<TNewMapping>
+    where
+        TNewMapping: ScaledFloatMapping,
+    {
+        ScaledDecimal::new(number.value)
+    }
+}
+
+impl<TMapping> ScaledFloatFieldType<TMapping> for ScaledDecimal<TMapping> where
+    TMapping: ScaledFloatMapping
+{
+}
+
+impl_mapping_type!(Decimal, ScaledDecimal, ScaledFloatMapping);
+
+impl<TMapping> Serialize for ScaledDecimal<TMapping>
+where
+    TMapping: ScaledFloatMapping,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&self.value)
+    }
+}
+
+impl<'de, TMapping> Deserialize<'de> for ScaledDecimal<TMapping>
+where
+    TMapping: ScaledFloatMapping,
+{
+    fn deserialize<D>(deserializer: D) -> Result<ScaledDecimal<TMapping>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let t = try!(<Decimal as Deserialize>::deserialize(deserializer));
+
+        Ok(ScaledDecimal::new(t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use serde_json;
+
+    use prelude::*;
+
+    #[derive(Default)]
+    struct PriceMapping;
+    impl ScaledFloatMapping for PriceMapping {
+        fn scaling_factor() -> f64 {
+            100.0
+        }
+    }
+
+    #[derive(Serialize, ElasticType)]
+    struct DecimalType {
+        price: ScaledDecimal<PriceMapping>,
+        amount: Decimal,
+    }
+
+    #[test]
+    fn serialise_decimal_mapping() {
+        let ser = serde_json::to_string(&DecimalType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "price": {
+                    "type": "scaled_float",
+                    "scaling_factor": 100.0
+                },
+                "amount": {
+                    "type": "keyword"
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_scaled_decimal() {
+        let price: ScaledDecimal<PriceMapping> =
+            ScaledDecimal::new("12345678901234567.89".parse::<Decimal>().unwrap());
+
+        let ser = serde_json::to_string(&price).unwrap();
+
+        assert_eq!(r#""12345678901234567.89""#, ser);
+    }
+
+    #[test]
+    fn deserialise_scaled_decimal() {
+        let from_str: ScaledDecimal<PriceMapping> = serde_json::from_str(r#""0.10""#).unwrap();
+        let from_num: ScaledDecimal<PriceMapping> = serde_json::from_str("0.1").unwrap();
+
+        assert_eq!(Decimal::new(10, 2), *from_str);
+        assert_eq!(Decimal::new(1, 1), *from_num);
+    }
+}
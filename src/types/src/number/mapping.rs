@@ -3,6 +3,7 @@ Mapping for the Elasticsearch `number` types.
 
 Custom mappings can be defined by implementing the right number mapping for some Rust primitive number type.
 The implementation is the same for all number types, the only difference is the return type of `null_value`.
+The `scaled_float` mapping also requires a `scaling_factor`.
 
 # Examples
 
@@ -61,6 +62,11 @@ This will produce the following mapping:
 
 macro_rules! number_mapping {
     ($mapping:ident, $pivot:ident, $field_trait:ident, $datatype_name:expr, $std_ty:ty, $private_mod:ident) => {
+        number_mapping!($mapping, $pivot, $field_trait, $datatype_name, $std_ty, $private_mod, {});
+    };
+    ($mapping:ident, $pivot:ident, $field_trait:ident, $datatype_name:expr, $std_ty:ty, $private_mod:ident, {
+        $($(#[$required_attr:meta])* fn $required_fn:ident() -> $required_ty:ty;)*
+    }) => {
         /** A field that will be mapped as a number. */
         pub trait $field_trait<TMapping> {}
 
@@ -69,6 +75,11 @@ macro_rules! number_mapping {
         where
             Self: Default,
         {
+            $(
+                $(#[$required_attr])*
+                fn $required_fn() -> $required_ty;
+            )*
+
             /** Try to convert strings to numbers and truncate fractions for integers. Accepts `true` (default) and `false`. */
             fn coerce() -> Option<bool> {
                 None
@@ -79,6 +90,14 @@ macro_rules! number_mapping {
                 None
             }
 
+            /**
+            The names of other fields to copy the values of this field into.
+            Copying several fields into one lets them be searched together as a single field.
+            */
+            fn copy_to() -> Option<&'static [&'static str]> {
+                None
+            }
+
             /**
             Should the field be stored on disk in a column-stride fashion,
             so that it can later be used for sorting, aggregations, or scripting?
@@ -106,7 +125,7 @@ macro_rules! number_mapping {
                 None
             }
 
-            /** Should the field be searchable? Accepts `not_analyzed` (default) and `no`. */
+            /** Should the field be searchable? Accepts `true` (default) and `false`. */
             fn index() -> Option<bool> {
                 None
             }
@@ -121,7 +140,7 @@ macro_rules! number_mapping {
 
             /**
             Whether the field value should be stored and retrievable separately from the `_source` field.
-            Accepts true or false (default).
+            Accepts `true` or `false` (default).
             */
             fn store() -> Option<bool> {
                 None
@@ -130,9 +149,11 @@ macro_rules! number_mapping {
 
         mod $private_mod {
             use super::{$field_trait, $mapping};
+            use derive::NullValueMapping;
             use private::field::{FieldMapping, FieldType, SerializeFieldMapping, StaticSerialize};
-            use serde::ser::SerializeStruct;
+            use serde::ser::{Error, SerializeStruct};
             use serde::Serialize;
+            use serde_json::Value;
 
             #[derive(Default)]
             pub struct $pivot;
@@ -163,25 +184,62 @@ macro_rules! number_mapping {
                 where
                     S: ::serde::Serializer,
                 {
-                    let mut state = try!(serializer.serialize_struct("mapping", 8));
+                    let mut state = try!(serializer.serialize_struct("mapping", 10));
 
                     try!(state.serialize_field("type", TMapping::data_type()));
 
+                    $(
+                        try!(state.serialize_field(stringify!($required_fn), &TMapping::$required_fn()));
+                    )*
+
                     ser_field!(state, "coerce", TMapping::coerce());
                     ser_field!(state, "boost", TMapping::boost());
+                    ser_field!(state, "copy_to", TMapping::copy_to());
                     ser_field!(state, "doc_values", TMapping::doc_values());
                     ser_field!(state, "ignore_malformed", TMapping::ignore_malformed());
                     ser_field!(state, "include_in_all", TMapping::include_in_all());
-                    ser_field!(state, "null_value", TMapping::null_value());
+                    if let Some(null_value) = TMapping::null_value() {
+                        let null_value = try!(super::finite_null_value(null_value).map_err(S::Error::custom));
+                        try!(state.serialize_field("null_value", &null_value));
+                    }
+
                     ser_field!(state, "store", TMapping::store());
 
                     state.end()
                 }
             }
+
+            impl<TMapping> NullValueMapping<$pivot> for TMapping
+            where
+                TMapping: $mapping,
+            {
+                type NullValue = $std_ty;
+
+                fn to_null_value(value: $std_ty) -> Result<Value, String> {
+                    super::finite_null_value(value)
+                }
+            }
         }
     };
 }
 
+// Floating point numbers that aren't finite would be serialised as `null`.
+// Going through a string keeps the shortest representation of an `f32`.
+fn finite_null_value<TNumber>(value: TNumber) -> Result<::serde_json::Value, String>
+where
+    TNumber: ::serde::Serialize,
+{
+    let value: ::serde_json::Value = try!(::serde_json::to_string(&value)
+        .and_then(|value| ::serde_json::from_str(&value))
+        .map_err(|e| e.to_string()));
+
+    if value.is_number() {
+        Ok(value)
+    } else {
+        Err("the null_value must be a finite number".to_owned())
+    }
+}
+
 number_mapping!(
     IntegerMapping,
     IntegerFormat,
@@ -230,12 +288,36 @@ number_mapping!(
     f64,
     private_f64
 );
+number_mapping!(
+    HalfFloatMapping,
+    HalfFloatFormat,
+    HalfFloatFieldType,
+    "half_float",
+    f32,
+    private_half_float
+);
+number_mapping!(
+    ScaledFloatMapping,
+    ScaledFloatFormat,
+    ScaledFloatFieldType,
+    "scaled_float",
+    f64,
+    private_scaled_float,
+    {
+        /**
+        The factor to multiply values by before they're indexed as a `long`.
+        Values are rounded to the precision this factor allows, so a factor of `100` stores values to two decimal places.
+        */
+        fn scaling_factor() -> f64;
+    }
+);
 
 /** Default mapping for an `integer` type. */
 #[derive(PartialEq, Debug, Default, Clone, Copy)]
 pub struct DefaultIntegerMapping;
 impl IntegerMapping for DefaultIntegerMapping {}
 impl IntegerFieldType<DefaultIntegerMapping> for i32 {}
+impl IntegerFieldType<DefaultIntegerMapping> for u16 {}
 
 /** Default mapping for a `long` type. */
 #[derive(PartialEq, Debug, Default, Clone, Copy)]
@@ -243,12 +325,14 @@ pub struct DefaultLongMapping;
 impl LongMapping for DefaultLongMapping {}
 impl LongFieldType<DefaultLongMapping> for i64 {}
 impl LongFieldType<DefaultLongMapping> for isize {}
+impl LongFieldType<DefaultLongMapping> for u32 {}
 
 /** Default mapping for a `short` type. */
 #[derive(PartialEq, Debug, Default, Clone, Copy)]
 pub struct DefaultShortMapping;
 impl ShortMapping for DefaultShortMapping {}
 impl ShortFieldType<DefaultShortMapping> for i16 {}
+impl ShortFieldType<DefaultShortMapping> for u8 {}
 
 /** Default mapping for a `byte` type. */
 #[derive(PartialEq, Debug, Default, Clone, Copy)]
@@ -268,6 +352,11 @@ pub struct DefaultDoubleMapping;
 impl DoubleMapping for DefaultDoubleMapping {}
 impl DoubleFieldType<DefaultDoubleMapping> for f64 {}
 
+/** Default mapping for a `half_float` type. */
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub struct DefaultHalfFloatMapping;
+impl HalfFloatMapping for DefaultHalfFloatMapping {}
+
 #[cfg(test)]
 mod tests {
     use serde_json;
@@ -286,6 +375,10 @@ mod tests {
             Some(1.1)
         }
 
+        fn copy_to() -> Option<&'static [&'static str]> {
+            Some(&["all_numbers"])
+        }
+
         fn doc_values() -> Option<bool> {
             Some(false)
         }
@@ -491,6 +584,30 @@ mod tests {
         }
     }
 
+    #[derive(Default, Clone)]
+    pub struct MyHalfFloatMapping;
+    impl HalfFloatMapping for MyHalfFloatMapping {
+        fn null_value() -> Option<f32> {
+            Some(1.5)
+        }
+    }
+
+    #[derive(Default, Clone)]
+    pub struct MyScaledFloatMapping;
+    impl ScaledFloatMapping for MyScaledFloatMapping {
+        fn scaling_factor() -> f64 {
+            100.0
+        }
+
+        fn coerce() -> Option<bool> {
+            Some(true)
+        }
+
+        fn null_value() -> Option<f64> {
+            Some(1.25)
+        }
+    }
+
     #[test]
     fn serialise_mapping_integer_default() {
         let ser = serde_json::to_string(&field::serialize(DefaultIntegerMapping)).unwrap();
@@ -510,6 +627,7 @@ mod tests {
             "type": "integer",
             "coerce": true,
             "boost": 1.1,
+            "copy_to": ["all_numbers"],
             "doc_values": false,
             "ignore_malformed": true,
             "include_in_all": true,
@@ -636,6 +754,21 @@ mod tests {
         assert_eq!(expected, ser);
     }
 
+    #[test]
+    fn serialise_mapping_double_nan_null_value() {
+        #[derive(Default, Clone)]
+        struct NanDoubleMapping;
+        impl DoubleMapping for NanDoubleMapping {
+            fn null_value() -> Option<f64> {
+                Some(::std::f64::NAN)
+            }
+        }
+
+        let ser = serde_json::to_string(&field::serialize(NanDoubleMapping));
+
+        assert!(ser.is_err());
+    }
+
     #[test]
     fn serialise_mapping_float_default() {
         let ser = serde_json::to_string(&field::serialize(DefaultFloatMapping)).unwrap();
@@ -664,4 +797,41 @@ mod tests {
 
         assert_eq!(expected, ser);
     }
+
+    #[test]
+    fn serialise_mapping_half_float_default() {
+        let ser = serde_json::to_string(&field::serialize(DefaultHalfFloatMapping)).unwrap();
+
+        let expected = json_str!({
+            "type": "half_float"
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_half_float_custom() {
+        let ser = serde_json::to_string(&field::serialize(MyHalfFloatMapping)).unwrap();
+
+        let expected = json_str!({
+            "type": "half_float",
+            "null_value": 1.5
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_scaled_float_custom() {
+        let ser = serde_json::to_string(&field::serialize(MyScaledFloatMapping)).unwrap();
+
+        let expected = json_str!({
+            "type": "scaled_float",
+            "scaling_factor": 100.0,
+            "coerce": true,
+            "null_value": 1.25
+        });
+
+        assert_eq!(expected, ser);
+    }
 }
@@ -5,15 +5,45 @@ Numeric types come in a number of flavours that correspond to primitives in Rust
 
 Rust | Elasticsearch
 ------ | ------------------
-`i64` |  `long`
-`i32` | `integer`
-`i16` | `short`
+`i64`, `isize`, `u32` |  `long`
+`i32`, `u16` | `integer`
+`i16`, `u8` | `short`
 `i8` | `byte`
 `f64` | `double`
 `f32` | `float`
 
 For mapping a number with the default mapping, you can use the Rust primitive.
-If you need to use a custom mapping, then there is an `Elastic*` type for each number.
+If you need to use a custom mapping, then there is a wrapper type for each number.
+
+The `half_float` and `scaled_float` types don't have a Rust primitive, so they're mapped using
+the `HalfFloat` and `ScaledFloat` types.
+A `ScaledFloat` needs a mapping that provides its `scaling_factor`:
+
+```
+# extern crate serde;
+# #[macro_use]
+# extern crate elastic_types;
+# use elastic_types::prelude::*;
+# fn main() {
+#[derive(Default)]
+struct PriceMapping;
+impl ScaledFloatMapping for PriceMapping {
+    fn scaling_factor() -> f64 {
+        100.0
+    }
+}
+
+struct MyType {
+    pub price: ScaledFloat<PriceMapping>
+}
+# }
+```
+
+## Decimals
+
+With the `rust_decimal` feature, a `rust_decimal::Decimal` is mapped as a `keyword` by default, so its exact value is kept.
+To search decimals as numbers, use a `ScaledDecimal` with a `ScaledFloatMapping`.
+Values are stored in Elasticsearch as a `long` multiplied by the `scaling_factor`, but are serialised as strings so the `_source` keeps their exact value.
 
 # Examples
 
@@ -68,7 +98,11 @@ impl IntegerFieldType<DefaultIntegerMapping> for MyIntegerField {}
 
 pub mod mapping;
 
+#[cfg(feature = "rust_decimal")]
+mod decimal;
 mod impls;
+#[cfg(feature = "rust_decimal")]
+pub use self::decimal::*;
 pub use self::impls::*;
 
 pub mod prelude {
@@ -78,6 +112,8 @@ pub mod prelude {
     This is a convenience module to make it easy to build mappings for multiple types without too many `use` statements.
     */
 
+    #[cfg(feature = "rust_decimal")]
+    pub use super::decimal::*;
     pub use super::{
         impls::*,
         mapping::*,
@@ -13,6 +13,8 @@ use std::{
     marker::PhantomData,
 };
 
+use derive::ObjectDataTypeMapping;
+
 use super::field::{
     FieldMapping,
     FieldType,
@@ -89,6 +91,11 @@ where
     }
 }
 
+impl<TMapping, TPivot> ObjectDataTypeMapping for WrappedMapping<TMapping, TPivot> where
+    TMapping: FieldMapping<TPivot> + ObjectDataTypeMapping
+{
+}
+
 impl<TField> FieldType<DefaultMapping, ()> for TField where TField: DefaultFieldType {}
 
 /** Mapping implementation for a standard binary tree map. */
@@ -8,4 +8,4 @@ This module contains implementation details that support serialisation of mappab
 pub mod macros;
 pub mod field;
 
-mod impls;
+pub(crate) mod impls;
@@ -0,0 +1,471 @@
+use super::mapping::*;
+use date::{
+    DateFormat,
+    DateValue,
+};
+use serde::{
+    de::{
+        Error,
+        Visitor,
+    },
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use std::{
+    marker::PhantomData,
+    net::IpAddr,
+};
+
+/** The bounds of a range that are serialised, skipping any that aren't set. */
+#[derive(Serialize)]
+struct SerializeBounds<T> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gte: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gt: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lte: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lt: Option<T>,
+}
+
+/** The bounds of a range that are deserialised. */
+#[derive(Deserialize)]
+struct DeserializeBounds<T> {
+    gte: Option<T>,
+    gt: Option<T>,
+    lte: Option<T>,
+    lt: Option<T>,
+}
+
+macro_rules! range_type {
+    ($wrapper_ty:ident, $mapping_ty:ident, $field_trait:ident, $value_ty:ty) => {
+        /**
+        A range of values with a given mapping.
+
+        Each bound is optional, so a range can be open on either side.
+        */
+        #[derive(Debug, Default, Clone, PartialEq)]
+        pub struct $wrapper_ty<TMapping>
+        where
+            TMapping: $mapping_ty,
+        {
+            /** The range includes values greater than or equal to this one. */
+            pub gte: Option<$value_ty>,
+            /** The range includes values greater than this one. */
+            pub gt: Option<$value_ty>,
+            /** The range includes values less than or equal to this one. */
+            pub lte: Option<$value_ty>,
+            /** The range includes values less than this one. */
+            pub lt: Option<$value_ty>,
+            _m: PhantomData<TMapping>,
+        }
+
+        impl<TMapping> $wrapper_ty<TMapping>
+        where
+            TMapping: $mapping_ty,
+        {
+            /** Creates a new range without any bounds. */
+            pub fn new() -> $wrapper_ty<TMapping> {
+                $wrapper_ty {
+                    gte: None,
+                    gt: None,
+                    lte: None,
+                    lt: None,
+                    _m: PhantomData,
+                }
+            }
+
+            /** Creates a new range that includes the given values and all values between them. */
+            pub fn between<IFrom, ITo>(gte: IFrom, lte: ITo) -> $wrapper_ty<TMapping>
+            where
+                IFrom: Into<$value_ty>,
+                ITo: Into<$value_ty>,
+            {
+                $wrapper_ty {
+                    gte: Some(gte.into()),
+                    lte: Some(lte.into()),
+                    ..$wrapper_ty::new()
+                }
+            }
+
+            /** Change the mapping of this range. */
+            pub fn remap<TNewMapping>(range: $wrapper_ty<TMapping>) -> $wrapper_ty<TNewMapping>
+            where
+                TNewMapping: $mapping_ty,
+            {
+                $wrapper_ty {
+                    gte: range.gte,
+                    gt: range.gt,
+                    lte: range.lte,
+                    lt: range.lt,
+                    _m: PhantomData,
+                }
+            }
+        }
+
+        impl<TMapping> $field_trait<TMapping> for $wrapper_ty<TMapping> where TMapping: $mapping_ty {}
+
+        //Serialize elastic range.
+        impl<TMapping> Serialize for $wrapper_ty<TMapping>
+        where
+            TMapping: $mapping_ty,
+        {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                SerializeBounds {
+                    gte: self.gte.as_ref(),
+                    gt: self.gt.as_ref(),
+                    lte: self.lte.as_ref(),
+                    lt: self.lt.as_ref(),
+                }
+                .serialize(serializer)
+            }
+        }
+
+        //Deserialize elastic range.
+        impl<'de, TMapping> Deserialize<'de> for $wrapper_ty<TMapping>
+        where
+            TMapping: $mapping_ty,
+        {
+            fn deserialize<D>(deserializer: D) -> Result<$wrapper_ty<TMapping>, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let bounds = try!(DeserializeBounds::<$value_ty>::deserialize(deserializer));
+
+                Ok($wrapper_ty {
+                    gte: bounds.gte,
+                    gt: bounds.gt,
+                    lte: bounds.lte,
+                    lt: bounds.lt,
+                    _m: PhantomData,
+                })
+            }
+        }
+    };
+}
+
+range_type!(
+    IntegerRange,
+    IntegerRangeMapping,
+    IntegerRangeFieldType,
+    i32
+);
+range_type!(LongRange, LongRangeMapping, LongRangeFieldType, i64);
+range_type!(FloatRange, FloatRangeMapping, FloatRangeFieldType, f32);
+range_type!(DoubleRange, DoubleRangeMapping, DoubleRangeFieldType, f64);
+range_type!(IpRange, IpRangeMapping, IpRangeFieldType, IpAddr);
+
+/**
+A range of dates with a given mapping.
+
+Each bound is optional, so a range can be open on either side.
+The bounds are formatted using the format on the mapping.
+
+# Examples
+
+Defining a range of dates:
+
+```
+# use elastic_types::prelude::*;
+let range = DateRange::<DefaultDateRangeMapping<EpochMillis>>::between(
+    DateValue::build(2015, 5, 13, 0, 0, 0, 0),
+    DateValue::build(2015, 5, 14, 0, 0, 0, 0),
+);
+```
+*/
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DateRange<TMapping>
+where
+    TMapping: DateRangeMapping,
+{
+    /** The range includes dates on or after this one. */
+    pub gte: Option<DateValue>,
+    /** The range includes dates after this one. */
+    pub gt: Option<DateValue>,
+    /** The range includes dates on or before this one. */
+    pub lte: Option<DateValue>,
+    /** The range includes dates before this one. */
+    pub lt: Option<DateValue>,
+    _m: PhantomData<TMapping>,
+}
+
+impl<TMapping> DateRange<TMapping>
+where
+    TMapping: DateRangeMapping,
+{
+    /** Creates a new range without any bounds. */
+    pub fn new() -> DateRange<TMapping> {
+        DateRange {
+            gte: None,
+            gt: None,
+            lte: None,
+            lt: None,
+            _m: PhantomData,
+        }
+    }
+
+    /** Creates a new range that includes the given dates and all dates between them. */
+    pub fn between<IFrom, ITo>(gte: IFrom, lte: ITo) -> DateRange<TMapping>
+    where
+        IFrom: Into<DateValue>,
+        ITo: Into<DateValue>,
+    {
+        DateRange {
+            gte: Some(gte.into()),
+            lte: Some(lte.into()),
+            ..DateRange::new()
+        }
+    }
+
+    /** Change the format/mapping of this range. */
+    pub fn remap<TNewMapping>(range: DateRange<TMapping>) -> DateRange<TNewMapping>
+    where
+        TNewMapping: DateRangeMapping,
+    {
+        DateRange {
+            gte: range.gte,
+            gt: range.gt,
+            lte: range.lte,
+            lt: range.lt,
+            _m: PhantomData,
+        }
+    }
+}
+
+impl<TMapping> DateRangeFieldType<TMapping> for DateRange<TMapping> where TMapping: DateRangeMapping {}
+
+/** A date bound that's formatted when it's serialised. */
+struct FormattedBound<'a, TFormat> {
+    value: &'a DateValue,
+    _f: PhantomData<TFormat>,
+}
+
+impl<'a, TFormat> Serialize for FormattedBound<'a, TFormat>
+where
+    TFormat: DateFormat,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&TFormat::format(self.value))
+    }
+}
+
+/** A date bound that's parsed when it's deserialised. */
+struct ParsedBound<TFormat> {
+    value: DateValue,
+    _f: PhantomData<TFormat>,
+}
+
+impl<'de, TFormat> Deserialize<'de> for ParsedBound<TFormat>
+where
+    TFormat: DateFormat,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ParsedBoundVisitor<TFormat> {
+            _f: PhantomData<TFormat>,
+        }
+
+        impl<'de, TFormat> Visitor<'de> for ParsedBoundVisitor<TFormat>
+        where
+            TFormat: DateFormat,
+        {
+            type Value = ParsedBound<TFormat>;
+
+            fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                write!(
+                    formatter,
+                    "a json string or number containing a formatted date"
+                )
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<ParsedBound<TFormat>, E>
+            where
+                E: Error,
+            {
+                let value = TFormat::parse(v).map_err(|err| Error::custom(format!("{}", err)))?;
+
+                Ok(ParsedBound {
+                    value,
+                    _f: PhantomData,
+                })
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<ParsedBound<TFormat>, E>
+            where
+                E: Error,
+            {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<ParsedBound<TFormat>, E>
+            where
+                E: Error,
+            {
+                self.visit_str(&v.to_string())
+            }
+        }
+
+        deserializer.deserialize_any(ParsedBoundVisitor { _f: PhantomData })
+    }
+}
+
+impl<TMapping> Serialize for DateRange<TMapping>
+where
+    TMapping: DateRangeMapping,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        fn formatted<TFormat>(value: &Option<DateValue>) -> Option<FormattedBound<TFormat>> {
+            value.as_ref().map(|value| FormattedBound {
+                value,
+                _f: PhantomData,
+            })
+        }
+
+        SerializeBounds {
+            gte: formatted::<TMapping::Format>(&self.gte),
+            gt: formatted::<TMapping::Format>(&self.gt),
+            lte: formatted::<TMapping::Format>(&self.lte),
+            lt: formatted::<TMapping::Format>(&self.lt),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, TMapping> Deserialize<'de> for DateRange<TMapping>
+where
+    TMapping: DateRangeMapping,
+{
+    fn deserialize<D>(deserializer: D) -> Result<DateRange<TMapping>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bounds = DeserializeBounds::<ParsedBound<TMapping::Format>>::deserialize(deserializer)?;
+
+        Ok(DateRange {
+            gte: bounds.gte.map(|bound| bound.value),
+            gt: bounds.gt.map(|bound| bound.value),
+            lte: bounds.lte.map(|bound| bound.value),
+            lt: bounds.lt.map(|bound| bound.value),
+            _m: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+    use std::net::{
+        IpAddr,
+        Ipv4Addr,
+    };
+
+    use prelude::*;
+
+    #[derive(Default, PartialEq, Debug)]
+    struct MyIntegerRangeMapping;
+    impl IntegerRangeMapping for MyIntegerRangeMapping {}
+
+    #[test]
+    fn can_change_range_mapping() {
+        fn takes_custom_mapping(_: IntegerRange<MyIntegerRangeMapping>) -> bool {
+            true
+        }
+
+        let range: IntegerRange<DefaultIntegerRangeMapping> = IntegerRange::between(1, 5);
+
+        assert!(takes_custom_mapping(IntegerRange::remap(range)));
+    }
+
+    #[test]
+    fn serialise_elastic_ranges() {
+        let ser = vec![
+            {
+                let range = IntegerRange::<DefaultIntegerRangeMapping>::between(1, 5);
+                serde_json::to_string(&range).unwrap()
+            },
+            {
+                let mut range = LongRange::<DefaultLongRangeMapping>::new();
+                range.gt = Some(1);
+                serde_json::to_string(&range).unwrap()
+            },
+            {
+                let mut range = FloatRange::<DefaultFloatRangeMapping>::new();
+                range.lt = Some(1.5);
+                serde_json::to_string(&range).unwrap()
+            },
+            {
+                let range = DoubleRange::<DefaultDoubleRangeMapping>::new();
+                serde_json::to_string(&range).unwrap()
+            },
+            {
+                let range = IpRange::<DefaultIpRangeMapping>::between(
+                    Ipv4Addr::new(192, 168, 0, 0),
+                    Ipv4Addr::new(192, 168, 0, 255),
+                );
+                serde_json::to_string(&range).unwrap()
+            },
+        ];
+
+        let expected_ser = vec![
+            json_str!({ "gte": 1, "lte": 5 }),
+            json_str!({ "gt": 1 }),
+            json_str!({ "lt": 1.5 }),
+            json_str!({}),
+            json_str!({ "gte": "192.168.0.0", "lte": "192.168.0.255" }),
+        ];
+
+        assert_eq!(expected_ser, ser);
+    }
+
+    #[test]
+    fn deserialise_elastic_ranges() {
+        let int_de: IntegerRange<DefaultIntegerRangeMapping> =
+            serde_json::from_str(r#"{"gte":1,"lt":5}"#).unwrap();
+        let ip_de: IpRange<DefaultIpRangeMapping> =
+            serde_json::from_str(r#"{"gt":"10.0.0.1"}"#).unwrap();
+
+        assert_eq!(
+            (Some(1), None, None, Some(5)),
+            (int_de.gte, int_de.gt, int_de.lte, int_de.lt)
+        );
+        assert_eq!(Some(IpAddr::from(Ipv4Addr::new(10, 0, 0, 1))), ip_de.gt);
+    }
+
+    #[test]
+    fn serialise_elastic_date_range() {
+        let range = DateRange::<DefaultDateRangeMapping<EpochMillis>>::between(
+            DateValue::build(2015, 5, 13, 0, 0, 0, 0),
+            DateValue::build(2015, 5, 14, 0, 0, 0, 0),
+        );
+
+        let ser = serde_json::to_string(&range).unwrap();
+
+        assert_eq!(
+            json_str!({ "gte": "1431475200000", "lte": "1431561600000" }),
+            ser
+        );
+    }
+
+    #[test]
+    fn deserialise_elastic_date_range() {
+        let range: DateRange<DefaultDateRangeMapping<BasicDateTime>> =
+            serde_json::from_str(r#"{"gte":"20150513T000000.000Z"}"#).unwrap();
+
+        assert_eq!(Some(DateValue::build(2015, 5, 13, 0, 0, 0, 0)), range.gte);
+        assert_eq!(None, range.lte);
+    }
+}
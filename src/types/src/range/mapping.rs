@@ -0,0 +1,469 @@
+/*!
+Mapping for the Elasticsearch `range` types.
+
+Custom mappings can be defined by implementing the right range mapping for the type of values in the range.
+The implementation is the same for all range types, except `date_range` which also has a `Format`.
+
+# Examples
+
+Define a custom `IntegerRangeMapping`:
+
+```
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# use elastic_types::prelude::*;
+#[derive(Default)]
+struct MyIntegerRangeMapping;
+impl IntegerRangeMapping for MyIntegerRangeMapping {
+    //Overload the mapping functions here
+    fn coerce() -> Option<bool> {
+        Some(false)
+    }
+}
+# fn main() {}
+```
+
+This will produce the following mapping:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# extern crate serde_json;
+# use elastic_types::prelude::*;
+# #[derive(Default)]
+# struct MyIntegerRangeMapping;
+# impl IntegerRangeMapping for MyIntegerRangeMapping {
+#   fn coerce() -> Option<bool> {
+#       Some(false)
+#   }
+# }
+# fn main() {
+# let mapping = elastic_types::derive::standalone_field_ser(MyIntegerRangeMapping).unwrap();
+# let json = json_str!(
+{
+    "type": "integer_range",
+    "coerce": false
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+*/
+
+use date::{
+    DateFormat,
+    DefaultDateFormat,
+};
+use std::marker::PhantomData;
+
+macro_rules! range_mapping {
+    ($mapping:ident, $pivot:ident, $field_trait:ident, $datatype_name:expr, $private_mod:ident) => {
+        /** A field that will be mapped as a range. */
+        pub trait $field_trait<TMapping> {}
+
+        /** Base `range` mapping. */
+        pub trait $mapping
+        where
+            Self: Default,
+        {
+            /** Try to convert strings to numbers and truncate fractions for integers. Accepts `true` (default) and `false`. */
+            fn coerce() -> Option<bool> {
+                None
+            }
+
+            /** Field-level index time boosting. Accepts a floating point number, defaults to `1.0`. */
+            fn boost() -> Option<f32> {
+                None
+            }
+
+            /**
+            Should the field be stored on disk in a column-stride fashion,
+            so that it can later be used for sorting, aggregations, or scripting?
+            Accepts `true` (default) or `false`.
+            */
+            fn doc_values() -> Option<bool> {
+                None
+            }
+
+            /** Should the field be searchable? Accepts `true` (default) and `false`. */
+            fn index() -> Option<bool> {
+                None
+            }
+
+            /**
+            Whether the field value should be stored and retrievable separately from the `_source` field.
+            Accepts `true` or `false` (default).
+            */
+            fn store() -> Option<bool> {
+                None
+            }
+        }
+
+        mod $private_mod {
+            use super::{$field_trait, $mapping};
+            use private::field::{FieldMapping, FieldType, SerializeFieldMapping, StaticSerialize};
+            use serde::ser::SerializeStruct;
+            use serde::Serialize;
+
+            #[derive(Default)]
+            pub struct $pivot;
+
+            impl<TField, TMapping> FieldType<TMapping, $pivot> for TField
+            where
+                TField: $field_trait<TMapping> + Serialize,
+                TMapping: $mapping,
+            {
+            }
+
+            impl<TMapping> FieldMapping<$pivot> for TMapping
+            where
+                TMapping: $mapping,
+            {
+                type SerializeFieldMapping = SerializeFieldMapping<TMapping, $pivot>;
+
+                fn data_type() -> &'static str {
+                    $datatype_name
+                }
+            }
+
+            impl<TMapping> StaticSerialize for SerializeFieldMapping<TMapping, $pivot>
+            where
+                TMapping: FieldMapping<$pivot> + $mapping,
+            {
+                fn static_serialize<S>(serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: ::serde::Serializer,
+                {
+                    let mut state = try!(serializer.serialize_struct("mapping", 6));
+
+                    try!(state.serialize_field("type", TMapping::data_type()));
+
+                    ser_field!(state, "coerce", TMapping::coerce());
+                    ser_field!(state, "boost", TMapping::boost());
+                    ser_field!(state, "doc_values", TMapping::doc_values());
+                    ser_field!(state, "index", TMapping::index());
+                    ser_field!(state, "store", TMapping::store());
+
+                    state.end()
+                }
+            }
+        }
+    };
+}
+
+range_mapping!(
+    IntegerRangeMapping,
+    IntegerRangePivot,
+    IntegerRangeFieldType,
+    "integer_range",
+    private_integer_range
+);
+range_mapping!(
+    LongRangeMapping,
+    LongRangePivot,
+    LongRangeFieldType,
+    "long_range",
+    private_long_range
+);
+range_mapping!(
+    FloatRangeMapping,
+    FloatRangePivot,
+    FloatRangeFieldType,
+    "float_range",
+    private_float_range
+);
+range_mapping!(
+    DoubleRangeMapping,
+    DoubleRangePivot,
+    DoubleRangeFieldType,
+    "double_range",
+    private_double_range
+);
+range_mapping!(
+    IpRangeMapping,
+    IpRangePivot,
+    IpRangeFieldType,
+    "ip_range",
+    private_ip_range
+);
+
+/** A field that will be mapped as a `date_range`. */
+pub trait DateRangeFieldType<TMapping> {}
+
+/** Base `date_range` mapping. */
+pub trait DateRangeMapping {
+    /**
+    The date format bound to this mapping.
+
+    The value of `Format::name()` is what's sent to Elasticsearch as the format to use.
+    This is also used to serialise and deserialise the bounds of a `DateRange`.
+    */
+    type Format: DateFormat;
+
+    /** Try to convert strings to dates for the bounds of the range. Accepts `true` (default) and `false`. */
+    fn coerce() -> Option<bool> {
+        None
+    }
+
+    /** Field-level index time boosting. Accepts a floating point number, defaults to `1.0`. */
+    fn boost() -> Option<f32> {
+        None
+    }
+
+    /**
+    Should the field be stored on disk in a column-stride fashion,
+    so that it can later be used for sorting, aggregations, or scripting?
+    Accepts `true` (default) or `false`.
+    */
+    fn doc_values() -> Option<bool> {
+        None
+    }
+
+    /** Should the field be searchable? Accepts `true` (default) and `false`. */
+    fn index() -> Option<bool> {
+        None
+    }
+
+    /**
+    Whether the field value should be stored and retrievable separately from the `_source` field.
+    Accepts `true` or `false` (default).
+    */
+    fn store() -> Option<bool> {
+        None
+    }
+}
+
+/** Default mapping for an `integer_range` type. */
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub struct DefaultIntegerRangeMapping;
+impl IntegerRangeMapping for DefaultIntegerRangeMapping {}
+
+/** Default mapping for a `long_range` type. */
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub struct DefaultLongRangeMapping;
+impl LongRangeMapping for DefaultLongRangeMapping {}
+
+/** Default mapping for a `float_range` type. */
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub struct DefaultFloatRangeMapping;
+impl FloatRangeMapping for DefaultFloatRangeMapping {}
+
+/** Default mapping for a `double_range` type. */
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub struct DefaultDoubleRangeMapping;
+impl DoubleRangeMapping for DefaultDoubleRangeMapping {}
+
+/** Default mapping for an `ip_range` type. */
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub struct DefaultIpRangeMapping;
+impl IpRangeMapping for DefaultIpRangeMapping {}
+
+/** Default mapping for a `date_range` type. */
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub struct DefaultDateRangeMapping<TFormat = DefaultDateFormat>
+where
+    TFormat: DateFormat,
+{
+    _f: PhantomData<TFormat>,
+}
+
+impl<TFormat> DateRangeMapping for DefaultDateRangeMapping<TFormat>
+where
+    TFormat: DateFormat,
+{
+    type Format = TFormat;
+}
+
+mod private_date_range {
+    use super::{
+        DateRangeFieldType,
+        DateRangeMapping,
+    };
+    use date::DateFormat;
+    use private::field::{
+        FieldMapping,
+        FieldType,
+        SerializeFieldMapping,
+        StaticSerialize,
+    };
+    use serde::{
+        ser::SerializeStruct,
+        Serialize,
+        Serializer,
+    };
+
+    #[derive(Default)]
+    pub struct DateRangePivot;
+
+    impl<TField, TMapping> FieldType<TMapping, DateRangePivot> for TField
+    where
+        TField: DateRangeFieldType<TMapping> + Serialize,
+        TMapping: DateRangeMapping,
+    {
+    }
+
+    impl<TMapping> FieldMapping<DateRangePivot> for TMapping
+    where
+        TMapping: DateRangeMapping,
+    {
+        type SerializeFieldMapping = SerializeFieldMapping<TMapping, DateRangePivot>;
+
+        fn data_type() -> &'static str {
+            "date_range"
+        }
+    }
+
+    impl<TMapping> StaticSerialize for SerializeFieldMapping<TMapping, DateRangePivot>
+    where
+        TMapping: FieldMapping<DateRangePivot> + DateRangeMapping,
+    {
+        fn static_serialize<S>(serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut state = try!(serializer.serialize_struct("mapping", 7));
+
+            try!(state.serialize_field("type", TMapping::data_type()));
+            try!(state.serialize_field("format", TMapping::Format::name()));
+
+            ser_field!(state, "coerce", TMapping::coerce());
+            ser_field!(state, "boost", TMapping::boost());
+            ser_field!(state, "doc_values", TMapping::doc_values());
+            ser_field!(state, "index", TMapping::index());
+            ser_field!(state, "store", TMapping::store());
+
+            state.end()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use prelude::*;
+    use private::field;
+
+    #[derive(Default, Clone)]
+    pub struct MyIntegerRangeMapping;
+    impl IntegerRangeMapping for MyIntegerRangeMapping {
+        fn coerce() -> Option<bool> {
+            Some(true)
+        }
+
+        fn boost() -> Option<f32> {
+            Some(1.1)
+        }
+
+        fn doc_values() -> Option<bool> {
+            Some(false)
+        }
+
+        fn index() -> Option<bool> {
+            Some(false)
+        }
+
+        fn store() -> Option<bool> {
+            Some(true)
+        }
+    }
+
+    #[derive(Default, Clone)]
+    pub struct MyDateRangeMapping;
+    impl DateRangeMapping for MyDateRangeMapping {
+        type Format = EpochMillis;
+
+        fn coerce() -> Option<bool> {
+            Some(false)
+        }
+
+        fn boost() -> Option<f32> {
+            Some(1.1)
+        }
+
+        fn doc_values() -> Option<bool> {
+            Some(true)
+        }
+
+        fn index() -> Option<bool> {
+            Some(false)
+        }
+
+        fn store() -> Option<bool> {
+            Some(true)
+        }
+    }
+
+    #[test]
+    fn serialise_mapping_range_default() {
+        let ser = vec![
+            serde_json::to_string(&field::serialize(DefaultIntegerRangeMapping)).unwrap(),
+            serde_json::to_string(&field::serialize(DefaultLongRangeMapping)).unwrap(),
+            serde_json::to_string(&field::serialize(DefaultFloatRangeMapping)).unwrap(),
+            serde_json::to_string(&field::serialize(DefaultDoubleRangeMapping)).unwrap(),
+            serde_json::to_string(&field::serialize(DefaultIpRangeMapping)).unwrap(),
+        ];
+
+        let expected = vec![
+            json_str!({ "type": "integer_range" }),
+            json_str!({ "type": "long_range" }),
+            json_str!({ "type": "float_range" }),
+            json_str!({ "type": "double_range" }),
+            json_str!({ "type": "ip_range" }),
+        ];
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_range_custom() {
+        let ser = serde_json::to_string(&field::serialize(MyIntegerRangeMapping)).unwrap();
+
+        let expected = json_str!({
+            "type": "integer_range",
+            "coerce": true,
+            "boost": 1.1,
+            "doc_values": false,
+            "index": false,
+            "store": true
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_date_range_default() {
+        let ser = serde_json::to_string(&field::serialize(DefaultDateRangeMapping::<
+            DefaultDateFormat,
+        >::default()))
+        .unwrap();
+
+        let expected = json_str!({
+            "type": "date_range",
+            "format": "basic_date_time"
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_date_range_custom() {
+        let ser = serde_json::to_string(&field::serialize(MyDateRangeMapping)).unwrap();
+
+        let expected = json_str!({
+            "type": "date_range",
+            "format": "epoch_millis",
+            "coerce": false,
+            "boost": 1.1,
+            "doc_values": true,
+            "index": false,
+            "store": true
+        });
+
+        assert_eq!(expected, ser);
+    }
+}
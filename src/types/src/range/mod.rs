@@ -0,0 +1,68 @@
+/*!
+Implementation of the Elasticsearch `range` types.
+
+Range fields contain a range of values instead of a single value, like the dates a document is valid for.
+Each bound of a range is optional, so a range can be open on either side.
+
+Range types come in a number of flavours that correspond to the type of values they contain:
+
+Rust | Elasticsearch
+------ | ------------------
+`IntegerRange` | `integer_range`
+`LongRange` | `long_range`
+`FloatRange` | `float_range`
+`DoubleRange` | `double_range`
+`DateRange` | `date_range`
+`IpRange` | `ip_range`
+
+# Examples
+
+For defining your own range mapping, see [mapping details](mapping/index.html).
+
+Map a range of dates:
+
+```
+# use elastic_types::prelude::*;
+struct MyType {
+    pub valid: DateRange<DefaultDateRangeMapping>
+}
+```
+
+Create a range of integers:
+
+```
+# use elastic_types::prelude::*;
+let range = IntegerRange::<DefaultIntegerRangeMapping>::between(18, 65);
+```
+
+Create a range with an exclusive upper bound:
+
+```
+# use elastic_types::prelude::*;
+let mut range = LongRange::<DefaultLongRangeMapping>::new();
+range.gte = Some(0);
+range.lt = Some(100);
+```
+
+# Links
+
+- [Elasticsearch Doc](https://www.elastic.co/guide/en/elasticsearch/reference/current/range.html)
+*/
+
+pub mod mapping;
+
+mod impls;
+pub use self::impls::*;
+
+pub mod prelude {
+    /*!
+    Includes all types for the `range` types.
+
+    This is a convenience module to make it easy to build mappings for multiple types without too many `use` statements.
+    */
+
+    pub use super::{
+        impls::*,
+        mapping::*,
+    };
+}
@@ -2,6 +2,7 @@ use super::mapping::{
     KeywordFieldType,
     KeywordMapping,
 };
+use document::DocumentId;
 use serde::{
     de::{
         Error,
@@ -13,7 +14,10 @@ use serde::{
     Serializer,
 };
 use std::{
-    borrow::Borrow,
+    borrow::{
+        Borrow,
+        Cow,
+    },
     marker::PhantomData,
 };
 
@@ -70,6 +74,26 @@ where
         }
     }
 
+    /**
+    Get the value normalized for a term-level query on this field.
+
+    # Examples
+
+    Keywords with a `lowercase` normalizer are lowercased:
+
+    ```
+    use elastic_types::string::keyword::mapping::LowercaseKeywordMapping;
+    use elastic_types::string::keyword::Keyword;
+
+    let string = Keyword::<LowercaseKeywordMapping>::new("Kimchy");
+
+    assert_eq!("kimchy", string.normalized());
+    ```
+    */
+    pub fn normalized(&self) -> Cow<'_, str> {
+        TMapping::normalize_term(&self.value)
+    }
+
     /** Change the mapping of this string. */
     pub fn remap<TNewMapping>(keyword: Keyword<TMapping>) -> Keyword<TNewMapping>
     where
@@ -80,3 +104,15 @@ where
 }
 
 impl_string_type!(Keyword, KeywordMapping, KeywordFieldType);
+
+impl<TMapping> DocumentId for Keyword<TMapping>
+where
+    TMapping: KeywordMapping,
+{
+    fn document_id(&self) -> Option<Cow<str>> {
+        Some(Cow::Borrowed(&self.value))
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl KeywordFieldType<super::mapping::DefaultKeywordMapping> for ::uuid::Uuid {}
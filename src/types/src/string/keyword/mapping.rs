@@ -6,7 +6,10 @@ use serde::{
     Serialize,
     Serializer,
 };
-use std::collections::BTreeMap;
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+};
 use string::mapping::{
     IndexOptions,
     StringField,
@@ -89,6 +92,14 @@ pub trait KeywordMapping {
         None
     }
 
+    /**
+    The names of other fields to copy the values of this field into.
+    Copying several fields into one lets them be searched together as a single field.
+    */
+    fn copy_to() -> Option<&'static [&'static str]> {
+        None
+    }
+
     /**
     Should the field be stored on disk in a column-stride fashion,
     so that it can later be used for sorting, aggregations, or scripting?
@@ -184,6 +195,30 @@ pub trait KeywordMapping {
         None
     }
 
+    /**
+    The normalizer to apply to values before they're indexed, like `lowercase`.
+    Values in term-level queries on the field are normalized the same way.
+    */
+    fn normalizer() -> Option<&'static str> {
+        None
+    }
+
+    /**
+    Normalize a value to search for in a term-level query on this field.
+
+    Values are lowercased when the `normalizer` is `lowercase`, so a `term` query for `Kimchy` will match an indexed `kimchy`.
+    Other normalizers are defined in the index settings, so values are returned unchanged.
+    Override this method if the field uses a custom normalizer that can be applied to query values.
+    */
+    fn normalize_term<'a>(term: &'a str) -> Cow<'a, str> {
+        match Self::normalizer() {
+            Some(LOWERCASE_NORMALIZER) if term.chars().any(char::is_uppercase) => {
+                Cow::Owned(term.to_lowercase())
+            }
+            _ => Cow::Borrowed(term),
+        }
+    }
+
     /**
     Accepts a `string` value which is substituted for any explicit null values.
     Defaults to `null`, which means the field is treated as missing.
@@ -217,11 +252,28 @@ pub trait KeywordMapping {
     }
 }
 
+/** The name of the normalizer that lowercases `keyword` values. */
+pub const LOWERCASE_NORMALIZER: &str = "lowercase";
+
 /** Default mapping for `bool`. */
 #[derive(PartialEq, Debug, Default, Clone, Copy)]
 pub struct DefaultKeywordMapping;
 impl KeywordMapping for DefaultKeywordMapping {}
 
+/**
+Mapping for a `keyword` that's matched case-insensitively.
+
+Values are indexed with the `lowercase` normalizer, and values in term-level queries are lowercased before they're sent.
+The `lowercase` normalizer needs to be available in the index, either as a built-in normalizer or defined in the index analysis settings.
+*/
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub struct LowercaseKeywordMapping;
+impl KeywordMapping for LowercaseKeywordMapping {
+    fn normalizer() -> Option<&'static str> {
+        Some(LOWERCASE_NORMALIZER)
+    }
+}
+
 /** A multi-field string mapping. */
 #[derive(Debug, Default, Clone, Copy)]
 pub struct KeywordFieldMapping {
@@ -261,6 +313,8 @@ pub struct KeywordFieldMapping {
     pub index_options: Option<IndexOptions>,
     /** Whether field-length should be taken into account when scoring queries. Accepts `true` (default) or `false`. */
     pub norms: Option<bool>,
+    /** The normalizer to apply to values before they're indexed, like `lowercase`. */
+    pub normalizer: Option<&'static str>,
     /**
     Whether the field value should be stored and retrievable separately from the `_source` field.
     Accepts `true` or `false` (default).
@@ -283,7 +337,7 @@ impl Serialize for KeywordFieldMapping {
     where
         S: Serializer,
     {
-        let mut state = try!(serializer.serialize_struct("mapping", 12));
+        let mut state = try!(serializer.serialize_struct("mapping", 13));
 
         try!(state.serialize_field("type", DefaultKeywordMapping::data_type()));
 
@@ -295,6 +349,7 @@ impl Serialize for KeywordFieldMapping {
         ser_field!(state, "index", self.index);
         ser_field!(state, "index_options", self.index_options);
         ser_field!(state, "norms", self.norms);
+        ser_field!(state, "normalizer", self.normalizer);
         ser_field!(state, "store", self.store);
         ser_field!(state, "search_analyzer", self.search_analyzer);
         ser_field!(state, "similarity", self.similarity);
@@ -308,6 +363,7 @@ mod private {
         KeywordFieldType,
         KeywordMapping,
     };
+    use derive::NullValueMapping;
     use private::field::{
         FieldMapping,
         FieldType,
@@ -319,6 +375,7 @@ mod private {
         Serialize,
         Serializer,
     };
+    use serde_json::Value;
 
     #[derive(Default)]
     pub struct KeywordPivot;
@@ -349,12 +406,13 @@ mod private {
         where
             S: Serializer,
         {
-            let mut state = try!(serializer.serialize_struct("mapping", 15));
+            let mut state = try!(serializer.serialize_struct("mapping", 17));
 
             try!(state.serialize_field("type", TMapping::data_type()));
 
             ser_field!(state, "boost", TMapping::boost());
             ser_field!(state, "analyzer", TMapping::analyzer());
+            ser_field!(state, "copy_to", TMapping::copy_to());
             ser_field!(state, "doc_values", TMapping::doc_values());
             ser_field!(
                 state,
@@ -367,6 +425,7 @@ mod private {
             ser_field!(state, "index", TMapping::index());
             ser_field!(state, "index_options", TMapping::index_options());
             ser_field!(state, "norms", TMapping::norms());
+            ser_field!(state, "normalizer", TMapping::normalizer());
             ser_field!(state, "null_value", TMapping::null_value());
             ser_field!(state, "store", TMapping::store());
             ser_field!(state, "search_analyzer", TMapping::search_analyzer());
@@ -375,4 +434,15 @@ mod private {
             state.end()
         }
     }
+
+    impl<TMapping> NullValueMapping<KeywordPivot> for TMapping
+    where
+        TMapping: KeywordMapping,
+    {
+        type NullValue = &'static str;
+
+        fn to_null_value(value: &'static str) -> Result<Value, String> {
+            Ok(Value::from(value))
+        }
+    }
 }
@@ -14,7 +14,11 @@ use serde::{
 };
 use std::collections::BTreeMap;
 
-/** Default mapping for `String`. */
+/**
+Default mapping for `String`.
+
+The field is mapped as `text`, with a `keyword` sub-field for values up to 256 characters.
+*/
 #[derive(PartialEq, Debug, Default, Clone, Copy)]
 pub struct DefaultStringMapping;
 impl TextMapping for DefaultStringMapping {
@@ -100,7 +104,54 @@ impl Serialize for StringField {
     }
 }
 
-/** A multi-field string mapping for a [token count](https://www.elastic.co/guide/en/elasticsearch/reference/current/token-count.html). */
+/**
+A multi-field string mapping for a [token count](https://www.elastic.co/guide/en/elasticsearch/reference/current/token-count.html).
+
+A `token_count` sub-field indexes the number of tokens in a string, so documents can be filtered or sorted by the length of a field.
+
+# Examples
+
+Add a `token_count` sub-field that uses the `standard` analyzer:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# use std::collections::BTreeMap;
+# use elastic_types::prelude::*;
+#[derive(Default)]
+struct MyTextMapping;
+impl TextMapping for MyTextMapping {
+    fn fields() -> Option<BTreeMap<&'static str, StringField>> {
+        let mut fields = BTreeMap::new();
+
+        fields.insert("length", StringField::TokenCount(ElasticTokenCountFieldMapping {
+            analyzer: Some("standard"),
+            ..Default::default()
+        }));
+
+        Some(fields)
+    }
+}
+# fn main() {
+# let mapping = elastic_types::derive::standalone_field_ser(MyTextMapping).unwrap();
+# let json = json_str!(
+{
+    "type": "text",
+    "fields": {
+        "length": {
+            "type": "token_count",
+            "analyzer": "standard"
+        }
+    }
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+*/
 #[derive(Debug, Default, Clone, Copy)]
 pub struct ElasticTokenCountFieldMapping {
     /**
@@ -117,6 +168,12 @@ pub struct ElasticTokenCountFieldMapping {
     Accepts `true` (default) or `false`.
     */
     pub doc_values: Option<bool>,
+    /**
+    Whether position increments should be counted.
+    Set to `false` to ignore tokens removed by analyzer filters, like stop words.
+    Defaults to `true`.
+    */
+    pub enable_position_increments: Option<bool>,
     /** Should the field be searchable? Accepts `not_analyzed` (default) and `no`. */
     pub index: Option<IndexAnalysis>,
     /**
@@ -127,6 +184,11 @@ pub struct ElasticTokenCountFieldMapping {
     */
     pub include_in_all: Option<bool>,
     /**
+    Accepts a numeric value of the same type as the field which is substituted for any explicit null values.
+    Defaults to `null`, which means the field is treated as missing.
+    */
+    pub null_value: Option<u32>,
+    /**
     Controls the number of extra terms that are indexed to make range queries faster.
     Defaults to `32`.
     */
@@ -143,15 +205,21 @@ impl Serialize for ElasticTokenCountFieldMapping {
     where
         S: Serializer,
     {
-        let mut state = try!(serializer.serialize_struct("mapping", 8));
+        let mut state = try!(serializer.serialize_struct("mapping", 10));
 
         try!(state.serialize_field("type", "token_count"));
 
         ser_field!(state, "analyzer", self.analyzer);
         ser_field!(state, "boost", self.boost);
         ser_field!(state, "doc_values", self.doc_values);
+        ser_field!(
+            state,
+            "enable_position_increments",
+            self.enable_position_increments
+        );
         ser_field!(state, "index", self.index);
         ser_field!(state, "include_in_all", self.include_in_all);
+        ser_field!(state, "null_value", self.null_value);
         ser_field!(state, "precision_step", self.precision_step);
         ser_field!(state, "store", self.store);
 
@@ -422,6 +490,10 @@ mod tests {
             Some(false)
         }
 
+        fn normalizer() -> Option<&'static str> {
+            Some("my_normalizer")
+        }
+
         fn null_value() -> Option<&'static str> {
             Some("my string")
         }
@@ -546,6 +618,7 @@ mod tests {
             "index": true,
             "index_options": "docs",
             "norms": false,
+            "normalizer": "my_normalizer",
             "null_value": "my string",
             "store": false,
             "search_analyzer": "my_analyzer",
@@ -555,6 +628,18 @@ mod tests {
         assert_eq!(expected, ser);
     }
 
+    #[test]
+    fn serialise_keyword_mapping_lowercase() {
+        let ser = serde_json::to_string(&field::serialize(LowercaseKeywordMapping)).unwrap();
+
+        let expected = json_str!({
+            "type": "keyword",
+            "normalizer": "lowercase"
+        });
+
+        assert_eq!(expected, ser);
+    }
+
     #[test]
     fn serialise_mapping_field_filter() {
         let filter = FieldDataFrequencyFilter {
@@ -642,6 +727,7 @@ mod tests {
             index: Some(false),
             index_options: Some(IndexOptions::Docs),
             norms: Some(true),
+            normalizer: Some("my_normalizer"),
             store: Some(true),
             search_analyzer: Some("my_analyzer"),
             similarity: Some("my_analyzer"),
@@ -658,6 +744,7 @@ mod tests {
             "index":false,
             "index_options":"docs",
             "norms":true,
+            "normalizer":"my_normalizer",
             "store":true,
             "search_analyzer":"my_analyzer",
             "similarity":"my_analyzer"
@@ -720,8 +807,10 @@ mod tests {
             analyzer: Some("my_analyzer"),
             boost: Some(1.3),
             doc_values: Some(false),
+            enable_position_increments: Some(false),
             index: Some(IndexAnalysis::No),
             include_in_all: Some(true),
+            null_value: Some(0),
             precision_step: Some(15),
             store: Some(true),
         });
@@ -732,8 +821,10 @@ mod tests {
             "analyzer": "my_analyzer",
             "boost": 1.3,
             "doc_values": false,
+            "enable_position_increments": false,
             "index": "no",
             "include_in_all": true,
+            "null_value": 0,
             "precision_step": 15,
             "store": true
         });
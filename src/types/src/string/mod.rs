@@ -10,6 +10,9 @@ They're useful for blobs of content that can be sliced in various ways, like pro
 
 As far as serialisation is concerned, `keyword` and `text` are equivalent.
 
+Elasticsearch no longer has a single `string` type, so a plain `String` is mapped as `text`
+with a `keyword` sub-field that can be used for sorting and aggregations.
+
 # Examples
 
 For defining your own string mapping, see:
@@ -17,7 +20,7 @@ For defining your own string mapping, see:
 - [keyword mapping details](keyword/mapping/trait.KeywordMapping.html#derive-mapping)
 - [text mapping details](text/mapping/trait.TextMapping.html#derive-mapping).
 
-Map with a default `string` (follows the semantics for legacy `string` mapping):
+Map a `String` as `text` with a `keyword` sub-field:
 
 ```
 struct MyType {
@@ -150,6 +153,47 @@ mod tests {
         assert_eq!("my string", string);
     }
 
+    #[test]
+    fn normalize_elastic_keyword() {
+        let lowercase: Keyword<LowercaseKeywordMapping> = Keyword::new("My String");
+        let default: Keyword<DefaultKeywordMapping> = Keyword::new("My String");
+
+        assert_eq!("my string", lowercase.normalized());
+        assert_eq!("My String", default.normalized());
+        assert_eq!("my string", MyKeywordMapping::normalize_term("my string"));
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn serialise_uuid_keyword() {
+        use uuid::Uuid;
+
+        #[derive(Serialize, ElasticType)]
+        struct UuidType {
+            id: Uuid,
+        }
+
+        let ser = serde_json::to_string(&UuidType::index_mapping()).unwrap();
+
+        let expected = json_str!({
+            "properties": {
+                "id": {
+                    "type": "keyword"
+                }
+            }
+        });
+
+        assert_eq!(expected, ser);
+
+        let doc = UuidType {
+            id: Uuid::parse_str("936da01f9abd4d9d80c702af85c822a8").unwrap(),
+        };
+
+        let ser = serde_json::to_string(&doc).unwrap();
+
+        assert_eq!(r#"{"id":"936da01f-9abd-4d9d-80c7-02af85c822a8"}"#, ser);
+    }
+
     #[test]
     fn can_change_text_mapping() {
         fn takes_custom_mapping(_: Text<MyTextMapping>) -> bool {
@@ -89,6 +89,14 @@ pub trait TextMapping {
         None
     }
 
+    /**
+    The names of other fields to copy the values of this field into.
+    Copying several fields into one lets them be searched together as a single field.
+    */
+    fn copy_to() -> Option<&'static [&'static str]> {
+        None
+    }
+    
     /**
     Should global ordinals be loaded eagerly on refresh?
     Accepts `true` or `false` (default).
@@ -457,11 +465,12 @@ mod private {
         where
             S: Serializer,
         {
-            let mut state = try!(serializer.serialize_struct("mapping", 18));
+            let mut state = try!(serializer.serialize_struct("mapping", 19));
 
             try!(state.serialize_field("type", TMapping::data_type()));
 
             ser_field!(state, "boost", TMapping::boost());
+            ser_field!(state, "copy_to", TMapping::copy_to());
             ser_field!(state, "analyzer", TMapping::analyzer());
             ser_field!(
                 state,
@@ -0,0 +1,203 @@
+use super::mapping::{
+    DenseVectorFieldType,
+    DenseVectorMapping,
+};
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use std::{
+    borrow::Borrow,
+    marker::PhantomData,
+};
+
+/**
+An Elasticsearch `dense_vector` with a mapping.
+
+A `DenseVector` is serialised as an array of floats.
+The number of dimensions is part of the mapping, so vectors in a field should all have the same length.
+
+# Examples
+
+Defining a `dense_vector` with a mapping:
+
+```
+# extern crate serde;
+# #[macro_use]
+# extern crate elastic_types;
+# fn main() {
+# use elastic_types::prelude::*;
+# #[derive(Default)]
+# struct MyDenseVectorMapping;
+# impl DenseVectorMapping for MyDenseVectorMapping { fn dims() -> u32 { 3 } }
+let vector = DenseVector::<MyDenseVectorMapping>::new(vec![0.5, 10.0, 6.0]);
+# }
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct DenseVector<TMapping>
+where
+    TMapping: DenseVectorMapping,
+{
+    value: Vec<f32>,
+    _m: PhantomData<TMapping>,
+}
+
+impl<TMapping> DenseVector<TMapping>
+where
+    TMapping: DenseVectorMapping,
+{
+    /**
+    Creates a new `DenseVector` with the given mapping.
+
+    # Examples
+
+    Create a new `DenseVector` from a `Vec<f32>`:
+
+    ```
+    # extern crate serde;
+    # #[macro_use]
+    # extern crate elastic_types;
+    # fn main() {
+    # use elastic_types::prelude::*;
+    # #[derive(Default)]
+    # struct MyDenseVectorMapping;
+    # impl DenseVectorMapping for MyDenseVectorMapping { fn dims() -> u32 { 3 } }
+    let vector = DenseVector::<MyDenseVectorMapping>::new(vec![0.5, 10.0, 6.0]);
+    # }
+    ```
+    */
+    pub fn new<I>(vector: I) -> DenseVector<TMapping>
+    where
+        I: Into<Vec<f32>>,
+    {
+        DenseVector {
+            value: vector.into(),
+            _m: PhantomData,
+        }
+    }
+
+    /**
+    Change the mapping of this vector.
+
+    # Examples
+
+    Change the mapping for a given `DenseVector`:
+
+    ```
+    # extern crate serde;
+    # #[macro_use]
+    # extern crate elastic_types;
+    # fn main() {
+    # use elastic_types::prelude::*;
+    # #[derive(Default)]
+    # struct MyDenseVectorMapping;
+    # impl DenseVectorMapping for MyDenseVectorMapping { fn dims() -> u32 { 3 } }
+    # #[derive(Default)]
+    # struct MyIndexedDenseVectorMapping;
+    # impl DenseVectorMapping for MyIndexedDenseVectorMapping { fn dims() -> u32 { 3 } }
+    let vector = DenseVector::<MyDenseVectorMapping>::new(vec![0.5, 10.0, 6.0]);
+
+    let vector: DenseVector<MyIndexedDenseVectorMapping> = DenseVector::remap(vector);
+    # }
+    ```
+    */
+    pub fn remap<TNewMapping>(vector: DenseVector<TMapping>) -> DenseVector<TNewMapping>
+    where
+        TNewMapping: DenseVectorMapping,
+    {
+        DenseVector::new(vector.value)
+    }
+}
+
+impl<TMapping> DenseVectorFieldType<TMapping> for DenseVector<TMapping> where
+    TMapping: DenseVectorMapping
+{
+}
+
+impl_mapping_type!(Vec<f32>, DenseVector, DenseVectorMapping);
+
+// Serialize elastic dense vector
+impl<TMapping> Serialize for DenseVector<TMapping>
+where
+    TMapping: DenseVectorMapping,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.value.serialize(serializer)
+    }
+}
+
+// Deserialize elastic dense vector
+impl<'de, TMapping> Deserialize<'de> for DenseVector<TMapping>
+where
+    TMapping: DenseVectorMapping,
+{
+    fn deserialize<D>(deserializer: D) -> Result<DenseVector<TMapping>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = try!(Vec::<f32>::deserialize(deserializer));
+
+        Ok(DenseVector::new(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use prelude::*;
+
+    #[derive(Default)]
+    struct MyDenseVectorMapping;
+    impl DenseVectorMapping for MyDenseVectorMapping {
+        fn dims() -> u32 {
+            3
+        }
+    }
+
+    #[derive(Default)]
+    struct MyIndexedDenseVectorMapping;
+    impl DenseVectorMapping for MyIndexedDenseVectorMapping {
+        fn dims() -> u32 {
+            3
+        }
+
+        fn index() -> Option<bool> {
+            Some(true)
+        }
+    }
+
+    #[test]
+    fn can_change_dense_vector_mapping() {
+        fn takes_custom_mapping(_: DenseVector<MyIndexedDenseVectorMapping>) -> bool {
+            true
+        }
+
+        let vector: DenseVector<MyDenseVectorMapping> = DenseVector::new(vec![0.5, 10.0, 6.0]);
+
+        assert!(takes_custom_mapping(DenseVector::remap(vector)));
+    }
+
+    #[test]
+    fn serialise_elastic_dense_vector() {
+        let vector: DenseVector<MyDenseVectorMapping> = DenseVector::new(vec![0.5, 10.0, 6.0]);
+
+        let ser = serde_json::to_string(&vector).unwrap();
+
+        assert_eq!("[0.5,10.0,6.0]", ser);
+    }
+
+    #[test]
+    fn deserialise_elastic_dense_vector() {
+        let vector: DenseVector<MyDenseVectorMapping> =
+            serde_json::from_str("[0.5,10,6.0]").unwrap();
+
+        assert_eq!(vec![0.5, 10.0, 6.0], *vector);
+    }
+}
@@ -0,0 +1,253 @@
+/*! Mapping for the Elasticsearch `dense_vector` type. */
+
+use serde::{
+    Serialize,
+    Serializer,
+};
+
+/** A field that will be mapped as a `dense_vector`. */
+pub trait DenseVectorFieldType<TMapping> {}
+
+/**
+The base requirements for mapping a `dense_vector` type.
+
+Custom mappings can be defined by implementing `DenseVectorMapping`.
+There's no default mapping because the number of dimensions must always be supplied.
+
+# Examples
+
+Define a custom `DenseVectorMapping`:
+
+```
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# use elastic_types::prelude::*;
+#[derive(Default)]
+struct MyDenseVectorMapping;
+impl DenseVectorMapping for MyDenseVectorMapping {
+    fn dims() -> u32 {
+        3
+    }
+
+    //Overload the mapping functions here
+    fn similarity() -> Option<Similarity> {
+        Some(Similarity::Cosine)
+    }
+}
+# fn main() {}
+```
+
+This will produce the following mapping:
+
+```
+# #[macro_use]
+# extern crate json_str;
+# #[macro_use]
+# extern crate elastic_types;
+# extern crate serde;
+# extern crate serde_json;
+# use elastic_types::prelude::*;
+# #[derive(Default)]
+# struct MyDenseVectorMapping;
+# impl DenseVectorMapping for MyDenseVectorMapping {
+#     fn dims() -> u32 {
+#         3
+#     }
+#     fn similarity() -> Option<Similarity> {
+#         Some(Similarity::Cosine)
+#     }
+# }
+# fn main() {
+# let mapping = elastic_types::derive::standalone_field_ser(MyDenseVectorMapping).unwrap();
+# let json = json_str!(
+{
+    "type": "dense_vector",
+    "dims": 3,
+    "similarity": "cosine"
+}
+# );
+# assert_eq!(json, mapping);
+# }
+```
+*/
+pub trait DenseVectorMapping
+where
+    Self: Default,
+{
+    /** The number of dimensions in each vector. */
+    fn dims() -> u32;
+
+    /**
+    Should the vector be indexed for kNN search? Accepts `true` or `false` (default).
+    A `similarity` is required when the vector is indexed.
+    */
+    fn index() -> Option<bool> {
+        None
+    }
+
+    /** The vector similarity metric to use in kNN search. */
+    fn similarity() -> Option<Similarity> {
+        None
+    }
+}
+
+/** The vector similarity metric used to score documents in kNN search. */
+#[derive(Debug, Clone, Copy)]
+pub enum Similarity {
+    /** Computes similarity based on the L2 distance (also known as Euclidean distance) between the vectors. */
+    L2Norm,
+    /**
+    Computes the dot product of two vectors.
+    All vectors, including the query vector, must be normalised to unit length.
+    */
+    DotProduct,
+    /** Computes the cosine similarity between the vectors. */
+    Cosine,
+}
+
+impl Serialize for Similarity {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(match *self {
+            Similarity::L2Norm => "l2_norm",
+            Similarity::DotProduct => "dot_product",
+            Similarity::Cosine => "cosine",
+        })
+    }
+}
+
+mod private {
+    use super::{
+        DenseVectorFieldType,
+        DenseVectorMapping,
+    };
+    use private::field::{
+        FieldMapping,
+        FieldType,
+        SerializeFieldMapping,
+        StaticSerialize,
+    };
+    use serde::{
+        ser::SerializeStruct,
+        Serialize,
+        Serializer,
+    };
+
+    #[derive(Default)]
+    pub struct DenseVectorPivot;
+
+    impl<TField, TMapping> FieldType<TMapping, DenseVectorPivot> for TField
+    where
+        TField: DenseVectorFieldType<TMapping> + Serialize,
+        TMapping: DenseVectorMapping,
+    {
+    }
+
+    impl<TMapping> FieldMapping<DenseVectorPivot> for TMapping
+    where
+        TMapping: DenseVectorMapping,
+    {
+        type SerializeFieldMapping = SerializeFieldMapping<TMapping, DenseVectorPivot>;
+
+        fn data_type() -> &'static str {
+            "dense_vector"
+        }
+    }
+
+    impl<TMapping> StaticSerialize for SerializeFieldMapping<TMapping, DenseVectorPivot>
+    where
+        TMapping: FieldMapping<DenseVectorPivot> + DenseVectorMapping,
+    {
+        fn static_serialize<S>(serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut state = try!(serializer.serialize_struct("mapping", 4));
+
+            try!(state.serialize_field("type", TMapping::data_type()));
+            try!(state.serialize_field("dims", &TMapping::dims()));
+
+            ser_field!(state, "index", TMapping::index());
+            ser_field!(state, "similarity", TMapping::similarity());
+
+            state.end()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use prelude::*;
+    use private::field;
+
+    #[derive(Default, Clone)]
+    pub struct MyDenseVectorMapping;
+    impl DenseVectorMapping for MyDenseVectorMapping {
+        fn dims() -> u32 {
+            3
+        }
+    }
+
+    #[derive(Default, Clone)]
+    pub struct MyIndexedDenseVectorMapping;
+    impl DenseVectorMapping for MyIndexedDenseVectorMapping {
+        fn dims() -> u32 {
+            128
+        }
+
+        fn index() -> Option<bool> {
+            Some(true)
+        }
+
+        fn similarity() -> Option<Similarity> {
+            Some(Similarity::DotProduct)
+        }
+    }
+
+    #[test]
+    fn serialise_mapping_default() {
+        let ser = serde_json::to_string(&field::serialize(MyDenseVectorMapping)).unwrap();
+
+        let expected = json_str!({
+            "type": "dense_vector",
+            "dims": 3
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_custom() {
+        let ser = serde_json::to_string(&field::serialize(MyIndexedDenseVectorMapping)).unwrap();
+
+        let expected = json_str!({
+            "type": "dense_vector",
+            "dims": 128,
+            "index": true,
+            "similarity": "dot_product"
+        });
+
+        assert_eq!(expected, ser);
+    }
+
+    #[test]
+    fn serialise_mapping_similarity() {
+        let ser: Vec<String> = vec![
+            Similarity::L2Norm,
+            Similarity::DotProduct,
+            Similarity::Cosine,
+        ]
+        .iter()
+        .map(|similarity| serde_json::to_string(similarity).unwrap())
+        .collect();
+
+        let expected = vec![r#""l2_norm""#, r#""dot_product""#, r#""cosine""#];
+
+        assert_eq!(expected, ser);
+    }
+}
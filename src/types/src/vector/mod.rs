@@ -0,0 +1,69 @@
+/*!
+Implementation of the Elasticsearch `dense_vector` type.
+
+A `dense_vector` field stores a fixed-length array of floats, like an embedding produced by a machine learning model.
+Indexed vectors can be searched for their nearest neighbours using kNN search.
+
+# Examples
+
+For defining your own dense vector mapping, see [mapping details](mapping/trait.DenseVectorMapping.html).
+
+Map with a custom `dense_vector`:
+
+```
+# extern crate serde;
+# #[macro_use]
+# extern crate elastic_types;
+# fn main() {
+# use elastic_types::prelude::*;
+# #[derive(Default)]
+# struct MyDenseVectorMapping;
+# impl DenseVectorMapping for MyDenseVectorMapping { fn dims() -> u32 { 3 } }
+struct MyType {
+    pub field: DenseVector<MyDenseVectorMapping>
+}
+# }
+```
+
+Map a custom type as a `dense_vector` field:
+
+```
+# extern crate serde;
+# #[macro_use]
+# extern crate elastic_types;
+# #[macro_use]
+# extern crate serde_derive;
+# fn main() {
+# use elastic_types::prelude::*;
+# #[derive(Default)]
+# struct MyDenseVectorMapping;
+# impl DenseVectorMapping for MyDenseVectorMapping { fn dims() -> u32 { 3 } }
+#[derive(Serialize)]
+struct MyDenseVectorField([f32; 3]);
+
+impl DenseVectorFieldType<MyDenseVectorMapping> for MyDenseVectorField {}
+# }
+```
+
+# Links
+
+- [Elasticsearch Doc](https://www.elastic.co/guide/en/elasticsearch/reference/current/dense-vector.html)
+*/
+
+pub mod mapping;
+
+mod impls;
+pub use self::impls::*;
+
+pub mod prelude {
+    /*!
+    Includes all types for the `dense_vector` type.
+
+    This is a convenience module to make it easy to build mappings for multiple types without too many `use` statements.
+    */
+
+    pub use super::{
+        impls::*,
+        mapping::*,
+    };
+}
@@ -50,4 +50,17 @@ impl DerivedDocument3 {
     }
 }
 
+#[derive(ElasticType)]
+pub struct DerivedNewtype(String);
+
+#[derive(ElasticType)]
+#[elastic(mapping = "elastic_types::prelude::DefaultKeywordMapping")]
+pub struct DerivedNewtypeWithMapping(String);
+
+#[derive(ElasticType)]
+pub struct DerivedDocument4 {
+    pub field1: DerivedNewtype,
+    pub field2: Vec<DerivedNewtypeWithMapping>,
+}
+
 fn main() {}
@@ -1,12 +1,15 @@
-use quote::Tokens;
+use proc_macro2::{
+    Span,
+    TokenStream,
+};
 use syn;
 
 mod parse;
 
 use super::{
+    check_elastic_attrs,
     expect_name_value,
     get_elastic_meta_items,
-    get_str_from_lit,
 };
 
 /**
@@ -16,50 +19,83 @@ The input must satisfy the following rules:
 
 - It must be a unit struct.
 - It must have an `#[elastic(date_format="<value>")]` attribute.
+
+The format can contain alternatives separated by `||`.
+Dates are parsed with the first alternative that matches, and formatted with the first alternative.
 */
 pub fn expand_derive(
-    crate_root: Tokens,
-    input: &syn::MacroInput,
-) -> Result<Vec<Tokens>, DeriveDateFormatError> {
+    crate_root: TokenStream,
+    input: &syn::DeriveInput,
+) -> Result<Vec<TokenStream>, DeriveDateFormatError> {
     // Annotatable item for a unit struct
-    match input.body {
-        syn::Body::Struct(ref data) => match *data {
-            syn::VariantData::Unit => Ok(()),
-            _ => Err(DeriveDateFormatError::InvalidInput),
+    match input.data {
+        syn::Data::Struct(ref data) => match data.fields {
+            syn::Fields::Unit => Ok(()),
+            _ => Err(DeriveDateFormatError::InvalidInput(input.ident.span())),
         },
-        _ => Err(DeriveDateFormatError::InvalidInput),
+        _ => Err(DeriveDateFormatError::InvalidInput(input.ident.span())),
     }?;
 
-    let format = get_format_from_attr(input).ok_or(DeriveDateFormatError::MissingFormat)?;
+    check_elastic_attrs(&input.attrs)?;
+
+    let format_lit = get_format_from_attr(input)
+        .ok_or_else(|| DeriveDateFormatError::MissingFormat(input.ident.span()))?;
+    let format = format_lit.value();
 
     let name = get_name_from_attr(input).unwrap_or_else(|| format.clone());
 
-    let tokens: Vec<Tokens> = parse::to_tokens(&format)?
+    let formats: Vec<Vec<TokenStream>> = parse::to_formats(&format)
+        .map_err(|err| DeriveDateFormatError::InvalidFormat(err, format_lit.span()))?
         .into_iter()
-        .map(|t| t.into_tokens(&crate_root))
+        .map(|tokens| {
+            tokens
+                .into_iter()
+                .map(|t| t.into_tokens(&crate_root))
+                .collect()
+        })
         .collect();
 
-    let derived = impl_date_format(crate_root, input, &name, &tokens);
+    let derived = impl_date_format(crate_root, input, &name, &formats);
 
     Ok(vec![derived])
 }
 
 // Implement DateFormat for the type being derived with the mapping
 fn impl_date_format(
-    crate_root: Tokens,
-    item: &syn::MacroInput,
+    crate_root: TokenStream,
+    item: &syn::DeriveInput,
     name: &str,
-    format: &[Tokens],
-) -> Tokens {
+    formats: &[Vec<TokenStream>],
+) -> TokenStream {
     let ty = &item.ident;
 
-    let parse_fn = quote!(
-        fn parse(date: &str) -> ::std::result::Result<#crate_root::derive::DateValue, #crate_root::derive::ParseError> {
-            let fmt = vec![ #(#format),* ];
+    let parse_fn = if formats.len() == 1 {
+        let format = &formats[0];
 
-            #crate_root::derive::parse_from_tokens(date, fmt)
-        }
-    );
+        quote!(
+            fn parse(date: &str) -> ::std::result::Result<#crate_root::derive::DateValue, #crate_root::derive::ParseError> {
+                let fmt = vec![ #(#format),* ];
+
+                #crate_root::derive::parse_from_tokens(date, fmt)
+            }
+        )
+    } else {
+        let fmts: Vec<TokenStream> = formats
+            .iter()
+            .map(|format| quote!(vec![ #(#format),* ]))
+            .collect();
+
+        quote!(
+            fn parse(date: &str) -> ::std::result::Result<#crate_root::derive::DateValue, #crate_root::derive::ParseError> {
+                let fmts = vec![ #(#fmts),* ];
+
+                #crate_root::derive::parse_from_formats(date, fmts)
+            }
+        )
+    };
+
+    // Dates are always formatted using the first format
+    let format = &formats[0];
 
     let format_fn = quote!(
         fn format<'a>(date: &'a #crate_root::derive::DateValue) -> #crate_root::derive::FormattedDate<'a> {
@@ -87,7 +123,7 @@ fn impl_date_format(
 }
 
 // Get the format string supplied by an #[elastic()] attribute
-fn get_format_from_attr<'a>(item: &'a syn::MacroInput) -> Option<String> {
+fn get_format_from_attr<'a>(item: &'a syn::DeriveInput) -> Option<syn::LitStr> {
     let val = get_elastic_meta_items(&item.attrs);
 
     let val = val
@@ -95,11 +131,14 @@ fn get_format_from_attr<'a>(item: &'a syn::MacroInput) -> Option<String> {
         .filter_map(|meta| expect_name_value("date_format", &meta))
         .next();
 
-    val.and_then(|v| get_str_from_lit(v).ok().map(Into::into))
+    val.and_then(|v| match *v {
+        syn::Lit::Str(ref format) => Some(format.clone()),
+        _ => None,
+    })
 }
 
 // Get the name string supplied by an #[elastic()] attribute
-fn get_name_from_attr<'a>(item: &'a syn::MacroInput) -> Option<String> {
+fn get_name_from_attr<'a>(item: &'a syn::DeriveInput) -> Option<String> {
     let val = get_elastic_meta_items(&item.attrs);
 
     let val = val
@@ -107,26 +146,38 @@ fn get_name_from_attr<'a>(item: &'a syn::MacroInput) -> Option<String> {
         .filter_map(|meta| expect_name_value("date_format_name", &meta))
         .next();
 
-    val.and_then(|v| get_str_from_lit(v).ok().map(Into::into))
+    val.and_then(|v| match *v {
+        syn::Lit::Str(ref name) => Some(name.value()),
+        _ => None,
+    })
 }
 
 impl<'a> parse::DateFormatToken<'a> {
-    fn into_tokens(self, crate_root: &Tokens) -> Tokens {
+    fn into_tokens(self, crate_root: &TokenStream) -> TokenStream {
         use self::parse::DateFormatToken::*;
 
         match self {
             Year => {
                 quote!(#crate_root::derive::Item::Numeric(#crate_root::derive::Numeric::Year, #crate_root::derive::Pad::Zero))
             }
+            WeekYear => {
+                quote!(#crate_root::derive::Item::Numeric(#crate_root::derive::Numeric::IsoYear, #crate_root::derive::Pad::Zero))
+            }
             Month => {
                 quote!(#crate_root::derive::Item::Numeric(#crate_root::derive::Numeric::Month, #crate_root::derive::Pad::Zero))
             }
+            WeekOfWeekYear => {
+                quote!(#crate_root::derive::Item::Numeric(#crate_root::derive::Numeric::IsoWeek, #crate_root::derive::Pad::Zero))
+            }
             DayOfMonth => {
                 quote!(#crate_root::derive::Item::Numeric(#crate_root::derive::Numeric::Day, #crate_root::derive::Pad::Zero))
             }
             DayOfYear => {
                 quote!(#crate_root::derive::Item::Numeric(#crate_root::derive::Numeric::Ordinal, #crate_root::derive::Pad::Zero))
             }
+            DayOfWeek => {
+                quote!(#crate_root::derive::Item::Numeric(#crate_root::derive::Numeric::WeekdayFromMon, #crate_root::derive::Pad::None))
+            }
             Hour => {
                 quote!(#crate_root::derive::Item::Numeric(#crate_root::derive::Numeric::Hour, #crate_root::derive::Pad::Zero))
             }
@@ -139,7 +190,19 @@ impl<'a> parse::DateFormatToken<'a> {
             Millisecond => {
                 quote!(#crate_root::derive::Item::Fixed(#crate_root::derive::Fixed::Nanosecond3))
             }
+            Microsecond => {
+                quote!(#crate_root::derive::Item::Fixed(#crate_root::derive::Fixed::Nanosecond6))
+            }
+            Nanosecond => {
+                quote!(#crate_root::derive::Item::Fixed(#crate_root::derive::Fixed::Nanosecond9))
+            }
             Utc => quote!(#crate_root::derive::Item::Literal("Z")),
+            OffsetColon => {
+                quote!(#crate_root::derive::Item::Fixed(#crate_root::derive::Fixed::TimezoneOffsetColon))
+            }
+            OffsetColonZ => {
+                quote!(#crate_root::derive::Item::Fixed(#crate_root::derive::Fixed::TimezoneOffsetColonZ))
+            }
             Delim(s) => quote!(#crate_root::derive::Item::Literal(#s)),
             Escaped(s) => quote!(#crate_root::derive::Item::Literal(#s)),
         }
@@ -149,15 +212,35 @@ impl<'a> parse::DateFormatToken<'a> {
 quick_error! {
     #[derive(Debug)]
     pub enum DeriveDateFormatError {
-        InvalidInput {
+        InvalidInput(span: Span) {
             display("deriving a date format is only valid for unit structs")
         }
-        MissingFormat {
+        MissingFormat(span: Span) {
             display("missing date format. Add a `#[elastic(date_format=\"<format here>\")]`")
         }
-        InvalidFormat(err: parse::Error) {
-            display("error parsing date format")
-            from()
+        InvalidFormat(err: parse::Error, span: Span) {
+            display("error parsing date format. Check the format only contains supported tokens, and any literal text is quoted like `'T'`")
+        }
+        InvalidAttribute(msg: String, span: Span) {
+            display("{}", msg)
+        }
+    }
+}
+
+impl DeriveDateFormatError {
+    /** Get the span of the input that caused the error. */
+    pub fn span(&self) -> Span {
+        match *self {
+            DeriveDateFormatError::InvalidInput(span)
+            | DeriveDateFormatError::MissingFormat(span)
+            | DeriveDateFormatError::InvalidFormat(_, span)
+            | DeriveDateFormatError::InvalidAttribute(_, span) => span,
         }
     }
 }
+
+impl From<syn::Error> for DeriveDateFormatError {
+    fn from(err: syn::Error) -> Self {
+        DeriveDateFormatError::InvalidAttribute(err.to_string(), err.span())
+    }
+}
@@ -6,14 +6,21 @@ pub type Error = NomError;
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DateFormatToken<'a> {
     Year,
+    WeekYear,
     Month,
+    WeekOfWeekYear,
     DayOfMonth,
     DayOfYear,
+    DayOfWeek,
     Hour,
     Minute,
     Second,
     Millisecond,
+    Microsecond,
+    Nanosecond,
     Utc,
+    OffsetColon,
+    OffsetColonZ,
     Delim(&'a str),
     Escaped(&'a str),
 }
@@ -22,21 +29,35 @@ pub fn to_tokens<'a>(input: &'a str) -> Result<Vec<DateFormatToken<'a>>, Error>
     format(input.as_bytes()).to_result()
 }
 
+/** Parse each of the alternative formats in a format like `yyyy-MM-dd||yyyy/MM/dd`. */
+pub fn to_formats<'a>(input: &'a str) -> Result<Vec<Vec<DateFormatToken<'a>>>, Error> {
+    input.split("||").map(to_tokens).collect()
+}
+
 named!(format(&[u8]) -> Vec<DateFormatToken>,
-    complete!(tokens)
+    complete!(terminated!(tokens, eof!()))
 );
 
+// Tokens that share a prefix with a shorter token are wrapped in `complete!`
+// so running out of input tries the next alternative instead of stopping
 named!(tokens(&[u8]) -> Vec<DateFormatToken>,
     many1!(
         alt!(
             year |
+            week_year |
             month |
+            week_of_week_year |
             day_of_month |
             day_of_year |
+            day_of_week |
             hour |
             minute |
             second |
+            complete!(nanosecond) |
+            complete!(microsecond) |
             millisecond |
+            complete!(offset_colon) |
+            complete!(offset_colon_z) |
             utc |
             escaped |
             delim
@@ -52,6 +73,14 @@ named!(year(&[u8]) -> DateFormatToken,
     )
 );
 
+/** Parse `xxxx` as a 4 digit ISO week-based year. */
+named!(week_year(&[u8]) -> DateFormatToken,
+    do_parse!(
+        count!(char!('x'), 4) >>
+        (DateFormatToken::WeekYear)
+    )
+);
+
 /** Parse `MM` as a 2 digit month of year. */
 named!(month(&[u8]) -> DateFormatToken,
     do_parse!(
@@ -60,6 +89,14 @@ named!(month(&[u8]) -> DateFormatToken,
     )
 );
 
+/** Parse `ww` as a 2 digit ISO week of the week-based year. */
+named!(week_of_week_year(&[u8]) -> DateFormatToken,
+    do_parse!(
+        count!(char!('w'), 2) >>
+        (DateFormatToken::WeekOfWeekYear)
+    )
+);
+
 /** Parse `dd` as a 2 digit day of month. */
 named!(day_of_month(&[u8]) -> DateFormatToken,
     do_parse!(
@@ -76,6 +113,14 @@ named!(day_of_year(&[u8]) -> DateFormatToken,
     )
 );
 
+/** Parse `e` as a 1 digit day of week, where Monday is `1`. */
+named!(day_of_week(&[u8]) -> DateFormatToken,
+    do_parse!(
+        char!('e') >>
+        (DateFormatToken::DayOfWeek)
+    )
+);
+
 /** Parse `HH` as a 2 digit hour of day (24hr). */
 named!(hour(&[u8]) -> DateFormatToken,
     do_parse!(
@@ -109,6 +154,40 @@ named!(millisecond(&[u8]) -> DateFormatToken,
     )
 );
 
+/** Parse `.SSSSSS` as a 6 digit microsecond of second. */
+named!(microsecond(&[u8]) -> DateFormatToken,
+    do_parse!(
+        tag!(".") >>
+        count!(char!('S'), 6) >>
+        (DateFormatToken::Microsecond)
+    )
+);
+
+/** Parse `.SSSSSSSSS` as a 9 digit nanosecond of second. */
+named!(nanosecond(&[u8]) -> DateFormatToken,
+    do_parse!(
+        tag!(".") >>
+        count!(char!('S'), 9) >>
+        (DateFormatToken::Nanosecond)
+    )
+);
+
+/** Parse `ZZ` as a timezone offset with a colon, like `+10:00`. */
+named!(offset_colon(&[u8]) -> DateFormatToken,
+    do_parse!(
+        tag!("ZZ") >>
+        (DateFormatToken::OffsetColon)
+    )
+);
+
+/** Parse `XXX` as a timezone offset with a colon, or `Z` for Utc. */
+named!(offset_colon_z(&[u8]) -> DateFormatToken,
+    do_parse!(
+        tag!("XXX") >>
+        (DateFormatToken::OffsetColonZ)
+    )
+);
+
 /** Parse `Z` as a Utc timezone. */
 named!(utc(&[u8]) -> DateFormatToken,
     do_parse!(
@@ -213,4 +292,52 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn parse_week_date() {
+        assert_parse(
+            b"xxxx-'W'ww-e",
+            vec![
+                WeekYear,
+                Delim("-"),
+                Escaped("W"),
+                WeekOfWeekYear,
+                Delim("-"),
+                DayOfWeek,
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_fractional_seconds() {
+        assert_parse(b"ss.SSS", vec![Second, Millisecond]);
+        assert_parse(b"ss.SSSSSS", vec![Second, Microsecond]);
+        assert_parse(b"ss.SSSSSSSSS", vec![Second, Nanosecond]);
+    }
+
+    #[test]
+    fn parse_offsets() {
+        assert_parse(b"HH:mmZ", vec![Hour, Delim(":"), Minute, Utc]);
+        assert_parse(b"HH:mmZZ", vec![Hour, Delim(":"), Minute, OffsetColon]);
+        assert_parse(b"HH:mmXXX", vec![Hour, Delim(":"), Minute, OffsetColonZ]);
+    }
+
+    #[test]
+    fn parse_multiple_formats() {
+        let formats = to_formats("yyyy-MM-dd||yyyy/MM/dd").unwrap();
+
+        assert_eq!(
+            vec![
+                vec![Year, Delim("-"), Month, Delim("-"), DayOfMonth],
+                vec![Year, Delim("/"), Month, Delim("/"), DayOfMonth],
+            ],
+            formats
+        );
+    }
+
+    #[test]
+    fn parse_invalid_format_is_err() {
+        assert!(to_formats("yyyy-MM-dd||yyyy-MM-dq").is_err());
+        assert!(to_formats("yyyy-MM-dd||").is_err());
+    }
 }
@@ -1,22 +1,34 @@
 use super::{
+    check_elastic_attrs,
     expect_ident,
     expect_list,
     expect_name_value,
     get_elastic_meta_items,
-    get_ident_from_lit,
+    get_str_from_lit,
     get_tokens_from_lit,
 };
-use quote::Tokens;
+use chrono::format::{
+    Item,
+    StrftimeItems,
+};
+use proc_macro2::{
+    Span,
+    TokenStream,
+};
 use serde_derive_internals::{
     self,
     attr as serde_attr,
 };
-use syn;
+use syn::{
+    self,
+    punctuated::Punctuated,
+    spanned::Spanned,
+};
 
 struct ElasticDocumentMapping {
-    ident: syn::Ident,
-    definition: Tokens,
-    impl_block: Tokens,
+    ty: TokenStream,
+    definition: TokenStream,
+    impl_block: TokenStream,
 }
 
 /**
@@ -24,49 +36,109 @@ Derive `DocumentType` for the given input.
 
 The input must satisfy the following rules:
 
-- It must be a struct.
+- It must be a struct with named fields, or a newtype struct.
 - The structs field types must implement `FieldType` (or be ignored).
 - A mapping type supplied by `#[elastic(mapping="<ident>")]` must implement `DocumentMapping`,
 but not `PropertiesMapping`.
+
+A newtype struct derives `FieldType` with the mapping of the type it wraps instead of `DocumentType`.
 */
 pub fn expand_derive(
-    crate_root: Tokens,
-    input: &syn::MacroInput,
-) -> Result<Vec<Tokens>, DeriveElasticTypeError> {
-    // Annotatable item for a struct with struct fields
-    let fields = match input.body {
-        syn::Body::Struct(ref data) => match *data {
-            syn::VariantData::Struct(ref fields) => Some(fields),
+    crate_root: TokenStream,
+    input: &syn::DeriveInput,
+) -> Result<Vec<TokenStream>, DeriveElasticTypeError> {
+    check_elastic_attrs(&input.attrs)?;
+
+    // Annotatable item for a struct with struct fields, or a newtype struct
+    let fields = match input.data {
+        syn::Data::Struct(ref data) => match data.fields {
+            syn::Fields::Named(ref fields) => Some(fields),
+            syn::Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+                return expand_newtype_derive(crate_root, input, &fields.unnamed[0]);
+            }
             _ => None,
         },
         _ => None,
     };
 
-    let fields = fields.ok_or(DeriveElasticTypeError::InvalidInput)?;
+    let struct_fields =
+        fields.ok_or_else(|| DeriveElasticTypeError::InvalidInput(input.ident.span()))?;
+
+    for field in &struct_fields.named {
+        check_elastic_attrs(&field.attrs)
+            .map_err(|err| DeriveElasticTypeError::from(err).for_field(field))?;
+    }
 
     // Get the serializable fields
-    let fields: Vec<(syn::Ident, &syn::Field)> = fields
+    let serde_item = get_ser_item(input);
+    let rename_rules = serde_item.as_ref().map(|item| item.rename_all_rules());
+
+    let fields: Vec<(String, &syn::Field)> = struct_fields
+        .named
         .iter()
-        .map(|f| get_ser_field(f))
-        .filter(|f| f.is_some())
-        .map(|f| f.unwrap())
+        .map(|f| get_ser_field(rename_rules, f))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter_map(|f| f)
         .collect();
 
-    let mapping = get_mapping(&crate_root, input);
+    // Get the fields to map, where there isn't `#[elastic(skip)]`
+    // Skipped fields can still be used for document metadata, like the id
+    let mut mapped_fields: Vec<(String, &syn::Field)> = Vec::new();
+    for &(ref name, field) in &fields {
+        let skip =
+            get_bool_param_from_attrs("skip", &field.attrs).map_err(|err| err.for_field(field))?;
+
+        if skip != Some(true) {
+            mapped_fields.push((name.clone(), field));
+        }
+    }
+
+    let generics = get_generics(&crate_root, input, &mapped_fields)?;
+
+    let mapping = get_mapping(&crate_root, input, &generics)?;
+
+    let doc_ty_impl_block =
+        get_doc_ty_impl_block(&crate_root, input, &generics, &fields, &mapping.ty)?;
+
+    let props_impl_block =
+        get_props_impl_block(&crate_root, &input.ident, &generics, &mapped_fields)?;
 
-    let doc_ty_impl_block = get_doc_ty_impl_block(&crate_root, input, &fields, &mapping.ident);
+    let fields_definition = get_fields_definition(&crate_root, input, &generics, &mapped_fields)?;
 
-    let props_impl_block = get_props_impl_block(&crate_root, &input.ident, &fields);
+    let mapping_json_impl_block = get_mapping_json_impl_block(&crate_root, input, &generics)?;
 
-    let dummy_wrapper = syn::Ident::new(format!("_IMPL_EASTIC_TYPE_FOR_{}", input.ident));
+    let partial_definition =
+        get_partial_definition(&crate_root, input, &generics, rename_rules, struct_fields)?;
+
+    let dummy_wrapper = syn::Ident::new(
+        &format!("_IMPL_EASTIC_TYPE_FOR_{}", input.ident),
+        Span::call_site(),
+    );
 
     let mapping_definition = &mapping.definition;
     let mapping_impl_block = &mapping.impl_block;
 
+    let fields_struct = &fields_definition.definition;
+    let fields_impl_block = &fields_definition.impl_block;
+
+    let partial_struct = partial_definition
+        .as_ref()
+        .map(|partial| &partial.definition);
+    let partial_impl_block = partial_definition
+        .as_ref()
+        .map(|partial| &partial.impl_block);
+
     Ok(vec![quote!(
         #[allow(missing_docs)]
         #mapping_definition
 
+        #[allow(missing_docs)]
+        #fields_struct
+
+        #[allow(missing_docs)]
+        #partial_struct
+
         #[allow(non_upper_case_globals, dead_code, unused_variables)]
         const #dummy_wrapper: () = {
             #mapping_impl_block
@@ -74,112 +146,827 @@ pub fn expand_derive(
             #doc_ty_impl_block
 
             #props_impl_block
+
+            #fields_impl_block
+
+            #mapping_json_impl_block
+
+            #partial_impl_block
         };
     )])
 }
 
-fn get_mapping(crate_root: &Tokens, input: &syn::MacroInput) -> ElasticDocumentMapping {
+// Derive `FieldType` for a newtype struct so it's mapped the same way as the type it wraps
+// The newtype is a `WrappedFieldType`, like `Vec` and `Option`, so its mapping is the mapping of the wrapped type
+// A mapping supplied by #[elastic(mapping = $lit)] is used instead of the mapping for the wrapped type
+fn expand_newtype_derive(
+    crate_root: TokenStream,
+    input: &syn::DeriveInput,
+    field: &syn::Field,
+) -> Result<Vec<TokenStream>, DeriveElasticTypeError> {
+    let newtype = &input.ident;
+    let inner_ty = &field.ty;
+
+    let mapping = get_elastic_meta_items(&input.attrs)
+        .iter()
+        .filter_map(|meta| expect_name_value("mapping", meta))
+        .next()
+        .map(|mapping| match get_tokens_from_lit(mapping) {
+            Ok(mapping) => Ok(mapping),
+            Err(_) => invalid_attribute(
+                mapping.span(),
+                "the mapping in a mapping attribute must be a string",
+            ),
+        })
+        .transpose()?;
+
+    let mut generics = input.generics.clone();
+
+    let (mapping, bounds) = match mapping {
+        Some(mapping) => {
+            let bounds = quote!(#mapping: #crate_root::derive::FieldMapping<TElasticPivot>);
+
+            (mapping, bounds)
+        }
+        None => {
+            generics.params.push(parse_quote!(TElasticMapping));
+
+            let bounds = quote!(
+                #inner_ty: #crate_root::derive::FieldType<TElasticMapping, TElasticPivot>,
+                TElasticMapping: #crate_root::derive::FieldMapping<TElasticPivot>
+            );
+
+            (quote!(TElasticMapping), bounds)
+        }
+    };
+
+    generics.params.push(parse_quote!(TElasticPivot));
+
+    match syn::parse2::<syn::WhereClause>(quote!(where #bounds)) {
+        Ok(where_clause) => generics
+            .make_where_clause()
+            .predicates
+            .extend(where_clause.predicates),
+        Err(_) => {
+            return invalid_attribute(
+                mapping.span(),
+                format!(
+                    "the mapping `{}` in a mapping attribute must be a valid type",
+                    mapping
+                ),
+            )
+        }
+    }
+
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(vec![quote!(
+        impl #impl_generics #crate_root::derive::WrappedFieldType<#mapping, TElasticPivot> for #newtype #ty_generics #where_clause {}
+    )])
+}
+
+struct ElasticDocumentFields {
+    definition: TokenStream,
+    impl_block: TokenStream,
+}
+
+// Define a struct of typed field references named `{TypeName}Fields`
+// Each serialized field gets a `FieldRef` with the same visibility as the field on the document
+fn get_fields_definition(
+    crate_root: &TokenStream,
+    item: &syn::DeriveInput,
+    generics: &syn::Generics,
+    fields: &[(String, &syn::Field)],
+) -> Result<ElasticDocumentFields, DeriveElasticTypeError> {
+    let vis = &item.vis;
+    let doc_ty = &item.ident;
+    let fields_ty = syn::Ident::new(&format!("{}Fields", item.ident), Span::call_site());
+
+    let (def_generics, ty_generics, def_where_clause) = item.generics.split_for_impl();
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    // Generic parameters might not be used by any fields
+    let (marker_def, marker_init) = if is_generic(item) {
+        (
+            Some(quote!(_marker: ::std::marker::PhantomData<fn() -> #doc_ty #ty_generics>,)),
+            Some(quote!(_marker: ::std::marker::PhantomData,)),
+        )
+    } else {
+        (None, None)
+    };
+
+    let field_defs = fields
+        .iter()
+        .map(|&(_, ref field)| {
+            let field_vis = &field.vis;
+            let field_ident = &field.ident;
+            let ty = get_field_ref_ty(crate_root, field).map_err(|err| err.for_field(field))?;
+
+            Ok(quote!(#field_vis #field_ident: #crate_root::derive::FieldRef<#ty>))
+        })
+        .collect::<Result<Vec<TokenStream>, DeriveElasticTypeError>>()?;
+
+    let field_inits: Vec<TokenStream> = fields
+        .iter()
+        .map(|&(ref name, ref field)| {
+            let field_ident = &field.ident;
+            let lit = name.as_str();
+
+            quote!(#field_ident: #crate_root::derive::FieldRef::new(#crate_root::derive::field_path(path, #lit)))
+        })
+        .collect();
+
+    Ok(ElasticDocumentFields {
+        definition: quote!(
+            #[derive(Debug, Clone)]
+            #vis struct #fields_ty #def_generics #def_where_clause {
+                #marker_def
+                #(#field_defs),*
+            }
+        ),
+        impl_block: quote!(
+            impl #impl_generics #crate_root::derive::DocumentFields for #doc_ty #ty_generics #where_clause {
+                type Fields = #fields_ty #ty_generics;
+
+                fn fields_at(path: &str) -> Self::Fields {
+                    #fields_ty {
+                        #marker_init
+                        #(#field_inits),*
+                    }
+                }
+            }
+        ),
+    })
+}
+
+// Get the type a field is referenced as in the generated fields struct
+// Fields with a `lowercase` normalizer are referenced as lowercase keywords so query terms are normalized the same way as indexed values
+fn get_field_ref_ty(
+    crate_root: &TokenStream,
+    field: &syn::Field,
+) -> Result<TokenStream, DeriveElasticTypeError> {
+    let ty = &field.ty;
+
+    let lowercase = match get_normalizer_from_attrs(&field.attrs)? {
+        Some(ref normalizer) => get_str_from_lit(normalizer)
+            .map(|normalizer| normalizer == "lowercase")
+            .unwrap_or(false),
+        None => false,
+    };
+
+    if lowercase {
+        Ok(quote!(#crate_root::derive::Keyword<#crate_root::derive::LowercaseKeywordMapping>))
+    } else {
+        Ok(quote!(#ty))
+    }
+}
+
+struct ElasticPartialDocument {
+    definition: TokenStream,
+    impl_block: TokenStream,
+}
+
+struct ElasticPartialField<'a> {
+    name: String,
+    field: &'a syn::Field,
+    serialize_with: Option<syn::ExprPath>,
+    skip_serializing_if: Option<syn::ExprPath>,
+}
+
+// Get a field to serialize on a partial document
+// Partial documents are merged into the `_source`, so their fields use the `serde` name
+// Returns `None` if the field isn't serialised
+// Parses #[serde(serialize_with = $path)], #[serde(with = $path)] and #[serde(skip_serializing_if = $path)]
+fn get_partial_field<'a>(
+    rename_rules: Option<&serde_attr::RenameAllRules>,
+    field: &'a syn::Field,
+) -> Result<Option<ElasticPartialField<'a>>, DeriveElasticTypeError> {
+    let ctxt = serde_derive_internals::Ctxt::new();
+    let mut serde_field =
+        serde_attr::Field::from_ast(&ctxt, 0, field, None, &serde_attr::Default::None);
+
+    // If the `serde` parse fails, ignore the field and let `serde` panic later
+    if ctxt.check().is_err() || serde_field.skip_serializing() {
+        return Ok(None);
+    }
+
+    // Flattened fields can't be set as a single value on the partial document
+    if serde_field.flatten() {
+        return invalid_attribute(
+            field.span(),
+            "flattened fields aren't supported in partial documents",
+        )
+        .map_err(|err| err.for_field(field));
+    }
+
+    if let Some(rename_rules) = rename_rules {
+        serde_field.rename_by_rules(rename_rules);
+    }
+
+    Ok(Some(ElasticPartialField {
+        name: serde_field.name().serialize_name(),
+        field,
+        serialize_with: serde_field.serialize_with().cloned(),
+        skip_serializing_if: serde_field.skip_serializing_if().cloned(),
+    }))
+}
+
+// Define a struct named `{TypeName}Partial` for partial updates if there's an #[elastic(partial)] attribute
+// Each serialized field is optional and has a builder method with the same visibility as the field on the document
+// Fields that aren't set are omitted when the partial document is serialized
+// Fields that are set are serialized like `serde` would for the document, using `serialize_with` and `skip_serializing_if`
+// Parses #[elastic(partial)]
+fn get_partial_definition(
+    crate_root: &TokenStream,
+    item: &syn::DeriveInput,
+    generics: &syn::Generics,
+    rename_rules: Option<&serde_attr::RenameAllRules>,
+    fields: &syn::FieldsNamed,
+) -> Result<Option<ElasticPartialDocument>, DeriveElasticTypeError> {
+    let val = get_elastic_meta_items(&item.attrs);
+
+    if !val.iter().any(|meta| expect_ident("partial", meta)) {
+        return Ok(None);
+    }
+
+    let fields: Vec<ElasticPartialField> = fields
+        .named
+        .iter()
+        .map(|field| get_partial_field(rename_rules, field))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter_map(|f| f)
+        .collect();
+
+    let vis = &item.vis;
+    let doc_ty = &item.ident;
+    let partial_ty = syn::Ident::new(&format!("{}Partial", item.ident), Span::call_site());
+
+    let (def_generics, ty_generics, def_where_clause) = item.generics.split_for_impl();
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    // Generic parameters might not be used by any fields
+    let (marker_def, marker_init) = if is_generic(item) {
+        (
+            Some(quote!(_marker: ::std::marker::PhantomData<fn() -> #doc_ty #ty_generics>,)),
+            Some(quote!(_marker: ::std::marker::PhantomData,)),
+        )
+    } else {
+        (None, None)
+    };
+
+    let field_defs: Vec<TokenStream> = fields
+        .iter()
+        .map(|&ElasticPartialField { field, .. }| {
+            let field_vis = &field.vis;
+            let field_ident = &field.ident;
+            let ty = &field.ty;
+
+            quote!(#field_vis #field_ident: ::std::option::Option<#ty>)
+        })
+        .collect();
+
+    let field_inits: Vec<TokenStream> = fields
+        .iter()
+        .map(|&ElasticPartialField { field, .. }| {
+            let field_ident = &field.ident;
+
+            quote!(#field_ident: ::std::option::Option::None)
+        })
+        .collect();
+
+    let field_setters: Vec<TokenStream> = fields
+        .iter()
+        .map(|&ElasticPartialField { field, .. }| {
+            let field_vis = &field.vis;
+            let field_ident = &field.ident;
+            let ty = &field.ty;
+
+            quote!(
+                #field_vis fn #field_ident(mut self, value: #ty) -> Self {
+                    self.#field_ident = ::std::option::Option::Some(value);
+                    self
+                }
+            )
+        })
+        .collect();
+
+    // A wrapper for fields with `serialize_with` carries the document's generics so the field type can use them
+    let mut wrapper_generics = generics.clone();
+    wrapper_generics
+        .params
+        .insert(0, parse_quote!('__elastic_a));
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+
+    let field_ser_stmts: Vec<TokenStream> = fields
+        .iter()
+        .map(|partial_field| {
+            let field_ident = &partial_field.field.ident;
+            let ty = &partial_field.field.ty;
+            let lit = partial_field.name.as_str();
+
+            let ser_entry = match partial_field.serialize_with {
+                Some(ref serialize_with) => quote!({
+                    struct __SerializeWith #wrapper_impl_generics #where_clause {
+                        value: &'__elastic_a #ty,
+                        phantom: ::std::marker::PhantomData<#partial_ty #ty_generics>,
+                    }
+
+                    impl #wrapper_impl_generics #crate_root::derive::Serialize for __SerializeWith #wrapper_ty_generics #where_clause {
+                        fn serialize<__S>(&self, serializer: __S) -> ::std::result::Result<__S::Ok, __S::Error>
+                        where
+                            __S: #crate_root::derive::Serializer,
+                        {
+                            #serialize_with(self.value, serializer)
+                        }
+                    }
+
+                    try!(#crate_root::derive::SerializeMap::serialize_entry(&mut state, #lit, &__SerializeWith {
+                        value: value,
+                        phantom: ::std::marker::PhantomData::<#partial_ty #ty_generics>,
+                    }));
+                }),
+                None => quote!(
+                    try!(#crate_root::derive::SerializeMap::serialize_entry(&mut state, #lit, value));
+                ),
+            };
+
+            let ser_entry = match partial_field.skip_serializing_if {
+                Some(ref skip_serializing_if) => quote!(
+                    if !#skip_serializing_if(value) {
+                        #ser_entry
+                    }
+                ),
+                None => ser_entry,
+            };
+
+            quote!(
+                if let ::std::option::Option::Some(ref value) = self.#field_ident {
+                    #ser_entry
+                }
+            )
+        })
+        .collect();
+
+    // Fields with `serialize_with` don't need to implement `Serialize` themselves
+    let mut ser_generics = generics.clone();
+    for partial_field in fields
+        .iter()
+        .filter(|partial_field| partial_field.serialize_with.is_none())
+    {
+        let ty = &partial_field.field.ty;
+
+        ser_generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#ty: #crate_root::derive::Serialize));
+    }
+
+    let (_, _, ser_where_clause) = ser_generics.split_for_impl();
+
+    Ok(Some(ElasticPartialDocument {
+        definition: quote!(
+            #vis struct #partial_ty #def_generics #def_where_clause {
+                #marker_def
+                #(#field_defs),*
+            }
+        ),
+        impl_block: quote!(
+            impl #impl_generics ::std::default::Default for #partial_ty #ty_generics #where_clause {
+                fn default() -> Self {
+                    #partial_ty {
+                        #marker_init
+                        #(#field_inits),*
+                    }
+                }
+            }
+
+            impl #impl_generics #partial_ty #ty_generics #where_clause {
+                /** Create a partial document without any fields set. */
+                pub fn new() -> Self {
+                    ::std::default::Default::default()
+                }
+
+                #(#field_setters)*
+            }
+
+            impl #impl_generics #crate_root::derive::Serialize for #partial_ty #ty_generics #ser_where_clause {
+                fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+                where
+                    S: #crate_root::derive::Serializer,
+                {
+                    let mut state = try!(serializer.serialize_map(::std::option::Option::None));
+
+                    #(#field_ser_stmts)*
+
+                    #crate_root::derive::SerializeMap::end(state)
+                }
+            }
+
+            impl #impl_generics #crate_root::derive::PartialDocument for #partial_ty #ty_generics #ser_where_clause {
+                type Document = #doc_ty #ty_generics;
+            }
+        ),
+    }))
+}
+
+fn get_mapping(
+    crate_root: &TokenStream,
+    input: &syn::DeriveInput,
+    generics: &syn::Generics,
+) -> Result<ElasticDocumentMapping, DeriveElasticTypeError> {
     // Define a struct for the mapping with a few defaults
-    fn define_mapping(vis: &syn::Visibility, name: &syn::Ident) -> Tokens {
+    fn define_mapping(item: &syn::DeriveInput, name: &syn::Ident) -> TokenStream {
+        let vis = &item.vis;
+
+        if !is_generic(item) {
+            return quote!(
+                #[derive(Default, Clone, Copy, Debug)]
+                #vis struct #name;
+            );
+        }
+
+        // Derived traits would require the same traits on the generic parameters
+        let doc_ty = &item.ident;
+        let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
         quote!(
-            #[derive(Default, Clone, Copy, Debug)]
-            #vis struct #name;
+            #vis struct #name #impl_generics #where_clause {
+                _m: ::std::marker::PhantomData<fn() -> #doc_ty #ty_generics>,
+            }
+
+            impl #impl_generics ::std::default::Default for #name #ty_generics #where_clause {
+                fn default() -> Self {
+                    #name { _m: ::std::marker::PhantomData }
+                }
+            }
+
+            impl #impl_generics ::std::clone::Clone for #name #ty_generics #where_clause {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl #impl_generics ::std::marker::Copy for #name #ty_generics #where_clause { }
+
+            impl #impl_generics ::std::fmt::Debug for #name #ty_generics #where_clause {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                    f.debug_struct(stringify!(#name)).finish()
+                }
+            }
         )
     }
 
     // Get the default mapping name
-    fn get_default_mapping(item: &syn::MacroInput) -> syn::Ident {
-        syn::Ident::from(format!("{}Mapping", item.ident))
+    fn get_default_mapping(item: &syn::DeriveInput) -> syn::Ident {
+        syn::Ident::new(&format!("{}Mapping", item.ident), Span::call_site())
     }
 
-    // Get the mapping ident supplied by an #[elastic()] attribute or create a default one
-    fn get_mapping_from_attr(item: &syn::MacroInput) -> Option<syn::Ident> {
+    // Get the mapping type supplied by an #[elastic()] attribute
+    fn get_mapping_from_attr(
+        item: &syn::DeriveInput,
+    ) -> Result<Option<TokenStream>, DeriveElasticTypeError> {
         let val = get_elastic_meta_items(&item.attrs);
 
-        let val = val
-            .iter()
+        val.iter()
             .filter_map(|meta| expect_name_value("mapping", &meta))
-            .next();
+            .next()
+            .map(|mapping| match get_tokens_from_lit(mapping) {
+                Ok(mapping) => Ok(mapping),
+                Err(_) => invalid_attribute(
+                    mapping.span(),
+                    "the mapping in a mapping attribute must be a string",
+                ),
+            })
+            .transpose()
+    }
+
+    // Get the `_source` setting supplied by an #[elastic(source(enabled = $lit))] attribute
+    // Parses #[elastic(source(enabled = $lit))]
+    fn get_source_enabled_from_attr(item: &syn::DeriveInput) -> Option<TokenStream> {
+        let val = get_elastic_meta_items(&item.attrs);
+
+        val.iter()
+            .filter_map(|meta| expect_list("source", meta))
+            .flat_map(|attrs| attrs)
+            .filter_map(|meta| expect_name_value("enabled", meta))
+            .filter_map(|lit| match *lit {
+                syn::Lit::Bool(ref enabled) => Some(quote!(#enabled)),
+                _ => None,
+            })
+            .next()
+    }
+
+    // Get the `dynamic` setting supplied by an #[elastic(dynamic = $lit)] attribute
+    // Parses #[elastic(dynamic = $bool)]
+    // Parses #[elastic(dynamic = "strict")]
+    fn get_dynamic_from_attr(
+        crate_root: &TokenStream,
+        item: &syn::DeriveInput,
+    ) -> Result<Option<TokenStream>, DeriveElasticTypeError> {
+        let val = get_elastic_meta_items(&item.attrs);
+
+        val.iter()
+            .filter_map(|meta| expect_name_value("dynamic", meta))
+            .map(|lit| match *lit {
+                syn::Lit::Bool(ref dynamic) if dynamic.value => {
+                    Ok(quote!(#crate_root::derive::Dynamic::True))
+                }
+                syn::Lit::Bool(_) => Ok(quote!(#crate_root::derive::Dynamic::False)),
+                syn::Lit::Str(ref dynamic) => match dynamic.value().as_str() {
+                    "true" => Ok(quote!(#crate_root::derive::Dynamic::True)),
+                    "false" => Ok(quote!(#crate_root::derive::Dynamic::False)),
+                    "strict" => Ok(quote!(#crate_root::derive::Dynamic::Strict)),
+                    _ => invalid_attribute(
+                        lit.span(),
+                        "dynamic attributes must be one of `true`, `false` or `\"strict\"`",
+                    ),
+                },
+                _ => invalid_attribute(
+                    lit.span(),
+                    "dynamic attributes must be one of `true`, `false` or `\"strict\"`",
+                ),
+            })
+            .next()
+            .transpose()
+    }
+
+    // Get the `enabled` setting supplied by an #[elastic(enabled = $lit)] attribute
+    // Parses #[elastic(enabled = $bool)]
+    fn get_enabled_from_attr(item: &syn::DeriveInput) -> Option<TokenStream> {
+        let val = get_elastic_meta_items(&item.attrs);
+
+        val.iter()
+            .filter_map(|meta| expect_name_value("enabled", meta))
+            .filter_map(|lit| match *lit {
+                syn::Lit::Bool(ref enabled) => Some(quote!(#enabled)),
+                _ => None,
+            })
+            .next()
+    }
+
+    // Get the datatype supplied by an #[elastic(nested)] or #[elastic(object)] attribute
+    // Parses #[elastic(nested)]
+    // Parses #[elastic(object)]
+    // The `enabled` setting only applies to objects, so #[elastic(enabled = $bool)] implies #[elastic(object)]
+    fn get_data_type_from_attr(
+        crate_root: &TokenStream,
+        item: &syn::DeriveInput,
+        enabled: bool,
+    ) -> Result<Option<TokenStream>, DeriveElasticTypeError> {
+        let val = get_elastic_meta_items(&item.attrs);
+
+        let nested = val.iter().find(|meta| expect_ident("nested", meta));
+        let object = enabled || val.iter().any(|meta| expect_ident("object", meta));
+
+        match (nested, object) {
+            (Some(nested), true) => invalid_attribute(
+                nested.span(),
+                "a type can't be mapped as both #[elastic(nested)] and #[elastic(object)] or #[elastic(enabled)]"
+            ),
+            (Some(_), false) => Ok(Some(quote!(#crate_root::derive::NESTED_DATATYPE))),
+            (None, true) => Ok(Some(quote!(#crate_root::derive::OBJECT_DATATYPE))),
+            (None, false) => Ok(None),
+        }
+    }
+
+    // Get the runtime fields supplied by an #[elastic(runtime())] attribute as a static slice
+    // Parses #[elastic(runtime($name(ty = $lit), ...))]
+    // Parses #[elastic(runtime($name(ty = $lit, script = $lit), ...))]
+    fn get_runtime_from_attr(
+        crate_root: &TokenStream,
+        item: &syn::DeriveInput,
+    ) -> Result<Option<TokenStream>, DeriveElasticTypeError> {
+        let val = get_elastic_meta_items(&item.attrs);
+
+        let lists: Vec<&Punctuated<syn::NestedMeta, Token![,]>> = val
+            .iter()
+            .filter_map(|meta| expect_list("runtime", meta))
+            .collect();
+
+        if lists.is_empty() {
+            return Ok(None);
+        }
+
+        const INVALID_RUNTIME: &str = "runtime attributes must be of the form #[elastic(runtime(name(ty = \"type\", script = \"script\")))]";
+
+        let fields: Vec<TokenStream> = lists
+            .into_iter()
+            .flat_map(|attrs| attrs)
+            .map(|meta| {
+                let (name, params) = match *meta {
+                    syn::NestedMeta::Meta(syn::Meta::List(ref list)) => {
+                        match list.path.get_ident() {
+                            Some(name) => (name, &list.nested),
+                            None => return invalid_attribute(list.path.span(), INVALID_RUNTIME),
+                        }
+                    }
+                    _ => return invalid_attribute(meta.span(), INVALID_RUNTIME),
+                };
+
+                let ty = match params
+                    .iter()
+                    .filter_map(|meta| expect_name_value("ty", meta))
+                    .next()
+                {
+                    Some(ty) => ty,
+                    None => return invalid_attribute(meta.span(), format!("the runtime field `{}` must have a ty, like #[elastic(runtime(name(ty = \"keyword\")))]", name)),
+                };
+
+                let script = params
+                    .iter()
+                    .filter_map(|meta| expect_name_value("script", meta))
+                    .next()
+                    .map(|script| quote!(::std::option::Option::Some(#script)))
+                    .unwrap_or_else(|| quote!(::std::option::Option::None));
+
+                let name = name.to_string();
+
+                Ok(quote!(#crate_root::derive::RuntimeFieldMapping {
+                    name: #name,
+                    ty: #ty,
+                    script: #script,
+                }))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Some(quote!(&[#(#fields),*])))
+    }
 
-        val.and_then(|v| get_ident_from_lit(v).ok())
+    // The settings supplied by #[elastic()] attributes that are used by the mapping
+    struct ObjectMappingAttrs {
+        data_type: Option<TokenStream>,
+        dynamic: Option<TokenStream>,
+        enabled: Option<TokenStream>,
+        source_enabled: Option<TokenStream>,
+        meta: Option<TokenStream>,
+        runtime: Option<TokenStream>,
+    }
+
+    fn get_object_mapping_attrs(
+        crate_root: &TokenStream,
+        item: &syn::DeriveInput,
+    ) -> Result<ObjectMappingAttrs, DeriveElasticTypeError> {
+        let enabled = get_enabled_from_attr(item);
+
+        Ok(ObjectMappingAttrs {
+            data_type: get_data_type_from_attr(crate_root, item, enabled.is_some())?,
+            dynamic: get_dynamic_from_attr(crate_root, item)?,
+            enabled,
+            source_enabled: get_source_enabled_from_attr(item),
+            meta: get_meta_from_attrs(&item.attrs)?,
+            runtime: get_runtime_from_attr(crate_root, item)?,
+        })
     }
 
     // Implement DocumentMapping for the mapping
     fn impl_document_mapping(
-        crate_root: &Tokens,
+        crate_root: &TokenStream,
         mapping: &syn::Ident,
         properties: &syn::Ident,
-    ) -> Tokens {
+        generics: &syn::Generics,
+        attrs: ObjectMappingAttrs,
+    ) -> TokenStream {
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        let data_type = attrs.data_type.map(|data_type| {
+            quote!(
+                fn data_type() -> &'static str {
+                    #data_type
+                }
+            )
+        });
+
+        let dynamic = attrs.dynamic.map(|dynamic| {
+            quote!(
+                fn dynamic() -> ::std::option::Option<#crate_root::derive::Dynamic> {
+                    ::std::option::Option::Some(#dynamic)
+                }
+            )
+        });
+
+        let enabled = attrs.enabled.map(|enabled| {
+            quote!(
+                fn enabled() -> ::std::option::Option<bool> {
+                    ::std::option::Option::Some(#enabled)
+                }
+            )
+        });
+
+        let source_enabled = attrs.source_enabled.map(|enabled| {
+            quote!(
+                fn source_enabled() -> ::std::option::Option<bool> {
+                    ::std::option::Option::Some(#enabled)
+                }
+            )
+        });
+
+        let meta = attrs.meta.map(|meta| {
+            quote!(
+                fn meta() -> ::std::option::Option<&'static [(&'static str, &'static str)]> {
+                    ::std::option::Option::Some(#meta)
+                }
+            )
+        });
+
+        let runtime = attrs.runtime.map(|runtime| {
+            quote!(
+                fn runtime() -> ::std::option::Option<&'static [#crate_root::derive::RuntimeFieldMapping]> {
+                    ::std::option::Option::Some(#runtime)
+                }
+            )
+        });
+
         quote!(
-            impl #crate_root::derive::ObjectMapping for #mapping {
-                type Properties = #properties;
+            impl #impl_generics #crate_root::derive::ObjectMapping for #mapping #ty_generics #where_clause {
+                type Properties = #properties #ty_generics;
+
+                #data_type
+
+                #dynamic
+
+                #enabled
+
+                #source_enabled
+
+                #meta
+
+                #runtime
             }
         )
     }
 
-    if let Some(ident) = get_mapping_from_attr(input) {
-        ElasticDocumentMapping {
-            ident,
-            definition: Tokens::new(),
-            impl_block: Tokens::new(),
-        }
+    if let Some(mapping) = get_mapping_from_attr(input)? {
+        Ok(ElasticDocumentMapping {
+            ty: mapping,
+            definition: TokenStream::new(),
+            impl_block: TokenStream::new(),
+        })
     } else {
         let ident = get_default_mapping(input);
-        let definition = define_mapping(&input.vis, &ident);
-        let impl_block = impl_document_mapping(&crate_root, &ident, &input.ident);
+        let definition = define_mapping(input, &ident);
+        let attrs = get_object_mapping_attrs(&crate_root, input)?;
+        let impl_block = impl_document_mapping(&crate_root, &ident, &input.ident, generics, attrs);
+
+        let (_, ty_generics, _) = generics.split_for_impl();
 
-        ElasticDocumentMapping {
-            ident,
+        Ok(ElasticDocumentMapping {
+            ty: quote!(#ident #ty_generics),
             definition,
             impl_block,
-        }
+        })
     }
 }
 
 // Implement DocumentType for the type being derived with the mapping
 fn get_doc_ty_impl_block(
-    crate_root: &Tokens,
-    item: &syn::MacroInput,
-    fields: &[(syn::Ident, &syn::Field)],
-    mapping: &syn::Ident,
-) -> Tokens {
+    crate_root: &TokenStream,
+    item: &syn::DeriveInput,
+    generics: &syn::Generics,
+    fields: &[(String, &syn::Field)],
+    mapping: &TokenStream,
+) -> Result<TokenStream, DeriveElasticTypeError> {
     struct MetadataBlock {
-        instance_methods: Tokens,
-        static_impls: Tokens,
+        instance_methods: TokenStream,
+        static_impls: TokenStream,
     }
 
     // Implement DocumentMetadata for the type being derived with the mapping
     fn get_doc_ty_methods(
-        crate_root: &Tokens,
-        item: &syn::MacroInput,
-        fields: &[(syn::Ident, &syn::Field)],
-    ) -> MetadataBlock {
+        crate_root: &TokenStream,
+        item: &syn::DeriveInput,
+        generics: &syn::Generics,
+        fields: &[(String, &syn::Field)],
+    ) -> Result<MetadataBlock, DeriveElasticTypeError> {
         struct ElasticMetadataMethods {
-            index: Tokens,
+            index: TokenStream,
             index_is_static: bool,
-            ty: Tokens,
+            ty: TokenStream,
             ty_is_static: bool,
-            id: Tokens,
+            id: TokenStream,
+            routing: TokenStream,
         }
 
         // Get the default method blocks for `DocumentType`
         fn get_doc_type_methods(
-            crate_root: &Tokens,
-            item: &syn::MacroInput,
-            fields: &[(syn::Ident, &syn::Field)],
-        ) -> ElasticMetadataMethods {
+            crate_root: &TokenStream,
+            item: &syn::DeriveInput,
+            fields: &[(String, &syn::Field)],
+        ) -> Result<ElasticMetadataMethods, DeriveElasticTypeError> {
             // Get the default name for the indexed elasticsearch type name
-            fn get_elastic_type_name(item: &syn::MacroInput) -> syn::Lit {
-                syn::Lit::Str(
-                    format!("{}", item.ident).to_lowercase(),
-                    syn::StrStyle::Cooked,
-                )
+            fn get_elastic_type_name(item: &syn::DeriveInput) -> String {
+                format!("{}", item.ident).to_lowercase()
             }
 
             let (index, index_is_static) = {
-                match get_method_from_struct(item, "index") {
-                    Some(MethodFromStruct::Literal(name)) => (name, true),
-                    Some(MethodFromStruct::Expr(expr)) => (expr, false),
+                match (
+                    get_index_template_from_struct(crate_root, item)?,
+                    get_method_from_struct(item, "index"),
+                ) {
+                    (Some(template), _) => (template, false),
+                    (_, Some(MethodFromStruct::Literal(name))) => (name, true),
+                    (_, Some(MethodFromStruct::Expr(expr))) => (expr, false),
                     _ => {
                         let name = get_elastic_type_name(item);
                         (quote!(#name), true)
@@ -195,31 +982,45 @@ fn get_doc_ty_impl_block(
                 }
             };
 
-            let id = get_method_from_struct(item, "id")
-                .map(|id_expr| match id_expr {
-                    MethodFromStruct::Expr(expr) => expr,
-                    _ => panic!("id attributes on a struct definition must be of the form #[id(expr = \"expression\")]"),
-                })
-                .or_else(|| {
-                    get_method_from_fields(fields, "id").map(|field| match field {
-                        MethodFromField::Field(field) => quote!(&self . #field),
-                        MethodFromField::Expr(field, expr) => quote!({
-                            let #field = &self . #field;
-                            #expr
-                        }),
-                        _ => panic!("id attributes on a field must be of the form #[id] or #[id(expr = \"expression\")]"),
-                    })
-                })
-                .map(|id_expr| quote!(Some((#id_expr).into())))
-                .unwrap_or_else(|| quote!(None));
+            // Get an optional value, like the id, from the struct or one of its fields
+            fn get_document_id(
+                crate_root: &TokenStream,
+                item: &syn::DeriveInput,
+                fields: &[(String, &syn::Field)],
+                method: &str,
+            ) -> Result<TokenStream, DeriveElasticTypeError> {
+                if let Some(id_expr) = get_method_from_struct(item, method) {
+                    return match id_expr {
+                        MethodFromStruct::Expr(expr) => Ok(quote!(Some((#expr).into()))),
+                        MethodFromStruct::Literal(lit) => invalid_attribute(lit.span(), format!("{} attributes on a struct definition must be of the form #[elastic({}(expr = \"expression\"))]", method, method)),
+                    };
+                }
+
+                match get_method_from_fields(fields, method) {
+                    Some(MethodFromField::Field(field)) => Ok(quote!(#crate_root::derive::DocumentId::document_id(&self . #field))),
+                    Some(MethodFromField::Expr(field, expr)) => Ok(quote!(Some({
+                        let #field = &self . #field;
+                        #expr
+                    }.into()))),
+                    Some(MethodFromField::Literal(field, lit)) => Err(DeriveElasticTypeError::InvalidFieldAttribute(
+                        field.to_string(),
+                        Box::new(DeriveElasticTypeError::InvalidAttribute(format!("{} attributes on a field must be of the form #[elastic({})] or #[elastic({}(expr = \"expression\"))]", method, method, method), lit.span())),
+                    )),
+                    None => Ok(quote!(None)),
+                }
+            }
+
+            let id = get_document_id(crate_root, item, fields, "id")?;
+            let routing = get_document_id(crate_root, item, fields, "routing")?;
 
-            ElasticMetadataMethods {
+            Ok(ElasticMetadataMethods {
                 index,
                 index_is_static,
                 ty,
                 ty_is_static,
                 id,
-            }
+                routing,
+            })
         }
 
         let ElasticMetadataMethods {
@@ -228,9 +1029,11 @@ fn get_doc_ty_impl_block(
             ref ty,
             ty_is_static,
             ref id,
-        } = get_doc_type_methods(crate_root, item, fields);
+            ref routing,
+        } = get_doc_type_methods(crate_root, item, fields)?;
 
         let doc_ty = &item.ident;
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
         let (partial_static_index, static_index_block) = if index_is_static {
             let method = quote!(
@@ -240,7 +1043,7 @@ fn get_doc_ty_impl_block(
             );
 
             let block = quote!(
-                impl #crate_root::derive::StaticIndex for #doc_ty { }
+                impl #impl_generics #crate_root::derive::StaticIndex for #doc_ty #ty_generics #where_clause { }
             );
 
             (Some(method), Some(block))
@@ -262,7 +1065,7 @@ fn get_doc_ty_impl_block(
             );
 
             let block = quote!(
-                impl #crate_root::derive::StaticType for #doc_ty { }
+                impl #impl_generics #crate_root::derive::StaticType for #doc_ty #ty_generics #where_clause { }
             );
 
             (Some(method), Some(block))
@@ -289,72 +1092,211 @@ fn get_doc_ty_impl_block(
                 (#id).into()
             }
 
+            fn partial_routing(&self) -> ::std::option::Option<::std::borrow::Cow<str>> {
+                (#routing).into()
+            }
+
             #partial_static_index
 
             #partial_static_ty
         );
 
-        MetadataBlock {
+        Ok(MetadataBlock {
             instance_methods,
             static_impls: quote!(
                 #static_index_block
 
                 #static_ty_block
             ),
-        }
+        })
     }
 
     let doc_ty = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let MetadataBlock {
         instance_methods,
         static_impls,
-    } = get_doc_ty_methods(crate_root, item, fields);
+    } = get_doc_ty_methods(crate_root, item, generics, fields)?;
 
-    quote!(
-        impl #crate_root::derive::ObjectFieldType for #doc_ty {
+    Ok(quote!(
+        impl #impl_generics #crate_root::derive::ObjectFieldType for #doc_ty #ty_generics #where_clause {
             type Mapping = #mapping;
         }
 
-        impl #crate_root::derive::DocumentType for #doc_ty {
+        impl #impl_generics #crate_root::derive::DocumentType for #doc_ty #ty_generics #where_clause {
             #instance_methods
         }
 
         #static_impls
-    )
+    ))
+}
+
+// Implement a function that returns the serialised index mapping for the type being derived
+// Parses #[elastic(mapping_json)]
+// Parses #[elastic(mapping_json = "compact")]
+// Parses #[elastic(mapping_json = "pretty")]
+fn get_mapping_json_impl_block(
+    crate_root: &TokenStream,
+    item: &syn::DeriveInput,
+    generics: &syn::Generics,
+) -> Result<Option<TokenStream>, DeriveElasticTypeError> {
+    let val = get_elastic_meta_items(&item.attrs);
+
+    let pretty = if val.iter().any(|meta| expect_ident("mapping_json", meta)) {
+        Some(false)
+    } else {
+        val.iter()
+            .filter_map(|meta| expect_name_value("mapping_json", meta))
+            .next()
+            .map(|lit| {
+                let format = get_str_from_lit(lit);
+
+                match format.as_ref().map(String::as_str) {
+                    Ok("compact") => Ok(false),
+                    Ok("pretty") => Ok(true),
+                    _ => invalid_attribute(
+                        lit.span(),
+                        "mapping_json attributes must be one of `\"compact\"` or `\"pretty\"`",
+                    ),
+                }
+            })
+            .transpose()?
+    };
+
+    let pretty = match pretty {
+        Some(pretty) => pretty,
+        None => return Ok(None),
+    };
+
+    let doc_ty = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(Some(quote!(
+        impl #impl_generics #doc_ty #ty_generics #where_clause {
+            /**
+            Get the index mapping for this document serialised as json.
+
+            The json is serialised when this function is called, because field mappings can't be serialised in a constant.
+            */
+            pub fn mapping_json() -> ::std::result::Result<::std::string::String, #crate_root::derive::MappingJsonError> {
+                #crate_root::derive::mapping_json::<Self>(#pretty)
+            }
+        }
+    )))
 }
 
 // Implement PropertiesMapping for the mapping
 fn get_props_impl_block(
-    crate_root: &Tokens,
+    crate_root: &TokenStream,
     props_ty: &syn::Ident,
-    fields: &[(syn::Ident, &syn::Field)],
-) -> Tokens {
+    generics: &syn::Generics,
+    fields: &[(String, &syn::Field)],
+) -> Result<TokenStream, DeriveElasticTypeError> {
+    // Get the serde serialisation statement for a field on the type being derived
+    fn get_field_ser_stmt(
+        crate_root: &TokenStream,
+        name: &str,
+        field: &syn::Field,
+    ) -> Result<TokenStream, DeriveElasticTypeError> {
+        let lit = name;
+        let ty = &field.ty;
+
+        let params: Vec<TokenStream> = vec![
+            get_copy_to_from_attrs(&field.attrs)?.map(|copy_to| quote!(copy_to: ::std::option::Option::Some(#copy_to))),
+            get_normalizer_from_attrs(&field.attrs)?.map(|normalizer| quote!(normalizer: ::std::option::Option::Some(#normalizer))),
+            get_lit_param_from_attrs("analyzer", &field.attrs).map(|analyzer| quote!(analyzer: ::std::option::Option::Some(#analyzer))),
+            get_lit_param_from_attrs("search_analyzer", &field.attrs).map(|search_analyzer| quote!(search_analyzer: ::std::option::Option::Some(#search_analyzer))),
+            get_lit_param_from_attrs("boost", &field.attrs).map(|boost| quote!(boost: ::std::option::Option::Some(#boost))),
+            get_lit_param_from_attrs("ignore_above", &field.attrs).map(|ignore_above| quote!(ignore_above: ::std::option::Option::Some(#ignore_above))),
+            get_bool_param_from_attrs("ignore_malformed", &field.attrs)?.map(|ignore_malformed| quote!(ignore_malformed: ::std::option::Option::Some(#ignore_malformed))),
+            get_bool_param_from_attrs("coerce", &field.attrs)?.map(|coerce| quote!(coerce: ::std::option::Option::Some(#coerce))),
+            get_bool_param_from_attrs("doc_values", &field.attrs)?.map(|doc_values| quote!(doc_values: ::std::option::Option::Some(#doc_values))),
+            get_bool_param_from_attrs("index", &field.attrs)?.map(|index| quote!(index: ::std::option::Option::Some(#index))),
+            get_bool_param_from_attrs("store", &field.attrs)?.map(|store| quote!(store: ::std::option::Option::Some(#store))),
+            get_null_value_from_attrs(&field.attrs).map(|null_value| quote!(null_value: ::std::option::Option::Some(#crate_root::derive::null_value::<#ty, _, _>(#null_value)))),
+            get_field_data_type_from_attrs(crate_root, &field.attrs)?.map(|data_type| quote!(data_type: ::std::option::Option::Some(#crate_root::derive::object_data_type::<#ty, _, _>(#data_type)))),
+            get_sub_fields_from_attrs(crate_root, &field.attrs)?.map(|fields| quote!(fields: ::std::option::Option::Some(#fields))),
+        ]
+        .into_iter()
+        .filter_map(|param| param)
+        .collect();
+
+        let stmt = if params.is_empty() {
+            quote!(try!(#crate_root::derive::field_ser::<#ty, _, _, _>(state, #lit));)
+        } else {
+            quote!(try!(#crate_root::derive::field_ser_with_params::<#ty, _, _, _>(state, #lit, #crate_root::derive::FieldParams {
+                #(#params,)*
+                ..::std::default::Default::default()
+            }));)
+        };
+
+        Ok(stmt)
+    }
+
     // Get the serde serialisation statements for each of the fields on the type being derived
     fn get_field_ser_stmts(
-        crate_root: &Tokens,
-        fields: &[(syn::Ident, &syn::Field)],
-    ) -> Vec<Tokens> {
-        let fields: Vec<Tokens> = fields
+        crate_root: &TokenStream,
+        fields: &[(String, &syn::Field)],
+    ) -> Result<Vec<TokenStream>, DeriveElasticTypeError> {
+        fields
             .iter()
-            .cloned()
-            .map(|(name, field)| {
-                let lit = syn::Lit::Str(name.as_ref().to_string(), syn::StrStyle::Cooked);
-                let ty = &field.ty;
-
-                // quote!()
-                quote!(try!(#crate_root::derive::field_ser::<#ty, _, _, _>(state, #lit));)
+            .map(|&(ref name, field)| {
+                get_field_ser_stmt(crate_root, name, field).map_err(|err| err.for_field(field))
             })
-            .collect();
+            .collect()
+    }
 
-        fields
+    // Get the metadata for each of the fields that are deprecated or have custom metadata
+    // Parses #[elastic(deprecated)]
+    // Parses #[elastic(meta($key = $lit))]
+    fn get_field_meta_stmts(
+        crate_root: &TokenStream,
+        fields: &[(String, &syn::Field)],
+    ) -> Result<Vec<TokenStream>, DeriveElasticTypeError> {
+        let mut stmts = Vec::new();
+
+        for &(ref name, field) in fields {
+            let val = get_elastic_meta_items(&field.attrs);
+
+            let deprecated = val.iter().any(|meta| expect_ident("deprecated", meta));
+            let meta = get_meta_from_attrs(&field.attrs).map_err(|err| err.for_field(field))?;
+
+            if !deprecated && meta.is_none() {
+                continue;
+            }
+
+            let lit = name.as_str();
+            let meta = meta.unwrap_or_else(|| quote!(&[]));
+
+            stmts.push(quote!(#crate_root::derive::FieldMeta {
+                name: #lit,
+                deprecated: #deprecated,
+                meta: #meta,
+            }));
+        }
+
+        Ok(stmts)
     }
 
-    let stmts = get_field_ser_stmts(crate_root, fields);
+    let stmts = get_field_ser_stmts(crate_root, fields)?;
     let stmts_len = stmts.len();
 
-    quote!(
-        impl #crate_root::derive::PropertiesMapping for #props_ty {
+    let meta_stmts = get_field_meta_stmts(crate_root, fields)?;
+    let props_meta = if meta_stmts.is_empty() {
+        None
+    } else {
+        Some(quote!(
+            fn props_meta() -> &'static [#crate_root::derive::FieldMeta] {
+                &[#(#meta_stmts),*]
+            }
+        ))
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote!(
+        impl #impl_generics #crate_root::derive::PropertiesMapping for #props_ty #ty_generics #where_clause {
             fn props_len() -> usize { #stmts_len }
 
             fn serialize_props<S>(state: &mut S) -> ::std::result::Result<(), S::Error>
@@ -362,13 +1304,381 @@ fn get_props_impl_block(
                 #(#stmts)*
                 Ok(())
             }
+
+            #props_meta
+        }
+    ))
+}
+
+// Get the custom metadata supplied by an #[elastic()] attribute as a static slice of key-value pairs
+// Parses #[elastic(meta($key = $lit, ...))]
+fn get_meta_from_attrs(
+    attrs: &[syn::Attribute],
+) -> Result<Option<TokenStream>, DeriveElasticTypeError> {
+    let val = get_elastic_meta_items(attrs);
+
+    let lists: Vec<&Punctuated<syn::NestedMeta, Token![,]>> = val
+        .iter()
+        .filter_map(|meta| expect_list("meta", meta))
+        .collect();
+
+    if lists.is_empty() {
+        return Ok(None);
+    }
+
+    const INVALID_META: &str =
+        "meta attributes must be of the form #[elastic(meta(key = \"value\"))]";
+
+    let pairs: Vec<TokenStream> = lists
+        .into_iter()
+        .flat_map(|attrs| attrs)
+        .map(|meta| match *meta {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(ref name_value)) => {
+                let key = match name_value.path.get_ident() {
+                    Some(key) => key.to_string(),
+                    None => return invalid_attribute(name_value.path.span(), INVALID_META),
+                };
+                let value = match name_value.lit {
+                    syn::Lit::Str(ref value) => value.value(),
+                    syn::Lit::Int(ref value) => value.base10_digits().to_owned(),
+                    syn::Lit::Bool(ref value) => value.value.to_string(),
+                    _ => return invalid_attribute(name_value.lit.span(), INVALID_META),
+                };
+
+                Ok(quote!((#key, #value)))
+            }
+            _ => invalid_attribute(meta.span(), INVALID_META),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Some(quote!(&[#(#pairs),*])))
+}
+
+// Get the fields to copy values into supplied by an #[elastic()] attribute as a static slice
+// Parses #[elastic(copy_to = $lit)]
+// Parses #[elastic(copy_to($lit, ...))]
+fn get_copy_to_from_attrs(
+    attrs: &[syn::Attribute],
+) -> Result<Option<TokenStream>, DeriveElasticTypeError> {
+    let val = get_elastic_meta_items(attrs);
+
+    let mut fields: Vec<&syn::Lit> = Vec::new();
+    for meta in &val {
+        match expect_name_value("copy_to", meta) {
+            Some(field) => fields.push(field),
+            None => {
+                for meta in expect_list("copy_to", meta)
+                    .into_iter()
+                    .flat_map(|list| list)
+                {
+                    match *meta {
+                        syn::NestedMeta::Lit(ref field) => fields.push(field),
+                        _ => return invalid_attribute(
+                            meta.span(),
+                            "copy_to attributes must be of the form #[elastic(copy_to(\"field\", ...))]",
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    if fields.is_empty() {
+        return Ok(None);
+    }
+
+    for field in &fields {
+        if get_str_from_lit(field).is_err() {
+            return invalid_attribute(
+                field.span(),
+                "the fields in a copy_to attribute must be strings",
+            );
+        }
+    }
+
+    Ok(Some(quote!(&[#(#fields),*])))
+}
+
+// Get the normalizer supplied by an #[elastic()] attribute
+// Parses #[elastic(normalizer = $lit)]
+fn get_normalizer_from_attrs(
+    attrs: &[syn::Attribute],
+) -> Result<Option<syn::Lit>, DeriveElasticTypeError> {
+    let val = get_elastic_meta_items(attrs);
+
+    val.iter()
+        .filter_map(|meta| expect_name_value("normalizer", meta))
+        .next()
+        .map(|normalizer| match get_str_from_lit(normalizer) {
+            Ok(_) => Ok(normalizer.clone()),
+            Err(_) => invalid_attribute(
+                normalizer.span(),
+                "the normalizer in a normalizer attribute must be a string",
+            ),
+        })
+        .transpose()
+}
+
+// Get the null value supplied by an #[elastic()] attribute
+// The type of the literal is checked against the field mapping by the compiler
+// Parses #[elastic(null_value = $lit)]
+fn get_null_value_from_attrs(attrs: &[syn::Attribute]) -> Option<syn::Lit> {
+    let val = get_elastic_meta_items(attrs);
+
+    val.iter()
+        .filter_map(|meta| expect_name_value("null_value", meta))
+        .next()
+        .cloned()
+}
+
+// Get the datatype for an object field supplied by an #[elastic()] attribute
+// The field is checked to be an object by the compiler
+// Parses #[elastic(nested)]
+// Parses #[elastic(object)]
+fn get_field_data_type_from_attrs(
+    crate_root: &TokenStream,
+    attrs: &[syn::Attribute],
+) -> Result<Option<TokenStream>, DeriveElasticTypeError> {
+    let val = get_elastic_meta_items(attrs);
+
+    let nested = val.iter().find(|meta| expect_ident("nested", meta));
+    let object = val.iter().any(|meta| expect_ident("object", meta));
+
+    match (nested, object) {
+        (Some(nested), true) => invalid_attribute(
+            nested.span(),
+            "a field can't be mapped as both #[elastic(nested)] and #[elastic(object)]",
+        ),
+        (Some(_), false) => Ok(Some(quote!(#crate_root::derive::NESTED_DATATYPE))),
+        (None, true) => Ok(Some(quote!(#crate_root::derive::OBJECT_DATATYPE))),
+        (None, false) => Ok(None),
+    }
+}
+
+// Get a mapping parameter supplied by an #[elastic()] attribute
+// The type of the literal is checked against the parameter by the compiler
+// Parses #[elastic($name = $lit)]
+fn get_lit_param_from_attrs(name: &str, attrs: &[syn::Attribute]) -> Option<syn::Lit> {
+    let val = get_elastic_meta_items(attrs);
+
+    val.iter()
+        .filter_map(|meta| expect_name_value(name, meta))
+        .next()
+        .cloned()
+}
+
+// Get a boolean mapping parameter supplied by an #[elastic()] attribute
+// Parses #[elastic($name)]
+// Parses #[elastic($name = $lit)]
+fn get_bool_param_from_attrs(
+    name: &str,
+    attrs: &[syn::Attribute],
+) -> Result<Option<bool>, DeriveElasticTypeError> {
+    let val = get_elastic_meta_items(attrs);
+
+    val.iter()
+        .filter_map(|meta| {
+            if expect_ident(name, meta) {
+                return Some(Ok(true));
+            }
+
+            expect_name_value(name, meta).map(|lit| match *lit {
+                syn::Lit::Bool(ref value) => Ok(value.value),
+                _ => invalid_attribute(
+                    lit.span(),
+                    format!(
+                        "{} attributes must be of the form #[elastic({})] or #[elastic({} = bool)]",
+                        name, name, name
+                    ),
+                ),
+            })
+        })
+        .next()
+        .transpose()
+}
+
+// Get the multi-fields supplied by an #[elastic()] attribute as a slice of named `StringField`s
+// Parses #[elastic(fields($name, ...))]
+// Parses #[elastic(fields($name($key = $lit, ...), ...))]
+// The type of a multi-field is taken from its name if it's `keyword`, `text`, `token_count` or `completion`
+// Otherwise it's `text`, unless a `ty = $lit` is given
+fn get_sub_fields_from_attrs(
+    crate_root: &TokenStream,
+    attrs: &[syn::Attribute],
+) -> Result<Option<TokenStream>, DeriveElasticTypeError> {
+    let val = get_elastic_meta_items(attrs);
+
+    let lists: Vec<&Punctuated<syn::NestedMeta, Token![,]>> = val
+        .iter()
+        .filter_map(|meta| expect_list("fields", meta))
+        .collect();
+
+    if lists.is_empty() {
+        return Ok(None);
+    }
+
+    const INVALID_FIELDS: &str =
+        "fields attributes must be of the form #[elastic(fields(name(key = \"value\")))]";
+
+    let sub_fields: Vec<TokenStream> = lists
+        .into_iter()
+        .flat_map(|attrs| attrs)
+        .map(|meta| {
+            let (name, params): (&syn::Path, Vec<&syn::NestedMeta>) = match *meta {
+                syn::NestedMeta::Meta(syn::Meta::Path(ref name)) => (name, Vec::new()),
+                syn::NestedMeta::Meta(syn::Meta::List(ref list)) => {
+                    (&list.path, list.nested.iter().collect())
+                }
+                _ => return invalid_attribute(meta.span(), INVALID_FIELDS),
+            };
+
+            let name = match name.get_ident() {
+                Some(name) => name.to_string(),
+                None => return invalid_attribute(name.span(), INVALID_FIELDS),
+            };
+
+            let ty = match params
+                .iter()
+                .filter_map(|meta| expect_name_value("ty", meta))
+                .next()
+            {
+                Some(ty) => match get_str_from_lit(ty) {
+                    Ok(ty) => ty,
+                    Err(_) => return invalid_attribute(ty.span(), "the ty of a field in a fields attribute must be a string"),
+                },
+                None => match name.as_str() {
+                    ty @ "keyword" | ty @ "text" | ty @ "token_count" | ty @ "completion" => ty.to_owned(),
+                    _ => "text".to_owned(),
+                },
+            };
+
+            let (variant, mapping) = match ty.as_str() {
+                "keyword" => (quote!(Keyword), quote!(KeywordFieldMapping)),
+                "text" => (quote!(Text), quote!(TextFieldMapping)),
+                "token_count" => (quote!(TokenCount), quote!(ElasticTokenCountFieldMapping)),
+                "completion" => (quote!(Completion), quote!(ElasticCompletionFieldMapping)),
+                _ => return invalid_attribute(
+                    meta.span(),
+                    "the ty of a field in a fields attribute must be one of `keyword`, `text`, `token_count` or `completion`"
+                ),
+            };
+
+            let mut sub_field_params: Vec<TokenStream> = Vec::new();
+            for meta in params {
+                match *meta {
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(ref name_value))
+                        if name_value.path.is_ident("ty") => {}
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(ref name_value)) => {
+                        let key = &name_value.path;
+                        let lit = &name_value.lit;
+
+                        sub_field_params.push(quote!(#key: ::std::option::Option::Some(#lit)))
+                    }
+                    _ => return invalid_attribute(meta.span(), INVALID_FIELDS),
+                }
+            }
+
+            Ok(quote!((#name, #crate_root::derive::StringField::#variant(#crate_root::derive::#mapping {
+                #(#sub_field_params,)*
+                ..::std::default::Default::default()
+            }))))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Some(quote!(&[#(#sub_fields),*])))
+}
+
+fn is_generic(item: &syn::DeriveInput) -> bool {
+    !item.generics.params.is_empty()
+}
+
+// Get the generics for the derived impls, with bounds for the type parameters of mapped fields
+// Type parameters are bound as object types unless bounds are supplied by an #[elastic()] attribute
+// Parses #[elastic(bound = $lit)]
+fn get_generics(
+    crate_root: &TokenStream,
+    item: &syn::DeriveInput,
+    fields: &[(String, &syn::Field)],
+) -> Result<syn::Generics, DeriveElasticTypeError> {
+    let mut generics = item.generics.clone();
+
+    let bound = get_elastic_meta_items(&item.attrs)
+        .iter()
+        .filter_map(|meta| expect_name_value("bound", meta))
+        .next()
+        .map(|bound| match *bound {
+            syn::Lit::Str(ref predicates) => predicates
+                .parse_with(Punctuated::<syn::WherePredicate, Token![,]>::parse_terminated)
+                .or_else(|_| {
+                    invalid_attribute(
+                        bound.span(),
+                        format!(
+                            "the bounds `{}` in a bound attribute must be valid where predicates",
+                            predicates.value()
+                        ),
+                    )
+                }),
+            _ => invalid_attribute(
+                bound.span(),
+                "the bounds in a bound attribute must be a string",
+            ),
+        })
+        .transpose()?;
+
+    let bounds = match bound {
+        Some(bound) => bound,
+        None => {
+            let field_tys: Vec<String> = fields
+                .iter()
+                .map(|&(_, field)| {
+                    let ty = &field.ty;
+                    quote!(#ty).to_string()
+                })
+                .collect();
+
+            item.generics
+                .type_params()
+                .map(|param| &param.ident)
+                .filter(|param| {
+                    field_tys.iter().any(|ty| {
+                        ty.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                            .any(|ident| *param == ident)
+                    })
+                })
+                .map(|param| -> syn::WherePredicate {
+                    parse_quote!(#param: #crate_root::derive::ObjectFieldType)
+                })
+                .collect()
         }
-    )
+    };
+
+    if !bounds.is_empty() {
+        generics.make_where_clause().predicates.extend(bounds);
+    }
+
+    Ok(generics)
 }
 
-fn get_ser_field(field: &syn::Field) -> Option<(syn::Ident, &syn::Field)> {
+fn get_ser_item(item: &syn::DeriveInput) -> Option<serde_attr::Container> {
     let ctxt = serde_derive_internals::Ctxt::new();
-    let serde_field = serde_attr::Field::from_ast(&ctxt, 0, field);
+    let serde_item = serde_attr::Container::from_ast(&ctxt, item);
+
+    // If the `serde` parse fails, return `None` and let `serde` panic later
+    match ctxt.check() {
+        Err(_) => None,
+        _ => Some(serde_item),
+    }
+}
+
+// Get the name a field is serialised with by `serde`
+// Returns `None` if the field isn't serialised
+fn get_serde_name(
+    rename_rules: Option<&serde_attr::RenameAllRules>,
+    field: &syn::Field,
+) -> Option<String> {
+    let ctxt = serde_derive_internals::Ctxt::new();
+    let mut serde_field =
+        serde_attr::Field::from_ast(&ctxt, 0, field, None, &serde_attr::Default::None);
 
     // If the `serde` parse fails, return `None` and let `serde` panic later
     match ctxt.check() {
@@ -381,36 +1691,118 @@ fn get_ser_field(field: &syn::Field) -> Option<(syn::Ident, &syn::Field)> {
         return None;
     }
 
-    Some((
-        syn::Ident::from(serde_field.name().serialize_name().as_ref()),
-        field,
-    ))
+    // Apply `#[serde(rename_all)]` to fields that aren't renamed themselves
+    if let Some(rename_rules) = rename_rules {
+        serde_field.rename_by_rules(rename_rules);
+    }
+
+    Some(serde_field.name().serialize_name())
+}
+
+fn get_ser_field<'a>(
+    rename_rules: Option<&serde_attr::RenameAllRules>,
+    field: &'a syn::Field,
+) -> Result<Option<(String, &'a syn::Field)>, DeriveElasticTypeError> {
+    let serde_name = match get_serde_name(rename_rules, field) {
+        Some(serde_name) => serde_name,
+        None => return Ok(None),
+    };
+
+    // An `#[elastic(rename)]` takes precedence over the serialised name
+    let name = get_rename_from_attrs(&field.attrs)
+        .map_err(|err| err.for_field(field))?
+        .unwrap_or(serde_name);
+
+    Ok(Some((name, field)))
+}
+
+// Get the name for a field in the mapping supplied by an #[elastic()] attribute
+// Parses #[elastic(rename = $lit)]
+fn get_rename_from_attrs(
+    attrs: &[syn::Attribute],
+) -> Result<Option<String>, DeriveElasticTypeError> {
+    let val = get_elastic_meta_items(attrs);
+
+    val.iter()
+        .filter_map(|meta| expect_name_value("rename", meta))
+        .next()
+        .map(|rename| match get_str_from_lit(rename) {
+            Ok(rename) => Ok(rename),
+            Err(_) => invalid_attribute(
+                rename.span(),
+                "the name in a rename attribute must be a string",
+            ),
+        })
+        .transpose()
 }
 
 quick_error! {
     #[derive(Debug)]
     pub enum DeriveElasticTypeError {
-        InvalidInput {
-            display("deriving a document type is only valid for structs")
+        InvalidInput(span: Span) {
+            display("deriving a document type is only valid for structs with named fields, or newtype structs")
+        }
+        InvalidAttribute(msg: String, span: Span) {
+            display("{}", msg)
+        }
+        InvalidFieldAttribute(field: String, err: Box<DeriveElasticTypeError>) {
+            display("invalid attribute on field `{}`: {}", field, err)
+        }
+    }
+}
+
+impl DeriveElasticTypeError {
+    /** Get the span of the input that caused the error. */
+    pub fn span(&self) -> Span {
+        match *self {
+            DeriveElasticTypeError::InvalidInput(span)
+            | DeriveElasticTypeError::InvalidAttribute(_, span) => span,
+            DeriveElasticTypeError::InvalidFieldAttribute(_, ref err) => err.span(),
+        }
+    }
+
+    // Attach the name of the field an attribute was declared on to an error
+    fn for_field(self, field: &syn::Field) -> Self {
+        match self {
+            DeriveElasticTypeError::InvalidAttribute(..) => {
+                let name = field
+                    .ident
+                    .as_ref()
+                    .map(|ident| ident.to_string())
+                    .unwrap_or_default();
+
+                DeriveElasticTypeError::InvalidFieldAttribute(name, Box::new(self))
+            }
+            err => err,
         }
     }
 }
 
+impl From<syn::Error> for DeriveElasticTypeError {
+    fn from(err: syn::Error) -> Self {
+        DeriveElasticTypeError::InvalidAttribute(err.to_string(), err.span())
+    }
+}
+
+fn invalid_attribute<T>(span: Span, msg: impl Into<String>) -> Result<T, DeriveElasticTypeError> {
+    Err(DeriveElasticTypeError::InvalidAttribute(msg.into(), span))
+}
+
 enum MethodFromStruct {
-    Literal(Tokens),
-    Expr(Tokens),
+    Literal(TokenStream),
+    Expr(TokenStream),
 }
 
 enum MethodFromField {
-    Field(Tokens),
-    Literal(Tokens, Tokens),
-    Expr(Tokens, Tokens),
+    Field(TokenStream),
+    Literal(TokenStream, TokenStream),
+    Expr(TokenStream, TokenStream),
 }
 
 // Get the mapping ident supplied by an #[elastic()] attribute or create a default one
 // Parses #[elastic(method = $lit)]
 // Parses #[elastic(method(expr = $expr))]
-fn get_method_from_struct(item: &syn::MacroInput, method: &str) -> Option<MethodFromStruct> {
+fn get_method_from_struct(item: &syn::DeriveInput, method: &str) -> Option<MethodFromStruct> {
     let val = get_elastic_meta_items(&item.attrs);
 
     // Attempt to get a literal
@@ -436,8 +1828,104 @@ fn get_method_from_struct(item: &syn::MacroInput, method: &str) -> Option<Method
     None
 }
 
+// Get an index name that's formatted from the fields of a document
+// Placeholders are replaced with the value of a field, or a date field in the given format
+// Parses #[elastic(index = "prefix-{field}-{date_field:format}")]
+fn get_index_template_from_struct(
+    crate_root: &TokenStream,
+    item: &syn::DeriveInput,
+) -> Result<Option<TokenStream>, DeriveElasticTypeError> {
+    let val = get_elastic_meta_items(&item.attrs);
+
+    let (lit, template) = match val
+        .iter()
+        .filter_map(|meta| expect_name_value("index", meta))
+        .next()
+    {
+        Some(lit) => match get_str_from_lit(lit) {
+            Ok(template) if template.contains('{') => (lit, template),
+            _ => return Ok(None),
+        },
+        None => return Ok(None),
+    };
+
+    let mut fmt = String::new();
+    let mut args = Vec::new();
+    let mut rest = template.as_str();
+
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => {
+                return invalid_attribute(
+                    lit.span(),
+                    format!(
+                        "placeholders in the index name `{}` must be closed with a `}}`",
+                        template
+                    ),
+                )
+            }
+        };
+
+        fmt.push_str(&rest[..start].replace('}', "}}"));
+        fmt.push_str("{}");
+
+        let mut placeholder = rest[start + 1..end].splitn(2, ':');
+        let field = placeholder.next().unwrap_or_default().trim();
+        if field.is_empty() {
+            return invalid_attribute(
+                lit.span(),
+                format!(
+                    "the index name `{}` contains an empty placeholder; placeholders must name a field, like `{{field}}` or `{{field:%Y.%m.%d}}`",
+                    template
+                ),
+            );
+        }
+
+        let field = match syn::parse_str::<syn::Ident>(field) {
+            Ok(field) => field,
+            Err(_) => {
+                return invalid_attribute(
+                    lit.span(),
+                    format!(
+                        "the placeholder `{}` in the index name `{}` must be a field name",
+                        field, template
+                    ),
+                )
+            }
+        };
+
+        args.push(match placeholder.next() {
+            Some(date_format) => {
+                // Check the format is one chrono can render so mistakes show up at compile time
+                let is_valid = !date_format.is_empty()
+                    && StrftimeItems::new(date_format).all(|item| item != Item::Error);
+
+                if !is_valid {
+                    return invalid_attribute(
+                        lit.span(),
+                        format!(
+                            "the date format `{}` for the placeholder `{}` in the index name `{}` is not a valid chrono format",
+                            date_format, field, template
+                        ),
+                    );
+                }
+
+                quote!(#crate_root::derive::format_index_date(&self.#field, #date_format))
+            }
+            None => quote!(&self.#field),
+        });
+
+        rest = &rest[end + 1..];
+    }
+
+    fmt.push_str(&rest.replace('}', "}}"));
+
+    Ok(Some(quote!(format!(#fmt, #(#args),*))))
+}
+
 fn get_method_from_fields(
-    fields: &[(syn::Ident, &syn::Field)],
+    fields: &[(String, &syn::Field)],
     method: &str,
 ) -> Option<MethodFromField> {
     for &(_, ref field) in fields {
@@ -473,3 +1961,247 @@ fn get_method_from_fields(
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_err(input: &str) -> String {
+        let ast = syn::parse_str::<syn::DeriveInput>(input).unwrap();
+
+        match expand_derive(quote!(::elastic_types), &ast) {
+            Ok(_) => panic!("expected the derive to fail"),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn expand_valid_struct() {
+        let ast = syn::parse_str::<syn::DeriveInput>(
+            "struct Doc { #[elastic(id, doc_values = false)] id: String, count: i32 }",
+        )
+        .unwrap();
+
+        assert!(expand_derive(quote!(::elastic_types), &ast).is_ok());
+    }
+
+    #[test]
+    fn expand_mapping_json() {
+        let ast = syn::parse_str::<syn::DeriveInput>(
+            "#[elastic(mapping_json = \"pretty\")] struct Doc { count: i32 }",
+        )
+        .unwrap();
+
+        let expanded = expand_derive(quote!(::elastic_types), &ast).unwrap();
+        let expanded = quote!(#(#expanded)*).to_string();
+
+        assert!(expanded.contains("pub fn mapping_json ()"));
+        assert!(expanded.contains("mapping_json :: < Self > (true)"));
+    }
+
+    #[test]
+    fn expand_generic_mapping_json() {
+        let ast = syn::parse_str::<syn::DeriveInput>(
+            "#[elastic(mapping_json)] struct Doc<T> { value: T }",
+        )
+        .unwrap();
+
+        let expanded = expand_derive(quote!(::elastic_types), &ast).unwrap();
+        let expanded = quote!(#(#expanded)*).to_string();
+
+        assert!(expanded.contains("impl < T > Doc < T >"));
+        assert!(expanded.contains("mapping_json :: < Self > (false)"));
+    }
+
+    #[test]
+    fn expand_invalid_mapping_json_attribute_is_err() {
+        assert_eq!(
+            "mapping_json attributes must be one of `\"compact\"` or `\"pretty\"`",
+            expand_err("#[elastic(mapping_json = \"yaml\")] struct Doc { count: i32 }")
+        );
+    }
+
+    #[test]
+    fn expand_partial() {
+        let ast = syn::parse_str::<syn::DeriveInput>(
+            "#[elastic(partial)] pub struct Doc { pub id: String, #[serde(rename = \"n\")] count: i32 }",
+        )
+        .unwrap();
+
+        let expanded = expand_derive(quote!(::elastic_types), &ast).unwrap();
+        let expanded = quote!(#(#expanded)*).to_string();
+
+        assert!(expanded.contains("pub struct DocPartial"));
+        assert!(expanded.contains("pub id : :: std :: option :: Option < String >"));
+        assert!(expanded.contains("pub fn id (mut self , value : String)"));
+        assert!(expanded.contains("serialize_entry (& mut state , \"n\" , value)"));
+    }
+
+    #[test]
+    fn expand_partial_with_serde_field_attributes() {
+        let ast = syn::parse_str::<syn::DeriveInput>(
+            "#[elastic(partial)] pub struct Doc { #[serde(serialize_with = \"ser_title\")] pub title: Title, #[serde(skip_serializing_if = \"Vec::is_empty\")] pub tags: Vec<String> }",
+        )
+        .unwrap();
+
+        let expanded = expand_derive(quote!(::elastic_types), &ast).unwrap();
+        let expanded = quote!(#(#expanded)*).to_string();
+
+        assert!(expanded.contains("ser_title (self . value , serializer)"));
+        assert!(expanded.contains("if ! Vec :: is_empty (value)"));
+        assert!(!expanded.contains("Title : :: elastic_types :: derive :: Serialize"));
+        assert!(expanded.contains("Vec < String > : :: elastic_types :: derive :: Serialize"));
+    }
+
+    #[test]
+    fn expand_partial_with_flatten_is_err() {
+        assert_eq!(
+            "invalid attribute on field `extra`: flattened fields aren't supported in partial documents",
+            expand_err(
+                "#[elastic(partial)] struct Doc { #[serde(flatten)] extra: Map<String, Value> }"
+            )
+        );
+    }
+
+    #[test]
+    fn expand_newtype() {
+        let ast = syn::parse_str::<syn::DeriveInput>("struct UserId(String);").unwrap();
+
+        let expanded = expand_derive(quote!(::elastic_types), &ast).unwrap();
+        let expanded = quote!(#(#expanded)*).to_string();
+
+        let impl_ty = "WrappedFieldType < TElasticMapping , TElasticPivot > for UserId";
+        let bound =
+            "String : :: elastic_types :: derive :: FieldType < TElasticMapping , TElasticPivot >";
+
+        assert!(expanded.contains(impl_ty));
+        assert!(expanded.contains(bound));
+    }
+
+    #[test]
+    fn expand_newtype_with_mapping() {
+        let ast = syn::parse_str::<syn::DeriveInput>(
+            "#[elastic(mapping = \"DefaultKeywordMapping\")] struct UserId(String);",
+        )
+        .unwrap();
+
+        let expanded = expand_derive(quote!(::elastic_types), &ast).unwrap();
+        let expanded = quote!(#(#expanded)*).to_string();
+
+        let impl_ty = "WrappedFieldType < DefaultKeywordMapping , TElasticPivot > for UserId";
+
+        assert!(expanded.contains(impl_ty));
+    }
+
+    #[test]
+    fn expand_non_struct_is_err() {
+        assert_eq!(
+            "deriving a document type is only valid for structs with named fields, or newtype structs",
+            expand_err("enum Doc { A, B }")
+        );
+        assert_eq!(
+            "deriving a document type is only valid for structs with named fields, or newtype structs",
+            expand_err("struct Point(i32, i32);")
+        );
+    }
+
+    #[test]
+    fn expand_invalid_struct_attribute_is_err() {
+        assert_eq!(
+            "dynamic attributes must be one of `true`, `false` or `\"strict\"`",
+            expand_err("#[elastic(dynamic = \"sometimes\")] struct Doc { count: i32 }")
+        );
+    }
+
+    #[test]
+    fn expand_invalid_field_attribute_is_err() {
+        assert_eq!(
+            "invalid attribute on field `count`: doc_values attributes must be of the form #[elastic(doc_values)] or #[elastic(doc_values = bool)]",
+            expand_err("struct Doc { #[elastic(doc_values = \"no\")] count: i32 }")
+        );
+    }
+
+    #[test]
+    fn expand_invalid_id_attribute_is_err() {
+        assert_eq!(
+            "invalid attribute on field `id`: id attributes on a field must be of the form #[elastic(id)] or #[elastic(id(expr = \"expression\"))]",
+            expand_err("struct Doc { #[elastic(id = \"1\")] id: String }")
+        );
+    }
+
+    #[test]
+    fn expand_serde_rename_all() {
+        let ast = syn::parse_str::<syn::DeriveInput>(
+            "#[serde(rename_all = \"camelCase\")] struct Doc { user_name: String }",
+        )
+        .unwrap();
+
+        let expanded = expand_derive(quote!(::elastic_types), &ast).unwrap();
+        let expanded = quote!(#(#expanded)*).to_string();
+
+        assert!(expanded.contains("(state , \"userName\")"));
+    }
+
+    #[test]
+    fn expand_lowercase_normalizer_field_ref() {
+        let ast = syn::parse_str::<syn::DeriveInput>(
+            "struct Doc { #[elastic(normalizer = \"lowercase\")] email: Keyword<DefaultKeywordMapping>, code: Keyword<DefaultKeywordMapping> }",
+        )
+        .unwrap();
+
+        let expanded = expand_derive(quote!(::elastic_types), &ast).unwrap();
+        let expanded = quote!(#(#expanded)*).to_string();
+
+        assert!(expanded.contains("email : :: elastic_types :: derive :: FieldRef < :: elastic_types :: derive :: Keyword < :: elastic_types :: derive :: LowercaseKeywordMapping > >"));
+        assert!(expanded.contains(
+            "code : :: elastic_types :: derive :: FieldRef < Keyword < DefaultKeywordMapping > >"
+        ));
+    }
+
+    #[test]
+    fn expand_malformed_attribute_is_err() {
+        assert_eq!(
+            "invalid attribute on field `count`: elastic attributes must be of the form #[elastic(key = \"value\")]",
+            expand_err("struct Doc { #[elastic = \"count\"] count: i32 }")
+        );
+    }
+
+    #[test]
+    fn expand_unclosed_index_placeholder_is_err() {
+        assert_eq!(
+            "placeholders in the index name `logs-{service` must be closed with a `}`",
+            expand_err("#[elastic(index = \"logs-{service\")] struct Doc { service: String }")
+        );
+    }
+
+    #[test]
+    fn expand_empty_index_placeholder_is_err() {
+        assert_eq!(
+            "the index name `logs-{}` contains an empty placeholder; placeholders must name a field, like `{field}` or `{field:%Y.%m.%d}`",
+            expand_err("#[elastic(index = \"logs-{}\")] struct Doc { service: String }")
+        );
+    }
+
+    #[test]
+    fn expand_invalid_index_date_format_is_err() {
+        assert_eq!(
+            "the date format `%Y.%Q` for the placeholder `timestamp` in the index name `logs-{timestamp:%Y.%Q}` is not a valid chrono format",
+            expand_err(
+                "#[elastic(index = \"logs-{timestamp:%Y.%Q}\")] struct Doc { timestamp: Date<DefaultDateMapping> }"
+            )
+        );
+    }
+
+    #[test]
+    fn expand_index_date_format() {
+        let ast = syn::parse_str::<syn::DeriveInput>(
+            "#[elastic(index = \"logs-{service}-{timestamp:%Y.%m.%d}\")] struct Doc { service: String, timestamp: Date<DefaultDateMapping> }",
+        )
+        .unwrap();
+
+        let expanded = expand_derive(quote!(::elastic_types), &ast).unwrap();
+        let expanded = quote!(#(#expanded)*).to_string();
+
+        assert!(expanded.contains("format ! (\"logs-{}-{}\" , & self . service , :: elastic_types :: derive :: format_index_date (& self . timestamp , \"%Y.%m.%d\"))"));
+    }
+}
@@ -8,7 +8,9 @@ This crate contains the internals for `elastic_types`-related codegen.
 
 #[macro_use]
 extern crate quote;
+#[macro_use]
 extern crate syn;
+extern crate proc_macro2;
 
 #[macro_use]
 extern crate quick_error;
@@ -25,27 +27,52 @@ extern crate chrono;
 pub mod date_format;
 pub mod elastic_type;
 
-fn get_elastic_meta_items<'a, I>(attrs: I) -> Vec<syn::NestedMetaItem>
+use proc_macro2::TokenStream;
+
+fn get_elastic_meta_items<'a, I>(attrs: I) -> Vec<syn::NestedMeta>
 where
     I: IntoIterator<Item = &'a syn::Attribute> + 'a,
 {
     attrs
         .into_iter()
-        .filter_map(|attr| match attr.value {
-            syn::MetaItem::List(ref key, ref list) if key == "elastic" => Some(list),
+        .filter(|attr| attr.path.is_ident("elastic"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => Some(list.nested),
             _ => None,
         })
         .flat_map(|list| list)
-        .cloned()
         .collect()
 }
 
-fn expect_name_value<'a>(name: &str, meta_item: &'a syn::NestedMetaItem) -> Option<&'a syn::Lit> {
+// Check each #[elastic()] attribute is a list of meta items, so malformed attributes aren't ignored
+fn check_elastic_attrs<'a, I>(attrs: I) -> Result<(), syn::Error>
+where
+    I: IntoIterator<Item = &'a syn::Attribute> + 'a,
+{
+    for attr in attrs
+        .into_iter()
+        .filter(|attr| attr.path.is_ident("elastic"))
+    {
+        match attr.parse_meta()? {
+            syn::Meta::List(_) => (),
+            meta => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    "elastic attributes must be of the form #[elastic(key = \"value\")]",
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn expect_name_value<'a>(name: &str, meta_item: &'a syn::NestedMeta) -> Option<&'a syn::Lit> {
     match *meta_item {
-        syn::NestedMetaItem::MetaItem(syn::MetaItem::NameValue(ref key, ref lit))
-            if key == name =>
+        syn::NestedMeta::Meta(syn::Meta::NameValue(ref name_value))
+            if name_value.path.is_ident(name) =>
         {
-            Some(lit)
+            Some(&name_value.lit)
         }
         _ => None,
     }
@@ -53,38 +80,33 @@ fn expect_name_value<'a>(name: &str, meta_item: &'a syn::NestedMetaItem) -> Opti
 
 fn expect_list<'a>(
     name: &str,
-    meta_item: &'a syn::NestedMetaItem,
-) -> Option<&'a [syn::NestedMetaItem]> {
+    meta_item: &'a syn::NestedMeta,
+) -> Option<&'a syn::punctuated::Punctuated<syn::NestedMeta, Token![,]>> {
     match *meta_item {
-        syn::NestedMetaItem::MetaItem(syn::MetaItem::List(ref key, ref list)) if key == name => {
-            Some(list)
+        syn::NestedMeta::Meta(syn::Meta::List(ref list)) if list.path.is_ident(name) => {
+            Some(&list.nested)
         }
         _ => None,
     }
 }
 
-fn expect_ident<'a>(name: &str, meta_item: &'a syn::NestedMetaItem) -> bool {
+fn expect_ident<'a>(name: &str, meta_item: &'a syn::NestedMeta) -> bool {
     match *meta_item {
-        syn::NestedMetaItem::MetaItem(syn::MetaItem::Word(ref ident)) if ident == name => true,
+        syn::NestedMeta::Meta(syn::Meta::Path(ref path)) if path.is_ident(name) => true,
         _ => false,
     }
 }
 
-fn get_ident_from_lit(lit: &syn::Lit) -> Result<syn::Ident, &'static str> {
-    get_str_from_lit(lit).map(Into::into)
-}
-
-fn get_tokens_from_lit<'a>(lit: &'a syn::Lit) -> Result<quote::Tokens, &'static str> {
-    get_str_from_lit(lit).map(|s| {
-        let mut tokens = quote::Tokens::new();
-        tokens.append(s);
-        tokens
-    })
+fn get_tokens_from_lit<'a>(lit: &'a syn::Lit) -> Result<TokenStream, &'static str> {
+    match *lit {
+        syn::Lit::Str(ref s) => s.parse().map_err(|_| "Unable to get tokens from lit"),
+        _ => Err("Unable to get str from lit"),
+    }
 }
 
-fn get_str_from_lit<'a>(lit: &'a syn::Lit) -> Result<&'a str, &'static str> {
+fn get_str_from_lit<'a>(lit: &'a syn::Lit) -> Result<String, &'static str> {
     match *lit {
-        syn::Lit::Str(ref s, _) => Ok(s.as_str()),
+        syn::Lit::Str(ref s) => Ok(s.value()),
         _ => {
             return Err("Unable to get str from lit");
         }
@@ -9,7 +9,7 @@ pub fn call(run: &str) -> Result<AsyncClient, Error> {
         // Get a client that sniffs nodes super frequently
         "sniffed_node" => AsyncClientBuilder::new()
             .sniff_nodes_fluent("http://localhost:9200", |n| n.wait(Duration::from_secs(1)))
-            //.serde_pool(Arc::new(ThreadPool::new()))
+            //.serde_pool(Handle::current())
             .build(),
         // Get a default client
         _ => AsyncClientBuilder::new().build(),
@@ -0,0 +1,67 @@
+use elastic::{
+    error::Error,
+    prelude::*,
+};
+use futures::Future;
+use run_tests::IntegrationTest;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BulkChunked;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, ElasticType)]
+#[elastic(index = "bulk_chunked")]
+pub struct Doc {
+    #[elastic(id)]
+    id: String,
+}
+
+impl IntegrationTest for BulkChunked {
+    type Response = BulkResponse;
+
+    fn kind() -> &'static str {
+        "bulk"
+    }
+    fn name() -> &'static str {
+        "chunked"
+    }
+
+    // Ensure the index doesn't exist
+    fn prepare(&self, client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
+        let delete_res = client
+            .index(Doc::static_index())
+            .delete()
+            .send()
+            .compat()
+            .map(|_| ());
+
+        Box::new(delete_res)
+    }
+
+    // Send some bulk operations split into several requests
+    fn request(&self, client: AsyncClient) -> Box<Future<Item = Self::Response, Error = Error>> {
+        let ops = (0..20)
+            .into_iter()
+            .map(|i| bulk().index(Doc { id: i.to_string() }));
+
+        let res_future = client
+            .bulk_chunked()
+            .body_size_ops(3)
+            .concurrency(2)
+            .extend(ops)
+            .send()
+            .compat();
+
+        Box::new(res_future)
+    }
+
+    // Ensure the responses for each chunk were combined in order
+    fn assert_ok(&self, res: &Self::Response) -> bool {
+        let ids: Vec<String> = res
+            .iter()
+            .filter_map(|item| item.ok().map(|item| item.id().to_owned()))
+            .collect();
+        let expected: Vec<String> = (0..20).map(|i| i.to_string()).collect();
+
+        res.is_ok() && ids == expected
+    }
+}
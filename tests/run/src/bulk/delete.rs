@@ -37,6 +37,7 @@ impl IntegrationTest for Delete {
             .index(Doc::static_index())
             .delete()
             .send()
+            .compat()
             .map(|_| ());
 
         Box::new(delete_res)
@@ -48,9 +49,10 @@ impl IntegrationTest for Delete {
             .document()
             .index(doc())
             .params_fluent(|p| p.url_param("refresh", true))
-            .send();
+            .send()
+            .compat();
 
-        let delete_res = client.bulk().push(bulk::<Doc>().delete(ID)).send();
+        let delete_res = client.bulk().push(bulk::<Doc>().delete(ID)).send().compat();
 
         Box::new(index_res.and_then(|_| delete_res))
     }
@@ -39,14 +39,14 @@ impl IntegrationTest for IndexCreate {
 
     // Ensure the index doesn't exist
     fn prepare(&self, client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
-        let delete_res = client.index(INDEX).delete().send().map(|_| ());
+        let delete_res = client.index(INDEX).delete().send().compat().map(|_| ());
 
         Box::new(delete_res)
     }
 
     // Index a document, then get it
     fn request(&self, client: AsyncClient) -> Box<Future<Item = Self::Response, Error = Error>> {
-        let bulk_res = client.bulk().push(bulk().create(doc())).send();
+        let bulk_res = client.bulk().push(bulk().create(doc())).send().compat();
 
         Box::new(bulk_res)
     }
@@ -33,6 +33,7 @@ impl IntegrationTest for IndexGet {
             .index(Doc::static_index())
             .delete()
             .send()
+            .compat()
             .map(|_| ());
 
         Box::new(delete_res)
@@ -52,9 +53,10 @@ impl IntegrationTest for IndexGet {
             .bulk()
             .extend(ops)
             .params_fluent(|p| p.url_param("refresh", true))
-            .send();
+            .send()
+            .compat();
 
-        let get_res = client.document().get("4").send();
+        let get_res = client.document().get("4").send().compat();
 
         Box::new(bulk_res.and_then(|_| get_res))
     }
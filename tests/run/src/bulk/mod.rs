@@ -3,11 +3,14 @@ use run_tests::{
     Test,
 };
 
+mod chunked;
 mod delete;
 mod index_create;
 mod index_get;
+mod processor;
 mod raw_index_create;
 mod raw_index_get;
+mod sink;
 mod stream;
 mod stream_tiny_size_limit;
 mod stream_tiny_timeout;
@@ -15,11 +18,14 @@ mod stream_zero_size_limit;
 
 pub fn tests() -> Vec<Test> {
     vec![
+        Box::new(|client| test(client, chunked::BulkChunked)),
         Box::new(|client| test(client, delete::Delete)),
         Box::new(|client| test(client, index_get::IndexGet)),
         Box::new(|client| test(client, index_create::IndexCreate)),
         Box::new(|client| test(client, raw_index_get::RawIndexGet)),
         Box::new(|client| test(client, raw_index_create::RawIndexCreate)),
+        Box::new(|client| test(client, processor::BulkProcessor)),
+        Box::new(|client| test(client, sink::BulkSink)),
         Box::new(|client| test(client, stream::BulkStream)),
         Box::new(|client| test(client, stream_tiny_size_limit::BulkStreamTinySize)),
         Box::new(|client| test(client, stream_zero_size_limit::BulkStreamZeroSize)),
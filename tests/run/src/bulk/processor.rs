@@ -0,0 +1,105 @@
+use std::sync::{
+    atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+    Arc,
+};
+
+use elastic::{
+    error::Error,
+    prelude::*,
+};
+use futures::{
+    stream,
+    Future,
+    Stream,
+};
+use run_tests::IntegrationTest;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BulkProcessor;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, ElasticType)]
+#[elastic(index = "bulk_processor")]
+pub struct Doc {
+    #[elastic(id)]
+    id: String,
+}
+
+impl IntegrationTest for BulkProcessor {
+    type Response = (usize, SearchResponse<Doc>);
+
+    fn kind() -> &'static str {
+        "bulk"
+    }
+    fn name() -> &'static str {
+        "processor"
+    }
+
+    // Ensure the index doesn't exist
+    fn prepare(&self, client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
+        let delete_res = client
+            .index(Doc::static_index())
+            .delete()
+            .send()
+            .compat()
+            .map(|_| ());
+
+        Box::new(delete_res)
+    }
+
+    // Queue some bulk operations on a processor
+    fn request(&self, client: AsyncClient) -> Box<Future<Item = Self::Response, Error = Error>> {
+        let flushed = Arc::new(AtomicUsize::new(0));
+        let after_flushed = flushed.clone();
+
+        let (processor, task) = client
+            .bulk_stream()
+            .body_size_ops(3)
+            .params_fluent(|p| p.url_param("refresh", true))
+            .build_processor()
+            .max_in_flight(2)
+            .after_flush(move |flush, res: Result<&BulkResponse, _>| {
+                if res.map(|res| res.is_ok()).unwrap_or(false) {
+                    after_flushed.fetch_add(flush.ops(), Ordering::SeqCst);
+                }
+            })
+            .build();
+
+        let ops = (0..20)
+            .into_iter()
+            .map(|i| bulk().index(Doc { id: i.to_string() }));
+
+        // Drop the processor once all operations are queued so the task can complete
+        let req_future = stream::iter_ok::<_, Error>(ops)
+            .forward(processor)
+            .map(|(_, processor)| drop(processor));
+        let task_future = task.then(|_| Ok::<_, Error>(()));
+
+        let search_client = client.clone();
+        let res_future = req_future.join(task_future).and_then(move |_| {
+            search_client
+                .document::<Doc>()
+                .search()
+                .body(json!({
+                    "size": 20,
+                    "query": {
+                        "match_all": {}
+                    }
+                }))
+                .send()
+                .compat()
+                .map(move |res| (flushed.load(Ordering::SeqCst), res))
+        });
+
+        Box::new(res_future)
+    }
+
+    // Ensure all operations were flushed to the index
+    fn assert_ok(&self, res: &Self::Response) -> bool {
+        let (flushed, ref res) = *res;
+
+        flushed == 20 && res.hits().count() == 20
+    }
+}
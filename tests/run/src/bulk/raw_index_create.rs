@@ -39,7 +39,7 @@ impl IntegrationTest for RawIndexCreate {
 
     // Ensure the index doesn't exist
     fn prepare(&self, client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
-        let delete_res = client.index(INDEX).delete().send().map(|_| ());
+        let delete_res = client.index(INDEX).delete().send().compat().map(|_| ());
 
         Box::new(delete_res)
     }
@@ -55,7 +55,8 @@ impl IntegrationTest for RawIndexCreate {
                     .ty(Doc::static_ty())
                     .id(ID),
             )
-            .send();
+            .send()
+            .compat();
 
         Box::new(bulk_res)
     }
@@ -0,0 +1,80 @@
+use elastic::{
+    error::Error,
+    prelude::*,
+};
+use futures::{
+    stream,
+    Future,
+    Stream,
+};
+use run_tests::IntegrationTest;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BulkSink;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, ElasticType)]
+#[elastic(index = "bulk_sink")]
+pub struct Doc {
+    #[elastic(id)]
+    id: String,
+}
+
+impl IntegrationTest for BulkSink {
+    type Response = SearchResponse<Doc>;
+
+    fn kind() -> &'static str {
+        "bulk"
+    }
+    fn name() -> &'static str {
+        "sink"
+    }
+
+    // Ensure the index doesn't exist
+    fn prepare(&self, client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
+        let delete_res = client
+            .index(Doc::static_index())
+            .delete()
+            .send()
+            .compat()
+            .map(|_| ());
+
+        Box::new(delete_res)
+    }
+
+    // Forward some bulk operations into a sink
+    fn request(&self, client: AsyncClient) -> Box<Future<Item = Self::Response, Error = Error>> {
+        let bulk_sink = client
+            .bulk_stream()
+            .body_size_ops(3)
+            .params_fluent(|p| p.url_param("refresh", true))
+            .build_sink();
+
+        let ops = (0..20)
+            .into_iter()
+            .map(|i| bulk().index(Doc { id: i.to_string() }));
+
+        let req_future = stream::iter_ok::<_, Error>(ops).forward(bulk_sink);
+
+        let search_client = client.clone();
+        let res_future = req_future.and_then(move |_| {
+            search_client
+                .document::<Doc>()
+                .search()
+                .body(json!({
+                    "size": 20,
+                    "query": {
+                        "match_all": {}
+                    }
+                }))
+                .send()
+                .compat()
+        });
+
+        Box::new(res_future)
+    }
+
+    // Ensure all operations were flushed to the index
+    fn assert_ok(&self, res: &Self::Response) -> bool {
+        res.hits().count() == 20
+    }
+}
@@ -43,6 +43,7 @@ impl IntegrationTest for BulkStream {
             .index(Doc::static_index())
             .delete()
             .send()
+            .compat()
             .map(|_| ());
 
         Box::new(delete_res)
@@ -38,6 +38,7 @@ impl IntegrationTest for BulkStreamTinyTimeout {
             .index(Doc::static_index())
             .delete()
             .send()
+            .compat()
             .map(|_| ());
 
         Box::new(delete_res)
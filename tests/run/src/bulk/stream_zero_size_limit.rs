@@ -37,6 +37,7 @@ impl IntegrationTest for BulkStreamZeroSize {
             .index(Doc::static_index())
             .delete()
             .send()
+            .compat()
             .map(|_| ());
 
         Box::new(delete_res)
@@ -37,6 +37,7 @@ impl IntegrationTest for Delete {
             .index(Doc::static_index())
             .delete()
             .send()
+            .compat()
             .map(|_| ());
 
         Box::new(delete_res)
@@ -48,17 +49,19 @@ impl IntegrationTest for Delete {
             .document()
             .index(doc())
             .params_fluent(|p| p.url_param("refresh", true))
-            .send();
+            .send()
+            .compat();
 
-        let pre_delete_res = client.document().get(ID).send();
+        let pre_delete_res = client.document().get(ID).send().compat();
 
         let delete_res = client
             .document::<Doc>()
             .delete(ID)
             .params_fluent(|p| p.url_param("refresh", true))
-            .send();
+            .send()
+            .compat();
 
-        let post_delete_res = client.document().get(ID).send();
+        let post_delete_res = client.document().get(ID).send().compat();
 
         Box::new(
             index_res
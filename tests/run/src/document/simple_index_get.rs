@@ -43,6 +43,7 @@ impl IntegrationTest for SimpleIndexGet {
             .index(Doc::static_index())
             .delete()
             .send()
+            .compat()
             .map(|_| ());
 
         Box::new(delete_res)
@@ -54,9 +55,10 @@ impl IntegrationTest for SimpleIndexGet {
             .document()
             .index(doc())
             .params_fluent(|p| p.url_param("refresh", true))
-            .send();
+            .send()
+            .compat();
 
-        let get_res = client.document().get(ID).send();
+        let get_res = client.document().get(ID).send().compat();
 
         Box::new(index_res.and_then(|_| get_res))
     }
@@ -34,6 +34,7 @@ impl IntegrationTest for SimpleMapping {
             .index(Doc::static_index())
             .delete()
             .send()
+            .compat()
             .map(|_| ());
 
         Box::new(delete_res)
@@ -45,9 +46,10 @@ impl IntegrationTest for SimpleMapping {
             .index(Doc::static_index())
             .create()
             .send()
+            .compat()
             .map(|_| ());
 
-        let put_mapping = client.document::<Doc>().put_mapping().send();
+        let put_mapping = client.document::<Doc>().put_mapping().send().compat();
 
         let get_mapping = client
             .request(IndicesGetMappingRequest::for_index_ty(
@@ -55,7 +57,8 @@ impl IntegrationTest for SimpleMapping {
                 Doc::static_ty(),
             ))
             .send()
-            .and_then(|res| res.into_response::<Value>());
+            .compat()
+            .and_then(|res| res.into_response::<Value>().compat());
 
         Box::new(
             create_index
@@ -34,6 +34,7 @@ impl IntegrationTest for UpdateNoIndex {
             .index(Doc::static_index())
             .delete()
             .send()
+            .compat()
             .map(|_| ());
 
         Box::new(delete_res)
@@ -41,7 +42,12 @@ impl IntegrationTest for UpdateNoIndex {
 
     // Execute an update request against that index
     fn request(&self, client: AsyncClient) -> Box<Future<Item = Self::Response, Error = Error>> {
-        let res = client.document::<Doc>().update("1").doc(json!({})).send();
+        let res = client
+            .document::<Doc>()
+            .update("1")
+            .doc(json!({}))
+            .send()
+            .compat();
 
         Box::new(res)
     }
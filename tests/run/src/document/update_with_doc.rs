@@ -42,6 +42,7 @@ impl IntegrationTest for UpdateWithDoc {
             .index(Doc::static_index())
             .delete()
             .send()
+            .compat()
             .map(|_| ());
 
         Box::new(delete_res)
@@ -53,7 +54,8 @@ impl IntegrationTest for UpdateWithDoc {
             .document()
             .index(doc())
             .params_fluent(|p| p.url_param("refresh", true))
-            .send();
+            .send()
+            .compat();
 
         let update_res = client
             .document::<Doc>()
@@ -62,9 +64,10 @@ impl IntegrationTest for UpdateWithDoc {
                 "title": EXPECTED_TITLE.to_owned(),
             }))
             .params_fluent(|p| p.url_param("refresh", true))
-            .send();
+            .send()
+            .compat();
 
-        let get_res = client.document().get(ID).send();
+        let get_res = client.document().get(ID).send().compat();
 
         Box::new(
             index_res
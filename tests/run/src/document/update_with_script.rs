@@ -38,13 +38,14 @@ impl IntegrationTest for UpdateWithScript {
 
     // Ensure the index doesn't exist
     fn prepare(&self, client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
-        let delete_res = client.index(Doc::static_index()).delete().send();
+        let delete_res = client.index(Doc::static_index()).delete().send().compat();
 
         let index_res = client
             .document()
             .index(doc())
             .params_fluent(|p| p.url_param("refresh", true))
-            .send();
+            .send()
+            .compat();
 
         Box::new(delete_res.then(|_| index_res).map(|_| ()))
     }
@@ -58,9 +59,10 @@ impl IntegrationTest for UpdateWithScript {
                 s.param("newTitle", EXPECTED_TITLE)
             })
             .params_fluent(|p| p.url_param("refresh", true))
-            .send();
+            .send()
+            .compat();
 
-        let get_res = client.document().get(ID).send();
+        let get_res = client.document().get(ID).send().compat();
 
         Box::new(update_res.and_then(|update| get_res.map(|get| (update, get))))
     }
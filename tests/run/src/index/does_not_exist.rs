@@ -22,14 +22,14 @@ impl IntegrationTest for DoesNotExist {
 
     // Ensure the index doesn't exist
     fn prepare(&self, client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
-        let delete_res = client.index(INDEX).delete().send().map(|_| ());
+        let delete_res = client.index(INDEX).delete().send().compat().map(|_| ());
 
         Box::new(delete_res)
     }
 
     // Execute an index exists request
     fn request(&self, client: AsyncClient) -> Box<Future<Item = Self::Response, Error = Error>> {
-        let res = client.index(INDEX).exists().send();
+        let res = client.index(INDEX).exists().send().compat();
 
         Box::new(res)
     }
@@ -22,14 +22,14 @@ impl IntegrationTest for Exists {
 
     // Ensure the index exists
     fn prepare(&self, client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
-        let create_res = client.index(INDEX).create().send().map(|_| ());
+        let create_res = client.index(INDEX).create().send().compat().map(|_| ());
 
         Box::new(create_res)
     }
 
     // Execute an index exists request
     fn request(&self, client: AsyncClient) -> Box<Future<Item = Self::Response, Error = Error>> {
-        let res = client.index(INDEX).exists().send();
+        let res = client.index(INDEX).exists().send().compat();
 
         Box::new(res)
     }
@@ -12,6 +12,7 @@ extern crate elastic;
 extern crate elastic_derive;
 extern crate env_logger;
 extern crate futures;
+extern crate futures_util;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
@@ -19,8 +20,6 @@ extern crate serde_derive;
 extern crate serde_json;
 extern crate term_painter;
 extern crate tokio;
-extern crate tokio_threadpool;
-extern crate tokio_timer;
 
 use clap::{
     App,
@@ -42,6 +41,20 @@ mod search;
 mod sql;
 mod wait_until_ready;
 
+/** Run a future to completion on a single threaded runtime. */
+fn block_on<F>(fut: F) -> Result<F::Item, F::Error>
+where
+    F: futures::Future,
+{
+    use futures_util::compat::Future01CompatExt;
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to start runtime")
+        .block_on(fut.compat())
+}
+
 fn main() {
     env_logger::init_from_env("ELASTIC_LOG");
 
@@ -96,7 +96,7 @@ where
 }
 
 pub fn call(client: AsyncClient, max_concurrent_tests: usize) -> Result<Vec<TestResult>, ()> {
-    tokio::runtime::current_thread::block_on_all(call_future(client, max_concurrent_tests))
+    ::block_on(call_future(client, max_concurrent_tests))
 }
 
 fn call_future(
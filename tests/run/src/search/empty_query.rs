@@ -34,7 +34,7 @@ impl IntegrationTest for EmptyQuery {
 
     // Ensure the index doesn't exist
     fn prepare(&self, client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
-        let delete_res = client.index(Doc::static_index()).delete().send();
+        let delete_res = client.index(Doc::static_index()).delete().send().compat();
 
         let index_reqs = future::join_all((0..10).into_iter().map(move |_| {
             client
@@ -42,6 +42,7 @@ impl IntegrationTest for EmptyQuery {
                 .index(doc())
                 .params_fluent(|p| p.url_param("refresh", true))
                 .send()
+                .compat()
         }));
 
         Box::new(delete_res.then(|_| index_reqs.map(|_| ())))
@@ -49,7 +50,7 @@ impl IntegrationTest for EmptyQuery {
 
     // Execute a search request against that index
     fn request(&self, client: AsyncClient) -> Box<Future<Item = Self::Response, Error = Error>> {
-        let res = client.search().index(Doc::static_index()).send();
+        let res = client.search().index(Doc::static_index()).send().compat();
 
         Box::new(res)
     }
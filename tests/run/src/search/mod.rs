@@ -6,11 +6,13 @@ use run_tests::{
 mod empty_query;
 mod no_index;
 mod raw_query_string;
+mod scroll;
 
 pub fn tests() -> Vec<Test> {
     vec![
         Box::new(|client| test(client, no_index::NoIndex)),
         Box::new(|client| test(client, empty_query::EmptyQuery)),
         Box::new(|client| test(client, raw_query_string::RawQueryString)),
+        Box::new(|client| test(client, scroll::Scroll)),
     ]
 }
@@ -26,14 +26,19 @@ impl IntegrationTest for NoIndex {
 
     // Ensure the index doesn't exist
     fn prepare(&self, client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
-        let delete_res = client.index(INDEX).delete().send().map(|_| ());
+        let delete_res = client.index(INDEX).delete().send().compat().map(|_| ());
 
         Box::new(delete_res)
     }
 
     // Execute a search request against that index
     fn request(&self, client: AsyncClient) -> Box<Future<Item = Self::Response, Error = Error>> {
-        let res = client.search().index(INDEX).ty("no_index_ty").send();
+        let res = client
+            .search()
+            .index(INDEX)
+            .ty("no_index_ty")
+            .send()
+            .compat();
 
         Box::new(res)
     }
@@ -34,7 +34,7 @@ impl IntegrationTest for RawQueryString {
 
     // Ensure the index doesn't exist
     fn prepare(&self, client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
-        let delete_res = client.index(Doc::static_index()).delete().send();
+        let delete_res = client.index(Doc::static_index()).delete().send().compat();
 
         let index_reqs = future::join_all((0..10).into_iter().map(move |_| {
             client
@@ -42,6 +42,7 @@ impl IntegrationTest for RawQueryString {
                 .index(doc())
                 .params_fluent(|p| p.url_param("refresh", true))
                 .send()
+                .compat()
         }));
 
         Box::new(delete_res.then(|_| index_reqs.map(|_| ())))
@@ -59,7 +60,8 @@ impl IntegrationTest for RawQueryString {
                     }
                 }
             }))
-            .send();
+            .send()
+            .compat();
 
         Box::new(res)
     }
@@ -0,0 +1,72 @@
+use elastic::{
+    error::Error,
+    prelude::*,
+};
+use futures::{
+    future,
+    Future,
+    Stream,
+};
+use run_tests::IntegrationTest;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Scroll;
+
+#[derive(Debug, Serialize, Deserialize, ElasticType)]
+#[elastic(index = "scroll_idx")]
+pub struct Doc {
+    #[elastic(id)]
+    id: String,
+}
+
+impl IntegrationTest for Scroll {
+    type Response = Vec<String>;
+
+    fn kind() -> &'static str {
+        "search"
+    }
+    fn name() -> &'static str {
+        "scroll"
+    }
+
+    // Ensure the index only contains the documents to scroll through
+    fn prepare(&self, client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
+        let delete_res = client.index(Doc::static_index()).delete().send().compat();
+
+        let index_reqs = future::join_all((0..25).into_iter().map(move |i| {
+            client
+                .document()
+                .index(Doc { id: i.to_string() })
+                .params_fluent(|p| p.url_param("refresh", true))
+                .send()
+                .compat()
+        }));
+
+        Box::new(delete_res.then(|_| index_reqs.map(|_| ())))
+    }
+
+    // Scroll through every document in pages smaller than the index
+    fn request(&self, client: AsyncClient) -> Box<Future<Item = Self::Response, Error = Error>> {
+        let res = client
+            .search::<Doc>()
+            .index(Doc::static_index())
+            .body(json!({
+                "size": 10,
+                "sort": ["_doc"]
+            }))
+            .scroll()
+            .build()
+            .map(|hit| hit.id().to_owned())
+            .collect();
+
+        Box::new(res)
+    }
+
+    // Ensure every document was emitted exactly once
+    fn assert_ok(&self, res: &Self::Response) -> bool {
+        let mut ids: Vec<usize> = res.iter().filter_map(|id| id.parse().ok()).collect();
+        ids.sort();
+
+        ids == (0..25).collect::<Vec<_>>()
+    }
+}
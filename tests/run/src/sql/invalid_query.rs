@@ -25,14 +25,17 @@ impl IntegrationTest for InvalidQuery {
 
     // Ensure the index doesn't exist
     fn prepare(&self, client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
-        let delete_res = client.index(INDEX).delete().send().map(|_| ());
+        let delete_res = client.index(INDEX).delete().send().compat().map(|_| ());
 
         Box::new(delete_res)
     }
 
     // Execute a search request against that index
     fn request(&self, client: AsyncClient) -> Box<Future<Item = Self::Response, Error = Error>> {
-        let res = client.sql_query(&format!("select * from {}", INDEX)).send();
+        let res = client
+            .sql_query(&format!("select * from {}", INDEX))
+            .send()
+            .compat();
 
         Box::new(res)
     }
@@ -31,7 +31,7 @@ impl IntegrationTest for InvalidSyntax {
 
     // Execute a search request against that index
     fn request(&self, client: AsyncClient) -> Box<Future<Item = Self::Response, Error = Error>> {
-        let res = client.sql_query("select").send();
+        let res = client.sql_query("select").send().compat();
 
         Box::new(res)
     }
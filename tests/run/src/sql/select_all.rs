@@ -38,7 +38,7 @@ impl IntegrationTest for SelectAll {
 
     // Ensure the index doesn't exist
     fn prepare(&self, client: AsyncClient) -> Box<Future<Item = (), Error = Error>> {
-        let delete_res = client.index(Doc::static_index()).delete().send();
+        let delete_res = client.index(Doc::static_index()).delete().send().compat();
 
         let index_reqs = future::join_all((0..10).into_iter().map(move |i| {
             client
@@ -46,6 +46,7 @@ impl IntegrationTest for SelectAll {
                 .index(doc(i))
                 .params_fluent(|p| p.url_param("refresh", true))
                 .send()
+                .compat()
         }));
 
         Box::new(delete_res.then(|_| index_reqs.map(|_| ())))
@@ -55,7 +56,8 @@ impl IntegrationTest for SelectAll {
     fn request(&self, client: AsyncClient) -> Box<Future<Item = Self::Response, Error = Error>> {
         let res = client
             .sql_query(&format!("select * from {}", Doc::static_index()))
-            .send();
+            .send()
+            .compat();
 
         Box::new(res)
     }
@@ -4,6 +4,10 @@ use futures::{
     Future,
     Stream,
 };
+use futures_util::{
+    self,
+    compat::Compat,
+};
 use std::{
     error::Error as StdError,
     fmt,
@@ -13,7 +17,7 @@ use std::{
 type Error = Box<StdError>;
 
 pub fn call(client: AsyncClient, timeout_secs: u64) -> Result<(), Error> {
-    let wait = tokio::runtime::current_thread::block_on_all(call_future(client, timeout_secs));
+    let wait = ::block_on(call_future(client, timeout_secs));
 
     match wait {
         Ok(()) | Err(Done::Ready) => Ok(()),
@@ -33,22 +37,24 @@ fn call_future(client: AsyncClient, timeout_secs: u64) -> Box<Future<Item = (),
         } else {
             let client = client.clone();
 
-            let poll = tokio_timer::sleep(Duration::from_secs(3))
-                .map_err(Error::from)
-                .and_then(move |_| {
-                    client
-                        .ping()
-                        .send()
-                        .then(|r| {
-                            let r: Result<_, Error> = match r {
-                                Ok(_) => Ok(((), true)),
-                                Err(_) => Ok(((), false)),
-                            };
-
-                            r
-                        })
-                        .map_err(Error::from)
-                });
+            let sleep = tokio::time::sleep(Duration::from_secs(3));
+            let sleep = futures_util::FutureExt::map(sleep, Ok::<(), Error>);
+
+            let poll = Compat::new(Box::pin(sleep)).and_then(move |_| {
+                client
+                    .ping()
+                    .send()
+                    .compat()
+                    .then(|r| {
+                        let r: Result<_, Error> = match r {
+                            Ok(_) => Ok(((), true)),
+                            Err(_) => Ok(((), false)),
+                        };
+
+                        r
+                    })
+                    .map_err(Error::from)
+            });
 
             Some(poll)
         }